@@ -1,6 +1,9 @@
 //! See Grammar.txt for the python reference grammar.
 //!
-use ::token::OwnedTk;
+//! Every node knows the `Span` of the first token it was parsed from. Nodes that
+//! start with a keyword or bracket that is not kept in the tree carry the span of
+//! that token in a `span` field, the rest take it from their first child.
+use ::token::{OwnedTk, Span};
 
 
 #[derive(Debug, Clone, Eq, PartialEq, Serialize)]
@@ -18,6 +21,17 @@ impl Default for Ast {
 }
 
 
+impl Ast {
+    pub fn span(&self) -> Span {
+        match *self {
+            Ast::Module(Module::Body(ref stmts)) => first_span(stmts, Stmt::span),
+            Ast::Statement(ref stmt) => stmt.span(),
+            Ast::Expression(ref expr) => expr.span(),
+        }
+    }
+}
+
+
 #[derive(Debug, Clone, Eq, PartialEq, Serialize)]
 pub enum Module {
     Body(Vec<Stmt>)
@@ -39,11 +53,11 @@ pub enum FnType {
 
 #[derive(Debug, Clone, Eq, PartialEq, Serialize)]
 pub enum Stmt {
-    FunctionDef { fntype: FnType, name: OwnedTk, arguments: Vec<Expr>, body: Box<Stmt> },
+    FunctionDef { fntype: FnType, name: OwnedTk, arguments: Vec<Expr>, body: Box<Stmt>, span: Span },
     Block(Vec<Stmt>),
-    ClassDef {name: OwnedTk, bases: Vec<Expr>, body: Box<Stmt> },
-    Return(Option<Expr>),
-    Delete(Vec<Expr>),
+    ClassDef {name: OwnedTk, bases: Vec<Expr>, body: Box<Stmt>, span: Span },
+    Return { value: Option<Expr>, span: Span },
+    Delete { targets: Vec<Expr>, span: Span },
     Assign { target: Expr, value: Expr},
     AugAssign { target: Expr, op: Op, value: Expr},
 
@@ -51,7 +65,7 @@ pub enum Stmt {
     ImportFrom, // (identifier? module, alias* names, int? level))
     Global(Vec<OwnedTk>),
     Nonlocal(Vec<OwnedTk>),
-    Assert { test: Expr, message: Option<Expr> },
+    Assert { test: Expr, message: Option<Expr>, span: Span },
     Expr(Expr),
     Pass,
     Break,
@@ -60,16 +74,42 @@ pub enum Stmt {
 }
 
 
+impl Stmt {
+    pub fn span(&self) -> Span {
+        match *self {
+            Stmt::FunctionDef { span, .. }      |
+            Stmt::ClassDef { span, .. }         |
+            Stmt::Return { span, .. }           |
+            Stmt::Delete { span, .. }           |
+            Stmt::Assert { span, .. }           => span,
+            Stmt::Block(ref stmts)              => first_span(stmts, Stmt::span),
+            Stmt::Assign { ref target, .. }     |
+            Stmt::AugAssign { ref target, .. }  => target.span(),
+            Stmt::Global(ref names)             |
+            Stmt::Nonlocal(ref names)           => first_span(names, OwnedTk::span),
+            Stmt::Expr(ref expr)                => expr.span(),
+            Stmt::Import                        |
+            Stmt::ImportFrom                    |
+            Stmt::Pass                          |
+            Stmt::Break                         |
+            Stmt::Continue                      |
+            Stmt::Newline(_)                    => Span::default(),
+        }
+    }
+}
+
+
 #[derive(Debug, Clone, Eq, PartialEq, Serialize)]
 pub enum Expr {
-    Lambda {arguments: Vec<Expr>, body: Box<Expr>},
+    Lambda {arguments: Vec<Expr>, body: Box<Expr>, span: Span},
     Conditional {condition: Box<Expr>, consequent: Box<Expr>, alternative: Box<Expr>},
     BinOp { op: Op, left: BoxedExpr, right: BoxedExpr },
     UnaryOp { op: Op, operand: BoxedExpr },
     Call { func: OwnedTk, args: Vec<Expr>,  keywords: ()},
     Attribute { value: Box<Expr>, attr: OwnedTk },
-    Dict { items: Vec<(Expr, Expr)> },
-    List { elems: Vec<Expr> },
+    Dict { items: Vec<(Expr, Expr)>, span: Span },
+    List { elems: Vec<Expr>, span: Span },
+    Tuple { elems: Vec<Expr>, span: Span },
     NameConstant(OwnedTk),
     Constant(OwnedTk),
     None
@@ -82,5 +122,31 @@ impl Default for Expr {
 }
 
 
+impl Expr {
+    pub fn span(&self) -> Span {
+        match *self {
+            Expr::Lambda { span, .. }               |
+            Expr::Dict { span, .. }                 |
+            Expr::List { span, .. }                 |
+            Expr::Tuple { span, .. }                => span,
+            Expr::Conditional { ref consequent, .. } => consequent.span(),
+            Expr::BinOp { ref left, .. }            => left.span(),
+            Expr::UnaryOp { ref op, .. }            => op.0.span(),
+            Expr::Call { ref func, .. }             => func.span(),
+            Expr::Attribute { ref value, .. }       => value.span(),
+            Expr::NameConstant(ref tk)              |
+            Expr::Constant(ref tk)                  => tk.span(),
+            Expr::None                              => Span::default(),
+        }
+    }
+}
+
+
 #[derive(Debug, Clone, Eq, PartialEq, Serialize)]
-pub struct Op(pub OwnedTk);
\ No newline at end of file
+pub struct Op(pub OwnedTk);
+
+
+/// Span of the first node in `nodes`, if any
+fn first_span<T, F: Fn(&T) -> Span>(nodes: &[T], span: F) -> Span {
+    nodes.first().map(span).unwrap_or_default()
+}
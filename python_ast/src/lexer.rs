@@ -5,7 +5,7 @@ use std::rc::Rc;
 use nom::{IResult, hex_digit, digit, Needed};
 use nom::{AsChar, InputLength, InputIter, Slice, ErrorKind};
 
-use ::token::{Id, Tk, New, Tag, Num, Ws};
+use ::token::{Id, Tk, New, Tag, Num, Ws, Span};
 
 pub use nom::IResult as LexResult;
 
@@ -23,17 +23,35 @@ impl Lexer {
     /// Convert a slice of bytes into a `Rc<Vec<Tk>>`
     #[deprecated]
     pub fn tokenize<'a>(&self, bytes: &'a [u8]) -> Rc<LexResult<&'a [u8], Vec<Tk<'a>>>> {
-        let result = tokenize_bytes(bytes);
+        let result = self.tokenize2(bytes);
         Rc::new(result)
     }
 
-    /// Convert a slice of bytes into `Vec<Tk<'a>>`
+    /// Convert a slice of bytes into `Vec<Tk<'a>>` where each token knows its `Span`
     pub fn tokenize2<'a>(&self, bytes: &'a [u8]) -> LexResult<&'a [u8], Vec<Tk<'a>>>{
-        tokenize_bytes(bytes)
+        match tokenize_bytes(bytes) {
+            LexResult::Done(remaining, tokens) => LexResult::Done(remaining, locate(bytes, tokens)),
+            other => other
+        }
     }
 }
 
 
+/// Set the span of each token from where its bytes are in `input`. The tokens must
+/// be slices of `input` in order, like the ones made by `tokenize_bytes`. Prefixed
+/// strings start at their opening quote since the prefix is not part of their bytes.
+pub fn locate<'a>(input: &'a [u8], tokens: Vec<Tk<'a>>) -> Vec<Tk<'a>> {
+    let base = input.as_ptr() as usize;
+    let mut span = Span { offset: 0, line: 1, column: 0 };
+
+    tokens.into_iter().map(|tk| {
+        let offset = (tk.bytes().as_ptr() as usize - base).max(span.offset);
+        span = span.after(&input[span.offset..offset]);
+        tk.with_span(span)
+    }).collect()
+}
+
+
 /// Entry point of the lexer into nom parser
 named!(pub tokenize_bytes <Vec<Tk>>, do_parse!(
     tokens: many0!(
@@ -588,6 +606,32 @@ gamma'''
         assert_token(&value, Id::Name, Tag::Ident);
     }
 
+    #[test]
+    fn tk_spans() {
+        let input = "x = 1\n  'é' + '''a\nb'''\ny".as_bytes();
+        let tokens = match Lexer::new().tokenize2(input) {
+            IResult::Done(_, tokens) => tokens,
+            _ => panic!("Unable to tokenize input")
+        };
+
+        let spans = tokens.iter()
+            .filter(|tk| tk.id() != Id::Space)
+            .map(|tk| (tk.as_string(), tk.span().line, tk.span().column, tk.span().offset))
+            .collect::<Vec<_>>();
+
+        assert_eq!(spans, vec![
+            (String::from("x"),             1, 0, 0),
+            (String::from("="),             1, 2, 2),
+            (String::from("1"),             1, 4, 4),
+            (String::from("\n"),            1, 5, 5),
+            (String::from("'é'"),           2, 2, 8),
+            (String::from("+"),             2, 6, 13),
+            (String::from("'''a\nb'''"),    2, 8, 15),
+            (String::from("\n"),            3, 4, 24),
+            (String::from("y"),             4, 0, 25),
+        ]);
+    }
+
    #[test]
     fn expr_x_eq_1() {
         let value = tokenize_bytes(r#"x = 1"#.as_bytes()).unwrap();
@@ -37,6 +37,6 @@ pub mod util;
 pub mod fmt;
 
 pub use ast::{Ast, Module, Stmt, Expr, Op};
-pub use token::{Tk, OwnedTk, Id, Tag, Num};
+pub use token::{Tk, OwnedTk, Id, Tag, Num, Span};
 pub use lexer::{Lexer, LexResult};
-pub use parser::{Parser, ParserResult, ParsedAst};
+pub use parser::{Parser, ParserResult, ParsedAst, ParseError};
@@ -1,19 +1,22 @@
 //! Take a slice of tokens `TkSlice` and convert it into an `Ast`.
 use nom;
-use nom::{IResult, ErrorKind, Err};
+use nom::{IResult, ErrorKind, Err, Slice};
 
-use ::token::{Id, Tk, Tag, OwnedTk};
+use ::token::{Id, Tk, Tag, OwnedTk, Span};
 use ::slice::{TkSlice};
 use ::ast::{Ast, Module, Stmt, Expr, Op, FnType};
 use ::traits::redefs_nom::InputLengthRedef;
 use ::preprocessor::{Preprocessor, BlockScopePreprocessor};
+use ::util::filter_non_critical_python_whitespace;
 
 use self::internal::*;
 
 
-// Hacks to splice in an is not token
+// Hacks to splice in the two keyword operators as a single token
 const IS_NOT_BYTES: &'static [u8] = &[105, 115, 32, 110, 111, 116];
-const IS_NOT_TKSLICE: TkSlice<'static>  = TkSlice(&[Tk::const_(Id::IsNot, IS_NOT_BYTES, Tag::None)]);
+const IS_NOT_TK: Tk<'static> = Tk::const_(Id::IsNot, IS_NOT_BYTES, Tag::None);
+const NOT_IN_BYTES: &'static [u8] = &[110, 111, 116, 32, 105, 110];
+const NOT_IN_TK: Tk<'static> = Tk::const_(Id::NotIn, NOT_IN_BYTES, Tag::None);
 
 /// The result type returned by `Parser`. Both `Ok` and `Error` variants contain an
 /// instance of `ParsedAst`.  `Ok` variants are considered to be the case where the
@@ -42,6 +45,9 @@ pub struct ParsedAst{
     /// Tokens that were not consumed by the parser. Useful to debug
     /// where the parsing stopped.
     pub remaining_tokens: Vec<OwnedTk>,
+
+    /// Where and why the parsing stopped when the tokens were not fully consumed
+    pub error: Option<ParseError>,
 }
 
 
@@ -61,7 +67,73 @@ impl ParsedAst {
                 .map(OwnedTk::from)
                 .collect::<Vec<OwnedTk>>(),
             p1_tokens: p1_tokens.iter().map(OwnedTk::from).collect::<Vec<OwnedTk>>(),
-            p2_tokens: p2_tokens.iter().map(OwnedTk::from).collect::<Vec<OwnedTk>>()
+            p2_tokens: p2_tokens.iter().map(OwnedTk::from).collect::<Vec<OwnedTk>>(),
+            error: None
+        }
+    }
+
+    fn with_error(mut self, error: ParseError) -> Self {
+        self.error = Some(error);
+        self
+    }
+}
+
+
+/// The token the parser could not make sense of and what it expected instead, enough
+/// to report a `SyntaxError` that points at the source.
+#[derive(Debug, Clone, Serialize)]
+pub struct ParseError {
+    /// Position of the offending token or of the end of the input, not known
+    /// when the tokens could not be split into blocks.
+    pub span: Span,
+
+    /// The offending token, `None` at the end of the input
+    pub found: Option<OwnedTk>,
+
+    /// Description of what was expected instead, like `')'` or `an expression`
+    pub expected: Option<String>,
+
+    /// One line summary of the error
+    pub message: String,
+}
+
+
+impl ParseError {
+    /// Error at the token `found` or at the end of the input `end` when there is none
+    fn new(found: Option<&Tk>, end: Span, expected: Option<String>) -> Self {
+        let unexpected = match found.map(|tk| (tk.id(), tk)) {
+            None                            => String::from("unexpected end of input"),
+            Some((Id::Newline, _))          => String::from("unexpected end of line"),
+            Some((Id::BlockStart, _))       => String::from("unexpected indent"),
+            Some((Id::BlockEnd, _))         => String::from("unexpected unindent"),
+            Some((Id::ErrorMarker, tk))     => format!("invalid character '{}'", tk.as_string()),
+            Some((_, tk))                   => format!("unexpected '{}'", tk.as_string()),
+        };
+
+        ParseError {
+            span: found.map(Tk::span).unwrap_or(end),
+            found: found.map(OwnedTk::from),
+            message: match expected {
+                Some(ref expected) => format!("{}, expected {}", unexpected, expected),
+                None => unexpected
+            },
+            expected: expected,
+        }
+    }
+
+    /// True if the input ended before the statement did, so more input could make
+    /// it valid. The repl asks for another line instead of reporting these.
+    pub fn is_incomplete(&self) -> bool {
+        self.found.is_none() && self.span.is_known()
+    }
+
+    /// Error that is not about a single token, like inconsistent indents
+    fn message(message: String) -> Self {
+        ParseError {
+            span: Span::default(),
+            found: None,
+            expected: None,
+            message: message
         }
     }
 }
@@ -69,26 +141,109 @@ impl ParsedAst {
 
 #[derive(Debug, Copy, Clone, Serialize, Default)]
 struct ParserState<'a> {
-    /// Track number of `\n` seen in an erratic and
-    /// inconsistent manner
-    line: usize,
-    column: usize,
     indent: usize,
-    unused: Option<TkSlice<'a>>
+    unused: Option<TkSlice<'a>>,
+
+    /// The error of the expression parser that happened furthest into the
+    /// input, with the number of tokens that were left when it happened.
+    failure: Option<(usize, ParserError)>,
 }
 
 
-/// The custom result that happens when the parser is unable to parse
-/// a nested expression.
-#[repr(u32)]
+impl<'a> ParserState<'a> {
+    /// Keep `error` if it happened further into the input than the known failure
+    fn fail(&mut self, remaining: usize, error: ParserError) {
+        match self.failure {
+            Some((least, _)) if least <= remaining => {},
+            _ => self.failure = Some((remaining, error))
+        }
+    }
+}
+
+
+/// The custom results that happen when the parser is unable to parse
+/// an expression.
+#[derive(Debug, Copy, Clone, Serialize)]
 enum ParserError {
-    SubExpr = 1024,
+    /// There was no name, constant, or display where an operand was expected
+    Atom,
+    /// A specific token like a closing bracket or `else` was expected
+    Token(Id),
 }
 
 
 impl ParserError {
-    pub const fn code<'a>(self) -> Err<TkSlice<'a>, u32> {
-        Err::Code(ErrorKind::Custom(self as u32))
+    pub fn code<'a>(self) -> Err<TkSlice<'a>, u32> {
+        let code = match self {
+            ParserError::Atom => 1024,
+            ParserError::Token(_) => 1025,
+        };
+
+        Err::Code(ErrorKind::Custom(code))
+    }
+
+    /// Description of what the parser was looking for
+    fn expected(&self) -> String {
+        match *self {
+            ParserError::Atom => String::from("an expression"),
+            ParserError::Token(id) => id.describe(),
+        }
+    }
+}
+
+
+/// Read position of the expression parser. Non critical whitespace is skipped
+/// before looking at each token so the expression methods only see significant tokens.
+#[derive(Debug, Copy, Clone)]
+struct TkCursor<'b>(TkSlice<'b>);
+
+
+impl<'b> TkCursor<'b> {
+    fn skip_spaces(&mut self) {
+        if let IResult::Done(remaining, _) = filter_non_critical_python_whitespace(self.0) {
+            self.0 = remaining;
+        }
+    }
+
+    /// Id of the next significant token
+    fn peek(&mut self) -> Option<Id> {
+        self.skip_spaces();
+        self.0.tokens().first().map(Tk::id)
+    }
+
+    /// Id of the significant token after the next one
+    fn peek_second(&mut self) -> Option<Id> {
+        if self.peek().is_none() {
+            return None;
+        }
+
+        let mut ahead = TkCursor(self.0.slice(1..));
+        ahead.peek()
+    }
+
+    /// Consume the next significant token, callers must `peek` first
+    fn bump(&mut self) -> TkSlice<'b> {
+        self.skip_spaces();
+        let token = self.0.slice(..1);
+        self.0 = self.0.slice(1..);
+        token
+    }
+
+    /// Consume the next token if it is `id`
+    fn eat(&mut self, id: Id) -> bool {
+        if self.peek() == Some(id) {
+            self.bump();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, id: Id) -> Result<TkSlice<'b>, ParserError> {
+        match self.peek() {
+            Some(next) if next == id => Ok(self.bump()),
+            _ => Err(ParserError::Token(id))
+        }
     }
 }
 
@@ -120,11 +275,9 @@ impl<'a> Parser<'a> {
         let bspp_tokens: Box<[Tk<'b>]> = match bspp.transform(p1_tokens) {
             Ok(boxed_tks) => boxed_tks,
             Err(err) => {
-                warn!("Had to eat error due to unspecific return type";
-                    "Error" => format!("{}", err));
-
                 return ParserResult::Error(
-                    ParsedAst::new(None, None, TkSlice(tokens), &[]));
+                    ParsedAst::new(None, None, TkSlice(tokens), &[])
+                        .with_error(ParseError::message(err)));
             }
         };
         let p2_tokens = &(*bspp_tokens);
@@ -135,10 +288,10 @@ impl<'a> Parser<'a> {
         //  *(box -> value) => value
         //  &(value) => ptr value
         let slice = TkSlice(p2_tokens);
-        let result = self.tkslice_to_ast(slice).1;
-
+        self.state.failure = None;
+        let (parser, result) = self.tkslice_to_ast(slice);
+        *self = parser;
 
-        // TODO: {T94} Try to incorporate parser error messages here
         match result {
             IResult::Done(ref remaining, ref ast) if remaining.len() == 0 => {
                 ParserResult::Ok(ParsedAst::new(Some(ast), None, p1_tokens, p2_tokens))
@@ -146,26 +299,43 @@ impl<'a> Parser<'a> {
 
             // Still an error case since there are remaining tokens
             IResult::Done(ref remaining, ref ast) => {
+                let error = self.parse_error(tokens, p2_tokens, remaining.len());
                 ParserResult::Error(ParsedAst::new(
-                    Some(ast), Some(*remaining), p1_tokens, p2_tokens))
+                    Some(ast), Some(*remaining), p1_tokens, p2_tokens).with_error(error))
             },
-            IResult::Error(_) => {
-                // TODO: {T94} Consume error in parse result in some useful message
-                ParserResult::Error(ParsedAst::new(None, None, p1_tokens, p2_tokens))
-            }
+            IResult::Error(_) |
             IResult::Incomplete(_) => {
-                // TODO: {T94} nom::Needed enum has some extra info about parsing
-                ParserResult::Error(ParsedAst::new(None, None, p1_tokens, p2_tokens))
+                let error = self.parse_error(tokens, p2_tokens, p2_tokens.len());
+                ParserResult::Error(ParsedAst::new(None, None, p1_tokens, p2_tokens).with_error(error))
             }
         }
     }
 
-    // Example of keeping parser state, see sub_stmt_next_line.
-    // Eventually the parser should inject extra context into
-    // ast nodes for richer errors.
-    fn inc_lineno(&mut self) -> usize{
-        self.state.line += 1;
-        self.state.line
+    /// Find the token that stopped the statement parsers with `remaining` of the
+    /// preprocessed `tokens` left. Failures of the expression parser that got further
+    /// into a statement are more precise and know what they expected.
+    fn parse_error<'b>(&self, source: &'b [Tk<'b>], tokens: &'b [Tk<'b>], remaining: usize) -> ParseError {
+        let (remaining, expected) = match self.state.failure {
+            Some((left, error)) if left < remaining => (left, Some(error.expected())),
+            _ => (remaining, None)
+        };
+
+        let rest = TkSlice(&tokens[tokens.len() - remaining..]);
+        let found = match filter_non_critical_python_whitespace(rest) {
+            IResult::Done(rest, _) => rest.tokens().first(),
+            _ => rest.tokens().first()
+        };
+
+        // The end of the input is just after the last token that is not whitespace
+        let end = source.iter().rev()
+            .find(|tk| match tk.id() {
+                Id::Space | Id::Tab | Id::Newline => false,
+                _ => true
+            })
+            .map(|tk| tk.span().after(tk.bytes()))
+            .unwrap_or(Span { offset: 0, line: 1, column: 0 });
+
+        ParseError::new(found, end, expected)
     }
 
     // AST Parser-Builders - they map approximately with Grammar.txt exceptions
@@ -195,6 +365,7 @@ impl<'a> Parser<'a> {
             call_m!(self.sub_stmt_funcdef)                      |
             call_m!(self.sub_stmt_block)                        |
             call_m!(self.sub_stmt_return)                       |
+            call_m!(self.sub_stmt_delete)                       |
             call_m!(self.sub_stmt_assign)                       |
             call_m!(self.sub_stmt_augassign)                    |
             call_m!(self.sub_stmt_assert)                       |
@@ -213,22 +384,42 @@ impl<'a> Parser<'a> {
     ///     return 1
     /// ```
     tk_method!(sub_stmt_funcdef, 'b, <Parser<'a>, Stmt>, mut self, do_parse!(
-                   def_keyword                                  >>
+          keyword: def_keyword                                  >>
         func_name: name_token                                   >>
                    lparen_token                                 >>
             args:  call_m!(self.sub_expr_func_args)             >>
                    rparen_token                                 >>
                    colon_token                                  >>
-       body_block: call_m!(self.sub_stmt_block)                 >>
+       body_block: call_m!(self.sub_stmt_body)                  >>
 
           (Stmt::FunctionDef {
                 fntype: FnType::Sync ,
                 name: func_name.as_owned_token(),
                 body: Box::new(body_block),
-                arguments: args
+                arguments: args,
+                span: keyword.as_token().span()
            })
     ));
 
+    /// The block of a compound statement. An indented block is required after the
+    /// colon, a missing one is recorded as the failure so a def without a body is
+    /// reported as such, and as incomplete when the input ends after the colon.
+    fn sub_stmt_body<'b>(mut self, i: TkSlice<'b>) -> (Parser<'a>, IResult<TkSlice<'b>, Stmt>) {
+        if let IResult::Done(_, _) = block_start(i) {
+            return self.sub_stmt_block(i);
+        }
+
+        let remaining = i.tokens().iter()
+            .skip_while(|tk| match tk.id() {
+                Id::Space | Id::Tab | Id::Newline | Id::LineContinuation | Id::Comment => true,
+                _ => false
+            })
+            .count();
+
+        self.state.fail(remaining, ParserError::Token(Id::BlockStart));
+        self.sub_stmt_block(i)
+    }
+
     /// Blocks are a unit of nesting that can contain many statements including
     /// other nested blocks and functions and stuff.
     ///
@@ -256,10 +447,39 @@ impl<'a> Parser<'a> {
     /// return to_sender
     /// ```
     tk_method!(sub_stmt_return, 'b, <Parser<'a>, Stmt>, mut self, do_parse!(
-        return_keyword                                          >>
-        value: opt!(call_m!(self.start_expr))                   >>
+       keyword: return_keyword                                  >>
+         value: opt!(call_m!(self.start_expr))                  >>
+
+        (Stmt::Return {
+            value: value,
+            span: keyword.as_token().span()
+        })
+    ));
+
+    /// 4.1  | Delete(expr* targets)
+    ///
+    /// ```python
+    /// del the_evidence, the_backups
+    /// ```
+    ///
+    /// Notes:
+    ///   1. Only supports names as targets (no `del x.attr` or `del x[0]`)
+    tk_method!(sub_stmt_delete, 'b, <Parser<'a>, Stmt>, mut self, do_parse!(
+       keyword: del_keyword                                     >>
+         first: name_token                                      >>
+          rest: many0!(preceded!(comma_token, name_token))      >>
 
-        (Stmt::Return(value))
+        ({
+            let mut targets: Vec<Expr> = vec![Expr::Constant(first.as_owned_token())];
+            for tk in rest.iter() {
+                targets.push(Expr::Constant(tk.as_owned_token()));
+            }
+
+            Stmt::Delete {
+                targets: targets,
+                span: keyword.as_token().span()
+            }
+        })
     ));
 
     /// 5.   | Assign(expr* targets, expr value)
@@ -301,19 +521,18 @@ impl<'a> Parser<'a> {
     /// assert [1,2,3,4]
     /// ```
     tk_method!(sub_stmt_assert, 'b, <Parser<'a>, Stmt>, mut self, do_parse!(
-                 assert_keyword                                 >>
-            stmt: alt_complete!(
+        keyword: assert_keyword                                 >>
+           args: alt_complete!(
                     call_m!(self.sub_stmt_assert_2arg)          |
                     map!(
                         call_m!(self.start_expr),
-                        |expr: Expr| {
-                            Stmt::Assert {
-                                test:expr,
-                                message: None
-                                }
-                        })                                      )>>
-
-            (stmt)
+                        |expr: Expr| (expr, None))              )>>
+
+            (Stmt::Assert {
+                test: args.0,
+                message: args.1,
+                span: keyword.as_token().span()
+            })
     ));
 
     /// 16.2 - an assert with an additional expression as a message
@@ -321,16 +540,13 @@ impl<'a> Parser<'a> {
     /// ```python
     /// assert False, "halp!"
     /// ```
-    tk_method!(sub_stmt_assert_2arg, 'b, <Parser<'a>, Stmt>, mut self, do_parse!(
+    tk_method!(sub_stmt_assert_2arg, 'b, <Parser<'a>, (Expr, Option<Expr>)>, mut self, do_parse!(
            test: terminated!(
                     call_m!(self.start_expr),
                     comma_token)                            >>
         message: call_m!(self.start_expr)                   >>
 
-        (Stmt::Assert {
-            test: test,
-            message: Some(message)
-        })
+        ((test, Some(message)))
     ));
 
 
@@ -345,468 +561,274 @@ impl<'a> Parser<'a> {
     /// 22.   └ attributes (int lineno, int col_offset)
     /// Inject a empty statement for the next line
     tk_method!(sub_stmt_next_line, 'b, <Parser<'a>, Stmt>, mut self, do_parse!(
-        newline_token                                           >>
+        newline: newline_token                                  >>
 
-        (Stmt::Newline(self.inc_lineno()))
+        (Stmt::Newline(newline.as_token().span().line))
     ));
 
 
 
     /// START(expr)
-    tk_method!(pub start_expr, 'b, <Parser<'a>, Expr>, mut self, do_parse!(
-        expression: alt_complete!(
-            call_m!(self.sub_expr_lambda)                       |
-            call_m!(self.sub_expr_conditional)                  |
-            call_m!(self.sub_expr_call)                         |
-            call_m!(self.sub_expr_getattr)                      |
-            call_m!(self.sub_expr_list)                         |
-            call_m!(self.sub_expr_dict)                         |
-            call_m!(self.sub_expr_operator)                     |
-            call_m!(self.sub_expr_nameconstant)                 |
-            call_m!(self.sub_expr_constant)                     ) >>
-        (expression)
-    ));
-
-    /// 4.   | Lambda(arguments args, expr body)
     ///
-    /// ```python
-    /// lambda fleece: fleece == 'white as snow'
-    /// ```
-    tk_method!(sub_expr_lambda, 'b, <Parser<'a>, Expr>, mut self, do_parse!(
-              lambda_keyword                                    >>
-        args: call_m!(self.sub_expr_func_args)                  >>
-              colon_token                                       >>
-        body: call_m!(self.start_expr)                          >>
-
-        (Expr::Lambda {
-            arguments: args,
-            body: Box::new(body)
-        })
-    ));
+    /// Expressions are parsed with precedence climbing (a Pratt parser) in a single left
+    /// to right pass over the tokens. Each operator token is looked at once to decide if
+    /// it binds tighter than the operator to its left, so there is no backtracking and
+    /// the cost of parsing an expression is linear in the number of tokens.
+    pub fn start_expr<'b>(mut self, i: TkSlice<'b>) -> (Parser<'a>, IResult<TkSlice<'b>, Expr>) {
+        let mut cursor = TkCursor(i);
+
+        let result = match self.parse_test(&mut cursor) {
+            Ok(expr) => IResult::Done(cursor.0, expr),
+            Err(error) => {
+                cursor.skip_spaces();
+                self.state.fail(cursor.0.len(), error);
+                IResult::Error(error.code())
+            }
+        };
 
+        (self, result)
+    }
+
+    /// 4.   | Lambda(arguments args, expr body)
     /// 5.   | IfExp(expr test, expr body, expr orelse)
     ///
     /// ```python
+    /// lambda fleece: fleece == 'white as snow'
     /// cant_handle if True else ignorance_is_bliss
     /// ```
-    tk_method!(sub_expr_conditional, 'b, <Parser<'a>, Expr>, mut self, do_parse!(
-        cons: many1!(not_if_keyword)                            >>
-              if_keyword                                        >>
-        cond: many1!(not_else_keyword)                          >>
-              else_keyword                                      >>
-         alt: call_m!(self.start_expr)                          >>
-        expr: call_m!(self.build_conditional, cons, cond, alt)  >>
-       (expr)
-    ));
-
-    /// 1.   = BoolOp(boolop op, expr* values)
-    /// 2.   | BinOp(expr left, operator op, expr right)
-    /// 3.   | UnaryOp(unaryop op, expr operand)
-    ///
-    ///  Uses the order of the explicit rules to do a variation on
-    ///  using a greedily consuming recursive decent with backtracking.
-    ///  Note that this implementation considers boolop to be a binary op.
-    tk_method!(sub_expr_operator, 'b, <Parser<'a>, Expr>, mut self, do_parse!(
-        expression: alt_complete!(
-          call_m!(self.sub_expr_boolop_logic_or)                |
-          call_m!(self.sub_expr_boolop_logic_and)               |
-          call_m!(self.sub_expr_binop_equality)                 |
-          call_m!(self.sub_expr_binop_inequality)               |
-          call_m!(self.sub_expr_binop_is)                       |
-          call_m!(self.sub_expr_binop_in)                       |
-          call_m!(self.sub_expr_binop_not_in)                   |
-          call_m!(self.sub_expr_unaryop_logicnot)               |
-          call_m!(self.sub_expr_unaryop_neg)                    |
-          call_m!(self.sub_expr_binop_lt)                       |
-          call_m!(self.sub_expr_binop_lte)                      |
-          call_m!(self.sub_expr_binop_gt)                       |
-          call_m!(self.sub_expr_binop_gte)                      |
-          call_m!(self.sub_expr_binop_or)                       |
-          call_m!(self.sub_expr_binop_xor)                      |
-          call_m!(self.sub_expr_binop_and)                      |
-          call_m!(self.sub_expr_binop_lshift)                   |
-          call_m!(self.sub_expr_binop_rshift)                   |
-          call_m!(self.sub_expr_binop_add)                      |
-          call_m!(self.sub_expr_binop_sub)                      |
-          call_m!(self.sub_expr_binop_mul)                      |
-          call_m!(self.sub_expr_binop_matmul)                   |
-          call_m!(self.sub_expr_binop_truediv)                  |
-          call_m!(self.sub_expr_binop_floordiv)                 |
-          call_m!(self.sub_expr_binop_mod)                      |
-          // NOTE: The power operator ** binds less tightly than an arithmetic or
-          // bitwise unary operator on its right, that is, 2**-1 is 0.5.
-          call_m!(self.sub_expr_binop_pow)                      ) >>
-
-       (expression)
-    ));
-
-
-    /// 1.1.  `a or b`
-    tk_method!(sub_expr_boolop_logic_or, 'b, <Parser<'a>, Expr>, mut self, do_parse!(
-        lhs: many1!(not_or_token)                               >>
-         op: or_token                                           >>
-        rhs: call_m!(self.start_expr)                           >>
-       expr: call_m!(self.build_binop, op, lhs, rhs)            >>
-
-        (expr)
-    ));
-
-
-    /// 1.2. `a and b`
-    tk_method!(sub_expr_boolop_logic_and, 'b, <Parser<'a>, Expr>, mut self, do_parse!(
-        lhs: many1!(not_and_token)                              >>
-         op: and_token                                          >>
-        rhs: call_m!(self.start_expr)                           >>
-       expr: call_m!(self.build_binop, op, lhs, rhs)            >>
-
-        (expr)
-    ));
-
-
-    /// 2.1. `a | b`
-    tk_method!(sub_expr_binop_or, 'b, <Parser<'a>, Expr>, mut self, do_parse!(
-        lhs: many1!(not_pipe_token)                             >>
-         op: pipe_token                                         >>
-        rhs: call_m!(self.start_expr)                           >>
-       expr: call_m!(self.build_binop, op, lhs, rhs)            >>
-
-        (expr)
-    ));
-
-
-    /// 2.2. `a ^ b`
-    tk_method!(sub_expr_binop_xor, 'b, <Parser<'a>, Expr>, mut self, do_parse!(
-        lhs: many1!(not_caret_token)                            >>
-         op: caret_token                                        >>
-        rhs: call_m!(self.start_expr)                           >>
-       expr: call_m!(self.build_binop, op, lhs, rhs)            >>
-
-        (expr)
-    ));
-
-    /// 2.3. `a & b`
-    tk_method!(sub_expr_binop_and, 'b, <Parser<'a>, Expr>, mut self, do_parse!(
-        lhs: many1!(not_amp_token)                              >>
-         op: amp_token                                          >>
-        rhs: call_m!(self.start_expr)                           >>
-       expr: call_m!(self.build_binop, op, lhs, rhs)            >>
+    fn parse_test<'b>(&self, cursor: &mut TkCursor<'b>) -> Result<Expr, ParserError> {
+        if cursor.peek() == Some(Id::Lambda) {
+            return self.parse_lambda(cursor);
+        }
 
-        (expr)
-    ));
+        let consequent = self.parse_operator(cursor, precedence::LOWEST)?;
+        if !cursor.eat(Id::If) {
+            return Ok(consequent);
+        }
 
-    ///  `not a`
-    tk_method!(sub_expr_unaryop_logicnot, 'b, <Parser<'a>, Expr>, mut self, do_parse!(
-             op: not_token                                      >>
-        operand: call_m!(self.start_expr)                       >>
-        (Expr::UnaryOp {
-            op: Op(op.as_owned_token()),
-            operand: Box::new(operand)
-        })
-    ));
+        let condition = self.parse_operator(cursor, precedence::LOWEST)?;
+        cursor.expect(Id::Else)?;
+        let alternative = self.parse_test(cursor)?;
 
-    ///  `-a`
-    tk_method!(sub_expr_unaryop_neg, 'b, <Parser<'a>, Expr>, mut self, do_parse!(
-             op: minus_token                                    >>
-        operand: call_m!(self.start_expr)                       >>
-        (Expr::UnaryOp {
-            op: Op(op.as_owned_token()),
-            operand: Box::new(operand)
+        Ok(Expr::Conditional {
+            consequent: Box::new(consequent),
+            condition: Box::new(condition),
+            alternative: Box::new(alternative)
         })
-    ));
-
-    ///  `a == b`
-    tk_method!(sub_expr_binop_equality, 'b, <Parser<'a>, Expr>, mut self, do_parse!(
-        lhs: many1!(not_doubleequal_token)                      >>
-        op: doubleequal_token                                   >>
-        rhs: call_m!(self.start_expr)                           >>
-        expr: call_m!(self.build_binop, op, lhs, rhs)           >>
-
-        (expr)
-    ));
-
-    /// `a != b`
-    tk_method!(sub_expr_binop_inequality, 'b, <Parser<'a>, Expr>, mut self, do_parse!(
-        lhs: many1!(not_notequal_token)                         >>
-         op: notequal_token                                     >>
-        rhs: call_m!(self.start_expr)                           >>
-        expr: call_m!(self.build_binop, op, lhs, rhs)           >>
-
-        (expr)
-    ));
-
-    /// `a is (not?) b`
-    tk_method!(sub_expr_binop_is, 'b, <Parser<'a>, Expr>, mut self, do_parse!(
-        lhs: many1!(not_is_token)                               >>
-         op: alt_complete!(
-                // inline subparser to ensure `is not`
-                // is captured regardless of the spaces
-                // between 'is' and 'not' which can not
-                // be done with a tag.
-                do_parse!(is_token      >>
-                          not_token     >>
-                      (IS_NOT_TKSLICE))     |
-                is_token)                                       >>
-        rhs: call_m!(self.start_expr)                           >>
-        expr: call_m!(self.build_binop, op, lhs, rhs)           >>
-
-        (expr)
-    ));
-
-    /// `a in b`
-    tk_method!(sub_expr_binop_in, 'b, <Parser<'a>, Expr>, mut self, do_parse!(
-        lhs: many1!(not_in_token)                               >>
-         op: in_token                                           >>
-        rhs: call_m!(self.start_expr)                           >>
-        expr: call_m!(self.build_binop, op, lhs, rhs)           >>
-
-        (expr)
-    ));
-
-    /// `a not in b`
-    tk_method!(sub_expr_binop_not_in, 'b, <Parser<'a>, Expr>, mut self, do_parse!(
-        lhs: many1!(not_notin_token)                            >>
-         op: notin_token                                        >>
-        rhs: call_m!(self.start_expr)                           >>
-        expr: call_m!(self.build_binop, op, lhs, rhs)           >>
-
-        (expr)
-    ));
-
-    /// `a < b`
-    tk_method!(sub_expr_binop_lt, 'b, <Parser<'a>, Expr>, mut self, do_parse!(
-        lhs: many1!(not_less_token)                             >>
-         op: less_token                                         >>
-        rhs: call_m!(self.start_expr)                           >>
-        expr: call_m!(self.build_binop, op, lhs, rhs)           >>
-
-        (expr)
-    ));
-
-    /// `a <= b`
-    tk_method!(sub_expr_binop_lte, 'b, <Parser<'a>, Expr>, mut self, do_parse!(
-        lhs: many1!(not_lessorequal_token)                      >>
-         op: lessorequal_token                                  >>
-        rhs: call_m!(self.start_expr)                           >>
-        expr: call_m!(self.build_binop, op, lhs, rhs)           >>
-
-        (expr)
-    ));
-
-    /// `a > b`
-    tk_method!(sub_expr_binop_gt, 'b, <Parser<'a>, Expr>, mut self, do_parse!(
-        lhs: many1!(not_greater_token)                          >>
-         op: greater_token                                      >>
-        rhs: call_m!(self.start_expr)                           >>
-        expr: call_m!(self.build_binop, op, lhs, rhs)           >>
-
-        (expr)
-    ));
-
-    /// `a >= b`
-    tk_method!(sub_expr_binop_gte, 'b, <Parser<'a>, Expr>, mut self, do_parse!(
-        lhs: many1!(not_greaterorequal_token)                   >>
-         op: greaterorequal_token                               >>
-        rhs: call_m!(self.start_expr)                           >>
-        expr: call_m!(self.build_binop, op, lhs, rhs)           >>
-
-        (expr)
-    ));
-
-    /// 2.4. `a << b`
-    tk_method!(sub_expr_binop_lshift, 'b, <Parser<'a>, Expr>, mut self, do_parse!(
-        lhs: many1!(not_leftshift_token)                        >>
-         op: leftshift_token                                    >>
-        rhs: call_m!(self.start_expr)                           >>
-        expr: call_m!(self.build_binop, op, lhs, rhs)           >>
-
-        (expr)
-    ));
-
-
-    /// 2.5. `a >> b`
-    tk_method!(sub_expr_binop_rshift, 'b, <Parser<'a>, Expr>, mut self, do_parse!(
-        lhs: many1!(not_rightshift_token)                       >>
-         op: rightshift_token                                   >>
-        rhs: call_m!(self.start_expr)                           >>
-        expr: call_m!(self.build_binop, op, lhs, rhs)           >>
-
-        (expr)
-    ));
-
-
-    /// 2.6. `a + b`
-    tk_method!(sub_expr_binop_add, 'b, <Parser<'a>, Expr>, mut self, do_parse!(
-        lhs: many1!(not_plus_token)                             >>
-         op: plus_token                                         >>
-        rhs: call_m!(self.start_expr)                           >>
-        expr: call_m!(self.build_binop, op, lhs, rhs)           >>
-
-        (expr)
-    ));
-
-
-    /// 2.7. `a - b`
-    tk_method!(sub_expr_binop_sub, 'b, <Parser<'a>, Expr>, mut self, do_parse!(
-        lhs: many1!(not_minus_token)                            >>
-         op: minus_token                                        >>
-        rhs: call_m!(self.start_expr)                           >>
-        expr: call_m!(self.build_binop, op, lhs, rhs)           >>
-
-        (expr)
-    ));
+    }
 
-    /// 2.8. `a * b`
-    tk_method!(sub_expr_binop_mul, 'b, <Parser<'a>, Expr>, mut self, do_parse!(
-        lhs: many1!(not_star_token)                             >>
-         op: star_token                                         >>
-        rhs: call_m!(self.start_expr)                           >>
-        expr: call_m!(self.build_binop, op, lhs, rhs)           >>
+    /// `lambda a, b: body` where the arguments follow the same rules as `sub_expr_func_args`
+    fn parse_lambda<'b>(&self, cursor: &mut TkCursor<'b>) -> Result<Expr, ParserError> {
+        let keyword = cursor.expect(Id::Lambda)?;
 
-        (expr)
-    ));
+        let mut arguments: Vec<Expr> = Vec::new();
+        if cursor.peek() == Some(Id::Name) {
+            arguments.push(Expr::Constant(cursor.bump().as_owned_token()));
 
+            while cursor.eat(Id::Comma) {
+                arguments.push(Expr::Constant(cursor.expect(Id::Name)?.as_owned_token()));
+            }
+        }
 
-    /// 2.9. `a @ b`
-    tk_method!(sub_expr_binop_matmul, 'b, <Parser<'a>, Expr>, mut self, do_parse!(
-        lhs: many1!(not_at_token)                               >>
-         op: at_token                                           >>
-        rhs: call_m!(self.start_expr)                           >>
-        expr: call_m!(self.build_binop, op, lhs, rhs)           >>
-
-        (expr)
-    ));
+        cursor.expect(Id::Colon)?;
+        let body = self.parse_test(cursor)?;
 
+        Ok(Expr::Lambda {
+            arguments: arguments,
+            body: Box::new(body),
+            span: keyword.as_token().span()
+        })
+    }
 
-    /// 2.10. `a / b`
-    tk_method!(sub_expr_binop_truediv, 'b, <Parser<'a>, Expr>, mut self, do_parse!(
-        lhs: many1!(not_slash_token)                            >>
-         op: slash_token                                        >>
-        rhs: call_m!(self.start_expr)                           >>
-        expr: call_m!(self.build_binop, op, lhs, rhs)           >>
+    /// 1.   = BoolOp(boolop op, expr* values)
+    /// 2.   | BinOp(expr left, operator op, expr right)
+    /// 3.   | UnaryOp(unaryop op, expr operand)
+    ///
+    /// Parse the prefix operators or an operand and then keep folding binary operators
+    /// into the left hand side as long as they bind at least as tightly as `min_power`.
+    /// Note that this implementation considers boolop to be a binary op.
+    fn parse_operator<'b>(&self, cursor: &mut TkCursor<'b>, min_power: u8) -> Result<Expr, ParserError> {
+        let mut lhs = match cursor.peek().and_then(precedence::prefix) {
+            Some(power) => {
+                let op = cursor.bump();
+                let operand = self.parse_operator(cursor, power)?;
+
+                Expr::UnaryOp {
+                    op: Op(op.as_owned_token()),
+                    operand: Box::new(operand)
+                }
+            },
+            None => self.parse_atom(cursor)?
+        };
 
-        (expr)
-    ));
+        loop {
+            let (id, width) = match (cursor.peek(), cursor.peek_second()) {
+                // `is not` and `not in` are two keywords acting as a single operator
+                (Some(Id::Is), Some(Id::Not))   => (Id::IsNot, 2),
+                (Some(Id::Not), Some(Id::In))   => (Id::NotIn, 2),
+                (Some(Id::Not), _)              => break,
+                (Some(id), _)                   => (id, 1),
+                (None, _)                       => break,
+            };
+
+            let (left_power, right_power) = match precedence::infix(id) {
+                Some(powers) => powers,
+                None => break
+            };
+
+            if left_power < min_power {
+                break;
+            }
 
+            let op = match width {
+                1 => cursor.bump().as_owned_token(),
+                _ => {
+                    let span = cursor.bump().as_token().span();
+                    cursor.bump();
+                    match id {
+                        Id::IsNot => OwnedTk::from(&IS_NOT_TK.with_span(span)),
+                        _ => OwnedTk::from(&NOT_IN_TK.with_span(span)),
+                    }
+                }
+            };
+
+            let rhs = self.parse_operator(cursor, right_power)?;
+            lhs = Expr::BinOp {
+                op: Op(op),
+                left: Box::new(lhs),
+                right: Box::new(rhs)
+            };
+        }
 
-    /// 2.11. `a // b`
-    tk_method!(sub_expr_binop_floordiv, 'b, <Parser<'a>, Expr>, mut self, do_parse!(
-        lhs: many1!(not_doubleslash_token)                      >>
-         op: doubleslash_token                                  >>
-        rhs: call_m!(self.start_expr)                           >>
-        expr: call_m!(self.build_binop, op, lhs, rhs)           >>
+        Ok(lhs)
+    }
 
-        (expr)
-    ));
+    /// Names, constants, calls, and displays followed by any number of `.attr` trailers
+    fn parse_atom<'b>(&self, cursor: &mut TkCursor<'b>) -> Result<Expr, ParserError> {
+        let atom = match cursor.peek() {
+            Some(Id::Name) => {
+                let name = cursor.bump();
+
+                if cursor.eat(Id::LeftParen) {
+                    // 16.  | Call(expr func, expr* args, keyword* keywords)
+                    Expr::Call {
+                        func: name.as_owned_token(),
+                        args: self.parse_elements(cursor, Id::RightParen)?,
+                        keywords: (),
+                    }
+                } else {
+                    Expr::Constant(name.as_owned_token())
+                }
+            },
 
+            // 22.  | NameConstant(singleton value)
+            Some(Id::True)          |
+            Some(Id::False)         |
+            Some(Id::None)          => Expr::NameConstant(cursor.bump().as_owned_token()),
+
+            // 24.  | Constant(constant value)
+            Some(Id::Number)        |
+            Some(Id::String)        |
+            Some(Id::RawString)     |
+            Some(Id::FormatString)  |
+            Some(Id::ByteString)    => Expr::Constant(cursor.bump().as_owned_token()),
+
+            // 29. | List(expr* elts, expr_context ctx)
+            Some(Id::LeftBracket) => {
+                let open = cursor.bump().as_token();
+                Expr::List {
+                    elems: self.parse_elements(cursor, Id::RightBracket)?,
+                    span: open.span()
+                }
+            },
 
-    /// 2.12. `a % b`
-    tk_method!(sub_expr_binop_mod, 'b, <Parser<'a>, Expr>, mut self, do_parse!(
-        lhs: many1!(not_percent_token)                          >>
-         op: percent_token                                      >>
-        rhs: call_m!(self.start_expr)                           >>
-        expr: call_m!(self.build_binop, op, lhs, rhs)           >>
+            Some(Id::LeftBrace) => {
+                let open = cursor.bump().as_token();
+                Expr::Dict {
+                    items: self.parse_dict_items(cursor)?,
+                    span: open.span()
+                }
+            },
 
-        (expr)
-    ));
+            Some(Id::LeftParen) => {
+                let open = cursor.bump().as_token();
+                self.parse_parenthesized(cursor, open.span())?
+            },
 
-    /// 2.13. `a ** b`
-    tk_method!(sub_expr_binop_pow, 'b, <Parser<'a>, Expr>, mut self, do_parse!(
-        lhs: many1!(not_doublestar_token)                       >>
-         op: doublestar_token                                   >>
-        rhs: call_m!(self.start_expr)                           >>
-        expr: call_m!(self.build_binop, op, lhs, rhs)           >>
+            _ => return Err(ParserError::Atom)
+        };
 
-        (expr)
-    ));
+        // 25.  | Attribute(expr value, identifier attr, expr_context ctx)
+        let mut expr = atom;
+        while cursor.eat(Id::Dot) {
+            expr = Expr::Attribute {
+                value: Box::new(expr),
+                attr: cursor.expect(Id::Name)?.as_owned_token()
+            };
+        }
 
+        Ok(expr)
+    }
 
-    /// 16.  | Call(expr func, expr* args, keyword* keywords)
+    /// 30. | Tuple(expr* elts, expr_context ctx)
     ///
-    /// `a(b)`
-    tk_method!(sub_expr_call, 'b, <Parser<'a>, Expr>, mut self, do_parse!(
-        // TODO: func_name needs to be a scan and build like
-        // many1!(not_doublestar_token) because (1+2)() is valid in the
-        // grammar.
-        func_name: name_token                                   >>
-                   lparen_token                                 >>
-             args: call_m!(self.sub_expr_call_args)             >>
-                   rparen_token                                 >>
-
-        (Expr::Call {
-            func: func_name.as_owned_token(),
-            args: args,
-            keywords: (),
-         })
-    ));
-
+    /// A parenthesized expression is only a tuple if it is empty or contains a comma,
+    /// otherwise the parens just group the inner expression.
+    fn parse_parenthesized<'b>(&self, cursor: &mut TkCursor<'b>, span: Span) -> Result<Expr, ParserError> {
+        if cursor.eat(Id::RightParen) {
+            return Ok(Expr::Tuple { elems: Vec::new(), span: span });
+        }
 
-    /// 25.  | Attribute(expr value, identifier attr, expr_context ctx)
-    ///
-    /// `a.b`
-    tk_method!(sub_expr_getattr, 'b, <Parser<'a>, Expr>, mut self, do_parse!(
-        name: name_token                                        >>
-        expr: fold_many1!(
-                preceded!(dot_token, name_token),
-                Expr::Constant(name.as_owned_token()),
-                |acc, attr: TkSlice<'b>| {
-                    Expr::Attribute {
-                        value: Box::new(acc),
-                        attr: attr.as_owned_token()
-                    }
-                  }                                             )>>
+        let first = self.parse_test(cursor)?;
+        if cursor.eat(Id::RightParen) {
+            return Ok(first);
+        }
 
-        (expr)
-    ));
+        cursor.expect(Id::Comma)?;
+        let mut elems = vec![first];
+        elems.append(&mut self.parse_elements(cursor, Id::RightParen)?);
 
+        Ok(Expr::Tuple { elems: elems, span: span })
+    }
 
-    /// 29. | List(expr* elts, expr_context ctx)
+    /// Comma separated expressions up to and including the `close` token. A trailing
+    /// comma is allowed. Used for call arguments and list and tuple displays.
     ///
-    /// `[a, b, ...]`
-    tk_method!(sub_expr_list, 'b, <Parser<'a>, Expr>, mut self, do_parse!(
-               lbracket_token                                   >>
-        elems: call_m!(self.sub_expr_call_args)                 >>
-               rbracket_token                                   >>
-        (Expr::List { elems: elems })
-    ));
+    /// Notes:
+    ///   1. Only supports positional arguments. (no *args, or **kwargs).
+    fn parse_elements<'b>(&self, cursor: &mut TkCursor<'b>, close: Id) -> Result<Vec<Expr>, ParserError> {
+        let mut elems: Vec<Expr> = Vec::new();
 
-    tk_method!(sub_expr_dict, 'b, <Parser<'a>, Expr>, mut self, do_parse!(
-        expr: alt_complete!(
-            do_parse!(
-                       lbrace_token                         >>
-                items: call_m!(self.sub_expr_dict_items)    >>
-                       rbrace_token                         >>
-                (Expr::Dict { items: items}))                   |
-            do_parse!(
-                 lbrace_token                               >>
-                 rbrace_token                               >>
-                 (Expr::Dict {items: Vec::new()})
-            )) >>
-        (expr)
-    ));
+        while cursor.peek() != Some(close) {
+            elems.push(self.parse_test(cursor)?);
 
-    /// 22.  | NameConstant(singleton value)
-    tk_method!(sub_expr_nameconstant, 'b, <Parser<'a>, Expr>, mut self, do_parse!(
-        constant: alt_complete!(
-            tag!(&[Id::True])                                   |
-            tag!(&[Id::False])                                  |
-            tag!(&[Id::None])                                   ) >>
+            if !cursor.eat(Id::Comma) {
+                break;
+            }
+        }
 
-        (Expr::NameConstant(constant.as_owned_token()))
-    ));
+        cursor.expect(close)?;
+        Ok(elems)
+    }
 
-    /// 24.  | Constant(constant value)
-    tk_method!(sub_expr_constant, 'b, <Parser<'a>, Expr>, mut self, do_parse!(
-        constant: constant_token                                >>
+    /// Comma separated `key: value` pairs up to and including the closing brace
+    fn parse_dict_items<'b>(&self, cursor: &mut TkCursor<'b>) -> Result<Vec<(Expr, Expr)>, ParserError> {
+        let mut items: Vec<(Expr, Expr)> = Vec::new();
 
-        (Expr::Constant(constant.as_owned_token()))
-    ));
+        while cursor.peek() != Some(Id::RightBrace) {
+            let key = self.parse_test(cursor)?;
+            cursor.expect(Id::Colon)?;
+            let value = self.parse_test(cursor)?;
+            items.push((key, value));
 
-    //    // 31.   └ attributes (int lineno, int col_offset)
-    //    tk_method!(sub_expr_ended, 'b, <Parser<'a>, Expr>, mut self, do_parse!(
-    //        token: newline_token >>
-    //        (Expr::End)
-    //    ));
+            if !cursor.eat(Id::Comma) {
+                break;
+            }
+        }
 
+        cursor.expect(Id::RightBrace)?;
+        Ok(items)
+    }
 
     /// Function Args Sub Expression Parser
     ///
@@ -840,140 +862,76 @@ impl<'a> Parser<'a> {
             }
         })
     ));
+}
 
-    /// Call Args Sub Expression Parser
-    ///
-    /// Create an optional pair tuple (TkSlice, Vec<TkSlice>) by matching
-    /// against the special case of the single argument (e.g. `hello(name):`
-    /// and then the rest as the general case of argument names preceded by a comma
-    /// (e.g. `def add_all(a, b, c, d, e, f):`.
-    ///
-    /// Notes:
-    ///   1. Only supports positional arguments. (no *args, or **kwargs).
-    tk_method!(sub_expr_call_args, 'b, <Parser<'a>, Vec<Expr>>, mut self, do_parse!(
-        opt_arg_names: opt!(pair!(
-                            call_m!(self.start_expr),
-                            many0!(
-                                preceded!(
-                                    comma_token,
-                                    call_m!(self.start_expr))))
-                                                                ) >>
-        ({
-            match opt_arg_names {
-                Some(arg_names) => {
-                    let mut names: Vec<Expr> = Vec::new();
-                    names.push(arg_names.0);
-                    for tk in arg_names.1.iter() {
-                        names.push(tk.clone());
-                    }
-
-                    names
-                },
-                None => Vec::new()
-            }
-        })
-    ));
-
-    /// Call Args Sub Expression Parser
-    ///
-    tk_method!(sub_expr_dict_items, 'b, <Parser<'a>, Vec<(Expr, Expr)>>, mut self, do_parse!(
-        items: separated_list!(comma_token, call_m!(self.sub_expr_dict_item)) >>
-        (items)
-    ));
 
-    tk_method!(sub_expr_dict_item, 'b, <Parser<'a>, (Expr, Expr)>, mut self, do_parse!(
-        key: many1!(not_colon_token)                      >>
-        colon_token                                  >>
-        value: call_m!(self.start_expr)                     >>
-        item: call_m!(self.build_dict_item, key, value)    >>
-        (item)
-    ));
+/// Binding powers of the expression operators from loosest to tightest, see the
+/// operator precedence table in the python language reference. Each level is two apart
+/// so a binary operator can bind one tighter on its right hand side than its left to
+/// make it left associative.
+mod precedence {
+    use ::token::Id;
 
-    /// Helper for the recursive parsing of a subexpression as is common in
-    /// in cases where something has an infix-y semantic. For example:
-    /// ```x = 1 if y else 2``` or ```q = [i for i in entries if i >= 3]```
-    fn parse_sub_expr<'b>(mut self,
-                          slices: &'b [TkSlice<'b>]
-    ) -> Result<Expr, ParserError> {
-
-        // Flatten the tokens in all tokens slices into a single TkSlice
-        // and then try to parse that as an expression
-        let flattened: Vec<Tk<'b>> = slices.iter()
-            .flat_map(TkSlice::iter)
-            .map(|tk| *tk)
-            .collect::<Vec<Tk<'b>>>();
-
-        match self.start_expr(TkSlice(&flattened)) {
-            (_, IResult::Done(_, expr)) => Ok(expr),
-            _ => Err(ParserError::SubExpr)
+    pub const LOWEST: u8        = 0;
+    pub const OR: u8            = 2;
+    pub const AND: u8           = 4;
+    pub const NOT: u8           = 6;
+    pub const COMPARISON: u8    = 8;
+    pub const BITWISE_OR: u8    = 10;
+    pub const BITWISE_XOR: u8   = 12;
+    pub const BITWISE_AND: u8   = 14;
+    pub const SHIFT: u8         = 16;
+    pub const ARITHMETIC: u8    = 18;
+    pub const TERM: u8          = 20;
+    pub const UNARY: u8         = 22;
+    pub const POWER: u8         = 24;
+
+    /// Binding power of the operand of a prefix operator
+    pub fn prefix(id: Id) -> Option<u8> {
+        match id {
+            Id::Not             => Some(NOT),
+            Id::Plus            |
+            Id::Minus           |
+            Id::Tilde           => Some(UNARY),
+            _                   => None
         }
     }
 
-    /// Offload the work to parse tokens in the LHS here instead of in the binop parsers
-    /// as a way to get around type inference hell. See `build_conditional`.
-    fn build_binop<'b>(mut self,
-                       i: TkSlice<'b>,
-                       op: TkSlice<'b>,
-                       lhs: Vec<TkSlice<'b>>,
-                       rhs: Expr) -> (Parser<'a>, IResult<TkSlice<'b>, Expr>) {
-
-        let left = match self.parse_sub_expr(&lhs) {
-            Ok(expr) => expr,
-            Err(error) => return (self, IResult::Error(error.code()))
+    /// Left and right binding powers of a binary operator
+    pub fn infix(id: Id) -> Option<(u8, u8)> {
+        let power = match id {
+            Id::Or              => OR,
+            Id::And             => AND,
+            Id::DoubleEqual     |
+            Id::NotEqual        |
+            Id::LeftAngle       |
+            Id::LessOrEqual     |
+            Id::RightAngle      |
+            Id::GreaterOrEqual  |
+            Id::Is              |
+            Id::IsNot           |
+            Id::In              |
+            Id::NotIn           => COMPARISON,
+            Id::Pipe            => BITWISE_OR,
+            Id::Caret           => BITWISE_XOR,
+            Id::Amp             => BITWISE_AND,
+            Id::LeftShift       |
+            Id::RightShift      => SHIFT,
+            Id::Plus            |
+            Id::Minus           => ARITHMETIC,
+            Id::Star            |
+            Id::At              |
+            Id::Slash           |
+            Id::DoubleSlash     |
+            Id::Percent         => TERM,
+            // NOTE: The power operator ** binds less tightly than an arithmetic or
+            // bitwise unary operator on its right, that is, 2**-1 is 0.5. It is also
+            // the only right associative operator.
+            Id::DoubleStar      => return Some((POWER, POWER - 1)),
+            _                   => return None
         };
 
-        let binop_expr = Expr::BinOp {
-            op: Op(op.as_owned_token()),
-            left: Box::new(left),
-            right: Box::new(rhs)
-        };
-
-        let result: IResult<TkSlice<'b>, Expr> = IResult::Done(i, binop_expr);
-        (self, result)
-    }
-
-    /// Offloads the work to parse the conditional subexpressions found in `sub_expr_conditional`.
-    /// This could probably be done in that function however, there were issues with
-    /// proper error type inference. In the future, a brave soul may wish to try to
-    /// add a map!(..., ...) onto the subexpression matchers for purity.
-    fn build_conditional<'b>(mut self, i: TkSlice<'b>,
-                             cons: Vec<TkSlice<'b>>,
-                             cond: Vec<TkSlice<'b>>,
-                             alt: Expr
-    ) -> (Parser<'a>, IResult<TkSlice<'b>, Expr>) {
-
-        let consequent = match self.parse_sub_expr(&cons) {
-            Ok(expr) => expr,
-            Err(error) => return (self, IResult::Error(error.code()))
-        };
-
-        let conditional = match self.parse_sub_expr(&cond) {
-            Ok(expr) => expr,
-            Err(error) => return (self, IResult::Error(error.code()))
-        };
-
-        let cond_expr = Expr::Conditional {
-            consequent: Box::new(consequent),
-            condition: Box::new(conditional),
-            alternative: Box::new(alt)
-        };
-
-        let result: IResult<TkSlice<'b>, Expr> = IResult::Done(i, cond_expr);
-        (self, result)
-    }
-
-    fn build_dict_item<'b>(mut self,
-                           i: TkSlice<'b>,
-                           key: Vec<TkSlice<'b>>,
-                           value: Expr) -> (Parser<'a>, IResult<TkSlice<'b>, (Expr, Expr)>) {
-
-        let key_expr = match self.parse_sub_expr(&key) {
-            Ok(expr) => expr,
-            Err(error) => return (self, IResult::Error(error.code()))
-        };
-
-        let result: IResult<TkSlice<'b>, (Expr, Expr)> = IResult::Done(i, (key_expr, value));
-        (self, result)
+        Some((power, power + 1))
     }
 }
 
@@ -1006,6 +964,7 @@ mod internal {
     tk_named!(pub assert_keyword     <TkSlice<'a>>, ignore_spaces!(tag!(&[Id::Assert])));
     tk_named!(pub async_keyword     <TkSlice<'a>>, ignore_spaces!(tag!(&[Id::Async])));
     tk_named!(pub await_keyword     <TkSlice<'a>>, ignore_spaces!(tag!(&[Id::Await])));
+    tk_named!(pub del_keyword       <TkSlice<'a>>, ignore_spaces!(tag!(&[Id::Del])));
     tk_named!(pub def_keyword       <TkSlice<'a>>, ignore_spaces!(tag!(&[Id::Def])));
     tk_named!(pub class_keyword     <TkSlice<'a>>, ignore_spaces!(tag!(&[Id::Class])));
     tk_named!(pub if_keyword        <TkSlice<'a>>, ignore_spaces!(tag!(&[Id::If])));
@@ -1052,58 +1011,6 @@ mod internal {
     tk_named!(pub block_end         <TkSlice<'a>>, ignore_spaces!(tag!(&[Id::BlockEnd])));
 
 
-    // Flattened not definitions to make the type inference happy to prevent this case:
-    //
-    // error[E0282]: type annotations needed
-    //     --> src/parser.rs:404:5
-    //     |
-    //     404 |  tk_method!(sub_expr_conditional, 'b, <Parser<'a>, Expr>, mut self, do_parse!(
-    //     |   _____^
-    //     |  |_____|
-    //     | ||
-    // 405 | ||         cons: many1!(not!(if_keyword))      >>
-    // 406 | ||               if_keyword                    >>
-    // 407 | ||         cond: many1!(not!(else_keyword))    >>
-    // ...   ||
-    // 413 | ||        (expr)
-    // 414 | ||     ));
-    //     | ||       ^
-    //     | ||_______|
-    //     |  |_______in this macro invocation
-    //     |          cannot infer type for `E`
-    //     |
-    //     = note: this error originates in a macro outside of the current crate
-    tk_named!(pub not_if_keyword        <TkSlice<'a>>,  tk_is_none_of!(&[Id::If, Id::Newline]));
-    tk_named!(pub not_else_keyword      <TkSlice<'a>>,  tk_is_none_of!(&[Id::Else, Id::Newline]));
-
-    tk_named!(pub not_or_token          <TkSlice<'a>>,  tk_is_none_of!(&[Id::Or, Id::Newline]));
-    tk_named!(pub not_and_token         <TkSlice<'a>>,  tk_is_none_of!(&[Id::And, Id::Newline]));
-    tk_named!(pub not_pipe_token        <TkSlice<'a>>,  tk_is_none_of!(&[Id::Pipe, Id::Newline]));
-    tk_named!(pub not_caret_token       <TkSlice<'a>>,  tk_is_none_of!(&[Id::Caret, Id::Newline]));
-    tk_named!(pub not_amp_token         <TkSlice<'a>>,  tk_is_none_of!(&[Id::Amp, Id::Newline]));
-    tk_named!(pub not_not_token         <TkSlice<'a>>,  tk_is_none_of!(&[Id::Not, Id::Newline]));
-    tk_named!(pub not_doubleequal_token <TkSlice<'a>>,  tk_is_none_of!(&[Id::DoubleEqual, Id::Newline]));
-    tk_named!(pub not_notequal_token    <TkSlice<'a>>,  tk_is_none_of!(&[Id::NotEqual, Id::Newline]));
-    tk_named!(pub not_is_token          <TkSlice<'a>>,  tk_is_none_of!(&[Id::Is, Id::Newline]));
-    tk_named!(pub not_in_token          <TkSlice<'a>>,  tk_is_none_of!(&[Id::In, Id::Newline]));
-    tk_named!(pub not_notin_token       <TkSlice<'a>>,  tk_is_none_of!(&[Id::Not, Id::In, Id::Newline]));
-    tk_named!(pub not_less_token        <TkSlice<'a>>,  tk_is_none_of!(&[Id::LeftAngle, Id::Newline]));
-    tk_named!(pub not_lessorequal_token <TkSlice<'a>>,  tk_is_none_of!(&[Id::LessOrEqual, Id::Newline]));
-    tk_named!(pub not_greater_token     <TkSlice<'a>>,  tk_is_none_of!(&[Id::RightAngle, Id::Newline]));
-    tk_named!(pub not_greaterorequal_token <TkSlice<'a>>,  tk_is_none_of!(&[Id::GreaterOrEqual, Id::Newline]));
-    tk_named!(pub not_leftshift_token   <TkSlice<'a>>,  tk_is_none_of!(&[Id::LeftShift, Id::Newline]));
-    tk_named!(pub not_rightshift_token  <TkSlice<'a>>,  tk_is_none_of!(&[Id::RightShift, Id::Newline]));
-    tk_named!(pub not_plus_token        <TkSlice<'a>>,  tk_is_none_of!(&[Id::Plus, Id::Newline]));
-    tk_named!(pub not_minus_token       <TkSlice<'a>>,  tk_is_none_of!(&[Id::Minus, Id::Newline]));
-    tk_named!(pub not_star_token        <TkSlice<'a>>,  tk_is_none_of!(&[Id::Star, Id::Newline]));
-    tk_named!(pub not_at_token          <TkSlice<'a>>,  tk_is_none_of!(&[Id::At, Id::Newline]));
-    tk_named!(pub not_slash_token       <TkSlice<'a>>,  tk_is_none_of!(&[Id::Slash, Id::Newline]));
-    tk_named!(pub not_doubleslash_token <TkSlice<'a>>,  tk_is_none_of!(&[Id::DoubleSlash, Id::Newline]));
-    tk_named!(pub not_percent_token     <TkSlice<'a>>,  tk_is_none_of!(&[Id::Percent, Id::Newline]));
-    tk_named!(pub not_doublestar_token  <TkSlice<'a>>,  tk_is_none_of!(&[Id::DoubleStar, Id::Newline]));
-    tk_named!(pub not_dot_token         <TkSlice<'a>>,  tk_is_none_of!(&[Id::Dot, Id::Newline]));
-    tk_named!(pub not_colon_token       <TkSlice<'a>>,  tk_is_none_of!(&[Id::Colon, Id::Newline]));
-
     /// Unary Operatos: `+`, `-`,
     tk_named!(pub unaryop_token <TkSlice<'a>>, ignore_spaces!(
         alt_complete!(
@@ -1201,6 +1108,10 @@ mod tests {
         }
     }
 
+    // Stmt::Delete
+    basic_test!(stmt_del_one,            "del x");
+    basic_test!(stmt_del_many,           "del x, y,z");
+
     // Stmt::Assign(Expr::Constant)
     basic_test!(stmt_assign_int,         "x = 134567");
     basic_test!(stmt_assign_hex,         "x = 0xabdef");
@@ -1279,6 +1190,8 @@ mod tests {
     basic_test!(expr_binop_n3, "1 - 1 ** 2");
     basic_test!(expr_binop_n4, "1 + 1 // 2");
     basic_test!(expr_binop_n5, "1 + 1 // 2 * 'hello' ^ 'world' ");
+    basic_test!(expr_binop_n6, "a not in b or c is not d");
+    basic_test!(expr_binop_n7, "(a + b) * -c ** 2");
 
 
     // Expr::Call
@@ -1309,6 +1222,11 @@ mod tests {
     // TODO: {T118} Binop Scanning Wrecks Args and Elems
     basic_test!(expr_list_05, r#"[3, [a,b,c,len([1,2,3])], x + y]"#);
 
+    // Expr::Tuple
+    basic_test!(expr_tuple_01, r#"()"#);
+    basic_test!(expr_tuple_02, r#"(a,)"#);
+    basic_test!(expr_tuple_03, r#"(a, (b, c), [d],)"#);
+
     // Expr::Dict
     basic_test!(expr_dict_01, r#"{}"#);
     basic_test!(expr_dict_02, r#"{a: b}"#);
@@ -1338,4 +1256,151 @@ x = 1 + \
 "#);
 
     basic_test!(empty_str, r#"f = ''"#);
+
+
+    /// Use to create a named test case that checks the shape of the parsed expression
+    /// by comparing it to a prefix notation rendering of the `Expr`.
+    macro_rules! expr_test {
+        ($name:ident, $code:expr, $expected:expr) => {
+            #[test]
+            fn $name() {
+                assert_eq!(render(&parse_expr($code)), $expected);
+            }
+        };
+    }
+
+    fn parse_expr(input: &str) -> Expr {
+        let r: Rc<IResult<&[u8], Vec<Tk>>> = Lexer::new().tokenize(input.as_bytes());
+        let b: &IResult<&[u8], Vec<Tk>> = r.borrow();
+
+        let tokens = match b {
+            &IResult::Done(_, ref tokens) => tokens,
+            _ => panic!("Unable to tokenize input")
+        };
+
+        match Parser::new().start_expr(TkSlice(tokens)).1 {
+            IResult::Done(ref remaining, ref expr) if remaining.len() == 0 => expr.clone(),
+            result => panic!("Unable to parse expression {:?}", result)
+        }
+    }
+
+    fn render(expr: &Expr) -> String {
+        let render_all = |exprs: &[Expr]| exprs.iter().map(render).collect::<Vec<String>>().join(" ");
+
+        match *expr {
+            Expr::BinOp { ref op, ref left, ref right } => {
+                format!("({} {} {})", op.0.as_string(), render(left), render(right))
+            },
+            Expr::UnaryOp { ref op, ref operand } => {
+                format!("({} {})", op.0.as_string(), render(operand))
+            },
+            Expr::Conditional { ref condition, ref consequent, ref alternative } => {
+                format!("(if {} {} {})", render(condition), render(consequent), render(alternative))
+            },
+            Expr::Lambda { ref arguments, ref body, .. } => {
+                format!("(lambda ({}) {})", render_all(arguments), render(body))
+            },
+            Expr::Call { ref func, ref args, .. } => {
+                format!("({} {})", func.as_string(), render_all(args))
+            },
+            Expr::Attribute { ref value, ref attr } => {
+                format!("(. {} {})", render(value), attr.as_string())
+            },
+            Expr::List { ref elems, .. } => format!("[{}]", render_all(elems)),
+            Expr::Tuple { ref elems, .. } => format!("(tuple {})", render_all(elems)),
+            Expr::Dict { ref items, .. } => {
+                let items = items.iter()
+                    .map(|&(ref key, ref value)| format!("{}: {}", render(key), render(value)))
+                    .collect::<Vec<String>>();
+                format!("{{{}}}", items.join(", "))
+            },
+            Expr::NameConstant(ref tk) |
+            Expr::Constant(ref tk) => tk.as_string(),
+            Expr::None => String::from("?")
+        }
+    }
+
+    expr_test!(shape_left_assoc,        "a - b - c",            "(- (- a b) c)");
+    expr_test!(shape_precedence,        "a + b * c - d",        "(- (+ a (* b c)) d)");
+    expr_test!(shape_pow_right_assoc,   "a ** b ** c",          "(** a (** b c))");
+    expr_test!(shape_pow_unary_rhs,     "2 ** -1",              "(** 2 (- 1))");
+    expr_test!(shape_pow_unary_lhs,     "-a ** b",              "(- (** a b))");
+    expr_test!(shape_unary_term,        "-a * b",               "(* (- a) b)");
+    expr_test!(shape_logic,             "not a == b and c or d", "(or (and (not (== a b)) c) d)");
+    expr_test!(shape_is_not,            "a is not b",           "(is not a b)");
+    expr_test!(shape_not_in,            "a not in b",           "(not in a b)");
+    expr_test!(shape_bitwise,           "a | b ^ c & d << e",   "(| a (^ b (& c (<< d e))))");
+    expr_test!(shape_parens,            "(a + b) * c",          "(* (+ a b) c)");
+    expr_test!(shape_tuple,             "(a, b + c)",           "(tuple a (+ b c))");
+    expr_test!(shape_call_args,         "f(a, b + c, [d])",     "(f a (+ b c) [d])");
+    expr_test!(shape_call_attr,         "f(a).b.c",             "(. (. (f a) b) c)");
+    expr_test!(shape_conditional,       "a if b else c if d else e", "(if b a (if d c e))");
+    expr_test!(shape_lambda,            "lambda x, y: x + y",   "(lambda (x y) (+ x y))");
+    expr_test!(shape_dict,              "{a: b + c, d: [e]}",   "{a: (+ b c), d: [e]}");
+
+
+    fn tokenize<'a>(input: &'a str) -> Vec<Tk<'a>> {
+        match Lexer::new().tokenize2(input.as_bytes()) {
+            IResult::Done(_, tokens) => tokens,
+            _ => panic!("Unable to tokenize input")
+        }
+    }
+
+    #[test]
+    fn node_spans() {
+        let tokens = tokenize("x = 1\nreturn (a, [b])\n");
+
+        let stmts = match Parser::new().parse_tokens(&tokens) {
+            ParserResult::Ok(ParsedAst { ast: Ast::Module(Module::Body(stmts)), .. }) => stmts,
+            result => panic!("Unable to parse input {:?}", result)
+        };
+
+        let position = |span: Span| (span.line, span.column);
+        assert_eq!(position(stmts[0].span()), (1, 0));
+
+        match stmts[2] {
+            Stmt::Return { value: Some(Expr::Tuple { ref elems, span }), .. } => {
+                assert_eq!(position(stmts[2].span()), (2, 0));
+                assert_eq!(position(span), (2, 7));
+                assert_eq!(position(elems[1].span()), (2, 11));
+            },
+            ref other => panic!("Expected a return statement, got {:?}", other)
+        }
+    }
+
+    /// Use to create a named test case that checks where a syntax error is reported
+    /// and its message.
+    macro_rules! error_test {
+        ($name:ident, $code:expr, ($line:expr, $column:expr), $message:expr) => {
+            #[test]
+            fn $name() {
+                let tokens = tokenize($code);
+                let error = match Parser::new().parse_tokens(&tokens) {
+                    ParserResult::Error(ParsedAst { error: Some(error), .. }) => error,
+                    result => panic!("Expected a parse error, got {:?}", result)
+                };
+
+                assert_eq!((error.span.line, error.span.column), ($line, $column));
+                assert_eq!(error.message, $message);
+            }
+        };
+    }
+
+    error_test!(error_missing_operand,  "x = )",            (1, 4),     "unexpected ')', expected an expression");
+    error_test!(error_unclosed_paren,   "print((1, 2)",     (1, 12),    "unexpected end of input, expected ')'");
+    error_test!(error_unclosed_list,    "x = 1\ny = [1, 2\nz", (2, 9), "unexpected end of line, expected ']'");
+    error_test!(error_invalid_char,     "x = 1 + $",        (1, 8),     "invalid character '$', expected an expression");
+    error_test!(error_missing_body,     "def f():\n",      (1, 8),     "unexpected end of input, expected an indented block");
+
+    #[test]
+    fn incomplete_input() {
+        let error = |code: &str| match Parser::new().parse_tokens(&tokenize(code)) {
+            ParserResult::Error(ParsedAst { error: Some(error), .. }) => error,
+            result => panic!("Expected a parse error, got {:?}", result)
+        };
+
+        assert!(error("def f():\n").is_incomplete());
+        assert!(error("print((1, 2)").is_incomplete());
+        assert!(!error("x = )").is_incomplete());
+    }
 }
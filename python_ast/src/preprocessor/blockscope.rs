@@ -5,7 +5,7 @@ use slog;
 use slog_scope;
 
 
-use ::token::{NEWLINE_BYTES, NEWLINE, SPACE, Tk, Id, Tag};
+use ::token::{NEWLINE_BYTES, TK_NEWLINE, TK_SPACE, Tk, Id, Tag, Span};
 use ::slice::{TkSlice};
 use ::preprocessor::Preprocessor;
 
@@ -32,11 +32,6 @@ pub const TK_LINE_CONT: Tk  = Tk::const_(
 );
 
 
-const BLOCK_START: &'static [Tk] = &[TK_BLOCK_START];
-const BLOCK_END  : &'static [Tk] = &[TK_BLOCK_END];
-const LINE_CONT  : &'static [Tk] = &[TK_LINE_CONT];
-
-
 // TODO: {T91} move to rsnek_runtime::macros
 macro_rules! strings_error_indent_mismatch {
     ($len:expr, $indent:expr) => {
@@ -118,11 +113,12 @@ impl BlockScopePreprocessor {
     }
 
     /// Given the length of the current span of whitespace, the size of the first discovered
-    /// indent, and the current index in the indent stack.
+    /// indent, and the current index in the indent stack. The emitted tokens are located
+    /// at `newline`, the newline they replace.
     #[inline]
     fn balance_scopes<'b>(&self, span_len: usize, indent: usize,
                           stack_idx_start: usize, indent_stack: &mut [usize],
-                          acc: &mut Vec<TkSlice<'b>>) -> Result<usize, String> {
+                          newline: Span, acc: &mut Vec<Tk<'b>>) -> Result<usize, String> {
 
         if span_len % indent != 0 {
             return Err(strings_error_indent_mismatch!(span_len, indent));
@@ -145,7 +141,7 @@ impl BlockScopePreprocessor {
                 "action" => "emit",
                 "token" => "BlockStart",
                 "stack_idx" => stack_idx);
-                acc.push(TkSlice(&BLOCK_START));
+                acc.push(TK_BLOCK_START.with_span(newline));
             },
             // The de-indent case where we are going from a nested scope N back to the
             // N-1 scope.
@@ -158,7 +154,7 @@ impl BlockScopePreprocessor {
                     "action" => "emit",
                     "token" => "BlockEnd",
                     "stack_idx" => stack_idx);
-                    acc.push(TkSlice(&BLOCK_END));
+                    acc.push(TK_BLOCK_END.with_span(newline));
 
                     if indent_stack[stack_idx] == span_len {
                         break 'emit_block_end;
@@ -167,7 +163,7 @@ impl BlockScopePreprocessor {
             },
             // Indent is the same as the current indent, so no changes
             curr if span_len == curr => {
-                acc.push(TkSlice(&NEWLINE));
+                acc.push(TK_NEWLINE.with_span(newline));
             }
             // Over indenting
             curr => {
@@ -196,7 +192,8 @@ impl<'a> Preprocessor<'a> for BlockScopePreprocessor {
             return Ok(tokens.tokens().to_owned().into_boxed_slice());
         }
 
-        let mut acc: Vec<TkSlice<'b>> = Vec::new();
+        let mut acc: Vec<Tk<'b>> = Vec::new();
+        let mut newline = Span::default();
 
         let mut stack_idx = 0;
         let mut indent_stack: [usize; INDENT_STACK_SIZE] = [0; INDENT_STACK_SIZE];
@@ -214,9 +211,9 @@ impl<'a> Preprocessor<'a> for BlockScopePreprocessor {
                 // continuation so start collapsing the whitespace
                 (Id::Newline, Id::Space, false, false, None) => {
                     if let Some(e) = end {
-                        acc.push(tokens.slice(e..idx - 1))
+                        acc.extend_from_slice(tokens.slice(e..idx - 1).tokens())
                     } else {
-                        acc.push(tokens.slice(..idx - 1));
+                        acc.extend_from_slice(tokens.slice(..idx - 1).tokens());
                     }
 
                     start = Some(idx);
@@ -236,21 +233,21 @@ impl<'a> Preprocessor<'a> for BlockScopePreprocessor {
                     end = Some(idx);
 
                     match self.balance_scopes(span.len(), indent, stack_idx,
-                                              &mut indent_stack, &mut acc) {
+                                              &mut indent_stack, newline, &mut acc) {
                         Ok(new_stack_idx) => stack_idx = new_stack_idx,
                         Err(string) => return Err(string)
                     };
 
-                    acc.push(span);
+                    acc.extend_from_slice(span.tokens());
                 },
                 // A top level scope cannot close its scopes until it finds the next one
                 (Id::Newline, id, false, false, _) if id != Id::Space && id != Id::Newline => {
                     // TODO: {T92} Formalize preprocessing to allow for injection of expression start
                     // and end
                     if let Some(e) = end {
-                        acc.push(tokens.slice(e..idx - 1))
+                        acc.extend_from_slice(tokens.slice(e..idx - 1).tokens())
                     } else {
-                        acc.push(tokens.slice(..idx - 1));
+                        acc.extend_from_slice(tokens.slice(..idx - 1).tokens());
                     }
 
                     seen_non_ws = true;
@@ -258,7 +255,7 @@ impl<'a> Preprocessor<'a> for BlockScopePreprocessor {
                     end = Some(idx);
 
                     match self.balance_scopes(0, indent, stack_idx,
-                                              &mut indent_stack, &mut acc) {
+                                              &mut indent_stack, newline, &mut acc) {
                         Ok(new_stack_idx) => stack_idx = new_stack_idx,
                         Err(string) => return Err(string)
                     };
@@ -274,11 +271,11 @@ impl<'a> Preprocessor<'a> for BlockScopePreprocessor {
                     is_continuation = true;
                     match (start, end) {
                         (Some(s), None) => {
-                            acc.push(tokens.slice(s..idx - 1));
+                            acc.extend_from_slice(tokens.slice(s..idx - 1).tokens());
                             //start = Some(idx);
                         },
                         (None, Some(e)) => {
-                            acc.push(tokens.slice(e..idx - 1));
+                            acc.extend_from_slice(tokens.slice(e..idx - 1).tokens());
                             end = Some(idx);
                         },
 
@@ -299,12 +296,12 @@ impl<'a> Preprocessor<'a> for BlockScopePreprocessor {
                         "stack_idx" => stack_idx
                     );
 
-                    acc.push(TkSlice(&SPACE));
+                    acc.push(TK_SPACE.with_span(tk.span()));
                     start = Some(idx);
                 },
                 // Continuation start case.
                 (Id::Backslash, Id::Newline, true, _, Some(s)) => {
-                    acc.push(tokens.slice(s..idx - 1));
+                    acc.extend_from_slice(tokens.slice(s..idx - 1).tokens());
 
                     slog_trace!(self.log,
                         "{}", self.name();
@@ -313,7 +310,7 @@ impl<'a> Preprocessor<'a> for BlockScopePreprocessor {
                         "stack_idx" => stack_idx
                     );
 
-                    acc.push(TkSlice(&LINE_CONT));
+                    acc.push(TK_LINE_CONT.with_span(tk.span()));
                     start = Some(idx + 1);
                 },
                 // TODO: {T92} Formalize preprocessing to allow for injection of expression start
@@ -333,6 +330,10 @@ impl<'a> Preprocessor<'a> for BlockScopePreprocessor {
                 _ => {}
             };
 
+            if tk.id() == Id::Newline {
+                newline = tk.span();
+            }
+
             last_tk = (idx, tk.clone());
         }
 
@@ -340,7 +341,7 @@ impl<'a> Preprocessor<'a> for BlockScopePreprocessor {
         // scope boundary.
         match (start, end) {
             (Some(i), _) |
-            (_, Some(i)) => acc.push(tokens.slice(i..)),
+            (_, Some(i)) => acc.extend_from_slice(tokens.slice(i..).tokens()),
             _ => unreachable!()
         };
 
@@ -349,15 +350,10 @@ impl<'a> Preprocessor<'a> for BlockScopePreprocessor {
         // all open block starts are balanced with block ends.
         'emit_trailing_end_scopes: while stack_idx != 0 {
             stack_idx -= 1;
-            acc.push(TkSlice(&BLOCK_END));
+            acc.push(TK_BLOCK_END.with_span(newline));
         }
 
-        let scoped_tokens = acc.iter()
-            .flat_map(TkSlice::iter)
-            .map(Tk::clone)
-            .collect::<Vec<Tk<'b>>>();
-
-        Ok(scoped_tokens.into_boxed_slice())
+        Ok(acc.into_boxed_slice())
     }
 
 }
\ No newline at end of file
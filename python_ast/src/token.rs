@@ -14,9 +14,45 @@ pub const SPACE_BYTES:   &'static [u8] = &[32];
 pub const TK_NEWLINE: Tk    = Tk::const_(Id::Newline, NEWLINE_BYTES, Tag::W(Ws::Newline));
 pub const TK_SPACE: Tk      = Tk::const_(Id::Space,   SPACE_BYTES,   Tag::W(Ws::Space));
 
-//pub const TAB:      &'static [Tk]       = &[TK_TAB];
-pub const NEWLINE:  &'static [Tk]       = &[TK_NEWLINE];
-pub const SPACE:    &'static [Tk]       = &[TK_SPACE];
+
+/// Where a token starts in the source. `offset` is in bytes from the start of the
+/// input, `line` counts from 1 and `column` counts characters from 0 like the
+/// `lineno` and `col_offset` attributes of CPython's ast nodes. Tokens that were
+/// not read from the source have a line of 0.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, PartialOrd, Ord, Hash, Default, Serialize)]
+pub struct Span {
+    pub offset: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+
+impl Span {
+    /// True if the span points into the source
+    pub fn is_known(&self) -> bool {
+        self.line != 0
+    }
+
+    /// The position just past `bytes` when they start at this span
+    pub fn after(&self, bytes: &[u8]) -> Span {
+        let mut span = *self;
+        span.offset += bytes.len();
+
+        for &byte in bytes.iter() {
+            match byte {
+                b'\n' => {
+                    span.line += 1;
+                    span.column = 0;
+                },
+                // Continuation bytes of multibyte utf-8 characters
+                byte if byte & 0xC0 == 0x80 => {},
+                _ => span.column += 1
+            }
+        }
+
+        span
+    }
+}
 
 
 /// Attempt to make an owned token to get out of lifetime hell. I found myself
@@ -27,7 +63,8 @@ pub const SPACE:    &'static [Tk]       = &[TK_SPACE];
 pub struct OwnedTk {
     id: Id,
     bytes: Vec<u8>,
-    tag: Tag
+    tag: Tag,
+    span: Span
 }
 
 impl<'a> From<&'a Tk<'a>> for OwnedTk {
@@ -35,7 +72,8 @@ impl<'a> From<&'a Tk<'a>> for OwnedTk {
         OwnedTk {
             id: tk.id,
             bytes: tk.bytes.to_vec(),
-            tag: tk.tag
+            tag: tk.tag,
+            span: tk.span
         }
     }
 }
@@ -46,7 +84,8 @@ impl<'a> From<&'a TkSlice<'a>> for OwnedTk {
         OwnedTk {
             id: tk.id,
             bytes: tk.bytes.to_vec(),
-            tag: tk.tag
+            tag: tk.tag,
+            span: tk.span
         }
     }
 }
@@ -59,6 +98,7 @@ impl OwnedTk {
         self.id
     }
     pub fn tag(&self) -> Tag { self.tag }
+    pub fn span(&self) -> Span { self.span }
 
     pub fn as_string(&self) -> String {
         String::from_utf8_lossy(self.bytes()).to_string()
@@ -72,9 +112,10 @@ impl Serialize for OwnedTk {
         where S: Serializer
     {
         // 3 is the number of fields in the struct.
-        let mut state = serializer.serialize_struct("OwnedTk", 2)?;
+        let mut state = serializer.serialize_struct("OwnedTk", 3)?;
         state.serialize_field("id", &self.id)?;
         state.serialize_field("value", &self.as_string())?;
+        state.serialize_field("span", &self.span)?;
         //state.serialize_field("tag", &self.tag)?;
         state.end()
     }
@@ -87,7 +128,9 @@ pub struct Tk<'a> {
     #[serde(with = "serde_bytes")]
     bytes: &'a [u8],
 
-    tag: Tag
+    tag: Tag,
+
+    span: Span
 }
 
 
@@ -101,10 +144,17 @@ impl<'a> Tk<'a> {
         Tk {
             id: id,
             bytes: bytes,
-            tag: tag
+            tag: tag,
+            span: Span { offset: 0, line: 0, column: 0 }
         }
     }
 
+    /// The same token located at `span`
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.span = span;
+        self
+    }
+
     pub fn bytes(&self) -> &[u8] {
         &self.bytes[..]
     }
@@ -112,6 +162,7 @@ impl<'a> Tk<'a> {
         self.id
     }
     pub fn tag(&self) -> Tag { self.tag }
+    pub fn span(&self) -> Span { self.span }
 
     pub fn as_string(&self) -> String {
         String::from_utf8_lossy(self.bytes).to_string()
@@ -147,7 +198,8 @@ impl<'a> New<'a> for Tk<'a> {
         Tk {
             id: id,
             bytes: bytes,
-            tag: tag
+            tag: tag,
+            span: Span::default()
         }
     }
 }
@@ -420,3 +472,37 @@ impl Display for Id {
     }
 }
 
+
+
+impl Id {
+    /// How a token with this id is described in syntax errors
+    pub fn describe(&self) -> String {
+        let text = match *self {
+            Id::Name                => return String::from("a name"),
+            Id::Number              => return String::from("a number"),
+            Id::Newline             => return String::from("a newline"),
+            Id::BlockStart          => return String::from("an indented block"),
+            Id::BlockEnd            => return String::from("a dedent"),
+            Id::String              |
+            Id::RawString           |
+            Id::ByteString          |
+            Id::FormatString        => return String::from("a string"),
+            Id::LeftParen           => "(",
+            Id::LeftBracket         => "[",
+            Id::LeftBrace           => "{",
+            Id::RightParen          => ")",
+            Id::RightBracket        => "]",
+            Id::RightBrace          => "}",
+            Id::Colon               => ":",
+            Id::Comma               => ",",
+            Id::Semicolon           => ";",
+            Id::Equal               => "=",
+            Id::Dot                 => ".",
+            Id::Else                => "else",
+            Id::In                  => "in",
+            other                   => return format!("{:?}", other)
+        };
+
+        format!("'{}'", text)
+    }
+}
@@ -31,6 +31,22 @@ pub fn setup<'a>(text: &str) ->  Ast {
     }
 }
 
+/// Lex outside of the timed loop so only the parser is measured
+macro_rules! parser_benchmark (
+    ($name:ident, $code:expr) => (
+        #[bench]
+        fn $name(b: &mut Bencher) {
+            let text: String = $code.to_string();
+            let tokens = match Lexer::new().tokenize2(text.as_bytes()) {
+                LexResult::Done(left, ref tokens) if left.len() == 0 => tokens.clone(),
+                _ => unreachable!()
+            };
+
+            b.iter(|| Parser::new().parse_tokens(&tokens));
+        }
+    );
+);
+
 macro_rules! compiler_benchmark (
     ($name:ident, $code:expr) => (
         #[bench]
@@ -180,6 +196,8 @@ self.{}
     chained_attr!(attrs_0016,     16);
     chained_attr!(attrs_0064,     64);
     chained_attr!(attrs_0256,    256);
+    chained_attr!(attrs_1024,   1024);
+    chained_attr!(attrs_4096,   4096);
 }
 
 #[cfg(test)]
@@ -203,5 +221,58 @@ mod expr_binop {
     chained_binop!(ops_0008,      8);
     chained_binop!(ops_0016,     16);
     chained_binop!(ops_0064,     64);
+    chained_binop!(ops_0256,    256);
+    chained_binop!(ops_1024,   1024);
+    chained_binop!(ops_4096,   4096);
+}
+
+
+/// Parse only benchmarks of long expressions. The time per operand should stay flat
+/// as the number of operands grows since the expression parser does not backtrack.
+#[cfg(test)]
+mod parse_expr {
+    use super::*;
+
+    fn format_binop(n: usize, op: &str) -> String {
+        format!("x = {}\n", (0..n).map(|i| format!("var{}", i)).join(op))
+    }
+
+    fn format_nested_parens(n: usize) -> String {
+        format!("x = {}var{}\n", "(".repeat(n), ")".repeat(n))
+    }
+
+    macro_rules! parse_binop(
+        ($name:ident, $N:expr, $op:expr) => (
+            parser_benchmark!($name, format_binop($N, $op));
+        );
+    );
+
+    macro_rules! parse_parens(
+        ($name:ident, $N:expr) => (
+            parser_benchmark!($name, format_nested_parens($N));
+        );
+    );
+
+    parse_binop!(add_0002,          2, " + ");
+    parse_binop!(add_0016,         16, " + ");
+    parse_binop!(add_0256,        256, " + ");
+    parse_binop!(add_1024,       1024, " + ");
+    parse_binop!(add_4096,       4096, " + ");
+
+    parse_binop!(mixed_0002,        2, " * 2 + ");
+    parse_binop!(mixed_0016,       16, " * 2 + ");
+    parse_binop!(mixed_0256,      256, " * 2 + ");
+    parse_binop!(mixed_1024,     1024, " * 2 + ");
+    parse_binop!(mixed_4096,     4096, " * 2 + ");
+
+    parse_binop!(compare_0002,      2, " < ");
+    parse_binop!(compare_0016,     16, " < ");
+    parse_binop!(compare_0256,    256, " < ");
+    parse_binop!(compare_1024,   1024, " < ");
+    parse_binop!(compare_4096,   4096, " < ");
+
+    parse_parens!(parens_0002,      2);
+    parse_parens!(parens_0016,     16);
+    parse_parens!(parens_0256,    256);
 }
 
@@ -8,7 +8,9 @@ use test::Bencher;
 
 
 use rsnek::compiler::{Compiler, CompilerResult, Instr};
+use rsnek::resources::strings;
 use rsnek::runtime::{Runtime, Interpreter};
+use rsnek::system::primitives::Code;
 
 
 pub fn setup<'a>(code: &str) ->  Box<[Instr]> {
@@ -32,6 +34,21 @@ macro_rules! bench_snippet (
     );
 );
 
+/// Same as `bench_snippet!` but runs one code object every iteration so its inline
+/// caches are filled by the first run and hit by the others.
+macro_rules! bench_cached (
+    ($name:ident, $code:expr) => (
+        #[bench]
+        fn $name(b: &mut Bencher) {
+            let rt = Runtime::new();
+            let mut interpreter = Interpreter::new(&rt);
+            let code = Code::module(strings::SOURCE_STRING, setup($code).to_vec());
+            b.iter(|| interpreter.exec_code(&rt, &code).unwrap());
+            assert!(code.co_caches.hits() > 0, "no inline cache hits");
+        }
+    );
+);
+
 
 #[cfg(test)]
 mod expr_call {
@@ -77,3 +94,22 @@ mod expr_binop {
     bench_snippet!(_0019_list_mul_16,           r#"[0,1,2,3,4,5,6,7,8,9] * 16"#);
     bench_snippet!(_0020_list_mul_64,           r#"[0,1,2,3,4,5,6,7,8,9] * 64"#);
 }
+
+#[cfg(test)]
+mod expr_attr {
+    use super::*;
+
+    bench_snippet!(_0000_module_attr,           r#"gc.collect"#);
+    bench_snippet!(_0001_module_attr_x4,        "gc.collect\ngc.enable\ngc.disable\ngc.isenabled");
+    bench_snippet!(_0002_sys_attr,              r#"sys.intern"#);
+}
+
+#[cfg(test)]
+mod expr_attr_cached {
+    use super::*;
+
+    bench_cached!(_0000_module_attr,            r#"gc.collect"#);
+    bench_cached!(_0001_module_attr_x4,         "gc.collect\ngc.enable\ngc.disable\ngc.isenabled");
+    bench_snippet!(_0002_list_method,           "l = [1, 2, 3]\nappend = l.append");
+    bench_cached!(_0003_list_method_cached,     "l = [1, 2, 3]\nappend = l.append");
+}
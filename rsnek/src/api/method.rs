@@ -91,7 +91,7 @@ api_trait!(ternary, self, __setattr__, SetAttr, op_setattr, native_setattr, rs::
 /// 
 /// ```
 ///
-api_trait!(binary, self, __delattr__, DelAttr, op_delattr, native_delattr);
+api_trait!(binary, self, __delattr__, DelAttr, op_delattr, native_delattr, rs::None);
 
 
 //
@@ -795,12 +795,12 @@ api_trait!(unary, self, __length_hint__, LengthHint, op_length_hint, native_leng
 api_trait!(unary, self, __next__, Next, op_next, native_next, RtObject);
 
 /// object.__reversed__
-/// 
-/// 
+///
+///
 /// ```python
-/// 
+/// reversed(x)
 /// ```
-api_trait!(unary, self, __reversed__, Reversed, op_reversed, native_reversed);
+api_trait!(unary, self, __reversed__, Reversed, op_reversed, native_reversed, rs::Iterator);
 
 /// __getitem__
 ///
@@ -36,7 +36,10 @@ pub enum ErrorType {
     Recursion,
     Assertion,
     Syntax,
-    Index
+    Index,
+    ZeroDivision,
+    /// Raised by `sys.exit()` with the exit status of the process
+    SystemExit(i64),
 }
 
 
@@ -104,10 +107,29 @@ impl Error {
         Error(ErrorType::Syntax, message.to_string())
     }
 
+    /// Syntax error at `column` of `text`, the content of line `line` of `filename`.
+    /// The location is kept ahead of the message and printed with a caret under the
+    /// column like CPython does.
+    pub fn syntax_at(filename: &str, line: usize, text: &str, column: usize, message: &str) -> Error {
+        Error(ErrorType::Syntax, format!(
+            "  File \"{}\", line {}\n    {}\n    {}^\n{}",
+            filename, line, text, " ".repeat(column), message))
+    }
+
     pub fn index(message: &str) -> Error {
         Error(ErrorType::Index, message.to_string())
     }
 
+    pub fn zero_division(message: &str) -> Error {
+        Error(ErrorType::ZeroDivision, message.to_string())
+    }
+
+    /// Exit the process with `status`, `message` is printed to stderr first unless
+    /// it is empty.
+    pub fn system_exit(status: i64, message: &str) -> Error {
+        Error(ErrorType::SystemExit(status), message.to_string())
+    }
+
 
     pub fn log(&self) {
         error!("{:?}Error", self.error_type(); "message" => self.message());
@@ -117,7 +139,15 @@ impl Error {
 
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{:?}Error: {}", self.error_type(), self.message())
+        match (&self.0, self.1.rfind('\n')) {
+            (&ErrorType::SystemExit(status), _) if self.1.is_empty() => write!(f, "SystemExit: {}", status),
+            (&ErrorType::SystemExit(_), _) => write!(f, "SystemExit: {}", self.1),
+            // The location of a syntax error from `Error::syntax_at` goes first
+            (&ErrorType::Syntax, Some(idx)) => {
+                write!(f, "{}\n{:?}Error: {}", &self.1[..idx], self.error_type(), &self.1[idx + 1..])
+            },
+            _ => write!(f, "{:?}Error: {}", self.error_type(), self.message())
+        }
     }
 }
 
@@ -12,7 +12,7 @@ use std::ops::Deref;
 use num::Zero;
 use serde::ser::{Serialize, Serializer};
 
-use ::api::result::{Error, ErrorType, ObjectResult, RtResult};
+use ::api::result::{Error, ObjectResult, RtResult};
 use ::api::method::{self, Id, Next, StringCast, StringRepresentation, Equal};
 use ::api;
 use ::runtime::Runtime;
@@ -31,6 +31,21 @@ type RuntimeWeakRef = WeakRc<Type>;
 ///
 pub struct RtObject(RuntimeRef);
 
+/// Objects can be shared between threads when built with `rsnek_multithreaded`:
+///
+///  - `StrongRc` and `WeakRc` are `Arc` and `sync::Weak`
+///  - Mutable state is kept in the lock backed cells of `system::sync`, no object
+///    type uses the `std::cell` types or `std::rc::Rc`
+///  - The closures of `rs::WrapperFn` and `rs::MethodFn` are bound by `Send + Sync`
+///  - Iterators hold a `Runtime`, which is shared for the same reasons
+///
+/// A new object type or field has to keep to these or this impl is unsound.
+#[cfg(feature="rsnek_multithreaded")]
+unsafe impl Send for RtObject {}
+
+#[cfg(feature="rsnek_multithreaded")]
+unsafe impl Sync for RtObject {}
+
 
 impl RtObject {
     #[inline]
@@ -119,31 +134,6 @@ impl AsRef<Type> for RtObject {
     }
 }
 
-/// While it is cool to be able to directly iterate over an `RtObject`
-/// it is impractical and likely impossible to debug if the critical
-/// case is hit.
-impl Iterator for RtObject {
-    type Item = RtObject;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        match self.as_ref() {
-            &Type::Iter(ref iterator) => {
-                match iterator.native_next() {
-                    Ok(objref) => Some(objref),
-                    Err(Error(ErrorType::StopIteration, _)) => None,
-                    Err(err) => {
-                        crit!("Iterator logic fault"; "cause" => format!("{:?}", err));
-                        None
-                    }
-                }
-            }
-            _ => None
-        }
-    }
-
-}
-
-
 impl api::PyAPI for RtObject {}
 
 impl method::GetAttr for RtObject {
@@ -167,6 +157,16 @@ impl method::SetAttr for RtObject {
     }
 }
 
+impl method::DelAttr for RtObject {
+    fn op_delattr(&self, rt: &Runtime, name: &RtObject) -> ObjectResult {
+        foreach_type!(self.as_ref(), rt, op_delattr, lhs, name)
+    }
+
+    fn native_delattr(&self, name: &Type) -> RtResult<rs::None> {
+        native_foreach_type!(self.as_ref(), native_delattr, lhs, name)
+    }
+}
+
 
 impl method::Id for RtObject {
     fn op_id(&self, rt: &Runtime) -> ObjectResult {
@@ -753,6 +753,17 @@ impl method::Iter for RtObject {
 }
 
 
+impl method::Reversed for RtObject {
+    fn op_reversed(&self, rt: &Runtime) -> ObjectResult {
+        foreach_type!(self.as_ref(), rt, op_reversed, lhs)
+    }
+
+    fn native_reversed(&self) -> RtResult<rs::Iterator> {
+        native_foreach_type!(self.as_ref(), native_reversed, lhs)
+    }
+}
+
+
 impl method::Call for RtObject {
     fn op_call(&self, rt: &Runtime, pos_args: &RtObject, starargs: &RtObject, kwargs: &RtObject) -> ObjectResult {
         foreach_type!(self.as_ref(), rt, op_call, method, pos_args, starargs, kwargs)
@@ -907,13 +918,13 @@ impl method::Keys for RtObject {
 
 
 method_not_implemented!(RtObject,
-    Await   Clear   Close   DelAttr   Delete
+    Await   Clear   Close   Delete
     DescriptorGet   DescriptorSet   DescriptorSetName   Discard   Enter
     Exit   Get   GetAttribute   Init   Items
     LengthHint   New   PopItem   ReflectedAdd   ReflectedBitwiseAnd
     ReflectedBitwiseOr   ReflectedDivMod   ReflectedFloorDivision   ReflectedLeftShift
     ReflectedMatrixMultiply  ReflectedModulus   ReflectedMultiply   ReflectedPow
-    ReflectedRightShift   ReflectedSubtract  ReflectedTrueDivision   ReflectedXOr   Reversed
+    ReflectedRightShift   ReflectedSubtract  ReflectedTrueDivision   ReflectedXOr
     Rounding   Send   SetDefault   Throw   Update   Values
 );
 
@@ -924,6 +935,13 @@ method_not_implemented!(RtObject,
 
 pub struct WeakRtObject(RuntimeWeakRef);
 
+/// See the `Send` and `Sync` impls of `RtObject`
+#[cfg(feature="rsnek_multithreaded")]
+unsafe impl Send for WeakRtObject {}
+
+#[cfg(feature="rsnek_multithreaded")]
+unsafe impl Sync for WeakRtObject {}
+
 
 impl Default for WeakRtObject {
     fn default() -> WeakRtObject {
@@ -973,6 +991,12 @@ impl WeakRtObject {
             Some(objref) => Ok(RtObject(objref)),
         }
     }
+
+    /// Same as `upgrade` but for callers where a dead reference is expected
+    /// and not worth building an error for, like the cycle collector.
+    pub fn try_upgrade(&self) -> Option<RtObject> {
+        WeakRc::upgrade(&self.0).map(RtObject)
+    }
 }
 
 
@@ -3,7 +3,6 @@
 //! Using `RtValue<T>` allows for injection of a `WeakRc` back to the contained `RtValue<T>`
 //! value after it has been boxed and moved into the `StrongRc` struct.
 //!
-use std::cell::RefCell;
 use std::fmt::{Debug, Formatter, Result};
 use std::ops::Deref;
 
@@ -13,6 +12,7 @@ use ::api::result::Error;
 use ::api::{RtObject, WeakRtObject};
 use ::api::result::ObjectResult;
 use ::system::primitives as rs;
+use ::system::sync::RefCell;
 
 
 
@@ -4,10 +4,14 @@
 extern crate slog;
 extern crate slog_term;
 extern crate slog_async;
+extern crate slog_json;
 #[macro_use]
 extern crate slog_scope;
 
 use slog::Drain;
+use std::env;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
 use std::sync::Arc;
 
 
@@ -18,23 +22,115 @@ extern crate rsnek;
 use clap::{Arg, App, ArgGroup};
 
 use rsnek::resources::strings;
-use rsnek::runtime::{Interpreter, Config, Logging, Mode};
-use rsnek::system::ThreadModel;
+use rsnek::runtime::{Interpreter, Config, Dump, Logging, LogFormat, LogLevel, LogTarget, Mode, Profile};
+use rsnek::runtime::config::LOG_ENV;
+use rsnek::system::{ExitCode, ThreadModel, GREENLET_STACK_SIZE, RECURSION_LIMIT};
+use rsnek::system::stack::MIN_STACK_SIZE;
 
 
-fn main() {
-    let decorator = slog_term::TermDecorator::new().build();
-    let drain = slog_term::FullFormat::new(decorator).build().fuse();
-    /// Chan size should probably be at least the recursion limit so it doesn't overflow
-    /// when a python script gets into super recursion
+/// Send log records where `logging` says, until this is called they are discarded
+/// so that stdout and stderr only carry the output of the program. Release builds
+/// leave out the debug and trace records when they are compiled, those cost
+/// nothing then.
+fn init_logging(logging: &Logging) -> io::Result<Option<slog_scope::GlobalLoggerGuard>> {
+    if !logging.is_enabled() {
+        return Ok(None)
+    }
+
+    let log = match (&logging.target, logging.format) {
+        (&LogTarget::Stderr, LogFormat::Text) => {
+            let decorator = slog_term::TermDecorator::new().stderr().build();
+            root_logger(slog_term::FullFormat::new(decorator).build().fuse(), logging)
+        },
+        (&LogTarget::File(ref path), LogFormat::Text) => {
+            let decorator = slog_term::PlainDecorator::new(open_log_file(path)?);
+            root_logger(slog_term::FullFormat::new(decorator).build().fuse(), logging)
+        },
+        (&LogTarget::Stderr, LogFormat::Json) => {
+            root_logger(slog_json::Json::default(io::stderr()).fuse(), logging)
+        },
+        (&LogTarget::File(ref path), LogFormat::Json) => {
+            root_logger(slog_json::Json::default(open_log_file(path)?).fuse(), logging)
+        },
+    };
+
+    Ok(Some(slog_scope::set_global_logger(log)))
+}
+
+
+fn open_log_file(path: &str) -> io::Result<std::fs::File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+
+/// Log the records `logging` allows to `drain` from a background thread
+fn root_logger<D>(drain: D, logging: &Logging) -> slog::Logger
+    where D: Drain<Ok=(), Err=slog::Never> + Send + 'static {
+
+    // Records are filtered before they are sent to the logging thread
+    let logging = logging.clone();
+    let drain = drain.filter(move |record: &slog::Record| {
+        logging.allows(record.module(), record.level())
+    }).ignore_res();
+
+    // Chan size should probably be at least the recursion limit so it doesn't overflow
+    // when a python script gets into super recursion
     let drain = slog_async::Async::new(drain).chan_size(8 * 1024).build();
-    let drain = drain.filter_level(slog::Level::Trace).fuse();
 
-    let log = slog::Logger::root(Arc::new(drain.fuse()), o!());
-    let _guard = slog_scope::set_global_logger(log);
+    slog::Logger::root(Arc::new(drain.fuse()), o!())
+}
 
-    info!("{}", format!("welcome to {}", strings::PROGRAM));
 
+/// The logging configuration of `RSNEK_LOG` and the flags in `matches`. `-v` sets
+/// the level of the modules without a level of their own in `RSNEK_LOG`, `-q` turns
+/// everything off.
+fn logging_config(matches: &clap::ArgMatches) -> Result<Logging, String> {
+    let mut logging = Logging::default();
+
+    if let Ok(spec) = env::var(LOG_ENV) {
+        logging.parse(&spec).map_err(|err| format!("{}: {}", LOG_ENV, err))?;
+    }
+
+    let verbosity = matches.occurrences_of("verbose") as usize;
+    if verbosity > 0 {
+        logging.level = LogLevel::verbosity(verbosity);
+    }
+
+    if matches.is_present("quiet") {
+        logging.level = LogLevel::Off;
+        logging.filters.clear();
+    }
+
+    if let Some(path) = matches.value_of("log_file") {
+        logging.target = LogTarget::File(path.to_string());
+    }
+
+    if let Some("json") = matches.value_of("log_format") {
+        logging.format = LogFormat::Json;
+    }
+
+    Ok(logging)
+}
+
+
+/// The size of the green thread stacks from `--stack-size`, anything smaller than
+/// `MIN_STACK_SIZE` would make every call raise a `RecursionError`.
+fn stack_size(matches: &clap::ArgMatches) -> Result<usize, String> {
+    let value = match matches.value_of("stack_size") {
+        Some(value) => value,
+        None => return Ok(GREENLET_STACK_SIZE)
+    };
+
+    match value.parse::<usize>() {
+        Ok(size) if size >= MIN_STACK_SIZE => Ok(size),
+        Ok(size) => Err(format!("--stack-size: {} bytes is too small, the minimum is {} bytes",
+                                size, MIN_STACK_SIZE)),
+        Err(_) => Err(format!("--stack-size: '{}' is not a size in bytes", value))
+    }
+}
+
+
+fn main() {
     let matches = App::new(strings::PROGRAM)
         .version(strings::VERSION)
         .author(strings::AUTHORS)
@@ -52,6 +148,57 @@ fn main() {
             .long("green-threads")
             .help("Sets the threading model")
             .takes_value(false))
+        .arg(Arg::with_name("stack_size")
+            .long("stack-size")
+            .help("Size in bytes of the stack of each green thread")
+            .takes_value(true))
+        .arg(Arg::with_name("verbose")
+            .short("v")
+            .long("verbose")
+            .multiple(true)
+            .help("Log the internals of the interpreter, -vv for debug and -vvv for trace records")
+            .takes_value(false))
+        .arg(Arg::with_name("quiet")
+            .short("q")
+            .long("quiet")
+            .conflicts_with("verbose")
+            .help("Log nothing, even if RSNEK_LOG is set")
+            .takes_value(false))
+        .arg(Arg::with_name("log_file")
+            .long("log-file")
+            .help("Append log records to a file instead of stderr")
+            .takes_value(true))
+        .arg(Arg::with_name("log_format")
+            .long("log-format")
+            .help("Write log records as text or as one JSON object per line")
+            .possible_values(&["text", "json"])
+            .takes_value(true))
+        .arg(Arg::with_name("optimize")
+            .short("O")
+            .multiple(true)
+            .help("Optimize the compiled code before running it")
+            .takes_value(false))
+        .arg(Arg::with_name("debug")
+            .long("debug")
+            .help("Stop in the debugger before the first line of the program")
+            .takes_value(false))
+        .arg(Arg::with_name("dump")
+            .long("dump")
+            .help("Print the tokens, ast, or bytecode of the input instead of running it")
+            .possible_values(&["tokens", "ast", "bytecode"])
+            .takes_value(true))
+        .arg(Arg::with_name("profile")
+            .long("profile")
+            .help("Profile the program and print the function stats (the default), collapsed stacks, or opcode counts to stderr")
+            .possible_values(&["stats", "collapsed", "opcodes"])
+            .takes_value(true)
+            .min_values(0)
+            .require_equals(true))
+        .arg(Arg::with_name("coverage")
+            .long("coverage")
+            .value_name("FILE")
+            .help("Add the lines that ran to a JSON, or LCOV for .info and .lcov, coverage report")
+            .takes_value(true))
         .arg(Arg::with_name("args")
             .index(1)
             .multiple(true))
@@ -60,6 +207,24 @@ fn main() {
                 .args(&["cmd", "mod",]))
         .get_matches();
 
+    let logging = match logging_config(&matches) {
+        Ok(logging) => logging,
+        Err(err) => {
+            let _ = writeln!(io::stderr(), "{}", err);
+            std::process::exit(ExitCode::GenericError as i32)
+        }
+    };
+
+    let _guard = match init_logging(&logging) {
+        Ok(guard) => guard,
+        Err(err) => {
+            let _ = writeln!(io::stderr(), "Unable to open the log file: {}", err);
+            std::process::exit(ExitCode::GenericError as i32)
+        }
+    };
+
+    info!("{}", format!("welcome to {}", strings::PROGRAM));
+
 
     // Parse Args
     let matched_args = matches.values_of_lossy("args");
@@ -69,19 +234,43 @@ fn main() {
         .map(|s| s.as_str())
         .collect();
 
-    let logging = Logging{};
-    let debug_support = false;
+    let debug_support = matches.is_present("debug");
 
     let thread_model = match matches.is_present("green_threads") {
         true => ThreadModel::GreenThreads,
         false => ThreadModel::OsThreads
     };
 
+    let stack_size = match stack_size(&matches) {
+        Ok(size) => size,
+        Err(err) => {
+            let _ = writeln!(io::stderr(), "{}", err);
+            std::process::exit(ExitCode::GenericError as i32)
+        }
+    };
+
+    let dump = match matches.value_of("dump") {
+        Some("tokens") => Some(Dump::Tokens),
+        Some("ast") => Some(Dump::Ast),
+        Some("bytecode") => Some(Dump::Bytecode),
+        _ => None
+    };
+
+    // The report is given as `--profile=<report>` so that `--profile script.py` profiles
+    // the script with the default report.
+    let profile = match (matches.is_present("profile"), matches.value_of("profile")) {
+        (false, _) => None,
+        (true, Some("collapsed")) => Some(Profile::Collapsed),
+        (true, Some("opcodes")) => Some(Profile::Opcodes),
+        (true, _) => Some(Profile::Stats),
+    };
+
     let ms = (matches.value_of("cmd"), matches.value_of("mod"), args.get(0));
 
     let mode = match ms {
-        (Some(cmd), None, None)     => Mode::Command(cmd.to_string()),
-        (None, Some(module), None)  => Mode::Module(module.to_string()),
+        // Arguments after a command or module go to `sys.argv`
+        (Some(cmd), None, _)        => Mode::Command(cmd.to_string()),
+        (None, Some(module), _)     => Mode::Module(module.to_string()),
         (None, None, Some(_))       => Mode::File,
         (None, None, None)          => Mode::Interactive,
         _                           => {
@@ -98,8 +287,18 @@ fn main() {
         thread_model: thread_model,
         logging: logging,
         debug_support: debug_support,
+        optimize: matches.occurrences_of("optimize") as usize,
+        stack_size: stack_size,
+        recursion_limit: RECURSION_LIMIT,
+        dump: dump,
+        profile: profile,
+        coverage: matches.value_of("coverage").map(String::from),
     };
 
 
-    Interpreter::run(&config);
+    let status = Interpreter::run(&config);
+
+    // `exit` does not run destructors, the log records must be flushed first
+    drop(_guard);
+    std::process::exit(status as i32);
 }
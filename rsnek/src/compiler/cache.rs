@@ -0,0 +1,269 @@
+//! `.rsc` files - compiled code cached next to its source file
+//!
+//! Compiling `script.py` writes `script.py.rsc` so the next run can skip lexing,
+//! parsing, and compiling as long as the source has not changed. The file is a
+//! bincode encoded `Header` followed by the module's `rs::Code`:
+//!
+//! ```ignore
+//! +---------------------------------------------------------------+
+//! | magic | version | source size | source mtime | source hash    |
+//! +---------------------------------------------------------------+
+//! | rs::Code                                                      |
+//! +---------------------------------------------------------------+
+//! ```
+//!
+//! The header is decoded on its own first so that files written by another
+//! version of rsnek, whose `rs::Code` may not have the same layout, are rebuilt
+//! instead of misread.
+//!
+use std::collections::hash_map::DefaultHasher;
+use std::fs::{self, File};
+use std::hash::Hasher;
+use std::io::{Read, Write};
+use std::time::UNIX_EPOCH;
+
+use bincode;
+
+use ::api::result::Error;
+use ::compiler::{Compiler, CompilerResult};
+use ::compiler::fmt;
+use ::resources::strings;
+use ::system::primitives as rs;
+
+
+/// First bytes of every `.rsc` file
+pub const MAGIC: [u8; 4] = [b'R', b'S', b'C', 0x02];
+
+/// Upper bound of the encoded header size, protects against allocating
+/// huge strings when reading a file that is not a `.rsc` at all.
+const MAX_HEADER_SIZE: u64 = 1024;
+
+
+/// Identifies the rsnek version that wrote the file and the source it was compiled from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Header {
+    pub magic: [u8; 4],
+    pub version: String,
+    pub source_size: u64,
+    pub source_mtime: u64,
+    pub source_hash: u64,
+}
+
+
+impl Header {
+    /// Create the header that a fresh `.rsc` of `source` must have. `source_mtime`
+    /// is in seconds since the unix epoch.
+    pub fn new(source: &[u8], source_mtime: u64) -> Self {
+        let mut hasher = DefaultHasher::new();
+        hasher.write(source);
+
+        Header {
+            magic: MAGIC,
+            version: String::from(strings::VERSION.trim()),
+            source_size: source.len() as u64,
+            source_mtime: source_mtime,
+            source_hash: hasher.finish(),
+        }
+    }
+
+    /// True if the file was written by this version of rsnek
+    pub fn is_native(&self) -> bool {
+        self.magic == MAGIC && self.version == strings::VERSION.trim()
+    }
+}
+
+
+/// Path of the cache file for the source file at `path`
+pub fn cache_path(path: &str) -> String {
+    format!("{}.{}", path, strings::COMPILED_SOURCE_EXT)
+}
+
+
+/// Encode `code` with its header into the `.rsc` format
+pub fn encode(header: &Header, code: &rs::Code) -> Vec<u8> {
+    let mut bytes = fmt::bincode(header);
+    bytes.extend(fmt::bincode(code));
+    bytes
+}
+
+
+/// Decode the contents of a `.rsc` file. Files with the wrong magic number or
+/// written by another version of rsnek are an error.
+pub fn decode(bytes: &[u8]) -> Result<(Header, rs::Code), Error> {
+    if !bytes.starts_with(&MAGIC) {
+        return Err(Error::value("not an rsnek compiled file"))
+    }
+
+    let mut reader: &[u8] = bytes;
+    let header: Header = match bincode::deserialize_from(&mut reader, bincode::Bounded(MAX_HEADER_SIZE)) {
+        Ok(header) => header,
+        Err(err) => return Err(Error::value(&format!("malformed compiled file header: {}", err)))
+    };
+
+    if !header.is_native() {
+        return Err(Error::value(&format!(
+            "compiled file was written by rsnek {}", header.version)))
+    }
+
+    match bincode::deserialize_from(&mut reader, bincode::Infinite) {
+        Ok(code) => Ok((header, code)),
+        Err(err) => Err(Error::value(&format!("malformed compiled file: {}", err)))
+    }
+}
+
+
+/// Read a `.rsc` file directly, without a source file to check it against
+pub fn read(path: &str) -> CompilerResult {
+    let mut bytes: Vec<u8> = Vec::new();
+    match File::open(path).and_then(|mut file| file.read_to_end(&mut bytes)) {
+        Ok(_) => {},
+        Err(err) => return Err(Error::value(&format!("{}: {}", path, err)))
+    };
+
+    let (_, code) = decode(&bytes)?;
+    Ok(code.co_code.into_boxed_slice())
+}
+
+
+/// Compile the contents `source` of the file at `path`. The instructions are loaded
+/// from `<path>.rsc` when it is fresh, otherwise the source is compiled and the cache
+/// file is (re)written. Failing to write the cache is not an error.
+pub fn compile_file(path: &str, source: &[u8]) -> CompilerResult {
+    let expected = Header::new(source, source_mtime(path));
+    let rsc_path = cache_path(path);
+
+    match load(&rsc_path, &expected) {
+        Some(code) => {
+            debug!("Cache"; "action" => "hit", "path" => &rsc_path);
+            return Ok(code.co_code.into_boxed_slice())
+        },
+        None => debug!("Cache"; "action" => "miss", "path" => &rsc_path)
+    }
+
+    let text = String::from_utf8_lossy(source).to_string();
+    let ins = Compiler::new().compile_source(path, &text)?;
+
+    let code = rs::Code::module(path, ins.to_vec());
+
+    match File::create(&rsc_path).and_then(|mut file| file.write_all(&encode(&expected, &code))) {
+        Err(err) => debug!("Cache"; "action" => "write", "path" => &rsc_path, "error" => format!("{:?}", err)),
+        _ => {}
+    };
+
+    Ok(ins)
+}
+
+
+/// Load the code of the `.rsc` file at `rsc_path` if its header matches `expected`
+fn load(rsc_path: &str, expected: &Header) -> Option<rs::Code> {
+    let mut bytes: Vec<u8> = Vec::new();
+    match File::open(rsc_path).and_then(|mut file| file.read_to_end(&mut bytes)) {
+        Ok(_) => {},
+        Err(_) => return None
+    };
+
+    match decode(&bytes) {
+        Ok((ref header, code)) if header == expected => Some(code),
+        Ok(_) => None,
+        Err(err) => {
+            debug!("Cache"; "action" => "decode", "path" => rsc_path, "error" => err.1);
+            None
+        }
+    }
+}
+
+
+/// Modification time of the file at `path` in seconds since the epoch, 0 if unknown
+fn source_mtime(path: &str) -> u64 {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|mtime| mtime.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use ::system::primitives::{Instr, Native};
+    use super::*;
+
+    fn compile(text: &str) -> rs::Code {
+        let ins = Compiler::new().compile_str(text).unwrap();
+        rs::Code::module(strings::SOURCE_STRING, ins.to_vec())
+    }
+
+    fn temp_source(name: &str, text: &str) -> String {
+        let path = env::temp_dir().join(format!("rsnek-test-{}.py", name));
+        let path = path.to_string_lossy().to_string();
+        File::create(&path).unwrap().write_all(text.as_bytes()).unwrap();
+        path
+    }
+
+    #[test]
+    fn round_trip() {
+        let text = "x = 123456789012345678901234567890\ndef f(a):\n    return a * 2.5\n";
+        let code = compile(text);
+        let header = Header::new(text.as_bytes(), 0);
+
+        let (decoded_header, decoded) = decode(&encode(&header, &code)).unwrap();
+        assert_eq!(decoded_header, header);
+        assert_eq!(format!("{:?}", decoded), format!("{:?}", code));
+
+        let big = decoded.co_code.iter().any(|instr| match *instr {
+            Instr(_, Some(Native::Int(ref int))) => int.to_string() == "123456789012345678901234567890",
+            _ => false
+        });
+        assert!(big, "Big integers must survive the round trip");
+    }
+
+    #[test]
+    fn foreign_version() {
+        let code = compile("x = 1");
+        let mut header = Header::new(b"x = 1", 0);
+        header.version = String::from("v0.0.0");
+
+        assert!(decode(&encode(&header, &code)).is_err());
+    }
+
+    #[test]
+    fn not_a_compiled_file() {
+        assert!(decode(b"x = 1\n").is_err());
+        assert!(decode(&MAGIC).is_err());
+    }
+
+    #[test]
+    fn truncated() {
+        let code = compile("x = 1");
+        let bytes = encode(&Header::new(b"x = 1", 0), &code);
+        assert!(decode(&bytes[..bytes.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn compile_file_writes_and_reuses_cache() {
+        let path = temp_source("cache", "x = 1\n");
+        let rsc_path = cache_path(&path);
+
+        compile_file(&path, b"x = 1\n").unwrap();
+        let expected = Header::new(b"x = 1\n", source_mtime(&path));
+        assert!(load(&rsc_path, &expected).is_some());
+
+        // Replace the cached code, a fresh cache is used as is
+        File::create(&rsc_path).unwrap()
+            .write_all(&encode(&expected, &compile("y = 2\nz = 3\n"))).unwrap();
+        let ins = compile_file(&path, b"x = 1\n").unwrap();
+        assert_eq!(ins.len(), compile("y = 2\nz = 3\n").co_code.len());
+
+        // A changed source makes the cache stale and it is rebuilt
+        File::create(&path).unwrap().write_all(b"x = 2\n").unwrap();
+        let ins = compile_file(&path, b"x = 2\n").unwrap();
+        assert_eq!(ins.len(), compile("x = 2\n").co_code.len());
+        assert!(load(&rsc_path, &Header::new(b"x = 2\n", source_mtime(&path))).is_some());
+
+        fs::remove_file(&path).unwrap();
+        fs::remove_file(&rsc_path).unwrap();
+    }
+}
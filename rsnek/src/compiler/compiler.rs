@@ -3,7 +3,7 @@
 use std::convert::TryFrom;
 use std::borrow::{Borrow, BorrowMut};
 use std::cell::{RefMut, RefCell, Cell};
-use std::collections::{HashSet, HashMap, VecDeque};
+use std::collections::{BTreeSet, HashSet, HashMap, VecDeque};
 use std::hash::Hash;
 
 use serde::{Serialize, Serializer};
@@ -11,16 +11,18 @@ use serde::ser::{SerializeSeq};
 
 use python_ast::{
     Ast, Module, Stmt, Expr, Op, Lexer,
-    LexResult, Parser, ParserResult,
-    OwnedTk, Id};
+    LexResult, Parser, ParserResult, ParsedAst, ParseError,
+    OwnedTk, Id, Span};
 use python_ast::fmt;
 
-use ::api::result::Error;
+use ::api::result::{Error, ErrorType};
 use ::compiler::graph::{Node, Graph};
 use ::compiler::scope::ScopeHint::{self, BaseScope, ModuleScope, FunctionScope};
 use ::compiler::scope::{ScopeNode, ManageScope, Descriptor};
 use ::compiler::symbol::{SymbolMetadata, TrackSymbol, Symbol, Definition};
+use ::resources::strings;
 use ::runtime::OpCode;
+use ::runtime::inline_cache::InlineCaches;
 use ::system::primitives as rs;
 use ::system::primitives::Native;
 pub use ::system::primitives::Instr;
@@ -31,7 +33,80 @@ pub type CompilerResult = Result<Box<[Instr]>, Error>;
 #[derive(Debug, PartialEq, Eq, Ord, PartialOrd, Hash, Copy, Clone, Serialize)]
 pub enum Context {
     Load,
-    Store
+    Store,
+    Del
+}
+
+
+/// Report `error` like CPython reports a `SyntaxError`, with the line of `input`
+/// it happened on and a caret under the offending token:
+///
+/// ```text
+///   File "<string>", line 1
+///     x = )
+///         ^
+/// SyntaxError: unexpected ')', expected an expression
+/// ```
+fn syntax_error(filename: &str, input: &str, error: &ParseError) -> Error {
+    syntax_error_at(filename, input, error.span, &error.message)
+}
+
+
+/// `SyntaxError` with `message` pointing at `span` of `input`
+fn syntax_error_at(filename: &str, input: &str, span: Span, message: &str) -> Error {
+    if !span.is_known() {
+        return Error::syntax(message);
+    }
+
+    let line = input.lines().nth(span.line - 1).unwrap_or("");
+    let text = line.trim_left();
+    let indent = line.chars().count() - text.chars().count();
+
+    Error::syntax_at(filename, span.line, text.trim_right(),
+                     span.column.saturating_sub(indent), message)
+}
+
+
+/// Add the lines `compile_stmt` sets for `stmt` and the statements nested in it
+fn statement_lines(stmt: &Stmt, lines: &mut BTreeSet<usize>) {
+    match *stmt {
+        Stmt::Newline(line) => {
+            lines.insert(line);
+        },
+        Stmt::Block(ref stmts) => for stmt in stmts.iter() {
+            statement_lines(stmt, lines);
+        },
+        Stmt::FunctionDef { ref body, span, .. } |
+        Stmt::ClassDef { ref body, span, .. } => {
+            lines.insert(span.line);
+            statement_lines(body, lines);
+        },
+        _ if stmt.span().is_known() => {
+            lines.insert(stmt.span().line);
+        },
+        _ => {}
+    }
+}
+
+
+/// Placeholder for the target of a jump that may not have been emitted yet.
+/// Jumps are emitted with the label id as their argument and are backpatched
+/// with the real instruction offset by `resolve_labels` once all of the
+/// instructions of a code object are known.
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone, Serialize)]
+struct Label(usize);
+
+
+impl Label {
+    /// Pseudo instruction marking the position of the label
+    fn mark(&self) -> Instr {
+        Instr(OpCode::Label, Some(Native::Count(self.0)))
+    }
+
+    /// Jump instruction of type `code` that targets this label
+    fn jump(&self, code: OpCode) -> Instr {
+        Instr(code, Some(Native::Count(self.0)))
+    }
 }
 
 
@@ -61,6 +136,17 @@ pub struct Compiler<'a> {
     parser: Parser<'a>,
     metadata: SymbolMetadata,
     module: ModuleCode,
+    labels: Cell<usize>,
+    filename: String,
+
+    /// The input being compiled, for the location of errors found in its AST
+    source: String,
+
+    /// Compiling repl input, the values of expression statements are displayed
+    interactive: bool,
+
+    /// The last input ended before its last statement did
+    incomplete: bool,
 }
 
 
@@ -70,12 +156,41 @@ impl<'a> Compiler<'a> {
             lexer: Lexer::new(),
             parser: Parser::new(),
             metadata: SymbolMetadata::new(),
-            module: ModuleCode::new()
+            module: ModuleCode::new(),
+            labels: Cell::new(0),
+            filename: String::from(strings::SOURCE_STRING),
+            source: String::new(),
+            interactive: false,
+            incomplete: false,
         }
     }
 
     pub fn compile_str<'b>(&mut self, input: &'b str) -> CompilerResult {
+        self.compile_source(strings::SOURCE_STRING, input)
+    }
+
+    /// Compile a statement typed into the repl, the value of each expression
+    /// statement is displayed and kept as `_`.
+    pub fn compile_interactive(&mut self, input: &str) -> CompilerResult {
+        self.interactive = true;
+        let result = self.compile_source(strings::SOURCE_STDIN, input);
+        self.interactive = false;
+        result
+    }
+
+    /// True if the last input failed to compile because it ended before its last
+    /// statement did, the repl reads another line instead of reporting the error.
+    pub fn is_incomplete(&self) -> bool {
+        self.incomplete
+    }
+
+    /// Compile `input` that was read from `filename`, the name syntax errors are
+    /// reported against and that is given to the code objects of the functions it defines.
+    pub fn compile_source(&mut self, filename: &str, input: &str) -> CompilerResult {
         let mut parser = Parser::new();
+        self.filename = String::from(filename);
+        self.source = String::from(input);
+        self.incomplete = false;
 
         let tokens = match self.lexer.tokenize2(input.as_bytes()) {
             LexResult::Done(left, ref tokens) if left.len() == 0 => tokens.clone(),
@@ -93,6 +208,10 @@ impl<'a> Compiler<'a> {
                 "module" => format!("{}", fmt::json(&self.module)));
                 result
             },
+            ParserResult::Error(ParsedAst { error: Some(ref error), .. }) => {
+                self.incomplete = error.is_incomplete();
+                Err(syntax_error(filename, input, error))
+            },
             other => {
                 trace!("Parser"; "Result" => fmt::json(&other));
                 Err(Error::syntax("Could not parse input"))
@@ -100,6 +219,54 @@ impl<'a> Compiler<'a> {
         }
     }
 
+    /// Table of the tokens of `input`, see `python_ast::fmt::tokens`
+    pub fn dump_tokens(&self, input: &str) -> Result<String, Error> {
+        match self.lexer.tokenize2(input.as_bytes()) {
+            LexResult::Done(left, ref tokens) if left.len() == 0 => Ok(fmt::tokens(tokens, true)),
+            _ => Err(Error::syntax("Could not tokenize input"))
+        }
+    }
+
+    /// The AST of `input` read from `filename` as json
+    pub fn dump_ast(&self, filename: &str, input: &str) -> Result<String, Error> {
+        self.parse(filename, input).map(|ast| fmt::json(&ast))
+    }
+
+    /// The lines of all of the statements of `input`, including the ones in function
+    /// bodies. These are the lines `SetLineNumber` is emitted for, taken from the AST
+    /// so that lines the optimizer removes as dead code are still among them.
+    pub fn statement_lines(&self, filename: &str, input: &str) -> Result<BTreeSet<usize>, Error> {
+        let mut lines = BTreeSet::new();
+
+        match self.parse(filename, input)? {
+            Ast::Module(Module::Body(ref stmts)) => for stmt in stmts.iter() {
+                statement_lines(stmt, &mut lines);
+            },
+            Ast::Statement(ref stmt) => statement_lines(stmt, &mut lines),
+            Ast::Expression(_) => {}
+        };
+
+        lines.remove(&0);
+        Ok(lines)
+    }
+
+    fn parse(&self, filename: &str, input: &str) -> Result<Ast, Error> {
+        let tokens = match self.lexer.tokenize2(input.as_bytes()) {
+            LexResult::Done(left, ref tokens) if left.len() == 0 => tokens.clone(),
+            _ => return Err(Error::syntax("Could not tokenize input"))
+        };
+
+        match Parser::new().parse_tokens(&tokens) {
+            ParserResult::Ok(ref result) if result.remaining_tokens.len() == 0 => {
+                Ok(result.ast.clone())
+            },
+            ParserResult::Error(ParsedAst { error: Some(ref error), .. }) => {
+                Err(syntax_error(filename, input, error))
+            },
+            _ => Err(Error::syntax("Could not parse input"))
+        }
+    }
+
     // Ast Compiler Methods
     pub fn compile_ast(&mut self, ast: &Ast) -> CompilerResult{
         let mut instructions: Vec<Instr> = vec![];
@@ -107,7 +274,7 @@ impl<'a> Compiler<'a> {
         let ins = match *ast {
             Ast::Module(ref module) => {
                 self.enter_scope(ModuleScope);
-                self.exit_scope(self.compile_module(module))?
+                resolve_labels(&self.exit_scope(self.compile_module(module))?)?
             },
             _ => Box::default()
         };
@@ -123,7 +290,18 @@ impl<'a> Compiler<'a> {
         match *module {
             Module::Body(ref stmts) => {
                 for stmt in stmts {
-                    instructions.append(&mut self.compile_stmt(&stmt)?.to_vec());
+                    let mut ins = self.compile_stmt(&stmt)?.to_vec();
+
+                    // The repl displays the value instead of discarding it
+                    match *stmt {
+                        Stmt::Expr(_) if self.interactive => {
+                            ins.pop();
+                            ins.push(Instr(OpCode::PrintExpr, None));
+                        },
+                        _ => {}
+                    }
+
+                    instructions.append(&mut ins);
                 }
             }
         }
@@ -135,8 +313,18 @@ impl<'a> Compiler<'a> {
     fn compile_stmt(&self, stmt: &'a Stmt) -> CompilerResult {
         let mut instructions: Vec<Instr> = vec![];
 
+        // Each statement starts by setting the line it is on so errors raised
+        // by it are reported against that line and not the previous one.
+        match *stmt {
+            Stmt::Block(_) | Stmt::Newline(_) => {},
+            _ if stmt.span().is_known() => {
+                instructions.push(Instr(OpCode::SetLineNumber, Some(Native::Count(stmt.span().line))));
+            },
+            _ => {}
+        };
+
         let ins: Box<[Instr]> = match *stmt {
-            Stmt::FunctionDef {fntype: _, ref name, ref arguments, ref body } => {
+            Stmt::FunctionDef {fntype: _, ref name, ref arguments, ref body, .. } => {
                 self.enter_scope(FunctionScope);
                 self.exit_scope(self.compile_stmt_funcdef(name, arguments, body))?
             },
@@ -148,13 +336,13 @@ impl<'a> Compiler<'a> {
 
                 block_ins.into_boxed_slice()
             },
-            Stmt::Return(Some(ref value)) => {
+            Stmt::Return { value: Some(ref value), .. } => {
                 let mut return_ins: Vec<Instr> = vec![];
                 return_ins.append(&mut self.compile_expr(&value, Context::Load)?.to_vec());
                 return_ins.push(Instr(OpCode::ReturnValue, None));
                 return_ins.into_boxed_slice()
             },
-            Stmt::Return(None) => {
+            Stmt::Return { value: None, .. } => {
                 let return_ins: Vec<Instr> = vec![
                     Instr(OpCode::LoadName, Some(Native::from("None"))),
                     Instr(OpCode::ReturnValue, None)
@@ -167,7 +355,7 @@ impl<'a> Compiler<'a> {
                 ins.push(Instr(OpCode::PopTop, None));
                 ins.into_boxed_slice()
             },
-            Stmt::Assert { ref test, ref message } => {
+            Stmt::Assert { ref test, ref message, .. } => {
                 let mut ins: Vec<Instr> = Vec::new();
                 ins.append(&mut self.compile_expr(test, Context::Load)?.to_vec());
                 let args = match *message {
@@ -181,9 +369,16 @@ impl<'a> Compiler<'a> {
                 ins.push(Instr(OpCode::AssertCondition, Some(args)));
                 ins.into_boxed_slice()
             },
-            Stmt::Delete(_)                     => {Box::default()},
+            Stmt::Delete { ref targets, .. } => {
+                let mut ins: Vec<Instr> = Vec::new();
+                for target in targets.iter() {
+                    ins.append(&mut self.compile_expr(target, Context::Del)?.to_vec());
+                }
+
+                ins.into_boxed_slice()
+            },
             Stmt::AugAssign {ref target, ref op, ref value} => {Box::default()},
-            Stmt::ClassDef {ref name, ref bases, ref body}  => {Box::default()},
+            Stmt::ClassDef {ref name, ref bases, ref body, ..} => {Box::default()},
             Stmt::Newline(line)                 => {
                 vec![
                     Instr(OpCode::SetLineNumber, Some(Native::Count(line)))
@@ -213,14 +408,17 @@ impl<'a> Compiler<'a> {
             }
         };
 
-        let stmt =  self.compile_stmt(body)?;
+        let stmt = resolve_labels(&self.compile_stmt(body)?)?;
 
         let code = rs::Code {
             co_name: name.as_string(),
+            co_filename: self.filename.clone(),
             co_names: argnames.iter().cloned().collect::<Vec<_>>(),
             co_varnames: Vec::new(),
             co_code: stmt.to_vec(),
             co_consts: Vec::new(),
+            co_lnotab: Vec::new(),
+            co_caches: InlineCaches::default(),
         };
 
         let defn = Definition(name.as_string(), Native::Code(code.clone()));
@@ -280,23 +478,24 @@ impl<'a> Compiler<'a> {
             Expr::UnaryOp {ref op, ref operand} => {
                 self.compile_expr_unaryop(op, operand)?
             },
-            Expr::Lambda {ref arguments, ref body } => {
+            Expr::Lambda {ref arguments, ref body, .. } => {
                 return Err(Error::system(&format!(
                     "Compiler does not implement Lambda expressions; file: {}, line: {}",
                     file!(), line!())))
             },
             Expr::Conditional {ref condition, ref consequent, ref alternative} => {
-                return Err(Error::system(&format!(
-                    "Compiler does not implement Conditional expressions; file: {}, line: {}",
-                    file!(), line!())))
+                self.compile_expr_conditional(condition, consequent, alternative)?
             },
             Expr::Attribute {ref value, ref attr} => {
                 self.compile_expr_attr(value, attr, ctx)?
             },
-            Expr::List {ref elems} => {
+            Expr::List {ref elems, ..} => {
                 self.compile_expr_list(elems)?
             },
-            Expr::Dict {ref items} => {
+            Expr::Tuple {ref elems, ..} => {
+                self.compile_expr_tuple(elems)?
+            },
+            Expr::Dict {ref items, ..} => {
                 self.compile_expr_dict(items)?
             }
             Expr::None => return Err(Error::system(&format!(
@@ -322,7 +521,45 @@ impl<'a> Compiler<'a> {
         Ok(instructions.into_boxed_slice())
     }
 
+    /// `consequent if condition else alternative`
+    fn compile_expr_conditional(&self, condition: &'a Expr, consequent: &'a Expr,
+                                alternative: &'a Expr) -> CompilerResult {
+        let orelse = self.new_label();
+        let end = self.new_label();
+
+        let mut instructions: Vec<Instr> = vec![];
+        instructions.append(&mut self.compile_expr(condition, Context::Load)?.to_vec());
+        instructions.push(orelse.jump(OpCode::PopJumpIfFalse));
+        instructions.append(&mut self.compile_expr(consequent, Context::Load)?.to_vec());
+        instructions.push(end.jump(OpCode::JumpAbsolute));
+        instructions.push(orelse.mark());
+        instructions.append(&mut self.compile_expr(alternative, Context::Load)?.to_vec());
+        instructions.push(end.mark());
+
+        Ok(instructions.into_boxed_slice())
+    }
+
+    /// Short circuiting `and` and `or`, the right hand side is only evaluated
+    /// when the left hand side does not already decide the result.
+    fn compile_expr_logical(&self, code: OpCode, left: &'a Expr, right: &'a Expr) -> CompilerResult {
+        let end = self.new_label();
+
+        let mut instructions: Vec<Instr> = vec![];
+        instructions.append(&mut self.compile_expr(left, Context::Load)?.to_vec());
+        instructions.push(end.jump(code));
+        instructions.append(&mut self.compile_expr(right, Context::Load)?.to_vec());
+        instructions.push(end.mark());
+
+        Ok(instructions.into_boxed_slice())
+    }
+
     fn compile_expr_binop(&self, op: &'a Op, left: &'a Expr, right: &'a Expr) -> CompilerResult {
+        match op.0.id() {
+            Id::And => return self.compile_expr_logical(OpCode::JumpIfFalseOrPop, left, right),
+            Id::Or  => return self.compile_expr_logical(OpCode::JumpIfTrueOrPop, left, right),
+            _ => {}
+        };
+
         let mut instructions: Vec<Instr> = vec![];
 
         instructions.append(&mut self.compile_expr(left, Context::Load)?.to_vec());
@@ -339,14 +576,12 @@ impl<'a> Compiler<'a> {
             Id::LessOrEqual     => Instr(OpCode::CompareLessOrEqual, None),
             Id::RightAngle      => Instr(OpCode::CompareGreater, None),
             Id::GreaterOrEqual  => Instr(OpCode::CompareGreaterOrEqual, None),
-            Id::And             => Instr(OpCode::LogicalAnd, None),
-            Id::Or              => Instr(OpCode::LogicalOr, None),
             Id::Plus            => Instr(OpCode::BinaryAdd, None),
             Id::Minus           => Instr(OpCode::BinarySubtract, None),
             Id::Star            => Instr(OpCode::BinaryMultiply, None),
             Id::DoubleStar      => Instr(OpCode::BinaryPower, None),
             Id::Slash           => Instr(OpCode::BinaryTrueDivide, None),
-            Id::DoubleSlash     => Instr(OpCode::BinaryTrueDivide, None),
+            Id::DoubleSlash     => Instr(OpCode::BinaryFloorDivide, None),
             Id::Pipe            => Instr(OpCode::BinaryOr, None),
             Id::Percent         => Instr(OpCode::BinaryModulo, None),
             Id::Amp             => Instr(OpCode::BinaryAnd, None),
@@ -389,12 +624,15 @@ impl<'a> Compiler<'a> {
     fn compile_expr_constant(&self, ctx: Context, tk: &'a OwnedTk) -> CompilerResult {
         let instr = match ctx {
             Context::Store => {
-                let name = Native::from(tk);
+                let name = self.literal(tk)?;
                 //self.define_symbol(&name)?;
                 Instr(OpCode::StoreName, Some(name))
             },
+            Context::Del => {
+                Instr(OpCode::DeleteName, Some(self.literal(tk)?))
+            },
             Context::Load => {
-                let name = Native::from(tk);
+                let name = self.literal(tk)?;
                 let code = match tk.id() {
 
                     Id::Name => {
@@ -411,6 +649,19 @@ impl<'a> Compiler<'a> {
         Ok(vec![instr].into_boxed_slice())
     }
 
+    /// The value of the name or literal `tk`, a literal without one is a
+    /// `SyntaxError` at the token like CPython reports it
+    fn literal(&self, tk: &'a OwnedTk) -> Result<Native, Error> {
+        Native::try_from(tk).map_err(|err| {
+            let message = match err.0 {
+                ErrorType::Syntax => err.1,
+                _ => format!("({} error) {}", format!("{:?}", err.0).to_lowercase(), err.1),
+            };
+
+            syntax_error_at(&self.filename, &self.source, tk.span(), &message)
+        })
+    }
+
     fn compile_expr_attr(&self, value: &'a Expr, attr: &'a OwnedTk, ctx: Context) -> CompilerResult {
         if ctx != Context::Load {
             return Err(Error::system(&format!(
@@ -420,7 +671,7 @@ impl<'a> Compiler<'a> {
 
         let mut instructions: Vec<Instr> = Vec::new();
         instructions.append(&mut self.compile_expr(value, ctx)?.to_vec());
-        instructions.push(Instr(OpCode::LoadAttr, Some(Native::from(attr))));
+        instructions.push(Instr(OpCode::LoadAttr, Some(self.literal(attr)?)));
         Ok(instructions.into_boxed_slice())
     }
 
@@ -435,6 +686,17 @@ impl<'a> Compiler<'a> {
         Ok(instructions.into_boxed_slice())
     }
 
+    fn compile_expr_tuple(&self, elem_exprs: &'a[Expr]) -> CompilerResult {
+        let mut instructions: Vec<Instr> = Vec::new();
+
+        for expr in elem_exprs.iter().as_ref() {
+            instructions.append(&mut self.compile_expr(&expr, Context::Load)?.to_vec());
+        }
+
+        instructions.push(Instr(OpCode::BuildTuple, Some(Native::Count(elem_exprs.len()))));
+        Ok(instructions.into_boxed_slice())
+    }
+
     fn compile_expr_dict(&self, items: &'a[(Expr, Expr)]) -> CompilerResult {
         let mut instructions: Vec<Instr> = Vec::new();
 
@@ -446,6 +708,49 @@ impl<'a> Compiler<'a> {
         instructions.push(Instr(OpCode::BuildMap, Some(Native::Count(items.len()))));
         Ok(instructions.into_boxed_slice())
     }
+
+    /// Create a new label that is unique for the lifetime of the compiler
+    fn new_label(&self) -> Label {
+        let label = Label(self.labels.get());
+        self.labels.set(label.0 + 1);
+        label
+    }
+}
+
+
+/// Remove the `OpCode::Label` markers from the instructions of a single code object
+/// and backpatch every jump with the offset of the instruction that follows its label.
+fn resolve_labels(ins: &[Instr]) -> CompilerResult {
+    let mut offsets: HashMap<usize, usize> = HashMap::new();
+    let mut resolved: Vec<Instr> = Vec::with_capacity(ins.len());
+
+    for instr in ins.iter() {
+        match *instr {
+            Instr(OpCode::Label, Some(Native::Count(label))) => {
+                offsets.insert(label, resolved.len());
+            },
+            _ => resolved.push(instr.clone())
+        }
+    }
+
+    for instr in resolved.iter_mut() {
+        if !instr.0.is_jump() {
+            continue;
+        }
+
+        let offset = match instr.1 {
+            Some(Native::Count(label)) => offsets.get(&label).cloned(),
+            _ => None
+        };
+
+        match offset {
+            Some(offset) => instr.1 = Some(Native::Count(offset)),
+            None => return Err(Error::system(&format!(
+                "Jump to undefined label {:?}; file: {}, line: {}", instr, file!(), line!())))
+        }
+    }
+
+    Ok(resolved.into_boxed_slice())
 }
 
 impl<'a> ManageScope for Compiler<'a> {
@@ -567,12 +872,183 @@ z = x + y
     // Expr::Attribute
     basic_test!(expr_attribute,        "thing.attribute.otherthing");
 
+    // Stmt::Delete
+    basic_test!(stmt_delete, "x = 1\ny = 2\ndel x, y\n");
+
     // Expr::List
     basic_test!(expr_list, "[1,2,3,4]");
 
+    // Expr::Tuple
+    basic_test!(expr_tuple, "(1, (2, 3), ())");
+
     // Expr::Dict
     basic_test!(expr_dict, "{a: b, 'c': 'd', True: False}");
 
+    // Expr::Conditional
+    basic_test!(expr_conditional, "x = 1 if a else 2");
+    basic_test!(expr_conditional_nested, "x = (1 if a else 2) if b else (3 if c else 4)");
+
+    #[test]
+    fn resolve_forward_and_backward_labels() {
+        let ins = vec![
+            Instr(OpCode::Label, Some(Native::Count(0))),
+            Instr(OpCode::LoadName, Some(Native::from("x"))),
+            Instr(OpCode::PopJumpIfFalse, Some(Native::Count(1))),
+            Instr(OpCode::JumpAbsolute, Some(Native::Count(0))),
+            Instr(OpCode::Label, Some(Native::Count(1))),
+            Instr(OpCode::SetLineNumber, Some(Native::Count(7))),
+        ];
+
+        let resolved = resolve_labels(&ins).unwrap();
+        assert_eq!(resolved.len(), 4);
+
+        match resolved[1] {
+            Instr(OpCode::PopJumpIfFalse, Some(Native::Count(3))) => {},
+            ref other => panic!("Forward jump was not patched: {:?}", other)
+        }
+
+        match resolved[2] {
+            Instr(OpCode::JumpAbsolute, Some(Native::Count(0))) => {},
+            ref other => panic!("Backward jump was not patched: {:?}", other)
+        }
+
+        // Arguments of non jump instructions are left alone
+        match resolved[3] {
+            Instr(OpCode::SetLineNumber, Some(Native::Count(7))) => {},
+            ref other => panic!("Unexpected instruction: {:?}", other)
+        }
+    }
+
+    #[test]
+    fn resolve_undefined_label() {
+        let ins = vec![Instr(OpCode::JumpAbsolute, Some(Native::Count(42)))];
+        assert!(resolve_labels(&ins).is_err());
+    }
+
+    #[test]
+    fn logical_ops_short_circuit() {
+        let mut compiler = Compiler::new();
+        let ins = compiler.compile_str("x = a and b or c").unwrap();
+
+        assert!(ins.iter().all(|instr| instr.code() != OpCode::Label));
+        assert!(ins.iter().all(|instr| instr.code() != OpCode::LogicalAnd));
+
+        // Every jump lands inside of the code or on its end
+        for instr in ins.iter().filter(|instr| instr.code().is_jump()) {
+            match instr.value() {
+                Some(Native::Count(offset)) => assert!(offset <= ins.len()),
+                other => panic!("Jump without a target {:?}", other)
+            }
+        }
+    }
+
+    #[test]
+    fn syntax_error_points_at_token() {
+        let err = Compiler::new().compile_str("x = 1\ny = (2 + )\n").unwrap_err();
+
+        assert_eq!(format!("{}", err), [
+            "  File \"<string>\", line 2",
+            "    y = (2 + )",
+            "             ^",
+            "SyntaxError: unexpected ')', expected an expression"].join("\n"));
+    }
+
+    #[test]
+    fn syntax_error_in_block() {
+        let err = Compiler::new().compile_source("block.py", "def f():\n    return [1, 2 3]\n").unwrap_err();
+
+        assert_eq!(format!("{}", err), [
+            "  File \"block.py\", line 2",
+            "    return [1, 2 3]",
+            "                 ^",
+            "SyntaxError: unexpected '3', expected ']'"].join("\n"));
+    }
+
+    #[test]
+    fn bytes_literal_invalid_escape() {
+        let err = Compiler::new().compile_str("x = b'\\xZZ'\n").unwrap_err();
+
+        assert_eq!(format!("{}", err), [
+            "  File \"<string>\", line 1",
+            "    x = b'\\xZZ'",
+            "         ^",
+            "SyntaxError: (value error) invalid \\x escape at position 0"].join("\n"));
+    }
+
+    #[test]
+    fn bytes_literal_not_ascii() {
+        let err = Compiler::new().compile_str("x = 1\ny = b'caf\u{e9}'\n").unwrap_err();
+
+        assert_eq!(format!("{}", err), [
+            "  File \"<string>\", line 2",
+            "    y = b'caf\u{e9}'",
+            "         ^",
+            "SyntaxError: bytes can only contain ASCII literal characters."].join("\n"));
+    }
+
+    #[test]
+    fn statement_lines_include_dead_code() {
+        let source = "def f():\n    return 1\n    x = 2\n\ny = f()\n";
+        let lines = Compiler::new().statement_lines("lines.py", source).unwrap();
+
+        assert!(lines.contains(&1));
+        assert!(lines.contains(&3));
+        assert!(lines.contains(&5));
+        assert!(!lines.contains(&0));
+    }
+
+    /// Line set for the first instruction that does something
+    fn first_line(ins: &[Instr]) -> Option<usize> {
+        match ins.iter().take_while(|instr| instr.code() == OpCode::SetLineNumber).last() {
+            Some(&Instr(OpCode::SetLineNumber, Some(Native::Count(line)))) => Some(line),
+            _ => None
+        }
+    }
+
+    #[test]
+    fn function_filename_and_lines() {
+        let ins = Compiler::new().compile_source("lines.py", "x = 1\n\ndef f():\n    return x\n").unwrap();
+        assert_eq!(first_line(&ins), Some(1));
+
+        let code = ins.iter().filter_map(|instr| match *instr {
+            Instr(OpCode::LoadConst, Some(Native::Code(ref code))) => Some(code.clone()),
+            _ => None
+        }).next().unwrap();
+
+        assert_eq!(code.co_filename, "lines.py");
+        assert_eq!(first_line(&code.co_code), Some(4));
+    }
+
+    #[test]
+    fn interactive_expr() {
+        let displays = |ins: &[Instr]| ins.iter().any(|instr| match *instr {
+            Instr(OpCode::PrintExpr, None) => true,
+            _ => false
+        });
+
+        let mut compiler = Compiler::new();
+        assert!(displays(&compiler.compile_interactive("x = 1\nx + 1\n").unwrap()));
+        assert!(!displays(&compiler.compile_interactive("x = 1\n").unwrap()));
+
+        assert!(compiler.compile_interactive("def f():\n").is_err());
+        assert!(compiler.is_incomplete());
+
+        assert!(compiler.compile_interactive("x = )\n").is_err());
+        assert!(!compiler.is_incomplete());
+
+        assert!(!displays(&compiler.compile_str("x + 1\n").unwrap()));
+    }
+
+    #[test]
+    fn dump_tokens_and_ast() {
+        let compiler = Compiler::new();
+        assert!(compiler.dump_tokens("x = 1\n").unwrap().contains("Name"));
+        assert!(compiler.dump_ast(strings::SOURCE_STRING, "x = 1\n").unwrap().contains("Assign"));
+
+        let err = compiler.dump_ast(strings::SOURCE_STRING, "x = )\n").unwrap_err();
+        assert!(format!("{}", err).contains("line 1"), "{}", err);
+    }
+
     basic_test!(multiline, r#"
 x = 1
 y = "somewhere over the dynamic language rainbow"
@@ -1,12 +1,196 @@
 //! Useful non-rust representation of compiler artifacts
 //!
+use std::collections::HashSet;
+
 use bincode;
 use serde::Serialize;
 
+use ::runtime::OpCode;
+use ::system::primitives as rs;
+use ::system::primitives::{Instr, Native};
+
 
 /// Take some input that implements `serde::Serialize` and convert it to
 /// bincode encoded `Vec<u8>`.
 ///
 pub fn bincode<'a, T>(input: &'a T) -> Vec<u8> where T: Serialize {
     bincode::serialize(&input, bincode::Infinite).unwrap()
-}
\ No newline at end of file
+}
+
+
+/// Human readable listing of the instructions of `code` in the spirit of CPython's
+/// `dis.dis`, followed by the listings of the code objects it loads as constants.
+/// Each row has the source line when it changes, `>>` if the instruction is a jump
+/// target, the offset, the opcode and its argument:
+///
+/// ```text
+///    1           0 SetLineNumber            1
+///                1 LoadConst                <code object f, file "<string>">
+///                2 LoadConst                "f"
+///                3 MakeFunction
+///                4 StoreName                f
+///
+/// Disassembly of <code object f, file "<string>">:
+///    2           0 SetLineNumber            2
+///                1 LoadName                 a
+///                2 ReturnValue
+/// ```
+pub fn code(code: &rs::Code) -> String {
+    let mut text = String::new();
+    listing(code, &mut text);
+    text
+}
+
+
+fn listing(code: &rs::Code, text: &mut String) {
+    let targets: HashSet<usize> = code.co_code.iter()
+        .filter(|instr| instr.code().is_jump())
+        .filter_map(|instr| match instr.value() {
+            Some(Native::Count(offset)) => Some(offset),
+            _ => None
+        })
+        .collect();
+
+    let mut nested: Vec<&rs::Code> = Vec::new();
+    let mut current: Option<rs::Count> = None;
+    let mut printed: Option<rs::Count> = None;
+
+    for (offset, instr) in code.co_code.iter().enumerate() {
+        current = match *instr {
+            Instr(OpCode::SetLineNumber, Some(Native::Count(line))) => Some(line),
+            _ => rs::line_for_offset(&code.co_lnotab, offset).or(current)
+        };
+
+        let line = match current {
+            Some(line) if current != printed => format!("{}", line),
+            _ => String::new()
+        };
+        printed = current;
+
+        let marker = if targets.contains(&offset) { ">>" } else { "" };
+
+        if let Instr(OpCode::LoadConst, Some(Native::Code(ref func))) = *instr {
+            nested.push(func);
+        }
+
+        let row = format!("{:>4} {:>3} {:>7} {:<24} {}",
+                          line, marker, offset, format!("{:?}", instr.0), argument(instr));
+        text.push_str(row.trim_right());
+        text.push('\n');
+    }
+
+    for func in nested.into_iter() {
+        text.push_str(&format!("\nDisassembly of {}:\n", code_name(func)));
+        listing(func, text);
+    }
+}
+
+
+/// The argument of `instr` as it is shown in a listing, constants that are strings
+/// are quoted to tell them apart from names.
+fn argument(instr: &Instr) -> String {
+    match instr.1 {
+        Some(Native::Str(ref string)) if instr.0 == OpCode::LoadConst => format!("{:?}", string),
+        Some(Native::Str(ref name)) => name.clone(),
+        Some(Native::Bytes(ref bytes)) => format!("b{:?}", String::from_utf8_lossy(bytes)),
+        Some(Native::Int(ref int)) => format!("{}", int),
+        Some(Native::Float(float)) => format!("{:?}", float),
+        Some(Native::Bool(true)) => String::from("True"),
+        Some(Native::Bool(false)) => String::from("False"),
+        Some(Native::Complex(complex)) => format!("({}+{}j)", complex.re, complex.im),
+        Some(Native::Count(count)) => format!("{}", count),
+        Some(Native::Code(ref func)) => code_name(func),
+        Some(Native::List(ref list)) => format!("<list of {} objects>", list.len()),
+        Some(Native::None) => String::from("None"),
+        None => String::new()
+    }
+}
+
+
+fn code_name(code: &rs::Code) -> String {
+    format!("<code object {}, file \"{}\">", code.co_name, code.co_filename)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use ::compiler::Compiler;
+    use ::resources::strings;
+    use super::*;
+
+    fn compile(text: &str) -> rs::Code {
+        let ins = Compiler::new().compile_str(text).unwrap();
+        rs::Code::module(strings::SOURCE_STRING, ins.to_vec())
+    }
+
+    #[test]
+    fn listing_lines_and_arguments() {
+        let func = rs::Code::module(strings::SOURCE_STRING, vec![
+            Instr(OpCode::SetLineNumber, Some(Native::Count(1))),
+            Instr(OpCode::LoadConst, Some(Native::from("a"))),
+            Instr(OpCode::StoreName, Some(Native::from("x"))),
+            Instr(OpCode::SetLineNumber, Some(Native::Count(1))),
+            Instr(OpCode::SetLineNumber, Some(Native::Count(2))),
+            Instr(OpCode::LoadName, Some(Native::from("x"))),
+            Instr(OpCode::CallFunction, Some(Native::Count(0))),
+            Instr(OpCode::PopTop, None),
+        ]);
+
+        assert_eq!(code(&func), [
+            "   1           0 SetLineNumber            1",
+            "               1 LoadConst                \"a\"",
+            "               2 StoreName                x",
+            "               3 SetLineNumber            1",
+            "   2           4 SetLineNumber            2",
+            "               5 LoadName                 x",
+            "               6 CallFunction             0",
+            "               7 PopTop",
+            ""].join("\n"));
+    }
+
+    #[test]
+    fn listing_jump_targets() {
+        let func = compile("x = a if b else c\n");
+        let listing = code(&func);
+
+        let target = func.co_code.iter()
+            .filter(|instr| instr.code().is_jump())
+            .filter_map(|instr| match instr.value() {
+                Some(Native::Count(offset)) => Some(offset),
+                _ => None
+            })
+            .next()
+            .unwrap();
+
+        let row = listing.lines().nth(target).unwrap();
+        assert!(row.contains(&format!(">> {:>7}", target)), "{}", row);
+    }
+
+    #[test]
+    fn listing_line_table() {
+        let func = rs::Code {
+            co_lnotab: vec![(0, 3), (2, 4)],
+            ..rs::Code::module(strings::SOURCE_STRING, vec![
+                Instr(OpCode::LoadConst, Some(Native::Int(rs::Integer::from(1)))),
+                Instr(OpCode::PopTop, None),
+                Instr(OpCode::LoadConst, Some(Native::None)),
+            ])
+        };
+
+        assert_eq!(code(&func), [
+            "   3           0 LoadConst                1",
+            "               1 PopTop",
+            "   4           2 LoadConst                None",
+            ""].join("\n"));
+    }
+
+    #[test]
+    fn listing_nested_code() {
+        let listing = code(&compile("def f(a):\n    return a\n"));
+
+        assert!(listing.contains("LoadConst                <code object f, file \"<string>\">\n"));
+        let nested = listing.split("\nDisassembly of <code object f, file \"<string>\">:\n").nth(1).unwrap();
+        assert!(nested.contains("LoadName                 a\n"), "{}", nested);
+        assert!(nested.contains("ReturnValue\n"), "{}", nested);
+    }
+}
@@ -4,6 +4,8 @@ mod graph;
 mod symbol;
 mod scope;
 
+pub mod cache;
 pub mod fmt;
+pub mod optimizer;
 
 pub use self::compiler::{Compiler, CompilerResult, Context, Instr};
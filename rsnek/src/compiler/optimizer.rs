@@ -0,0 +1,475 @@
+//! Peephole optimizer for compiled code
+//!
+//! `optimize` makes a single pass over the instructions of a code object, and
+//! recursively over the code objects of the functions defined in it:
+//!
+//!  - Constant expressions are folded by evaluating them with the native API so
+//!    `x = 2 ** 10 * 3` executes as `x = 3072`. Operations whose results would be
+//!    too large to keep, like `2 ** 10 ** 9`, are checked for before they are
+//!    evaluated and left for the code to compute if it ever runs.
+//!  - `LoadConst` immediately followed by `PopTop` is removed
+//!  - Unreachable instructions after returns and unconditional jumps are removed
+//!  - `SetLineNumber` instructions are replaced by the `co_lnotab` line table
+//!
+//! Instructions that are jump targets never get merged into the instructions before
+//! them, and jump offsets are remapped once instructions are removed.
+//!
+use std::collections::HashSet;
+
+use num::{Signed, ToPrimitive, Zero};
+
+use ::api::RtObject;
+use ::modules::builtins::Type;
+use ::runtime::{OpCode, Runtime, binary_op, unary_op};
+use ::runtime::inline_cache::InlineCaches;
+use ::runtime::traits::{BooleanProvider, BytesProvider, FloatProvider, IntegerProvider,
+                        NoneProvider, StringProvider};
+use ::system::primitives as rs;
+use ::system::primitives::{Instr, Native};
+
+
+/// Folded strings and bytes longer than this are left to be built at runtime
+/// so the compiled code does not balloon, same limit as CPython.
+pub const MAX_STR_SIZE: usize = 4096;
+
+/// Folded integers larger than this many bits are left to be computed at runtime
+pub const MAX_INT_BITS: usize = 128;
+
+
+/// Optimize `code` and the functions defined in it
+pub fn optimize(rt: &Runtime, code: &rs::Code) -> rs::Code {
+    let targets: HashSet<usize> = code.co_code.iter()
+        .filter(|instr| instr.code().is_jump())
+        .filter_map(|instr| match instr.value() {
+            Some(Native::Count(offset)) => Some(offset),
+            _ => None
+        })
+        .collect();
+
+    // Optimized instructions paired with the offset of the first
+    // original instruction they were made from.
+    let mut out: Vec<(usize, Instr)> = Vec::with_capacity(code.co_code.len());
+    let mut lines: Vec<(usize, rs::Count)> = Vec::new();
+
+    // Index of the first instruction in `out` that later instructions may be merged
+    // into, jump targets and line boundaries always start a new sequence.
+    let mut floor: usize = 0;
+    let mut reachable = true;
+
+    for (offset, instr) in code.co_code.iter().enumerate() {
+        if targets.contains(&offset) {
+            floor = out.len();
+            reachable = true;
+        }
+
+        if !reachable {
+            continue;
+        }
+
+        match *instr {
+            Instr(OpCode::SetLineNumber, Some(Native::Count(line))) => {
+                lines.push((offset, line));
+                floor = out.len();
+            },
+            Instr(OpCode::LoadConst, Some(Native::Code(ref func))) => {
+                out.push((offset, Instr(OpCode::LoadConst, Some(Native::Code(optimize(rt, func))))));
+            },
+            Instr(OpCode::PopTop, None) if constants(&out, floor, 1).is_some() => {
+                out.pop();
+            },
+            Instr(opcode, None) if is_foldable_binary(opcode) => {
+                let folded = constants(&out, floor, 2)
+                    .and_then(|consts| match is_cheap(opcode, &consts[0], &consts[1]) {
+                        true => fold(rt, consts, |lhs, rhs| binary_op(rt, opcode, lhs, rhs)),
+                        false => None
+                    });
+
+                match folded {
+                    Some(native) => replace_top(&mut out, 2, native),
+                    None => out.push((offset, instr.clone()))
+                }
+            },
+            Instr(opcode, None) if is_unary(opcode) => {
+                let folded = constants(&out, floor, 1)
+                    .and_then(|consts| fold(rt, consts, |operand, _| unary_op(rt, opcode, operand)));
+
+                match folded {
+                    Some(native) => replace_top(&mut out, 1, native),
+                    None => out.push((offset, instr.clone()))
+                }
+            },
+            Instr(OpCode::ReturnValue, _)   |
+            Instr(OpCode::JumpAbsolute, _)  |
+            Instr(OpCode::JumpForward, _)   |
+            Instr(OpCode::ContinueLoop, _)  |
+            Instr(OpCode::BreakLoop, _)     => {
+                out.push((offset, instr.clone()));
+                reachable = false;
+            },
+            _ => out.push((offset, instr.clone()))
+        }
+    }
+
+    let starts: Vec<usize> = out.iter().map(|&(start, _)| start).collect();
+    let remap = |offset: usize| -> usize {
+        match starts.binary_search(&offset) {
+            Ok(idx) | Err(idx) => idx
+        }
+    };
+
+    let co_code: Vec<Instr> = out.into_iter()
+        .map(|(_, instr)| match instr {
+            Instr(opcode, Some(Native::Count(target))) if opcode.is_jump() => {
+                Instr(opcode, Some(Native::Count(remap(target))))
+            },
+            instr => instr
+        })
+        .collect();
+
+    let mut co_lnotab: Vec<rs::LineEntry> = code.co_lnotab.clone();
+    for (offset, line) in lines.into_iter() {
+        let offset = remap(offset);
+        match co_lnotab.last().cloned() {
+            Some((last, _)) if last == offset => {
+                co_lnotab.pop();
+            },
+            Some((_, last_line)) if last_line == line => continue,
+            _ => {}
+        };
+
+        co_lnotab.push((offset, line));
+    }
+
+    rs::Code {
+        co_code: co_code,
+        co_lnotab: co_lnotab,
+        co_caches: InlineCaches::default(),
+        ..code.clone()
+    }
+}
+
+
+/// The values of the last `count` instructions of `out` if they are all constants
+/// that can be merged with the next instruction.
+fn constants(out: &[(usize, Instr)], floor: usize, count: usize) -> Option<Vec<Native>> {
+    if out.len() < floor + count {
+        return None
+    }
+
+    out[out.len() - count..].iter()
+        .map(|&(_, ref instr)| match *instr {
+            Instr(OpCode::LoadConst, Some(ref native)) if is_constant(native) => Some(native.clone()),
+            _ => None
+        })
+        .collect()
+}
+
+
+/// Replace the last `count` instructions of `out` with a single `LoadConst`
+fn replace_top(out: &mut Vec<(usize, Instr)>, count: usize, native: Native) {
+    let start = out.len() - count;
+    let offset = out[start].0;
+    out.truncate(start);
+    out.push((offset, Instr(OpCode::LoadConst, Some(native))));
+}
+
+
+/// Evaluate `func` with the constants as its operands, `None` if the operation
+/// fails or the result is not something that should become a constant.
+fn fold<F>(rt: &Runtime, consts: Vec<Native>, func: F) -> Option<Native>
+    where F: Fn(&RtObject, &RtObject) -> ::api::result::ObjectResult {
+
+    let objects: Vec<RtObject> = consts.iter().filter_map(|native| to_object(rt, native)).collect();
+    let result = match objects.len() {
+        1 => func(&objects[0], &rt.none()),
+        2 => func(&objects[0], &objects[1]),
+        _ => return None
+    };
+
+    match result {
+        Ok(object) => to_native(&object),
+        // Errors like ZeroDivisionError are raised when the code runs
+        Err(_) => None
+    }
+}
+
+
+/// False if `lhs <opcode> rhs` would build a result too large to fold, judged from
+/// the sizes of the operands alone like CPython's `safe_multiply`, `safe_power` and
+/// `safe_lshift` so that the compiler never evaluates it.
+fn is_cheap(opcode: OpCode, lhs: &Native, rhs: &Native) -> bool {
+    match (opcode, as_int(lhs), as_int(rhs)) {
+        (OpCode::BinaryMultiply, Some(lhs), Some(rhs)) => {
+            lhs.bits() + rhs.bits() <= MAX_INT_BITS
+        },
+        (OpCode::BinaryMultiply, Some(count), None) => is_cheap_repeat(rhs, &count),
+        (OpCode::BinaryMultiply, None, Some(count)) => is_cheap_repeat(lhs, &count),
+        // Negative powers are computed as floats
+        (OpCode::BinaryPower, Some(base), Some(exponent)) => {
+            match exponent.to_usize() {
+                Some(exponent) => base.bits().saturating_mul(exponent) <= MAX_INT_BITS,
+                None => exponent.is_negative()
+            }
+        },
+        // Negative shifts are a ValueError
+        (OpCode::BinaryLshift, Some(value), Some(shift)) => {
+            match shift.to_usize() {
+                Some(shift) => value.bits().saturating_add(shift) <= MAX_INT_BITS,
+                None => shift.is_negative()
+            }
+        },
+        _ => true
+    }
+}
+
+
+/// False if repeating the str or bytes `sequence` `count` times is too long to fold
+fn is_cheap_repeat(sequence: &Native, count: &rs::Integer) -> bool {
+    let len = match *sequence {
+        Native::Str(ref string) => string.len(),
+        Native::Bytes(ref bytes) => bytes.len(),
+        _ => return true
+    };
+
+    match count.to_usize() {
+        Some(count) => len.saturating_mul(count) <= MAX_STR_SIZE,
+        None => count.is_negative() || len == 0
+    }
+}
+
+
+/// The value of an int or bool constant
+fn as_int(native: &Native) -> Option<rs::Integer> {
+    match *native {
+        Native::Int(ref int) => Some(int.clone()),
+        Native::Bool(boolean) => Some(rs::Integer::from(boolean as u8)),
+        _ => None
+    }
+}
+
+
+fn is_constant(native: &Native) -> bool {
+    match *native {
+        Native::Str(_)      |
+        Native::Bytes(_)    |
+        Native::Int(_)      |
+        Native::Float(_)    |
+        Native::Bool(_)     |
+        Native::None        => true,
+        _ => false
+    }
+}
+
+
+/// Binary operations that can be evaluated at compile time. Identity comparisons
+/// are left alone since they depend on which objects are shared at runtime.
+fn is_foldable_binary(opcode: OpCode) -> bool {
+    match opcode {
+        OpCode::CompareEqual            |
+        OpCode::CompareNotEqual         |
+        OpCode::CompareLess             |
+        OpCode::CompareLessOrEqual      |
+        OpCode::CompareGreater          |
+        OpCode::CompareGreaterOrEqual   |
+        OpCode::CompareIn               |
+        OpCode::BinaryAdd               |
+        OpCode::BinarySubtract          |
+        OpCode::BinaryMultiply          |
+        OpCode::BinaryPower             |
+        OpCode::BinaryTrueDivide        |
+        OpCode::BinaryFloorDivide       |
+        OpCode::BinaryModulo            |
+        OpCode::BinaryAnd               |
+        OpCode::BinaryOr                |
+        OpCode::BinaryXor               |
+        OpCode::BinaryLshift            |
+        OpCode::BinaryRshift            => true,
+        _ => false
+    }
+}
+
+
+fn is_unary(opcode: OpCode) -> bool {
+    match opcode {
+        OpCode::UnaryNot        |
+        OpCode::UnaryNegative   |
+        OpCode::UnaryPositive   |
+        OpCode::UnaryInvert     => true,
+        _ => false
+    }
+}
+
+
+fn to_object(rt: &Runtime, native: &Native) -> Option<RtObject> {
+    match *native {
+        Native::Str(ref string) => Some(rt.str(string.clone())),
+        Native::Bytes(ref bytes) => Some(rt.bytes(bytes.clone())),
+        Native::Int(ref int) => Some(rt.int(int.clone())),
+        Native::Float(float) => Some(rt.float(float)),
+        Native::Bool(boolean) => Some(rt.bool(boolean)),
+        Native::None => Some(rt.none()),
+        _ => None
+    }
+}
+
+
+fn to_native(object: &RtObject) -> Option<Native> {
+    match object.as_ref() {
+        &Type::Str(ref string) if string.value.0.len() <= MAX_STR_SIZE => {
+            Some(Native::Str(string.value.0.clone()))
+        },
+        &Type::Bytes(ref bytes) if bytes.value.0.len() <= MAX_STR_SIZE => {
+            Some(Native::Bytes(bytes.value.0.clone()))
+        },
+        &Type::Int(ref int) if int.value.0.bits() <= MAX_INT_BITS => {
+            Some(Native::Int(int.value.0.clone()))
+        },
+        &Type::Float(ref float) => Some(Native::Float(float.value.0)),
+        &Type::Bool(ref boolean) => Some(Native::Bool(!boolean.value.0.is_zero())),
+        &Type::None(_) => Some(Native::None),
+        _ => None
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use ::compiler::Compiler;
+    use ::resources::strings;
+    use super::*;
+
+    fn compile(text: &str) -> rs::Code {
+        let ins = Compiler::new().compile_str(text).unwrap();
+        rs::Code::module(strings::SOURCE_STRING, ins.to_vec())
+    }
+
+    fn opcodes(code: &rs::Code) -> Vec<OpCode> {
+        code.co_code.iter().map(Instr::code).collect()
+    }
+
+    #[test]
+    fn fold_binary_and_unary() {
+        let rt = Runtime::new();
+        let code = optimize(&rt, &compile("x = -(2 ** 10 * 3)\n"));
+
+        assert_eq!(opcodes(&code), vec![OpCode::LoadConst, OpCode::StoreName]);
+        match code.co_code[0] {
+            Instr(OpCode::LoadConst, Some(Native::Int(ref int))) => assert_eq!(int.to_string(), "-3072"),
+            ref other => panic!("Expected a folded constant, got {:?}", other)
+        }
+    }
+
+    #[test]
+    fn errors_are_not_folded() {
+        let rt = Runtime::new();
+        let code = optimize(&rt, &compile("x = 1 // 0\n"));
+        assert!(opcodes(&code).contains(&OpCode::BinaryFloorDivide));
+    }
+
+    #[test]
+    fn large_results_are_not_folded() {
+        let rt = Runtime::new();
+        let code = optimize(&rt, &compile("x = 'abc' * 10000\ny = 2 ** 1000\n"));
+        assert!(opcodes(&code).contains(&OpCode::BinaryMultiply));
+        assert!(opcodes(&code).contains(&OpCode::BinaryPower));
+    }
+
+    /// These would take minutes and gigabytes to evaluate, the operands alone
+    /// show that they are not worth folding
+    #[test]
+    fn huge_results_are_not_evaluated() {
+        let rt = Runtime::new();
+        let code = optimize(&rt, &compile([
+            "w = 2 ** 10 ** 9",
+            "x = 'a' * 10 ** 9",
+            "y = 10 ** 9 * b'ab'",
+            "z = 1 << 10 ** 9",
+            "v = 7 ** 123456789 * 3",
+            ""].join("\n").as_str()));
+
+        let ops = opcodes(&code);
+        assert_eq!(ops.iter().filter(|&&op| op == OpCode::BinaryPower).count(), 2);
+        assert_eq!(ops.iter().filter(|&&op| op == OpCode::BinaryMultiply).count(), 3);
+        assert!(ops.contains(&OpCode::BinaryLshift));
+
+        // The exponents themselves are small enough to fold
+        assert!(code.co_code.iter().any(|instr| match *instr {
+            Instr(OpCode::LoadConst, Some(Native::Int(ref int))) => int.to_string() == "1000000000",
+            _ => false
+        }));
+    }
+
+    #[test]
+    fn cheap_operands() {
+        let int = |value: i64| Native::Int(rs::Integer::from(value));
+
+        assert!(is_cheap(OpCode::BinaryPower, &int(2), &int(64)));
+        assert!(is_cheap(OpCode::BinaryPower, &int(2), &int(-100000)));
+        assert!(!is_cheap(OpCode::BinaryPower, &int(3), &int(100)));
+        assert!(is_cheap(OpCode::BinaryMultiply, &Native::Str(String::from("ab")), &int(2048)));
+        assert!(!is_cheap(OpCode::BinaryMultiply, &int(2049), &Native::Str(String::from("ab"))));
+        assert!(is_cheap(OpCode::BinaryMultiply, &Native::Str(String::from("ab")), &int(-5)));
+        assert!(is_cheap(OpCode::BinaryLshift, &int(1), &int(-1)));
+        assert!(!is_cheap(OpCode::BinaryLshift, &int(1), &int(128)));
+        assert!(is_cheap(OpCode::BinaryAdd, &int(1), &int(1)));
+    }
+
+    #[test]
+    fn const_pop_top() {
+        let rt = Runtime::new();
+        let code = optimize(&rt, &compile("'docstring'\n1 + 2\nx = 1\n"));
+        assert_eq!(opcodes(&code), vec![OpCode::LoadConst, OpCode::StoreName]);
+    }
+
+    #[test]
+    fn dead_code_after_return() {
+        let rt = Runtime::new();
+        let code = optimize(&rt, &compile("def f():\n    return 1\n    x = 2\n"));
+
+        let func = code.co_code.iter()
+            .filter_map(|instr| match *instr {
+                Instr(OpCode::LoadConst, Some(Native::Code(ref func))) => Some(func.clone()),
+                _ => None
+            })
+            .next()
+            .unwrap();
+
+        assert_eq!(opcodes(&func), vec![OpCode::LoadConst, OpCode::ReturnValue]);
+    }
+
+    #[test]
+    fn jumps_are_remapped() {
+        let rt = Runtime::new();
+        let code = optimize(&rt, &compile("x = (1 + 1) if y else (2 + 2)\n"));
+
+        assert!(code.co_code.iter().all(|instr| instr.code() != OpCode::BinaryAdd));
+        for instr in code.co_code.iter().filter(|instr| instr.code().is_jump()) {
+            match instr.value() {
+                Some(Native::Count(offset)) => assert!(offset <= code.co_code.len()),
+                other => panic!("Jump without a target {:?}", other)
+            }
+        }
+
+        // y is false so the jump lands on the folded alternative
+        match code.co_code[1] {
+            Instr(OpCode::PopJumpIfFalse, Some(Native::Count(offset))) => {
+                match code.co_code[offset] {
+                    Instr(OpCode::LoadConst, Some(Native::Int(ref int))) => assert_eq!(int.to_string(), "4"),
+                    ref other => panic!("Jump landed on {:?}", other)
+                }
+            },
+            ref other => panic!("Expected a conditional jump, got {:?}", other)
+        }
+    }
+
+    #[test]
+    fn line_table() {
+        let rt = Runtime::new();
+        let code = optimize(&rt, &compile("x = 1\n\n\ny = 2\nz = 3\n"));
+
+        assert!(code.co_code.iter().all(|instr| instr.code() != OpCode::SetLineNumber));
+        assert!(!code.co_lnotab.is_empty());
+        assert!(code.co_lnotab.windows(2).all(|pair| pair[0].0 < pair[1].0 && pair[0].1 < pair[1].1));
+        assert!(rs::line_for_offset(&code.co_lnotab, code.co_code.len() - 1).is_some());
+    }
+}
@@ -25,6 +25,7 @@ extern crate num;
 extern crate num_bigint;
 extern crate rustyline;
 extern crate serde;
+extern crate serde_json;
 extern crate test;
 
 // First Party Extern Crates
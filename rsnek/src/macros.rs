@@ -78,10 +78,14 @@ macro_rules! expr_foreach_type {
             &Type::None(ref $inner) => $e,
             &Type::Int(ref $inner) => $e,
             &Type::Float(ref $inner) => $e,
+            &Type::Complex(ref $inner) => $e,
             &Type::Iter(ref $inner) => $e,
             &Type::Dict(ref $inner) => $e,
             &Type::Str(ref $inner) => $e,
             &Type::Bytes(ref $inner) => $e,
+            &Type::ByteArray(ref $inner) => $e,
+            &Type::Slice(ref $inner) => $e,
+            &Type::Range(ref $inner) => $e,
             &Type::Tuple(ref $inner) =>$e,
             &Type::List(ref $inner) =>$e,
             &Type::Function(ref $inner) => $e,
@@ -111,10 +115,14 @@ macro_rules! unary_op_foreach{
             &Type::None(ref $lhs) => $lhs.$op($rt),
             &Type::Int(ref $lhs) => $lhs.$op($rt),
             &Type::Float(ref $lhs) => $lhs.$op($rt),
+            &Type::Complex(ref $lhs) => $lhs.$op($rt),
             &Type::Iter(ref $lhs) => $lhs.$op($rt),
             &Type::Dict(ref $lhs) => $lhs.$op($rt),
             &Type::Str(ref $lhs) => $lhs.$op($rt),
             &Type::Bytes(ref $lhs) => $lhs.$op($rt),
+            &Type::ByteArray(ref $lhs) => $lhs.$op($rt),
+            &Type::Slice(ref $lhs) => $lhs.$op($rt),
+            &Type::Range(ref $lhs) => $lhs.$op($rt),
             &Type::Tuple(ref $lhs) => $lhs.$op($rt),
             &Type::List(ref $lhs) => $lhs.$op($rt),
             &Type::Function(ref $lhs) => $lhs.$op($rt),
@@ -144,10 +152,14 @@ macro_rules! binary_op_foreach{
             &Type::None(ref $lhs) => $lhs.$op($rt, $rhs),
             &Type::Int(ref $lhs) => $lhs.$op($rt, $rhs),
             &Type::Float(ref $lhs) => $lhs.$op($rt, $rhs),
+            &Type::Complex(ref $lhs) => $lhs.$op($rt, $rhs),
             &Type::Iter(ref $lhs) => $lhs.$op($rt, $rhs),
             &Type::Dict(ref $lhs) => $lhs.$op($rt, $rhs),
             &Type::Str(ref $lhs) => $lhs.$op($rt, $rhs),
             &Type::Bytes(ref $lhs) => $lhs.$op($rt, $rhs),
+            &Type::ByteArray(ref $lhs) => $lhs.$op($rt, $rhs),
+            &Type::Slice(ref $lhs) => $lhs.$op($rt, $rhs),
+            &Type::Range(ref $lhs) => $lhs.$op($rt, $rhs),
             &Type::Tuple(ref $lhs) => $lhs.$op($rt, $rhs),
             &Type::List(ref $lhs) => $lhs.$op($rt, $rhs),
             &Type::Function(ref $lhs) => $lhs.$op($rt, $rhs),
@@ -177,10 +189,14 @@ macro_rules! ternary_op_foreach{
             &Type::None(ref $lhs) => $lhs.$op($rt, $mid, $rhs),
             &Type::Int(ref $lhs) => $lhs.$op($rt, $mid, $rhs),
             &Type::Float(ref $lhs) => $lhs.$op($rt, $mid, $rhs),
+            &Type::Complex(ref $lhs) => $lhs.$op($rt, $mid, $rhs),
             &Type::Iter(ref $lhs) => $lhs.$op($rt, $mid, $rhs),
             &Type::Dict(ref $lhs) => $lhs.$op($rt, $mid, $rhs),
             &Type::Str(ref $lhs) => $lhs.$op($rt, $mid, $rhs),
             &Type::Bytes(ref $lhs) => $lhs.$op($rt, $mid, $rhs),
+            &Type::ByteArray(ref $lhs) => $lhs.$op($rt, $mid, $rhs),
+            &Type::Slice(ref $lhs) => $lhs.$op($rt, $mid, $rhs),
+            &Type::Range(ref $lhs) => $lhs.$op($rt, $mid, $rhs),
             &Type::Tuple(ref $lhs) => $lhs.$op($rt, $mid, $rhs),
             &Type::List(ref $lhs) => $lhs.$op($rt, $mid, $rhs),
             &Type::Function(ref $lhs) => $lhs.$op($rt, $mid, $rhs),
@@ -210,10 +226,14 @@ macro_rules! _4ary_op_foreach{
             &Type::None(ref $lhs) => $lhs.$op($rt, $arg0, $arg1, $arg2),
             &Type::Int(ref $lhs) => $lhs.$op($rt, $arg0, $arg1, $arg2),
             &Type::Float(ref $lhs) => $lhs.$op($rt, $arg0, $arg1, $arg2),
+            &Type::Complex(ref $lhs) => $lhs.$op($rt, $arg0, $arg1, $arg2),
             &Type::Iter(ref $lhs) => $lhs.$op($rt, $arg0, $arg1, $arg2),
             &Type::Dict(ref $lhs) => $lhs.$op($rt, $arg0, $arg1, $arg2),
             &Type::Str(ref $lhs) => $lhs.$op($rt, $arg0, $arg1, $arg2),
             &Type::Bytes(ref $lhs) => $lhs.$op($rt, $arg0, $arg1, $arg2),
+            &Type::ByteArray(ref $lhs) => $lhs.$op($rt, $arg0, $arg1, $arg2),
+            &Type::Slice(ref $lhs) => $lhs.$op($rt, $arg0, $arg1, $arg2),
+            &Type::Range(ref $lhs) => $lhs.$op($rt, $arg0, $arg1, $arg2),
             &Type::Tuple(ref $lhs) => $lhs.$op($rt, $arg0, $arg1, $arg2),
             &Type::List(ref $lhs) => $lhs.$op($rt, $arg0, $arg1, $arg2),
             &Type::Function(ref $lhs) => $lhs.$op($rt, $arg0, $arg1, $arg2),
@@ -258,10 +278,14 @@ macro_rules! native_unary_op_foreach{
             &Type::None(ref $lhs) => $lhs.$op(),
             &Type::Int(ref $lhs) => $lhs.$op(),
             &Type::Float(ref $lhs) => $lhs.$op(),
+            &Type::Complex(ref $lhs) => $lhs.$op(),
             &Type::Iter(ref $lhs) => $lhs.$op(),
             &Type::Dict(ref $lhs) => $lhs.$op(),
             &Type::Str(ref $lhs) => $lhs.$op(),
             &Type::Bytes(ref $lhs) => $lhs.$op(),
+            &Type::ByteArray(ref $lhs) => $lhs.$op(),
+            &Type::Slice(ref $lhs) => $lhs.$op(),
+            &Type::Range(ref $lhs) => $lhs.$op(),
             &Type::Tuple(ref $lhs) => $lhs.$op(),
             &Type::List(ref $lhs) => $lhs.$op(),
             &Type::Function(ref $lhs) => $lhs.$op(),
@@ -287,10 +311,14 @@ macro_rules! native_binary_op_foreach{
             &Type::None(ref $lhs) => $lhs.$op($rhs),
             &Type::Int(ref $lhs) => $lhs.$op($rhs),
             &Type::Float(ref $lhs) => $lhs.$op($rhs),
+            &Type::Complex(ref $lhs) => $lhs.$op($rhs),
             &Type::Iter(ref $lhs) => $lhs.$op($rhs),
             &Type::Dict(ref $lhs) => $lhs.$op($rhs),
             &Type::Str(ref $lhs) => $lhs.$op($rhs),
             &Type::Bytes(ref $lhs) => $lhs.$op($rhs),
+            &Type::ByteArray(ref $lhs) => $lhs.$op($rhs),
+            &Type::Slice(ref $lhs) => $lhs.$op($rhs),
+            &Type::Range(ref $lhs) => $lhs.$op($rhs),
             &Type::Tuple(ref $lhs) => $lhs.$op($rhs),
             &Type::List(ref $lhs) => $lhs.$op($rhs),
             &Type::Function(ref $lhs) => $lhs.$op($rhs),
@@ -316,10 +344,14 @@ macro_rules! native_ternary_op_foreach{
             &Type::None(ref $lhs) => $lhs.$op($mid, $rhs),
             &Type::Int(ref $lhs) => $lhs.$op($mid, $rhs),
             &Type::Float(ref $lhs) => $lhs.$op($mid, $rhs),
+            &Type::Complex(ref $lhs) => $lhs.$op($mid, $rhs),
             &Type::Iter(ref $lhs) => $lhs.$op($mid, $rhs),
             &Type::Dict(ref $lhs) => $lhs.$op($mid, $rhs),
             &Type::Str(ref $lhs) => $lhs.$op($mid, $rhs),
             &Type::Bytes(ref $lhs) => $lhs.$op($mid, $rhs),
+            &Type::ByteArray(ref $lhs) => $lhs.$op($mid, $rhs),
+            &Type::Slice(ref $lhs) => $lhs.$op($mid, $rhs),
+            &Type::Range(ref $lhs) => $lhs.$op($mid, $rhs),
             &Type::Tuple(ref $lhs) => $lhs.$op($mid, $rhs),
             &Type::List(ref $lhs) => $lhs.$op($mid, $rhs),
             &Type::Function(ref $lhs) => $lhs.$op($mid, $rhs),
@@ -346,10 +378,14 @@ macro_rules! native_4ary_op_foreach {
             &Type::None(ref $lhs) => $lhs.$op($arg0, $arg1, $arg2),
             &Type::Int(ref $lhs) => $lhs.$op($arg0, $arg1, $arg2),
             &Type::Float(ref $lhs) => $lhs.$op($arg0, $arg1, $arg2),
+            &Type::Complex(ref $lhs) => $lhs.$op($arg0, $arg1, $arg2),
             &Type::Iter(ref $lhs) => $lhs.$op($arg0, $arg1, $arg2),
             &Type::Dict(ref $lhs) => $lhs.$op($arg0, $arg1, $arg2),
             &Type::Str(ref $lhs) => $lhs.$op($arg0, $arg1, $arg2),
             &Type::Bytes(ref $lhs) => $lhs.$op($arg0, $arg1, $arg2),
+            &Type::ByteArray(ref $lhs) => $lhs.$op($arg0, $arg1, $arg2),
+            &Type::Slice(ref $lhs) => $lhs.$op($arg0, $arg1, $arg2),
+            &Type::Range(ref $lhs) => $lhs.$op($arg0, $arg1, $arg2),
             &Type::Tuple(ref $lhs) => $lhs.$op($arg0, $arg1, $arg2),
             &Type::List(ref $lhs) => $lhs.$op($arg0, $arg1, $arg2),
             &Type::Function(ref $lhs) => $lhs.$op($arg0, $arg1, $arg2),
@@ -511,6 +547,19 @@ macro_rules! strings_error_bad_operand {
 }
 
 
+/// Generic formatting for a bad comparison message. Given the python:
+/// ```ignore
+/// x = 1 < '3245'
+/// ```
+/// It will produce the string "'<' not supported between instances of 'int' and 'str'".
+#[macro_export]
+macro_rules! strings_error_bad_compare {
+    ($op:expr, $lhs:expr, $rhs:expr) => {
+        format!("'{}' not supported between instances of '{}' and '{}'", $op, $lhs, $rhs);
+    }
+}
+
+
 /// Missing attribute error message formatter
 #[macro_export]
 macro_rules! strings_error_no_attribute {
@@ -535,6 +584,30 @@ macro_rules! rsnek_exception_index {
     }
 }
 
+/// Create the `rs::Func` of the native function `$name` of the module `$module`, where
+/// `$callable` is anything that can be boxed as a `rs::WrapperFn`. Register the functions
+/// on the module with `modules::register`.
+///
+/// # Examples
+///
+/// ```ignore
+/// module_func!(strings::GC_MODULE, "collect", rs_gc_collect, ["generation"])
+/// ```
+#[macro_export]
+macro_rules! module_func (
+    ($module:expr, $name:expr, $callable:expr, $signature:expr) => ({
+        trace!("create module function"; "module" => $module, "function" => $name);
+        let callable: Box<rs::WrapperFn> = Box::new($callable);
+
+        rs::Func {
+            name: String::from($name),
+            module: String::from($module),
+            callable: rs::FuncType::Wrapper(callable),
+            signature: $signature.as_args()
+        }
+    });
+);
+
 /// Generate the code inline to wrap a native rust `PyAPI` unary method as a method-wrapper in a
 /// generic way since the methods cause the function signatures to be type specific to the
 /// implementation. For example, `PyInteger::op_add` has a signature of
@@ -545,9 +618,7 @@ macro_rules! rsnek_exception_index {
 #[macro_export]
 macro_rules! unary_method_wrapper (
     ($sel:ident, $tname:expr, $fname:ident, $rt:ident, $builtin:path, $func:ident) => ({
-        let selfref = $sel.rc.upgrade()?;
-        let callable: Box<rs::WrapperFn> = Box::new(move |rt, pos_args, starargs, kwargs| {
-            let object = selfref.clone();
+        let callable: ::std::sync::Arc<rs::MethodFn> = ::std::sync::Arc::new(move |rt, object, pos_args, starargs, kwargs| {
             check_args(0, &pos_args)?;
             check_args(0, &starargs)?;
             check_kwargs(0, &kwargs)?;
@@ -581,9 +652,7 @@ macro_rules! unary_method_wrapper (
 #[macro_export]
 macro_rules! binary_method_wrapper (
     ($sel:ident, $tname:expr, $fname:ident, $rt:ident, $builtin:path, $func:ident) => ({
-        let selfref = $sel.rc.upgrade()?;
-        let callable: Box<rs::WrapperFn> = Box::new(move |rt, pos_args, starargs, kwargs| {
-            let object = selfref.clone();
+        let callable: ::std::sync::Arc<rs::MethodFn> = ::std::sync::Arc::new(move |rt, object, pos_args, starargs, kwargs| {
             check_args(1, &pos_args)?;
             check_args(0, &starargs)?;
             check_kwargs(0, &kwargs)?;
@@ -619,9 +688,7 @@ macro_rules! binary_method_wrapper (
 #[macro_export]
 macro_rules! ternary_method_wrapper (
     ($sel:ident, $tname:expr, $fname:ident, $rt:ident, $builtin:path, $func:ident) => ({
-        let selfref = $sel.rc.upgrade()?;
-        let callable: Box<rs::WrapperFn> = Box::new(move |rt, pos_args, starargs, kwargs| {
-            let object = selfref.clone();
+        let callable: ::std::sync::Arc<rs::MethodFn> = ::std::sync::Arc::new(move |rt, object, pos_args, starargs, kwargs| {
             check_args(2, &pos_args)?;
             check_args(0, &starargs)?;
             check_kwargs(0, &kwargs)?;
@@ -645,3 +712,82 @@ macro_rules! ternary_method_wrapper (
 
     });
 );
+
+
+/// Generate the code inline to wrap a native rust method that takes a variable number of
+/// positional arguments as a method-wrapper. This is for the named methods such as
+/// `bytes.decode` or `int.to_bytes` which do not map onto a single `PyAPI` trait method.
+/// The wrapped `$func` has the signature `Fn(&T, &Runtime, &rs::List) -> ObjectResult` and
+/// `$nargs` is the `Range` of accepted argument counts.
+///
+#[macro_export]
+macro_rules! variadic_method_wrapper (
+    ($sel:ident, $tname:expr, $fname:ident, $rt:ident, $builtin:path, $func:ident, $nargs:expr) => ({
+        let callable: ::std::sync::Arc<rs::MethodFn> = ::std::sync::Arc::new(move |rt, object, pos_args, starargs, kwargs| {
+            check_args_range($nargs, &pos_args)?;
+            check_args(0, &starargs)?;
+            check_kwargs(0, &kwargs)?;
+
+            let args = ::objects::iterator::try_collect(&pos_args.op_iter(&rt)?)?;
+
+            match object.as_ref() {
+                &$builtin(ref value) => {
+                $func(value, rt, &args)
+                }
+                _ => unreachable!()
+            }
+        });
+
+        Ok($rt.function(rs::Func {
+            name: format!("'{}' of {} object", $fname, $tname),
+            signature: rs::Signature::new(&[], &[], Some("args"), None),
+            module: strings::BUILTINS_MODULE.to_string(),
+            callable: rs::FuncType::MethodWrapper($sel.rc.upgrade()?, callable)
+        }))
+
+    });
+);
+
+
+/// Implements the ordering comparison traits `LessThan`, `LessOrEqual`, `GreaterThan`, and
+/// `GreaterOrEqual` for `$Type` in terms of a single function `$cmp` with the signature
+/// `Fn(&$Type, &Type) -> RtResult<Option<Ordering>>`. The function should return `Ok(None)`
+/// for values that are unordered (e.g. `nan`) which makes every comparison `False` and a
+/// `TypeError` for types that cannot be compared, which is reformatted here with the operator.
+///
+/// # Examples
+///
+/// ```ignore
+/// ordering_comparison_impls!(PyString, TYPE_NAME, |this: &PyString, rhs: &Type| ...);
+/// ```
+#[macro_export]
+macro_rules! ordering_comparison_impls {
+    ($Type:ty, $tname:expr, $cmp:expr) => {
+        ordering_comparison_impls!(@impl $Type, $tname, $cmp, LessThan, op_lt, native_lt, "<",
+            |ordering| ordering == Ordering::Less);
+        ordering_comparison_impls!(@impl $Type, $tname, $cmp, LessOrEqual, op_le, native_le, "<=",
+            |ordering| ordering != Ordering::Greater);
+        ordering_comparison_impls!(@impl $Type, $tname, $cmp, GreaterThan, op_gt, native_gt, ">",
+            |ordering| ordering == Ordering::Greater);
+        ordering_comparison_impls!(@impl $Type, $tname, $cmp, GreaterOrEqual, op_ge, native_ge, ">=",
+            |ordering| ordering != Ordering::Less);
+    };
+    (@impl $Type:ty, $tname:expr, $cmp:expr, $tr:ident, $fname:ident, $nfname:ident, $op:expr, $test:expr) => {
+        impl method::$tr for $Type {
+            fn $fname(&self, rt: &Runtime, rhs: &RtObject) -> ObjectResult {
+                let truth = self.$nfname(rhs.as_ref())?;
+                Ok(rt.bool(truth))
+            }
+
+            fn $nfname(&self, rhs: &Type) -> RtResult<rs::Boolean> {
+                match $cmp(self, rhs) {
+                    Ok(Some(ordering)) => Ok($test(ordering)),
+                    Ok(None) => Ok(false),
+                    Err(Error(ErrorType::Type, _)) => Err(Error::typerr(
+                        &strings_error_bad_compare!($op, $tname, rhs.debug_name()))),
+                    Err(err) => Err(err)
+                }
+            }
+        }
+    };
+}
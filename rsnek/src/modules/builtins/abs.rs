@@ -0,0 +1,81 @@
+//! `abs()` - builtin function
+//!
+//! Get the absolute value of a number via `__abs__`.
+//!
+use ::api::method::{AbsValue, GetItem};
+use ::api::result::{Error, ErrorType, ObjectResult};
+use ::api::RtObject as ObjectRef;
+use ::modules::precondition::{check_args, check_kwargs};
+use ::resources::strings;
+use ::runtime::Runtime;
+use ::runtime::traits::{IntegerProvider};
+use ::system::primitives as rs;
+use ::system::primitives::{Func, FuncType, SignatureBuilder};
+
+
+const FUNC_NAME: &'static str = "abs";
+
+pub struct AbsFn;
+
+
+impl AbsFn {
+    pub fn create() -> rs::Func {
+        trace!("create builtin"; "function" => FUNC_NAME);
+        let callable: Box<rs::WrapperFn> = Box::new(rs_builtin_abs);
+
+        Func {
+            name: String::from(FUNC_NAME),
+            module: String::from(strings::BUILTINS_MODULE),
+            callable: FuncType::Wrapper(callable),
+            signature: ["x"].as_args()
+        }
+    }
+}
+
+
+/// `abs(x)`
+fn rs_builtin_abs(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_builtin" => FUNC_NAME);
+    check_args(1, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    let object = pos_args.op_getitem(&rt, &rt.int(0))?;
+    match object.op_abs(&rt) {
+        Err(Error(ErrorType::System, _)) => Err(Error::typerr(&format!(
+            "bad operand type for abs(): '{}'", object.debug_name()))),
+        result => result
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use ::runtime::traits::{BooleanProvider, DefaultDictProvider, DefaultTupleProvider,
+                            FloatProvider, StringProvider, TupleProvider};
+    use super::*;
+
+    fn setup() -> Runtime {
+        Runtime::new()
+    }
+
+    fn abs(rt: &Runtime, object: ObjectRef) -> ObjectResult {
+        rs_builtin_abs(rt, &rt.tuple(vec![object]), &rt.default_tuple(), &rt.default_dict())
+    }
+
+    #[test]
+    fn numbers() {
+        let rt = setup();
+
+        assert_eq!(abs(&rt, rt.int(-5)).unwrap(), rt.int(5));
+        assert_eq!(abs(&rt, rt.float(-0.5)).unwrap(), rt.float(0.5));
+        assert_eq!(abs(&rt, rt.bool(true)).unwrap(), rt.int(1));
+    }
+
+    #[test]
+    #[should_panic]
+    fn not_a_number() {
+        let rt = setup();
+        abs(&rt, rt.str("-1")).unwrap();
+    }
+}
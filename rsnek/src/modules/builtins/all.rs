@@ -4,8 +4,8 @@
 //! returns `True` after calling its `PyAPI::op_bool` method (`__bool__` in python code).
 use std::borrow::Borrow;
 
-use ::api::method::{GetItem, Iter, BooleanCast};
-use ::api::result::{ObjectResult};
+use ::api::method::{GetItem, Iter, BooleanCast, Next};
+use ::api::result::{Error, ErrorType, ObjectResult, RtResult};
 use ::api::RtObject;
 use ::modules::builtins::Type;
 use ::resources::strings;
@@ -44,18 +44,22 @@ fn rs_builtin_all(rt: &Runtime, pos_args: &RtObject, starargs: &RtObject, kwargs
     let value = pos_args.op_getitem(&rt, &rt.int(0))?;
     let iterable = value.op_iter(&rt)?;
 
-    Ok(rt.bool(iterator_all(&rt, iterable)))
+    Ok(rt.bool(iterator_all(&iterable)?))
 }
 
 
-pub fn iterator_all<I>(rt: &Runtime, iterator: I) -> rs::Boolean
-    where I: Iterator<Item=RtObject> {
-
-    let true_ = rt.bool(true);
-
-    iterator
-        .map(|obj| obj.op_bool(&rt).unwrap_or(rt.bool(false)))
-        .all(|t| t == true_)
+/// Whether every item of `iterator` is truthy, stopping at the first one that is not.
+/// Errors raised by the iterator or by `__bool__` are passed up to the caller.
+pub fn iterator_all(iterator: &RtObject) -> RtResult<rs::Boolean> {
+    loop {
+        match iterator.native_next() {
+            Ok(item) => if !item.native_bool()? {
+                return Ok(false)
+            },
+            Err(Error(ErrorType::StopIteration, _)) => return Ok(true),
+            Err(err) => return Err(err)
+        }
+    }
 }
 
 
@@ -80,7 +84,7 @@ mod tests {
         let tuple = rt.default_tuple();
         let iterator = rt.iter(rs::Iterator::new(&tuple).unwrap());
 
-        assert_eq!(iterator_all(&rt, iterator), true);
+        assert_eq!(iterator_all(&iterator).unwrap(), true);
     }
 
 
@@ -91,7 +95,7 @@ mod tests {
         let tuple = rt.tuple(vec![f.clone(),f.clone(),f.clone(),f.clone()]);
         let iterator = rt.iter(rs::Iterator::new(&tuple).unwrap());
 
-        assert_eq!(iterator_all(&rt, iterator), false);
+        assert_eq!(iterator_all(&iterator).unwrap(), false);
     }
 
     #[test]
@@ -101,7 +105,7 @@ mod tests {
         let tuple = rt.tuple(vec![t.clone(),t.clone(),t.clone(),t.clone()]);
         let iterator = rt.iter(rs::Iterator::new(&tuple).unwrap());
 
-        assert_eq!(iterator_all(&rt, iterator), true);
+        assert_eq!(iterator_all(&iterator).unwrap(), true);
     }
 
     #[test]
@@ -111,7 +115,7 @@ mod tests {
         let tuple = rt.tuple(vec![rt.bool(true), f.clone(), f.clone(), f.clone(), f.clone()]);
         let iterator = rt.iter(rs::Iterator::new(&tuple).unwrap());
 
-        assert_eq!(iterator_all(&rt, iterator), false);
+        assert_eq!(iterator_all(&iterator).unwrap(), false);
     }
 
     #[test]
@@ -121,7 +125,7 @@ mod tests {
         let tuple = rt.tuple(vec![t.clone(), t.clone(), t.clone(), t.clone(), rt.bool(false)]);
         let iterator = rt.iter(rs::Iterator::new(&tuple).unwrap());
 
-        assert_eq!(iterator_all(&rt, iterator), false);
+        assert_eq!(iterator_all(&iterator).unwrap(), false);
     }
 
 
@@ -131,20 +135,37 @@ mod tests {
         let tuple = rt.tuple(vec![rt.str("")]);
         let iterator = rt.iter(rs::Iterator::new(&tuple).unwrap());
 
-        assert_eq!(iterator_all(&rt, iterator), false);
+        assert_eq!(iterator_all(&iterator).unwrap(), false);
 
         let tuple = rt.tuple(vec![rt.str(" ")]);
         let iterator = rt.iter(rs::Iterator::new(&tuple).unwrap());
 
-        assert_eq!(iterator_all(&rt, iterator), true);
+        assert_eq!(iterator_all(&iterator).unwrap(), true);
     }
 
     #[test]
-    fn arrays() {
+    fn none_and_int() {
         let rt = setup();
-        assert_eq!(iterator_all(&rt, [rt.none()].iter().cloned()), false);
-        assert_eq!(iterator_all(&rt, [rt.none()].iter().cloned()), false);
+        let tuple = rt.tuple(vec![rt.none()]);
+        let iterator = rt.iter(rs::Iterator::new(&tuple).unwrap());
+        assert_eq!(iterator_all(&iterator).unwrap(), false);
 
-        //assert_eq!(native_all(&rt, iterator), true);
+        let tuple = rt.tuple(vec![rt.int(1)]);
+        let iterator = rt.iter(rs::Iterator::new(&tuple).unwrap());
+        assert_eq!(iterator_all(&iterator).unwrap(), true);
     }
-}
\ No newline at end of file
+
+    /// `all(map(int, ["x"]))` raises the `ValueError` of `int("x")`
+    #[test]
+    fn map_raises() {
+        let rt = setup();
+        let tuple = rt.tuple(vec![rt.str("x")]);
+        let source = rt.iter(rs::Iterator::new(&tuple).unwrap());
+        let iterator = rt.iter(rs::Iterator::Map {func: rt.get_builtin("int"), sources: vec![source]});
+
+        match iterator_all(&iterator) {
+            Err(Error(ErrorType::Value, _)) => {},
+            other => panic!("Expected ValueError, got {:?}", other)
+        }
+    }
+}
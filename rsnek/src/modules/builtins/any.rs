@@ -5,10 +5,10 @@
 use std::borrow::Borrow;
 
 use ::modules::precondition::{check_args, check_kwargs};
-use ::api::method::{GetItem, Iter, BooleanCast};
+use ::api::method::{GetItem, Iter, BooleanCast, Next};
 use ::api::RtObject;
 use ::resources::strings;
-use ::api::result::{ObjectResult};
+use ::api::result::{Error, ErrorType, ObjectResult, RtResult};
 use ::runtime::Runtime;
 use ::runtime::traits::{IntegerProvider, BooleanProvider};
 use ::modules::builtins::Type;
@@ -44,19 +44,22 @@ fn rs_builtin_any(rt: &Runtime, pos_args: &RtObject, starargs: &RtObject, kwargs
     let value = pos_args.op_getitem(&rt, &rt.int(0))?;
     let iterable = value.op_iter(&rt)?;
 
-    Ok(rt.bool(iterator_any(&rt, iterable)))
+    Ok(rt.bool(iterator_any(&iterable)?))
 }
 
 
-pub fn iterator_any<I>(rt: &Runtime, iterator: I) -> rs::Boolean
-    where I: Iterator<Item=RtObject> {
-
-    let true_ = rt.bool(true);
-    
-    iterator
-        .map(|obj| obj.op_bool(&rt).unwrap_or(rt.bool(false)))
-        .any(|t| t == true_)
-
+/// Whether any item of `iterator` is truthy, stopping at the first one that is.
+/// Errors raised by the iterator or by `__bool__` are passed up to the caller.
+pub fn iterator_any(iterator: &RtObject) -> RtResult<rs::Boolean> {
+    loop {
+        match iterator.native_next() {
+            Ok(item) => if item.native_bool()? {
+                return Ok(true)
+            },
+            Err(Error(ErrorType::StopIteration, _)) => return Ok(false),
+            Err(err) => return Err(err)
+        }
+    }
 }
 
 
@@ -81,7 +84,7 @@ mod tests {
         let tuple = rt.default_tuple();
         let iterator = rt.iter(rs::Iterator::new(&tuple).unwrap());
 
-        assert_eq!(iterator_any(&rt, iterator), false);
+        assert_eq!(iterator_any(&iterator).unwrap(), false);
     }
 
 
@@ -92,7 +95,7 @@ mod tests {
         let tuple = rt.tuple(vec![f.clone(),f.clone(),f.clone(),f.clone()]);
         let iterator = rt.iter(rs::Iterator::new(&tuple).unwrap());
 
-        assert_eq!(iterator_any(&rt, iterator), false);
+        assert_eq!(iterator_any(&iterator).unwrap(), false);
     }
 
     #[test]
@@ -102,7 +105,7 @@ mod tests {
         let tuple = rt.tuple(vec![t.clone(),t.clone(),t.clone(),t.clone()]);
         let iterator = rt.iter(rs::Iterator::new(&tuple).unwrap());
 
-        assert_eq!(iterator_any(&rt, iterator), true);
+        assert_eq!(iterator_any(&iterator).unwrap(), true);
     }
 
     #[test]
@@ -112,7 +115,7 @@ mod tests {
         let tuple = rt.tuple(vec![rt.bool(true), f.clone(), f.clone(), f.clone(), f.clone()]);
         let iterator = rt.iter(rs::Iterator::new(&tuple).unwrap());
 
-        assert_eq!(iterator_any(&rt, iterator), true);
+        assert_eq!(iterator_any(&iterator).unwrap(), true);
     }
 
     #[test]
@@ -122,7 +125,7 @@ mod tests {
         let tuple = rt.tuple(vec![t.clone(), t.clone(), t.clone(), t.clone(), rt.bool(false)]);
         let iterator = rt.iter(rs::Iterator::new(&tuple).unwrap());
 
-        assert_eq!(iterator_any(&rt, iterator), true);
+        assert_eq!(iterator_any(&iterator).unwrap(), true);
     }
 
     #[test]
@@ -131,21 +134,37 @@ mod tests {
         let tuple = rt.tuple(vec![rt.str("")]);
         let iterator = rt.iter(rs::Iterator::new(&tuple).unwrap());
 
-        assert_eq!(iterator_any(&rt, iterator), false);
+        assert_eq!(iterator_any(&iterator).unwrap(), false);
 
         let tuple = rt.tuple(vec![rt.str(" ")]);
         let iterator = rt.iter(rs::Iterator::new(&tuple).unwrap());
 
-        assert_eq!(iterator_any(&rt, iterator), true);
+        assert_eq!(iterator_any(&iterator).unwrap(), true);
     }
 
     #[test]
-    fn arrays() {
+    fn none_and_int() {
         let rt = setup();
-        assert_eq!(iterator_any(&rt, [rt.none()].iter().cloned()), false);
-        assert_eq!(iterator_any(&rt, [rt.none()].iter().cloned()), false);
-        assert_eq!(iterator_any(&rt, [rt.int(1)].iter().cloned()), true);
+        let tuple = rt.tuple(vec![rt.none()]);
+        let iterator = rt.iter(rs::Iterator::new(&tuple).unwrap());
+        assert_eq!(iterator_any(&iterator).unwrap(), false);
 
-        //assert_eq!(native_any(&rt, iterator), true);
+        let tuple = rt.tuple(vec![rt.int(1)]);
+        let iterator = rt.iter(rs::Iterator::new(&tuple).unwrap());
+        assert_eq!(iterator_any(&iterator).unwrap(), true);
     }
-}
\ No newline at end of file
+
+    /// `any(map(int, ["x"]))` raises the `ValueError` of `int("x")`
+    #[test]
+    fn map_raises() {
+        let rt = setup();
+        let tuple = rt.tuple(vec![rt.str("x")]);
+        let source = rt.iter(rs::Iterator::new(&tuple).unwrap());
+        let iterator = rt.iter(rs::Iterator::Map {func: rt.get_builtin("int"), sources: vec![source]});
+
+        match iterator_any(&iterator) {
+            Err(Error(ErrorType::Value, _)) => {},
+            other => panic!("Expected ValueError, got {:?}", other)
+        }
+    }
+}
@@ -0,0 +1,241 @@
+//! `getattr()`, `setattr()`, `hasattr()`, and `delattr()` - builtin functions
+//!
+//! Named attribute access routed through the `GetAttr`, `SetAttr`, and `DelAttr` traits.
+//!
+use ::api::method::{GetItem, GetAttr, SetAttr, DelAttr};
+use ::api::result::{Error, ErrorType, ObjectResult, RtResult};
+use ::api::RtObject as ObjectRef;
+use ::modules::builtins::Type;
+use ::modules::precondition::{check_args, check_args_range, check_kwargs};
+use ::resources::strings;
+use ::runtime::Runtime;
+use ::runtime::traits::{BooleanProvider, IntegerProvider};
+use ::system::primitives as rs;
+use ::system::primitives::{Func, FuncType, SignatureBuilder};
+
+
+pub struct GetAttrFn;
+pub struct SetAttrFn;
+pub struct HasAttrFn;
+pub struct DelAttrFn;
+
+
+impl GetAttrFn {
+    pub fn create() -> rs::Func {
+        trace!("create builtin"; "function" => "getattr");
+        let callable: Box<rs::WrapperFn> = Box::new(rs_builtin_getattr);
+
+        Func {
+            name: String::from("getattr"),
+            module: String::from(strings::BUILTINS_MODULE),
+            callable: FuncType::Wrapper(callable),
+            signature: ["object", "name", "default"].as_args()
+        }
+    }
+}
+
+
+impl SetAttrFn {
+    pub fn create() -> rs::Func {
+        trace!("create builtin"; "function" => "setattr");
+        let callable: Box<rs::WrapperFn> = Box::new(rs_builtin_setattr);
+
+        Func {
+            name: String::from("setattr"),
+            module: String::from(strings::BUILTINS_MODULE),
+            callable: FuncType::Wrapper(callable),
+            signature: ["object", "name", "value"].as_args()
+        }
+    }
+}
+
+
+impl HasAttrFn {
+    pub fn create() -> rs::Func {
+        trace!("create builtin"; "function" => "hasattr");
+        let callable: Box<rs::WrapperFn> = Box::new(rs_builtin_hasattr);
+
+        Func {
+            name: String::from("hasattr"),
+            module: String::from(strings::BUILTINS_MODULE),
+            callable: FuncType::Wrapper(callable),
+            signature: ["object", "name"].as_args()
+        }
+    }
+}
+
+
+impl DelAttrFn {
+    pub fn create() -> rs::Func {
+        trace!("create builtin"; "function" => "delattr");
+        let callable: Box<rs::WrapperFn> = Box::new(rs_builtin_delattr);
+
+        Func {
+            name: String::from("delattr"),
+            module: String::from(strings::BUILTINS_MODULE),
+            callable: FuncType::Wrapper(callable),
+            signature: ["object", "name"].as_args()
+        }
+    }
+}
+
+
+/// Attribute names must be strings regardless of what the object supports
+fn check_name(fname: &str, name: &ObjectRef) -> RtResult<()> {
+    match name.as_ref() {
+        &Type::Str(_) => Ok(()),
+        _ => Err(Error::typerr(&format!("{}(): attribute name must be string", fname)))
+    }
+}
+
+
+/// Types that do not implement an attribute trait at all have no attributes to
+/// speak of, so report that the same way as a missing attribute.
+fn missing_attribute(object: &ObjectRef, name: &ObjectRef, result: ObjectResult) -> ObjectResult {
+    match result {
+        Err(Error(ErrorType::System, _)) => Err(Error::attribute(
+            &strings_error_no_attribute!(object.debug_name(), name.to_string()))),
+        result => result
+    }
+}
+
+
+/// `getattr(object, name[, default])`
+fn rs_builtin_getattr(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_builtin" => "getattr");
+    let arg_count = check_args_range(2..4, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    let object = pos_args.op_getitem(&rt, &rt.int(0))?;
+    let name = pos_args.op_getitem(&rt, &rt.int(1))?;
+    check_name("getattr", &name)?;
+
+    let result = missing_attribute(&object, &name, object.op_getattr(&rt, &name));
+
+    match result {
+        Err(Error(ErrorType::Attribute, _)) if arg_count == 3 => pos_args.op_getitem(&rt, &rt.int(2)),
+        result => result
+    }
+}
+
+
+/// `setattr(object, name, value)`
+fn rs_builtin_setattr(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_builtin" => "setattr");
+    check_args(3, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    let object = pos_args.op_getitem(&rt, &rt.int(0))?;
+    let name = pos_args.op_getitem(&rt, &rt.int(1))?;
+    let value = pos_args.op_getitem(&rt, &rt.int(2))?;
+    check_name("setattr", &name)?;
+
+    missing_attribute(&object, &name, object.op_setattr(&rt, &name, &value))
+}
+
+
+/// `hasattr(object, name)`
+fn rs_builtin_hasattr(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_builtin" => "hasattr");
+    check_args(2, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    let object = pos_args.op_getitem(&rt, &rt.int(0))?;
+    let name = pos_args.op_getitem(&rt, &rt.int(1))?;
+    check_name("hasattr", &name)?;
+
+    match missing_attribute(&object, &name, object.op_getattr(&rt, &name)) {
+        Ok(_) => Ok(rt.bool(true)),
+        Err(Error(ErrorType::Attribute, _)) => Ok(rt.bool(false)),
+        Err(err) => Err(err)
+    }
+}
+
+
+/// `delattr(object, name)`
+fn rs_builtin_delattr(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_builtin" => "delattr");
+    check_args(2, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    let object = pos_args.op_getitem(&rt, &rt.int(0))?;
+    let name = pos_args.op_getitem(&rt, &rt.int(1))?;
+    check_name("delattr", &name)?;
+
+    missing_attribute(&object, &name, object.op_delattr(&rt, &name))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use ::runtime::traits::{DefaultDictProvider, DefaultTupleProvider, NoneProvider,
+                            ObjectProvider, StringProvider, TupleProvider};
+    use super::*;
+
+    fn setup() -> Runtime {
+        Runtime::new()
+    }
+
+    fn call(rt: &Runtime, func: &rs::WrapperFn, args: Vec<ObjectRef>) -> ObjectResult {
+        func(rt, &rt.tuple(args), &rt.default_tuple(), &rt.default_dict())
+    }
+
+    #[test]
+    fn set_get_has_del() {
+        let rt = setup();
+        let object = rt.object(rs::None());
+        let name = rt.str("answer");
+
+        let result = call(&rt, &rs_builtin_setattr, vec![object.clone(), name.clone(), rt.int(42)]);
+        assert_eq!(result.unwrap(), rt.none());
+
+        let result = call(&rt, &rs_builtin_getattr, vec![object.clone(), name.clone()]);
+        assert_eq!(result.unwrap(), rt.int(42));
+
+        let result = call(&rt, &rs_builtin_hasattr, vec![object.clone(), name.clone()]);
+        assert_eq!(result.unwrap(), rt.bool(true));
+
+        let result = call(&rt, &rs_builtin_delattr, vec![object.clone(), name.clone()]);
+        assert_eq!(result.unwrap(), rt.none());
+
+        let result = call(&rt, &rs_builtin_hasattr, vec![object.clone(), name.clone()]);
+        assert_eq!(result.unwrap(), rt.bool(false));
+    }
+
+    #[test]
+    fn getattr_default() {
+        let rt = setup();
+
+        let result = call(&rt, &rs_builtin_getattr, vec![rt.int(1), rt.str("potato"), rt.none()]);
+        assert_eq!(result.unwrap(), rt.none());
+
+        let result = call(&rt, &rs_builtin_getattr, vec![rt.int(1), rt.str("__doc__"), rt.none()]);
+        assert_eq!(result.unwrap().debug_name(), "str");
+    }
+
+    #[test]
+    fn hasattr_without_getattr() {
+        let rt = setup();
+
+        let result = call(&rt, &rs_builtin_hasattr, vec![rt.none(), rt.str("potato")]);
+        assert_eq!(result.unwrap(), rt.bool(false));
+    }
+
+    #[test]
+    #[should_panic]
+    fn getattr_missing() {
+        let rt = setup();
+        call(&rt, &rs_builtin_getattr, vec![rt.int(1), rt.str("potato")]).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn getattr_bad_name() {
+        let rt = setup();
+        call(&rt, &rs_builtin_getattr, vec![rt.int(1), rt.int(2)]).unwrap();
+    }
+}
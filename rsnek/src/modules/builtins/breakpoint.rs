@@ -0,0 +1,65 @@
+//! `breakpoint()` - builtin function
+//!
+//! Stop in the debugger before the next line is run. The interpreter starts a
+//! debugger for it when the program was not run with `--debug`.
+//!
+use ::api::result::{ObjectResult};
+use ::api::RtObject as ObjectRef;
+use ::modules::precondition::{check_args, check_kwargs};
+use ::resources::strings;
+use ::runtime::Runtime;
+use ::runtime::traits::NoneProvider;
+use ::system::primitives as rs;
+use ::system::primitives::{Func, FuncType, SignatureBuilder};
+
+
+const FUNC_NAME: &'static str = "breakpoint";
+
+pub struct BreakpointFn;
+
+
+impl BreakpointFn {
+    pub fn create() -> rs::Func {
+        trace!("create builtin"; "function" => FUNC_NAME);
+        let callable: Box<rs::WrapperFn> = Box::new(rs_builtin_breakpoint);
+
+        Func {
+            name: String::from(FUNC_NAME),
+            module: String::from(strings::BUILTINS_MODULE),
+            callable: FuncType::Wrapper(callable),
+            signature: [].as_args()
+        }
+    }
+}
+
+
+/// `breakpoint()`
+fn rs_builtin_breakpoint(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_builtin" => FUNC_NAME);
+    check_args(0, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    rt.request_break();
+    Ok(rt.none())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use ::runtime::traits::{DefaultDictProvider, DefaultTupleProvider, IntegerProvider, TupleProvider};
+    use super::*;
+
+    #[test]
+    fn requests_break() {
+        let rt = Runtime::new();
+        assert!(!rt.take_break_request());
+
+        let result = rs_builtin_breakpoint(&rt, &rt.default_tuple(), &rt.default_tuple(), &rt.default_dict());
+        assert_eq!(result.unwrap(), rt.none());
+        assert!(rt.take_break_request());
+        assert!(!rt.take_break_request());
+
+        assert!(rs_builtin_breakpoint(&rt, &rt.tuple(vec![rt.int(1)]), &rt.default_tuple(), &rt.default_dict()).is_err());
+    }
+}
@@ -0,0 +1,93 @@
+//! `bytes()` - builtin function
+//!
+//! In the future this will be replaced by a type object.
+use ::api::method::{GetItem};
+use ::api::result::{Error, ObjectResult};
+use ::api::RtObject as ObjectRef;
+use ::modules::builtins::Type;
+use ::modules::precondition::{check_args, check_kwargs, check_args_range};
+use ::objects::collection::buffer;
+use ::resources::strings;
+use ::runtime::Runtime;
+use ::runtime::traits::{IntegerProvider, DefaultBytesProvider, BytesProvider};
+use ::system::primitives as rs;
+use ::system::primitives::{Func, FuncType, SignatureBuilder};
+
+
+pub struct BytesFn;
+
+const FUNC_NAME: &'static str = "bytes";
+
+
+impl BytesFn {
+    pub fn create() -> rs::Func {
+        trace!("create builtin"; "function" => FUNC_NAME);
+        let callable: Box<rs::WrapperFn> = Box::new(rs_builtin_bytes);
+
+        Func {
+            name: String::from(FUNC_NAME),
+            module: String::from(strings::BUILTINS_MODULE),
+            callable: FuncType::Wrapper(callable),
+            signature: ["source", "encoding"].as_args()
+        }
+    }
+}
+
+
+fn rs_builtin_bytes(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_builtin" => FUNC_NAME);
+
+    let arg_count = check_args_range(0..3, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    if arg_count == 0 {
+        return Ok(rt.default_bytes())
+    }
+
+    let source = pos_args.op_getitem(&rt, &rt.int(0))?;
+    if arg_count == 1 {
+        return Ok(rt.bytes(buffer::from_object(rt, &source)?))
+    }
+
+    let encoding = pos_args.op_getitem(&rt, &rt.int(1))?;
+    match (source.as_ref(), encoding.as_ref()) {
+        (&Type::Str(ref string), &Type::Str(ref encoding)) => {
+            Ok(rt.bytes(buffer::encode(&string.value.0, &encoding.value.0)?))
+        },
+        (&Type::Str(_), other) => Err(Error::typerr(&format!(
+            "bytes() argument 2 must be str, not {}", other.debug_name()))),
+        _ => Err(Error::typerr("encoding without a string argument"))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use ::modules::call_function;
+    use ::runtime::traits::{ListProvider, StringProvider};
+    use super::*;
+
+
+    fn setup() -> Runtime {
+        Runtime::new()
+    }
+
+    #[test]
+    fn from_ints() {
+        let rt = setup();
+        let ints = rt.list(vec![rt.int(104), rt.int(105)]);
+
+        assert_eq!(call_function(&rt, &rt.get_builtin("bytes"), vec![ints]).unwrap(), rt.bytes(b"hi".to_vec()));
+        assert_eq!(call_function(&rt, &rt.get_builtin("bytes"), vec![rt.int(3)]).unwrap(), rt.bytes(vec![0, 0, 0]));
+        assert!(call_function(&rt, &rt.get_builtin("bytes"), vec![rt.list(vec![rt.int(256)])]).is_err());
+    }
+
+    #[test]
+    fn from_str() {
+        let rt = setup();
+
+        assert_eq!(call_function(&rt, &rt.get_builtin("bytes"), vec![rt.str("snek"), rt.str("utf-8")]).unwrap(), rt.bytes(b"snek".to_vec()));
+        assert!(call_function(&rt, &rt.get_builtin("bytes"), vec![rt.str("snek")]).is_err());
+    }
+}
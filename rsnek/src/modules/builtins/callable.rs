@@ -0,0 +1,85 @@
+//! `callable()` - builtin function
+//!
+//! Check if an object can be called. Only functions and type objects implement `__call__`.
+//!
+use ::api::method::GetItem;
+use ::api::result::{ObjectResult};
+use ::api::RtObject as ObjectRef;
+use ::modules::builtins::Type;
+use ::modules::precondition::{check_args, check_kwargs};
+use ::resources::strings;
+use ::runtime::Runtime;
+use ::runtime::traits::{BooleanProvider, IntegerProvider};
+use ::system::primitives as rs;
+use ::system::primitives::{Func, FuncType, SignatureBuilder};
+
+
+const FUNC_NAME: &'static str = "callable";
+
+pub struct CallableFn;
+
+
+impl CallableFn {
+    pub fn create() -> rs::Func {
+        trace!("create builtin"; "function" => FUNC_NAME);
+        let callable: Box<rs::WrapperFn> = Box::new(rs_builtin_callable);
+
+        Func {
+            name: String::from(FUNC_NAME),
+            module: String::from(strings::BUILTINS_MODULE),
+            callable: FuncType::Wrapper(callable),
+            signature: ["object"].as_args()
+        }
+    }
+}
+
+
+/// `callable(object)`
+fn rs_builtin_callable(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_builtin" => FUNC_NAME);
+    check_args(1, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    let object = pos_args.op_getitem(&rt, &rt.int(0))?;
+
+    let truth = match object.as_ref() {
+        &Type::Function(_) |
+        &Type::Type(_) => true,
+        _ => false
+    };
+
+    Ok(rt.bool(truth))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use ::runtime::traits::{DefaultDictProvider, DefaultTupleProvider, StringProvider,
+                            TupleProvider};
+    use super::*;
+
+    fn setup() -> Runtime {
+        Runtime::new()
+    }
+
+    fn callable(rt: &Runtime, object: ObjectRef) -> ObjectResult {
+        rs_builtin_callable(rt, &rt.tuple(vec![object]), &rt.default_tuple(), &rt.default_dict())
+    }
+
+    #[test]
+    fn functions_and_types() {
+        let rt = setup();
+
+        assert_eq!(callable(&rt, rt.get_builtin("len")).unwrap(), rt.bool(true));
+        assert_eq!(callable(&rt, rt.builtin_class("int").unwrap()).unwrap(), rt.bool(true));
+    }
+
+    #[test]
+    fn values() {
+        let rt = setup();
+
+        assert_eq!(callable(&rt, rt.int(1)).unwrap(), rt.bool(false));
+        assert_eq!(callable(&rt, rt.str("len")).unwrap(), rt.bool(false));
+    }
+}
@@ -0,0 +1,116 @@
+//! `dir()` - builtin function
+//!
+//! List the sorted attribute names of an object that has a `__dict__`. Objects without
+//! one have no attributes that can be listed. Since scopes are not exposed to builtin
+//! functions yet, calling `dir()` with no arguments lists the builtin module like `globals()`.
+//!
+use ::api::method::{GetItem, StringCast};
+use ::api::result::{Error, ObjectResult, RtResult};
+use ::api::RtObject as ObjectRef;
+use ::modules::builtins::Type;
+use ::modules::precondition::{check_args, check_args_range, check_kwargs};
+use ::resources::strings;
+use ::runtime::Runtime;
+use ::runtime::traits::{IntegerProvider, ListProvider, ModuleImporter, StringProvider};
+use ::system::primitives as rs;
+use ::system::primitives::{Func, FuncType, SignatureBuilder};
+
+
+const FUNC_NAME: &'static str = "dir";
+
+pub struct DirFn;
+
+
+impl DirFn {
+    pub fn create() -> rs::Func {
+        trace!("create builtin"; "function" => FUNC_NAME);
+        let callable: Box<rs::WrapperFn> = Box::new(rs_builtin_dir);
+
+        Func {
+            name: String::from(FUNC_NAME),
+            module: String::from(strings::BUILTINS_MODULE),
+            callable: FuncType::Wrapper(callable),
+            signature: ["object"].as_args()
+        }
+    }
+}
+
+
+/// `dir([object])`
+fn rs_builtin_dir(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_builtin" => FUNC_NAME);
+    let arg_count = check_args_range(0..2, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    let object = match arg_count {
+        0 => rt.import_module(strings::BUILTINS_MODULE)?,
+        _ => pos_args.op_getitem(&rt, &rt.int(0))?,
+    };
+
+    let names = attribute_names(&object)?;
+    Ok(rt.list(names.into_iter().map(|name| rt.str(name)).collect()))
+}
+
+
+/// The sorted attribute names of `object`, empty if it has no `__dict__`
+pub fn attribute_names(object: &ObjectRef) -> RtResult<Vec<rs::String>> {
+    let keys = match object.as_ref() {
+        &Type::Object(ref object) |
+        &Type::Module(ref object) => object.dir()?,
+        _ => rs::Tuple::new(),
+    };
+
+    let mut names = keys.iter()
+        .map(|key| key.native_str())
+        .collect::<RtResult<Vec<rs::String>>>()
+        .map_err(|_| Error::typerr("dir(): attribute names must be strings"))?;
+    names.sort();
+
+    Ok(names)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use ::api::method::SetAttr;
+    use ::runtime::traits::{DefaultDictProvider, DefaultTupleProvider, ObjectProvider,
+                            TupleProvider};
+    use super::*;
+
+    fn setup() -> Runtime {
+        Runtime::new()
+    }
+
+    fn dir(rt: &Runtime, args: Vec<ObjectRef>) -> ObjectResult {
+        rs_builtin_dir(rt, &rt.tuple(args), &rt.default_tuple(), &rt.default_dict())
+    }
+
+    #[test]
+    fn object() {
+        let rt = setup();
+        let object = rt.object(rs::None());
+        object.op_setattr(&rt, &rt.str("b"), &rt.int(2)).unwrap();
+        object.op_setattr(&rt, &rt.str("a"), &rt.int(1)).unwrap();
+
+        assert_eq!(dir(&rt, vec![object]).unwrap(), rt.list(vec![rt.str("a"), rt.str("b")]));
+    }
+
+    #[test]
+    fn without_dict() {
+        let rt = setup();
+        assert_eq!(dir(&rt, vec![rt.int(1)]).unwrap(), rt.list(vec![]));
+    }
+
+    #[test]
+    fn builtins() {
+        let rt = setup();
+        let names = dir(&rt, vec![]).unwrap();
+
+        let expected = rt.str(FUNC_NAME);
+        match names.as_ref() {
+            &Type::List(ref list) => assert!(list.value.0.borrow().contains(&expected)),
+            _ => panic!("dir() must return a list")
+        }
+    }
+}
@@ -0,0 +1,96 @@
+//! `divmod()` - builtin function
+//!
+//! Get the floored quotient and the remainder of two numbers via `__divmod__`.
+//!
+use ::api::method::{DivMod, GetItem};
+use ::api::result::{Error, ErrorType, ObjectResult};
+use ::api::RtObject as ObjectRef;
+use ::modules::precondition::{check_args, check_kwargs};
+use ::resources::strings;
+use ::runtime::Runtime;
+use ::runtime::traits::{IntegerProvider};
+use ::system::primitives as rs;
+use ::system::primitives::{Func, FuncType, SignatureBuilder};
+
+
+const FUNC_NAME: &'static str = "divmod";
+
+pub struct DivModFn;
+
+
+impl DivModFn {
+    pub fn create() -> rs::Func {
+        trace!("create builtin"; "function" => FUNC_NAME);
+        let callable: Box<rs::WrapperFn> = Box::new(rs_builtin_divmod);
+
+        Func {
+            name: String::from(FUNC_NAME),
+            module: String::from(strings::BUILTINS_MODULE),
+            callable: FuncType::Wrapper(callable),
+            signature: ["a", "b"].as_args()
+        }
+    }
+}
+
+
+/// `divmod(a, b)`
+fn rs_builtin_divmod(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_builtin" => FUNC_NAME);
+    check_args(2, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    let lhs = pos_args.op_getitem(&rt, &rt.int(0))?;
+    let rhs = pos_args.op_getitem(&rt, &rt.int(1))?;
+
+    match lhs.op_divmod(&rt, &rhs) {
+        Err(Error(ErrorType::System, _)) => Err(Error::typerr(&strings_error_bad_operand!(
+            "divmod()", lhs.debug_name(), rhs.debug_name()))),
+        result => result
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use ::runtime::traits::{DefaultDictProvider, DefaultTupleProvider, FloatProvider,
+                            StringProvider, TupleProvider};
+    use super::*;
+
+    fn setup() -> Runtime {
+        Runtime::new()
+    }
+
+    fn divmod(rt: &Runtime, lhs: ObjectRef, rhs: ObjectRef) -> ObjectResult {
+        rs_builtin_divmod(rt, &rt.tuple(vec![lhs, rhs]), &rt.default_tuple(), &rt.default_dict())
+    }
+
+    #[test]
+    fn ints() {
+        let rt = setup();
+
+        assert_eq!(divmod(&rt, rt.int(-7), rt.int(2)).unwrap(), rt.tuple(vec![rt.int(-4), rt.int(1)]));
+    }
+
+    #[test]
+    fn floats() {
+        let rt = setup();
+
+        assert_eq!(divmod(&rt, rt.float(7.5), rt.int(-2)).unwrap(),
+                   rt.tuple(vec![rt.float(-4.0), rt.float(-0.5)]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn by_zero() {
+        let rt = setup();
+        divmod(&rt, rt.int(1), rt.int(0)).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn not_a_number() {
+        let rt = setup();
+        divmod(&rt, rt.str("a"), rt.int(0)).unwrap();
+    }
+}
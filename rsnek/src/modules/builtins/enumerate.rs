@@ -0,0 +1,82 @@
+//! `enumerate()` - builtin function
+//!
+//! Lazily pairs each element of an iterable with a running count.
+
+use num::Zero;
+
+use ::api::method::{GetItem, Iter};
+use ::api::result::{ObjectResult};
+use ::api::RtObject as ObjectRef;
+use ::modules::builtins::range::as_integer;
+use ::modules::precondition::{check_args, check_kwargs, check_args_range};
+use ::resources::strings;
+use ::runtime::Runtime;
+use ::runtime::traits::{IntegerProvider, IteratorProvider};
+use ::system::primitives as rs;
+use ::system::primitives::{Func, FuncType, SignatureBuilder};
+use ::system::sync::RefCell;
+
+
+pub struct EnumerateFn;
+
+const FUNC_NAME: &'static str = "enumerate";
+
+
+impl EnumerateFn {
+    pub fn create() -> rs::Func {
+        trace!("create builtin"; "function" => FUNC_NAME);
+        let callable: Box<rs::WrapperFn> = Box::new(rs_builtin_enumerate);
+
+        Func {
+            name: String::from(FUNC_NAME),
+            module: String::from(strings::BUILTINS_MODULE),
+            callable: FuncType::Wrapper(callable),
+            signature: ["iterable", "start"].as_args()
+        }
+    }
+}
+
+
+/// `enumerate(iterable[, start])`
+fn rs_builtin_enumerate(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_builtin" => FUNC_NAME);
+
+    let arg_count = check_args_range(1..3, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    let source = pos_args.op_getitem(&rt, &rt.int(0))?.op_iter(&rt)?;
+    let start = match arg_count {
+        2 => as_integer(&pos_args.op_getitem(&rt, &rt.int(1))?)?,
+        _ => rs::Integer::zero()
+    };
+
+    Ok(rt.iter(rs::Iterator::Enumerate {source: source, count: RefCell::new(start)}))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use ::objects::iterator::try_collect;
+    use ::runtime::traits::{DefaultDictProvider, DefaultTupleProvider, StringProvider, TupleProvider};
+    use super::*;
+
+    fn setup() -> Runtime {
+        Runtime::new()
+    }
+
+    #[test]
+    fn with_start() {
+        let rt = setup();
+        let tuple = rt.tuple(vec![rt.str("a"), rt.str("b")]);
+
+        let iter = rs_builtin_enumerate(
+            &rt, &rt.tuple(vec![tuple, rt.int(1)]),
+            &rt.default_tuple(),
+            &rt.default_dict()).unwrap();
+
+        let pairs = try_collect(&iter).unwrap();
+        assert_eq!(pairs, vec![rt.tuple(vec![rt.int(1), rt.str("a")]),
+                               rt.tuple(vec![rt.int(2), rt.str("b")])]);
+    }
+}
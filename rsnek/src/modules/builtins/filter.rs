@@ -0,0 +1,87 @@
+//! `filter()` - builtin function
+//!
+//! Lazily produce the elements of an iterable for which the function returns a
+//! truthy value. When the function is `None` the elements themselves are tested.
+use ::api::method::{GetItem, Iter};
+use ::api::result::{ObjectResult};
+use ::api::RtObject as ObjectRef;
+use ::modules::precondition::{check_args, check_kwargs};
+use ::resources::strings;
+use ::runtime::Runtime;
+use ::runtime::traits::{IntegerProvider, IteratorProvider};
+use ::system::primitives as rs;
+use ::system::primitives::{Func, FuncType, SignatureBuilder};
+
+
+pub struct FilterFn;
+
+const FUNC_NAME: &'static str = "filter";
+
+
+impl FilterFn {
+    pub fn create() -> rs::Func {
+        trace!("create builtin"; "function" => FUNC_NAME);
+        let callable: Box<rs::WrapperFn> = Box::new(rs_builtin_filter);
+
+        Func {
+            name: String::from(FUNC_NAME),
+            module: String::from(strings::BUILTINS_MODULE),
+            callable: FuncType::Wrapper(callable),
+            signature: ["function", "iterable"].as_args()
+        }
+    }
+}
+
+
+/// `filter(function, iterable)`
+fn rs_builtin_filter(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_builtin" => FUNC_NAME);
+
+    check_args(2, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    let func = pos_args.op_getitem(&rt, &rt.int(0))?;
+    let source = pos_args.op_getitem(&rt, &rt.int(1))?.op_iter(&rt)?;
+
+    Ok(rt.iter(rs::Iterator::Filter {func: func, source: source}))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use ::objects::iterator::try_collect;
+    use ::runtime::traits::{BooleanProvider, DefaultDictProvider, DefaultTupleProvider,
+                            NoneProvider, StringProvider, TupleProvider};
+    use super::*;
+
+    fn setup() -> Runtime {
+        Runtime::new()
+    }
+
+    #[test]
+    fn none_func() {
+        let rt = setup();
+        let tuple = rt.tuple(vec![rt.int(0), rt.int(1), rt.str(""), rt.str("a"), rt.bool(false)]);
+
+        let iter = rs_builtin_filter(
+            &rt, &rt.tuple(vec![rt.none(), tuple]),
+            &rt.default_tuple(),
+            &rt.default_dict()).unwrap();
+
+        assert_eq!(try_collect(&iter).unwrap(), vec![rt.int(1), rt.str("a")]);
+    }
+
+    #[test]
+    fn builtin_func() {
+        let rt = setup();
+        let tuple = rt.tuple(vec![rt.str(""), rt.str("ab"), rt.tuple(vec![])]);
+
+        let iter = rs_builtin_filter(
+            &rt, &rt.tuple(vec![rt.get_builtin("len"), tuple]),
+            &rt.default_tuple(),
+            &rt.default_dict()).unwrap();
+
+        assert_eq!(try_collect(&iter).unwrap(), vec![rt.str("ab")]);
+    }
+}
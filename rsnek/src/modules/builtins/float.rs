@@ -0,0 +1,111 @@
+//! `float()` - builtin function
+//!
+//! Convert a number or a string to a float. Like `int()`, this will be replaced by the
+//! type object in the future.
+//!
+use ::api::method::{FloatCast, GetItem};
+use ::api::result::{Error, ObjectResult};
+use ::api::RtObject as ObjectRef;
+use ::modules::builtins::Type;
+use ::modules::precondition::{check_args, check_args_range, check_kwargs};
+use ::objects::number;
+use ::resources::strings;
+use ::runtime::Runtime;
+use ::runtime::traits::{FloatProvider, IntegerProvider};
+use ::system::primitives as rs;
+use ::system::primitives::{Func, FuncType, SignatureBuilder};
+
+
+const FUNC_NAME: &'static str = "float";
+
+pub struct FloatFn;
+
+
+impl FloatFn {
+    pub fn create() -> rs::Func {
+        trace!("create builtin"; "function" => FUNC_NAME);
+        let callable: Box<rs::WrapperFn> = Box::new(rs_builtin_float);
+
+        Func {
+            name: String::from(FUNC_NAME),
+            module: String::from(strings::BUILTINS_MODULE),
+            callable: FuncType::Wrapper(callable),
+            signature: ["x"].as_args()
+        }
+    }
+}
+
+
+/// `float([x])`
+fn rs_builtin_float(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_builtin" => FUNC_NAME);
+    let arg_count = check_args_range(0..2, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    if arg_count == 0 {
+        return Ok(rt.float(0.0));
+    }
+
+    let object = pos_args.op_getitem(&rt, &rt.int(0))?;
+    match object.as_ref() {
+        &Type::Float(_) |
+        &Type::Int(_) |
+        &Type::Bool(_) => object.op_float(&rt),
+        &Type::Str(ref string) => Ok(rt.float(number::parse_float(&string.value.0)?)),
+        other => Err(Error::typerr(&format!(
+            "float() argument must be a string or a number, not '{}'", other.debug_name())))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use ::api::result::ErrorType;
+    use ::runtime::traits::{BooleanProvider, DefaultDictProvider, DefaultTupleProvider,
+                            NoneProvider, StringProvider, TupleProvider};
+    use super::*;
+
+    fn setup() -> Runtime {
+        Runtime::new()
+    }
+
+    fn float(rt: &Runtime, args: Vec<ObjectRef>) -> ObjectResult {
+        rs_builtin_float(rt, &rt.tuple(args), &rt.default_tuple(), &rt.default_dict())
+    }
+
+    #[test]
+    fn numbers() {
+        let rt = setup();
+
+        assert_eq!(float(&rt, vec![]).unwrap(), rt.float(0.0));
+        assert_eq!(float(&rt, vec![rt.int(3)]).unwrap(), rt.float(3.0));
+        assert_eq!(float(&rt, vec![rt.bool(true)]).unwrap(), rt.float(1.0));
+        assert_eq!(float(&rt, vec![rt.float(2.5)]).unwrap(), rt.float(2.5));
+    }
+
+    #[test]
+    fn strings() {
+        let rt = setup();
+
+        assert_eq!(float(&rt, vec![rt.str("  -1_000.5 ")]).unwrap(), rt.float(-1000.5));
+        assert_eq!(float(&rt, vec![rt.str("-Infinity")]).unwrap(), rt.float(::std::f64::NEG_INFINITY));
+
+        match float(&rt, vec![rt.str("nan")]).unwrap().as_ref() {
+            &Type::Float(ref value) => assert!(value.value.0.is_nan()),
+            _ => panic!("float() must return a float")
+        }
+
+        match float(&rt, vec![rt.str("1..0")]) {
+            Err(Error(ErrorType::Value, _)) => {},
+            other => panic!("Expected ValueError, got {:?}", other)
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn not_a_number() {
+        let rt = setup();
+        float(&rt, vec![rt.none()]).unwrap();
+    }
+}
@@ -0,0 +1,74 @@
+//! `hash()` - builtin function
+//!
+//! Get the hash of an object via `__hash__`. Unhashable types raise a `TypeError`.
+//!
+use ::api::method::{GetItem, Hashed};
+use ::api::result::{ObjectResult};
+use ::api::RtObject as ObjectRef;
+use ::modules::precondition::{check_args, check_kwargs};
+use ::resources::strings;
+use ::runtime::Runtime;
+use ::runtime::traits::{IntegerProvider};
+use ::system::primitives as rs;
+use ::system::primitives::{Func, FuncType, SignatureBuilder};
+
+
+const FUNC_NAME: &'static str = "hash";
+
+pub struct HashFn;
+
+
+impl HashFn {
+    pub fn create() -> rs::Func {
+        trace!("create builtin"; "function" => FUNC_NAME);
+        let callable: Box<rs::WrapperFn> = Box::new(rs_builtin_hash);
+
+        Func {
+            name: String::from(FUNC_NAME),
+            module: String::from(strings::BUILTINS_MODULE),
+            callable: FuncType::Wrapper(callable),
+            signature: ["object"].as_args()
+        }
+    }
+}
+
+
+/// `hash(object)`
+fn rs_builtin_hash(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_builtin" => FUNC_NAME);
+    check_args(1, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    let object = pos_args.op_getitem(&rt, &rt.int(0))?;
+    object.op_hash(&rt)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use ::runtime::traits::{DefaultDictProvider, DefaultTupleProvider, ListProvider, TupleProvider};
+    use super::*;
+
+    fn setup() -> Runtime {
+        Runtime::new()
+    }
+
+    fn hash(rt: &Runtime, object: ObjectRef) -> ObjectResult {
+        rs_builtin_hash(rt, &rt.tuple(vec![object]), &rt.default_tuple(), &rt.default_dict())
+    }
+
+    #[test]
+    fn int() {
+        let rt = setup();
+        assert_eq!(hash(&rt, rt.int(42)).unwrap(), rt.int(42));
+        assert_eq!(hash(&rt, rt.int(-1)).unwrap(), rt.int(-2));
+    }
+
+    #[test]
+    #[should_panic]
+    fn unhashable() {
+        let rt = setup();
+        hash(&rt, rt.list(vec![])).unwrap();
+    }
+}
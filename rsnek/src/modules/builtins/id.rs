@@ -0,0 +1,76 @@
+//! `id()` - builtin function
+//!
+//! Get the identity of an object which is unique for the lifetime of the object. Like
+//! CPython this is the memory address of the object.
+//!
+use ::api::method::{GetItem, Id};
+use ::api::result::{ObjectResult};
+use ::api::RtObject as ObjectRef;
+use ::modules::precondition::{check_args, check_kwargs};
+use ::resources::strings;
+use ::runtime::Runtime;
+use ::runtime::traits::{IntegerProvider};
+use ::system::primitives as rs;
+use ::system::primitives::{Func, FuncType, SignatureBuilder};
+
+
+const FUNC_NAME: &'static str = "id";
+
+pub struct IdFn;
+
+
+impl IdFn {
+    pub fn create() -> rs::Func {
+        trace!("create builtin"; "function" => FUNC_NAME);
+        let callable: Box<rs::WrapperFn> = Box::new(rs_builtin_id);
+
+        Func {
+            name: String::from(FUNC_NAME),
+            module: String::from(strings::BUILTINS_MODULE),
+            callable: FuncType::Wrapper(callable),
+            signature: ["object"].as_args()
+        }
+    }
+}
+
+
+/// `id(object)`
+fn rs_builtin_id(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_builtin" => FUNC_NAME);
+    check_args(1, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    let object = pos_args.op_getitem(&rt, &rt.int(0))?;
+    object.op_id(&rt)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use ::runtime::traits::{DefaultDictProvider, DefaultTupleProvider, StringProvider, TupleProvider};
+    use super::*;
+
+    fn setup() -> Runtime {
+        Runtime::new()
+    }
+
+    fn id(rt: &Runtime, object: ObjectRef) -> ObjectResult {
+        rs_builtin_id(rt, &rt.tuple(vec![object]), &rt.default_tuple(), &rt.default_dict())
+    }
+
+    #[test]
+    fn same_object() {
+        let rt = setup();
+        let string = rt.str("snek");
+
+        assert_eq!(id(&rt, string.clone()).unwrap(), id(&rt, string.clone()).unwrap());
+        assert_eq!(id(&rt, string.clone()).unwrap(), rt.int(string.id()));
+    }
+
+    #[test]
+    fn different_objects() {
+        let rt = setup();
+        assert!(id(&rt, rt.str("snek")).unwrap() != id(&rt, rt.str("snek")).unwrap());
+    }
+}
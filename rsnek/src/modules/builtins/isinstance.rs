@@ -0,0 +1,193 @@
+//! `isinstance()` and `issubclass()` - builtin functions
+//!
+//! Check an object or class against a class, or a tuple of classes, by walking the bases
+//! of the type objects. Since the builtin types are still created by plain functions,
+//! the builtin functions which share the name of a type (`int`, `str`, etc.) are accepted
+//! in place of the type object itself.
+//!
+use ::api::method::GetItem;
+use ::api::result::{Error, ObjectResult, RtResult};
+use ::api::RtObject as ObjectRef;
+use ::modules::builtins::Type;
+use ::modules::precondition::{check_args, check_kwargs};
+use ::resources::strings;
+use ::runtime::Runtime;
+use ::runtime::traits::{BooleanProvider, IntegerProvider, PyTypeProvider};
+use ::system::primitives as rs;
+use ::system::primitives::{Func, FuncType, SignatureBuilder};
+
+
+pub struct IsInstanceFn;
+pub struct IsSubclassFn;
+
+
+impl IsInstanceFn {
+    pub fn create() -> rs::Func {
+        trace!("create builtin"; "function" => "isinstance");
+        let callable: Box<rs::WrapperFn> = Box::new(rs_builtin_isinstance);
+
+        Func {
+            name: String::from("isinstance"),
+            module: String::from(strings::BUILTINS_MODULE),
+            callable: FuncType::Wrapper(callable),
+            signature: ["object", "classinfo"].as_args()
+        }
+    }
+}
+
+
+impl IsSubclassFn {
+    pub fn create() -> rs::Func {
+        trace!("create builtin"; "function" => "issubclass");
+        let callable: Box<rs::WrapperFn> = Box::new(rs_builtin_issubclass);
+
+        Func {
+            name: String::from("issubclass"),
+            module: String::from(strings::BUILTINS_MODULE),
+            callable: FuncType::Wrapper(callable),
+            signature: ["class", "classinfo"].as_args()
+        }
+    }
+}
+
+
+/// `isinstance(object, classinfo)`
+fn rs_builtin_isinstance(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_builtin" => "isinstance");
+    check_args(2, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    let object = pos_args.op_getitem(&rt, &rt.int(0))?;
+    let classinfo = pos_args.op_getitem(&rt, &rt.int(1))?;
+
+    let class = rt.pytype(object.as_ref());
+    let truth = is_subclass(rt, &class, &classinfo, "isinstance")?;
+    Ok(rt.bool(truth))
+}
+
+
+/// `issubclass(class, classinfo)`
+fn rs_builtin_issubclass(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_builtin" => "issubclass");
+    check_args(2, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    let class = pos_args.op_getitem(&rt, &rt.int(0))?;
+    let classinfo = pos_args.op_getitem(&rt, &rt.int(1))?;
+
+    let class = match as_class(rt, &class) {
+        Some(class) => class,
+        None => return Err(Error::typerr("issubclass() arg 1 must be a class")),
+    };
+
+    let truth = is_subclass(rt, &class, &classinfo, "issubclass")?;
+    Ok(rt.bool(truth))
+}
+
+
+/// Resolve `object` to a type object if it is one or is the builtin function that
+/// stands in for one.
+pub fn as_class(rt: &Runtime, object: &ObjectRef) -> Option<ObjectRef> {
+    match object.as_ref() {
+        &Type::Type(_) => Some(object.clone()),
+        &Type::Function(ref func) if func.value.0.module == strings::BUILTINS_MODULE => {
+            rt.builtin_class(&func.value.0.name)
+        },
+        _ => None
+    }
+}
+
+
+/// Test `class` against `classinfo` which may be a class or an arbitrarily nested
+/// tuple of classes.
+fn is_subclass(rt: &Runtime, class: &ObjectRef, classinfo: &ObjectRef, fname: &str) -> RtResult<bool> {
+    if let &Type::Tuple(ref tuple) = classinfo.as_ref() {
+        for info in tuple.value.0.iter() {
+            if is_subclass(rt, class, info, fname)? {
+                return Ok(true);
+            }
+        }
+        return Ok(false);
+    }
+
+    let expected = match as_class(rt, classinfo) {
+        Some(expected) => expected,
+        None => return Err(Error::typerr(&format!(
+            "{}() arg 2 must be a type or tuple of types", fname))),
+    };
+
+    match (class.as_ref(), expected.as_ref()) {
+        (&Type::Type(ref class), &Type::Type(ref expected)) => Ok(class.is_subtype(expected)),
+        _ => Err(Error::system(
+            &format!("Resolved class was not a type; file: {}, line: {}", file!(), line!())))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use ::runtime::traits::{DefaultDictProvider, DefaultTupleProvider, StringProvider,
+                            TupleProvider};
+    use super::*;
+
+    fn setup() -> Runtime {
+        Runtime::new()
+    }
+
+    fn isinstance(rt: &Runtime, object: ObjectRef, classinfo: ObjectRef) -> ObjectResult {
+        rs_builtin_isinstance(
+            rt, &rt.tuple(vec![object, classinfo]), &rt.default_tuple(), &rt.default_dict())
+    }
+
+    fn issubclass(rt: &Runtime, class: ObjectRef, classinfo: ObjectRef) -> ObjectResult {
+        rs_builtin_issubclass(
+            rt, &rt.tuple(vec![class, classinfo]), &rt.default_tuple(), &rt.default_dict())
+    }
+
+    #[test]
+    fn isinstance_builtin_function() {
+        let rt = setup();
+
+        assert_eq!(isinstance(&rt, rt.int(1), rt.get_builtin("int")).unwrap(), rt.bool(true));
+        assert_eq!(isinstance(&rt, rt.bool(true), rt.get_builtin("int")).unwrap(), rt.bool(true));
+        assert_eq!(isinstance(&rt, rt.int(1), rt.get_builtin("str")).unwrap(), rt.bool(false));
+    }
+
+    #[test]
+    fn isinstance_tuple() {
+        let rt = setup();
+        let classinfo = rt.tuple(vec![
+            rt.get_builtin("str"),
+            rt.tuple(vec![rt.get_builtin("list"), rt.builtin_class("object").unwrap()])]);
+
+        assert_eq!(isinstance(&rt, rt.int(1), classinfo).unwrap(), rt.bool(true));
+
+        let classinfo = rt.tuple(vec![rt.get_builtin("str"), rt.get_builtin("bytes")]);
+        assert_eq!(isinstance(&rt, rt.int(1), classinfo).unwrap(), rt.bool(false));
+    }
+
+    #[test]
+    #[should_panic]
+    fn isinstance_bad_classinfo() {
+        let rt = setup();
+        isinstance(&rt, rt.int(1), rt.str("int")).unwrap();
+    }
+
+    #[test]
+    fn issubclass_() {
+        let rt = setup();
+        let boolean = rt.builtin_class("bool").unwrap();
+
+        assert_eq!(issubclass(&rt, boolean.clone(), rt.get_builtin("int")).unwrap(), rt.bool(true));
+        assert_eq!(issubclass(&rt, rt.get_builtin("int"), boolean).unwrap(), rt.bool(false));
+    }
+
+    #[test]
+    #[should_panic]
+    fn issubclass_not_a_class() {
+        let rt = setup();
+        issubclass(&rt, rt.int(1), rt.get_builtin("int")).unwrap();
+    }
+}
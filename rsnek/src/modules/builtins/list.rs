@@ -8,6 +8,7 @@ use ::api::result::{ObjectResult};
 use ::api::RtObject;
 use ::modules::builtins::Type;
 use ::modules::precondition::{check_args, check_kwargs, check_args_range};
+use ::objects::iterator::try_collect;
 use ::resources::strings;
 use ::runtime::Runtime;
 use ::runtime::traits::{ListProvider, DefaultListProvider, IntegerProvider};
@@ -45,7 +46,7 @@ fn rs_builtin_list(rt: &Runtime, pos_args: &RtObject, starargs: &RtObject, kwarg
     }
 
     let value = pos_args.op_getitem(&rt, &rt.int(0))?;
-    let new_list  = value.op_iter(&rt)?.collect::<rs::List>();
+    let new_list  = try_collect(&value.op_iter(&rt)?)?;
     Ok(rt.list(new_list))
 }
 
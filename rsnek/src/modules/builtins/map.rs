@@ -0,0 +1,87 @@
+//! `map()` - builtin function
+//!
+//! Lazily call a function with the n-th element of each iterable as its arguments.
+use ::api::method::{Iter};
+use ::api::result::{Error, ObjectResult, RtResult};
+use ::api::RtObject as ObjectRef;
+use ::modules::precondition::{check_args, check_kwargs};
+use ::objects::iterator::try_collect;
+use ::resources::strings;
+use ::runtime::Runtime;
+use ::runtime::traits::{IteratorProvider};
+use ::system::primitives as rs;
+use ::system::primitives::{Func, FuncType};
+
+
+pub struct MapFn;
+
+const FUNC_NAME: &'static str = "map";
+
+
+impl MapFn {
+    pub fn create() -> rs::Func {
+        trace!("create builtin"; "function" => FUNC_NAME);
+        let callable: Box<rs::WrapperFn> = Box::new(rs_builtin_map);
+
+        Func {
+            name: String::from(FUNC_NAME),
+            module: String::from(strings::BUILTINS_MODULE),
+            callable: FuncType::Wrapper(callable),
+            signature: rs::Signature::new(&["func"], &[], Some("iterables"), None)
+        }
+    }
+}
+
+
+/// `map(func, *iterables)`
+fn rs_builtin_map(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_builtin" => FUNC_NAME);
+
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    let args = try_collect(&pos_args.op_iter(&rt)?)?;
+    if args.len() < 2 {
+        return Err(Error::typerr("map() must have at least two arguments."));
+    }
+
+    let func = args[0].clone();
+    let sources = args[1..].iter()
+        .map(|iterable| iterable.op_iter(&rt))
+        .collect::<RtResult<Vec<ObjectRef>>>()?;
+
+    Ok(rt.iter(rs::Iterator::Map {func: func, sources: sources}))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use ::runtime::traits::{DefaultDictProvider, DefaultTupleProvider, IntegerProvider,
+                            StringProvider, TupleProvider};
+    use super::*;
+
+    fn setup() -> Runtime {
+        Runtime::new()
+    }
+
+    #[test]
+    fn builtin_func() {
+        let rt = setup();
+        let ints = rt.tuple(vec![rt.int(1), rt.int(22)]);
+
+        let iter = rs_builtin_map(
+            &rt, &rt.tuple(vec![rt.get_builtin("str"), ints]),
+            &rt.default_tuple(),
+            &rt.default_dict()).unwrap();
+
+        assert_eq!(try_collect(&iter).unwrap(), vec![rt.str("1"), rt.str("22")]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn no_iterables() {
+        let rt = setup();
+        rs_builtin_map(&rt, &rt.tuple(vec![rt.get_builtin("str")]),
+                       &rt.default_tuple(), &rt.default_dict()).unwrap();
+    }
+}
@@ -0,0 +1,171 @@
+//! `min()` and `max()` - builtin functions
+//!
+//! Return the smallest or largest element of an iterable or of the positional
+//! arguments, using the `<` (`__lt__`) of the elements or of `key(element)`.
+use std::cmp::Ordering;
+
+use num::ToPrimitive;
+
+use ::api::method::{GetItem, Iter, Length, Next};
+use ::api::result::{Error, ErrorType, ObjectResult, RtResult};
+use ::api::RtObject as ObjectRef;
+use ::modules::builtins::sorted::{apply_key, compare};
+use ::modules::precondition::{check_args, check_kwargs_names};
+use ::resources::strings;
+use ::runtime::Runtime;
+use ::runtime::traits::{IntegerProvider};
+use ::system::primitives as rs;
+use ::system::primitives::{Func, FuncType};
+
+
+pub struct MinFn;
+pub struct MaxFn;
+
+
+impl MinFn {
+    pub fn create() -> rs::Func {
+        trace!("create builtin"; "function" => "min");
+        let callable: Box<rs::WrapperFn> = Box::new(rs_builtin_min);
+
+        Func {
+            name: String::from("min"),
+            module: String::from(strings::BUILTINS_MODULE),
+            callable: FuncType::Wrapper(callable),
+            signature: rs::Signature::new(&[], &["key", "default"], Some("args"), None)
+        }
+    }
+}
+
+
+impl MaxFn {
+    pub fn create() -> rs::Func {
+        trace!("create builtin"; "function" => "max");
+        let callable: Box<rs::WrapperFn> = Box::new(rs_builtin_max);
+
+        Func {
+            name: String::from("max"),
+            module: String::from(strings::BUILTINS_MODULE),
+            callable: FuncType::Wrapper(callable),
+            signature: rs::Signature::new(&[], &["key", "default"], Some("args"), None)
+        }
+    }
+}
+
+
+/// `min(iterable, *[, key, default])` or `min(arg1, arg2, *args[, key])`
+fn rs_builtin_min(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_builtin" => "min");
+    extremum(rt, "min", Ordering::Less, pos_args, starargs, kwargs)
+}
+
+
+/// `max(iterable, *[, key, default])` or `max(arg1, arg2, *args[, key])`
+fn rs_builtin_max(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_builtin" => "max");
+    extremum(rt, "max", Ordering::Greater, pos_args, starargs, kwargs)
+}
+
+
+/// Find the first element whose key compares as `wanted` against every other
+/// element's key. Like CPython, the first of several equal elements wins.
+fn extremum(rt: &Runtime, name: &str, wanted: Ordering,
+            pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+
+    check_args(0, &starargs)?;
+    let keywords = check_kwargs_names(&["key", "default"], &kwargs)?;
+    let (key, default) = (keywords[0].as_ref(), keywords[1].clone());
+
+    let iterator = match pos_args.native_len()?.to_usize() {
+        Some(0) => return Err(Error::typerr(
+            &format!("{} expected at least 1 argument, got 0", name))),
+        Some(1) => pos_args.op_getitem(&rt, &rt.int(0))?.op_iter(&rt)?,
+        _ if default.is_some() => return Err(Error::typerr(&format!(
+            "Cannot specify a default for {}() with multiple positional arguments", name))),
+        _ => pos_args.op_iter(&rt)?,
+    };
+
+    let mut best: Option<(ObjectRef, ObjectRef)> = None;
+
+    loop {
+        let item = match iterator.native_next() {
+            Ok(item) => item,
+            Err(Error(ErrorType::StopIteration, _)) => break,
+            Err(err) => return Err(err)
+        };
+
+        let item_key = apply_key(&rt, key, &item)?;
+        let replace = match best {
+            Some((ref best_key, _)) => compare(&item_key, best_key)? == wanted,
+            None => true
+        };
+
+        if replace {
+            best = Some((item_key, item));
+        }
+    }
+
+    match (best, default) {
+        (Some((_, item)), _) => Ok(item),
+        (None, Some(default)) => Ok(default),
+        (None, None) => Err(Error::value(&format!("{}() arg is an empty sequence", name)))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use ::api::method::SetItem;
+    use ::runtime::traits::{DefaultDictProvider, DefaultTupleProvider, StringProvider, TupleProvider};
+    use super::*;
+
+    fn setup() -> Runtime {
+        Runtime::new()
+    }
+
+    #[test]
+    fn min_iterable() {
+        let rt = setup();
+        let tuple = rt.tuple(vec![rt.int(3), rt.int(1), rt.int(2)]);
+
+        let result = rs_builtin_min(
+            &rt, &rt.tuple(vec![tuple]),
+            &rt.default_tuple(),
+            &rt.default_dict()).unwrap();
+
+        assert_eq!(result, rt.int(1));
+    }
+
+    #[test]
+    fn max_args() {
+        let rt = setup();
+
+        let result = rs_builtin_max(
+            &rt, &rt.tuple(vec![rt.str("a"), rt.str("c"), rt.str("b")]),
+            &rt.default_tuple(),
+            &rt.default_dict()).unwrap();
+
+        assert_eq!(result, rt.str("c"));
+    }
+
+    #[test]
+    fn default() {
+        let rt = setup();
+        let kwargs = rt.default_dict();
+        kwargs.op_setitem(&rt, &rt.str("default"), &rt.int(42)).unwrap();
+
+        let result = rs_builtin_max(
+            &rt, &rt.tuple(vec![rt.default_tuple()]),
+            &rt.default_tuple(),
+            &kwargs).unwrap();
+
+        assert_eq!(result, rt.int(42));
+    }
+
+    #[test]
+    #[should_panic]
+    fn empty() {
+        let rt = setup();
+        rs_builtin_min(&rt, &rt.tuple(vec![rt.default_tuple()]),
+                       &rt.default_tuple(), &rt.default_dict()).unwrap();
+    }
+}
@@ -7,24 +7,74 @@ mod typefn;
 mod str;
 mod int;
 mod any;
+mod bytearray;
+mod bytes;
 mod all;
 mod and;
 mod or;
 mod list;
 mod globals;
+mod slice;
 mod tuple;
 mod types;
+mod range;
+mod enumerate;
+mod zip;
+mod map;
+mod filter;
+mod reversed;
+mod sorted;
+mod minmax;
+mod sum;
+mod isinstance;
+mod attr;
+mod id;
+mod hash;
+mod repr;
+mod callable;
+mod dir;
+mod vars;
+mod float;
+mod abs;
+mod round;
+mod divmod;
+mod breakpoint;
 
+pub use self::abs::AbsFn;
 pub use self::all::{AllFn, iterator_all};
 pub use self::and::logical_and;
 pub use self::any::{AnyFn, iterator_any};
+pub use self::attr::{DelAttrFn, GetAttrFn, HasAttrFn, SetAttrFn};
+pub use self::breakpoint::BreakpointFn;
+pub use self::bytearray::ByteArrayFn;
+pub use self::bytes::BytesFn;
+pub use self::callable::CallableFn;
+pub use self::dir::{DirFn, attribute_names};
+pub use self::divmod::DivModFn;
+pub use self::enumerate::EnumerateFn;
+pub use self::filter::FilterFn;
+pub use self::float::FloatFn;
 pub use self::globals::GlobalsFn;
+pub use self::hash::HashFn;
+pub use self::id::IdFn;
 pub use self::int::IntFn;
+pub use self::isinstance::{IsInstanceFn, IsSubclassFn};
 pub use self::len::LenFn;
 pub use self::list::ListFn;
+pub use self::map::MapFn;
+pub use self::minmax::{MaxFn, MinFn};
 pub use self::or::logical_or;
 pub use self::print::PrintFn;
+pub use self::range::RangeFn;
+pub use self::repr::{ReprFn, repr};
+pub use self::reversed::ReversedFn;
+pub use self::round::RoundFn;
+pub use self::slice::SliceFn;
+pub use self::sorted::SortedFn;
 pub use self::str::StrFn;
+pub use self::sum::SumFn;
 pub use self::tuple::TupleFn;
 pub use self::typefn::TypeFn;
-pub use self::types::Type;
\ No newline at end of file
+pub use self::types::Type;
+pub use self::vars::VarsFn;
+pub use self::zip::ZipFn;
\ No newline at end of file
@@ -1,17 +1,23 @@
 //! `print()` - builtin function
 //!
 //! For printing characters onto stdout.
-use std::borrow::Borrow;
-
-use itertools::Itertools;
-
-use ::api::method::StringCast;
-use ::api::result::{ObjectResult};
+//!
+//! ```ignore
+//! print(*objects, sep=' ', end='\n', file=sys.stdout, flush=False)
+//! ```
+//!
+//! The objects are converted with `str()`, joined by `sep`, followed by `end` and
+//! written with a single call to `file.write`. `sys.stdout` is looked up on each
+//! call so replacing it redirects `print`.
+use ::api::method::{BooleanCast, GetAttr, StringCast};
+use ::api::result::{Error, ObjectResult, RtResult};
 use ::api::RtObject as ObjectRef;
 use ::modules::builtins::Type;
+use ::modules::io;
+use ::modules::precondition::{check_args, check_kwargs_names};
 use ::resources::strings;
 use ::runtime::Runtime;
-use ::runtime::traits::{IteratorProvider, NoneProvider};
+use ::runtime::traits::{ModuleImporter, NoneProvider};
 use ::system::primitives as rs;
 use ::system::primitives::{Signature, Func, FuncType};
 
@@ -35,33 +41,135 @@ impl PrintFn {
 }
 
 
-#[allow(unused_variables)]
+/// The value of the `sep` or `end` keyword, `default` when it is missing or `None`
+fn text_keyword(keyword: &Option<ObjectRef>, name: &str, default: &str) -> RtResult<String> {
+    match *keyword {
+        None => Ok(String::from(default)),
+        Some(ref object) => match object.as_ref() {
+            &Type::None(_) => Ok(String::from(default)),
+            &Type::Str(ref string) => Ok(string.value.0.clone()),
+            other => Err(Error::typerr(&format!(
+                "{} must be None or a string, not {}", name, other.debug_name())))
+        }
+    }
+}
+
+
 fn rs_builtin_print(rt: &Runtime, pos_args: &ObjectRef,
                     starargs: &ObjectRef,
                     kwargs: &ObjectRef) -> ObjectResult {
     trace!("call"; "native_builtin" => "print");
+    check_args(0, &starargs)?;
+    let keywords = check_kwargs_names(&["sep", "end", "file", "flush"], &kwargs)?;
+
+    let sep = text_keyword(&keywords[0], "sep", " ")?;
+    let end = text_keyword(&keywords[1], "end", "\n")?;
 
-    let mut strings: Vec<String> = Vec::new();
-    let tuple_iterator = match rs::Iterator::new(pos_args){
-        Ok(iterator) => rt.iter(iterator),
-        Err(_) => unreachable!(),
+    let file = match keywords[2] {
+        Some(ref file) => match file.as_ref() {
+            &Type::None(_) => None,
+            _ => Some(file.clone())
+        },
+        None => None
     };
 
-     let output = &tuple_iterator.map(|object| {
-            match object.native_str() {
-                 Ok(string) => string,
-                Err(err) => {
-                    warn!("Error during call"; "native_builtin" => "str");
-                    format!("{}", object)
-                }
-            }
-        })
-         .map(|s| format!(">>> {}",s ))
-         .join("\n");
-
-    // TODO: {T71} Wrap this in the "canblock" macro when implemented
-    info!("rs_builtin_print";
-        "output" => format!("\n{}\n", output));
+    let file = match file {
+        Some(file) => file,
+        None => rt.import_module(strings::SYS_MODULE)?.op_getattr(&rt, &rt.intern("stdout"))?
+    };
+
+    let objects = match pos_args.as_ref() {
+        &Type::Tuple(ref tuple) => tuple.value.0.iter()
+            .map(|object| object.native_str())
+            .collect::<RtResult<Vec<String>>>()?,
+        _ => return Err(Error::typerr("Expected type tuple for pos_args"))
+    };
+
+    let mut text = objects.join(&sep);
+    text.push_str(&end);
+    io::write(rt, &file, &text)?;
+
+    let flush = match keywords[3] {
+        Some(ref flush) => flush.native_bool()?,
+        None => false
+    };
+
+    if flush {
+        io::flush(rt, &file)?;
+    }
 
     Ok(rt.none())
 }
+
+
+#[cfg(test)]
+mod tests {
+    use ::api::method::{Call, SetAttr, SetItem};
+    use ::api::result::ErrorType;
+    use ::runtime::traits::{BooleanProvider, DefaultDictProvider, DefaultTupleProvider,
+                            IntegerProvider, StringProvider, TupleProvider};
+    use super::*;
+
+    fn setup() -> (Runtime, ObjectRef) {
+        let rt = Runtime::new();
+        let buffer = io::text_stream(&rt, io::Sink::Buffer(String::new())).unwrap();
+        (rt, buffer)
+    }
+
+    fn getvalue(rt: &Runtime, buffer: &ObjectRef) -> ObjectRef {
+        let getvalue = buffer.op_getattr(&rt, &rt.str("getvalue")).unwrap();
+        getvalue.op_call(&rt, &rt.default_tuple(), &rt.default_tuple(), &rt.default_dict()).unwrap()
+    }
+
+    #[test]
+    fn sep_end_file() {
+        let (rt, buffer) = setup();
+        let kwargs = rt.default_dict();
+        kwargs.op_setitem(&rt, &rt.str("sep"), &rt.str(", ")).unwrap();
+        kwargs.op_setitem(&rt, &rt.str("end"), &rt.str("!")).unwrap();
+        kwargs.op_setitem(&rt, &rt.str("file"), &buffer).unwrap();
+        kwargs.op_setitem(&rt, &rt.str("flush"), &rt.bool(true)).unwrap();
+
+        let args = rt.tuple(vec![rt.str("a"), rt.int(1), rt.bool(false)]);
+        rs_builtin_print(&rt, &args, &rt.default_tuple(), &kwargs).unwrap();
+
+        assert_eq!(getvalue(&rt, &buffer), rt.str("a, 1, False!"));
+    }
+
+    #[test]
+    fn defaults() {
+        let (rt, buffer) = setup();
+        let kwargs = rt.default_dict();
+        kwargs.op_setitem(&rt, &rt.str("file"), &buffer).unwrap();
+        kwargs.op_setitem(&rt, &rt.str("sep"), &rt.none()).unwrap();
+
+        rs_builtin_print(&rt, &rt.tuple(vec![rt.str("a"), rt.str("b")]), &rt.default_tuple(), &kwargs).unwrap();
+        rs_builtin_print(&rt, &rt.default_tuple(), &rt.default_tuple(), &kwargs).unwrap();
+
+        assert_eq!(getvalue(&rt, &buffer), rt.str("a b\n\n"));
+    }
+
+    #[test]
+    fn replaced_sys_stdout() {
+        let (rt, buffer) = setup();
+        let sys = rt.import_module(strings::SYS_MODULE).unwrap();
+        sys.op_setattr(&rt, &rt.str("stdout"), &buffer).unwrap();
+
+        rs_builtin_print(&rt, &rt.tuple(vec![rt.int(42)]), &rt.default_tuple(), &rt.default_dict()).unwrap();
+        assert_eq!(getvalue(&rt, &buffer), rt.str("42\n"));
+    }
+
+    #[test]
+    fn sep_not_a_str() {
+        let (rt, buffer) = setup();
+        let kwargs = rt.default_dict();
+        kwargs.op_setitem(&rt, &rt.str("file"), &buffer).unwrap();
+        kwargs.op_setitem(&rt, &rt.str("sep"), &rt.int(1)).unwrap();
+
+        let result = rs_builtin_print(&rt, &rt.default_tuple(), &rt.default_tuple(), &kwargs);
+        match result {
+            Err(Error(ErrorType::Type, ref message)) => assert_eq!(message, "sep must be None or a string, not int"),
+            ref other => panic!("expected TypeError, got {:?}", other)
+        }
+    }
+}
@@ -0,0 +1,119 @@
+//! `range()` - builtin function
+//!
+//! In the future this will be replaced by a type object.
+use num::Zero;
+
+use ::api::method::{GetItem};
+use ::api::result::{Error, ObjectResult, RtResult};
+use ::api::RtObject as ObjectRef;
+use ::modules::builtins::Type;
+use ::modules::precondition::{check_args, check_kwargs, check_args_range};
+use ::resources::strings;
+use ::runtime::Runtime;
+use ::runtime::traits::{IntegerProvider, RangeProvider};
+use ::system::primitives as rs;
+use ::system::primitives::{Func, FuncType, SignatureBuilder};
+
+
+pub struct RangeFn;
+
+const FUNC_NAME: &'static str = "range";
+
+
+impl RangeFn {
+    pub fn create() -> rs::Func {
+        trace!("create builtin"; "function" => FUNC_NAME);
+        let callable: Box<rs::WrapperFn> = Box::new(rs_builtin_range);
+
+        Func {
+            name: String::from(FUNC_NAME),
+            module: String::from(strings::BUILTINS_MODULE),
+            callable: FuncType::Wrapper(callable),
+            signature: ["start", "stop", "step"].as_args()
+        }
+    }
+}
+
+
+/// `range(stop)` or `range(start, stop[, step])`
+fn rs_builtin_range(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_builtin" => FUNC_NAME);
+
+    let arg_count = check_args_range(1..4, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    let arg = |idx: usize| -> RtResult<rs::Integer> {
+        as_integer(&pos_args.op_getitem(&rt, &rt.int(idx))?)
+    };
+
+    let value = match arg_count {
+        1 => rs::Range {start: rs::Integer::zero(), stop: arg(0)?, step: rs::Integer::from(1)},
+        2 => rs::Range {start: arg(0)?, stop: arg(1)?, step: rs::Integer::from(1)},
+        _ => rs::Range {start: arg(0)?, stop: arg(1)?, step: arg(2)?},
+    };
+
+    if value.step.is_zero() {
+        return Err(Error::value("range() arg 3 must not be zero"));
+    }
+
+    Ok(rt.range(value))
+}
+
+
+/// Extract the value of an int (or bool) argument
+pub fn as_integer(object: &ObjectRef) -> RtResult<rs::Integer> {
+    match object.as_ref() {
+        &Type::Int(ref int) => Ok(int.value.0.clone()),
+        &Type::Bool(ref boolean) => Ok(boolean.value.0.clone()),
+        other => Err(Error::typerr(&format!(
+            "'{}' object cannot be interpreted as an integer", other.debug_name())))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use ::api::method::{Iter, Length};
+    use ::modules::call_function;
+    use ::objects::iterator::try_collect;
+    use ::runtime::traits::FloatProvider;
+    use super::*;
+
+    fn setup() -> Runtime {
+        Runtime::new()
+    }
+
+    #[test]
+    fn stop_only() {
+        let rt = setup();
+        let range = call_function(&rt, &rt.get_builtin("range"), vec![rt.int(3)]).unwrap();
+
+        assert_eq!(range.native_len().unwrap(), rs::Integer::from(3));
+        let values = try_collect(&range.op_iter(&rt).unwrap()).unwrap();
+        assert_eq!(values, vec![rt.int(0), rt.int(1), rt.int(2)]);
+    }
+
+    #[test]
+    fn negative_step() {
+        let rt = setup();
+        let range = call_function(&rt, &rt.get_builtin("range"), vec![rt.int(3), rt.int(-3), rt.int(-2)]).unwrap();
+
+        let values = try_collect(&range.op_iter(&rt).unwrap()).unwrap();
+        assert_eq!(values, vec![rt.int(3), rt.int(1), rt.int(-1)]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_step() {
+        let rt = setup();
+        call_function(&rt, &rt.get_builtin("range"), vec![rt.int(0), rt.int(3), rt.int(0)]).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn float_arg() {
+        let rt = setup();
+        call_function(&rt, &rt.get_builtin("range"), vec![rt.float(3.0)]).unwrap();
+    }
+}
@@ -0,0 +1,91 @@
+//! `repr()` - builtin function
+//!
+//! Get the printable representation of an object via `__repr__`. Types which do not
+//! have a `__repr__` of their own yet fall back to `__str__`.
+//!
+use ::api::method::{GetItem, StringCast, StringRepresentation};
+use ::api::result::{Error, ErrorType, ObjectResult};
+use ::api::RtObject as ObjectRef;
+use ::modules::precondition::{check_args, check_kwargs};
+use ::resources::strings;
+use ::runtime::Runtime;
+use ::runtime::traits::{IntegerProvider};
+use ::system::primitives as rs;
+use ::system::primitives::{Func, FuncType, SignatureBuilder};
+
+
+const FUNC_NAME: &'static str = "repr";
+
+pub struct ReprFn;
+
+
+impl ReprFn {
+    pub fn create() -> rs::Func {
+        trace!("create builtin"; "function" => FUNC_NAME);
+        let callable: Box<rs::WrapperFn> = Box::new(rs_builtin_repr);
+
+        Func {
+            name: String::from(FUNC_NAME),
+            module: String::from(strings::BUILTINS_MODULE),
+            callable: FuncType::Wrapper(callable),
+            signature: ["object"].as_args()
+        }
+    }
+}
+
+
+/// `repr(object)`
+fn rs_builtin_repr(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_builtin" => FUNC_NAME);
+    check_args(1, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    let object = pos_args.op_getitem(&rt, &rt.int(0))?;
+    repr(rt, &object)
+}
+
+
+/// The `str` returned by `repr(object)`
+pub fn repr(rt: &Runtime, object: &ObjectRef) -> ObjectResult {
+    match object.op_repr(&rt) {
+        Err(Error(ErrorType::System, _)) => object.op_str(&rt),
+        result => result
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use ::runtime::traits::{DefaultDictProvider, DefaultTupleProvider, StringProvider, TupleProvider};
+    use super::*;
+
+    fn setup() -> Runtime {
+        Runtime::new()
+    }
+
+    fn repr(rt: &Runtime, object: ObjectRef) -> ObjectResult {
+        rs_builtin_repr(rt, &rt.tuple(vec![object]), &rt.default_tuple(), &rt.default_dict())
+    }
+
+    #[test]
+    fn string() {
+        let rt = setup();
+        assert_eq!(repr(&rt, rt.str("snek")).unwrap(), rt.str("'snek'"));
+        assert_eq!(repr(&rt, rt.str("it's")).unwrap(), rt.str("\"it's\""));
+        assert_eq!(repr(&rt, rt.str("a\nb\\")).unwrap(), rt.str("'a\\nb\\\\'"));
+    }
+
+    #[test]
+    fn int() {
+        let rt = setup();
+        assert_eq!(repr(&rt, rt.int(42)).unwrap(), rt.str("42"));
+    }
+
+    #[test]
+    fn type_object() {
+        let rt = setup();
+        let int = rt.builtin_class("int").unwrap();
+        assert_eq!(repr(&rt, int).unwrap(), rt.str("<class 'int'>"));
+    }
+}
@@ -0,0 +1,97 @@
+//! `reversed()` - builtin function
+//!
+//! Produce an iterator over a sequence from back to front using the
+//! `__reversed__` method of the sequence.
+use ::api::method::{GetItem, Reversed};
+use ::api::result::{Error, ErrorType, ObjectResult};
+use ::api::RtObject as ObjectRef;
+use ::modules::precondition::{check_args, check_kwargs};
+use ::resources::strings;
+use ::runtime::Runtime;
+use ::runtime::traits::{IntegerProvider};
+use ::system::primitives as rs;
+use ::system::primitives::{Func, FuncType, SignatureBuilder};
+
+
+pub struct ReversedFn;
+
+const FUNC_NAME: &'static str = "reversed";
+
+
+impl ReversedFn {
+    pub fn create() -> rs::Func {
+        trace!("create builtin"; "function" => FUNC_NAME);
+        let callable: Box<rs::WrapperFn> = Box::new(rs_builtin_reversed);
+
+        Func {
+            name: String::from(FUNC_NAME),
+            module: String::from(strings::BUILTINS_MODULE),
+            callable: FuncType::Wrapper(callable),
+            signature: ["sequence"].as_args()
+        }
+    }
+}
+
+
+/// `reversed(sequence)`
+fn rs_builtin_reversed(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_builtin" => FUNC_NAME);
+
+    check_args(1, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    let sequence = pos_args.op_getitem(&rt, &rt.int(0))?;
+
+    match sequence.op_reversed(&rt) {
+        Err(Error(ErrorType::System, _)) => Err(Error::typerr(&format!(
+            "'{}' object is not reversible", sequence.debug_name()))),
+        result => result
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use ::objects::iterator::try_collect;
+    use ::runtime::traits::{DefaultDictProvider, DefaultTupleProvider, ListProvider,
+                            StringProvider, TupleProvider};
+    use super::*;
+
+    fn setup() -> Runtime {
+        Runtime::new()
+    }
+
+    #[test]
+    fn list() {
+        let rt = setup();
+        let list = rt.list(vec![rt.int(1), rt.int(2), rt.int(3)]);
+
+        let iter = rs_builtin_reversed(
+            &rt, &rt.tuple(vec![list]),
+            &rt.default_tuple(),
+            &rt.default_dict()).unwrap();
+
+        assert_eq!(try_collect(&iter).unwrap(), vec![rt.int(3), rt.int(2), rt.int(1)]);
+    }
+
+    #[test]
+    fn string() {
+        let rt = setup();
+
+        let iter = rs_builtin_reversed(
+            &rt, &rt.tuple(vec![rt.str("abc")]),
+            &rt.default_tuple(),
+            &rt.default_dict()).unwrap();
+
+        assert_eq!(try_collect(&iter).unwrap(), vec![rt.str("c"), rt.str("b"), rt.str("a")]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn int() {
+        let rt = setup();
+        rs_builtin_reversed(&rt, &rt.tuple(vec![rt.int(1)]),
+                            &rt.default_tuple(), &rt.default_dict()).unwrap();
+    }
+}
@@ -0,0 +1,134 @@
+//! `round()` - builtin function
+//!
+//! Round a number to a given precision in decimal digits with ties going to the even
+//! digit. Without `ndigits` the result is an int via `__round__`, otherwise the result
+//! has the same type as the number.
+//!
+use num::{Signed, ToPrimitive};
+
+use ::api::method::{GetItem, Rounding};
+use ::api::result::{Error, ErrorType, ObjectResult};
+use ::api::RtObject as ObjectRef;
+use ::modules::builtins::Type;
+use ::modules::precondition::{check_args, check_args_range, check_kwargs};
+use ::objects::number;
+use ::resources::strings;
+use ::runtime::Runtime;
+use ::runtime::traits::{FloatProvider, IntegerProvider};
+use ::system::primitives as rs;
+use ::system::primitives::{Func, FuncType, SignatureBuilder};
+
+
+const FUNC_NAME: &'static str = "round";
+
+pub struct RoundFn;
+
+
+impl RoundFn {
+    pub fn create() -> rs::Func {
+        trace!("create builtin"; "function" => FUNC_NAME);
+        let callable: Box<rs::WrapperFn> = Box::new(rs_builtin_round);
+
+        Func {
+            name: String::from(FUNC_NAME),
+            module: String::from(strings::BUILTINS_MODULE),
+            callable: FuncType::Wrapper(callable),
+            signature: ["number", "ndigits"].as_args()
+        }
+    }
+}
+
+
+/// `round(number[, ndigits])`
+fn rs_builtin_round(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_builtin" => FUNC_NAME);
+    let arg_count = check_args_range(1..3, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    let object = pos_args.op_getitem(&rt, &rt.int(0))?;
+
+    let ndigits = match arg_count {
+        2 => pos_args.op_getitem(&rt, &rt.int(1))?,
+        _ => return round_to_int(rt, &object),
+    };
+
+    let ndigits = match ndigits.as_ref() {
+        &Type::None(_) => return round_to_int(rt, &object),
+        &Type::Int(ref int) => clamp_ndigits(&int.value.0),
+        &Type::Bool(ref boolean) => clamp_ndigits(&boolean.value.0),
+        other => return Err(Error::typerr(&format!(
+            "'{}' object cannot be interpreted as an integer", other.debug_name())))
+    };
+
+    match object.as_ref() {
+        &Type::Float(ref float) => Ok(rt.float(number::float_round(float.value.0, ndigits)?)),
+        &Type::Int(ref int) => Ok(rt.int(number::int_round(&int.value.0, ndigits))),
+        &Type::Bool(ref boolean) => Ok(rt.int(number::int_round(&boolean.value.0, ndigits))),
+        other => Err(type_doesnt_define_round(other))
+    }
+}
+
+
+fn round_to_int(rt: &Runtime, object: &ObjectRef) -> ObjectResult {
+    match object.op_round(&rt) {
+        Err(Error(ErrorType::System, _)) => Err(type_doesnt_define_round(object.as_ref())),
+        result => result
+    }
+}
+
+
+/// Digits past the range of a float are all the same to `float_round` and `int_round`
+fn clamp_ndigits(ndigits: &rs::Integer) -> i64 {
+    match ndigits.to_i64() {
+        Some(ndigits) => ndigits,
+        None if ndigits.is_negative() => ::std::i64::MIN,
+        None => ::std::i64::MAX,
+    }
+}
+
+
+fn type_doesnt_define_round(object: &Type) -> Error {
+    Error::typerr(&format!("type {} doesn't define __round__ method", object.debug_name()))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use ::runtime::traits::{DefaultDictProvider, DefaultTupleProvider, NoneProvider,
+                            StringProvider, TupleProvider};
+    use super::*;
+
+    fn setup() -> Runtime {
+        Runtime::new()
+    }
+
+    fn round(rt: &Runtime, args: Vec<ObjectRef>) -> ObjectResult {
+        rs_builtin_round(rt, &rt.tuple(args), &rt.default_tuple(), &rt.default_dict())
+    }
+
+    #[test]
+    fn to_int() {
+        let rt = setup();
+
+        assert_eq!(round(&rt, vec![rt.float(2.5)]).unwrap(), rt.int(2));
+        assert_eq!(round(&rt, vec![rt.float(3.5), rt.none()]).unwrap(), rt.int(4));
+        assert_eq!(round(&rt, vec![rt.int(7)]).unwrap(), rt.int(7));
+    }
+
+    #[test]
+    fn ndigits() {
+        let rt = setup();
+
+        assert_eq!(round(&rt, vec![rt.float(2.675), rt.int(2)]).unwrap(), rt.float(2.67));
+        assert_eq!(round(&rt, vec![rt.float(1234.5), rt.int(-2)]).unwrap(), rt.float(1200.0));
+        assert_eq!(round(&rt, vec![rt.int(1250), rt.int(-2)]).unwrap(), rt.int(1200));
+    }
+
+    #[test]
+    #[should_panic]
+    fn not_a_number() {
+        let rt = setup();
+        round(&rt, vec![rt.str("1.5")]).unwrap();
+    }
+}
@@ -0,0 +1,52 @@
+//! `slice()` - builtin function
+//!
+//! In the future this will be replaced by a type object.
+use ::api::method::{GetItem};
+use ::api::result::{ObjectResult};
+use ::api::RtObject as ObjectRef;
+use ::modules::precondition::{check_args, check_kwargs, check_args_range};
+use ::resources::strings;
+use ::runtime::Runtime;
+use ::runtime::traits::{IntegerProvider, NoneProvider, SliceProvider};
+use ::system::primitives as rs;
+use ::system::primitives::{Func, FuncType, SignatureBuilder};
+
+
+pub struct SliceFn;
+
+const FUNC_NAME: &'static str = "slice";
+
+
+impl SliceFn {
+    pub fn create() -> rs::Func {
+        trace!("create builtin"; "function" => FUNC_NAME);
+        let callable: Box<rs::WrapperFn> = Box::new(rs_builtin_slice);
+
+        Func {
+            name: String::from(FUNC_NAME),
+            module: String::from(strings::BUILTINS_MODULE),
+            callable: FuncType::Wrapper(callable),
+            signature: ["start", "stop", "step"].as_args()
+        }
+    }
+}
+
+
+/// `slice(stop)` or `slice(start, stop[, step])`
+fn rs_builtin_slice(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_builtin" => FUNC_NAME);
+
+    let arg_count = check_args_range(1..4, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    let arg = |idx: usize| pos_args.op_getitem(&rt, &rt.int(idx));
+
+    let value = match arg_count {
+        1 => rs::Slice {start: rt.none(), stop: arg(0)?, step: rt.none()},
+        2 => rs::Slice {start: arg(0)?, stop: arg(1)?, step: rt.none()},
+        _ => rs::Slice {start: arg(0)?, stop: arg(1)?, step: arg(2)?},
+    };
+
+    Ok(rt.slice(value))
+}
@@ -0,0 +1,207 @@
+//! `sorted()` - builtin function
+//!
+//! Return a new list of the elements of an iterable in ascending order. The sort
+//! is stable and uses the `<` (`__lt__`) of the elements, or of `key(element)`
+//! when a key function is given.
+use std::cmp::Ordering;
+
+use ::api::method::{BooleanCast, Call, GetItem, Iter, LessThan};
+use ::api::result::{ObjectResult, RtResult};
+use ::api::RtObject as ObjectRef;
+use ::modules::builtins::Type;
+use ::modules::precondition::{check_args, check_kwargs_names};
+use ::objects::iterator::try_collect;
+use ::resources::strings;
+use ::runtime::Runtime;
+use ::runtime::traits::{DefaultDictProvider, DefaultTupleProvider, IntegerProvider,
+                        ListProvider, TupleProvider};
+use ::system::primitives as rs;
+use ::system::primitives::{Func, FuncType};
+
+
+pub struct SortedFn;
+
+const FUNC_NAME: &'static str = "sorted";
+
+
+impl SortedFn {
+    pub fn create() -> rs::Func {
+        trace!("create builtin"; "function" => FUNC_NAME);
+        let callable: Box<rs::WrapperFn> = Box::new(rs_builtin_sorted);
+
+        Func {
+            name: String::from(FUNC_NAME),
+            module: String::from(strings::BUILTINS_MODULE),
+            callable: FuncType::Wrapper(callable),
+            signature: rs::Signature::new(&["iterable"], &["key", "reverse"], None, None)
+        }
+    }
+}
+
+
+/// `sorted(iterable, *, key=None, reverse=False)`
+fn rs_builtin_sorted(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_builtin" => FUNC_NAME);
+
+    check_args(1, &pos_args)?;
+    check_args(0, &starargs)?;
+    let keywords = check_kwargs_names(&["key", "reverse"], &kwargs)?;
+
+    let iterable = pos_args.op_getitem(&rt, &rt.int(0))?;
+    let items = try_collect(&iterable.op_iter(&rt)?)?;
+
+    let reverse = match keywords[1] {
+        Some(ref flag) => flag.native_bool()?,
+        None => false
+    };
+
+    let sorted = sort_objects(&rt, items, keywords[0].as_ref(), reverse)?;
+    Ok(rt.list(sorted))
+}
+
+
+/// Call `key(item)` unless the key function is missing or `None`
+pub fn apply_key(rt: &Runtime, key: Option<&ObjectRef>, item: &ObjectRef) -> ObjectResult {
+    match key {
+        Some(func) => match func.as_ref() {
+            &Type::None(_) => Ok(item.clone()),
+            _ => func.op_call(&rt, &rt.tuple(vec![item.clone()]), &rt.default_tuple(), &rt.default_dict())
+        },
+        None => Ok(item.clone())
+    }
+}
+
+
+/// Order two objects by `<`, which is all python requires of sortable objects
+pub fn compare(lhs: &ObjectRef, rhs: &ObjectRef) -> RtResult<Ordering> {
+    if lhs.native_lt(rhs.as_ref())? {
+        Ok(Ordering::Less)
+    } else if rhs.native_lt(lhs.as_ref())? {
+        Ok(Ordering::Greater)
+    } else {
+        Ok(Ordering::Equal)
+    }
+}
+
+
+/// Stable sort of `items` that stops at the first comparison error and returns it,
+/// like CPython does.
+pub fn sort_objects(rt: &Runtime, items: rs::List, key: Option<&ObjectRef>, reverse: bool) -> RtResult<rs::List> {
+    let keys = items.iter()
+        .map(|item| apply_key(&rt, key, item))
+        .collect::<RtResult<rs::List>>()?;
+
+    let pairs = keys.into_iter().zip(items.into_iter()).collect::<Vec<_>>();
+
+    // Swapping the operands instead of reversing the result keeps equal elements
+    // in their original order, the same as CPython.
+    let sorted = merge_sort(pairs, &|a: &(ObjectRef, ObjectRef), b: &(ObjectRef, ObjectRef)| match reverse {
+        false => a.0.native_lt(b.0.as_ref()),
+        true => b.0.native_lt(a.0.as_ref()),
+    })?;
+
+    Ok(sorted.into_iter().map(|(_, item)| item).collect())
+}
+
+
+/// Stable merge sort of `items` by `less`. Unlike `sort_by`, it can give up at the
+/// first error instead of carrying on with a comparator that is no longer a total
+/// order, which `sort_by` is allowed to panic on.
+fn merge_sort<T, F>(mut items: Vec<T>, less: &F) -> RtResult<Vec<T>>
+    where F: Fn(&T, &T) -> RtResult<bool> {
+
+    if items.len() < 2 {
+        return Ok(items)
+    }
+
+    let right = items.split_off(items.len() / 2);
+    let mut left = merge_sort(items, less)?.into_iter().peekable();
+    let mut right = merge_sort(right, less)?.into_iter().peekable();
+    let mut merged = Vec::with_capacity(left.len() + right.len());
+
+    loop {
+        // Only strictly smaller elements of the right half go first
+        let from_right = match (left.peek(), right.peek()) {
+            (Some(lhs), Some(rhs)) => less(rhs, lhs)?,
+            (Some(_), None) => false,
+            (None, Some(_)) => true,
+            (None, None) => return Ok(merged)
+        };
+
+        match from_right {
+            true => merged.extend(right.next()),
+            false => merged.extend(left.next()),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use ::api::method::SetItem;
+    use ::api::result::{Error, ErrorType};
+    use ::runtime::traits::{StringProvider, BooleanProvider};
+    use super::*;
+
+    fn setup() -> Runtime {
+        Runtime::new()
+    }
+
+    #[test]
+    fn ints() {
+        let rt = setup();
+        let tuple = rt.tuple(vec![rt.int(3), rt.int(1), rt.int(2)]);
+
+        let list = rs_builtin_sorted(
+            &rt, &rt.tuple(vec![tuple]),
+            &rt.default_tuple(),
+            &rt.default_dict()).unwrap();
+
+        assert_eq!(list, rt.list(vec![rt.int(1), rt.int(2), rt.int(3)]));
+    }
+
+    #[test]
+    fn reverse_is_stable() {
+        let rt = setup();
+        let items = vec![rt.str("bb"), rt.str("a"), rt.str("cc")];
+        let len = rt.get_builtin("len");
+
+        let sorted = sort_objects(&rt, items, Some(&len), true).unwrap();
+        assert_eq!(sorted, vec![rt.str("bb"), rt.str("cc"), rt.str("a")]);
+    }
+
+    #[test]
+    fn unorderable() {
+        let rt = setup();
+
+        match sort_objects(&rt, vec![rt.int(1), rt.str("a")], None, false) {
+            Err(Error(ErrorType::Type, message)) => assert!(message.contains("'<'"), "{}", message),
+            other => panic!("expected TypeError, got {:?}", other)
+        }
+
+        // The error stops the sort instead of being taken for an equal pair
+        let items = vec![rt.int(3), rt.int(1), rt.str("a"), rt.int(2)];
+        assert!(sort_objects(&rt, items, None, true).is_err());
+    }
+
+    #[test]
+    fn stable_merge() {
+        let rt = setup();
+        let items = (0..20).map(|idx| rt.tuple(vec![rt.int(idx % 3), rt.int(idx)])).collect::<rs::List>();
+        let len = rt.get_builtin("len");
+
+        // Every key is 2, the tuples keep their order
+        let sorted = sort_objects(&rt, items.clone(), Some(&len), false).unwrap();
+        assert_eq!(sorted, items);
+    }
+
+    #[test]
+    #[should_panic]
+    fn bad_keyword() {
+        let rt = setup();
+        let kwargs = rt.default_dict();
+        kwargs.op_setitem(&rt, &rt.str("cmp"), &rt.bool(true)).unwrap();
+
+        rs_builtin_sorted(&rt, &rt.tuple(vec![rt.default_tuple()]), &rt.default_tuple(), &kwargs).unwrap();
+    }
+}
@@ -0,0 +1,115 @@
+//! `sum()` - builtin function
+//!
+//! Add the elements of an iterable to a start value, left to right.
+use ::api::method::{Add, GetItem, Iter, Next};
+use ::api::result::{Error, ErrorType, ObjectResult};
+use ::api::RtObject as ObjectRef;
+use ::modules::builtins::Type;
+use ::modules::precondition::{check_args, check_kwargs, check_args_range};
+use ::resources::strings;
+use ::runtime::Runtime;
+use ::runtime::traits::{IntegerProvider};
+use ::system::primitives as rs;
+use ::system::primitives::{Func, FuncType, SignatureBuilder};
+
+
+pub struct SumFn;
+
+const FUNC_NAME: &'static str = "sum";
+
+
+impl SumFn {
+    pub fn create() -> rs::Func {
+        trace!("create builtin"; "function" => FUNC_NAME);
+        let callable: Box<rs::WrapperFn> = Box::new(rs_builtin_sum);
+
+        Func {
+            name: String::from(FUNC_NAME),
+            module: String::from(strings::BUILTINS_MODULE),
+            callable: FuncType::Wrapper(callable),
+            signature: ["iterable", "start"].as_args()
+        }
+    }
+}
+
+
+/// `sum(iterable[, start])`
+fn rs_builtin_sum(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_builtin" => FUNC_NAME);
+
+    let arg_count = check_args_range(1..3, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    let iterator = pos_args.op_getitem(&rt, &rt.int(0))?.op_iter(&rt)?;
+    let mut total = match arg_count {
+        2 => pos_args.op_getitem(&rt, &rt.int(1))?,
+        _ => rt.int(0)
+    };
+
+    match total.as_ref() {
+        &Type::Str(_) => return Err(Error::typerr(
+            "sum() can't sum strings [use ''.join(seq) instead]")),
+        &Type::Bytes(_) => return Err(Error::typerr(
+            "sum() can't sum bytes [use b''.join(seq) instead]")),
+        &Type::ByteArray(_) => return Err(Error::typerr(
+            "sum() can't sum bytearray [use b''.join(seq) instead]")),
+        _ => {}
+    };
+
+    loop {
+        match iterator.native_next() {
+            Ok(item) => total = total.op_add(&rt, &item)?,
+            Err(Error(ErrorType::StopIteration, _)) => return Ok(total),
+            Err(err) => return Err(err)
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use ::runtime::traits::{DefaultDictProvider, DefaultTupleProvider, FloatProvider,
+                            StringProvider, TupleProvider};
+    use super::*;
+
+    fn setup() -> Runtime {
+        Runtime::new()
+    }
+
+    #[test]
+    fn ints() {
+        let rt = setup();
+        let tuple = rt.tuple(vec![rt.int(1), rt.int(2), rt.int(3)]);
+
+        let result = rs_builtin_sum(
+            &rt, &rt.tuple(vec![tuple]),
+            &rt.default_tuple(),
+            &rt.default_dict()).unwrap();
+
+        assert_eq!(result, rt.int(6));
+    }
+
+    #[test]
+    fn start() {
+        let rt = setup();
+        let tuple = rt.tuple(vec![rt.int(1), rt.float(0.5)]);
+
+        let result = rs_builtin_sum(
+            &rt, &rt.tuple(vec![tuple, rt.int(10)]),
+            &rt.default_tuple(),
+            &rt.default_dict()).unwrap();
+
+        assert_eq!(result, rt.float(11.5));
+    }
+
+    #[test]
+    #[should_panic]
+    fn strings() {
+        let rt = setup();
+        let tuple = rt.tuple(vec![rt.str("a")]);
+
+        rs_builtin_sum(&rt, &rt.tuple(vec![tuple, rt.str("")]),
+                       &rt.default_tuple(), &rt.default_dict()).unwrap();
+    }
+}
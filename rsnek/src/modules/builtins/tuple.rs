@@ -8,6 +8,7 @@ use ::api::result::ObjectResult;
 use ::api::RtObject as ObjectRef;
 use ::modules::builtins::Type;
 use ::modules::precondition::{check_args, check_kwargs, check_args_range};
+use ::objects::iterator::try_collect;
 use ::resources::strings;
 use ::runtime::Runtime;
 use ::runtime::traits::{IntegerProvider, TupleProvider, DefaultTupleProvider};
@@ -47,7 +48,7 @@ fn rs_builtin_tuple(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kw
     }
 
     let value = pos_args.op_getitem(&rt, &rt.int(0))?;
-    let new_tuple = value.op_iter(&rt)?.collect::<rs::List>();
+    let new_tuple = try_collect(&value.op_iter(&rt)?)?;
     Ok(rt.tuple(new_tuple))
 }
 
@@ -1,5 +1,7 @@
 //! `type()` - builtin function
 //!
+//! Get the type object of an object.
+//!
 use std::borrow::Borrow;
 
 use ::api::method::{GetItem};
@@ -9,7 +11,7 @@ use ::modules::builtins::Type;
 use ::modules::precondition::{check_args, check_kwargs};
 use ::resources::strings;
 use ::runtime::Runtime;
-use ::runtime::traits::{IntegerProvider, StringProvider};
+use ::runtime::traits::{IntegerProvider, PyTypeProvider};
 use ::system::primitives as rs;
 use ::system::primitives::{Signature, Func, FuncType};
 
@@ -40,9 +42,5 @@ fn rs_builtin_typefn(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, k
     check_kwargs(0, &kwargs)?;
 
     let value = pos_args.op_getitem(&rt, &rt.int(0))?;
-
-
-    // Hack for demo purposes since there are not type and class objects yet
-    // TODO: {T49} return the type when type objects are a thing
-    Ok(rt.str(value.as_ref().debug_name()))
+    Ok(rt.pytype(value.as_ref()))
 }
@@ -16,6 +16,7 @@ use ::api::RtObject;
 use ::api::selfref::SelfRef;
 use ::api::WeakRtObject as WeakRtObject;
 use ::objects::boolean::PyBoolean;
+use ::objects::bytearray::PyByteArray;
 use ::objects::bytes::PyBytes;
 use ::objects::code::PyCode;
 use ::objects::complex::PyComplex;
@@ -31,6 +32,8 @@ use ::objects::none::PyNone;
 use ::objects::object::PyObject;
 use ::objects::pytype::PyType;
 use ::objects::set::PySet;
+use ::objects::range::PyRange;
+use ::objects::slice::PySlice;
 use ::objects::string::PyString;
 use ::objects::tuple::PyTuple;
 use ::runtime::Runtime;
@@ -50,6 +53,7 @@ pub enum Type {
     Complex(PyComplex),
     Str(PyString),
     Bytes(PyBytes),
+    ByteArray(PyByteArray),
     Dict(PyDict),
     Tuple(PyTuple),
     List(PyList),
@@ -60,6 +64,8 @@ pub enum Type {
     Frame(PyFrame),
     Set(PySet),
     FrozenSet(PyFrozenSet),
+    Slice(PySlice),
+    Range(PyRange),
 
     // Utility Types
     DictKey(rs::DictKey),
@@ -81,6 +87,7 @@ impl Type {
             Type::Complex(_) => "complex",
             Type::Str(_) => "str",
             Type::Bytes(_) => "bytes",
+            Type::ByteArray(_) => "bytearray",
             Type::Dict(_) => "dict",
             Type::Tuple(_) => "tuple",
             Type::List(_) => "list",
@@ -91,6 +98,8 @@ impl Type {
             Type::Frame(_) => "frame",
             Type::Set(_) => "set",
             Type::FrozenSet(_) => "frozenset",
+            Type::Slice(_) => "slice",
+            Type::Range(_) => "range",
             Type::DictKey(_) => "dictkey",
         }
     }
@@ -121,19 +130,6 @@ impl fmt::Display for Type {
     }
 }
 
-impl Iterator for Type {
-    type Item = RtObject;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        match self {
-            &mut Type::Iter(ref mut iterator) => {
-                iterator.next()
-            }
-            _ => None
-        }
-    }
-}
-
 impl Hash for Type {
     fn hash<H: Hasher>(&self, state: &mut H) where H: Hasher{
         self.native_hash().unwrap().hash(state);
@@ -204,6 +200,16 @@ impl method::SetAttr for Type {
     }
 }
 
+impl method::DelAttr for Type {
+    fn op_delattr(&self, rt: &Runtime, name: &RtObject) -> ObjectResult {
+        foreach_type!(self, rt, op_delattr, lhs, name)
+    }
+
+    fn native_delattr(&self, name: &Type) -> RtResult<rs::None> {
+        native_foreach_type!(self, native_delattr, lhs, name)
+    }
+}
+
 impl method::Id for Type {
     fn op_id(&self, rt: &Runtime) -> ObjectResult {
         Ok(rt.int(self.native_id()))
@@ -702,6 +708,15 @@ impl method::Iter for Type {
         native_foreach_type!(self, native_iter, lhs)
     }
 }
+impl method::Reversed for Type {
+    fn op_reversed(&self, rt: &Runtime) -> ObjectResult {
+        foreach_type!(self, rt, op_reversed, lhs)
+    }
+
+    fn native_reversed(&self) -> RtResult<rs::Iterator> {
+        native_foreach_type!(self, native_reversed, lhs)
+    }
+}
 impl method::Call for Type {
     fn op_call(&self, rt: &Runtime, pos_args: &RtObject, starargs: &RtObject, kwargs: &RtObject) -> ObjectResult {
         foreach_type!(self, rt, op_call, method, pos_args, starargs, kwargs)
@@ -846,12 +861,12 @@ impl method::Keys for Type {
 
 
 method_not_implemented!(Type,
-    Await   Clear   Close   DelAttr   Delete   
+    Await   Clear   Close   Delete   
     DescriptorGet   DescriptorSet   DescriptorSetName   Discard   Enter   
     Exit   Get   GetAttribute   Init   Items   
     LengthHint   New   PopItem   ReflectedAdd   ReflectedBitwiseAnd   
     ReflectedBitwiseOr   ReflectedDivMod   ReflectedFloorDivision   ReflectedLeftShift
     ReflectedMatrixMultiply  ReflectedModulus   ReflectedMultiply   ReflectedPow
-    ReflectedRightShift   ReflectedSubtract  ReflectedTrueDivision   ReflectedXOr   Reversed
+    ReflectedRightShift   ReflectedSubtract  ReflectedTrueDivision   ReflectedXOr
     Rounding   Send   SetDefault   Throw   Update   Values
 );
@@ -0,0 +1,97 @@
+//! `vars()` - builtin function
+//!
+//! Get the `__dict__` of an object. Like `dir()`, calling `vars()` with no arguments uses
+//! the builtin module until scopes are exposed to builtin functions.
+//!
+use ::api::method::GetItem;
+use ::api::result::{Error, ObjectResult};
+use ::api::RtObject as ObjectRef;
+use ::modules::builtins::Type;
+use ::modules::precondition::{check_args, check_args_range, check_kwargs};
+use ::resources::strings;
+use ::runtime::Runtime;
+use ::runtime::traits::{IntegerProvider, ModuleImporter};
+use ::system::primitives as rs;
+use ::system::primitives::{Func, FuncType, SignatureBuilder};
+
+
+const FUNC_NAME: &'static str = "vars";
+
+pub struct VarsFn;
+
+
+impl VarsFn {
+    pub fn create() -> rs::Func {
+        trace!("create builtin"; "function" => FUNC_NAME);
+        let callable: Box<rs::WrapperFn> = Box::new(rs_builtin_vars);
+
+        Func {
+            name: String::from(FUNC_NAME),
+            module: String::from(strings::BUILTINS_MODULE),
+            callable: FuncType::Wrapper(callable),
+            signature: ["object"].as_args()
+        }
+    }
+}
+
+
+/// `vars([object])`
+fn rs_builtin_vars(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_builtin" => FUNC_NAME);
+    let arg_count = check_args_range(0..2, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    let object = match arg_count {
+        0 => rt.import_module(strings::BUILTINS_MODULE)?,
+        _ => pos_args.op_getitem(&rt, &rt.int(0))?,
+    };
+
+    match object.as_ref() {
+        &Type::Object(ref object) |
+        &Type::Module(ref object) => Ok(object.dict()),
+        _ => Err(Error::typerr("vars() argument must have __dict__ attribute"))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use ::api::method::SetAttr;
+    use ::runtime::traits::{DefaultDictProvider, DefaultTupleProvider, ObjectProvider,
+                            StringProvider, TupleProvider};
+    use super::*;
+
+    fn setup() -> Runtime {
+        Runtime::new()
+    }
+
+    fn vars(rt: &Runtime, args: Vec<ObjectRef>) -> ObjectResult {
+        rs_builtin_vars(rt, &rt.tuple(args), &rt.default_tuple(), &rt.default_dict())
+    }
+
+    #[test]
+    fn object() {
+        let rt = setup();
+        let object = rt.object(rs::None());
+        object.op_setattr(&rt, &rt.str("answer"), &rt.int(42)).unwrap();
+
+        let dict = vars(&rt, vec![object]).unwrap();
+        assert_eq!(dict.op_getitem(&rt, &rt.str("answer")).unwrap(), rt.int(42));
+    }
+
+    #[test]
+    fn builtins() {
+        let rt = setup();
+
+        let dict = vars(&rt, vec![]).unwrap();
+        assert_eq!(dict.op_getitem(&rt, &rt.str(FUNC_NAME)).unwrap(), rt.get_builtin(FUNC_NAME));
+    }
+
+    #[test]
+    #[should_panic]
+    fn without_dict() {
+        let rt = setup();
+        vars(&rt, vec![rt.int(1)]).unwrap();
+    }
+}
@@ -0,0 +1,84 @@
+//! `zip()` - builtin function
+//!
+//! Lazily produce tuples of the n-th element of each iterable, stopping
+//! when the shortest iterable is exhausted.
+use ::api::method::{Iter};
+use ::api::result::{ObjectResult, RtResult};
+use ::api::RtObject as ObjectRef;
+use ::modules::precondition::{check_args, check_kwargs};
+use ::objects::iterator::try_collect;
+use ::resources::strings;
+use ::runtime::Runtime;
+use ::runtime::traits::{IteratorProvider};
+use ::system::primitives as rs;
+use ::system::primitives::{Func, FuncType};
+
+
+pub struct ZipFn;
+
+const FUNC_NAME: &'static str = "zip";
+
+
+impl ZipFn {
+    pub fn create() -> rs::Func {
+        trace!("create builtin"; "function" => FUNC_NAME);
+        let callable: Box<rs::WrapperFn> = Box::new(rs_builtin_zip);
+
+        Func {
+            name: String::from(FUNC_NAME),
+            module: String::from(strings::BUILTINS_MODULE),
+            callable: FuncType::Wrapper(callable),
+            signature: rs::Signature::new(&[], &[], Some("iterables"), None)
+        }
+    }
+}
+
+
+/// `zip(*iterables)`
+fn rs_builtin_zip(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_builtin" => FUNC_NAME);
+
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    let sources = try_collect(&pos_args.op_iter(&rt)?)?.iter()
+        .map(|iterable| iterable.op_iter(&rt))
+        .collect::<RtResult<Vec<ObjectRef>>>()?;
+
+    Ok(rt.iter(rs::Iterator::Zip {sources: sources}))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use ::runtime::traits::{DefaultDictProvider, DefaultTupleProvider, IntegerProvider,
+                            StringProvider, TupleProvider};
+    use super::*;
+
+    fn setup() -> Runtime {
+        Runtime::new()
+    }
+
+    #[test]
+    fn shortest() {
+        let rt = setup();
+        let ints = rt.tuple(vec![rt.int(1), rt.int(2), rt.int(3)]);
+        let strs = rt.str("ab");
+
+        let iter = rs_builtin_zip(
+            &rt, &rt.tuple(vec![ints, strs]),
+            &rt.default_tuple(),
+            &rt.default_dict()).unwrap();
+
+        let pairs = try_collect(&iter).unwrap();
+        assert_eq!(pairs, vec![rt.tuple(vec![rt.int(1), rt.str("a")]),
+                               rt.tuple(vec![rt.int(2), rt.str("b")])]);
+    }
+
+    #[test]
+    fn empty() {
+        let rt = setup();
+        let iter = rs_builtin_zip(&rt, &rt.default_tuple(), &rt.default_tuple(), &rt.default_dict()).unwrap();
+        assert!(try_collect(&iter).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,144 @@
+//! `dis` - Disassembler for python functions
+//!
+//! ```ignore
+//! def add_one(a):
+//!     return a + 1
+//!
+//! disassemble = dis.dis
+//! disassemble(add_one)
+//! ```
+//!
+//! The listing is written to `sys.stdout` and is the same one that
+//! `rsnek --dump=bytecode` prints, see `compiler::fmt::code`.
+//!
+use ::api::method::{GetAttr, GetItem, SetAttr};
+use ::api::result::{Error, ObjectResult};
+use ::api::RtObject as ObjectRef;
+use ::compiler::fmt;
+use ::modules::builtins::Type;
+use ::modules::io;
+use ::modules::precondition::{check_args, check_kwargs};
+use ::modules::register;
+use ::resources::strings;
+use ::runtime::Runtime;
+use ::runtime::traits::{FunctionProvider, IntegerProvider, ModuleImporter, ModuleProvider,
+                        NoneProvider};
+use ::system::primitives as rs;
+use ::system::primitives::{FuncType, SignatureBuilder};
+
+
+/// Create the `dis` module object with all of its functions
+pub fn create_module(rt: &Runtime) -> ObjectResult {
+    let module = rt.module(rs::None());
+
+    let funcs = vec![
+        DisFn::create(),
+    ];
+
+    register(rt, &module, funcs)?;
+
+    Ok(module)
+}
+
+
+/// Listing of the code of a function or code object
+pub fn disassemble(object: &ObjectRef) -> Result<String, Error> {
+    match object.as_ref() {
+        &Type::Function(ref func) => match func.value.0.callable {
+            FuncType::Code(ref code) => Ok(fmt::code(code)),
+            _ => Err(Error::typerr(&format!(
+                "don't know how to disassemble native function {}", func.value.0.name)))
+        },
+        &Type::Code(ref code) => Ok(fmt::code(&code.value.0)),
+        other => Err(Error::typerr(&format!(
+            "don't know how to disassemble {} objects", other.debug_name())))
+    }
+}
+
+
+pub struct DisFn;
+
+
+impl DisFn {
+    pub fn create() -> rs::Func {
+        module_func!(strings::DIS_MODULE, "dis", rs_dis_dis, ["x"])
+    }
+}
+
+
+/// `dis.dis(x)` - write the listing of the function or code object `x` to `sys.stdout`
+fn rs_dis_dis(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_module" => strings::DIS_MODULE, "function" => "dis");
+    check_args(1, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    let listing = disassemble(&pos_args.op_getitem(&rt, &rt.int(0))?)?;
+
+    let stdout = rt.import_module(strings::SYS_MODULE)?.op_getattr(&rt, &rt.intern("stdout"))?;
+    io::write(rt, &stdout, &listing)?;
+    Ok(rt.none())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use ::api::method::Call;
+    use ::compiler::Compiler;
+    use ::runtime::traits::{CodeProvider, DefaultDictProvider, DefaultTupleProvider,
+                            StringProvider, TupleProvider};
+    use ::system::primitives::{Instr, Native};
+    use super::*;
+
+    /// Code of the function `f` defined by `SOURCE`
+    const SOURCE: &'static str = "def f(a):\n    return a + 1\n";
+
+    fn function_code() -> rs::Code {
+        let ins = Compiler::new().compile_str(SOURCE).unwrap();
+        ins.iter().filter_map(|instr| match *instr {
+            Instr(_, Some(Native::Code(ref code))) => Some(code.clone()),
+            _ => None
+        }).next().unwrap()
+    }
+
+    /// Call `dis.dis(object)` with `sys.stdout` captured in a buffer
+    fn dis(rt: &Runtime, object: &ObjectRef) -> ObjectResult {
+        let sys = rt.import_module(strings::SYS_MODULE)?;
+        let buffer = io::text_stream(rt, io::Sink::Buffer(String::new()))?;
+        sys.op_setattr(&rt, &rt.intern("stdout"), &buffer)?;
+
+        let func = create_module(&rt)?.op_getattr(&rt, &rt.str("dis"))?;
+        func.op_call(&rt, &rt.tuple(vec![object.clone()]), &rt.default_tuple(), &rt.default_dict())?;
+
+        let getvalue = buffer.op_getattr(&rt, &rt.str("getvalue"))?;
+        getvalue.op_call(&rt, &rt.default_tuple(), &rt.default_tuple(), &rt.default_dict())
+    }
+
+    #[test]
+    fn dis_function() {
+        let rt = Runtime::new();
+        let code = function_code();
+        let func = rt.function(rs::Func {
+            name: code.co_name.clone(),
+            module: String::from(strings::MAIN_MODULE),
+            signature: ["a"].as_args(),
+            callable: FuncType::Code(code.clone()),
+        });
+
+        assert_eq!(dis(&rt, &func).unwrap(), rt.str(fmt::code(&code)));
+    }
+
+    #[test]
+    fn dis_code() {
+        let rt = Runtime::new();
+        let code = function_code();
+        assert_eq!(dis(&rt, &rt.code(code.clone())).unwrap(), rt.str(fmt::code(&code)));
+    }
+
+    #[test]
+    fn dis_native_function() {
+        let rt = Runtime::new();
+        assert!(dis(&rt, &rt.get_builtin("len")).is_err());
+        assert!(dis(&rt, &rt.int(1)).is_err());
+    }
+}
@@ -0,0 +1,238 @@
+//! `gc` - Interface to the cycle collector
+//!
+//! ```ignore
+//! collect = gc.collect
+//! collect()
+//! ```
+//!
+//! See `runtime::gc::Collector` for how cycles are found.
+//!
+use num::ToPrimitive;
+
+use ::api::method::GetItem;
+use ::api::result::{Error, ObjectResult};
+use ::api::RtObject as ObjectRef;
+use ::modules::builtins::Type;
+use ::modules::precondition::{check_args, check_args_range, check_kwargs};
+use ::modules::register;
+use ::resources::strings;
+use ::runtime::Runtime;
+use ::runtime::gc::GENERATIONS;
+use ::runtime::traits::{BooleanProvider, IntegerProvider, ListProvider, ModuleProvider,
+                        NoneProvider, StringProvider, TupleProvider};
+use ::system::primitives as rs;
+use ::system::primitives::SignatureBuilder;
+
+
+/// Create the `gc` module object with all of its functions
+pub fn create_module(rt: &Runtime) -> ObjectResult {
+    let module = rt.module(rs::None());
+
+    let funcs = vec![
+        CollectFn::create(),
+        EnableFn::create(),
+        DisableFn::create(),
+        IsEnabledFn::create(),
+        GetCountFn::create(),
+        GetObjectsFn::create(),
+    ];
+
+    register(rt, &module, funcs)?;
+
+    Ok(module)
+}
+
+
+pub struct CollectFn;
+pub struct EnableFn;
+pub struct DisableFn;
+pub struct IsEnabledFn;
+pub struct GetCountFn;
+pub struct GetObjectsFn;
+
+
+impl CollectFn {
+    pub fn create() -> rs::Func {
+        module_func!(strings::GC_MODULE, "collect", rs_gc_collect, ["generation"])
+    }
+}
+
+impl EnableFn {
+    pub fn create() -> rs::Func {
+        module_func!(strings::GC_MODULE, "enable", rs_gc_enable, [])
+    }
+}
+
+impl DisableFn {
+    pub fn create() -> rs::Func {
+        module_func!(strings::GC_MODULE, "disable", rs_gc_disable, [])
+    }
+}
+
+impl IsEnabledFn {
+    pub fn create() -> rs::Func {
+        module_func!(strings::GC_MODULE, "isenabled", rs_gc_isenabled, [])
+    }
+}
+
+impl GetCountFn {
+    pub fn create() -> rs::Func {
+        module_func!(strings::GC_MODULE, "get_count", rs_gc_get_count, [])
+    }
+}
+
+impl GetObjectsFn {
+    pub fn create() -> rs::Func {
+        module_func!(strings::GC_MODULE, "get_objects", rs_gc_get_objects, [])
+    }
+}
+
+
+/// `gc.collect(generation=2)` - run a collection and return the number of
+/// unreachable objects found.
+fn rs_gc_collect(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_module" => strings::GC_MODULE, "function" => "collect");
+    let arg_count = check_args_range(0..2, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    let generation = match arg_count {
+        1 => pos_args.op_getitem(&rt, &rt.int(0))?,
+        _ => rt.int(GENERATIONS - 1),
+    };
+
+    let generation = match generation.as_ref() {
+        &Type::Int(ref int) => int.value.0.to_usize(),
+        other => return Err(Error::typerr(&format!(
+            "'{}' object cannot be interpreted as an integer", other.debug_name())))
+    };
+
+    match generation {
+        Some(generation) if generation < GENERATIONS => Ok(rt.int(rt.gc().collect(generation))),
+        _ => Err(Error::value("invalid generation"))
+    }
+}
+
+
+/// `gc.enable()`
+fn rs_gc_enable(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    check_args(0, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    rt.gc().enable();
+    Ok(rt.none())
+}
+
+
+/// `gc.disable()`
+fn rs_gc_disable(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    check_args(0, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    rt.gc().disable();
+    Ok(rt.none())
+}
+
+
+/// `gc.isenabled()`
+fn rs_gc_isenabled(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    check_args(0, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    Ok(rt.bool(rt.gc().is_enabled()))
+}
+
+
+/// `gc.get_count()` - a tuple of the collection counts of each generation
+fn rs_gc_get_count(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    check_args(0, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    let counts = rt.gc().get_count().iter()
+        .map(|&count| rt.int(count))
+        .collect::<rs::Tuple>();
+
+    Ok(rt.tuple(counts))
+}
+
+
+/// `gc.get_objects()` - a list of all of the objects tracked by the collector
+fn rs_gc_get_objects(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    check_args(0, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    Ok(rt.list(rt.gc().get_objects()))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use ::api::method::Append;
+    use ::modules::call;
+    use ::runtime::traits::DefaultListProvider;
+    use super::*;
+
+    #[test]
+    fn collect_cycle() {
+        let rt = Runtime::new();
+        let module = create_module(&rt).unwrap();
+        call(&rt, &module, "collect", vec![]).unwrap();
+
+        let list = rt.default_list();
+        list.meth_append(&rt, &list).unwrap();
+        drop(list);
+
+        assert_eq!(call(&rt, &module, "collect", vec![]).unwrap(), rt.int(1));
+        assert_eq!(call(&rt, &module, "collect", vec![rt.int(0)]).unwrap(), rt.int(0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn collect_invalid_generation() {
+        let rt = Runtime::new();
+        let module = create_module(&rt).unwrap();
+        call(&rt, &module, "collect", vec![rt.int(3)]).unwrap();
+    }
+
+    #[test]
+    fn enable_disable() {
+        let rt = Runtime::new();
+        let module = create_module(&rt).unwrap();
+
+        call(&rt, &module, "disable", vec![]).unwrap();
+        assert_eq!(call(&rt, &module, "isenabled", vec![]).unwrap(), rt.bool(false));
+        call(&rt, &module, "enable", vec![]).unwrap();
+        assert_eq!(call(&rt, &module, "isenabled", vec![]).unwrap(), rt.bool(true));
+    }
+
+    #[test]
+    fn get_count() {
+        let rt = Runtime::new();
+        let module = create_module(&rt).unwrap();
+        call(&rt, &module, "collect", vec![]).unwrap();
+
+        let count = call(&rt, &module, "get_count", vec![]).unwrap();
+        assert_eq!(count.op_getitem(&rt, &rt.int(1)).unwrap(), rt.int(0));
+        assert_eq!(count.op_getitem(&rt, &rt.int(2)).unwrap(), rt.int(0));
+    }
+
+    #[test]
+    fn get_objects() {
+        let rt = Runtime::new();
+        let module = create_module(&rt).unwrap();
+        let list = rt.default_list();
+
+        let objects = call(&rt, &module, "get_objects", vec![]).unwrap();
+        match objects.as_ref() {
+            &Type::List(ref objects) => {
+                assert!(objects.value.0.borrow().iter().any(|object| object.id() == list.id()))
+            },
+            _ => unreachable!()
+        }
+    }
+}
@@ -0,0 +1,395 @@
+//! `greenlet` - Cooperative green threads and channels
+//!
+//! ```ignore
+//! def produce(ch, count):
+//!     send = ch.send
+//!     send(count)
+//!
+//! spawn = greenlet.spawn
+//! ch = greenlet.channel()
+//! g = spawn(produce, ch, 3)
+//! recv = ch.recv
+//! count = recv()
+//! ```
+//!
+//! Greenlets are identified by the integer returned from `spawn`. They run on the
+//! OS thread that spawned them and switch only when one of them blocks, see
+//! `runtime::scheduler`. Like threads, each greenlet calls its function in a new
+//! `Interpreter` that starts with a copy of the names bound when `spawn` was
+//! called, so the function can call the other functions of its module. Names bound
+//! afterwards on either side are not seen by the other, objects that both need to
+//! change are passed as arguments.
+//!
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use num::ToPrimitive;
+
+use ::api::method::GetItem;
+use ::api::result::{Error, ObjectResult, RtResult};
+use ::api::RtObject as ObjectRef;
+use ::modules::builtins::Type;
+use ::modules::precondition::{check_args, check_args_range, check_kwargs};
+use ::modules::register;
+use ::modules::threading::{guard, instance};
+use ::resources::strings;
+use ::runtime::Runtime;
+use ::runtime::scheduler::{self, GreenletId};
+use ::runtime::traits::{BooleanProvider, IntegerProvider, ModuleProvider, NoneProvider};
+use ::system::primitives as rs;
+use ::system::primitives::SignatureBuilder;
+
+
+/// Create the `greenlet` module object with all of its functions
+pub fn create_module(rt: &Runtime) -> ObjectResult {
+    let module = rt.module(rs::None());
+
+    let funcs = vec![
+        SpawnFn::create(),
+        SwitchFn::create(),
+        JoinFn::create(),
+        SleepFn::create(),
+        ChannelFn::create(),
+    ];
+
+    register(rt, &module, funcs)?;
+
+    Ok(module)
+}
+
+
+pub struct SpawnFn;
+pub struct SwitchFn;
+pub struct JoinFn;
+pub struct SleepFn;
+pub struct ChannelFn;
+
+
+impl SpawnFn {
+    pub fn create() -> rs::Func {
+        module_func!(strings::GREENLET_MODULE, "spawn", rs_greenlet_spawn, ["func", "*args"])
+    }
+}
+
+impl SwitchFn {
+    pub fn create() -> rs::Func {
+        module_func!(strings::GREENLET_MODULE, "switch", rs_greenlet_switch, ["greenlet"])
+    }
+}
+
+impl JoinFn {
+    pub fn create() -> rs::Func {
+        module_func!(strings::GREENLET_MODULE, "join", rs_greenlet_join, ["greenlet"])
+    }
+}
+
+impl SleepFn {
+    pub fn create() -> rs::Func {
+        module_func!(strings::GREENLET_MODULE, "sleep", rs_greenlet_sleep, ["seconds"])
+    }
+}
+
+impl ChannelFn {
+    pub fn create() -> rs::Func {
+        module_func!(strings::GREENLET_MODULE, "channel", rs_greenlet_channel, ["capacity"])
+    }
+}
+
+
+/// Positional argument `index` as a non negative integer
+fn usize_arg(rt: &Runtime, pos_args: &ObjectRef, index: usize) -> RtResult<usize> {
+    let arg = pos_args.op_getitem(&rt, &rt.int(index))?;
+    let value = match arg.as_ref() {
+        &Type::Int(ref int) => int.value.0.to_usize(),
+        other => return Err(Error::typerr(&format!(
+            "'{}' object cannot be interpreted as an integer", other.debug_name())))
+    };
+
+    match value {
+        Some(value) => Ok(value),
+        None => Err(Error::value("expected a non negative integer"))
+    }
+}
+
+
+/// `greenlet.spawn(func, *args)` - schedule `func(*args)` to run as a new greenlet
+/// and return its id.
+fn rs_greenlet_spawn(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_module" => strings::GREENLET_MODULE, "function" => "spawn");
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    let mut args = match pos_args.as_ref() {
+        &Type::Tuple(ref tuple) => tuple.value.0.clone(),
+        _ => return Err(Error::typerr("Expected type tuple for pos_args"))
+    };
+
+    if args.is_empty() {
+        return Err(Error::typerr("spawn() missing required argument 'func'"));
+    }
+
+    let func = args.remove(0);
+    let id = scheduler::spawn(&rt, &func, args)?;
+    Ok(rt.int(id))
+}
+
+
+/// `greenlet.switch(greenlet=None)` - let the other greenlets run, starting with
+/// `greenlet` if it is given.
+fn rs_greenlet_switch(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_module" => strings::GREENLET_MODULE, "function" => "switch");
+    let arg_count = check_args_range(0..2, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    match arg_count {
+        1 => scheduler::switch(usize_arg(&rt, &pos_args, 0)? as GreenletId)?,
+        _ => scheduler::yield_now()
+    };
+
+    Ok(rt.none())
+}
+
+
+/// `greenlet.join(greenlet)` - wait for `greenlet` to finish and return the value
+/// returned by its function.
+fn rs_greenlet_join(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_module" => strings::GREENLET_MODULE, "function" => "join");
+    check_args(1, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    scheduler::join(&rt, usize_arg(&rt, &pos_args, 0)? as GreenletId)
+}
+
+
+/// `greenlet.sleep(seconds)` - block the current greenlet for at least `seconds`
+/// while the others run.
+fn rs_greenlet_sleep(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_module" => strings::GREENLET_MODULE, "function" => "sleep");
+    check_args(1, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    let arg = pos_args.op_getitem(&rt, &rt.int(0))?;
+    let seconds = match arg.as_ref() {
+        &Type::Int(ref int) => int.value.0.to_f64(),
+        &Type::Float(ref float) => Some(float.value.0),
+        other => return Err(Error::typerr(&format!(
+            "a float is required, not {}", other.debug_name())))
+    };
+
+    let seconds = match seconds {
+        Some(seconds) if seconds >= 0.0 => seconds,
+        _ => return Err(Error::value("sleep length must be non-negative"))
+    };
+
+    let nanos = (seconds.fract() * 1e9) as u32;
+    let deadline = Instant::now() + Duration::new(seconds.trunc() as u64, nanos);
+
+    // Each turn counts as progress, a sleeping greenlet is not a deadlock
+    scheduler::yield_now();
+    while Instant::now() < deadline {
+        scheduler::yield_now();
+    }
+    Ok(rt.none())
+}
+
+
+// ---------------------------------------
+//  Channel
+// ---------------------------------------
+
+struct ChannelState {
+    items: VecDeque<ObjectRef>,
+    capacity: usize,
+    closed: bool,
+    /// Count of items sent and received, an unbuffered send waits for its item
+    /// to be received.
+    sent: usize,
+    received: usize,
+}
+
+
+/// Send `item`, blocking while the channel is full. Unbuffered channels block
+/// until the item is received.
+fn channel_send(rt: &Runtime, state: &Mutex<ChannelState>, item: ObjectRef) -> RtResult<rs::None> {
+    let capacity = guard(state).capacity;
+    scheduler::block_until(rt, || {
+        let state = guard(state);
+        state.closed || state.items.len() < capacity.max(1)
+    })?;
+
+    let ticket = {
+        let mut state = guard(state);
+        if state.closed {
+            return Err(Error::value("send on closed channel"));
+        }
+
+        state.items.push_back(item);
+        state.sent += 1;
+        state.sent
+    };
+
+    if capacity == 0 {
+        scheduler::block_until(rt, || guard(state).received >= ticket)?;
+    }
+
+    Ok(rs::None())
+}
+
+
+/// Receive the next item, blocking until there is one. A closed channel that
+/// has no more items returns `None`.
+fn channel_recv(rt: &Runtime, state: &Mutex<ChannelState>) -> RtResult<Option<ObjectRef>> {
+    scheduler::block_until(rt, || {
+        let state = guard(state);
+        state.closed || !state.items.is_empty()
+    })?;
+
+    let mut state = guard(state);
+    let item = state.items.pop_front();
+    if item.is_some() {
+        state.received += 1;
+    }
+    Ok(item)
+}
+
+
+/// `greenlet.channel(capacity=0)` - a Go style channel, sends block once `capacity`
+/// items are waiting and receives block until an item is sent. Blocking yields
+/// to the other greenlets.
+fn rs_greenlet_channel(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_module" => strings::GREENLET_MODULE, "function" => "channel");
+    let arg_count = check_args_range(0..2, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    let capacity = match arg_count {
+        1 => usize_arg(&rt, &pos_args, 0)?,
+        _ => 0
+    };
+
+    let state = Arc::new(Mutex::new(ChannelState {
+        items: VecDeque::new(),
+        capacity: capacity,
+        closed: false,
+        sent: 0,
+        received: 0,
+    }));
+    let send = state.clone();
+    let recv = state.clone();
+    let close = state.clone();
+    let closed = state.clone();
+
+    instance(rt, vec![
+        module_func!(strings::GREENLET_MODULE, "send", move |rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef| {
+            check_args(1, &pos_args)?;
+            check_args(0, &starargs)?;
+            check_kwargs(0, &kwargs)?;
+
+            channel_send(&rt, &send, pos_args.op_getitem(&rt, &rt.int(0))?)?;
+            Ok(rt.none())
+        }, ["item"]),
+        module_func!(strings::GREENLET_MODULE, "recv", move |rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef| {
+            check_args(0, &pos_args)?;
+            check_args(0, &starargs)?;
+            check_kwargs(0, &kwargs)?;
+
+            Ok(channel_recv(&rt, &recv)?.unwrap_or_else(|| rt.none()))
+        }, []),
+        module_func!(strings::GREENLET_MODULE, "close", move |rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef| {
+            check_args(0, &pos_args)?;
+            check_args(0, &starargs)?;
+            check_kwargs(0, &kwargs)?;
+
+            guard(&close).closed = true;
+            Ok(rt.none())
+        }, []),
+        module_func!(strings::GREENLET_MODULE, "closed", move |rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef| {
+            check_args(0, &pos_args)?;
+            check_args(0, &starargs)?;
+            check_kwargs(0, &kwargs)?;
+
+            Ok(rt.bool(guard(&closed).closed))
+        }, []),
+    ])
+}
+
+
+#[cfg(test)]
+mod tests {
+    use ::api::method::GetAttr;
+    use ::api::result::ErrorType;
+    use ::modules::call;
+    use ::runtime::traits::StringProvider;
+    use super::*;
+
+    #[test]
+    fn channel_between_greenlets() {
+        let rt = Runtime::new();
+        let module = create_module(&rt).unwrap();
+        let ch = call(&rt, &module, "channel", vec![]).unwrap();
+        let send = ch.op_getattr(&rt, &rt.str("send")).unwrap();
+
+        let sender = call(&rt, &module, "spawn", vec![send.clone(), rt.int(1)]).unwrap();
+        let other = call(&rt, &module, "spawn", vec![send, rt.int(2)]).unwrap();
+
+        assert_eq!(call(&rt, &ch, "recv", vec![]).unwrap(), rt.int(1));
+        assert_eq!(call(&rt, &ch, "recv", vec![]).unwrap(), rt.int(2));
+
+        call(&rt, &module, "join", vec![sender]).unwrap();
+        call(&rt, &module, "join", vec![other]).unwrap();
+    }
+
+    #[test]
+    fn buffered_channel_close() {
+        let rt = Runtime::new();
+        let module = create_module(&rt).unwrap();
+        let ch = call(&rt, &module, "channel", vec![rt.int(2)]).unwrap();
+
+        call(&rt, &ch, "send", vec![rt.int(1)]).unwrap();
+        call(&rt, &ch, "send", vec![rt.int(2)]).unwrap();
+        call(&rt, &ch, "close", vec![]).unwrap();
+
+        assert!(call(&rt, &ch, "send", vec![rt.int(3)]).is_err());
+        assert_eq!(call(&rt, &ch, "closed", vec![]).unwrap(), rt.bool(true));
+        assert_eq!(call(&rt, &ch, "recv", vec![]).unwrap(), rt.int(1));
+        assert_eq!(call(&rt, &ch, "recv", vec![]).unwrap(), rt.int(2));
+        assert_eq!(call(&rt, &ch, "recv", vec![]).unwrap(), rt.none());
+    }
+
+    #[test]
+    fn recv_without_senders() {
+        let rt = Runtime::new();
+        let module = create_module(&rt).unwrap();
+        let ch = call(&rt, &module, "channel", vec![]).unwrap();
+
+        match call(&rt, &ch, "recv", vec![]) {
+            Err(Error(ErrorType::Runtime, message)) => assert_eq!(message, "deadlock: no greenlets can run"),
+            other => panic!("expected a RuntimeError, got {:?}", other)
+        }
+        assert!(call(&rt, &ch, "send", vec![rt.int(1)]).is_err());
+    }
+
+    #[test]
+    fn sleep() {
+        let rt = Runtime::new();
+        let module = create_module(&rt).unwrap();
+
+        let start = Instant::now();
+        call(&rt, &module, "sleep", vec![rt.int(0)]).unwrap();
+        assert!(call(&rt, &module, "sleep", vec![rt.int(-1)]).is_err());
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn join_unknown() {
+        let rt = Runtime::new();
+        let module = create_module(&rt).unwrap();
+
+        assert!(call(&rt, &module, "join", vec![rt.int(10000)]).is_err());
+        assert!(call(&rt, &module, "switch", vec![rt.int(10000)]).is_err());
+    }
+}
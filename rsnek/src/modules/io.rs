@@ -0,0 +1,232 @@
+//! `io` - Text streams
+//!
+//! ```ignore
+//! buffer = io.StringIO()
+//! write = buffer.write
+//! write('captured')
+//! getvalue = buffer.getvalue
+//! getvalue()
+//! ```
+//!
+//! `sys.stdout` and `sys.stderr` are text streams over the process' standard
+//! output and error, `StringIO` streams write to a string kept in memory. Each
+//! stream is an object with `write(s)` and `flush()` methods, so any object with
+//! a `write` method can stand in for one. `sys.stdin` reads the process' standard
+//! input with `readline()` and `read()`.
+//!
+use std::io::{self, Read, Write};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use ::api::method::{Call, GetAttr, GetItem};
+use ::api::result::{Error, ObjectResult, RtResult};
+use ::api::RtObject as ObjectRef;
+use ::modules::builtins::Type;
+use ::modules::precondition::{check_args, check_kwargs};
+use ::modules::register;
+use ::modules::threading::instance;
+use ::resources::strings;
+use ::runtime::Runtime;
+use ::runtime::traits::{DefaultDictProvider, DefaultTupleProvider, IntegerProvider,
+                        ModuleProvider, NoneProvider, StringProvider, TupleProvider};
+use ::system::primitives as rs;
+use ::system::primitives::SignatureBuilder;
+
+
+/// Where the text written to a stream goes
+pub enum Sink {
+    Stdout,
+    Stderr,
+    Buffer(String),
+}
+
+
+impl Sink {
+    fn write(&mut self, text: &str) -> io::Result<()> {
+        match *self {
+            Sink::Stdout => io::stdout().write_all(text.as_bytes()),
+            Sink::Stderr => io::stderr().write_all(text.as_bytes()),
+            Sink::Buffer(ref mut buffer) => {
+                buffer.push_str(text);
+                Ok(())
+            }
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match *self {
+            Sink::Stdout => io::stdout().flush(),
+            Sink::Stderr => io::stderr().flush(),
+            Sink::Buffer(_) => Ok(())
+        }
+    }
+}
+
+
+/// Create the `io` module object with all of its functions
+pub fn create_module(rt: &Runtime) -> ObjectResult {
+    let module = rt.module(rs::None());
+
+    let funcs = vec![
+        StringIOFn::create(),
+    ];
+
+    register(rt, &module, funcs)?;
+
+    Ok(module)
+}
+
+
+pub struct StringIOFn;
+
+
+impl StringIOFn {
+    pub fn create() -> rs::Func {
+        module_func!(strings::IO_MODULE, "StringIO", rs_io_stringio, [])
+    }
+}
+
+
+/// `io.StringIO()` - a text stream that keeps what is written in memory
+fn rs_io_stringio(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_module" => strings::IO_MODULE, "function" => "StringIO");
+    check_args(0, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    text_stream(rt, Sink::Buffer(String::new()))
+}
+
+
+fn io_error(err: io::Error) -> Error {
+    Error::system(&format!("{}", err))
+}
+
+
+/// Create a text stream object that writes to `sink`
+pub fn text_stream(rt: &Runtime, sink: Sink) -> ObjectResult {
+    let is_buffer = match sink {
+        Sink::Buffer(_) => true,
+        _ => false
+    };
+
+    let sink = Arc::new(Mutex::new(sink));
+    let write = sink.clone();
+    let flush = sink.clone();
+    let getvalue = sink.clone();
+
+    let mut methods = vec![
+        module_func!(strings::IO_MODULE, "write", move |rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef| {
+            check_args(1, &pos_args)?;
+            check_args(0, &starargs)?;
+            check_kwargs(0, &kwargs)?;
+
+            let text = pos_args.op_getitem(&rt, &rt.int(0))?;
+            let text = match text.as_ref() {
+                &Type::Str(ref string) => &string.value.0,
+                other => return Err(Error::typerr(&format!(
+                    "write() argument must be str, not {}", other.debug_name())))
+            };
+
+            let mut sink = write.lock().unwrap_or_else(PoisonError::into_inner);
+            sink.write(text).map_err(io_error)?;
+            Ok(rt.int(text.chars().count()))
+        }, ["s"]),
+        module_func!(strings::IO_MODULE, "flush", move |rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef| {
+            check_args(0, &pos_args)?;
+            check_args(0, &starargs)?;
+            check_kwargs(0, &kwargs)?;
+
+            let mut sink = flush.lock().unwrap_or_else(PoisonError::into_inner);
+            sink.flush().map_err(io_error)?;
+            Ok(rt.none())
+        }, []),
+    ];
+
+    if is_buffer {
+        methods.push(module_func!(strings::IO_MODULE, "getvalue", move |rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef| {
+            check_args(0, &pos_args)?;
+            check_args(0, &starargs)?;
+            check_kwargs(0, &kwargs)?;
+
+            let sink = getvalue.lock().unwrap_or_else(PoisonError::into_inner);
+            match *sink {
+                Sink::Buffer(ref buffer) => Ok(rt.str(buffer.clone())),
+                _ => unreachable!()
+            }
+        }, []));
+    }
+
+    instance(rt, methods)
+}
+
+
+/// Create a text stream object that reads from the process' standard input
+pub fn stdin_stream(rt: &Runtime) -> ObjectResult {
+    let methods = vec![
+        module_func!(strings::IO_MODULE, "readline", |rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef| {
+            check_args(0, &pos_args)?;
+            check_args(0, &starargs)?;
+            check_kwargs(0, &kwargs)?;
+
+            // The line keeps its newline, an empty string is the end of input
+            let mut line = String::new();
+            io::stdin().read_line(&mut line).map_err(io_error)?;
+            Ok(rt.str(line))
+        }, []),
+        module_func!(strings::IO_MODULE, "read", |rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef| {
+            check_args(0, &pos_args)?;
+            check_args(0, &starargs)?;
+            check_kwargs(0, &kwargs)?;
+
+            let mut text = String::new();
+            io::stdin().read_to_string(&mut text).map_err(io_error)?;
+            Ok(rt.str(text))
+        }, []),
+    ];
+
+    instance(rt, methods)
+}
+
+
+/// Call `file.write(text)`
+pub fn write(rt: &Runtime, file: &ObjectRef, text: &str) -> RtResult<rs::None> {
+    let write = file.op_getattr(&rt, &rt.intern("write"))?;
+    write.op_call(&rt, &rt.tuple(vec![rt.str(text)]), &rt.default_tuple(), &rt.default_dict())?;
+    Ok(rs::None())
+}
+
+
+/// Call `file.flush()`
+pub fn flush(rt: &Runtime, file: &ObjectRef) -> RtResult<rs::None> {
+    let flush = file.op_getattr(&rt, &rt.intern("flush"))?;
+    flush.op_call(&rt, &rt.default_tuple(), &rt.default_tuple(), &rt.default_dict())?;
+    Ok(rs::None())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use ::modules::call;
+    use super::*;
+
+    #[test]
+    fn stringio() {
+        let rt = Runtime::new();
+        let module = create_module(&rt).unwrap();
+        let buffer = call(&rt, &module, "StringIO", vec![]).unwrap();
+
+        assert_eq!(call(&rt, &buffer, "write", vec![rt.str("héllo")]).unwrap(), rt.int(5));
+        write(&rt, &buffer, " world\n").unwrap();
+        flush(&rt, &buffer).unwrap();
+
+        assert_eq!(call(&rt, &buffer, "getvalue", vec![]).unwrap(), rt.str("héllo world\n"));
+    }
+
+    #[test]
+    fn write_not_a_str() {
+        let rt = Runtime::new();
+        let stream = text_stream(&rt, Sink::Buffer(String::new())).unwrap();
+
+        assert!(call(&rt, &stream, "write", vec![rt.int(1)]).is_err());
+    }
+}
@@ -1,3 +1,51 @@
 //! Natively defined python modules
 pub mod builtins;
+pub mod dis;
+pub mod gc;
+pub mod greenlet;
+pub mod io;
 pub mod precondition;
+pub mod profile;
+pub mod sys;
+pub mod threading;
+pub mod traceback;
+
+use ::api::RtObject;
+use ::api::method::SetAttr;
+use ::api::result::{ObjectResult, RtResult};
+use ::runtime::Runtime;
+use ::runtime::traits::FunctionProvider;
+use ::system::primitives as rs;
+
+
+/// Set each of `funcs` as the attribute of `object` with the name of the function,
+/// used for the functions of native modules and the methods of the objects they create.
+pub fn register(rt: &Runtime, object: &RtObject, funcs: Vec<rs::Func>) -> RtResult<()> {
+    for func in funcs.into_iter() {
+        let name = rt.intern(&func.name);
+        object.op_setattr(&rt, &name, &rt.function(func))?;
+    }
+
+    Ok(())
+}
+
+
+/// Call `func` with the positional `args`, for the tests of the modules and builtins
+#[cfg(test)]
+pub fn call_function(rt: &Runtime, func: &RtObject, args: Vec<RtObject>) -> ObjectResult {
+    use ::api::method::Call;
+    use ::runtime::traits::{DefaultDictProvider, DefaultTupleProvider, TupleProvider};
+
+    func.op_call(&rt, &rt.tuple(args), &rt.default_tuple(), &rt.default_dict())
+}
+
+
+/// Call the method `name` of `object` with the positional `args`, for the tests
+/// of the modules
+#[cfg(test)]
+pub fn call(rt: &Runtime, object: &RtObject, name: &str, args: Vec<RtObject>) -> ObjectResult {
+    use ::api::method::GetAttr;
+    use ::runtime::traits::StringProvider;
+
+    call_function(rt, &object.op_getattr(&rt, &rt.str(name))?, args)
+}
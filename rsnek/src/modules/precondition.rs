@@ -2,13 +2,13 @@
 use std::ops::Range;
 use std::ops::Deref;
 use std::borrow::Borrow;
-use std::cell::Ref;
 
 use ::api::result::Error;
 use ::api::RtObject as ObjectRef;
 use ::api::result::{RtResult};
 use ::modules::builtins::Type;
 use ::system::primitives as rs;
+use ::system::sync::Ref;
 
 
 pub fn check_args(count: usize, pos_args: &ObjectRef) -> RtResult<rs::None> {
@@ -54,3 +54,32 @@ pub fn check_kwargs(count: usize, kwargs: &ObjectRef) -> RtResult<rs::None> {
     }
 
 }
+
+
+/// Look up each of the optional keyword arguments in `names` from `kwargs`, returning
+/// the values in the same order as `names`. Any other keyword is a `TypeError`.
+pub fn check_kwargs_names(names: &[&str], kwargs: &ObjectRef) -> RtResult<Vec<Option<ObjectRef>>> {
+    match kwargs.as_ref() {
+        &Type::Dict(ref dict) => {
+            let borrowed: Ref<rs::Dict> = dict.value.0.borrow();
+            let mut found: Vec<Option<ObjectRef>> = vec![None; names.len()];
+
+            for (key, value) in borrowed.iter() {
+                let key = key.value();
+                let name = match key.as_ref() {
+                    &Type::Str(ref string) => string.value.0.clone(),
+                    _ => return Err(Error::typerr("keywords must be strings")),
+                };
+
+                match names.iter().position(|expected| *expected == name.as_str()) {
+                    Some(idx) => found[idx] = Some(value.clone()),
+                    None => return Err(Error::typerr(
+                        &format!("'{}' is an invalid keyword argument", name)))
+                }
+            }
+
+            Ok(found)
+        }
+        _ => Err(Error::typerr("Expected type dict for kwargs")),
+    }
+}
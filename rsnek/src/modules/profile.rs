@@ -0,0 +1,179 @@
+//! `cProfile` - Deterministic profiling of python code, also imported as `profile`
+//!
+//! ```ignore
+//! Profile = cProfile.Profile
+//! profiler = Profile()
+//! enable = profiler.enable
+//! disable = profiler.disable
+//! print_stats = profiler.print_stats
+//!
+//! enable()
+//! work()
+//! disable()
+//! print_stats('cumulative')
+//! ```
+//!
+//! The report is written to `sys.stdout` in the layout of `pstats`, the sort key
+//! is one of `'calls'`, `'cumulative'`, `'time'`, or `'name'` and their `pstats`
+//! aliases. Only one profiler runs at a time, enabling one replaces the profiler
+//! of `rsnek --profile`. See `runtime::profiler::Profiler` for what is counted.
+//!
+use std::sync::PoisonError;
+
+use num::ToPrimitive;
+
+use ::api::method::{GetAttr, GetItem};
+use ::api::result::{Error, ObjectResult, RtResult};
+use ::api::RtObject as ObjectRef;
+use ::modules::builtins::Type;
+use ::modules::io;
+use ::modules::precondition::{check_args, check_args_range, check_kwargs};
+use ::modules::register;
+use ::modules::threading::instance;
+use ::resources::strings;
+use ::runtime::Runtime;
+use ::runtime::profiler::{Profiler, SharedProfiler, Sort};
+use ::runtime::traits::{IntegerProvider, ModuleImporter, ModuleProvider, NoneProvider,
+                        StringProvider};
+use ::system::primitives as rs;
+use ::system::primitives::SignatureBuilder;
+
+
+/// Create the `cProfile` module object with all of its functions
+pub fn create_module(rt: &Runtime) -> ObjectResult {
+    let module = rt.module(rs::None());
+
+    let funcs = vec![
+        ProfileFn::create(),
+    ];
+
+    register(rt, &module, funcs)?;
+
+    Ok(module)
+}
+
+
+pub struct ProfileFn;
+
+
+impl ProfileFn {
+    pub fn create() -> rs::Func {
+        module_func!(strings::CPROFILE_MODULE, "Profile", rs_cprofile_profile, [])
+    }
+}
+
+
+/// `cProfile.Profile()` - a profiler that is started with `enable()`, stopped with
+/// `disable()`, and reports with `print_stats(sort=-1)`
+fn rs_cprofile_profile(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_module" => strings::CPROFILE_MODULE, "function" => "Profile");
+    check_args(0, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    profile(rt, Profiler::shared())
+}
+
+
+/// Create a `Profile` object around `shared`
+fn profile(rt: &Runtime, shared: SharedProfiler) -> ObjectResult {
+    let enable = shared.clone();
+    let disable = shared.clone();
+    let clear = shared.clone();
+    let print_stats = shared.clone();
+
+    let methods = vec![
+        module_func!(strings::CPROFILE_MODULE, "enable", move |rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef| {
+            check_args(0, &pos_args)?;
+            check_args(0, &starargs)?;
+            check_kwargs(0, &kwargs)?;
+
+            rt.set_profiler(Some(enable.clone()));
+            Ok(rt.none())
+        }, []),
+        module_func!(strings::CPROFILE_MODULE, "disable", move |rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef| {
+            check_args(0, &pos_args)?;
+            check_args(0, &starargs)?;
+            check_kwargs(0, &kwargs)?;
+
+            rt.set_profiler(None);
+            disable.lock().unwrap_or_else(PoisonError::into_inner).finish();
+            Ok(rt.none())
+        }, []),
+        module_func!(strings::CPROFILE_MODULE, "clear", move |rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef| {
+            check_args(0, &pos_args)?;
+            check_args(0, &starargs)?;
+            check_kwargs(0, &kwargs)?;
+
+            clear.lock().unwrap_or_else(PoisonError::into_inner).clear();
+            Ok(rt.none())
+        }, []),
+        module_func!(strings::CPROFILE_MODULE, "print_stats", move |rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef| {
+            let arg_count = check_args_range(0..2, &pos_args)?;
+            check_args(0, &starargs)?;
+            check_kwargs(0, &kwargs)?;
+
+            let sort = match arg_count {
+                1 => sort_arg(&pos_args.op_getitem(&rt, &rt.int(0))?)?,
+                _ => Sort::Name,
+            };
+
+            let text = print_stats.lock().unwrap_or_else(PoisonError::into_inner).format_stats(sort);
+            let stdout = rt.import_module(strings::SYS_MODULE)?.op_getattr(&rt, &rt.intern("stdout"))?;
+            io::write(rt, &stdout, &text)?;
+            Ok(rt.none())
+        }, ["sort"]),
+    ];
+
+    instance(rt, methods)
+}
+
+
+/// The `sort` argument of `print_stats`, a `pstats` sort key or -1
+fn sort_arg(sort: &ObjectRef) -> RtResult<Sort> {
+    let key = match sort.as_ref() {
+        &Type::Str(ref string) => string.value.0.clone(),
+        &Type::Int(ref int) => match int.value.0.to_i64() {
+            Some(value) => value.to_string(),
+            None => String::new()
+        },
+        other => return Err(Error::typerr(&format!(
+            "sort must be str or int, not {}", other.debug_name())))
+    };
+
+    match Sort::parse(&key) {
+        Some(sort) => Ok(sort),
+        None => Err(Error::value(&format!("Invalid sort key: {}", key)))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use ::modules::call;
+    use super::*;
+
+    #[test]
+    fn enable_and_disable() {
+        let rt = Runtime::new();
+        let module = create_module(&rt).unwrap();
+        let profiler = call(&rt, &module, "Profile", vec![]).unwrap();
+
+        call(&rt, &profiler, "enable", vec![]).unwrap();
+        assert!(rt.is_profiling());
+
+        call(&rt, &profiler, "disable", vec![]).unwrap();
+        assert!(!rt.is_profiling());
+        assert!(rt.profiler().is_none());
+    }
+
+    #[test]
+    fn sort_keys() {
+        let rt = Runtime::new();
+
+        assert_eq!(sort_arg(&rt.str("tottime")).unwrap(), Sort::Time);
+        assert_eq!(sort_arg(&rt.int(-1)).unwrap(), Sort::Name);
+        assert!(sort_arg(&rt.str("bogus")).is_err());
+        assert!(sort_arg(&rt.none()).is_err());
+    }
+}
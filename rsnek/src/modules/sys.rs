@@ -0,0 +1,482 @@
+//! `sys` - Interpreter specific functions
+//!
+//! ```ignore
+//! intern = sys.intern
+//! name = intern('some_' + 'name')
+//!
+//! setrecursionlimit = sys.setrecursionlimit
+//! setrecursionlimit(1000)
+//!
+//! write = sys.stdout.write
+//! write('hello\n')
+//!
+//! script = sys.argv[0]
+//! exit = sys.exit
+//! exit(2)
+//!
+//! def tracer(frame, event, arg):
+//!     print(event, frame.f_lineno)
+//!     return tracer
+//!
+//! settrace = sys.settrace
+//! settrace(tracer)
+//! ```
+//!
+//! `argv` and `path` are set by `configure` from the command line the interpreter
+//! was started with, `modules` is the runtime's dict of the modules imported by name.
+//!
+//! Like CPython the trace function gets the `'call'` event of each python function and
+//! what it returns is the local trace function of that frame, which gets its `'line'`,
+//! `'return'` and `'exception'` events. The profile function gets `'call'` and `'return'`
+//! plus `'c_call'`, `'c_return'` and `'c_exception'` for native functions.
+//!
+use std::env;
+
+use num::ToPrimitive;
+
+use ::api::method::{GetItem, SetAttr, StringCast};
+use ::api::result::{Error, ObjectResult, RtResult};
+use ::api::RtObject as ObjectRef;
+use ::modules::builtins::Type;
+use ::modules::io;
+use ::modules::precondition::{check_args, check_args_range, check_kwargs};
+use ::modules::register;
+use ::resources::strings;
+use ::runtime::Runtime;
+use ::runtime::config::Mode;
+use ::runtime::traits::{IntegerProvider, ListProvider, ModuleImporter, ModuleProvider,
+                        NoneProvider, StringProvider, TupleProvider};
+use ::system::primitives as rs;
+use ::system::primitives::SignatureBuilder;
+use ::system::Argv;
+
+
+/// Create the `sys` module object with all of its functions
+pub fn create_module(rt: &Runtime) -> ObjectResult {
+    let module = rt.module(rs::None());
+
+    let funcs = vec![
+        InternFn::create(),
+        GetRecursionLimitFn::create(),
+        SetRecursionLimitFn::create(),
+        ExitFn::create(),
+        SetTraceFn::create(),
+        GetTraceFn::create(),
+        SetProfileFn::create(),
+        GetProfileFn::create(),
+    ];
+
+    register(rt, &module, funcs)?;
+
+    // `__stdout__` and `__stderr__` keep the original streams when
+    // `stdout` and `stderr` are replaced.
+    let stdout = io::text_stream(rt, io::Sink::Stdout)?;
+    let stderr = io::text_stream(rt, io::Sink::Stderr)?;
+    module.op_setattr(&rt, &rt.intern("stdout"), &stdout)?;
+    module.op_setattr(&rt, &rt.intern("__stdout__"), &stdout)?;
+    module.op_setattr(&rt, &rt.intern("stderr"), &stderr)?;
+    module.op_setattr(&rt, &rt.intern("__stderr__"), &stderr)?;
+
+    let stdin = io::stdin_stream(rt)?;
+    module.op_setattr(&rt, &rt.intern("stdin"), &stdin)?;
+    module.op_setattr(&rt, &rt.intern("__stdin__"), &stdin)?;
+
+    module.op_setattr(&rt, &rt.intern("argv"), &rt.list(vec![rt.str("")]))?;
+    module.op_setattr(&rt, &rt.intern("path"), &rt.list(vec![rt.str("")]))?;
+    module.op_setattr(&rt, &rt.intern("modules"), &rt.modules())?;
+
+    module.op_setattr(&rt, &rt.intern("version"), &rt.str(version()))?;
+    module.op_setattr(&rt, &rt.intern("version_info"), &version_info(rt))?;
+    module.op_setattr(&rt, &rt.intern("platform"), &rt.str(platform()))?;
+    module.op_setattr(&rt, &rt.intern("maxsize"), &rt.int(isize::max_value()))?;
+    module.op_setattr(&rt, &rt.intern("byteorder"), &rt.str(byteorder()))?;
+
+    Ok(module)
+}
+
+
+/// Set `sys.argv` and `sys.path` for the interpreter started in `mode` with `args`,
+/// like CPython `argv[0]` is the script, `'-c'` for a command, or `''` in the repl.
+pub fn configure(rt: &Runtime, mode: &Mode, args: Argv) -> RtResult<rs::None> {
+    let mut argv: Vec<String> = match *mode {
+        Mode::Command(_) => vec![String::from("-c")],
+        Mode::Module(_) => vec![String::from("-m")],
+        Mode::File => Vec::new(),
+        Mode::Interactive => vec![String::new()],
+    };
+    argv.extend(args.iter().map(|arg| arg.to_string()));
+
+    // Scripts can import what is next to them, everything else what is in the working directory
+    let path = match (mode, args.get(0)) {
+        (&Mode::File, Some(script)) => env::current_dir().ok()
+            .map(|cwd| cwd.join(script))
+            .and_then(|script| script.parent().map(|dir| dir.to_string_lossy().to_string()))
+            .unwrap_or(String::new()),
+        _ => String::new()
+    };
+
+    let module = rt.import_module(strings::SYS_MODULE)?;
+    module.op_setattr(&rt, &rt.intern("argv"), &rt.list(argv.into_iter().map(|arg| rt.str(arg)).collect()))?;
+    module.op_setattr(&rt, &rt.intern("path"), &rt.list(vec![rt.str(path)]))?;
+    Ok(rs::None())
+}
+
+
+/// `sys.version` - the version of rsnek and the compiler it was built with
+fn version() -> String {
+    format!("{} ({})", strings::VERSION.trim().trim_left_matches('v'), strings::BUILD.trim())
+}
+
+
+/// `sys.version_info` - `(major, minor, micro, releaselevel, serial)` of rsnek
+fn version_info(rt: &Runtime) -> ObjectRef {
+    let mut parts = strings::VERSION.trim().trim_left_matches('v').split('.')
+        .map(|part| part.parse::<usize>().unwrap_or(0));
+
+    rt.tuple(vec![
+        rt.int(parts.next().unwrap_or(0)),
+        rt.int(parts.next().unwrap_or(0)),
+        rt.int(parts.next().unwrap_or(0)),
+        rt.str("final"),
+        rt.int(0),
+    ])
+}
+
+
+/// `sys.platform` - the names CPython uses for the operating system
+fn platform() -> &'static str {
+    match env::consts::OS {
+        "macos" => "darwin",
+        "windows" => "win32",
+        os => os
+    }
+}
+
+
+/// `sys.byteorder` - the native byte order
+fn byteorder() -> &'static str {
+    if cfg!(target_endian = "little") { "little" } else { "big" }
+}
+
+
+pub struct InternFn;
+pub struct GetRecursionLimitFn;
+pub struct SetRecursionLimitFn;
+pub struct ExitFn;
+pub struct SetTraceFn;
+pub struct GetTraceFn;
+pub struct SetProfileFn;
+pub struct GetProfileFn;
+
+
+impl InternFn {
+    pub fn create() -> rs::Func {
+        module_func!(strings::SYS_MODULE, "intern", rs_sys_intern, ["string"])
+    }
+}
+
+impl GetRecursionLimitFn {
+    pub fn create() -> rs::Func {
+        module_func!(strings::SYS_MODULE, "getrecursionlimit", rs_sys_getrecursionlimit, [])
+    }
+}
+
+impl SetRecursionLimitFn {
+    pub fn create() -> rs::Func {
+        module_func!(strings::SYS_MODULE, "setrecursionlimit", rs_sys_setrecursionlimit, ["limit"])
+    }
+}
+
+impl ExitFn {
+    pub fn create() -> rs::Func {
+        module_func!(strings::SYS_MODULE, "exit", rs_sys_exit, ["status"])
+    }
+}
+
+impl SetTraceFn {
+    pub fn create() -> rs::Func {
+        module_func!(strings::SYS_MODULE, "settrace", rs_sys_settrace, ["tracefunc"])
+    }
+}
+
+impl GetTraceFn {
+    pub fn create() -> rs::Func {
+        module_func!(strings::SYS_MODULE, "gettrace", rs_sys_gettrace, [])
+    }
+}
+
+impl SetProfileFn {
+    pub fn create() -> rs::Func {
+        module_func!(strings::SYS_MODULE, "setprofile", rs_sys_setprofile, ["profilefunc"])
+    }
+}
+
+impl GetProfileFn {
+    pub fn create() -> rs::Func {
+        module_func!(strings::SYS_MODULE, "getprofile", rs_sys_getprofile, [])
+    }
+}
+
+
+/// `sys.intern(string)` - the shared string object equal to `string`
+fn rs_sys_intern(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_module" => strings::SYS_MODULE, "function" => "intern");
+    check_args(1, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    let string = pos_args.op_getitem(&rt, &rt.int(0))?;
+    rt.intern_object(&string)
+}
+
+
+/// `sys.getrecursionlimit()` - the maximum depth of the python call stack
+fn rs_sys_getrecursionlimit(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_module" => strings::SYS_MODULE, "function" => "getrecursionlimit");
+    check_args(0, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    Ok(rt.int(rt.recursion_limit()))
+}
+
+
+/// `sys.setrecursionlimit(limit)` - set the maximum depth of the python call stack.
+/// Running out of native stack still raises a `RecursionError` before `limit` is
+/// reached, so a high limit is safe.
+fn rs_sys_setrecursionlimit(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_module" => strings::SYS_MODULE, "function" => "setrecursionlimit");
+    check_args(1, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    let limit = pos_args.op_getitem(&rt, &rt.int(0))?;
+    let limit = match limit.as_ref() {
+        &Type::Int(ref int) => int.value.0.to_usize(),
+        other => return Err(Error::typerr(&format!(
+            "'{}' object cannot be interpreted as an integer", other.debug_name())))
+    };
+
+    match limit {
+        Some(limit) if limit >= 1 => {
+            rt.set_recursion_limit(limit);
+            Ok(rt.none())
+        },
+        _ => Err(Error::value("recursion limit must be greater or equal than 1"))
+    }
+}
+
+
+/// `sys.exit([status])` - raise `SystemExit` to exit the process with `status`.
+/// `None` is a successful exit, any other object that is not an integer is
+/// printed to stderr and the exit status is 1.
+fn rs_sys_exit(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_module" => strings::SYS_MODULE, "function" => "exit");
+    let arg_count = check_args_range(0..2, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    if arg_count == 0 {
+        return Err(Error::system_exit(0, ""))
+    }
+
+    let status = pos_args.op_getitem(&rt, &rt.int(0))?;
+    match status.as_ref() {
+        &Type::None(_) => Err(Error::system_exit(0, "")),
+        &Type::Bool(ref boolean) => Err(Error::system_exit(boolean.value.0.to_i64().unwrap_or(1), "")),
+        &Type::Int(ref int) => match int.value.0.to_i64() {
+            Some(status) => Err(Error::system_exit(status, "")),
+            None => Err(Error::overflow("exit status does not fit in a native integer"))
+        },
+        _ => Err(Error::system_exit(1, &status.native_str()?))
+    }
+}
+
+
+/// The hook function in the first argument of `pos_args`, `None` removes the hook
+fn hook_arg(rt: &Runtime, pos_args: &ObjectRef) -> RtResult<Option<ObjectRef>> {
+    let func = pos_args.op_getitem(&rt, &rt.int(0))?;
+    match func.as_ref() {
+        &Type::None(_) => Ok(None),
+        &Type::Function(_) => Ok(Some(func.clone())),
+        other => Err(Error::typerr(&format!(
+            "'{}' object is not callable", other.debug_name())))
+    }
+}
+
+
+/// `sys.settrace(tracefunc)` - call `tracefunc(frame, event, arg)` when a python
+/// function is called, `None` turns tracing off. Takes effect at the next call.
+fn rs_sys_settrace(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_module" => strings::SYS_MODULE, "function" => "settrace");
+    check_args(1, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    rt.set_trace_func(hook_arg(rt, pos_args)?);
+    Ok(rt.none())
+}
+
+
+/// `sys.gettrace()` - the function set by `sys.settrace()` or `None`
+fn rs_sys_gettrace(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_module" => strings::SYS_MODULE, "function" => "gettrace");
+    check_args(0, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    Ok(rt.trace_func().unwrap_or(rt.none()))
+}
+
+
+/// `sys.setprofile(profilefunc)` - call `profilefunc(frame, event, arg)` when a
+/// function is called or returns, `None` turns profiling off.
+fn rs_sys_setprofile(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_module" => strings::SYS_MODULE, "function" => "setprofile");
+    check_args(1, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    rt.set_profile_func(hook_arg(rt, pos_args)?);
+    Ok(rt.none())
+}
+
+
+/// `sys.getprofile()` - the function set by `sys.setprofile()` or `None`
+fn rs_sys_getprofile(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_module" => strings::SYS_MODULE, "function" => "getprofile");
+    check_args(0, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    Ok(rt.profile_func().unwrap_or(rt.none()))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use ::api::method::GetAttr;
+    use ::api::result::ErrorType;
+    use ::modules::call;
+    use ::system::RECURSION_LIMIT;
+    use super::*;
+
+    fn attr(rt: &Runtime, name: &str) -> ObjectResult {
+        rt.import_module(strings::SYS_MODULE)?.op_getattr(&rt, &rt.str(name))
+    }
+
+    /// Exit status and message of the `SystemExit` raised by `sys.exit(*args)`
+    fn exit(rt: &Runtime, args: Vec<ObjectRef>) -> (i64, String) {
+        match call(rt, &create_module(rt).unwrap(), "exit", args) {
+            Err(Error(ErrorType::SystemExit(status), message)) => (status, message),
+            other => panic!("Expected SystemExit, got {:?}", other)
+        }
+    }
+
+    #[test]
+    fn intern() {
+        let rt = Runtime::new();
+        let module = create_module(&rt).unwrap();
+        let first = rt.str("not_yet_interned");
+        let second = rt.str("not_yet_interned");
+        assert!(first.id() != second.id());
+        assert!(!rt.is_interned(&first));
+
+        let interned = call(&rt, &module, "intern", vec![first.clone()]).unwrap();
+        assert_eq!(interned.id(), first.id());
+        assert!(rt.is_interned(&first));
+
+        let interned = call(&rt, &module, "intern", vec![second.clone()]).unwrap();
+        assert_eq!(interned.id(), first.id());
+        assert_eq!(rt.intern("not_yet_interned").id(), first.id());
+    }
+
+    #[test]
+    #[should_panic]
+    fn intern_not_a_str() {
+        let rt = Runtime::new();
+        let module = create_module(&rt).unwrap();
+        call(&rt, &module, "intern", vec![rt.int(1)]).unwrap();
+    }
+
+    #[test]
+    fn recursion_limit() {
+        let rt = Runtime::new();
+        let module = create_module(&rt).unwrap();
+        assert_eq!(call(&rt, &module, "getrecursionlimit", vec![]).unwrap(), rt.int(RECURSION_LIMIT));
+
+        call(&rt, &module, "setrecursionlimit", vec![rt.int(1000)]).unwrap();
+        assert_eq!(rt.recursion_limit(), 1000);
+        assert_eq!(call(&rt, &module, "getrecursionlimit", vec![]).unwrap(), rt.int(1000));
+
+        assert!(call(&rt, &module, "setrecursionlimit", vec![rt.int(0)]).is_err());
+        assert!(call(&rt, &module, "setrecursionlimit", vec![rt.str("1")]).is_err());
+        assert_eq!(rt.recursion_limit(), 1000);
+    }
+
+    #[test]
+    fn configure_argv() {
+        let rt = Runtime::new();
+        assert_eq!(attr(&rt, "argv").unwrap(), rt.list(vec![rt.str("")]));
+
+        configure(&rt, &Mode::File, &["script.py", "-v", "x"]).unwrap();
+        assert_eq!(attr(&rt, "argv").unwrap(),
+                   rt.list(vec![rt.str("script.py"), rt.str("-v"), rt.str("x")]));
+
+        configure(&rt, &Mode::Command(String::from("pass")), &["x"]).unwrap();
+        assert_eq!(attr(&rt, "argv").unwrap(), rt.list(vec![rt.str("-c"), rt.str("x")]));
+        assert_eq!(attr(&rt, "path").unwrap(), rt.list(vec![rt.str("")]));
+    }
+
+    #[test]
+    fn modules() {
+        let rt = Runtime::new();
+        let modules = attr(&rt, "modules").unwrap();
+        let sys = modules.op_getitem(&rt, &rt.str(strings::SYS_MODULE)).unwrap();
+        assert_eq!(sys.id(), rt.import_module(strings::SYS_MODULE).unwrap().id());
+    }
+
+    #[test]
+    fn version_and_platform() {
+        let rt = Runtime::new();
+        let number = strings::VERSION.trim().trim_left_matches('v');
+        assert!(version().starts_with(number));
+
+        let info = attr(&rt, "version_info").unwrap();
+        assert_eq!(info.op_getitem(&rt, &rt.int(3)).unwrap(), rt.str("final"));
+
+        assert_eq!(attr(&rt, "maxsize").unwrap(), rt.int(isize::max_value()));
+        assert!(attr(&rt, "platform").is_ok());
+        assert!(attr(&rt, "byteorder").unwrap() == rt.str("little") ||
+                attr(&rt, "byteorder").unwrap() == rt.str("big"));
+        assert!(attr(&rt, "stdin").is_ok());
+    }
+
+    #[test]
+    fn exit_status() {
+        let rt = Runtime::new();
+        assert_eq!(exit(&rt, vec![]), (0, String::new()));
+        assert_eq!(exit(&rt, vec![rt.none()]), (0, String::new()));
+        assert_eq!(exit(&rt, vec![rt.int(3)]), (3, String::new()));
+        assert_eq!(exit(&rt, vec![rt.str("failed")]), (1, String::from("failed")));
+    }
+
+    #[test]
+    fn trace_and_profile_hooks() {
+        let rt = Runtime::new();
+        let module = create_module(&rt).unwrap();
+        let func = rt.get_builtin("print");
+
+        assert_eq!(call(&rt, &module, "gettrace", vec![]).unwrap(), rt.none());
+        call(&rt, &module, "settrace", vec![func.clone()]).unwrap();
+        assert_eq!(rt.trace_func().unwrap().id(), func.id());
+        assert_eq!(call(&rt, &module, "gettrace", vec![]).unwrap().id(), func.id());
+        call(&rt, &module, "settrace", vec![rt.none()]).unwrap();
+        assert!(rt.trace_func().is_none());
+
+        call(&rt, &module, "setprofile", vec![func.clone()]).unwrap();
+        assert_eq!(call(&rt, &module, "getprofile", vec![]).unwrap().id(), func.id());
+        assert!(call(&rt, &module, "setprofile", vec![rt.int(1)]).is_err());
+        assert_eq!(rt.profile_func().unwrap().id(), func.id());
+    }
+}
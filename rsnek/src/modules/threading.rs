@@ -0,0 +1,934 @@
+//! `threading` - Run python functions on OS threads that share one `Runtime`
+//!
+//! ```ignore
+//! def work(items, lock):
+//!     lock.acquire()
+//!     items.append(1)
+//!     lock.release()
+//!
+//! items = []
+//! thread = threading.Thread(work, (items, threading.Lock()))
+//! thread.start()
+//! thread.join()
+//! ```
+//!
+//! `Lock`, `RLock`, `Event`, `Condition` and `Queue` are instances whose methods
+//! are native functions over `std::sync` primitives.
+//!
+//! Each `Thread` runs its target in a new `Interpreter` that starts with a copy of
+//! the names bound when `start` was called, so the target can call the other
+//! functions of its module. Names bound afterwards on either side are not seen by
+//! the other, objects the thread shares with its creator are passed through `args`. Starting a thread
+//! requires rsnek to be built with the `rsnek_multithreaded` feature, without it
+//! the objects are not `Send` and `Thread.start` raises a `RuntimeError`.
+//!
+//! The cycle collector does not run while a started thread is running, see
+//! `runtime::gc`.
+//!
+use std::collections::VecDeque;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self, JoinHandle, ThreadId};
+
+use num::ToPrimitive;
+
+use ::api::method::{BooleanCast, GetItem};
+use ::api::result::{Error, ObjectResult, RtResult};
+use ::api::RtObject as ObjectRef;
+use ::modules::builtins::Type;
+use ::modules::precondition::{check_args, check_args_range, check_kwargs};
+use ::modules::register;
+use ::resources::strings;
+use ::runtime::Runtime;
+use ::runtime::traits::{BooleanProvider, IntegerProvider, ModuleProvider, NoneProvider,
+                        ObjectProvider};
+use ::system::primitives as rs;
+use ::system::primitives::SignatureBuilder;
+use ::system::sync::Ref;
+
+
+/// Create the `threading` module object with all of its functions
+pub fn create_module(rt: &Runtime) -> ObjectResult {
+    let module = rt.module(rs::None());
+
+    let funcs = vec![
+        ThreadFn::create(),
+        LockFn::create(),
+        RLockFn::create(),
+        EventFn::create(),
+        ConditionFn::create(),
+        QueueFn::create(),
+    ];
+
+    register(rt, &module, funcs)?;
+
+    Ok(module)
+}
+
+
+pub struct ThreadFn;
+pub struct LockFn;
+pub struct RLockFn;
+pub struct EventFn;
+pub struct ConditionFn;
+pub struct QueueFn;
+
+
+impl ThreadFn {
+    pub fn create() -> rs::Func {
+        module_func!(strings::THREADING_MODULE, "Thread", rs_threading_thread, ["target", "args"])
+    }
+}
+
+impl LockFn {
+    pub fn create() -> rs::Func {
+        module_func!(strings::THREADING_MODULE, "Lock", rs_threading_lock, [])
+    }
+}
+
+impl RLockFn {
+    pub fn create() -> rs::Func {
+        module_func!(strings::THREADING_MODULE, "RLock", rs_threading_rlock, [])
+    }
+}
+
+impl EventFn {
+    pub fn create() -> rs::Func {
+        module_func!(strings::THREADING_MODULE, "Event", rs_threading_event, [])
+    }
+}
+
+impl ConditionFn {
+    pub fn create() -> rs::Func {
+        module_func!(strings::THREADING_MODULE, "Condition", rs_threading_condition, [])
+    }
+}
+
+impl QueueFn {
+    pub fn create() -> rs::Func {
+        module_func!(strings::THREADING_MODULE, "Queue", rs_threading_queue, [])
+    }
+}
+
+
+/// Create an object with `methods` as its attributes
+pub fn instance(rt: &Runtime, methods: Vec<rs::Func>) -> ObjectResult {
+    let object = rt.object(rs::None());
+    register(rt, &object, methods)?;
+    Ok(object)
+}
+
+
+/// Lock a mutex that only guards native state, a panic while it was held cannot
+/// leave that state inconsistent so poisoning is ignored.
+pub fn guard<T>(mutex: &Mutex<T>) -> MutexGuard<T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+
+fn wait<'a, T>(condvar: &Condvar, guard: MutexGuard<'a, T>) -> MutexGuard<'a, T> {
+    condvar.wait(guard).unwrap_or_else(PoisonError::into_inner)
+}
+
+
+/// The optional `blocking` argument of the `acquire` methods
+fn blocking_arg(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> RtResult<bool> {
+    let arg_count = check_args_range(0..2, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    match arg_count {
+        1 => pos_args.op_getitem(&rt, &rt.int(0))?.native_bool(),
+        _ => Ok(true)
+    }
+}
+
+
+fn no_args(pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> RtResult<rs::None> {
+    check_args(0, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)
+}
+
+
+// ---------------------------------------
+//  Lock
+// ---------------------------------------
+
+struct LockState {
+    locked: Mutex<bool>,
+    released: Condvar,
+}
+
+
+impl LockState {
+    fn new() -> Self {
+        LockState {
+            locked: Mutex::new(false),
+            released: Condvar::new(),
+        }
+    }
+
+    fn acquire(&self, blocking: bool) -> bool {
+        let mut locked = guard(&self.locked);
+
+        while *locked {
+            if !blocking {
+                return false;
+            }
+            locked = wait(&self.released, locked);
+        }
+
+        *locked = true;
+        true
+    }
+
+    fn release(&self) -> RtResult<rs::None> {
+        let mut locked = guard(&self.locked);
+        if !*locked {
+            return Err(Error::runtime("release unlocked lock"));
+        }
+
+        *locked = false;
+        self.released.notify_one();
+        Ok(rs::None())
+    }
+
+    fn is_locked(&self) -> bool {
+        *guard(&self.locked)
+    }
+}
+
+
+/// `threading.Lock()` - a lock that any thread may release
+fn rs_threading_lock(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_module" => strings::THREADING_MODULE, "function" => "Lock");
+    no_args(&pos_args, &starargs, &kwargs)?;
+
+    let state = Arc::new(LockState::new());
+    let acquire = state.clone();
+    let release = state.clone();
+    let locked = state.clone();
+
+    instance(rt, vec![
+        module_func!(strings::THREADING_MODULE, "acquire", move |rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef| {
+            let blocking = blocking_arg(rt, pos_args, starargs, kwargs)?;
+            Ok(rt.bool(acquire.acquire(blocking)))
+        }, ["blocking"]),
+        module_func!(strings::THREADING_MODULE, "release", move |rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef| {
+            no_args(pos_args, starargs, kwargs)?;
+            release.release()?;
+            Ok(rt.none())
+        }, []),
+        module_func!(strings::THREADING_MODULE, "locked", move |rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef| {
+            no_args(pos_args, starargs, kwargs)?;
+            Ok(rt.bool(locked.is_locked()))
+        }, []),
+    ])
+}
+
+
+// ---------------------------------------
+//  RLock
+// ---------------------------------------
+
+struct RLockState {
+    /// The owning thread and how many times it has acquired the lock
+    owner: Mutex<(Option<ThreadId>, usize)>,
+    released: Condvar,
+}
+
+
+impl RLockState {
+    fn new() -> Self {
+        RLockState {
+            owner: Mutex::new((None, 0)),
+            released: Condvar::new(),
+        }
+    }
+
+    fn acquire(&self, blocking: bool) -> bool {
+        let me = thread::current().id();
+        let mut owner = guard(&self.owner);
+
+        loop {
+            match owner.0 {
+                Some(id) if id == me => break,
+                None => break,
+                Some(_) if !blocking => return false,
+                Some(_) => owner = wait(&self.released, owner),
+            }
+        }
+
+        owner.0 = Some(me);
+        owner.1 += 1;
+        true
+    }
+
+    fn release(&self) -> RtResult<rs::None> {
+        let mut owner = guard(&self.owner);
+        match owner.0 {
+            Some(id) if id == thread::current().id() => {},
+            _ => return Err(Error::runtime("cannot release un-acquired lock"))
+        }
+
+        owner.1 -= 1;
+        if owner.1 == 0 {
+            owner.0 = None;
+            self.released.notify_one();
+        }
+        Ok(rs::None())
+    }
+}
+
+
+/// `threading.RLock()` - a lock the owning thread may acquire again, it is released
+/// once every acquire has been matched by a release.
+fn rs_threading_rlock(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_module" => strings::THREADING_MODULE, "function" => "RLock");
+    no_args(&pos_args, &starargs, &kwargs)?;
+
+    let state = Arc::new(RLockState::new());
+    let acquire = state.clone();
+    let release = state.clone();
+
+    instance(rt, vec![
+        module_func!(strings::THREADING_MODULE, "acquire", move |rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef| {
+            let blocking = blocking_arg(rt, pos_args, starargs, kwargs)?;
+            Ok(rt.bool(acquire.acquire(blocking)))
+        }, ["blocking"]),
+        module_func!(strings::THREADING_MODULE, "release", move |rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef| {
+            no_args(pos_args, starargs, kwargs)?;
+            release.release()?;
+            Ok(rt.none())
+        }, []),
+    ])
+}
+
+
+// ---------------------------------------
+//  Event
+// ---------------------------------------
+
+struct EventState {
+    flag: Mutex<bool>,
+    changed: Condvar,
+}
+
+
+impl EventState {
+    fn new() -> Self {
+        EventState {
+            flag: Mutex::new(false),
+            changed: Condvar::new(),
+        }
+    }
+
+    fn set(&self, value: bool) {
+        *guard(&self.flag) = value;
+        self.changed.notify_all();
+    }
+
+    fn is_set(&self) -> bool {
+        *guard(&self.flag)
+    }
+
+    fn wait(&self) {
+        let mut flag = guard(&self.flag);
+        while !*flag {
+            flag = wait(&self.changed, flag);
+        }
+    }
+}
+
+
+/// `threading.Event()` - a flag threads can wait on until it is set
+fn rs_threading_event(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_module" => strings::THREADING_MODULE, "function" => "Event");
+    no_args(&pos_args, &starargs, &kwargs)?;
+
+    let state = Arc::new(EventState::new());
+    let set = state.clone();
+    let clear = state.clone();
+    let is_set = state.clone();
+    let wait = state.clone();
+
+    instance(rt, vec![
+        module_func!(strings::THREADING_MODULE, "set", move |rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef| {
+            no_args(pos_args, starargs, kwargs)?;
+            set.set(true);
+            Ok(rt.none())
+        }, []),
+        module_func!(strings::THREADING_MODULE, "clear", move |rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef| {
+            no_args(pos_args, starargs, kwargs)?;
+            clear.set(false);
+            Ok(rt.none())
+        }, []),
+        module_func!(strings::THREADING_MODULE, "is_set", move |rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef| {
+            no_args(pos_args, starargs, kwargs)?;
+            Ok(rt.bool(is_set.is_set()))
+        }, []),
+        module_func!(strings::THREADING_MODULE, "wait", move |rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef| {
+            no_args(pos_args, starargs, kwargs)?;
+            wait.wait();
+            Ok(rt.bool(true))
+        }, []),
+    ])
+}
+
+
+// ---------------------------------------
+//  Condition
+// ---------------------------------------
+
+struct ConditionState {
+    lock: LockState,
+    /// The number of waiting threads and how many of them have been notified
+    waiters: Mutex<(usize, usize)>,
+    notified: Condvar,
+}
+
+
+impl ConditionState {
+    fn new() -> Self {
+        ConditionState {
+            lock: LockState::new(),
+            waiters: Mutex::new((0, 0)),
+            notified: Condvar::new(),
+        }
+    }
+
+    /// Release the lock until notified, then acquire it again. The thread is counted
+    /// as a waiter before the lock is released so a notify in between is not lost.
+    fn wait(&self) -> RtResult<rs::None> {
+        if !self.lock.is_locked() {
+            return Err(Error::runtime("cannot wait on un-acquired lock"));
+        }
+
+        guard(&self.waiters).0 += 1;
+        self.lock.release()?;
+
+        {
+            let mut waiters = guard(&self.waiters);
+            while waiters.1 == 0 {
+                waiters = wait(&self.notified, waiters);
+            }
+            waiters.0 -= 1;
+            waiters.1 -= 1;
+        }
+
+        self.lock.acquire(true);
+        Ok(rs::None())
+    }
+
+    fn notify(&self, count: Option<usize>) -> RtResult<rs::None> {
+        if !self.lock.is_locked() {
+            return Err(Error::runtime("cannot notify on un-acquired lock"));
+        }
+
+        let mut waiters = guard(&self.waiters);
+        let unnotified = waiters.0 - waiters.1;
+        waiters.1 += match count {
+            Some(count) if count < unnotified => count,
+            _ => unnotified
+        };
+
+        self.notified.notify_all();
+        Ok(rs::None())
+    }
+}
+
+
+/// `threading.Condition()` - a condition variable with its own lock
+fn rs_threading_condition(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_module" => strings::THREADING_MODULE, "function" => "Condition");
+    no_args(&pos_args, &starargs, &kwargs)?;
+
+    let state = Arc::new(ConditionState::new());
+    let acquire = state.clone();
+    let release = state.clone();
+    let wait = state.clone();
+    let notify = state.clone();
+    let notify_all = state.clone();
+
+    instance(rt, vec![
+        module_func!(strings::THREADING_MODULE, "acquire", move |rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef| {
+            let blocking = blocking_arg(rt, pos_args, starargs, kwargs)?;
+            Ok(rt.bool(acquire.lock.acquire(blocking)))
+        }, ["blocking"]),
+        module_func!(strings::THREADING_MODULE, "release", move |rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef| {
+            no_args(pos_args, starargs, kwargs)?;
+            release.lock.release()?;
+            Ok(rt.none())
+        }, []),
+        module_func!(strings::THREADING_MODULE, "wait", move |rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef| {
+            no_args(pos_args, starargs, kwargs)?;
+            wait.wait()?;
+            Ok(rt.bool(true))
+        }, []),
+        module_func!(strings::THREADING_MODULE, "notify", move |rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef| {
+            let arg_count = check_args_range(0..2, &pos_args)?;
+            check_args(0, &starargs)?;
+            check_kwargs(0, &kwargs)?;
+
+            let count = match arg_count {
+                1 => match pos_args.op_getitem(&rt, &rt.int(0))?.as_ref() {
+                    &Type::Int(ref int) => int.value.0.to_usize(),
+                    other => return Err(Error::typerr(&format!(
+                        "'{}' object cannot be interpreted as an integer", other.debug_name())))
+                },
+                _ => Some(1)
+            };
+
+            notify.notify(Some(count.unwrap_or(0)))?;
+            Ok(rt.none())
+        }, ["n"]),
+        module_func!(strings::THREADING_MODULE, "notify_all", move |rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef| {
+            no_args(pos_args, starargs, kwargs)?;
+            notify_all.notify(None)?;
+            Ok(rt.none())
+        }, []),
+    ])
+}
+
+
+// ---------------------------------------
+//  Queue
+// ---------------------------------------
+
+struct QueueState {
+    items: Mutex<VecDeque<ObjectRef>>,
+    available: Condvar,
+}
+
+
+impl QueueState {
+    fn new() -> Self {
+        QueueState {
+            items: Mutex::new(VecDeque::new()),
+            available: Condvar::new(),
+        }
+    }
+
+    fn put(&self, item: ObjectRef) {
+        guard(&self.items).push_back(item);
+        self.available.notify_one();
+    }
+
+    fn get(&self) -> ObjectRef {
+        let mut items = guard(&self.items);
+        loop {
+            match items.pop_front() {
+                Some(item) => return item,
+                None => items = wait(&self.available, items)
+            }
+        }
+    }
+
+    fn len(&self) -> usize {
+        guard(&self.items).len()
+    }
+}
+
+
+/// `threading.Queue()` - a FIFO queue where `get` blocks until an item is available
+fn rs_threading_queue(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_module" => strings::THREADING_MODULE, "function" => "Queue");
+    no_args(&pos_args, &starargs, &kwargs)?;
+
+    let state = Arc::new(QueueState::new());
+    let put = state.clone();
+    let get = state.clone();
+    let qsize = state.clone();
+    let empty = state.clone();
+
+    instance(rt, vec![
+        module_func!(strings::THREADING_MODULE, "put", move |rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef| {
+            check_args(1, &pos_args)?;
+            check_args(0, &starargs)?;
+            check_kwargs(0, &kwargs)?;
+
+            put.put(pos_args.op_getitem(&rt, &rt.int(0))?);
+            Ok(rt.none())
+        }, ["item"]),
+        module_func!(strings::THREADING_MODULE, "get", move |_: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef| {
+            no_args(pos_args, starargs, kwargs)?;
+            Ok(get.get())
+        }, []),
+        module_func!(strings::THREADING_MODULE, "qsize", move |rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef| {
+            no_args(pos_args, starargs, kwargs)?;
+            Ok(rt.int(qsize.len()))
+        }, []),
+        module_func!(strings::THREADING_MODULE, "empty", move |rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef| {
+            no_args(pos_args, starargs, kwargs)?;
+            Ok(rt.bool(empty.len() == 0))
+        }, []),
+    ])
+}
+
+
+// ---------------------------------------
+//  Thread
+// ---------------------------------------
+
+struct ThreadState {
+    target: ObjectRef,
+    args: Vec<ObjectRef>,
+    handle: Mutex<Option<JoinHandle<()>>>,
+    alive: Arc<AtomicBool>,
+}
+
+
+impl ThreadState {
+    #[cfg(feature="rsnek_multithreaded")]
+    fn start(&self, rt: &Runtime) -> RtResult<rs::None> {
+        use ::modules::traceback;
+        use ::runtime::Interpreter;
+
+        let mut handle = guard(&self.handle);
+        if handle.is_some() || self.alive.load(Ordering::SeqCst) {
+            return Err(Error::runtime("threads can only be started once"));
+        }
+
+        let thread_rt = rt.clone();
+        let globals = Interpreter::caller_namespace();
+        let target = self.target.clone();
+        let args = self.args.clone();
+        let alive = self.alive.clone();
+        alive.store(true, Ordering::SeqCst);
+        rt.gc().thread_started();
+
+        let spawned = thread::Builder::new().spawn(move || {
+            let rt = thread_rt;
+            {
+                let target = target;
+                let mut interpreter = match globals {
+                    Some(ns) => Interpreter::with_namespace(&rt, ns),
+                    None => Interpreter::new(&rt)
+                };
+                if let Err(err) = interpreter.call(&rt, &target, args) {
+                    let _ = traceback::print(&rt, &interpreter.traceback(&err));
+                    error!("Thread"; "action" => "call", "error" => format!("{:?}", err));
+                }
+            }
+
+            // The interpreter and the target are dropped so the collector can run again
+            rt.gc().thread_finished();
+            alive.store(false, Ordering::SeqCst);
+        });
+
+        match spawned {
+            Ok(spawned) => {
+                *handle = Some(spawned);
+                Ok(rs::None())
+            },
+            Err(err) => {
+                rt.gc().thread_finished();
+                self.alive.store(false, Ordering::SeqCst);
+                Err(Error::runtime(&format!("can't start new thread: {}", err)))
+            }
+        }
+    }
+
+    #[cfg(not(feature="rsnek_multithreaded"))]
+    #[allow(unused_variables)]
+    fn start(&self, rt: &Runtime) -> RtResult<rs::None> {
+        Err(Error::runtime("threads require rsnek to be built with the rsnek_multithreaded feature"))
+    }
+
+    fn join(&self) -> RtResult<rs::None> {
+        let handle = guard(&self.handle).take();
+        match handle {
+            Some(handle) => match handle.join() {
+                Ok(_) => Ok(rs::None()),
+                Err(_) => Err(Error::runtime("thread panicked"))
+            },
+            None => Err(Error::runtime("cannot join thread before it is started"))
+        }
+    }
+}
+
+
+/// `threading.Thread(target, args=())` - call `target(*args)` on a new thread once
+/// `start` is called.
+fn rs_threading_thread(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_module" => strings::THREADING_MODULE, "function" => "Thread");
+    let arg_count = check_args_range(1..3, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    let target = pos_args.op_getitem(&rt, &rt.int(0))?;
+    let args = match arg_count {
+        2 => {
+            let args = pos_args.op_getitem(&rt, &rt.int(1))?;
+            let args = match args.as_ref() {
+                &Type::Tuple(ref tuple) => tuple.value.0.clone(),
+                &Type::List(ref list) => {
+                    let borrowed: Ref<rs::List> = list.value.0.borrow();
+                    borrowed.clone()
+                },
+                other => return Err(Error::typerr(&format!(
+                    "Thread() args must be a tuple or list, not {}", other.debug_name())))
+            };
+            args
+        },
+        _ => vec![]
+    };
+
+    let state = Arc::new(ThreadState {
+        target: target,
+        args: args,
+        handle: Mutex::new(None),
+        alive: Arc::new(AtomicBool::new(false)),
+    });
+    let start = state.clone();
+    let join = state.clone();
+    let is_alive = state.clone();
+
+    instance(rt, vec![
+        module_func!(strings::THREADING_MODULE, "start", move |rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef| {
+            no_args(pos_args, starargs, kwargs)?;
+            start.start(rt)?;
+            Ok(rt.none())
+        }, []),
+        module_func!(strings::THREADING_MODULE, "join", move |rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef| {
+            no_args(pos_args, starargs, kwargs)?;
+            join.join()?;
+            Ok(rt.none())
+        }, []),
+        module_func!(strings::THREADING_MODULE, "is_alive", move |rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef| {
+            no_args(pos_args, starargs, kwargs)?;
+            Ok(rt.bool(is_alive.alive.load(Ordering::SeqCst)))
+        }, []),
+    ])
+}
+
+
+#[cfg(test)]
+mod tests {
+    use ::api::method::GetAttr;
+    use ::modules::call;
+    use ::runtime::traits::{StringProvider, TupleProvider};
+    use super::*;
+
+    fn create(rt: &Runtime, name: &str) -> ObjectResult {
+        let module = create_module(rt)?;
+        call(rt, &module, name, vec![])
+    }
+
+    #[test]
+    fn lock() {
+        let rt = Runtime::new();
+        let lock = create(&rt, "Lock").unwrap();
+
+        assert_eq!(call(&rt, &lock, "acquire", vec![]).unwrap(), rt.bool(true));
+        assert_eq!(call(&rt, &lock, "locked", vec![]).unwrap(), rt.bool(true));
+        assert_eq!(call(&rt, &lock, "acquire", vec![rt.bool(false)]).unwrap(), rt.bool(false));
+
+        call(&rt, &lock, "release", vec![]).unwrap();
+        assert_eq!(call(&rt, &lock, "locked", vec![]).unwrap(), rt.bool(false));
+        assert!(call(&rt, &lock, "release", vec![]).is_err());
+    }
+
+    #[test]
+    fn rlock() {
+        let rt = Runtime::new();
+        let lock = create(&rt, "RLock").unwrap();
+
+        assert_eq!(call(&rt, &lock, "acquire", vec![]).unwrap(), rt.bool(true));
+        assert_eq!(call(&rt, &lock, "acquire", vec![rt.bool(false)]).unwrap(), rt.bool(true));
+        call(&rt, &lock, "release", vec![]).unwrap();
+        call(&rt, &lock, "release", vec![]).unwrap();
+        assert!(call(&rt, &lock, "release", vec![]).is_err());
+    }
+
+    #[test]
+    fn event() {
+        let rt = Runtime::new();
+        let event = create(&rt, "Event").unwrap();
+
+        assert_eq!(call(&rt, &event, "is_set", vec![]).unwrap(), rt.bool(false));
+        call(&rt, &event, "set", vec![]).unwrap();
+        assert_eq!(call(&rt, &event, "wait", vec![]).unwrap(), rt.bool(true));
+        call(&rt, &event, "clear", vec![]).unwrap();
+        assert_eq!(call(&rt, &event, "is_set", vec![]).unwrap(), rt.bool(false));
+    }
+
+    #[test]
+    fn condition_requires_lock() {
+        let rt = Runtime::new();
+        let condition = create(&rt, "Condition").unwrap();
+
+        assert!(call(&rt, &condition, "notify", vec![]).is_err());
+        assert!(call(&rt, &condition, "wait", vec![]).is_err());
+
+        call(&rt, &condition, "acquire", vec![]).unwrap();
+        call(&rt, &condition, "notify_all", vec![]).unwrap();
+        call(&rt, &condition, "release", vec![]).unwrap();
+    }
+
+    #[test]
+    fn queue() {
+        let rt = Runtime::new();
+        let queue = create(&rt, "Queue").unwrap();
+
+        assert_eq!(call(&rt, &queue, "empty", vec![]).unwrap(), rt.bool(true));
+        call(&rt, &queue, "put", vec![rt.int(1)]).unwrap();
+        call(&rt, &queue, "put", vec![rt.int(2)]).unwrap();
+        assert_eq!(call(&rt, &queue, "qsize", vec![]).unwrap(), rt.int(2));
+
+        assert_eq!(call(&rt, &queue, "get", vec![]).unwrap(), rt.int(1));
+        assert_eq!(call(&rt, &queue, "get", vec![]).unwrap(), rt.int(2));
+        assert_eq!(call(&rt, &queue, "empty", vec![]).unwrap(), rt.bool(true));
+    }
+
+    #[test]
+    #[cfg(not(feature="rsnek_multithreaded"))]
+    fn thread_requires_feature() {
+        let rt = Runtime::new();
+        let module = create_module(&rt).unwrap();
+        let target = module.op_getattr(&rt, &rt.str("Lock")).unwrap();
+        let thread = call(&rt, &module, "Thread", vec![target]).unwrap();
+
+        assert!(call(&rt, &thread, "start", vec![]).is_err());
+        assert!(call(&rt, &thread, "join", vec![]).is_err());
+    }
+
+    /// Mutate shared lists and dicts from many OS threads at once
+    #[cfg(feature="rsnek_multithreaded")]
+    mod stress {
+        use std::thread;
+
+        use ::api::method::{Append, GetItem, Length, SetItem};
+        use ::runtime::traits::{DefaultListProvider, DefaultDictProvider, IntegerProvider,
+                                ListProvider, StringProvider};
+        use super::*;
+
+        const THREADS: usize = 16;
+        const ITERATIONS: usize = 256;
+
+        fn len(rt: &Runtime, object: &ObjectRef) -> usize {
+            match object.op_len(&rt).unwrap().as_ref() {
+                &Type::Int(ref int) => int.value.0.to_usize().unwrap(),
+                _ => unreachable!()
+            }
+        }
+
+        #[test]
+        fn list_append() {
+            let rt = Runtime::new();
+            let list = rt.default_list();
+
+            let handles: Vec<_> = (0..THREADS).map(|_| {
+                let rt = rt.clone();
+                let list = list.clone();
+                thread::spawn(move || {
+                    for i in 0..ITERATIONS {
+                        list.meth_append(&rt, &rt.int(i)).unwrap();
+                    }
+                })
+            }).collect();
+
+            for handle in handles {
+                handle.join().unwrap();
+            }
+
+            assert_eq!(len(&rt, &list), THREADS * ITERATIONS);
+        }
+
+        #[test]
+        fn dict_setitem() {
+            let rt = Runtime::new();
+            let dict = rt.default_dict();
+
+            let handles: Vec<_> = (0..THREADS).map(|t| {
+                let rt = rt.clone();
+                let dict = dict.clone();
+                thread::spawn(move || {
+                    for i in 0..ITERATIONS {
+                        let key = rt.str(format!("{}-{}", t, i));
+                        dict.op_setitem(&rt, &key, &rt.int(i)).unwrap();
+                    }
+                })
+            }).collect();
+
+            for handle in handles {
+                handle.join().unwrap();
+            }
+
+            assert_eq!(len(&rt, &dict), THREADS * ITERATIONS);
+            assert_eq!(dict.op_getitem(&rt, &rt.str("0-0")).unwrap(), rt.int(0));
+        }
+
+        #[test]
+        fn shared_list_and_dict() {
+            let rt = Runtime::new();
+            let list = rt.list(vec![]);
+            let dict = rt.default_dict();
+
+            let handles: Vec<_> = (0..THREADS).map(|t| {
+                let rt = rt.clone();
+                let list = list.clone();
+                let dict = dict.clone();
+                thread::spawn(move || {
+                    for i in 0..ITERATIONS {
+                        list.meth_append(&rt, &rt.int(i)).unwrap();
+                        dict.op_setitem(&rt, &rt.int(i), &rt.int(t)).unwrap();
+                        len(&rt, &list);
+                    }
+                })
+            }).collect();
+
+            for handle in handles {
+                handle.join().unwrap();
+            }
+
+            assert_eq!(len(&rt, &list), THREADS * ITERATIONS);
+            assert_eq!(len(&rt, &dict), ITERATIONS);
+        }
+
+        #[test]
+        fn thread_lock_queue() {
+            let rt = Runtime::new();
+            let module = create_module(&rt).unwrap();
+            let queue = call(&rt, &module, "Queue", vec![]).unwrap();
+            let put = queue.op_getattr(&rt, &rt.str("put")).unwrap();
+
+            let threads: Vec<ObjectRef> = (0..THREADS).map(|t| {
+                let thread = call(&rt, &module, "Thread", vec![put.clone(), rt.tuple(vec![rt.int(t)])]).unwrap();
+                call(&rt, &thread, "start", vec![]).unwrap();
+                thread
+            }).collect();
+
+            for thread in threads.iter() {
+                call(&rt, thread, "join", vec![]).unwrap();
+                assert_eq!(call(&rt, thread, "is_alive", vec![]).unwrap(), rt.bool(false));
+            }
+
+            assert_eq!(call(&rt, &queue, "qsize", vec![]).unwrap(), rt.int(THREADS));
+        }
+
+        #[test]
+        fn no_collection_while_running() {
+            let rt = Runtime::new();
+            let module = create_module(&rt).unwrap();
+            let event = call(&rt, &module, "Event", vec![]).unwrap();
+            let wait = event.op_getattr(&rt, &rt.str("wait")).unwrap();
+
+            let list = rt.default_list();
+            list.meth_append(&rt, &list).unwrap();
+            let weak = list.downgrade();
+            drop(list);
+
+            let thread = call(&rt, &module, "Thread", vec![wait]).unwrap();
+            call(&rt, &thread, "start", vec![]).unwrap();
+            assert_eq!(rt.gc().collect(2), 0);
+            assert!(weak.try_upgrade().is_some());
+
+            call(&rt, &event, "set", vec![]).unwrap();
+            call(&rt, &thread, "join", vec![]).unwrap();
+            rt.gc().collect(2);
+            assert!(weak.try_upgrade().is_none());
+        }
+    }
+}
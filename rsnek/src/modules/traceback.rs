@@ -0,0 +1,282 @@
+//! `traceback` - Format and print the traceback of an error
+//!
+//! ```ignore
+//! format_exc = traceback.format_exc
+//! text = format_exc()
+//!
+//! print_exc = traceback.print_exc
+//! print_exc()
+//! ```
+//!
+//! rsnek has no `try`/`except` yet so the error reported is the last one that was
+//! not handled, like the statement that failed in the repl. Before any error
+//! `format_exc()` gives `'NoneType: None\n'` like CPython does outside of an
+//! `except` block.
+//!
+//! Tracebacks are formatted the way CPython formats them, most recent call last
+//! and with the line of source of each frame when it can be found:
+//!
+//! ```text
+//! Traceback (most recent call last):
+//!   File "<string>", line 4, in <module>
+//!     f()
+//!   File "<string>", line 2, in f
+//!     return undefined
+//! NameError: name 'undefined' is not defined
+//! ```
+//!
+use ::api::result::{Error, ObjectResult, RtResult};
+use ::api::RtObject as ObjectRef;
+use ::api::method::GetAttr;
+use ::modules::io;
+use ::modules::precondition::{check_args, check_kwargs};
+use ::modules::register;
+use ::resources::strings;
+use ::runtime::Runtime;
+use ::runtime::traits::{ModuleImporter, ModuleProvider, NoneProvider, StringProvider};
+use ::system::primitives as rs;
+use ::system::primitives::SignatureBuilder;
+
+
+/// Create the `traceback` module object with all of its functions
+pub fn create_module(rt: &Runtime) -> ObjectResult {
+    let module = rt.module(rs::None());
+
+    let funcs = vec![
+        FormatExcFn::create(),
+        PrintExcFn::create(),
+    ];
+
+    register(rt, &module, funcs)?;
+
+    Ok(module)
+}
+
+
+/// Where a frame was when the traceback was taken
+#[derive(Clone, Debug, Serialize)]
+pub struct TracebackFrame {
+    frame: ObjectRef,
+    filename: String,
+    name: String,
+    line: usize,
+}
+
+
+impl TracebackFrame {
+    pub fn new(frame: ObjectRef, filename: &str, name: &str, line: usize) -> Self {
+        TracebackFrame {
+            frame: frame,
+            filename: String::from(filename),
+            name: String::from(name),
+            line: line,
+        }
+    }
+
+    pub fn object(&self) -> &ObjectRef {
+        &self.frame
+    }
+
+    pub fn filename(&self) -> &str {
+        &self.filename
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+}
+
+
+/// An error and the frames it unwound, outermost first. `context` is the
+/// error that was being handled when this one was raised.
+#[derive(Clone, Debug, Serialize)]
+pub struct Traceback {
+    pub frames: Vec<TracebackFrame>,
+    pub error: Error,
+    pub context: Option<Box<Traceback>>,
+}
+
+
+impl Traceback {
+    pub fn new(frames: Vec<TracebackFrame>, error: Error) -> Self {
+        Traceback {
+            frames: frames,
+            error: error,
+            context: None,
+        }
+    }
+
+    /// Chain the traceback of the error that was being handled when this one happened
+    pub fn with_context(self, context: Traceback) -> Self {
+        Traceback {
+            context: Some(Box::new(context)),
+            ..self
+        }
+    }
+}
+
+
+/// Format `traceback` like CPython, chained errors go first
+pub fn format(rt: &Runtime, traceback: &Traceback) -> String {
+    let mut text = String::new();
+
+    if let Some(ref context) = traceback.context {
+        text.push_str(&format(rt, context));
+        text.push_str("\nDuring handling of the above exception, another exception occurred:\n\n");
+    }
+
+    if !traceback.frames.is_empty() {
+        text.push_str("Traceback (most recent call last):\n");
+    }
+
+    for frame in traceback.frames.iter() {
+        text.push_str(&format!("  File \"{}\", line {}, in {}\n", frame.filename, frame.line, frame.name));
+
+        if let Some(source) = rt.source_line(&frame.filename, frame.line) {
+            text.push_str(&format!("    {}\n", source.trim()));
+        }
+    }
+
+    text.push_str(&format!("{}\n", traceback.error));
+    text
+}
+
+
+/// Write the formatted `traceback` to `sys.stderr`
+pub fn print(rt: &Runtime, traceback: &Traceback) -> RtResult<rs::None> {
+    write_stderr(rt, &format(rt, traceback))
+}
+
+
+/// `sys.stderr` is looked up on each call so replacing it redirects tracebacks
+pub fn write_stderr(rt: &Runtime, text: &str) -> RtResult<rs::None> {
+    let stderr = rt.import_module(strings::SYS_MODULE)?.op_getattr(&rt, &rt.intern("stderr"))?;
+    io::write(rt, &stderr, text)?;
+    io::flush(rt, &stderr)
+}
+
+
+/// The last unhandled error formatted like CPython, or `NoneType: None` if there was none
+fn format_last(rt: &Runtime) -> String {
+    match rt.last_traceback() {
+        Some(traceback) => format(rt, &traceback),
+        None => String::from("NoneType: None\n")
+    }
+}
+
+
+pub struct FormatExcFn;
+pub struct PrintExcFn;
+
+
+impl FormatExcFn {
+    pub fn create() -> rs::Func {
+        module_func!(strings::TRACEBACK_MODULE, "format_exc", rs_traceback_format_exc, [])
+    }
+}
+
+
+/// `traceback.format_exc()` - the traceback of the last unhandled error as a string
+fn rs_traceback_format_exc(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_module" => strings::TRACEBACK_MODULE, "function" => "format_exc");
+    check_args(0, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    Ok(rt.str(format_last(rt)))
+}
+
+
+impl PrintExcFn {
+    pub fn create() -> rs::Func {
+        module_func!(strings::TRACEBACK_MODULE, "print_exc", rs_traceback_print_exc, [])
+    }
+}
+
+
+/// `traceback.print_exc()` - write the traceback of the last unhandled error to `sys.stderr`
+fn rs_traceback_print_exc(rt: &Runtime, pos_args: &ObjectRef, starargs: &ObjectRef, kwargs: &ObjectRef) -> ObjectResult {
+    trace!("call"; "native_module" => strings::TRACEBACK_MODULE, "function" => "print_exc");
+    check_args(0, &pos_args)?;
+    check_args(0, &starargs)?;
+    check_kwargs(0, &kwargs)?;
+
+    write_stderr(rt, &format_last(rt))?;
+    Ok(rt.none())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use ::compiler::Compiler;
+    use ::modules::call;
+    use ::runtime::Interpreter;
+    use super::*;
+
+    const SOURCE: &'static str = "def f():\n    return undefined\n\nf()\n";
+
+    /// Run `SOURCE` and keep the traceback of the error it raises
+    fn failed(rt: &Runtime) -> Traceback {
+        rt.add_source(strings::SOURCE_STRING, SOURCE);
+        let ins = Compiler::new().compile_str(SOURCE).unwrap();
+
+        let mut interpreter = Interpreter::new(&rt);
+        let err = interpreter.exec(&rt, &ins).unwrap_err();
+        interpreter.traceback(&err)
+    }
+
+    #[test]
+    fn frames() {
+        let rt = Runtime::new();
+        let traceback = failed(&rt);
+
+        let frames: Vec<(&str, &str, usize)> = traceback.frames.iter()
+            .map(|frame| (frame.filename(), frame.name(), frame.line()))
+            .collect();
+
+        assert_eq!(frames, vec![("<string>", "<module>", 4), ("<string>", "f", 2)]);
+    }
+
+    #[test]
+    fn format_source_lines() {
+        let rt = Runtime::new();
+        let traceback = failed(&rt);
+
+        assert_eq!(format(&rt, &traceback), [
+            "Traceback (most recent call last):",
+            "  File \"<string>\", line 4, in <module>",
+            "    f()",
+            "  File \"<string>\", line 2, in f",
+            "    return undefined",
+            "NameError: name 'undefined' is not defined\n"].join("\n"));
+    }
+
+    #[test]
+    fn format_context() {
+        let rt = Runtime::new();
+        let traceback = Traceback::new(Vec::new(), Error::runtime("while reporting"))
+            .with_context(Traceback::new(Vec::new(), Error::value("first")));
+
+        assert_eq!(format(&rt, &traceback), [
+            "ValueError: first",
+            "",
+            "During handling of the above exception, another exception occurred:",
+            "",
+            "RuntimeError: while reporting\n"].join("\n"));
+    }
+
+    #[test]
+    fn format_exc() {
+        let rt = Runtime::new();
+        let module = create_module(&rt).unwrap();
+        assert_eq!(call(&rt, &module, "format_exc", vec![]).unwrap(), rt.str("NoneType: None\n"));
+
+        let traceback = failed(&rt);
+        rt.set_last_traceback(traceback.clone());
+        assert_eq!(call(&rt, &module, "format_exc", vec![]).unwrap(), rt.str(format(&rt, &traceback)));
+    }
+}
@@ -1,6 +1,7 @@
 //! PyBoolean - Object for the True and False singletons
 //!
 use std;
+use std::cmp::Ordering;
 use std::ops::{Deref, Neg};
 use std::borrow::Borrow;
 use num::{Signed, Zero, FromPrimitive, ToPrimitive};
@@ -11,7 +12,7 @@ use api::selfref::{self, SelfRef};
 
 use ::runtime::Runtime;
 use ::runtime::traits::{BooleanProvider, StringProvider, IntegerProvider, FloatProvider};
-use ::api::result::{ObjectResult, RtResult};
+use ::api::result::{Error, ErrorType, ObjectResult, RtResult};
 use ::modules::builtins::Type;
 use ::api::RtObject;
 use ::objects::number;
@@ -302,11 +303,15 @@ impl method::PositiveValue for PyBoolean {
     }
 }
 
+/// `self < rhs`, `self <= rhs`, `self > rhs`, `self >= rhs`
+ordering_comparison_impls!(PyBoolean, "bool", |this: &PyBoolean, rhs: &Type| number::int_cmp(&this.value.0, rhs));
+
+
 method_not_implemented!(PyBoolean,
     New   Init   Delete   GetAttr   
     GetAttribute   SetAttr   DelAttr   StringFormat   
-    ComplexCast   LessThan   LessOrEqual   GreaterOrEqual   
-    GreaterThan   InvertValue   Add   BitwiseAnd   
+    ComplexCast   
+    InvertValue   Add   BitwiseAnd   
     DivMod   FloorDivision   LeftShift   Modulus   
     Multiply   MatrixMultiply   BitwiseOr   Pow   
     RightShift   Subtract   TrueDivision   XOr   
@@ -0,0 +1,636 @@
+//! PyByteArray - mutable sibling of `PyBytes`
+//!
+//! ```ignore
+//! bytearray()
+//! bytearray(b"\x00\x01binary")
+//! ```
+//!
+use std::fmt;
+use std::ops::Range;
+
+use num::ToPrimitive;
+
+use ::api::method::{self, Iter, Equal, Pop, StringCast, StringRepresentation};
+use ::api::result::{Error, RtResult, ObjectResult};
+use ::api::selfref::{self, SelfRef};
+use ::api::typing::{self, BuiltinType};
+use ::api::{self, RtObject, RtValue};
+use ::modules::builtins::Type;
+use ::modules::precondition::{check_args, check_args_range, check_kwargs};
+use ::objects::collection::{buffer, sequence};
+use ::objects::integer::PyIntegerType;
+use ::resources::strings;
+use ::runtime::Runtime;
+use ::runtime::traits::{BooleanProvider, ByteArrayProvider, BytesProvider, FunctionProvider,
+                        IntegerProvider, IteratorProvider, ListProvider, NoneProvider, StringProvider};
+use ::system::primitives::{SignatureBuilder};
+use ::system::primitives as rs;
+use ::system::sync::RefCell;
+
+
+const TYPE_NAME: &'static str = "bytearray";
+
+
+pub struct PyByteArrayType {}
+
+
+impl typing::BuiltinType for PyByteArrayType {
+    type T = PyByteArray;
+    type V = rs::Bytes;
+
+    #[allow(unused_variables)]
+    fn new(&self, rt: &Runtime, value: Self::V) -> RtObject {
+        PyByteArrayType::inject_selfref(PyByteArrayType::alloc(value))
+    }
+
+    fn init_type() -> Self {
+        PyByteArrayType {}
+    }
+
+    fn inject_selfref(value: Self::T) -> RtObject {
+        let object = RtObject::new(Type::ByteArray(value));
+        let new = object.clone();
+
+        match object.as_ref() {
+            &Type::ByteArray(ref bytearray) => {
+                bytearray.rc.set(&object.clone());
+            }
+            _ => unreachable!(),
+        }
+        new
+    }
+
+    fn alloc(value: Self::V) -> Self::T {
+        PyByteArray {
+            value: ByteArrayValue(RefCell::new(value)),
+            rc: selfref::RefCount::default(),
+        }
+    }
+}
+
+
+pub struct ByteArrayValue(pub RefCell<rs::Bytes>);
+pub type PyByteArray = RtValue<ByteArrayValue>;
+
+
+impl PyByteArray {
+    pub fn get_attribute(&self, rt: &Runtime, name: &str) -> ObjectResult {
+        match name {
+            "__bool__"          |
+            "__iter__"          |
+            "__len__"           |
+            "__repr__"          |
+            "__str__"           |
+            "clear"             => self.try_get_unary_method(rt, name),
+            "__add__"           |
+            "__contains__"      |
+            "__delitem__"       |
+            "__eq__"            |
+            "__getitem__"       |
+            "__iadd__"          |
+            "__mul__"           |
+            "__ne__"            |
+            "append"            |
+            "extend"            => self.try_get_binary_method(rt, name),
+            "__setitem__"       => self.try_get_ternary_method(rt, name),
+            "decode"            |
+            "find"              |
+            "fromhex"           |
+            "hex"               |
+            "pop"               |
+            "split"             => self.try_get_variadic_method(rt, name),
+            missing => Err(Error::attribute(
+                &strings_error_no_attribute!(TYPE_NAME, missing)))
+        }
+    }
+
+    fn try_get_unary_method(&self, rt: &Runtime, name: &str) -> ObjectResult {
+        let func = match name {
+            "__bool__"      => {PyByteArray::op_bool},
+            "__iter__"      => {PyByteArray::op_iter},
+            "__len__"       => {PyByteArray::op_len},
+            "__repr__"      => {PyByteArray::op_repr},
+            "__str__"       => {PyByteArray::op_str},
+            "clear"         => {PyByteArray::meth_clear},
+            missing => return Err(Error::attribute(
+                &strings_error_no_attribute!(TYPE_NAME, missing)))
+        };
+
+        unary_method_wrapper!(self, TYPE_NAME, name, rt, Type::ByteArray, func)
+    }
+
+    fn try_get_binary_method(&self, rt: &Runtime, name: &str) -> ObjectResult {
+        let func = match name {
+            "__add__"       => {PyByteArray::op_add},
+            "__contains__"  => {PyByteArray::op_contains},
+            "__delitem__"   => {PyByteArray::op_delitem},
+            "__eq__"        => {PyByteArray::op_eq},
+            "__getitem__"   => {PyByteArray::op_getitem},
+            "__iadd__"      => {PyByteArray::op_iadd},
+            "__mul__"       => {PyByteArray::op_mul},
+            "__ne__"        => {PyByteArray::op_ne},
+            "append"        => {PyByteArray::meth_append},
+            "extend"        => {PyByteArray::meth_extend},
+            missing => return Err(Error::attribute(
+                &strings_error_no_attribute!(TYPE_NAME, missing)))
+        };
+
+        binary_method_wrapper!(self, TYPE_NAME, name, rt, Type::ByteArray, func)
+    }
+
+    fn try_get_ternary_method(&self, rt: &Runtime, name: &str) -> ObjectResult {
+        let func = match name {
+            "__setitem__"   => {PyByteArray::op_setitem},
+            missing => return Err(Error::attribute(
+                &strings_error_no_attribute!(TYPE_NAME, missing)))
+        };
+
+        ternary_method_wrapper!(self, TYPE_NAME, name, rt, Type::ByteArray, func)
+    }
+
+    fn try_get_variadic_method(&self, rt: &Runtime, name: &str) -> ObjectResult {
+        let (func, nargs): (fn(&PyByteArray, &Runtime, &rs::List) -> ObjectResult, Range<usize>) = match name {
+            "decode"        => (PyByteArray::meth_decode, 0..3),
+            "find"          => (PyByteArray::meth_find, 1..4),
+            "fromhex"       => (PyByteArray::meth_fromhex, 1..2),
+            "hex"           => (PyByteArray::meth_hex, 0..1),
+            "pop"           => (PyByteArray::meth_pop_index, 0..2),
+            "split"         => (PyByteArray::meth_split, 0..3),
+            missing => return Err(Error::attribute(
+                &strings_error_no_attribute!(TYPE_NAME, missing)))
+        };
+
+        variadic_method_wrapper!(self, TYPE_NAME, name, rt, Type::ByteArray, func, nargs.clone())
+    }
+
+    /// `bytearray.decode(encoding='utf-8', errors='strict')`
+    pub fn meth_decode(&self, rt: &Runtime, args: &rs::List) -> ObjectResult {
+        let string = buffer::call_decode(&self.value.0.borrow(), args)?;
+        Ok(rt.str(string))
+    }
+
+    /// `bytearray.find(sub[, start[, end]])`
+    pub fn meth_find(&self, rt: &Runtime, args: &rs::List) -> ObjectResult {
+        let idx = buffer::call_find(&self.value.0.borrow(), args)?;
+        Ok(rt.int(idx))
+    }
+
+    /// `bytearray.fromhex(string)`, see `PyBytes::meth_fromhex`.
+    #[allow(unused_variables)]
+    pub fn meth_fromhex(&self, rt: &Runtime, args: &rs::List) -> ObjectResult {
+        let bytes = buffer::call_fromhex(args)?;
+        Ok(rt.bytearray(bytes))
+    }
+
+    /// `bytearray.hex()`
+    #[allow(unused_variables)]
+    pub fn meth_hex(&self, rt: &Runtime, args: &rs::List) -> ObjectResult {
+        Ok(rt.str(buffer::hex(&self.value.0.borrow())))
+    }
+
+    /// `bytearray.pop([index])` where the index defaults to the last element.
+    pub fn meth_pop_index(&self, rt: &Runtime, args: &rs::List) -> ObjectResult {
+        match args.get(0) {
+            Some(index) => self.meth_pop(rt, index),
+            None => self.meth_pop(rt, &rt.int(-1))
+        }
+    }
+
+    /// `bytearray.split(sep=None, maxsplit=-1)`
+    pub fn meth_split(&self, rt: &Runtime, args: &rs::List) -> ObjectResult {
+        let parts = buffer::call_split(&self.value.0.borrow(), args)?
+            .into_iter()
+            .map(|part| rt.bytearray(part))
+            .collect::<rs::List>();
+        Ok(rt.list(parts))
+    }
+
+    /// Convert a python index into a position in the buffer or raise an `IndexError`.
+    fn position(&self, index: &Type) -> RtResult<usize> {
+        let idx = match index {
+            &Type::Int(ref int) => int.value.0.to_isize(),
+            other => return Err(Error::typerr(&format!(
+                "bytearray indices must be integers or slices, not {}", other.debug_name())))
+        };
+
+        let len = self.value.0.borrow().len() as isize;
+        match idx {
+            Some(idx) if 0 <= idx && idx < len      => Ok(idx as usize),
+            Some(idx) if -len <= idx && idx < 0     => Ok((len + idx) as usize),
+            _ => Err(rsnek_exception_index!(TYPE_NAME))
+        }
+    }
+}
+
+
+impl fmt::Debug for PyByteArray {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ByteArray {{ {:?} }}", self.value.0.borrow())
+    }
+}
+
+
+impl api::PyAPI for PyByteArray {}
+
+
+/// `self.rhs`
+impl method::GetAttr for PyByteArray {
+    fn op_getattr(&self, rt: &Runtime, name: &RtObject) -> ObjectResult {
+        match name.as_ref() {
+            &Type::Str(ref pystring) => {
+                let string = pystring.value.0.clone();
+                self.get_attribute(&rt, &string)
+            }
+            other => Err(Error::typerr(&format!(
+                "getattr <{}>' requires string for attribute names, not {}",
+                TYPE_NAME, other.debug_name())))
+        }
+    }
+}
+
+
+impl method::Hashed for PyByteArray {
+    #[allow(unused_variables)]
+    fn op_hash(&self, rt: &Runtime) -> ObjectResult {
+        Err(Error::typerr(&format!("Unhashable type {}", TYPE_NAME)))
+    }
+
+    fn native_hash(&self) -> RtResult<rs::HashId> {
+        Err(Error::typerr(&format!("Unhashable type {}", TYPE_NAME)))
+    }
+}
+
+
+impl method::StringCast for PyByteArray {
+    fn op_str(&self, rt: &Runtime) -> ObjectResult {
+        let string = self.native_str()?;
+        Ok(rt.str(string))
+    }
+
+    fn native_str(&self) -> RtResult<rs::String> {
+        self.native_repr()
+    }
+}
+
+
+impl method::StringRepresentation for PyByteArray {
+    fn op_repr(&self, rt: &Runtime) -> ObjectResult {
+        let string = self.native_repr()?;
+        Ok(rt.str(string))
+    }
+
+    fn native_repr(&self) -> RtResult<rs::String> {
+        Ok(format!("bytearray({})", buffer::repr("b", &self.value.0.borrow())))
+    }
+}
+
+
+impl method::BytesCast for PyByteArray {
+    fn op_bytes(&self, rt: &Runtime) -> ObjectResult {
+        let bytes = self.native_bytes()?;
+        Ok(rt.bytes(bytes))
+    }
+
+    fn native_bytes(&self) -> RtResult<rs::Bytes> {
+        Ok(self.value.0.borrow().clone())
+    }
+}
+
+
+impl method::Equal for PyByteArray {
+    fn op_eq(&self, rt: &Runtime, rhs: &RtObject) -> ObjectResult {
+        let value = self.native_eq(rhs.as_ref())?;
+        Ok(rt.bool(value))
+    }
+
+    fn native_eq(&self, rhs: &Type) -> RtResult<rs::Boolean> {
+        match buffer::as_buffer(rhs) {
+            Some(other) => Ok(*self.value.0.borrow() == other),
+            None => Ok(false)
+        }
+    }
+}
+
+
+impl method::NotEqual for PyByteArray {
+    fn op_ne(&self, rt: &Runtime, rhs: &RtObject) -> ObjectResult {
+        let value = self.native_ne(rhs.as_ref())?;
+        Ok(rt.bool(value))
+    }
+
+    fn native_ne(&self, rhs: &Type) -> RtResult<rs::Boolean> {
+        let value = self.native_eq(rhs)?;
+        Ok(!value)
+    }
+}
+
+
+impl method::BooleanCast for PyByteArray {
+    fn op_bool(&self, rt: &Runtime) -> ObjectResult {
+        let truth = self.native_bool()?;
+        Ok(rt.bool(truth))
+    }
+
+    fn native_bool(&self) -> RtResult<rs::Boolean> {
+        Ok(!self.value.0.borrow().is_empty())
+    }
+}
+
+
+impl method::Add for PyByteArray {
+    fn op_add(&self, rt: &Runtime, rhs: &RtObject) -> ObjectResult {
+        match buffer::as_buffer(rhs.as_ref()) {
+            Some(other) => Ok(rt.bytearray([&self.value.0.borrow()[..], &other[..]].concat())),
+            None => Err(Error::typerr(
+                &strings_error_bad_operand!("+", TYPE_NAME, rhs.debug_name()))),
+        }
+    }
+}
+
+
+impl method::InPlaceAdd for PyByteArray {
+    /// `self += rhs` extends the buffer in place and returns the same object.
+    fn op_iadd(&self, rt: &Runtime, rhs: &RtObject) -> ObjectResult {
+        match buffer::as_buffer(rhs.as_ref()) {
+            Some(other) => {
+                self.value.0.borrow_mut().extend(other);
+                self.rc.upgrade()
+            },
+            None => Err(Error::typerr(
+                &strings_error_bad_operand!("+=", TYPE_NAME, rhs.debug_name()))),
+        }
+    }
+}
+
+
+impl method::Multiply for PyByteArray {
+    fn op_mul(&self, rt: &Runtime, rhs: &RtObject) -> ObjectResult {
+        match rhs.as_ref() {
+            &Type::Int(ref int) => {
+                match int.value.0.to_isize() {
+                    Some(count) if count <= 0   => Ok(rt.bytearray(rs::Bytes::new())),
+                    Some(count)                 => {
+                        let value = (0..count)
+                            .flat_map(|_| self.value.0.borrow().clone())
+                            .collect::<rs::Bytes>();
+                        Ok(rt.bytearray(value))
+                    },
+                    None                        => Err(Error::overflow(strings::ERROR_NATIVE_INT_OVERFLOW)),
+                }
+            }
+            other => Err(Error::typerr(
+                &strings_error_bad_operand!("*", TYPE_NAME, other.debug_name())))
+        }
+    }
+}
+
+
+impl method::Contains for PyByteArray {
+    fn op_contains(&self, rt: &Runtime, item: &RtObject) -> ObjectResult {
+        let truth = self.native_contains(item.as_ref())?;
+        Ok(rt.bool(truth))
+    }
+
+    fn native_contains(&self, item: &Type) -> RtResult<rs::Boolean> {
+        let needle = match item {
+            &Type::Int(_) => vec![buffer::byte_from_object(item)?],
+            other => match buffer::as_buffer(other) {
+                Some(bytes) => bytes,
+                None => return Err(Error::typerr(&format!(
+                    "a bytes-like object is required, not '{}'", other.debug_name())))
+            }
+        };
+
+        Ok(buffer::find(&self.value.0.borrow(), &needle, None, None) >= 0)
+    }
+}
+
+
+impl method::Iter for PyByteArray {
+    fn op_iter(&self, rt: &Runtime) -> ObjectResult {
+        let iter = self.native_iter()?;
+        Ok(rt.iter(iter))
+    }
+
+    fn native_iter(&self) -> RtResult<rs::Iterator> {
+        let selfref = self.rc.upgrade()?;
+        rs::Iterator::new(&selfref)
+    }
+}
+
+
+impl method::Reversed for PyByteArray {
+    fn op_reversed(&self, rt: &Runtime) -> ObjectResult {
+        let iter = self.native_reversed()?;
+        Ok(rt.iter(iter))
+    }
+
+    fn native_reversed(&self) -> RtResult<rs::Iterator> {
+        let selfref = self.rc.upgrade()?;
+        rs::Iterator::reversed(&selfref)
+    }
+}
+
+
+impl method::Length for PyByteArray {
+    fn op_len(&self, rt: &Runtime) -> ObjectResult {
+        Ok(rt.int(self.value.0.borrow().len()))
+    }
+
+    fn native_len(&self) -> RtResult<rs::Integer> {
+        Ok(rs::Integer::from(self.value.0.borrow().len()))
+    }
+}
+
+
+impl method::GetItem for PyByteArray {
+    /// Indexing a bytearray produces an int while slicing produces a new bytearray.
+    fn op_getitem(&self, rt: &Runtime, item: &RtObject) -> ObjectResult {
+        match item.as_ref() {
+            &Type::Slice(ref slice) => {
+                let (start, stop, step) = slice.native_bounds()?;
+                Ok(rt.bytearray(sequence::get_slice(&self.value.0.borrow(), start, stop, step)?))
+            },
+            other => {
+                let idx = self.position(other)?;
+                Ok(rt.int(self.value.0.borrow()[idx]))
+            }
+        }
+    }
+
+    fn native_getitem(&self, index: &Type) -> RtResult<RtObject> {
+        match index {
+            &Type::Slice(ref slice) => {
+                let (start, stop, step) = slice.native_bounds()?;
+                let bytes = sequence::get_slice(&self.value.0.borrow(), start, stop, step)?;
+                Ok(PyByteArrayType::inject_selfref(PyByteArrayType::alloc(bytes)))
+            },
+            other => {
+                let idx = self.position(other)?;
+                let byte = self.value.0.borrow()[idx];
+                Ok(PyIntegerType::inject_selfref(PyIntegerType::alloc(rs::Integer::from(byte))))
+            }
+        }
+    }
+}
+
+
+impl method::SetItem for PyByteArray {
+    /// `self[index] = int` or, for contiguous slices, `self[start:stop] = buffer`
+    /// which may grow or shrink the bytearray.
+    fn op_setitem(&self, rt: &Runtime, key: &RtObject, value: &RtObject) -> ObjectResult {
+        self.native_setitem(key.as_ref(), value.as_ref())?;
+        Ok(rt.none())
+    }
+
+    fn native_setitem(&self, key: &Type, value: &Type) -> RtResult<rs::None> {
+        match key {
+            &Type::Slice(ref slice) => {
+                let replacement = match buffer::as_buffer(value) {
+                    Some(bytes) => bytes,
+                    None => return Err(Error::typerr(&format!(
+                        "can assign only bytes, buffers, or iterables of ints in range(0, 256), not {}",
+                        value.debug_name())))
+                };
+
+                let len = self.value.0.borrow().len();
+                let (start, stop, step) = slice.native_indices(len)?;
+                if step != 1 {
+                    return Err(Error::value("extended slice assignment is not supported for bytearray"))
+                }
+
+                let stop = if stop < start {start} else {stop};
+                self.value.0.borrow_mut().splice(start as usize..stop as usize, replacement);
+            },
+            other => {
+                let idx = self.position(other)?;
+                let byte = buffer::byte_from_object(value)?;
+                self.value.0.borrow_mut()[idx] = byte;
+            }
+        };
+
+        Ok(rs::None())
+    }
+}
+
+
+impl method::DeleteItem for PyByteArray {
+    fn op_delitem(&self, rt: &Runtime, key: &RtObject) -> ObjectResult {
+        let idx = self.position(key.as_ref())?;
+        self.value.0.borrow_mut().remove(idx);
+        Ok(rt.none())
+    }
+}
+
+
+impl method::Append for PyByteArray {
+    fn meth_append(&self, rt: &Runtime, item: &RtObject) -> ObjectResult {
+        self.native_meth_append(item.as_ref())?;
+        Ok(rt.none())
+    }
+
+    fn native_meth_append(&self, item: &Type) -> RtResult<rs::None> {
+        let byte = buffer::byte_from_object(item)?;
+        self.value.0.borrow_mut().push(byte);
+        Ok(rs::None())
+    }
+}
+
+
+impl method::Extend for PyByteArray {
+    fn meth_extend(&self, rt: &Runtime, items: &RtObject) -> ObjectResult {
+        let bytes = buffer::from_object(rt, items)?;
+        self.value.0.borrow_mut().extend(bytes);
+        Ok(rt.none())
+    }
+}
+
+
+impl method::Pop for PyByteArray {
+    fn meth_pop(&self, rt: &Runtime, index: &RtObject) -> ObjectResult {
+        if self.value.0.borrow().is_empty() {
+            return Err(Error::index("pop from empty bytearray"))
+        }
+
+        let idx = self.position(index.as_ref())?;
+        let byte = self.value.0.borrow_mut().remove(idx);
+        Ok(rt.int(byte))
+    }
+}
+
+
+impl method::Clear for PyByteArray {
+    fn meth_clear(&self, rt: &Runtime) -> ObjectResult {
+        self.value.0.borrow_mut().clear();
+        Ok(rt.none())
+    }
+}
+
+
+method_not_implemented!(PyByteArray,
+    AbsValue   AddItem   Await   BitwiseAnd
+    BitwiseOr   Call   Close   ComplexCast
+    Count   DelAttr   Delete   DescriptorGet
+    DescriptorSet   DescriptorSetName   Discard   DivMod
+    Enter   Exit   FloatCast   FloorDivision
+    Get   GetAttribute   GreaterOrEqual   GreaterThan
+    Id   InPlaceBitwiseAnd   InPlaceBitwiseOr   InPlaceDivMod
+    InPlaceFloorDivision   InPlaceLeftShift   InPlaceMatrixMultiply   InPlaceModulus
+    InPlaceMultiply   InPlacePow   InPlaceRightShift   InPlaceSubtract
+    InPlaceTrueDivision   InPlaceXOr   Index   Init
+    IntegerCast   InvertValue   Is   IsDisjoint
+    IsNot   Items   Keys   LeftShift
+    LengthHint   LessOrEqual   LessThan   MatrixMultiply
+    Modulus   NegateValue   New   Next
+    PopItem   PositiveValue   Pow   ReflectedAdd
+    ReflectedBitwiseAnd   ReflectedBitwiseOr   ReflectedDivMod   ReflectedFloorDivision
+    ReflectedLeftShift   ReflectedMatrixMultiply   ReflectedModulus   ReflectedMultiply
+    ReflectedPow   ReflectedRightShift   ReflectedSubtract   ReflectedTrueDivision
+    ReflectedXOr   Remove   RightShift
+    Rounding   Send   SetAttr   SetDefault
+    StringFormat   Subtract   Throw   TrueDivision
+    Update   Values   XOr
+);
+
+
+#[cfg(test)]
+mod tests {
+    use ::runtime::traits::{SliceProvider};
+    use ::api::method::{GetItem, SetItem, Append, Length};
+    use super::*;
+
+    fn setup() -> Runtime {
+        Runtime::new()
+    }
+
+    #[test]
+    fn mutate() {
+        let rt = setup();
+        let bytearray = rt.bytearray(b"abc".to_vec());
+
+        bytearray.op_setitem(&rt, &rt.int(0), &rt.int(122)).unwrap();
+        assert_eq!(bytearray, rt.bytes(b"zbc".to_vec()));
+
+        match bytearray.as_ref() {
+            &Type::ByteArray(ref value) => {
+                value.meth_append(&rt, &rt.int(100)).unwrap();
+                assert!(value.meth_append(&rt, &rt.int(256)).is_err());
+                assert_eq!(value.meth_pop_index(&rt, &vec![]).unwrap(), rt.int(100));
+            },
+            _ => unreachable!()
+        }
+
+        assert_eq!(bytearray.op_len(&rt).unwrap(), rt.int(3));
+    }
+
+    #[test]
+    fn slice_assignment() {
+        let rt = setup();
+        let bytearray = rt.bytearray(b"abcdef".to_vec());
+        let slice = rt.slice(rs::Slice {start: rt.int(1), stop: rt.int(3), step: rt.none()});
+
+        bytearray.op_setitem(&rt, &slice, &rt.bytes(b"XYZ".to_vec())).unwrap();
+        assert_eq!(bytearray, rt.bytes(b"aXYZdef".to_vec()));
+        assert_eq!(bytearray.op_getitem(&rt, &slice).unwrap(), rt.bytearray(b"XY".to_vec()));
+    }
+}
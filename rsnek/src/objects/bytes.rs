@@ -1,24 +1,36 @@
 //! PyBytes - Like PyString but without an explicit encoding for working with binary
 //! data.
 //!
-use std::borrow::Borrow;
+//! ```ignore
+//! bytes()
+//! b"\x00\x01binary"
+//! ```
+//!
 use std::fmt;
-use std::ops::Deref;
-use std::hash::{Hash, Hasher};
-use std::collections::hash_map::DefaultHasher;
-
-use ::api::result::{RtResult, ObjectResult};
-use runtime::Runtime;
-use ::runtime::traits::{IntegerProvider, BooleanProvider};
+use std::cmp::Ordering;
+use std::ops::Range;
 
-use api::{self, RtValue};
-use api::selfref::{self, SelfRef};
-use api::typing;
-use api::method;
+use num::ToPrimitive;
 
-use ::system::primitives as rs;
-use ::api::RtObject;
+use ::api::method::{self, Iter, Equal, StringCast, StringRepresentation};
+use ::api::result::{Error, ErrorType, RtResult, ObjectResult};
+use ::api::selfref::{self, SelfRef};
+use ::api::typing::{self, BuiltinType};
+use ::api::{self, RtObject, RtValue};
 use ::modules::builtins::Type;
+use ::modules::precondition::{check_args, check_args_range, check_kwargs};
+use ::objects::collection::{buffer, sequence};
+use ::objects::integer::PyIntegerType;
+use ::resources::strings;
+use ::runtime::Runtime;
+use ::runtime::traits::{BooleanProvider, BytesProvider, DefaultBytesProvider, FunctionProvider,
+                        IntegerProvider, IteratorProvider, ListProvider, StringProvider};
+use ::system::hash;
+use ::system::primitives::{SignatureBuilder};
+use ::system::primitives as rs;
+
+
+const TYPE_NAME: &'static str = "bytes";
 
 
 pub struct PyBytesType {
@@ -67,6 +79,112 @@ pub struct StringValue(pub rs::Bytes);
 pub type PyBytes = RtValue<StringValue>;
 
 
+impl PyBytes {
+    pub fn get_attribute(&self, rt: &Runtime, name: &str) -> ObjectResult {
+        match name {
+            "__bool__"          |
+            "__hash__"          |
+            "__iter__"          |
+            "__len__"           |
+            "__repr__"          |
+            "__str__"           => self.try_get_unary_method(rt, name),
+            "__add__"           |
+            "__contains__"      |
+            "__eq__"            |
+            "__getitem__"       |
+            "__mul__"           |
+            "__ne__"            => self.try_get_binary_method(rt, name),
+            "decode"            |
+            "find"              |
+            "fromhex"           |
+            "hex"               |
+            "split"             => self.try_get_variadic_method(rt, name),
+            missing => Err(Error::attribute(
+                &strings_error_no_attribute!(TYPE_NAME, missing)))
+        }
+    }
+
+    fn try_get_unary_method(&self, rt: &Runtime, name: &str) -> ObjectResult {
+        let func = match name {
+            "__bool__"      => {PyBytes::op_bool},
+            "__hash__"      => {PyBytes::op_hash},
+            "__iter__"      => {PyBytes::op_iter},
+            "__len__"       => {PyBytes::op_len},
+            "__repr__"      => {PyBytes::op_repr},
+            "__str__"       => {PyBytes::op_str},
+            missing => return Err(Error::attribute(
+                &strings_error_no_attribute!(TYPE_NAME, missing)))
+        };
+
+        unary_method_wrapper!(self, TYPE_NAME, name, rt, Type::Bytes, func)
+    }
+
+    fn try_get_binary_method(&self, rt: &Runtime, name: &str) -> ObjectResult {
+        let func = match name {
+            "__add__"       => {PyBytes::op_add},
+            "__contains__"  => {PyBytes::op_contains},
+            "__eq__"        => {PyBytes::op_eq},
+            "__getitem__"   => {PyBytes::op_getitem},
+            "__mul__"       => {PyBytes::op_mul},
+            "__ne__"        => {PyBytes::op_ne},
+            missing => return Err(Error::attribute(
+                &strings_error_no_attribute!(TYPE_NAME, missing)))
+        };
+
+        binary_method_wrapper!(self, TYPE_NAME, name, rt, Type::Bytes, func)
+    }
+
+    fn try_get_variadic_method(&self, rt: &Runtime, name: &str) -> ObjectResult {
+        let (func, nargs): (fn(&PyBytes, &Runtime, &rs::List) -> ObjectResult, Range<usize>) = match name {
+            "decode"        => (PyBytes::meth_decode, 0..3),
+            "find"          => (PyBytes::meth_find, 1..4),
+            "fromhex"       => (PyBytes::meth_fromhex, 1..2),
+            "hex"           => (PyBytes::meth_hex, 0..1),
+            "split"         => (PyBytes::meth_split, 0..3),
+            missing => return Err(Error::attribute(
+                &strings_error_no_attribute!(TYPE_NAME, missing)))
+        };
+
+        variadic_method_wrapper!(self, TYPE_NAME, name, rt, Type::Bytes, func, nargs.clone())
+    }
+
+    /// `bytes.decode(encoding='utf-8', errors='strict')`
+    pub fn meth_decode(&self, rt: &Runtime, args: &rs::List) -> ObjectResult {
+        let string = buffer::call_decode(&self.value.0, args)?;
+        Ok(rt.str(string))
+    }
+
+    /// `bytes.find(sub[, start[, end]])`
+    pub fn meth_find(&self, rt: &Runtime, args: &rs::List) -> ObjectResult {
+        let idx = buffer::call_find(&self.value.0, args)?;
+        Ok(rt.int(idx))
+    }
+
+    /// `bytes.fromhex(string)`. Python defines this as a classmethod, it is available
+    /// on instances until there are type objects to hang it off of.
+    #[allow(unused_variables)]
+    pub fn meth_fromhex(&self, rt: &Runtime, args: &rs::List) -> ObjectResult {
+        let bytes = buffer::call_fromhex(args)?;
+        Ok(rt.bytes(bytes))
+    }
+
+    /// `bytes.hex()`
+    #[allow(unused_variables)]
+    pub fn meth_hex(&self, rt: &Runtime, args: &rs::List) -> ObjectResult {
+        Ok(rt.str(buffer::hex(&self.value.0)))
+    }
+
+    /// `bytes.split(sep=None, maxsplit=-1)`
+    pub fn meth_split(&self, rt: &Runtime, args: &rs::List) -> ObjectResult {
+        let parts = buffer::call_split(&self.value.0, args)?
+            .into_iter()
+            .map(|part| rt.bytes(part))
+            .collect::<rs::List>();
+        Ok(rt.list(parts))
+    }
+}
+
+
 impl fmt::Debug for PyBytes {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "Bytes {{ {:?} }}", self.value.0)
@@ -77,6 +195,22 @@ impl fmt::Debug for PyBytes {
 impl api::PyAPI for PyBytes {}
 
 
+/// `self.rhs`
+impl method::GetAttr for PyBytes {
+    fn op_getattr(&self, rt: &Runtime, name: &RtObject) -> ObjectResult {
+        match name.as_ref() {
+            &Type::Str(ref pystring) => {
+                let string = pystring.value.0.clone();
+                self.get_attribute(&rt, &string)
+            }
+            other => Err(Error::typerr(&format!(
+                "getattr <{}>' requires string for attribute names, not {}",
+                TYPE_NAME, other.debug_name())))
+        }
+    }
+}
+
+
 impl method::Hashed for PyBytes {
     fn op_hash(&self, rt: &Runtime) -> ObjectResult {
         let value = self.native_hash()?;
@@ -84,12 +218,47 @@ impl method::Hashed for PyBytes {
     }
 
     fn native_hash(&self) -> RtResult<rs::HashId> {
-        let mut s = DefaultHasher::new();
-        self.value.0.hash(&mut s);
-        Ok(s.finish())
+        Ok(hash::hash_bytes(&self.value.0))
+    }
+}
+
+
+impl method::StringCast for PyBytes {
+    fn op_str(&self, rt: &Runtime) -> ObjectResult {
+        let string = self.native_str()?;
+        Ok(rt.str(string))
+    }
+
+    fn native_str(&self) -> RtResult<rs::String> {
+        self.native_repr()
+    }
+}
+
+
+impl method::StringRepresentation for PyBytes {
+    fn op_repr(&self, rt: &Runtime) -> ObjectResult {
+        let string = self.native_repr()?;
+        Ok(rt.str(string))
+    }
+
+    fn native_repr(&self) -> RtResult<rs::String> {
+        Ok(buffer::repr("b", &self.value.0))
+    }
+}
+
+
+impl method::BytesCast for PyBytes {
+    #[allow(unused_variables)]
+    fn op_bytes(&self, rt: &Runtime) -> ObjectResult {
+        self.rc.upgrade()
+    }
+
+    fn native_bytes(&self) -> RtResult<rs::Bytes> {
+        Ok(self.value.0.clone())
     }
 }
 
+
 impl method::Equal for PyBytes {
     fn op_eq(&self, rt: &Runtime, rhs: &RtObject) -> ObjectResult {
         let value = self.native_eq(rhs.as_ref())?;
@@ -99,53 +268,274 @@ impl method::Equal for PyBytes {
     fn native_eq(&self, rhs: &Type) -> RtResult<rs::Boolean> {
         match rhs {
             &Type::Bytes(ref bytes) => Ok(self.value.0 == bytes.value.0),
+            &Type::ByteArray(ref bytearray) => Ok(self.value.0 == *bytearray.value.0.borrow()),
             _ => Ok(false),
         }
     }
 }
 
 
+impl method::NotEqual for PyBytes {
+    fn op_ne(&self, rt: &Runtime, rhs: &RtObject) -> ObjectResult {
+        let value = self.native_ne(rhs.as_ref())?;
+        Ok(rt.bool(value))
+    }
+
+    fn native_ne(&self, rhs: &Type) -> RtResult<rs::Boolean> {
+        let value = self.native_eq(rhs)?;
+        Ok(!value)
+    }
+}
+
+
+impl method::BooleanCast for PyBytes {
+    fn op_bool(&self, rt: &Runtime) -> ObjectResult {
+        let truth = self.native_bool()?;
+        Ok(rt.bool(truth))
+    }
+
+    fn native_bool(&self) -> RtResult<rs::Boolean> {
+        Ok(!self.value.0.is_empty())
+    }
+}
+
+
+impl method::Add for PyBytes {
+    fn op_add(&self, rt: &Runtime, rhs: &RtObject) -> ObjectResult {
+        match buffer::as_buffer(rhs.as_ref()) {
+            Some(other) => Ok(rt.bytes([&self.value.0[..], &other[..]].concat())),
+            None => Err(Error::typerr(
+                &strings_error_bad_operand!("+", TYPE_NAME, rhs.debug_name()))),
+        }
+    }
+}
+
+
+impl method::Multiply for PyBytes {
+    fn op_mul(&self, rt: &Runtime, rhs: &RtObject) -> ObjectResult {
+        match rhs.as_ref() {
+            &Type::Int(ref int) => {
+                match int.value.0.to_isize() {
+                    Some(count) if count <= 0   => Ok(rt.default_bytes()),
+                    Some(count) if count == 1   => self.rc.upgrade(),
+                    Some(count)                 => {
+                        let value = (0..count)
+                            .flat_map(|_| self.value.0.iter().cloned())
+                            .collect::<rs::Bytes>();
+                        Ok(rt.bytes(value))
+                    },
+                    None                        => Err(Error::overflow(strings::ERROR_NATIVE_INT_OVERFLOW)),
+                }
+            }
+            other => Err(Error::typerr(
+                &strings_error_bad_operand!("*", TYPE_NAME, other.debug_name())))
+        }
+    }
+}
+
+
+impl method::Contains for PyBytes {
+    fn op_contains(&self, rt: &Runtime, item: &RtObject) -> ObjectResult {
+        let truth = self.native_contains(item.as_ref())?;
+        Ok(rt.bool(truth))
+    }
+
+    fn native_contains(&self, item: &Type) -> RtResult<rs::Boolean> {
+        let needle = match item {
+            &Type::Int(_) => vec![buffer::byte_from_object(item)?],
+            other => match buffer::as_buffer(other) {
+                Some(bytes) => bytes,
+                None => return Err(Error::typerr(&format!(
+                    "a bytes-like object is required, not '{}'", other.debug_name())))
+            }
+        };
+
+        Ok(buffer::find(&self.value.0, &needle, None, None) >= 0)
+    }
+}
+
+
+impl method::Iter for PyBytes {
+    fn op_iter(&self, rt: &Runtime) -> ObjectResult {
+        let iter = self.native_iter()?;
+        Ok(rt.iter(iter))
+    }
+
+    fn native_iter(&self) -> RtResult<rs::Iterator> {
+        let selfref = self.rc.upgrade()?;
+        rs::Iterator::new(&selfref)
+    }
+}
+
+
+impl method::Reversed for PyBytes {
+    fn op_reversed(&self, rt: &Runtime) -> ObjectResult {
+        let iter = self.native_reversed()?;
+        Ok(rt.iter(iter))
+    }
+
+    fn native_reversed(&self) -> RtResult<rs::Iterator> {
+        let selfref = self.rc.upgrade()?;
+        rs::Iterator::reversed(&selfref)
+    }
+}
+
+
+impl method::Length for PyBytes {
+    fn op_len(&self, rt: &Runtime) -> ObjectResult {
+        Ok(rt.int(self.value.0.len()))
+    }
+
+    fn native_len(&self) -> RtResult<rs::Integer> {
+        Ok(rs::Integer::from(self.value.0.len()))
+    }
+}
+
+
+impl method::GetItem for PyBytes {
+    /// Indexing a bytes object produces an int while slicing produces a new bytes object.
+    fn op_getitem(&self, rt: &Runtime, item: &RtObject) -> ObjectResult {
+        match item.as_ref() {
+            &Type::Int(ref int) => {
+                let byte = sequence::get_index(&self.value.0, &int.value.0)?;
+                Ok(rt.int(byte))
+            },
+            &Type::Slice(ref slice) => {
+                let (start, stop, step) = slice.native_bounds()?;
+                Ok(rt.bytes(sequence::get_slice(&self.value.0, start, stop, step)?))
+            },
+            other => Err(Error::typerr(&format!(
+                "byte indices must be integers or slices, not {}", other.debug_name())))
+        }
+    }
+
+    fn native_getitem(&self, index: &Type) -> RtResult<RtObject> {
+        match index {
+            &Type::Int(ref int) => {
+                let byte = sequence::get_index(&self.value.0, &int.value.0)?;
+                Ok(PyIntegerType::inject_selfref(PyIntegerType::alloc(rs::Integer::from(byte))))
+            },
+            &Type::Slice(ref slice) => {
+                let (start, stop, step) = slice.native_bounds()?;
+                let bytes = sequence::get_slice(&self.value.0, start, stop, step)?;
+                Ok(PyBytesType::inject_selfref(PyBytesType::alloc(bytes)))
+            },
+            other => Err(Error::typerr(&format!(
+                "byte indices must be integers or slices, not {}", other.debug_name())))
+        }
+    }
+}
+
+
+/// `self < rhs`, `self <= rhs`, `self > rhs`, `self >= rhs`
+ordering_comparison_impls!(PyBytes, TYPE_NAME, |this: &PyBytes, rhs: &Type| match buffer::as_buffer(rhs) {
+    Some(other) => Ok(Some(this.value.0.cmp(&other))),
+    None => Err(Error::typerr(rhs.debug_name()))
+});
+
+
 method_not_implemented!(PyBytes,
-    AbsValue   Add   AddItem   Append
-    Await   BitwiseAnd   BitwiseOr   BooleanCast
-    BytesCast   Call   Clear   Close
-    ComplexCast   Contains   Count   DelAttr
+    AbsValue   AddItem   Append   Await
+    BitwiseAnd   BitwiseOr   Call   Clear
+    Close   ComplexCast   Count   DelAttr
     Delete   DeleteItem   DescriptorGet   DescriptorSet
     DescriptorSetName   Discard   DivMod   Enter
-    Exit   Extend   FloatCast
-    FloorDivision   Get   GetAttr   GetAttribute
-    GetItem   GreaterOrEqual   GreaterThan
+    Exit   Extend   FloatCast   FloorDivision
+    Get   GetAttribute
     Id   InPlaceAdd   InPlaceBitwiseAnd   InPlaceBitwiseOr
     InPlaceDivMod   InPlaceFloorDivision   InPlaceLeftShift   InPlaceMatrixMultiply
     InPlaceModulus   InPlaceMultiply   InPlacePow   InPlaceRightShift
     InPlaceSubtract   InPlaceTrueDivision   InPlaceXOr   Index
     Init   IntegerCast   InvertValue   Is
-    IsDisjoint   IsNot   Items   Iter
-    Keys   LeftShift   Length   LengthHint
-    LessOrEqual   LessThan   MatrixMultiply   Modulus
-    Multiply   NegateValue   New   Next
-    NotEqual   Pop   PopItem   PositiveValue
+    IsDisjoint   IsNot   Items   Keys
+    LeftShift   LengthHint
+    MatrixMultiply   Modulus   NegateValue   New
+    Next   Pop   PopItem   PositiveValue
     Pow   ReflectedAdd   ReflectedBitwiseAnd   ReflectedBitwiseOr
     ReflectedDivMod   ReflectedFloorDivision   ReflectedLeftShift   ReflectedMatrixMultiply
     ReflectedModulus   ReflectedMultiply   ReflectedPow   ReflectedRightShift
     ReflectedSubtract   ReflectedTrueDivision   ReflectedXOr   Remove
-    Reversed   RightShift   Rounding   Send
-    SetAttr   SetDefault   SetItem   StringCast
-    StringFormat   StringRepresentation   Subtract   Throw
-    TrueDivision   Update   Values   XOr
+    RightShift   Rounding   Send
+    SetAttr   SetDefault   SetItem   StringFormat
+    Subtract   Throw   TrueDivision   Update
+    Values   XOr
 );
 
 
 #[cfg(test)]
 mod tests {
-    use ::runtime::Runtime;
+    use ::runtime::traits::{DictProvider, NoneProvider, SliceProvider, TupleProvider};
+    use ::api::method::{Call, GetAttr, GetItem, Length, Add};
+    use super::*;
+
+    fn setup() -> Runtime {
+        Runtime::new()
+    }
 
-    fn setup() -> (Runtime, ) {
-        (Runtime::new(), )
+    fn call_method(rt: &Runtime, object: &RtObject, name: &str, args: Vec<RtObject>) -> ObjectResult {
+        let method = object.op_getattr(rt, &rt.str(name))?;
+        method.op_call(rt, &rt.tuple(args), &rt.tuple(vec![]), &rt.dict(rs::Dict::new()))
     }
 
     #[test]
-    fn stub() {
-        info!("stub");
+    fn getitem_returns_int() {
+        let rt = setup();
+        let bytes = rt.bytes(b"\x00\xffabc".to_vec());
+
+        assert_eq!(bytes.op_getitem(&rt, &rt.int(1)).unwrap(), rt.int(255));
+        assert_eq!(bytes.op_getitem(&rt, &rt.int(-1)).unwrap(), rt.int(99));
+        assert!(bytes.op_getitem(&rt, &rt.int(5)).is_err());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn getitem_slice() {
+        let rt = setup();
+        let bytes = rt.bytes(b"abcdef".to_vec());
+        let slice = rt.slice(rs::Slice {start: rt.int(1), stop: rt.none(), step: rt.int(2)});
+
+        assert_eq!(bytes.op_getitem(&rt, &slice).unwrap(), rt.bytes(b"bdf".to_vec()));
+    }
+
+    #[test]
+    fn concat() {
+        let rt = setup();
+        let lhs = rt.bytes(b"abc".to_vec());
+        let rhs = rt.bytes(b"def".to_vec());
+
+        let result = lhs.op_add(&rt, &rhs).unwrap();
+        assert_eq!(result, rt.bytes(b"abcdef".to_vec()));
+        assert_eq!(result.op_len(&rt).unwrap(), rt.int(6));
+        assert!(lhs.op_add(&rt, &rt.str("def")).is_err());
+    }
+
+    #[test]
+    fn hex_fromhex() {
+        let rt = setup();
+        let bytes = rt.bytes(b"\xca\xfe".to_vec());
+
+        let hex = call_method(&rt, &bytes, "hex", vec![]).unwrap();
+        assert_eq!(hex, rt.str("cafe"));
+
+        let roundtrip = call_method(&rt, &bytes, "fromhex", vec![hex]).unwrap();
+        assert_eq!(roundtrip, bytes);
+    }
+
+    #[test]
+    fn decode_find_split() {
+        let rt = setup();
+        let bytes = rt.bytes(b"snek snek".to_vec());
+
+        assert_eq!(call_method(&rt, &bytes, "decode", vec![]).unwrap(), rt.str("snek snek"));
+        assert_eq!(call_method(&rt, &bytes, "find", vec![rt.bytes(b"ek".to_vec())]).unwrap(), rt.int(2));
+
+        let parts = call_method(&rt, &bytes, "split", vec![]).unwrap();
+        assert_eq!(parts, rt.list(vec![rt.bytes(b"snek".to_vec()), rt.bytes(b"snek".to_vec())]));
+    }
+
+    #[test]
+    fn repr() {
+        let rt = setup();
+        let bytes = rt.bytes(b"\x00a'".to_vec());
+        assert_eq!(bytes.native_repr().unwrap(), r#"b"\x00a'""#);
+    }
+}
@@ -0,0 +1,537 @@
+//! Common functions for binary buffer data shared by `bytes` and `bytearray`. Everything
+//! here operates on plain `&[u8]` so the object types only have to worry about unwrapping
+//! their values and wrapping the results.
+use std::str;
+
+use num::ToPrimitive;
+
+use ::api::RtObject;
+use ::api::method::Iter;
+use ::api::result::{Error, RtResult};
+use ::modules::builtins::Type;
+use ::objects::iterator::try_collect;
+use ::runtime::Runtime;
+use ::system::primitives as rs;
+
+
+const HEX_DIGITS: &'static [u8] = b"0123456789abcdef";
+
+
+/// Decode the source of a `b"..."` literal into the bytes that it represents. The lexer
+/// strips the `b` prefix but leaves the quotes which may be single or triple quotes.
+///
+/// Supports the same escape sequences as CPython: `\\`, `\'`, `\"`, `\a`, `\b`, `\f`,
+/// `\n`, `\r`, `\t`, `\v`, `\ooo` octal, `\xhh` hex, and line continuations. Unknown
+/// escapes are left in the buffer as is.
+pub fn decode_literal(source: &[u8]) -> RtResult<rs::Bytes> {
+    let quote_len = if source.starts_with(b"'''") || source.starts_with(b"\"\"\"") {3} else {1};
+
+    if source.len() < quote_len * 2 {
+        return Err(Error::syntax("Malformed bytes literal"))
+    }
+
+    let body = &source[quote_len..source.len() - quote_len];
+    let mut bytes = rs::Bytes::with_capacity(body.len());
+    let mut idx = 0;
+
+    while idx < body.len() {
+        let byte = body[idx];
+
+        if byte > 0x7f {
+            return Err(Error::syntax("bytes can only contain ASCII literal characters."))
+        }
+
+        if byte != b'\\' || idx + 1 == body.len() {
+            bytes.push(byte);
+            idx += 1;
+            continue
+        }
+
+        let escape = body[idx + 1];
+        idx += 2;
+
+        match escape {
+            b'\n'   => {},
+            b'\\'   => bytes.push(b'\\'),
+            b'\''   => bytes.push(b'\''),
+            b'"'    => bytes.push(b'"'),
+            b'a'    => bytes.push(0x07),
+            b'b'    => bytes.push(0x08),
+            b'f'    => bytes.push(0x0c),
+            b'n'    => bytes.push(b'\n'),
+            b'r'    => bytes.push(b'\r'),
+            b't'    => bytes.push(b'\t'),
+            b'v'    => bytes.push(0x0b),
+            b'0'...b'7' => {
+                let mut value = (escape - b'0') as u32;
+                let mut count = 1;
+                while count < 3 && idx < body.len() && (b'0' <= body[idx] && body[idx] <= b'7') {
+                    value = value * 8 + (body[idx] - b'0') as u32;
+                    idx += 1;
+                    count += 1;
+                }
+                bytes.push((value & 0xff) as u8);
+            },
+            b'x'    => {
+                let digits = match body.get(idx..idx + 2) {
+                    Some(digits) => digits,
+                    None => return Err(Error::value(&format!(
+                        "invalid \\x escape at position {}", idx - 2)))
+                };
+
+                match (hex_value(digits[0]), hex_value(digits[1])) {
+                    (Some(hi), Some(lo)) => bytes.push((hi << 4) | lo),
+                    _ => return Err(Error::value(&format!(
+                        "invalid \\x escape at position {}", idx - 2)))
+                };
+                idx += 2;
+            },
+            other   => {
+                bytes.push(b'\\');
+                bytes.push(other);
+            }
+        }
+    }
+
+    Ok(bytes)
+}
+
+
+/// Format the buffer the way python's `repr()` would with `prefix` as the type marker,
+/// e.g. `b'\x00abc'` or `bytearray(b'abc')` when called with the matching arguments.
+pub fn repr(prefix: &str, data: &[u8]) -> rs::String {
+    let quote = if data.contains(&b'\'') && !data.contains(&b'"') {'"'} else {'\''};
+    let mut out = rs::String::with_capacity(data.len() + prefix.len() + 2);
+    out.push_str(prefix);
+    out.push(quote);
+
+    for &byte in data {
+        match byte {
+            b'\\'                       => out.push_str("\\\\"),
+            b'\t'                       => out.push_str("\\t"),
+            b'\n'                       => out.push_str("\\n"),
+            b'\r'                       => out.push_str("\\r"),
+            b'\'' if quote == '\''      => out.push_str("\\'"),
+            0x20...0x7e                 => out.push(byte as char),
+            _                           => out.push_str(&format!("\\x{:02x}", byte)),
+        }
+    }
+
+    out.push(quote);
+    out
+}
+
+
+/// `bytes.hex()` - lowercase hexadecimal digits, two per byte.
+pub fn hex(data: &[u8]) -> rs::String {
+    let mut out = rs::String::with_capacity(data.len() * 2);
+    for &byte in data {
+        out.push(HEX_DIGITS[(byte >> 4) as usize] as char);
+        out.push(HEX_DIGITS[(byte & 0x0f) as usize] as char);
+    }
+    out
+}
+
+
+/// `bytes.fromhex(string)` - inverse of `hex()`. ASCII whitespace is permitted
+/// between, but not within, each pair of digits.
+pub fn fromhex(string: &str) -> RtResult<rs::Bytes> {
+    let source = string.as_bytes();
+    let mut bytes = rs::Bytes::with_capacity(source.len() / 2);
+    let mut idx = 0;
+
+    while idx < source.len() {
+        if is_whitespace(source[idx]) {
+            idx += 1;
+            continue
+        }
+
+        let hi = hex_value(source[idx]);
+        let lo = source.get(idx + 1).and_then(|&b| hex_value(b));
+
+        match (hi, lo) {
+            (Some(hi), Some(lo)) => bytes.push((hi << 4) | lo),
+            (Some(_), None) => return Err(Error::value(&format!(
+                "non-hexadecimal number found in fromhex() arg at position {}", idx + 1))),
+            (None, _) => return Err(Error::value(&format!(
+                "non-hexadecimal number found in fromhex() arg at position {}", idx))),
+        };
+
+        idx += 2;
+    }
+
+    Ok(bytes)
+}
+
+
+/// `bytes.decode(encoding, errors)` for the encodings that do not require lookup tables:
+/// utf-8, ascii, and latin-1. `errors` may be one of `strict`, `replace`, or `ignore`.
+pub fn decode(data: &[u8], encoding: &str, errors: &str) -> RtResult<rs::String> {
+    let normalized = encoding.to_lowercase().replace("_", "-");
+
+    match (normalized.as_str(), errors) {
+        ("utf-8", "strict")     |
+        ("utf8", "strict")      => {
+            match str::from_utf8(data) {
+                Ok(string) => Ok(string.to_string()),
+                Err(err) => Err(Error::value(&format!(
+                    "'utf-8' codec can't decode byte 0x{:02x} in position {}: invalid start byte",
+                    data[err.valid_up_to()], err.valid_up_to())))
+            }
+        },
+        ("utf-8", "replace")    |
+        ("utf8", "replace")     => Ok(rs::String::from_utf8_lossy(data).to_string()),
+        ("utf-8", "ignore")     |
+        ("utf8", "ignore")      => Ok(rs::String::from_utf8_lossy(data).replace('\u{FFFD}', "")),
+        ("ascii", _)            => {
+            let mut out = rs::String::with_capacity(data.len());
+            for (idx, &byte) in data.iter().enumerate() {
+                match (byte, errors) {
+                    (0x00...0x7f, _)    => out.push(byte as char),
+                    (_, "replace")      => out.push('\u{FFFD}'),
+                    (_, "ignore")       => {},
+                    _ => return Err(Error::value(&format!(
+                        "'ascii' codec can't decode byte 0x{:02x} in position {}: ordinal not in range(128)",
+                        byte, idx)))
+                }
+            }
+            Ok(out)
+        },
+        ("latin-1", _)          |
+        ("latin1", _)           |
+        ("iso-8859-1", _)       => Ok(data.iter().map(|&b| b as char).collect()),
+        (_, "strict")           |
+        (_, "replace")          |
+        (_, "ignore")           => Err(Error::value(&format!("unknown encoding: {}", encoding))),
+        (_, other)              => Err(Error::value(&format!(
+            "unknown error handler name '{}'", other))),
+    }
+}
+
+
+/// `str.encode(encoding)` counterpart to `decode()` used by the `bytes()` and
+/// `bytearray()` constructors.
+pub fn encode(string: &str, encoding: &str) -> RtResult<rs::Bytes> {
+    let normalized = encoding.to_lowercase().replace("_", "-");
+
+    match normalized.as_str() {
+        "utf-8" | "utf8" => Ok(string.as_bytes().to_vec()),
+        "ascii" => {
+            match string.chars().position(|c| (c as u32) > 0x7f) {
+                Some(idx) => Err(Error::value(&format!(
+                    "'ascii' codec can't encode character in position {}: ordinal not in range(128)",
+                    idx))),
+                None => Ok(string.as_bytes().to_vec())
+            }
+        },
+        "latin-1" | "latin1" | "iso-8859-1" => {
+            string.chars().enumerate().map(|(idx, c)| {
+                match c as u32 {
+                    value @ 0...0xff => Ok(value as u8),
+                    _ => Err(Error::value(&format!(
+                        "'latin-1' codec can't encode character in position {}: ordinal not in range(256)",
+                        idx)))
+                }
+            }).collect()
+        },
+        _ => Err(Error::value(&format!("unknown encoding: {}", encoding)))
+    }
+}
+
+
+/// Find the lowest index of `needle` in `haystack[start..end]` using the python
+/// conventions for negative and out of range bounds. Returns -1 if not found.
+pub fn find(haystack: &[u8], needle: &[u8], start: Option<isize>, end: Option<isize>) -> isize {
+    let len = haystack.len() as isize;
+    let clamp = |idx: isize| -> usize {
+        let idx = if idx < 0 {idx + len} else {idx};
+        (if idx < 0 {0} else if idx > len {len} else {idx}) as usize
+    };
+
+    let start = clamp(start.unwrap_or(0));
+    let end = clamp(end.unwrap_or(len));
+
+    if start > end || end - start < needle.len() {
+        return -1
+    }
+
+    if needle.is_empty() {
+        return start as isize
+    }
+
+    haystack[start..end]
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|idx| (idx + start) as isize)
+        .unwrap_or(-1)
+}
+
+
+/// Split the buffer on `sep` or, if `sep` is `None`, on runs of ASCII whitespace. A
+/// negative `maxsplit` means no limit.
+pub fn split(data: &[u8], sep: Option<&[u8]>, maxsplit: isize) -> RtResult<Vec<rs::Bytes>> {
+    let mut parts: Vec<rs::Bytes> = Vec::new();
+    let mut splits = 0;
+
+    match sep {
+        Some(sep) if sep.is_empty() => return Err(Error::value("empty separator")),
+        Some(sep) => {
+            let mut begin = 0;
+            let mut idx = 0;
+
+            while idx + sep.len() <= data.len() && (maxsplit < 0 || splits < maxsplit) {
+                if &data[idx..idx + sep.len()] == sep {
+                    parts.push(data[begin..idx].to_vec());
+                    idx += sep.len();
+                    begin = idx;
+                    splits += 1;
+                } else {
+                    idx += 1;
+                }
+            }
+
+            parts.push(data[begin..].to_vec());
+        },
+        None => {
+            let mut idx = 0;
+
+            loop {
+                while idx < data.len() && is_whitespace(data[idx]) {
+                    idx += 1;
+                }
+
+                if idx == data.len() {
+                    break
+                }
+
+                if maxsplit >= 0 && splits == maxsplit {
+                    let mut end = data.len();
+                    while end > idx && is_whitespace(data[end - 1]) {
+                        end -= 1;
+                    }
+                    parts.push(data[idx..end].to_vec());
+                    break
+                }
+
+                let begin = idx;
+                while idx < data.len() && !is_whitespace(data[idx]) {
+                    idx += 1;
+                }
+
+                parts.push(data[begin..idx].to_vec());
+                splits += 1;
+            }
+        }
+    }
+
+    Ok(parts)
+}
+
+
+/// Convert an object into a single byte value for the mutating bytearray methods
+/// and the `bytes()` constructor.
+pub fn byte_from_object(object: &Type) -> RtResult<rs::Byte> {
+    match object {
+        &Type::Int(ref int) => {
+            match int.value.0.to_u8() {
+                Some(byte) => Ok(byte),
+                None => Err(Error::value("byte must be in range(0, 256)"))
+            }
+        },
+        &Type::Bool(ref boolean) => Ok(if boolean.value.0 {1} else {0}),
+        other => Err(Error::typerr(&format!(
+            "'{}' object cannot be interpreted as an integer", other.debug_name())))
+    }
+}
+
+
+/// Implements the shared argument handling of the `bytes(x)` and `bytearray(x)`
+/// constructors where `x` is an int count, a buffer, or an iterable of ints.
+pub fn from_object(rt: &Runtime, object: &RtObject) -> RtResult<rs::Bytes> {
+    match object.as_ref() {
+        &Type::Bytes(ref bytes) => Ok(bytes.value.0.clone()),
+        &Type::ByteArray(ref bytearray) => Ok(bytearray.value.0.borrow().clone()),
+        &Type::Int(ref int) => {
+            match int.value.0.to_isize() {
+                Some(count) if count < 0 => Err(Error::value("negative count")),
+                Some(count) => Ok(vec![0; count as usize]),
+                None => Err(Error::overflow("cannot fit 'int' into an index-sized integer"))
+            }
+        },
+        &Type::Str(_) => Err(Error::typerr("string argument without an encoding")),
+        _ => {
+            try_collect(&object.op_iter(rt)?)?.iter()
+                .map(|item| byte_from_object(item.as_ref()))
+                .collect::<RtResult<rs::Bytes>>()
+        }
+    }
+}
+
+
+/// Same as `from_object` for the case where the argument must already be a buffer
+/// such as the `sep` of `split()` and the right hand side of `+`.
+pub fn as_buffer(object: &Type) -> Option<rs::Bytes> {
+    match object {
+        &Type::Bytes(ref bytes) => Some(bytes.value.0.clone()),
+        &Type::ByteArray(ref bytearray) => Some(bytearray.value.0.borrow().clone()),
+        _ => None
+    }
+}
+
+
+/// Argument handling for `decode([encoding[, errors]])` shared by the buffer types.
+pub fn call_decode(data: &[u8], args: &[RtObject]) -> RtResult<rs::String> {
+    let encoding = match args.get(0) {
+        Some(object) => as_str(object.as_ref(), "decode() argument 1")?,
+        None => rs::String::from("utf-8")
+    };
+
+    let errors = match args.get(1) {
+        Some(object) => as_str(object.as_ref(), "decode() argument 2")?,
+        None => rs::String::from("strict")
+    };
+
+    decode(data, &encoding, &errors)
+}
+
+
+/// Argument handling for `find(sub[, start[, end]])` shared by the buffer types. `sub`
+/// may be a buffer or a single int byte value.
+pub fn call_find(data: &[u8], args: &[RtObject]) -> RtResult<isize> {
+    let needle = match args.get(0).map(|object| object.as_ref()) {
+        Some(&Type::Int(_)) => vec![byte_from_object(args[0].as_ref())?],
+        Some(other) => match as_buffer(other) {
+            Some(bytes) => bytes,
+            None => return Err(Error::typerr(&format!(
+                "argument should be integer or bytes-like object, not '{}'", other.debug_name())))
+        },
+        None => return Err(Error::typerr("find() takes at least 1 argument (0 given)"))
+    };
+
+    let start = match args.get(1) {
+        Some(object) => as_index(object.as_ref())?,
+        None => None
+    };
+
+    let end = match args.get(2) {
+        Some(object) => as_index(object.as_ref())?,
+        None => None
+    };
+
+    Ok(find(data, &needle, start, end))
+}
+
+
+/// Argument handling for `split([sep[, maxsplit]])` shared by the buffer types.
+pub fn call_split(data: &[u8], args: &[RtObject]) -> RtResult<Vec<rs::Bytes>> {
+    let sep = match args.get(0).map(|object| object.as_ref()) {
+        Some(&Type::None(_)) | None => None,
+        Some(other) => match as_buffer(other) {
+            Some(bytes) => Some(bytes),
+            None => return Err(Error::typerr(&format!(
+                "a bytes-like object is required, not '{}'", other.debug_name())))
+        }
+    };
+
+    let maxsplit = match args.get(1) {
+        Some(object) => as_index(object.as_ref())?.unwrap_or(-1),
+        None => -1
+    };
+
+    split(data, sep.as_ref().map(|bytes| &bytes[..]), maxsplit)
+}
+
+
+/// Argument handling for `fromhex(string)` shared by the buffer types.
+pub fn call_fromhex(args: &[RtObject]) -> RtResult<rs::Bytes> {
+    match args.get(0) {
+        Some(object) => fromhex(&as_str(object.as_ref(), "fromhex() argument")?),
+        None => Err(Error::typerr("fromhex() takes exactly one argument (0 given)"))
+    }
+}
+
+
+fn as_str(object: &Type, context: &str) -> RtResult<rs::String> {
+    match object {
+        &Type::Str(ref string) => Ok(string.value.0.clone()),
+        other => Err(Error::typerr(&format!(
+            "{} must be str, not {}", context, other.debug_name())))
+    }
+}
+
+
+fn as_index(object: &Type) -> RtResult<Option<isize>> {
+    match object {
+        &Type::None(_) => Ok(None),
+        &Type::Int(ref int) => match int.value.0.to_isize() {
+            Some(idx) => Ok(Some(idx)),
+            None => Err(Error::overflow("cannot fit 'int' into an index-sized integer"))
+        },
+        other => Err(Error::typerr(&format!(
+            "slice indices must be integers or None, not {}", other.debug_name())))
+    }
+}
+
+
+#[inline(always)]
+fn is_whitespace(byte: u8) -> bool {
+    match byte {
+        b' ' | b'\t' | b'\n' | b'\r' | 0x0b | 0x0c => true,
+        _ => false
+    }
+}
+
+
+#[inline(always)]
+fn hex_value(byte: u8) -> Option<u8> {
+    match byte {
+        b'0'...b'9' => Some(byte - b'0'),
+        b'a'...b'f' => Some(byte - b'a' + 10),
+        b'A'...b'F' => Some(byte - b'A' + 10),
+        _ => None
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_literal_escapes() {
+        let decoded = decode_literal(br#"'\x00\x7fabc\n\\\'\101'"#).unwrap();
+        assert_eq!(decoded, b"\x00\x7fabc\n\\'A".to_vec());
+    }
+
+    #[test]
+    fn decode_literal_triple_quoted() {
+        let decoded = decode_literal(br#""""a"b""""#).unwrap();
+        assert_eq!(decoded, b"a\"b".to_vec());
+    }
+
+    #[test]
+    fn hex_roundtrip() {
+        let data = b"\xde\xad\xbe\xef\x00";
+        assert_eq!(hex(data), "deadbeef00");
+        assert_eq!(fromhex("de ad be ef 00").unwrap(), data.to_vec());
+        assert!(fromhex("dea").is_err());
+    }
+
+    #[test]
+    fn find_and_split() {
+        assert_eq!(find(b"hello world", b"o", None, None), 4);
+        assert_eq!(find(b"hello world", b"o", Some(5), None), 7);
+        assert_eq!(find(b"hello world", b"z", None, None), -1);
+
+        assert_eq!(split(b"a,b,,c", Some(b","), -1).unwrap(),
+                   vec![b"a".to_vec(), b"b".to_vec(), b"".to_vec(), b"c".to_vec()]);
+        assert_eq!(split(b"  a  b c ", None, 1).unwrap(),
+                   vec![b"a".to_vec(), b"b c".to_vec()]);
+    }
+
+    #[test]
+    fn repr_quotes() {
+        assert_eq!(repr("b", b"abc\x00"), r"b'abc\x00'");
+        assert_eq!(repr("b", b"it's"), r#"b"it's""#);
+    }
+}
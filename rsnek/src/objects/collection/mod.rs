@@ -1,2 +1,3 @@
 //! DRY generic functions usable by all collection types
+pub mod buffer;
 pub mod sequence;
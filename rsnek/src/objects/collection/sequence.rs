@@ -1,5 +1,6 @@
 //! Common functions for sequence like data. Generally this mean that the backing data type
 //! is representable as a slice or an iterator.
+use std::cell::RefCell;
 use std::iter::FromIterator;
 use std::ops::Deref;
 use std::borrow::Borrow;
@@ -22,17 +23,51 @@ pub fn is_sequence(object: &RtObject) -> rs::Boolean {
         &Type::Set(_)        |
         &Type::FrozenSet(_)  |
         &Type::Str(_)        |
-        &Type::Bytes(_)      => true,
+        &Type::Bytes(_)      |
+        &Type::ByteArray(_)  => true,
         _ => false
     }
 }
 
 /// Determine if two two sequences are equal by first comparing their lengths
-/// then comparing each of their elements, in order.
+/// then comparing each of their elements, in order. Like CPython, elements that are the
+/// same object are equal without being compared so a list containing itself terminates.
 pub fn equals<'a>(left: &'a [RtObject], right: &'a [RtObject]) -> rs::Boolean {
     ((left.len() == right.len()) &&
         left.iter().zip(right.iter())
-            .all(|(l, r)| l == r))
+            .all(|(l, r)| l.id() == r.id() || l == r))
+}
+
+
+thread_local!(
+    /// Ids of the containers currently being formatted by this thread, see `enter_repr`.
+    static REPR_STACK: RefCell<Vec<rs::ObjectId>> = RefCell::new(Vec::new())
+);
+
+
+/// Run `format` unless the container identified by `id` is already being formatted further
+/// up the stack, in which case `None` is returned so the caller can write the `[...]`
+/// placeholder instead of recursing forever. The equivalent of CPython's `Py_ReprEnter`.
+pub fn enter_repr<T, F>(id: rs::ObjectId, format: F) -> Option<T>
+    where F: FnOnce() -> T {
+
+    let entered = REPR_STACK.with(|stack| {
+        let mut stack = stack.borrow_mut();
+        if stack.contains(&id) {
+            return false;
+        }
+
+        stack.push(id);
+        true
+    });
+
+    if !entered {
+        return None;
+    }
+
+    let result = format();
+    REPR_STACK.with(|stack| stack.borrow_mut().retain(|&active| active != id));
+    Some(result)
 }
 
 /// Test if `item` is contained in the sequence `seq`
@@ -85,4 +120,60 @@ pub fn get_index<'a, T>(seq: &'a [T], index: &ToPrimitive) -> RtResult<T>
 
     Err(rsnek_exception_index!("sequence"))
 
-}
\ No newline at end of file
+}
+
+/// Resolve the python slice indices `start`, `stop`, and `step` against a sequence of
+/// length `len`. Any of the values can be `None` to use the default for the direction
+/// of the step. Returns the normalized `(start, stop, step)` in the same form as
+/// `slice.indices(len)`.
+pub fn slice_indices(len: usize, start: Option<isize>, stop: Option<isize>,
+                     step: Option<isize>) -> RtResult<(isize, isize, isize)> {
+    let step = step.unwrap_or(1);
+    if step == 0 {
+        return Err(Error::value("slice step cannot be zero"))
+    }
+
+    let len = len as isize;
+    let (lower, upper) = if step < 0 {(-1, len - 1)} else {(0, len)};
+
+    let clamp = |idx: isize| -> isize {
+        if idx < 0 {
+            let idx = idx + len;
+            if idx < lower {lower} else {idx}
+        } else if idx > upper {
+            upper
+        } else {
+            idx
+        }
+    };
+
+    let start = match start {
+        Some(idx) => clamp(idx),
+        None => if step < 0 {upper} else {lower}
+    };
+
+    let stop = match stop {
+        Some(idx) => clamp(idx),
+        None => if step < 0 {lower} else {upper}
+    };
+
+    Ok((start, stop, step))
+}
+
+
+/// Create a new `Vec` from the elements of `seq` selected by the python slice semantics
+/// of `seq[start:stop:step]`.
+pub fn get_slice<'a, T>(seq: &'a [T], start: Option<isize>, stop: Option<isize>,
+                        step: Option<isize>) -> RtResult<Vec<T>>
+    where T: Clone, {
+    let (start, stop, step) = slice_indices(seq.len(), start, stop, step)?;
+
+    let mut elems = Vec::new();
+    let mut idx = start;
+    while (step > 0 && idx < stop) || (step < 0 && idx > stop) {
+        elems.push(seq[idx as usize].clone());
+        idx += step;
+    }
+
+    Ok(elems)
+}
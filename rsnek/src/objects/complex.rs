@@ -8,13 +8,19 @@
 use std::fmt;
 use std::ops::Deref;
 use std::borrow::Borrow;
+
+use num::ToPrimitive;
+
 use runtime::Runtime;
+use ::runtime::traits::{BooleanProvider, IntegerProvider};
 use api::{RtValue, PyAPI, method, typing};
 use api::selfref::{self, SelfRef};
+use ::api::result::{ObjectResult, RtResult};
 
 use ::system::primitives as rs;
 use ::modules::builtins::Type;
 use ::api::RtObject;
+use ::objects::number;
 
 
 #[derive(Clone)]
@@ -79,6 +85,40 @@ impl fmt::Debug for PyComplex {
 impl PyAPI for PyComplex {}
 
 
+/// `hash(self)`
+impl method::Hashed for PyComplex {
+    fn op_hash(&self, rt: &Runtime) -> ObjectResult {
+        let hash = self.native_hash()?;
+        Ok(rt.int(hash))
+    }
+
+    fn native_hash(&self) -> RtResult<rs::HashId> {
+        Ok(number::hash_complex(&self.value.0))
+    }
+}
+
+
+/// `self == rhs`
+impl method::Equal for PyComplex {
+    fn op_eq(&self, rt: &Runtime, rhs: &RtObject) -> ObjectResult {
+        let value = self.native_eq(rhs.as_ref())?;
+        Ok(rt.bool(value))
+    }
+
+    fn native_eq(&self, other: &Type) -> RtResult<rs::Boolean> {
+        let complex = &self.value.0;
+
+        match *other {
+            Type::Complex(ref obj) => Ok(*complex == obj.value.0),
+            Type::Float(ref obj) => Ok(complex.im == 0.0 && complex.re == obj.value.0),
+            Type::Int(ref obj) => Ok(complex.im == 0.0 && Some(complex.re) == obj.value.0.to_f64()),
+            Type::Bool(ref obj) => Ok(complex.im == 0.0 && Some(complex.re) == obj.value.0.to_f64()),
+            _ => Ok(false),
+        }
+    }
+}
+
+
 method_not_implemented!(PyComplex,
     AbsValue   Add   AddItem   Append
     Await   BitwiseAnd   BitwiseOr   BooleanCast
@@ -86,9 +126,9 @@ method_not_implemented!(PyComplex,
     ComplexCast   Contains   Count   DelAttr
     Delete   DeleteItem   DescriptorGet   DescriptorSet
     DescriptorSetName   Discard   DivMod   Enter
-    Equal   Exit   Extend   FloatCast
+    Exit   Extend   FloatCast
     FloorDivision   Get   GetAttr   GetAttribute
-    GetItem   GreaterOrEqual   GreaterThan   Hashed
+    GetItem   GreaterOrEqual   GreaterThan
     Id   InPlaceAdd   InPlaceBitwiseAnd   InPlaceBitwiseOr
     InPlaceDivMod   InPlaceFloorDivision   InPlaceLeftShift   InPlaceMatrixMultiply
     InPlaceModulus   InPlaceMultiply   InPlacePow   InPlaceRightShift
@@ -113,13 +153,22 @@ method_not_implemented!(PyComplex,
 #[cfg(test)]
 mod tests {
     use ::runtime::Runtime;
+    use ::runtime::traits::{FloatProvider, IntegerProvider};
+    use ::api::method::{Equal, Hashed};
+    use ::api::typing::BuiltinType;
+    use super::*;
 
     fn setup() -> (Runtime, ) {
         (Runtime::new(), )
     }
 
     #[test]
-    fn stub() {
-        info!("stub");
+    fn hash_eq_real_parts() {
+        let (rt, ) = setup();
+        let complex = PyComplexType::inject_selfref(PyComplexType::alloc(rs::Complex::new(2.0, 0.0)));
+
+        assert_eq!(complex.native_hash().unwrap(), rt.int(2).native_hash().unwrap());
+        assert_eq!(complex.native_hash().unwrap(), rt.float(2.0).native_hash().unwrap());
+        assert_eq!(complex.native_eq(rt.int(2).as_ref()).unwrap(), true);
     }
 }
\ No newline at end of file
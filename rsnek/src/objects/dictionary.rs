@@ -8,23 +8,35 @@
 use std::fmt;
 use std::ops::Deref;
 use std::borrow::Borrow;
-use std::cell::RefCell;
+use std::sync::atomic::{AtomicUsize, Ordering, ATOMIC_USIZE_INIT};
 
 use ::api::result::Error;
-use ::api::method::{self, Hashed, StringRepresentation};
+use ::api::method::{self, GetItem, Hashed, SetItem, StringRepresentation};
 use ::api::RtObject;
 use ::api::selfref::{self, SelfRef};
 use ::api::{self, RtValue, typing};
 use ::api::result::{RtResult, ObjectResult};
 use ::runtime::Runtime;
-use ::runtime::traits::{IntegerProvider, NoneProvider, BooleanProvider, TupleProvider};
+use ::runtime::traits::{IntegerProvider, NoneProvider, BooleanProvider, FunctionProvider, TupleProvider};
 use ::modules::builtins::Type;
-use ::system::primitives::{DictKey};
+use ::modules::precondition::{check_args, check_kwargs};
+use ::resources::strings;
+use ::system::primitives::{DictKey, SignatureBuilder};
 use ::system::primitives as rs;
+use ::system::sync::{Cell, RefCell};
 
 
 const TYPE_NAME: &'static str = "dict";
 
+/// Source of dict versions. Every change to any dict takes the next version so
+/// a cached `(dict, version)` pair is still valid if the version is the same.
+static DICT_VERSION: AtomicUsize = ATOMIC_USIZE_INIT;
+
+
+fn next_version() -> usize {
+    DICT_VERSION.fetch_add(1, Ordering::Relaxed) + 1
+}
+
 
 #[derive(Clone)]
 pub struct PyDictType;
@@ -58,16 +70,59 @@ impl typing::BuiltinType for PyDictType {
 
     fn alloc(value: Self::V) -> Self::T {
         PyDict {
-            value: DictValue(RefCell::new(value)),
+            value: DictValue(RefCell::new(value), Cell::new(next_version())),
             rc: selfref::RefCount::default(),
         }
     }
 }
 
-pub struct DictValue(pub RefCell<rs::Dict>);
+pub struct DictValue(pub RefCell<rs::Dict>, pub Cell<usize>);
 pub type PyDict = RtValue<DictValue>;
 
 
+impl PyDict {
+    /// Version of the contents of the dict, see `touch`
+    pub fn version(&self) -> usize {
+        self.value.1.get()
+    }
+
+    /// Give the dict a new version, must be called by anything that changes
+    /// the contents of the dict.
+    pub fn touch(&self) {
+        self.value.1.set(next_version());
+    }
+
+    pub fn get_attribute(&self, rt: &Runtime, name: &str) -> ObjectResult {
+        match name {
+            "__getitem__"       => self.try_get_binary_method(rt, name),
+            "__setitem__"       => self.try_get_ternary_method(rt, name),
+            missing => Err(Error::attribute(
+                &strings_error_no_attribute!(TYPE_NAME, missing)))
+        }
+    }
+
+    fn try_get_binary_method(&self, rt: &Runtime, name: &str) -> ObjectResult {
+        let func = match name {
+            "__getitem__"   => {PyDict::op_getitem},
+            missing => return Err(Error::attribute(
+                &strings_error_no_attribute!(TYPE_NAME, missing)))
+        };
+
+        binary_method_wrapper!(self, TYPE_NAME, name, rt, Type::Dict, func)
+    }
+
+    fn try_get_ternary_method(&self, rt: &Runtime, name: &str) -> ObjectResult {
+        let func = match name {
+            "__setitem__"   => {PyDict::op_setitem},
+            missing => return Err(Error::attribute(
+                &strings_error_no_attribute!(TYPE_NAME, missing)))
+        };
+
+        ternary_method_wrapper!(self, TYPE_NAME, name, rt, Type::Dict, func)
+    }
+}
+
+
 impl fmt::Debug for PyDict {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{:?}", self.value.0.borrow())
@@ -141,6 +196,20 @@ impl method::Length for PyDict {
     }
 }
 
+impl method::GetAttr for PyDict {
+    fn op_getattr(&self, rt: &Runtime, name: &RtObject) -> ObjectResult {
+        match name.as_ref() {
+            &Type::Str(ref pystring) => {
+                let string = pystring.value.0.clone();
+                self.get_attribute(&rt, &string)
+            }
+            other => Err(Error::typerr(&format!(
+                "getattr <{}>' requires string for attribute names, not {}",
+                TYPE_NAME, other.debug_name())))
+        }
+    }
+}
+
 impl method::GetItem for PyDict {
     #[allow(unused_variables)]
     fn op_getitem(&self, rt: &Runtime, key: &RtObject) -> ObjectResult {
@@ -191,6 +260,7 @@ impl method::SetItem for PyDict {
         match key {
             &Type::DictKey(ref key) => {
                 self.value.0.borrow_mut().insert(key.clone(), value.upgrade()?);
+                self.touch();
                 Ok(rs::None())
             }
             _ => Err(Error::typerr("key is not a dictkey type")),
@@ -223,7 +293,7 @@ method_not_implemented!(PyDict,
     DeleteItem   DescriptorGet   DescriptorSet   DescriptorSetName   
     Discard   DivMod   Enter   Equal   
     Exit   Extend   FloatCast   FloorDivision   
-    Get   GetAttr   GetAttribute   GreaterOrEqual   
+    Get   GetAttribute   GreaterOrEqual   
     GreaterThan   Id   Index   Init   
     InPlaceAdd   InPlaceBitwiseAnd   InPlaceBitwiseOr   InPlaceDivMod   
     InPlaceFloorDivision   InPlaceLeftShift   InPlaceMatrixMultiply   InPlaceModulus   
@@ -247,7 +317,7 @@ method_not_implemented!(PyDict,
 
 #[cfg(test)]
 mod tests {
-    use ::runtime::traits::{StringProvider, DictProvider};
+    use ::runtime::traits::{StringProvider, DictProvider, FloatProvider};
     use api::method::*;
     use super::*;
 
@@ -339,4 +409,17 @@ mod tests {
         assert_eq!(result, value);
     }
 
+    /// Numbers that compare equal hash equal so they find the same key
+    #[test]
+    fn __getitem__numeric_keys() {
+        let rt = setup_test();
+        let dict = rt.dict(rs::None());
+
+        let value = rt.str("a");
+        dict.op_setitem(&rt, &rt.int(1), &value).unwrap();
+
+        assert_eq!(dict.op_getitem(&rt, &rt.float(1.0)).unwrap(), value);
+        assert_eq!(dict.op_getitem(&rt, &rt.bool(true)).unwrap(), value);
+    }
+
 }
@@ -7,17 +7,22 @@
 //!
 use std::fmt;
 use std::borrow::Borrow;
+use std::cmp::Ordering;
 use std::ops::Deref;
 
 use num::Zero;
-use num::ToPrimitive;
 
 use runtime::Runtime;
-use ::runtime::traits::{BooleanProvider, StringProvider, IntegerProvider, FloatProvider};
-use ::api::result::Error;
+use ::modules::precondition::{check_args, check_kwargs};
+use ::runtime::traits::{BooleanProvider, StringProvider, IntegerProvider, FloatProvider,
+                        ComplexProvider, FunctionProvider, TupleProvider};
+use ::api::result::{Error, ErrorType};
 use ::api::result::{RtResult, ObjectResult};
+use ::api::method::*;
 use api::{self, RtValue, method, typing};
 use api::selfref::{self, SelfRef};
+use ::resources::strings;
+use ::system::primitives::SignatureBuilder;
 
 use ::system::primitives as rs;
 use ::api::RtObject;
@@ -25,6 +30,9 @@ use ::modules::builtins::Type;
 use objects::number::{self, FloatAdapter, IntAdapter};
 
 
+const TYPE_NAME: &'static str = "float";
+
+
 #[derive(Clone)]
 pub struct PyFloatType {}
 
@@ -70,6 +78,123 @@ pub struct FloatValue(pub rs::Float);
 pub type PyFloat = RtValue<FloatValue>;
 
 
+impl PyFloat {
+    pub fn get_attribute(&self, rt: &Runtime, name: &str) -> ObjectResult {
+        match name {
+            "__abs__"           |
+            "__bool__"          |
+            "__float__"         |
+            "__hash__"          |
+            "__int__"           |
+            "__neg__"           |
+            "__pos__"           |
+            "__repr__"          |
+            "__round__"         |
+            "__str__"           |
+            "as_integer_ratio"  |
+            "hex"               |
+            "is_integer"        => self.try_get_unary_method(rt, name),
+            "__add__"           |
+            "__divmod__"        |
+            "__eq__"            |
+            "__floordiv__"      |
+            "__ge__"            |
+            "__gt__"            |
+            "__le__"            |
+            "__lt__"            |
+            "__mod__"           |
+            "__mul__"           |
+            "__ne__"            |
+            "__sub__"           |
+            "__truediv__"       => self.try_get_binary_method(rt, name),
+            "__pow__"           => self.try_get_ternary_method(rt, name),
+            missing => Err(Error::attribute(
+                &strings_error_no_attribute!(TYPE_NAME, missing)))
+        }
+    }
+
+    fn try_get_unary_method(&self, rt: &Runtime, name: &str) -> ObjectResult {
+        let func = match name {
+            "__abs__"           => {PyFloat::op_abs},
+            "__bool__"          => {PyFloat::op_bool},
+            "__float__"         => {PyFloat::op_float},
+            "__hash__"          => {PyFloat::op_hash},
+            "__int__"           => {PyFloat::op_int},
+            "__neg__"           => {PyFloat::op_neg},
+            "__pos__"           => {PyFloat::op_pos},
+            "__repr__"          => {PyFloat::op_repr},
+            "__round__"         => {PyFloat::op_round},
+            "__str__"           => {PyFloat::op_str},
+            "as_integer_ratio"  => {PyFloat::meth_as_integer_ratio},
+            "hex"               => {PyFloat::meth_hex},
+            "is_integer"        => {PyFloat::meth_is_integer},
+            missing => return Err(Error::attribute(
+                &strings_error_no_attribute!(TYPE_NAME, missing)))
+        };
+
+        unary_method_wrapper!(self, TYPE_NAME, name, rt, Type::Float, func)
+    }
+
+    fn try_get_binary_method(&self, rt: &Runtime, name: &str) -> ObjectResult {
+        let func = match name {
+            "__add__"           => {PyFloat::op_add},
+            "__divmod__"        => {PyFloat::op_divmod},
+            "__eq__"            => {PyFloat::op_eq},
+            "__floordiv__"      => {PyFloat::op_floordiv},
+            "__ge__"            => {PyFloat::op_ge},
+            "__gt__"            => {PyFloat::op_gt},
+            "__le__"            => {PyFloat::op_le},
+            "__lt__"            => {PyFloat::op_lt},
+            "__mod__"           => {PyFloat::op_mod},
+            "__mul__"           => {PyFloat::op_mul},
+            "__ne__"            => {PyFloat::op_ne},
+            "__sub__"           => {PyFloat::op_sub},
+            "__truediv__"       => {PyFloat::op_truediv},
+            missing => return Err(Error::attribute(
+                &strings_error_no_attribute!(TYPE_NAME, missing)))
+        };
+
+        binary_method_wrapper!(self, TYPE_NAME, name, rt, Type::Float, func)
+    }
+
+    fn try_get_ternary_method(&self, rt: &Runtime, name: &str) -> ObjectResult {
+        let func = match name {
+            "__pow__"           => {PyFloat::op_pow},
+            missing => return Err(Error::attribute(
+                &strings_error_no_attribute!(TYPE_NAME, missing)))
+        };
+
+        ternary_method_wrapper!(self, TYPE_NAME, name, rt, Type::Float, func)
+    }
+
+    /// `float.is_integer()`
+    pub fn meth_is_integer(&self, rt: &Runtime) -> ObjectResult {
+        let value = self.value.0;
+        Ok(rt.bool(value.is_finite() && value.fract() == 0.0))
+    }
+
+    /// `float.as_integer_ratio()`
+    pub fn meth_as_integer_ratio(&self, rt: &Runtime) -> ObjectResult {
+        let (numerator, denominator) = number::float_as_integer_ratio(self.value.0)?;
+        Ok(rt.tuple(vec![rt.int(numerator), rt.int(denominator)]))
+    }
+
+    /// `float.hex()`
+    pub fn meth_hex(&self, rt: &Runtime) -> ObjectResult {
+        Ok(rt.str(number::float_hex(self.value.0)))
+    }
+
+    /// Widen the right hand side of a binary operator to a float
+    fn rhs_float(op: &str, rhs: &Type) -> RtResult<rs::Float> {
+        match number::as_float(rhs) {
+            Some(result) => result,
+            None => Err(Error::typerr(
+                &strings_error_bad_operand!(op, TYPE_NAME, rhs.debug_name())))
+        }
+    }
+}
+
+
 impl fmt::Display for PyFloat {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{}", self.value.0)
@@ -86,8 +211,16 @@ impl fmt::Debug for PyFloat {
 impl api::PyAPI for PyFloat {}
 
 
+/// `hash(self)`
 impl method::Hashed for PyFloat {
-    // TODO: {T87} python has its own algo for hashing floats ensure to look at that for compat.
+    fn op_hash(&self, rt: &Runtime) -> ObjectResult {
+        let hash = self.native_hash()?;
+        Ok(rt.int(hash))
+    }
+
+    fn native_hash(&self) -> RtResult<rs::HashId> {
+        Ok(number::hash_float(self.value.0))
+    }
 }
 
 impl method::StringCast for PyFloat {
@@ -117,6 +250,21 @@ impl method::StringRepresentation for PyFloat {
     }
 }
 
+/// `self.rhs`
+impl method::GetAttr for PyFloat {
+    fn op_getattr(&self, rt: &Runtime, name: &RtObject) -> ObjectResult {
+        match name.as_ref() {
+            &Type::Str(ref pystring) => {
+                let string = pystring.value.0.clone();
+                self.get_attribute(&rt, &string)
+            }
+            other => Err(Error::typerr(
+                string_error_bad_attr_type!("str", other.debug_name())))
+        }
+    }
+}
+
+
 impl method::Equal for PyFloat {
     fn op_eq(&self, rt: &Runtime, rhs: &RtObject) -> ObjectResult {
         match self.native_eq(rhs.as_ref()) {
@@ -129,12 +277,25 @@ impl method::Equal for PyFloat {
         match *other {
             Type::Float(ref float) => Ok(self.value.0 == float.value.0),
             Type::Int(ref int) => Ok(FloatAdapter(&self.value.0) == IntAdapter(&int.value.0)),
+            Type::Bool(ref boolean) => Ok(FloatAdapter(&self.value.0) == IntAdapter(&boolean.value.0)),
             _ => Ok(false),
         }
     }
 }
 
 
+impl method::NotEqual for PyFloat {
+    fn op_ne(&self, rt: &Runtime, rhs: &RtObject) -> ObjectResult {
+        let truth = self.native_ne(rhs.as_ref())?;
+        Ok(rt.bool(truth))
+    }
+
+    fn native_ne(&self, other: &Type) -> RtResult<rs::Boolean> {
+        Ok(!self.native_eq(other)?)
+    }
+}
+
+
 impl method::BooleanCast for PyFloat {
     fn op_bool(&self, rt: &Runtime) -> ObjectResult {
         if self.native_bool()? {
@@ -151,14 +312,12 @@ impl method::BooleanCast for PyFloat {
 
 impl method::IntegerCast for PyFloat {
     fn op_int(&self, rt: &Runtime) -> ObjectResult {
-        match self.native_int() {
-            Ok(int) => Ok(rt.int(int)),
-            _ => unreachable!()
-        }
+        let int = self.native_int()?;
+        Ok(rt.int(int))
     }
 
     fn native_int(&self) -> RtResult<rs::Integer> {
-        return Ok(rs::Integer::from(self.value.0 as i64));
+        number::float_to_int(self.value.0)
     }
 }
 
@@ -174,59 +333,175 @@ impl method::FloatCast for PyFloat {
 }
 
 
+/// `-self`
+impl method::NegateValue for PyFloat {
+    fn op_neg(&self, rt: &Runtime) -> ObjectResult {
+        Ok(rt.float(-self.value.0))
+    }
+
+    fn native_neg(&self) -> RtResult<rs::Number> {
+        Ok(rs::Number::Float(-self.value.0))
+    }
+}
+
+
+/// `+self`
+impl method::PositiveValue for PyFloat {
+    #[allow(unused_variables)]
+    fn op_pos(&self, rt: &Runtime) -> ObjectResult {
+        self.rc.upgrade()
+    }
+
+    fn native_pos(&self) -> RtResult<rs::Number> {
+        Ok(rs::Number::Float(self.value.0))
+    }
+}
+
+
+/// `abs(self)`
+impl method::AbsValue for PyFloat {
+    fn op_abs(&self, rt: &Runtime) -> ObjectResult {
+        Ok(rt.float(self.value.0.abs()))
+    }
+
+    fn native_abs(&self) -> RtResult<rs::Number> {
+        Ok(rs::Number::Float(self.value.0.abs()))
+    }
+}
+
+
+/// `round(self)` rounds half to even and gives back an int
+impl method::Rounding for PyFloat {
+    fn op_round(&self, rt: &Runtime) -> ObjectResult {
+        match self.native_round()? {
+            rs::Number::Int(int) => Ok(rt.int(int)),
+            _ => unreachable!()
+        }
+    }
+
+    fn native_round(&self) -> RtResult<rs::Number> {
+        let rounded = number::float_round(self.value.0, 0)?;
+        Ok(rs::Number::Int(number::float_to_int(rounded)?))
+    }
+}
+
+
+/// `self + rhs`
 impl method::Add for PyFloat {
     fn op_add(&self, rt: &Runtime, rhs: &RtObject) -> ObjectResult {
-        match rhs.as_ref(){
-            &Type::Float(ref rhs) => {
-                // TODO: {T103} Use checked arithmetic where appropriate... this is not the only
-                // example. But the float (and some int) methods are likely to be the highest
-                // frequency.
-                Ok(rt.float(self.value.0 + rhs.value.0))
-            }
-            &Type::Int(ref rhs) => {
-                match rhs.value.0.to_f64() {
-                    Some(float) => Ok(rt.float(self.value.0 + float)),
-                    None => Err(Error::overflow(&format!("{:?} + {} overflows", self.value.0, rhs.value.0))),
-                }
-            }
-            other => Err(Error::typerr(&format!("Cannot add {} to float", other.debug_name()))),
-        }
+        let rhs = PyFloat::rhs_float("+", rhs.as_ref())?;
+        Ok(rt.float(self.value.0 + rhs))
+    }
+}
+
+
+/// `self - rhs`
+impl method::Subtract for PyFloat {
+    fn op_sub(&self, rt: &Runtime, rhs: &RtObject) -> ObjectResult {
+        let rhs = PyFloat::rhs_float("-", rhs.as_ref())?;
+        Ok(rt.float(self.value.0 - rhs))
+    }
+}
+
+
+/// `self * rhs`
+impl method::Multiply for PyFloat {
+    fn op_mul(&self, rt: &Runtime, rhs: &RtObject) -> ObjectResult {
+        let rhs = PyFloat::rhs_float("*", rhs.as_ref())?;
+        Ok(rt.float(self.value.0 * rhs))
+    }
+}
+
+
+/// `self / rhs`
+impl method::TrueDivision for PyFloat {
+    fn op_truediv(&self, rt: &Runtime, rhs: &RtObject) -> ObjectResult {
+        let rhs = PyFloat::rhs_float("/", rhs.as_ref())?;
+        Ok(rt.float(number::float_truediv(self.value.0, rhs)?))
+    }
+}
+
+
+/// `self // rhs`
+impl method::FloorDivision for PyFloat {
+    fn op_floordiv(&self, rt: &Runtime, rhs: &RtObject) -> ObjectResult {
+        let rhs = PyFloat::rhs_float("//", rhs.as_ref())?;
+        Ok(rt.float(number::float_floordiv(self.value.0, rhs)?))
+    }
+}
+
+
+/// `self % rhs`
+impl method::Modulus for PyFloat {
+    fn op_mod(&self, rt: &Runtime, rhs: &RtObject) -> ObjectResult {
+        let rhs = PyFloat::rhs_float("%", rhs.as_ref())?;
+        Ok(rt.float(number::float_mod(self.value.0, rhs)?))
+    }
+}
+
+
+/// `divmod(self, rhs)`
+impl method::DivMod for PyFloat {
+    fn op_divmod(&self, rt: &Runtime, rhs: &RtObject) -> ObjectResult {
+        let rhs = PyFloat::rhs_float("divmod()", rhs.as_ref())?;
+        let (div, modulus) = number::float_divmod(self.value.0, rhs)?;
+        Ok(rt.tuple(vec![rt.float(div), rt.float(modulus)]))
     }
+}
+
+
+/// `self ** rhs`
+impl method::Pow for PyFloat {
+    fn op_pow(&self, rt: &Runtime, exponent: &RtObject, modulus: &RtObject) -> ObjectResult {
+        match modulus.as_ref() {
+            &Type::None(_) => {},
+            _ => return Err(Error::typerr(
+                "pow() 3rd argument not allowed unless all arguments are integers"))
+        }
 
+        let exponent = PyFloat::rhs_float("** or pow()", exponent.as_ref())?;
+        match number::float_pow(self.value.0, exponent)? {
+            rs::Number::Float(float) => Ok(rt.float(float)),
+            rs::Number::Complex(complex) => Ok(rt.complex(complex)),
+            _ => unreachable!()
+        }
+    }
 }
 
+/// `self < rhs`, `self <= rhs`, `self > rhs`, `self >= rhs`
+ordering_comparison_impls!(PyFloat, TYPE_NAME, |this: &PyFloat, rhs: &Type| number::float_cmp(this.value.0, rhs));
+
+
 method_not_implemented!(PyFloat,
-    AbsValue   AddItem   Append   Await   
-    BitwiseAnd   BitwiseOr   BytesCast   Call   
-    Clear   Close   ComplexCast   Contains   
-    Count   DelAttr   Delete   DeleteItem   
-    DescriptorGet   DescriptorSet   DescriptorSetName   Discard   
-    DivMod   Enter   Exit   Extend   
-    FloorDivision   Get   GetAttr   GetAttribute   
-    GetItem   GreaterOrEqual   GreaterThan   Id   
-    Index   Init   InPlaceAdd   InPlaceBitwiseAnd   
-    InPlaceBitwiseOr   InPlaceDivMod   InPlaceFloorDivision   InPlaceLeftShift   
-    InPlaceMatrixMultiply   InPlaceModulus   InPlaceMultiply   InPlacePow   
-    InPlaceRightShift   InPlaceSubtract   InPlaceTrueDivision   InPlaceXOr   
-    InvertValue   Is   IsDisjoint   IsNot   
-    Items   Iter   Keys   LeftShift   
-    Length   LengthHint   LessOrEqual   LessThan   
-    MatrixMultiply   Modulus   Multiply   NegateValue   
-    New   Next   NotEqual   Pop   
-    PopItem   PositiveValue   Pow   ReflectedAdd   
-    ReflectedBitwiseAnd   ReflectedBitwiseOr   ReflectedDivMod   ReflectedFloorDivision   
-    ReflectedLeftShift   ReflectedMatrixMultiply   ReflectedModulus   ReflectedMultiply   
-    ReflectedPow   ReflectedRightShift   ReflectedSubtract   ReflectedTrueDivision   
-    ReflectedXOr   Remove   Reversed   RightShift   
-    Rounding   Send   SetAttr   SetDefault   
-    SetItem   StringFormat   Subtract   Throw   
-    TrueDivision   Update   Values   XOr
+    AddItem   Append   Await   BitwiseAnd
+    BitwiseOr   BytesCast   Call   Clear
+    Close   ComplexCast   Contains   Count
+    DelAttr   Delete   DeleteItem   DescriptorGet
+    DescriptorSet   DescriptorSetName   Discard   Enter
+    Exit   Extend   Get   GetAttribute
+    GetItem   Id   InPlaceAdd   InPlaceBitwiseAnd
+    InPlaceBitwiseOr   InPlaceDivMod   InPlaceFloorDivision   InPlaceLeftShift
+    InPlaceMatrixMultiply   InPlaceModulus   InPlaceMultiply   InPlacePow
+    InPlaceRightShift   InPlaceSubtract   InPlaceTrueDivision   InPlaceXOr
+    Index   Init   InvertValue   Is
+    IsDisjoint   IsNot   Items   Iter
+    Keys   LeftShift   Length   LengthHint
+    MatrixMultiply   New   Next   Pop
+    PopItem   ReflectedAdd   ReflectedBitwiseAnd   ReflectedBitwiseOr
+    ReflectedDivMod   ReflectedFloorDivision   ReflectedLeftShift   ReflectedMatrixMultiply
+    ReflectedModulus   ReflectedMultiply   ReflectedPow   ReflectedRightShift
+    ReflectedSubtract   ReflectedTrueDivision   ReflectedXOr   Remove
+    Reversed   RightShift   Send   SetAttr
+    SetDefault   SetItem   StringFormat   Throw
+    Update   Values   XOr
 );
 
 
 #[cfg(test)]
 mod tests {
     use ::runtime::Runtime;
+    use ::runtime::traits::{DefaultDictProvider, DefaultTupleProvider, NoneProvider};
+    use super::*;
 
     fn setup() -> (Runtime, ) {
         (Runtime::new(), )
@@ -236,4 +511,68 @@ mod tests {
     fn stub() {
         info!("stub");
     }
+
+    #[test]
+    fn arithmetic() {
+        let (rt, ) = setup();
+        let (lhs, rhs) = (rt.float(7.5), rt.int(2));
+
+        assert_eq!(lhs.op_add(&rt, &rhs).unwrap(), rt.float(9.5));
+        assert_eq!(lhs.op_sub(&rt, &rhs).unwrap(), rt.float(5.5));
+        assert_eq!(lhs.op_mul(&rt, &rhs).unwrap(), rt.float(15.0));
+        assert_eq!(lhs.op_truediv(&rt, &rhs).unwrap(), rt.float(3.75));
+        assert_eq!(lhs.op_floordiv(&rt, &rhs).unwrap(), rt.float(3.0));
+        assert_eq!(lhs.op_mod(&rt, &rhs).unwrap(), rt.float(1.5));
+        assert_eq!(lhs.op_divmod(&rt, &rhs).unwrap(), rt.tuple(vec![rt.float(3.0), rt.float(1.5)]));
+        assert_eq!(lhs.op_pow(&rt, &rhs, &rt.none()).unwrap(), rt.float(56.25));
+    }
+
+    #[test]
+    fn zero_division() {
+        let (rt, ) = setup();
+        let (lhs, zero) = (rt.float(1.0), rt.float(0.0));
+
+        for result in vec![lhs.op_truediv(&rt, &zero), lhs.op_floordiv(&rt, &zero),
+                           lhs.op_mod(&rt, &zero), lhs.op_divmod(&rt, &zero)] {
+            match result {
+                Err(Error(ErrorType::ZeroDivision, _)) => {},
+                other => panic!("Expected ZeroDivisionError, got {:?}", other)
+            }
+        }
+    }
+
+    #[test]
+    fn round_and_int() {
+        let (rt, ) = setup();
+
+        assert_eq!(rt.float(2.5).op_round(&rt).unwrap(), rt.int(2));
+        assert_eq!(rt.float(-3.5).op_round(&rt).unwrap(), rt.int(-4));
+        assert_eq!(rt.float(-3.9).op_int(&rt).unwrap(), rt.int(-3));
+        assert!(rt.float(::std::f64::NAN).op_int(&rt).is_err());
+    }
+
+    #[test]
+    fn methods() {
+        let (rt, ) = setup();
+        let value = rt.float(0.75);
+
+        let is_integer = value.op_getattr(&rt, &rt.str("is_integer")).unwrap();
+        let args = (rt.default_tuple(), rt.default_tuple(), rt.default_dict());
+        assert_eq!(is_integer.op_call(&rt, &args.0, &args.1, &args.2).unwrap(), rt.bool(false));
+
+        let ratio = value.op_getattr(&rt, &rt.str("as_integer_ratio")).unwrap();
+        assert_eq!(ratio.op_call(&rt, &args.0, &args.1, &args.2).unwrap(),
+                   rt.tuple(vec![rt.int(3), rt.int(4)]));
+
+        let hex = value.op_getattr(&rt, &rt.str("hex")).unwrap();
+        assert_eq!(hex.op_call(&rt, &args.0, &args.1, &args.2).unwrap(),
+                   rt.str("0x1.8000000000000p-1"));
+    }
+
+    #[test]
+    fn repr() {
+        let (rt, ) = setup();
+        assert_eq!(rt.float(1e16).op_repr(&rt).unwrap(), rt.str("1e+16"));
+        assert_eq!(rt.float(0.1).op_str(&rt).unwrap(), rt.str("0.1"));
+    }
 }
@@ -9,7 +9,7 @@ use api::{PyAPI, RtValue, typing, method};
 use api::selfref::{self, SelfRef};
 use ::api::result::Error;
 use runtime::Runtime;
-//use ::runtime::traits::{BooleanProvider, IntegerProvider, NoneProvider};
+use ::runtime::traits::IntegerProvider;
 
 use ::api::result::{ObjectResult};
 use ::modules::builtins::Type;
@@ -92,6 +92,9 @@ impl method::GetAttr for PyFrame {
 
         match attr {
             "f_back" => Ok(self.value.0.f_back.clone()),
+            "f_code" => Ok(self.value.0.f_code.clone()),
+            "f_lasti" => Ok(rt.int(self.value.0.f_lasti.get())),
+            "f_lineno" => Ok(rt.int(self.value.0.f_lineno.get())),
             missing => return Err(Error::attribute(
                 &strings_error_no_attribute!("object", missing)))
         }
@@ -132,7 +135,10 @@ method_not_implemented!(PyFrame,
 
 #[cfg(test)]
 mod tests {
+    use ::api::method::GetAttr;
+    use ::modules::builtins::Type;
     use ::runtime::Runtime;
+    use ::runtime::traits::{DefaultFrameProvider, IntegerProvider, NoneProvider, StringProvider};
 
     fn setup() -> (Runtime, ) {
         (Runtime::new(), )
@@ -142,4 +148,18 @@ mod tests {
     fn stub() {
         info!("stub");
     }
+
+    #[test]
+    fn line_number() {
+        let (rt, ) = setup();
+        let frame = rt.default_frame();
+
+        if let &Type::Frame(ref pyframe) = frame.as_ref() {
+            pyframe.value.0.f_lineno.set(3);
+        }
+
+        assert_eq!(frame.op_getattr(&rt, &rt.str("f_lineno")).unwrap(), rt.int(3));
+        assert_eq!(frame.op_getattr(&rt, &rt.str("f_code")).unwrap(), rt.none());
+        assert!(frame.op_getattr(&rt, &rt.str("f_trace")).is_err());
+    }
 }
\ No newline at end of file
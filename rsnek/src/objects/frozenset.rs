@@ -57,7 +57,7 @@ impl typing::BuiltinType for PyFrozenSetType {
 
 
 #[derive(Clone)]
-pub struct FrozenSetValue(rs::Set);
+pub struct FrozenSetValue(pub rs::Set);
 pub type PyFrozenSet = RtValue<FrozenSetValue>;
 
 
@@ -7,12 +7,14 @@
 use std;
 use std::fmt;
 use std::borrow::Borrow;
-use std::ops::Deref;
+use std::cmp::Ordering;
+use std::ops::{Deref, Range};
 
-use num::{self, Zero, ToPrimitive};
+use num::{self, Signed, Zero, ToPrimitive};
+use num_bigint::Sign;
 
-use ::modules::precondition::{check_args, check_kwargs};
-use ::api::result::Error;
+use ::modules::precondition::{check_args, check_args_range, check_kwargs};
+use ::api::result::{Error, ErrorType};
 use ::api::method::*;
 use ::api::method::{Equal, Hashed, IntegerCast, StringCast, BooleanCast, NegateValue};
 use ::api::RtObject;
@@ -21,10 +23,12 @@ use ::api::{self, RtValue, method, typing};
 use ::resources::strings;
 use ::api::result::{RtResult, ObjectResult};
 use ::runtime::Runtime;
-use ::runtime::traits::{BooleanProvider, StringProvider, FunctionProvider, IntegerProvider, FloatProvider};
+use ::runtime::traits::{BooleanProvider, BytesProvider, StringProvider, FunctionProvider, IntegerProvider,
+                        FloatProvider, ComplexProvider, TupleProvider};
 use ::modules::builtins::Type;
 use ::system::primitives::{Native, HashId, SignatureBuilder};
 use ::system::primitives as rs;
+use ::objects::collection::buffer;
 use ::objects::number::{self, FloatAdapter, IntAdapter, format_int};
 
 
@@ -108,11 +112,9 @@ impl PyInteger {
         ('bit_length', <function int.bit_length>),
         ('conjugate', <function int.conjugate>),
         ('denominator', 1),
-        ('from_bytes', <function int.from_bytes>),
         ('imag', 0),
         ('numerator', 1),
-        ('real', 1),
-        ('to_bytes', <function int.to_bytes>)]
+        ('real', 1)]
     */
     pub fn get_attribute(&self, rt: &Runtime, name: &str) -> ObjectResult {
         match name {
@@ -163,6 +165,8 @@ impl PyInteger {
             "__pow__"           |
             "__rpow__"          |
             "__setattr__"       => self.try_get_ternary_method(rt, name),
+            "from_bytes"        |
+            "to_bytes"          => self.try_get_variadic_method(rt, name),
             missing => return Err(Error::attribute(
                 &strings_error_no_attribute!(TYPE_NAME, missing)))
         }
@@ -249,6 +253,93 @@ impl PyInteger {
         ternary_method_wrapper!(self, TYPE_NAME, name, rt, Type::Int, func)
     }
 
+    fn try_get_variadic_method(&self, rt: &Runtime, name: &str) -> ObjectResult {
+        let (func, nargs): (fn(&PyInteger, &Runtime, &rs::List) -> ObjectResult, Range<usize>) = match name {
+            "from_bytes"       => (PyInteger::meth_from_bytes, 2..4),
+            "to_bytes"         => (PyInteger::meth_to_bytes, 2..4),
+            missing => return Err(Error::attribute(
+                &strings_error_no_attribute!(TYPE_NAME, missing)))
+        };
+
+        variadic_method_wrapper!(self, TYPE_NAME, name, rt, Type::Int, func, nargs.clone())
+    }
+
+    /// `int.to_bytes(length, byteorder[, signed])` where `byteorder` is `'big'` or `'little'`.
+    pub fn meth_to_bytes(&self, rt: &Runtime, args: &rs::List) -> ObjectResult {
+        let length = match args[0].as_ref() {
+            &Type::Int(ref int) => match int.value.0.to_usize() {
+                Some(length) => length,
+                None => return Err(Error::value("length argument must be non-negative"))
+            },
+            other => return Err(Error::typerr(&format!(
+                "'{}' object cannot be interpreted as an integer", other.debug_name())))
+        };
+
+        let big_endian = is_big_endian(args[1].as_ref())?;
+        let signed = match args.get(2) {
+            Some(object) => object.native_bool()?,
+            None => false
+        };
+
+        let ref value = self.value.0;
+        if !signed && value.sign() == Sign::Minus {
+            return Err(Error::overflow("can't convert negative int to unsigned"))
+        }
+
+        let mut bytes = match signed {
+            true => value.to_signed_bytes_le(),
+            false => value.to_bytes_le().1,
+        };
+
+        // The minimal encodings of zero are 1 byte long but `0.to_bytes(0, ...)` is valid
+        if value.is_zero() {
+            bytes.clear();
+        }
+
+        if bytes.len() > length {
+            return Err(Error::overflow("int too big to convert"))
+        }
+
+        let fill = if signed && value.sign() == Sign::Minus {0xff} else {0x00};
+        bytes.resize(length, fill);
+
+        if big_endian {
+            bytes.reverse();
+        }
+
+        Ok(rt.bytes(bytes))
+    }
+
+    /// `int.from_bytes(bytes, byteorder[, signed])`. Python defines this as a classmethod,
+    /// it is available on instances until there are type objects to hang it off of.
+    #[allow(unused_variables)]
+    pub fn meth_from_bytes(&self, rt: &Runtime, args: &rs::List) -> ObjectResult {
+        let bytes = buffer::from_object(rt, &args[0])?;
+        let big_endian = is_big_endian(args[1].as_ref())?;
+        let signed = match args.get(2) {
+            Some(object) => object.native_bool()?,
+            None => false
+        };
+
+        let value = match (big_endian, signed) {
+            (true, true)    => rs::Integer::from_signed_bytes_be(&bytes),
+            (true, false)   => rs::Integer::from_bytes_be(Sign::Plus, &bytes),
+            (false, true)   => rs::Integer::from_signed_bytes_le(&bytes),
+            (false, false)  => rs::Integer::from_bytes_le(Sign::Plus, &bytes),
+        };
+
+        Ok(rt.int(value))
+    }
+
+}
+
+
+fn is_big_endian(byteorder: &Type) -> RtResult<bool> {
+    match byteorder {
+        &Type::Str(ref string) if string.value.0 == "big" => Ok(true),
+        &Type::Str(ref string) if string.value.0 == "little" => Ok(false),
+        _ => Err(Error::value("byteorder must be either 'little' or 'big'"))
+    }
 }
 
 
@@ -400,6 +491,57 @@ impl method::NegateValue for PyInteger {
 }
 
 
+/// `float(self)`
+impl method::FloatCast for PyInteger {
+    fn op_float(&self, rt: &Runtime) -> ObjectResult {
+        let float = self.native_float()?;
+        Ok(rt.float(float))
+    }
+
+    fn native_float(&self) -> RtResult<rs::Float> {
+        number::int_to_float(&self.value.0)
+    }
+}
+
+
+/// `+self`
+impl method::PositiveValue for PyInteger {
+    #[allow(unused_variables)]
+    fn op_pos(&self, rt: &Runtime) -> ObjectResult {
+        self.rc.upgrade()
+    }
+
+    fn native_pos(&self) -> RtResult<rs::Number> {
+        Ok(rs::Number::Int(self.value.0.clone()))
+    }
+}
+
+
+/// `abs(self)`
+impl method::AbsValue for PyInteger {
+    fn op_abs(&self, rt: &Runtime) -> ObjectResult {
+        Ok(rt.int(self.value.0.abs()))
+    }
+
+    fn native_abs(&self) -> RtResult<rs::Number> {
+        Ok(rs::Number::Int(self.value.0.abs()))
+    }
+}
+
+
+/// `round(self)`
+impl method::Rounding for PyInteger {
+    #[allow(unused_variables)]
+    fn op_round(&self, rt: &Runtime) -> ObjectResult {
+        self.rc.upgrade()
+    }
+
+    fn native_round(&self) -> RtResult<rs::Number> {
+        Ok(rs::Number::Int(self.value.0.clone()))
+    }
+}
+
+
 /// `self + rhs`
 impl method::Add for PyInteger {
     fn op_add(&self, rt: &Runtime, rhs: &RtObject) -> ObjectResult {
@@ -469,14 +611,11 @@ impl method::Multiply for PyInteger {
     }
 }
 
-/// `self ** rhs`
+/// `self ** rhs` and `pow(self, rhs, modulus)`
 impl method::Pow for PyInteger {
-
-    // TODO: modulus not currently used
-    #[allow(unused_variables)]
     fn op_pow(&self, rt: &Runtime, exponent: &RtObject, modulus: &RtObject) -> ObjectResult {
-        match exponent.as_ref() {
-            &Type::Int(ref power) =>  {
+        match (exponent.as_ref(), modulus.as_ref()) {
+            (&Type::Int(ref power), &Type::None(_)) if !power.value.0.is_negative() => {
                 let base = self.value.0.clone();
 
                 match power.value.0.to_usize() {
@@ -486,8 +625,28 @@ impl method::Pow for PyInteger {
                     },
                 }
             },
-            other => Err(Error::typerr(
-                &strings_error_bad_operand!("**", TYPE_NAME, other.debug_name()))),
+            (&Type::Int(ref power), &Type::Int(ref modulus)) => {
+                Ok(rt.int(number::int_modpow(&self.value.0, &power.value.0, &modulus.value.0)?))
+            },
+            (&Type::Int(_), &Type::None(_)) |
+            (&Type::Float(_), &Type::None(_)) => {
+                let base = number::int_to_float(&self.value.0)?;
+                let float = match number::as_float(exponent.as_ref()) {
+                    Some(result) => result?,
+                    None => unreachable!()
+                };
+
+                match number::float_pow(base, float)? {
+                    rs::Number::Float(float) => Ok(rt.float(float)),
+                    rs::Number::Complex(complex) => Ok(rt.complex(complex)),
+                    _ => unreachable!()
+                }
+            },
+            (&Type::Int(_), _) |
+            (&Type::Float(_), _) => Err(Error::typerr(
+                "pow() 3rd argument not allowed unless all arguments are integers")),
+            (other, _) => Err(Error::typerr(
+                &strings_error_bad_operand!("** or pow()", TYPE_NAME, other.debug_name()))),
         }
     }
 }
@@ -535,15 +694,90 @@ impl method::Subtract for PyInteger {
 
 }
 
+
+/// `self / rhs`
+impl method::TrueDivision for PyInteger {
+    fn op_truediv(&self, rt: &Runtime, rhs: &RtObject) -> ObjectResult {
+        match rhs.as_ref() {
+            &Type::Int(ref rhs) => Ok(rt.float(number::int_truediv(&self.value.0, &rhs.value.0)?)),
+            &Type::Float(ref rhs) => {
+                let lhs = number::int_to_float(&self.value.0)?;
+                Ok(rt.float(number::float_truediv(lhs, rhs.value.0)?))
+            }
+            other => Err(Error::typerr(
+                &strings_error_bad_operand!("/", TYPE_NAME, other.debug_name())))
+        }
+    }
+}
+
+
+/// `self // rhs`
+impl method::FloorDivision for PyInteger {
+    fn op_floordiv(&self, rt: &Runtime, rhs: &RtObject) -> ObjectResult {
+        match rhs.as_ref() {
+            &Type::Int(ref rhs) => {
+                let (div, _) = number::int_divmod(&self.value.0, &rhs.value.0)?;
+                Ok(rt.int(div))
+            }
+            &Type::Float(ref rhs) => {
+                let lhs = number::int_to_float(&self.value.0)?;
+                Ok(rt.float(number::float_floordiv(lhs, rhs.value.0)?))
+            }
+            other => Err(Error::typerr(
+                &strings_error_bad_operand!("//", TYPE_NAME, other.debug_name())))
+        }
+    }
+}
+
+
+/// `self % rhs`
+impl method::Modulus for PyInteger {
+    fn op_mod(&self, rt: &Runtime, rhs: &RtObject) -> ObjectResult {
+        match rhs.as_ref() {
+            &Type::Int(ref rhs) => {
+                let (_, modulus) = number::int_divmod(&self.value.0, &rhs.value.0)?;
+                Ok(rt.int(modulus))
+            }
+            &Type::Float(ref rhs) => {
+                let lhs = number::int_to_float(&self.value.0)?;
+                Ok(rt.float(number::float_mod(lhs, rhs.value.0)?))
+            }
+            other => Err(Error::typerr(
+                &strings_error_bad_operand!("%", TYPE_NAME, other.debug_name())))
+        }
+    }
+}
+
+
+/// `divmod(self, rhs)`
+impl method::DivMod for PyInteger {
+    fn op_divmod(&self, rt: &Runtime, rhs: &RtObject) -> ObjectResult {
+        match rhs.as_ref() {
+            &Type::Int(ref rhs) => {
+                let (div, modulus) = number::int_divmod(&self.value.0, &rhs.value.0)?;
+                Ok(rt.tuple(vec![rt.int(div), rt.int(modulus)]))
+            }
+            &Type::Float(ref rhs) => {
+                let lhs = number::int_to_float(&self.value.0)?;
+                let (div, modulus) = number::float_divmod(lhs, rhs.value.0)?;
+                Ok(rt.tuple(vec![rt.float(div), rt.float(modulus)]))
+            }
+            other => Err(Error::typerr(
+                &strings_error_bad_operand!("divmod()", TYPE_NAME, other.debug_name())))
+        }
+    }
+}
+
+/// `self < rhs`, `self <= rhs`, `self > rhs`, `self >= rhs`
+ordering_comparison_impls!(PyInteger, TYPE_NAME, |this: &PyInteger, rhs: &Type| number::int_cmp(&this.value.0, rhs));
+
+
 method_not_implemented!(PyInteger,
     New   Init   Delete   GetAttribute   
     SetAttr   DelAttr   Id   Is   
-    IsNot   BytesCast   StringFormat   LessThan   
-    LessOrEqual   GreaterOrEqual   GreaterThan   FloatCast   
-    ComplexCast   Rounding   Index   AbsValue   
-    PositiveValue   InvertValue   BitwiseAnd   DivMod   
-    FloorDivision   Modulus   BitwiseOr   MatrixMultiply   
-    TrueDivision   XOr   ReflectedAdd   ReflectedBitwiseAnd   
+    IsNot   BytesCast   StringFormat   ComplexCast
+    Index   InvertValue   BitwiseAnd   BitwiseOr
+    MatrixMultiply   XOr   ReflectedAdd   ReflectedBitwiseAnd   
     ReflectedDivMod   ReflectedFloorDivision   ReflectedLeftShift   ReflectedModulus   
     ReflectedMultiply   ReflectedMatrixMultiply   ReflectedBitwiseOr   ReflectedPow   
     ReflectedRightShift   ReflectedSubtract   ReflectedTrueDivision   ReflectedXOr   
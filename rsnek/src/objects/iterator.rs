@@ -8,13 +8,15 @@ use std::default::Default;
 use std::ops::{Deref};
 use std::borrow::Borrow;
 
+use num::Signed;
+
 use ::api::result::{Error, ErrorType};
 use runtime::Runtime;
-use ::runtime::traits::IntegerProvider;
+use ::runtime::traits::{DefaultDictProvider, DefaultTupleProvider, IntegerProvider, TupleProvider};
 use ::api::result::{ObjectResult, RtResult};
 use api::{self, RtValue, typing};
 use api::selfref::{self, SelfRef};
-use api::method::{self, GetItem, Next};
+use api::method::{self, BooleanCast, Call, GetItem, Next};
 use api::typing::BuiltinType;
 
 use ::modules::builtins::Type;
@@ -89,19 +91,6 @@ impl fmt::Debug for PyIterator {
 }
 
 
-impl Iterator for PyIterator {
-    type Item = RtObject;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        match self.native_next() {
-            Ok(objref) => Some(objref),
-            Err(Error(ErrorType::StopIteration, _)) => None,
-            Err(_) => panic!("Iterator logic fault")
-        }
-    }
-}
-
-
 impl api::PyAPI for PyIterator {}
 
 impl method::Next for PyIterator {
@@ -132,11 +121,107 @@ impl method::Next for PyIterator {
                 }
             }
 
+            rs::Iterator::Reversed {ref source, ref idx_next} => {
+                let idx = match idx_next.get() {
+                    0 => return Err(Error::stop_iteration()),
+                    remaining => remaining - 1
+                };
+
+                idx_next.set(idx);
+                source.native_getitem(rt.int(idx).as_ref()).map_err(|_| Error::stop_iteration())
+            }
+
+            rs::Iterator::Range {ref next, ref stop, ref step} => {
+                let value = next.borrow().clone();
+                let exhausted = match step.is_positive() {
+                    true => value >= *stop,
+                    false => value <= *stop
+                };
+
+                if exhausted {
+                    return Err(Error::stop_iteration());
+                }
+
+                *next.borrow_mut() = &value + step;
+                Ok(rt.int(value))
+            }
+
+            rs::Iterator::Enumerate {ref source, ref count} => {
+                let item = source.native_next()?;
+                let idx = count.borrow().clone();
+
+                *count.borrow_mut() = &idx + rs::Integer::from(1);
+                Ok(rt.tuple(vec![rt.int(idx), item]))
+            }
+
+            rs::Iterator::Zip {ref sources} => {
+                if sources.is_empty() {
+                    return Err(Error::stop_iteration());
+                }
+
+                let items = sources.iter()
+                    .map(Next::native_next)
+                    .collect::<RtResult<rs::Tuple>>()?;
+
+                Ok(rt.tuple(items))
+            }
+
+            rs::Iterator::Map {ref func, ref sources} => {
+                let args = sources.iter()
+                    .map(Next::native_next)
+                    .collect::<RtResult<rs::Tuple>>()?;
+
+                func.op_call(rt, &rt.tuple(args), &rt.default_tuple(), &rt.default_dict())
+            }
+
+            rs::Iterator::Filter {ref func, ref source} => {
+                loop {
+                    let item = source.native_next()?;
+
+                    let truth = match func.as_ref() {
+                        &Type::None(_) => item.native_bool()?,
+                        _ => {
+                            let args = rt.tuple(vec![item.clone()]);
+                            func.op_call(rt, &args, &rt.default_tuple(), &rt.default_dict())?.native_bool()?
+                        }
+                    };
+
+                    if truth {
+                        return Ok(item);
+                    }
+                }
+            }
+
             rs::Iterator::Empty => Err(Error::stop_iteration())
         }
     }
 }
 
+
+/// Iterators are their own iterators so they can be passed anywhere an iterable
+/// is expected, e.g. `zip(map(str, x), y)`.
+impl method::Iter for PyIterator {
+    #[allow(unused_variables)]
+    fn op_iter(&self, rt: &Runtime) -> ObjectResult {
+        self.rc.upgrade()
+    }
+}
+
+
+/// Exhaust the iterator object `iterator` into a list. Errors other than
+/// `StopIteration` are propagated to the caller.
+pub fn try_collect(iterator: &RtObject) -> RtResult<rs::List> {
+    let mut items = rs::List::new();
+
+    loop {
+        match iterator.native_next() {
+            Ok(item) => items.push(item),
+            Err(Error(ErrorType::StopIteration, _)) => return Ok(items),
+            Err(err) => return Err(err)
+        }
+    }
+}
+
 method_not_implemented!(PyIterator,
     AbsValue   Add   AddItem   Append
     Await   BitwiseAnd   BitwiseOr   BooleanCast
@@ -152,7 +237,7 @@ method_not_implemented!(PyIterator,
     InPlaceModulus   InPlaceMultiply   InPlacePow   InPlaceRightShift
     InPlaceSubtract   InPlaceTrueDivision   InPlaceXOr   Index
     Init   IntegerCast   InvertValue   Is
-    IsDisjoint   IsNot   Items   Iter
+    IsDisjoint   IsNot   Items
     Keys   LeftShift   Length   LengthHint
     LessOrEqual   LessThan   MatrixMultiply   Modulus
     Multiply   NegateValue   New
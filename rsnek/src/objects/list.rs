@@ -1,4 +1,4 @@
-//! PyTuple - dynamically sized, mutable, sequence of `RtObject` elements
+//! PyList - dynamically sized, mutable, sequence of `RtObject` elements
 //!
 //! ```ignore
 //! list()
@@ -6,6 +6,7 @@
 //! ```
 //!
 use std::fmt;
+use std::mem;
 use std::ops::Deref;
 use std::borrow::Borrow;
 
@@ -16,22 +17,26 @@ use ::resources::strings;
 use ::api::result::Error;
 use ::api::result::{ObjectResult, RtResult};
 use runtime::Runtime;
-use ::runtime::traits::{BooleanProvider, IntegerProvider, StringProvider,
-             IteratorProvider, DefaultListProvider, ListProvider};
+use ::runtime::traits::{BooleanProvider, IntegerProvider, StringProvider, FunctionProvider,
+             IteratorProvider, DefaultListProvider, ListProvider, NoneProvider};
 use api::{RtValue, typing, PyAPI};
-use api::method::{self, Equal};
+use api::method::{self, Equal, Iter, StringCast, Length, BooleanCast, Contains, GetItem, Multiply};
 use api::selfref::{self, SelfRef};
 
+use ::modules::precondition::{check_args, check_kwargs};
 use ::objects::collection::sequence;
+use ::objects::iterator::try_collect;
 use ::modules::builtins::Type;
-use ::system::primitives::{List};
+use ::system::primitives::{List, SignatureBuilder};
 use ::system::primitives as rs;
+use ::system::sync::RefCell;
 use ::api::RtObject;
 
 
-pub struct PyListType {
-    pub empty: RtObject,
-}
+const TYPE_NAME: &'static str = "list";
+
+
+pub struct PyListType {}
 
 
 impl typing::BuiltinType for PyListType {
@@ -45,7 +50,7 @@ impl typing::BuiltinType for PyListType {
     }
 
     fn init_type() -> Self {
-        PyListType { empty: PyListType::inject_selfref(PyListType::alloc(rs::List::new())) }
+        PyListType {}
     }
 
     fn inject_selfref(value: Self::T) -> RtObject {
@@ -63,31 +68,109 @@ impl typing::BuiltinType for PyListType {
 
     fn alloc(value: Self::V) -> Self::T {
         PyList {
-            value: ListValue(value),
+            value: ListValue(RefCell::new(value)),
             rc: selfref::RefCount::default(),
         }
     }
 }
 
-pub struct ListValue(pub rs::List);
+pub struct ListValue(pub RefCell<rs::List>);
 pub type PyList = RtValue<ListValue>;
 
 
+impl PyList {
+    pub fn get_attribute(&self, rt: &Runtime, name: &str) -> ObjectResult {
+        match name {
+            "__bool__"          |
+            "__iter__"          |
+            "__len__"           |
+            "__str__"           |
+            "clear"             => self.try_get_unary_method(rt, name),
+            "__contains__"      |
+            "__eq__"            |
+            "__getitem__"       |
+            "__mul__"           |
+            "__ne__"            |
+            "append"            |
+            "extend"            => self.try_get_binary_method(rt, name),
+            missing => Err(Error::attribute(
+                &strings_error_no_attribute!(TYPE_NAME, missing)))
+        }
+    }
+
+    fn try_get_unary_method(&self, rt: &Runtime, name: &str) -> ObjectResult {
+        let func = match name {
+            "__bool__"      => {PyList::op_bool},
+            "__iter__"      => {PyList::op_iter},
+            "__len__"       => {PyList::op_len},
+            "__str__"       => {PyList::op_str},
+            "clear"         => {PyList::meth_clear},
+            missing => return Err(Error::attribute(
+                &strings_error_no_attribute!(TYPE_NAME, missing)))
+        };
+
+        unary_method_wrapper!(self, TYPE_NAME, name, rt, Type::List, func)
+    }
+
+    fn try_get_binary_method(&self, rt: &Runtime, name: &str) -> ObjectResult {
+        let func = match name {
+            "__contains__"  => {PyList::op_contains},
+            "__eq__"        => {PyList::op_eq},
+            "__getitem__"   => {PyList::op_getitem},
+            "__mul__"       => {PyList::op_mul},
+            "__ne__"        => {PyList::op_ne},
+            "append"        => {PyList::meth_append},
+            "extend"        => {PyList::meth_extend},
+            missing => return Err(Error::attribute(
+                &strings_error_no_attribute!(TYPE_NAME, missing)))
+        };
+
+        binary_method_wrapper!(self, TYPE_NAME, name, rt, Type::List, func)
+    }
+
+    /// Take all of the elements out of the list, used by the cycle collector to
+    /// drop the references held by an unreachable list.
+    pub fn take_elements(&self) -> rs::List {
+        mem::replace(&mut *self.value.0.borrow_mut(), rs::List::new())
+    }
+}
+
+
 impl fmt::Display for PyList {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "List({:?})", self.value.0)
+        fmt::Debug::fmt(self, f)
     }
 }
 
 impl fmt::Debug for PyList {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "List({:?})", self.value.0)
+        let id = (self as *const PyList) as rs::ObjectId;
+
+        match sequence::enter_repr(id, || write!(f, "List({:?})", self.value.0.borrow())) {
+            Some(result) => result,
+            None => write!(f, "List([...])")
+        }
     }
 }
 
 
 impl PyAPI for PyList {}
 
+
+impl method::GetAttr for PyList {
+    fn op_getattr(&self, rt: &Runtime, name: &RtObject) -> ObjectResult {
+        match name.as_ref() {
+            &Type::Str(ref pystring) => {
+                let string = pystring.value.0.clone();
+                self.get_attribute(&rt, &string)
+            }
+            other => Err(Error::typerr(&format!(
+                "getattr <{}>' requires string for attribute names, not {}",
+                TYPE_NAME, other.debug_name())))
+        }
+    }
+}
+
 impl method::StringCast for PyList {
     fn op_str(&self, rt: &Runtime) -> ObjectResult {
         let s = self.native_str()?;
@@ -95,12 +178,19 @@ impl method::StringCast for PyList {
     }
 
     fn native_str(&self) -> RtResult<rs::String> {
-        let elems = self.value.0.iter()
-            .map(RtObject::native_str)
-            .fold_results(Vec::new(), |mut acc, s| {acc.push(s); acc})
-            ?.join(", ");
-
-        Ok(format!("[{}]", elems))
+        let id = (self as *const PyList) as rs::ObjectId;
+
+        let formatted = sequence::enter_repr(id, || {
+            self.value.0.borrow().iter()
+                .map(RtObject::native_str)
+                .fold_results(Vec::new(), |mut acc, s| {acc.push(s); acc})
+                .map(|elems| format!("[{}]", elems.join(", ")))
+        });
+
+        match formatted {
+            Some(result) => result,
+            None => Ok(String::from("[...]"))
+        }
     }
 }
 
@@ -115,9 +205,9 @@ impl method::Equal for PyList {
     fn native_eq(&self, rhs: &Type) -> RtResult<rs::Boolean> {
         match rhs {
             &Type::List(ref other) => {
-                let left = &self.value.0;
-                let right = &other.value.0;
-                Ok(sequence::equals(left, right))
+                let left = self.value.0.borrow();
+                let right = other.value.0.borrow();
+                Ok(sequence::equals(&left, &right))
             }
             _ => Ok(false)
         }
@@ -144,7 +234,7 @@ impl method::BooleanCast for PyList {
     }
 
     fn native_bool(&self) -> RtResult<rs::Boolean> {
-        Ok(!self.value.0.is_empty())
+        Ok(!self.value.0.borrow().is_empty())
     }
 }
 
@@ -156,9 +246,8 @@ impl method::Multiply for PyList {
             &Type::Int(ref int) => {
                 match int.value.0.to_usize() {
                     Some(int) if int <= 0   => Ok(rt.default_list()),
-                    Some(int) if int == 1   => self.rc.upgrade(),
                     Some(int)               => {
-                        let list = sequence::multiply::<List>(&self.value.0, int);
+                        let list = sequence::multiply::<List>(&self.value.0.borrow(), int);
                         Ok(rt.list(list))
                     },
                     None                    => {
@@ -180,7 +269,7 @@ impl method::Contains for PyList {
     }
 
     fn native_contains(&self, item: &Type) -> RtResult<rs::Boolean> {
-        Ok(sequence::contains(&self.value.0, item))
+        Ok(sequence::contains(&self.value.0.borrow(), item))
     }
 }
 impl method::Iter for PyList {
@@ -198,6 +287,19 @@ impl method::Iter for PyList {
 
 }
 
+
+impl method::Reversed for PyList {
+    fn op_reversed(&self, rt: &Runtime) -> ObjectResult {
+        let iter = self.native_reversed()?;
+        Ok(rt.iter(iter))
+    }
+
+    fn native_reversed(&self) -> RtResult<rs::Iterator> {
+        let selfref = self.rc.upgrade()?;
+        rs::Iterator::reversed(&selfref)
+    }
+}
+
 impl method::Length for PyList {
     fn op_len(&self, rt: &Runtime) -> ObjectResult {
         match self.native_len() {
@@ -207,7 +309,7 @@ impl method::Length for PyList {
     }
 
     fn native_len(&self) -> RtResult<rs::Integer> {
-        Ok(rs::Integer::from(self.value.0.len()))
+        Ok(rs::Integer::from(self.value.0.borrow().len()))
     }
 }
 
@@ -220,38 +322,65 @@ impl method::GetItem for PyList {
     fn native_getitem(&self, index: &Type) -> ObjectResult {
         match index {
             &Type::Int(ref int) => {
-                sequence::get_index(&self.value.0, &int.value.0)
+                sequence::get_index(&self.value.0.borrow(), &int.value.0)
             }
             _ => Err(Error::typerr("list indices must be integers")),
         }
     }
 }
 
+impl method::Append for PyList {
+    fn meth_append(&self, rt: &Runtime, item: &RtObject) -> ObjectResult {
+        self.value.0.borrow_mut().push(item.clone());
+        Ok(rt.none())
+    }
+}
+
+
+impl method::Extend for PyList {
+    fn meth_extend(&self, rt: &Runtime, items: &RtObject) -> ObjectResult {
+        // Collect first so extending a list with itself does not hold a borrow
+        // while its elements are being read, and so that an error raised part way
+        // through leaves the list unchanged.
+        let elems = try_collect(&items.op_iter(rt)?)?;
+        self.value.0.borrow_mut().extend(elems);
+        Ok(rt.none())
+    }
+}
+
+
+impl method::Clear for PyList {
+    fn meth_clear(&self, rt: &Runtime) -> ObjectResult {
+        self.take_elements();
+        Ok(rt.none())
+    }
+}
+
+
 method_not_implemented!(PyList,
-    AbsValue   Add   AddItem   Append   
-    Await   BitwiseAnd   BitwiseOr   BytesCast   
-    Call   Clear   Close   ComplexCast   
-    Count   DelAttr   Delete   DeleteItem   
-    DescriptorGet   DescriptorSet   DescriptorSetName   Discard   
-    DivMod   Enter   Exit   Extend   
-    FloatCast   FloorDivision   Get   GetAttr   
-    GetAttribute   GreaterOrEqual   GreaterThan   Hashed   
-    Index   Init   InPlaceAdd   InPlaceBitwiseAnd   
-    InPlaceBitwiseOr   InPlaceDivMod   InPlaceFloorDivision   InPlaceLeftShift   
-    InPlaceMatrixMultiply   InPlaceModulus   InPlaceMultiply   InPlacePow   
-    InPlaceRightShift   InPlaceSubtract   InPlaceTrueDivision   InPlaceXOr   
-    IntegerCast   InvertValue   IsDisjoint   Items   
-    Keys   LeftShift   LengthHint   LessOrEqual   
-    LessThan   MatrixMultiply   Modulus   NegateValue   
-    New   Next   Pop   PopItem   
-    PositiveValue   Pow   ReflectedAdd   ReflectedBitwiseAnd   
-    ReflectedBitwiseOr   ReflectedDivMod   ReflectedFloorDivision   ReflectedLeftShift   
-    ReflectedMatrixMultiply   ReflectedModulus   ReflectedMultiply   ReflectedPow   
-    ReflectedRightShift   ReflectedSubtract   ReflectedTrueDivision   ReflectedXOr   
-    Remove   Reversed   RightShift   Rounding   
-    Send   SetAttr   SetDefault   SetItem   
-    StringFormat   StringRepresentation   Subtract   Throw   
-    TrueDivision   Update   Values   XOr
+    AbsValue   Add   AddItem   Await
+    BitwiseAnd   BitwiseOr   BytesCast   Call
+    Close   ComplexCast   Count   DelAttr
+    Delete   DeleteItem   DescriptorGet   DescriptorSet
+    DescriptorSetName   Discard   DivMod   Enter
+    Exit   FloatCast   FloorDivision   Get
+    GetAttribute   GreaterOrEqual   GreaterThan   Hashed
+    InPlaceAdd   InPlaceBitwiseAnd   InPlaceBitwiseOr   InPlaceDivMod
+    InPlaceFloorDivision   InPlaceLeftShift   InPlaceMatrixMultiply   InPlaceModulus
+    InPlaceMultiply   InPlacePow   InPlaceRightShift   InPlaceSubtract
+    InPlaceTrueDivision   InPlaceXOr   Index   Init
+    IntegerCast   InvertValue   IsDisjoint   Items
+    Keys   LeftShift   LengthHint   LessOrEqual
+    LessThan   MatrixMultiply   Modulus   NegateValue
+    New   Next   Pop   PopItem
+    PositiveValue   Pow   ReflectedAdd   ReflectedBitwiseAnd
+    ReflectedBitwiseOr   ReflectedDivMod   ReflectedFloorDivision   ReflectedLeftShift
+    ReflectedMatrixMultiply   ReflectedModulus   ReflectedMultiply   ReflectedPow
+    ReflectedRightShift   ReflectedSubtract   ReflectedTrueDivision   ReflectedXOr
+    Remove   RightShift   Rounding   Send
+    SetAttr   SetDefault   SetItem   StringFormat
+    StringRepresentation   Subtract   Throw   TrueDivision
+    Update   Values   XOr
 );
 
 #[cfg(test)]
@@ -265,7 +394,8 @@ mod tests {
         TupleProvider,
         FloatProvider
     };
-    use ::api::method::{BooleanCast, GetItem, Multiply, NotEqual, Length, StringCast, Iter};
+    use ::api::method::{BooleanCast, Extend, GetItem, Multiply, NotEqual, Length, StringCast, Iter};
+    use ::api::result::ErrorType;
     use super::*;
 
     fn setup() -> (Runtime,) {
@@ -424,7 +554,7 @@ mod tests {
         let list = rt.default_list();
 
         let iter = list.op_iter(&rt).unwrap();
-        assert_eq!(iter.count(), 0);
+        assert_eq!(try_collect(&iter).unwrap().len(), 0);
 
         // N Elements
         let list = rt.list(vec![
@@ -435,7 +565,35 @@ mod tests {
         ]);
 
         let iter = list.op_iter(&rt).unwrap();
-        assert_eq!(iter.count(), 4);
+        assert_eq!(try_collect(&iter).unwrap().len(), 4);
+    }
+
+    #[test]
+    fn extend() {
+        let (rt,) = setup();
+        let list = rt.list(vec![rt.int(1)]);
+
+        list.meth_extend(&rt, &rt.tuple(vec![rt.int(2), rt.int(3)])).unwrap();
+        assert_eq!(list, rt.list(vec![rt.int(1), rt.int(2), rt.int(3)]));
+
+        // Extending with itself reads the elements before adding any
+        list.meth_extend(&rt, &list.clone()).unwrap();
+        assert_eq!(list.op_len(&rt).unwrap(), rt.int(6));
+    }
+
+    #[test]
+    fn extend_error() {
+        let (rt,) = setup();
+        let list = rt.list(vec![rt.int(1)]);
+        let strs = rt.tuple(vec![rt.str("2"), rt.str("x")]);
+        let source = strs.op_iter(&rt).unwrap();
+        let items = rt.iter(rs::Iterator::Map {func: rt.get_builtin("int"), sources: vec![source]});
+
+        match list.meth_extend(&rt, &items) {
+            Err(Error(ErrorType::Value, _)) => {},
+            other => panic!("Expected ValueError, got {:?}", other)
+        }
+        assert_eq!(list, rt.list(vec![rt.int(1)]));
     }
 
     #[test]
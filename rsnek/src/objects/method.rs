@@ -328,7 +328,7 @@ impl method::Hashed for PyFunction {
     fn native_hash(&self) -> RtResult<rs::HashId> {
         let mut s = DefaultHasher::new();
         self.native_id().hash(&mut s);
-        Ok(s.finish())
+        Ok(s.finish() as rs::HashId)
     }
 }
 
@@ -360,7 +360,7 @@ impl method::Equal for PyFunction {
 impl method::Call for PyFunction {
     fn op_call(&self, rt: &Runtime, pos_args: &RtObject, star_args: &RtObject, kwargs: &RtObject) -> ObjectResult {
         match self.value.0.callable {
-            FuncType::MethodWrapper(_, ref func) => func(&rt, &pos_args, &star_args, &kwargs),
+            FuncType::MethodWrapper(ref object, ref func) => func(&rt, object, &pos_args, &star_args, &kwargs),
             FuncType::Wrapper(ref func) => func(&rt, &pos_args, &star_args, &kwargs),
             FuncType::Code(_) => {
                 Err(Error::typerr("'code' object is not callable"))
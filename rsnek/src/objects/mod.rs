@@ -1,5 +1,6 @@
 //! Definitions of builtin objects available to all python implementations
 pub mod boolean;
+pub mod bytearray;
 pub mod bytes;
 pub mod code;
 pub mod complex;
@@ -15,7 +16,9 @@ pub mod module;
 pub mod none;
 pub mod object;
 pub mod pytype;
+pub mod range;
 pub mod set;
+pub mod slice;
 pub mod string;
 pub mod tuple;
 
@@ -1,18 +1,103 @@
 //! Native number coercions and comparisons. I think rust already does this with the `Wrapped`
 //! traits...
 use std;
-use std::hash::{Hash, Hasher};
-use std::collections::hash_map::DefaultHasher;
+use std::cmp::Ordering;
 
-use num::{ToPrimitive};
+use std::f64::consts::PI;
 
+use num::{self, Integer, One, Signed, ToPrimitive, Zero};
+
+use ::api::result::{Error, RtResult};
+use ::modules::builtins::Type;
 use ::system::primitives::{HashId};
 use ::system::primitives as rs;
 
 
+/// Format a float the way CPython's `repr` does: the shortest string that round trips,
+/// in positional notation when the decimal exponent is in `-4..16` and scientific
+/// notation with a signed, at least two digit exponent otherwise.
 pub fn format_float(float: &rs::Float) -> rs::String {
-    format!("{:?}", *float)
+    let float = *float;
+
+    if float.is_nan() {
+        return "nan".to_string();
+    }
+
+    if float.is_infinite() {
+        return if float > 0.0 {"inf"} else {"-inf"}.to_string();
+    }
+
+    let sign = if float.is_sign_negative() {"-"} else {""};
+    if float == 0.0 {
+        return format!("{}0.0", sign);
+    }
 
+    // `{:e}` gives the shortest round trip digits as `d.ddddde[-]x`
+    let scientific = format!("{:e}", float.abs());
+    let (mantissa, exponent) = scientific.split_at(scientific.find('e').unwrap());
+    let exponent: i32 = exponent[1..].parse().unwrap();
+    let digits: String = mantissa.chars().filter(|ch| *ch != '.').collect();
+
+    let body = if exponent < -4 || exponent >= 16 {
+        let mut mantissa = digits[..1].to_string();
+        if digits.len() > 1 {
+            mantissa.push('.');
+            mantissa.push_str(&digits[1..]);
+        }
+        format!("{}e{}{:02}", mantissa, if exponent < 0 {'-'} else {'+'}, exponent.abs())
+    } else if exponent < 0 {
+        format!("0.{}{}", "0".repeat((-exponent - 1) as usize), digits)
+    } else {
+        let point = (exponent + 1) as usize;
+        if digits.len() > point {
+            format!("{}.{}", &digits[..point], &digits[point..])
+        } else {
+            format!("{}{}.0", digits, "0".repeat(point - digits.len()))
+        }
+    };
+
+    format!("{}{}", sign, body)
+}
+
+
+/// Parse the argument of `float(str)` which allows surrounding whitespace, `inf`,
+/// `infinity`, and `nan` in any case, and underscores between digits.
+pub fn parse_float(string: &str) -> RtResult<rs::Float> {
+    let error = || Error::value(&format!("could not convert string to float: '{}'", string));
+
+    let trimmed = string.trim();
+    let (negative, unsigned) = match trimmed.chars().next() {
+        Some('-') => (true, &trimmed[1..]),
+        Some('+') => (false, &trimmed[1..]),
+        _ => (false, trimmed)
+    };
+
+    let value = match unsigned.to_lowercase().as_str() {
+        "inf" | "infinity" => std::f64::INFINITY,
+        "nan" => std::f64::NAN,
+        _ => {
+            let chars: Vec<char> = unsigned.chars().collect();
+            let mut literal = String::with_capacity(chars.len());
+
+            for (idx, ch) in chars.iter().enumerate() {
+                match *ch {
+                    '_' => {
+                        let between_digits = idx > 0 && idx + 1 < chars.len() &&
+                            chars[idx - 1].is_digit(10) && chars[idx + 1].is_digit(10);
+                        if !between_digits {
+                            return Err(error());
+                        }
+                    },
+                    '0'...'9' | '.' | 'e' | 'E' | '+' | '-' => literal.push(*ch),
+                    _ => return Err(error())
+                }
+            }
+
+            literal.parse::<rs::Float>().map_err(|_| error())?
+        }
+    };
+
+    Ok(if negative {-value} else {value})
 }
 
 pub fn format_int(int: &rs::Integer) -> rs::String {
@@ -20,12 +105,425 @@ pub fn format_int(int: &rs::Integer) -> rs::String {
 }
 
 
+// Numeric hashing follows CPython's scheme so that numbers which compare equal hash equal
+// regardless of type, e.g. `hash(1) == hash(1.0) == hash(True)`. The hash of a rational
+// number `x` is `x mod P` where `P` is the Mersenne prime `2**61 - 1`.
+// See: https://docs.python.org/3/library/stdtypes.html#hashing-of-numeric-types
+
+/// Number of bits in the modulus `P`
+pub const HASH_BITS: u32 = 61;
+
+/// The Mersenne prime `2**61 - 1`
+pub const HASH_MODULUS: u64 = (1 << HASH_BITS) - 1;
+
+/// `hash(float('inf'))`, negated for `-inf`
+pub const HASH_INF: HashId = 314159;
+
+/// `hash(float('nan'))`
+pub const HASH_NAN: HashId = 0;
+
+/// Multiplier for the imaginary part of a complex number
+pub const HASH_IMAG: u64 = 1000003;
+
+
+/// -1 is reserved as an error value by CPython so it is never a valid hash
+#[inline]
+fn fix_hash(hash: HashId) -> HashId {
+    if hash == -1 { -2 } else { hash }
+}
+
+
 pub fn hash_int(int: &rs::Integer) -> HashId {
-    let mut s = DefaultHasher::new();
-    int.hash(&mut s);
-    s.finish()
+    let modulus = rs::Integer::from(HASH_MODULUS);
+    // The remainder is always less than 2**61 so the conversion cannot fail
+    let hash = (int.abs() % modulus).to_i64().unwrap();
+
+    if int.is_negative() {
+        fix_hash(-hash)
+    } else {
+        hash
+    }
+}
+
+
+/// Split a finite float into `(negative, mantissa, exponent)` where the magnitude of the
+/// float is exactly `mantissa * 2**exponent` and the mantissa is at most 53 bits.
+fn decompose_float(float: rs::Float) -> (bool, u64, i64) {
+    let bits = float.to_bits();
+    let negative = (bits >> 63) != 0;
+    let biased = ((bits >> 52) & 0x7ff) as i64;
+    let fraction = bits & ((1 << 52) - 1);
+
+    match biased {
+        0 => (negative, fraction, -1074),
+        _ => (negative, fraction | (1 << 52), biased - 1075),
+    }
+}
+
+
+pub fn hash_float(float: rs::Float) -> HashId {
+    if float.is_nan() {
+        return HASH_NAN;
+    }
+
+    if float.is_infinite() {
+        return if float > 0.0 { HASH_INF } else { -HASH_INF };
+    }
+
+    let (negative, mantissa, exponent) = decompose_float(float);
+
+    // Since 2**61 = 1 (mod P), multiplying by 2**exponent is a rotation of a 61 bit value
+    // and a negative exponent is the rotation by its inverse.
+    let bits = HASH_BITS as i64;
+    let exponent = (if exponent >= 0 {
+        exponent % bits
+    } else {
+        bits - 1 - ((-1 - exponent) % bits)
+    }) as u32;
+
+    let hash = (((mantissa << exponent) & HASH_MODULUS) | (mantissa >> (HASH_BITS - exponent))) as HashId;
+
+    if negative {
+        fix_hash(-hash)
+    } else {
+        hash
+    }
+}
+
+
+pub fn hash_complex(complex: &rs::Complex) -> HashId {
+    let real = hash_float(complex.re) as u64;
+    let imag = hash_float(complex.im) as u64;
+
+    fix_hash(real.wrapping_add(HASH_IMAG.wrapping_mul(imag)) as HashId)
+}
+
+/// Order an integer against another number, `Ok(None)` when the other number is `nan`.
+pub fn int_cmp(lhs: &rs::Integer, rhs: &Type) -> RtResult<Option<Ordering>> {
+    match rhs {
+        &Type::Int(ref int) => Ok(Some(lhs.cmp(&int.value.0))),
+        &Type::Bool(ref boolean) => Ok(Some(lhs.cmp(&boolean.value.0))),
+        &Type::Float(ref float) => Ok(lhs.to_f64().and_then(|value| value.partial_cmp(&float.value.0))),
+        other => Err(Error::typerr(other.debug_name()))
+    }
+}
+
+
+/// Order a float against another number, `Ok(None)` when either is `nan`.
+pub fn float_cmp(lhs: rs::Float, rhs: &Type) -> RtResult<Option<Ordering>> {
+    match rhs {
+        &Type::Float(ref float) => Ok(lhs.partial_cmp(&float.value.0)),
+        &Type::Int(ref int) => Ok(int.value.0.to_f64().and_then(|value| lhs.partial_cmp(&value))),
+        &Type::Bool(ref boolean) => Ok(boolean.value.0.to_f64().and_then(|value| lhs.partial_cmp(&value))),
+        other => Err(Error::typerr(other.debug_name()))
+    }
+}
+
+/// Widen an int, bool, or float operand to a float, `None` if `value` is not a real number.
+pub fn as_float(value: &Type) -> Option<RtResult<rs::Float>> {
+    match value {
+        &Type::Float(ref float) => Some(Ok(float.value.0)),
+        &Type::Int(ref int) => Some(int_to_float(&int.value.0)),
+        &Type::Bool(ref boolean) => Some(int_to_float(&boolean.value.0)),
+        _ => None
+    }
+}
+
+
+pub fn int_to_float(int: &rs::Integer) -> RtResult<rs::Float> {
+    match int.to_f64() {
+        Some(float) if float.is_finite() => Ok(float),
+        _ => Err(Error::overflow("int too large to convert to float"))
+    }
+}
+
+
+/// Truncate a float towards zero
+pub fn float_to_int(float: rs::Float) -> RtResult<rs::Integer> {
+    if float.is_nan() {
+        return Err(Error::value("cannot convert float NaN to integer"));
+    }
+
+    if float.is_infinite() {
+        return Err(Error::overflow("cannot convert float infinity to integer"));
+    }
+
+    let (negative, mantissa, exponent) = decompose_float(float.trunc());
+    let magnitude = match exponent {
+        exponent if exponent >= 0 => rs::Integer::from(mantissa) << exponent as usize,
+        exponent if exponent > -64 => rs::Integer::from(mantissa >> -exponent),
+        _ => rs::Integer::zero(),
+    };
+
+    Ok(if negative {-magnitude} else {magnitude})
+}
+
+
+/// `float.as_integer_ratio()` as the lowest terms `(numerator, denominator)`
+pub fn float_as_integer_ratio(float: rs::Float) -> RtResult<(rs::Integer, rs::Integer)> {
+    if float.is_nan() {
+        return Err(Error::value("cannot convert NaN to integer ratio"));
+    }
+
+    if float.is_infinite() {
+        return Err(Error::overflow("cannot convert Infinity to integer ratio"));
+    }
+
+    let (negative, mut mantissa, mut exponent) = decompose_float(float);
+    if mantissa == 0 {
+        return Ok((rs::Integer::zero(), rs::Integer::one()));
+    }
+
+    let zeros = mantissa.trailing_zeros();
+    mantissa >>= zeros;
+    exponent += zeros as i64;
+
+    let numerator = rs::Integer::from(mantissa);
+    let (numerator, denominator) = match exponent {
+        exponent if exponent >= 0 => (numerator << exponent as usize, rs::Integer::one()),
+        exponent => (numerator, rs::Integer::one() << -exponent as usize),
+    };
+
+    Ok((if negative {-numerator} else {numerator}, denominator))
+}
+
+
+/// `float.hex()` e.g. `0x1.8000000000000p+0` for `1.5`
+pub fn float_hex(float: rs::Float) -> rs::String {
+    if float.is_nan() || float.is_infinite() {
+        return format_float(&float);
+    }
+
+    let sign = if float.is_sign_negative() {"-"} else {""};
+    if float == 0.0 {
+        return format!("{}0x0.0p+0", sign);
+    }
+
+    let bits = float.to_bits();
+    let biased = ((bits >> 52) & 0x7ff) as i64;
+    let fraction = bits & ((1 << 52) - 1);
+
+    let (leading, exponent) = match biased {
+        0 => (0, -1022),
+        _ => (1, biased - 1023),
+    };
+
+    format!("{}0x{}.{:013x}p{}{}", sign, leading, fraction,
+            if exponent < 0 {'-'} else {'+'}, exponent.abs())
+}
+
+
+/// `lhs / rhs`
+pub fn float_truediv(lhs: rs::Float, rhs: rs::Float) -> RtResult<rs::Float> {
+    if rhs == 0.0 {
+        return Err(Error::zero_division("float division by zero"));
+    }
+
+    Ok(lhs / rhs)
+}
+
+
+/// `lhs // rhs`
+pub fn float_floordiv(lhs: rs::Float, rhs: rs::Float) -> RtResult<rs::Float> {
+    if rhs == 0.0 {
+        return Err(Error::zero_division("float floor division by zero"));
+    }
+
+    Ok(divmod_float(lhs, rhs).0)
+}
+
+
+/// `lhs % rhs` where the result takes the sign of `rhs`
+pub fn float_mod(lhs: rs::Float, rhs: rs::Float) -> RtResult<rs::Float> {
+    if rhs == 0.0 {
+        return Err(Error::zero_division("float modulo by zero"));
+    }
+
+    Ok(divmod_float(lhs, rhs).1)
+}
+
+
+/// `divmod(lhs, rhs)`
+pub fn float_divmod(lhs: rs::Float, rhs: rs::Float) -> RtResult<(rs::Float, rs::Float)> {
+    if rhs == 0.0 {
+        return Err(Error::zero_division("float divmod()"));
+    }
+
+    Ok(divmod_float(lhs, rhs))
+}
+
+
+/// `magnitude` with the sign of `sign`, including the sign of zeros
+fn copysign(magnitude: rs::Float, sign: rs::Float) -> rs::Float {
+    if sign.is_sign_negative() {-magnitude.abs()} else {magnitude.abs()}
+}
+
+
+/// Floor division and modulus of floats using the same steps as CPython's `float_divmod`
+/// so the signs of zero results and the rounding of the quotient are consistent.
+fn divmod_float(lhs: rs::Float, rhs: rs::Float) -> (rs::Float, rs::Float) {
+    let mut modulus = lhs % rhs;
+    let mut div = (lhs - modulus) / rhs;
+
+    if modulus != 0.0 {
+        if (rhs < 0.0) != (modulus < 0.0) {
+            modulus += rhs;
+            div -= 1.0;
+        }
+    } else {
+        modulus = copysign(0.0, rhs);
+    }
+
+    let floordiv = if div != 0.0 {
+        let floor = div.floor();
+        if div - floor > 0.5 { floor + 1.0 } else { floor }
+    } else {
+        copysign(0.0, lhs / rhs)
+    };
+
+    (floordiv, modulus)
+}
+
+
+/// `lhs ** rhs` which is a complex number when a negative base is raised to a
+/// fractional power.
+pub fn float_pow(lhs: rs::Float, rhs: rs::Float) -> RtResult<rs::Number> {
+    if lhs == 0.0 && rhs < 0.0 {
+        return Err(Error::zero_division("0.0 cannot be raised to a negative power"));
+    }
+
+    if lhs < 0.0 && lhs.is_finite() && rhs.is_finite() && rhs.fract() != 0.0 {
+        let magnitude = (-lhs).powf(rhs);
+        let angle = PI * rhs;
+        return Ok(rs::Number::Complex(rs::Complex::new(magnitude * angle.cos(), magnitude * angle.sin())));
+    }
+
+    let result = lhs.powf(rhs);
+    if result.is_infinite() && lhs.is_finite() && rhs.is_finite() {
+        return Err(Error::overflow("(34, 'Numerical result out of range')"));
+    }
+
+    Ok(rs::Number::Float(result))
+}
+
+
+/// Round a float to `ndigits` decimal places with ties going to the even digit. The
+/// rounding is done on the exact value of the float using integer arithmetic and the
+/// result is converted back through a decimal string so it is correctly rounded.
+pub fn float_round(float: rs::Float, ndigits: i64) -> RtResult<rs::Float> {
+    if !float.is_finite() || float == 0.0 || ndigits > 323 {
+        return Ok(float);
+    }
+
+    if ndigits < -308 {
+        return Ok(copysign(0.0, float));
+    }
+
+    let (negative, mantissa, exponent) = decompose_float(float);
+    let mut numerator = rs::Integer::from(mantissa);
+    let mut denominator = rs::Integer::one();
+
+    if exponent >= 0 {
+        numerator = numerator << exponent as usize;
+    } else {
+        denominator = denominator << -exponent as usize;
+    }
+
+    let scale = num::pow::pow(rs::Integer::from(10), ndigits.abs() as usize);
+    if ndigits >= 0 {
+        numerator = numerator * scale;
+    } else {
+        denominator = denominator * scale;
+    }
+
+    let (quotient, remainder) = numerator.div_rem(&denominator);
+    let twice = remainder * rs::Integer::from(2);
+    let quotient = if twice > denominator || (twice == denominator && quotient.is_odd()) {
+        quotient + rs::Integer::one()
+    } else {
+        quotient
+    };
+
+    let decimal = format!("{}{}e{}", if negative {"-"} else {""}, quotient, -ndigits);
+    match decimal.parse::<rs::Float>() {
+        Ok(rounded) if rounded.is_finite() => Ok(rounded),
+        _ => Err(Error::overflow("rounded value too large to represent"))
+    }
+}
+
+
+
+/// Round an int to `ndigits` decimal places with ties going to the even digit. Only a
+/// negative `ndigits` can change the value.
+pub fn int_round(int: &rs::Integer, ndigits: i64) -> rs::Integer {
+    if ndigits >= 0 {
+        return int.clone();
+    }
+
+    let scale = num::pow::pow(rs::Integer::from(10), (-ndigits) as usize);
+    let (quotient, remainder) = int.div_mod_floor(&scale);
+    let twice = remainder * rs::Integer::from(2);
+    let quotient = if twice > scale || (twice == scale && quotient.is_odd()) {
+        quotient + rs::Integer::one()
+    } else {
+        quotient
+    };
+
+    quotient * scale
+}
+
+
+/// `lhs / rhs` for ints, which is always a float
+pub fn int_truediv(lhs: &rs::Integer, rhs: &rs::Integer) -> RtResult<rs::Float> {
+    if rhs.is_zero() {
+        return Err(Error::zero_division("division by zero"));
+    }
+
+    match (lhs.to_f64(), rhs.to_f64()) {
+        (Some(lhs), Some(rhs)) if lhs.is_finite() && rhs.is_finite() => Ok(lhs / rhs),
+        _ => Err(Error::overflow("integer division result too large for a float"))
+    }
+}
+
+
+/// `divmod(lhs, rhs)` for ints where the quotient is floored and the modulus takes the
+/// sign of `rhs`.
+pub fn int_divmod(lhs: &rs::Integer, rhs: &rs::Integer) -> RtResult<(rs::Integer, rs::Integer)> {
+    if rhs.is_zero() {
+        return Err(Error::zero_division("integer division or modulo by zero"));
+    }
+
+    Ok(lhs.div_mod_floor(rhs))
 }
 
+
+/// `pow(base, exponent, modulus)` for ints using square and multiply
+pub fn int_modpow(base: &rs::Integer, exponent: &rs::Integer, modulus: &rs::Integer) -> RtResult<rs::Integer> {
+    if modulus.is_zero() {
+        return Err(Error::value("pow() 3rd argument cannot be 0"));
+    }
+
+    if exponent.is_negative() {
+        return Err(Error::value("pow() 2nd argument cannot be negative when 3rd argument specified"));
+    }
+
+    let mut result = rs::Integer::one().mod_floor(modulus);
+    let mut base = base.mod_floor(modulus);
+    let mut exponent = exponent.clone();
+    let two = rs::Integer::from(2);
+
+    while !exponent.is_zero() {
+        if exponent.is_odd() {
+            result = (result * &base).mod_floor(modulus);
+        }
+        base = (&base * &base).mod_floor(modulus);
+        exponent = exponent / &two;
+    }
+
+    Ok(result)
+}
+
+
 // To make int == float not such a pain in the ass
 pub struct IntAdapter<'a>(pub &'a rs::Integer);
 pub struct FloatAdapter<'a>(pub &'a rs::Float);
@@ -56,3 +554,157 @@ impl<'a, 'b> std::cmp::PartialEq<FloatAdapter<'b>> for IntAdapter<'a> {
 //        match self.0
 //    }
 //}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num::Num;
+
+    /// Expected values are from CPython on a 64 bit platform
+    #[test]
+    fn hash_int_matches_cpython() {
+        assert_eq!(hash_int(&rs::Integer::from(1)), 1);
+        assert_eq!(hash_int(&rs::Integer::from(-1)), -2);
+        assert_eq!(hash_int(&rs::Integer::from(HASH_MODULUS)), 0);
+
+        let big = rs::Integer::from_str_radix("2305843009213693952", 10).unwrap();
+        assert_eq!(hash_int(&big), 1);
+        assert_eq!(hash_int(&-(big + rs::Integer::from(5))), -6);
+    }
+
+    #[test]
+    fn hash_float_matches_cpython() {
+        assert_eq!(hash_float(1.0), 1);
+        assert_eq!(hash_float(-1.0), -2);
+        assert_eq!(hash_float(1.5), 1152921504606846977);
+        assert_eq!(hash_float(0.1), 230584300921369408);
+        assert_eq!(hash_float(1e300), 1224995262755759164);
+        assert_eq!(hash_float(std::f64::INFINITY), HASH_INF);
+        assert_eq!(hash_float(std::f64::NEG_INFINITY), -HASH_INF);
+        assert_eq!(hash_float(std::f64::NAN), HASH_NAN);
+    }
+
+    #[test]
+    fn hash_int_eq_hash_float() {
+        for value in &[0i64, 1, -7, 42, 1 << 40] {
+            assert_eq!(hash_int(&rs::Integer::from(*value)), hash_float(*value as f64));
+        }
+    }
+
+    #[test]
+    fn format_float_matches_cpython() {
+        assert_eq!(format_float(&1.0), "1.0");
+        assert_eq!(format_float(&0.1), "0.1");
+        assert_eq!(format_float(&-0.0), "-0.0");
+        assert_eq!(format_float(&123456.789), "123456.789");
+        assert_eq!(format_float(&1e16), "1e+16");
+        assert_eq!(format_float(&1e15), "1000000000000000.0");
+        assert_eq!(format_float(&0.0001), "0.0001");
+        assert_eq!(format_float(&0.00001), "1e-05");
+        assert_eq!(format_float(&1.5e-300), "1.5e-300");
+        assert_eq!(format_float(&(0.1 + 0.2)), "0.30000000000000004");
+        assert_eq!(format_float(&std::f64::INFINITY), "inf");
+        assert_eq!(format_float(&std::f64::NAN), "nan");
+    }
+
+    #[test]
+    fn parse_float_special_values() {
+        assert_eq!(parse_float("  1.5\n").unwrap(), 1.5);
+        assert_eq!(parse_float("-Infinity").unwrap(), std::f64::NEG_INFINITY);
+        assert_eq!(parse_float("1_000.5").unwrap(), 1000.5);
+        assert!(parse_float("nan").unwrap().is_nan());
+        assert!(parse_float("1__0").is_err());
+        assert!(parse_float("_1").is_err());
+        assert!(parse_float("snek").is_err());
+        assert!(parse_float("").is_err());
+    }
+
+    #[test]
+    fn float_divmod_matches_cpython() {
+        assert_eq!(float_divmod(7.0, 2.0).unwrap(), (3.0, 1.0));
+        assert_eq!(float_divmod(-7.0, 2.0).unwrap(), (-4.0, 1.0));
+        assert_eq!(float_divmod(7.0, -2.0).unwrap(), (-4.0, -1.0));
+        assert_eq!(float_mod(-0.0, 5.0).unwrap().is_sign_negative(), false);
+        assert!(float_divmod(1.0, 0.0).is_err());
+        assert!(float_truediv(1.0, 0.0).is_err());
+    }
+
+    #[test]
+    fn float_pow_special_cases() {
+        match float_pow(-8.0, 1.0 / 3.0).unwrap() {
+            rs::Number::Complex(complex) => {
+                assert_eq!(complex.re, 1.0000000000000002);
+                assert_eq!(complex.im, 1.7320508075688772);
+            },
+            _ => panic!("negative base with fractional power must be complex")
+        }
+
+        assert!(float_pow(0.0, -1.0).is_err());
+        assert!(float_pow(10.0, 400.0).is_err());
+    }
+
+    #[test]
+    fn float_round_half_even() {
+        assert_eq!(float_round(0.5, 0).unwrap(), 0.0);
+        assert_eq!(float_round(1.5, 0).unwrap(), 2.0);
+        assert_eq!(float_round(2.675, 2).unwrap(), 2.67);
+        assert_eq!(float_round(0.125, 2).unwrap(), 0.12);
+        assert_eq!(float_round(1234.5, -2).unwrap(), 1200.0);
+        assert_eq!(float_round(-1.25, 1).unwrap(), -1.2);
+    }
+
+    #[test]
+    fn float_conversions() {
+        assert_eq!(float_to_int(-2.9).unwrap(), rs::Integer::from(-2));
+        assert_eq!(float_to_int(1e20).unwrap(),
+                   rs::Integer::from_str_radix("100000000000000000000", 10).unwrap());
+        assert!(float_to_int(std::f64::NAN).is_err());
+
+        assert_eq!(float_as_integer_ratio(0.75).unwrap(), (rs::Integer::from(3), rs::Integer::from(4)));
+        assert_eq!(float_as_integer_ratio(-2.0).unwrap(), (rs::Integer::from(-2), rs::Integer::from(1)));
+
+        assert_eq!(float_hex(1.5), "0x1.8000000000000p+0");
+        assert_eq!(float_hex(-0.1), "-0x1.999999999999ap-4");
+        assert_eq!(float_hex(5e-324), "0x0.0000000000001p-1022");
+    }
+
+    #[test]
+    fn hash_complex_matches_cpython() {
+        assert_eq!(hash_complex(&rs::Complex::new(1.0, 2.0)), 2000007);
+        assert_eq!(hash_complex(&rs::Complex::new(3.0, 0.0)), hash_float(3.0));
+    }
+
+    #[test]
+    fn int_division_matches_cpython() {
+        let int = |value: i64| rs::Integer::from(value);
+
+        assert_eq!(int_divmod(&int(7), &int(-2)).unwrap(), (int(-4), int(-1)));
+        assert_eq!(int_divmod(&int(-7), &int(2)).unwrap(), (int(-4), int(1)));
+        assert_eq!(int_truediv(&int(1), &int(4)).unwrap(), 0.25);
+        assert!(int_divmod(&int(1), &int(0)).is_err());
+        assert!(int_truediv(&int(1), &int(0)).is_err());
+    }
+
+    #[test]
+    fn int_round_half_even() {
+        let int = |value: i64| rs::Integer::from(value);
+
+        assert_eq!(int_round(&int(1234), 2), int(1234));
+        assert_eq!(int_round(&int(1250), -2), int(1200));
+        assert_eq!(int_round(&int(1350), -2), int(1400));
+        assert_eq!(int_round(&int(-1251), -2), int(-1300));
+        assert_eq!(int_round(&int(15), -5), int(0));
+    }
+
+    #[test]
+    fn int_modpow_matches_cpython() {
+        let int = |value: i64| rs::Integer::from(value);
+
+        assert_eq!(int_modpow(&int(3), &int(200), &int(7)).unwrap(), int(2));
+        assert_eq!(int_modpow(&int(-2), &int(3), &int(5)).unwrap(), int(2));
+        assert_eq!(int_modpow(&int(2), &int(3), &int(-5)).unwrap(), int(-2));
+        assert!(int_modpow(&int(2), &int(-1), &int(5)).is_err());
+        assert!(int_modpow(&int(2), &int(1), &int(0)).is_err());
+    }
+}
@@ -99,6 +99,11 @@ impl PyObject {
         self.value.0.dict.native_meth_keys()
     }
 
+    /// The `__dict__` holding the attributes of the object
+    pub fn dict(&self) -> RtObject {
+        self.value.0.dict.clone()
+    }
+
 }
 
 impl fmt::Display for PyObject {
@@ -145,8 +150,8 @@ impl method::GetAttr for PyObject {
                             }
                             _ => unreachable!(),
                         }
-                        info!("NOPE!");
-                        Err(err)
+                        debug!("{:?}", err);
+                        Err(Error::attribute(&strings_error_no_attribute!("object", string.value.0)))
                     }
                 }
             }
@@ -176,6 +181,34 @@ impl method::SetAttr for PyObject {
     }
 }
 
+impl method::DelAttr for PyObject {
+    fn op_delattr(&self, rt: &Runtime, name: &RtObject) -> ObjectResult {
+        self.native_delattr(name.as_ref())?;
+        Ok(rt.none())
+    }
+
+    fn native_delattr(&self, name: &Type) -> RtResult<rs::None> {
+        let string = match name {
+            &Type::Str(ref string) => string,
+            _ => return Err(Error::typerr("delattr(): attribute name must be string")),
+        };
+
+        let key = DictKey::new(string.native_hash()?, &string.rc.upgrade()?);
+
+        match self.value.0.dict.as_ref() {
+            &Type::Dict(ref dict) => match dict.value.0.borrow_mut().remove(&key) {
+                Some(_) => {
+                    dict.touch();
+                    Ok(rs::None())
+                },
+                None => Err(Error::attribute(&strings_error_no_attribute!("object", string.value.0))),
+            },
+            _ => Err(Error::system(
+                &format!("Object dict was not a dict; file: {}, line: {}", file!(), line!())))
+        }
+    }
+}
+
 impl method::Id for PyObject {
     fn native_id(&self) -> rs::ObjectId {
         match self.rc.upgrade() {
@@ -194,14 +227,14 @@ impl method::Hashed for PyObject {
     fn native_hash(&self) -> RtResult<rs::HashId> {
         let mut s = DefaultHasher::new();
         self.native_id().hash(&mut s);
-        Ok(s.finish())
+        Ok(s.finish() as rs::HashId)
     }
 }
 
 
 method_not_implemented!(PyObject,
     AbsValue   Add   AddItem   Append  Await   BitwiseAnd   BitwiseOr   BooleanCast
-    BytesCast   Call   Clear   Close  ComplexCast   Contains   Count   DelAttr
+    BytesCast   Call   Clear   Close  ComplexCast   Contains   Count
     Delete   DeleteItem   DescriptorGet   DescriptorSet DescriptorSetName   Discard   DivMod
     Enter Equal   Exit   Extend   FloatCast FloorDivision   Get  GetAttribute
     GetItem   GreaterOrEqual   GreaterThan   InPlaceAdd   InPlaceBitwiseAnd   InPlaceBitwiseOr
@@ -273,6 +306,21 @@ mod tests {
         assert_eq!(result, rt.none())
     }
 
+    #[test]
+    fn __delattr__() {
+        let rt = setup_test();
+        let object = rt.object(rs::None());
+
+        let key = rt.str("hello");
+        object.op_setattr(&rt, &key, &rt.int(234)).unwrap();
+
+        let result = object.op_delattr(&rt, &key).unwrap();
+        assert_eq!(result, rt.none());
+
+        assert!(object.op_getattr(&rt, &key).is_err());
+        assert!(object.op_delattr(&rt, &key).is_err());
+    }
+
     #[cfg(test)]
     mod __getattr__ {
         use super::*;
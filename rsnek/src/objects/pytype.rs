@@ -3,23 +3,86 @@
 //! ```ignore
 //! type(thing)
 //! ```
+//!
+//! Each builtin type has a single type object created by `PyMeta` when the runtime is
+//! initialized. Type objects only know their names and bases which is enough for
+//! `isinstance()` and `issubclass()` to walk the hierarchy.
 use std::fmt;
-use std::cell::RefCell;
 use std::borrow::Borrow;
+use std::collections::HashMap;
 use std::ops::Deref;
 
 use ::resources::strings;
 use runtime::Runtime;
+use ::runtime::traits::{BooleanProvider, IntegerProvider, StringProvider, TupleProvider, ModuleImporter};
 use api::{self, RtValue, method, typing};
+use api::method::{GetAttr, Id, Call};
+use api::result::{Error, ObjectResult, RtResult};
 use api::selfref::{self, SelfRef};
 
 use ::system::primitives as rs;
+use ::system::sync::RefCell;
 use ::api::RtObject;
 use ::modules::builtins::Type;
 
 
+const TYPE_NAME: &'static str = "type";
+
+/// Names of the builtin types paired with the name of their single base class. The order
+/// matters since a base must be created before any of its subclasses.
+const BUILTIN_CLASSES: &'static [(&'static str, &'static str)] = &[
+    ("NoneType", "object"),
+    ("int", "object"),
+    ("bool", "int"),
+    ("float", "object"),
+    ("complex", "object"),
+    ("str", "object"),
+    ("bytes", "object"),
+    ("bytearray", "object"),
+    ("tuple", "object"),
+    ("list", "object"),
+    ("dict", "object"),
+    ("set", "object"),
+    ("frozenset", "object"),
+    ("slice", "object"),
+    ("range", "object"),
+    ("iterator", "object"),
+    ("function", "object"),
+    ("module", "object"),
+    ("code", "object"),
+    ("frame", "object"),
+];
+
+
 pub struct PyMeta {
     pub pytype: RtObject,
+    classes: HashMap<&'static str, RtObject>,
+}
+
+
+impl PyMeta {
+    /// Get the type object of a builtin type by name
+    pub fn class(&self, name: &str) -> Option<RtObject> {
+        self.classes.get(name).cloned()
+    }
+
+    /// Get the type object for the type of `value`. Anything without a type object of its
+    /// own is treated as a plain `object`.
+    pub fn class_of(&self, value: &Type) -> RtObject {
+        match self.class(value.debug_name()) {
+            Some(class) => class,
+            None => self.classes["object"].clone(),
+        }
+    }
+
+    fn new_class(name: &str, bases: rs::Tuple) -> RtObject {
+        PyMeta::inject_selfref(PyMeta::alloc(rs::Type {
+            name: name.to_string(),
+            module: strings::BUILTINS_MODULE.to_string(),
+            bases: bases,
+            subclasses: RefCell::new(Vec::new()),
+        }))
+    }
 }
 
 
@@ -34,13 +97,21 @@ impl typing::BuiltinType for PyMeta {
     }
 
     fn init_type() -> Self {
+        let object = PyMeta::new_class("object", Vec::new());
+        let pytype = PyMeta::new_class(TYPE_NAME, vec![object.clone()]);
+
+        let mut classes = HashMap::new();
+        classes.insert("object", object);
+        classes.insert(TYPE_NAME, pytype.clone());
+
+        for &(name, base) in BUILTIN_CLASSES {
+            let base = classes[base].clone();
+            classes.insert(name, PyMeta::new_class(name, vec![base]));
+        }
+
         PyMeta {
-            pytype: PyMeta::inject_selfref(PyMeta::alloc(rs::Type {
-                                                             name: "type".to_string(),
-                                                             module: strings::BUILTINS_MODULE.to_string(),
-                                                             bases: Vec::new(),
-                                                             subclasses: RefCell::new(Vec::new()),
-                                                         })),
+            pytype: pytype,
+            classes: classes,
         }
     }
 
@@ -70,6 +141,25 @@ pub type PyType = RtValue<TypeValue>;
 pub struct TypeValue(pub rs::Type);
 
 
+impl PyType {
+    pub fn name(&self) -> &str {
+        &self.value.0.name
+    }
+
+    /// True if `other` is this type or any type in its chain of bases
+    pub fn is_subtype(&self, other: &PyType) -> bool {
+        if self.native_id() == other.native_id() {
+            return true;
+        }
+
+        self.value.0.bases.iter().any(|base| match base.as_ref() {
+            &Type::Type(ref base) => base.is_subtype(other),
+            _ => false,
+        })
+    }
+}
+
+
 impl fmt::Display for PyType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{:?}", self.value.0)
@@ -86,47 +176,221 @@ impl fmt::Debug for PyType {
 impl api::PyAPI for PyType {}
 
 
+/// `self.rhs`
+impl method::GetAttr for PyType {
+    fn op_getattr(&self, rt: &Runtime, name: &RtObject) -> ObjectResult {
+        match name.as_ref() {
+            &Type::Str(ref pystring) => {
+                match pystring.value.0.as_str() {
+                    "__name__" => Ok(rt.str(self.value.0.name.clone())),
+                    "__module__" => Ok(rt.str(self.value.0.module.clone())),
+                    "__bases__" => Ok(rt.tuple(self.value.0.bases.clone())),
+                    missing => Err(Error::attribute(
+                        &strings_error_no_attribute!(self.name(), missing)))
+                }
+            },
+            other => Err(Error::typerr(
+                string_error_bad_attr_type!("str", other.debug_name())))
+        }
+    }
+}
+
+
+impl method::Id for PyType {
+    fn native_id(&self) -> rs::ObjectId {
+        match self.rc.upgrade() {
+            Ok(this_object) => this_object.native_id(),
+            Err(_) => 0,
+        }
+    }
+}
+
+
+impl method::Hashed for PyType {
+    fn op_hash(&self, rt: &Runtime) -> ObjectResult {
+        let value = self.native_hash()?;
+        Ok(rt.int(value))
+    }
+
+    fn native_hash(&self) -> RtResult<rs::HashId> {
+        Ok(self.native_id() as rs::HashId)
+    }
+}
+
+
+impl method::Equal for PyType {
+    fn op_eq(&self, rt: &Runtime, rhs: &RtObject) -> ObjectResult {
+        let truth = self.native_eq(rhs.as_ref())?;
+        Ok(rt.bool(truth))
+    }
+
+    fn native_eq(&self, rhs: &Type) -> RtResult<rs::Boolean> {
+        Ok(self.native_id() == rhs.native_id())
+    }
+}
+
+
+impl method::NotEqual for PyType {
+    fn op_ne(&self, rt: &Runtime, rhs: &RtObject) -> ObjectResult {
+        let truth = self.native_ne(rhs.as_ref())?;
+        Ok(rt.bool(truth))
+    }
+
+    fn native_ne(&self, rhs: &Type) -> RtResult<rs::Boolean> {
+        Ok(self.native_id() != rhs.native_id())
+    }
+}
+
+
+impl method::BooleanCast for PyType {
+    fn op_bool(&self, rt: &Runtime) -> ObjectResult {
+        Ok(rt.bool(true))
+    }
+
+    fn native_bool(&self) -> RtResult<rs::Boolean> {
+        Ok(true)
+    }
+}
+
+
+impl method::StringCast for PyType {
+    fn op_str(&self, rt: &Runtime) -> ObjectResult {
+        let string = self.native_str()?;
+        Ok(rt.str(string))
+    }
+
+    fn native_str(&self) -> RtResult<rs::String> {
+        Ok(format!("<class '{}'>", self.name()))
+    }
+}
+
+
+impl method::StringRepresentation for PyType {
+    fn op_repr(&self, rt: &Runtime) -> ObjectResult {
+        self.op_str(rt)
+    }
+
+    fn native_repr(&self) -> RtResult<rs::String> {
+        self.native_str()
+    }
+}
+
+
+/// Calling a type object defers to the builtin function of the same name so
+/// `type(x)(y)` behaves like `int(y)` when `x` is an int.
+impl method::Call for PyType {
+    fn op_call(&self, rt: &Runtime, pos_args: &RtObject, starargs: &RtObject, kwargs: &RtObject) -> ObjectResult {
+        let builtins = rt.import_module(strings::BUILTINS_MODULE)?;
+
+        match builtins.op_getattr(&rt, &rt.str(self.name())) {
+            Ok(ref func) if func.debug_name() == "function" => {
+                func.op_call(&rt, &pos_args, &starargs, &kwargs)
+            },
+            _ => Err(Error::typerr(&format!("cannot create '{}' instances", self.name())))
+        }
+    }
+}
+
+
 method_not_implemented!(PyType,
     AbsValue   Add   AddItem   Append
-    Await   BitwiseAnd   BitwiseOr   BooleanCast
-    BytesCast   Call   Clear   Close
-    ComplexCast   Contains   Count   DelAttr
-    Delete   DeleteItem   DescriptorGet   DescriptorSet
-    DescriptorSetName   Discard   DivMod   Enter
-    Equal   Exit   Extend   FloatCast
-    FloorDivision   Get   GetAttr   GetAttribute
-    GetItem   GreaterOrEqual   GreaterThan   Hashed
-    Id   InPlaceAdd   InPlaceBitwiseAnd   InPlaceBitwiseOr
-    InPlaceDivMod   InPlaceFloorDivision   InPlaceLeftShift   InPlaceMatrixMultiply
-    InPlaceModulus   InPlaceMultiply   InPlacePow   InPlaceRightShift
-    InPlaceSubtract   InPlaceTrueDivision   InPlaceXOr   Index
-    Init   IntegerCast   InvertValue   Is
-    IsDisjoint   IsNot   Items   Iter
-    Keys   LeftShift   Length   LengthHint
-    LessOrEqual   LessThan   MatrixMultiply   Modulus
-    Multiply   NegateValue   New   Next
-    NotEqual   Pop   PopItem   PositiveValue
-    Pow   ReflectedAdd   ReflectedBitwiseAnd   ReflectedBitwiseOr
-    ReflectedDivMod   ReflectedFloorDivision   ReflectedLeftShift   ReflectedMatrixMultiply
-    ReflectedModulus   ReflectedMultiply   ReflectedPow   ReflectedRightShift
-    ReflectedSubtract   ReflectedTrueDivision   ReflectedXOr   Remove
-    Reversed   RightShift   Rounding   Send
-    SetAttr   SetDefault   SetItem   StringCast
-    StringFormat   StringRepresentation   Subtract   Throw
-    TrueDivision   Update   Values   XOr
+    Await   BitwiseAnd   BitwiseOr   BytesCast
+    Clear   Close   ComplexCast   Contains
+    Count   DelAttr   Delete   DeleteItem
+    DescriptorGet   DescriptorSet   DescriptorSetName   Discard
+    DivMod   Enter   Exit   Extend
+    FloatCast   FloorDivision   Get   GetAttribute
+    GetItem   GreaterOrEqual   GreaterThan   InPlaceAdd
+    InPlaceBitwiseAnd   InPlaceBitwiseOr   InPlaceDivMod   InPlaceFloorDivision
+    InPlaceLeftShift   InPlaceMatrixMultiply   InPlaceModulus   InPlaceMultiply
+    InPlacePow   InPlaceRightShift   InPlaceSubtract   InPlaceTrueDivision
+    InPlaceXOr   Index   Init   IntegerCast
+    InvertValue   Is   IsDisjoint   IsNot
+    Items   Iter   Keys   LeftShift
+    Length   LengthHint   LessOrEqual   LessThan
+    MatrixMultiply   Modulus   Multiply   NegateValue
+    New   Next   Pop   PopItem
+    PositiveValue   Pow   ReflectedAdd   ReflectedBitwiseAnd
+    ReflectedBitwiseOr   ReflectedDivMod   ReflectedFloorDivision   ReflectedLeftShift
+    ReflectedMatrixMultiply   ReflectedModulus   ReflectedMultiply   ReflectedPow
+    ReflectedRightShift   ReflectedSubtract   ReflectedTrueDivision   ReflectedXOr
+    Remove   Reversed   RightShift   Rounding
+    Send   SetAttr   SetDefault   SetItem
+    StringFormat   Subtract   Throw   TrueDivision
+    Update   Values   XOr
 );
 
 
 #[cfg(test)]
 mod tests {
     use ::runtime::Runtime;
+    use ::runtime::traits::{IntegerProvider, StringProvider, TupleProvider, DictProvider,
+                            PyTypeProvider};
+    use api::method::*;
+    use super::*;
 
     fn setup() -> (Runtime, ) {
         (Runtime::new(), )
     }
 
+    fn class(rt: &Runtime, name: &str) -> RtObject {
+        rt.builtin_class(name).unwrap()
+    }
+
     #[test]
     fn stub() {
         info!("stub");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn is_subtype() {
+        let (rt, ) = setup();
+
+        let (object, int, boolean, string) = (
+            class(&rt, "object"), class(&rt, "int"), class(&rt, "bool"), class(&rt, "str"));
+
+        match (boolean.as_ref(), int.as_ref(), object.as_ref(), string.as_ref()) {
+            (&Type::Type(ref boolean), &Type::Type(ref int),
+             &Type::Type(ref object), &Type::Type(ref string)) => {
+                assert!(boolean.is_subtype(boolean));
+                assert!(boolean.is_subtype(int));
+                assert!(boolean.is_subtype(object));
+                assert!(!boolean.is_subtype(string));
+                assert!(!int.is_subtype(boolean));
+            }
+            _ => panic!("builtin classes must be type objects")
+        }
+    }
+
+    #[test]
+    fn pytype_of_value() {
+        let (rt, ) = setup();
+        let value = rt.int(1);
+
+        let int = rt.pytype(value.as_ref());
+        assert_eq!(int, class(&rt, "int"));
+        assert_eq!(int.native_repr().unwrap(), "<class 'int'>");
+    }
+
+    #[test]
+    fn __getattr__() {
+        let (rt, ) = setup();
+        let boolean = class(&rt, "bool");
+
+        let name = boolean.op_getattr(&rt, &rt.str("__name__")).unwrap();
+        assert_eq!(name, rt.str("bool"));
+
+        let bases = boolean.op_getattr(&rt, &rt.str("__bases__")).unwrap();
+        assert_eq!(bases.native_len().unwrap(), rs::Integer::from(1));
+    }
+
+    #[test]
+    fn __call__() {
+        let (rt, ) = setup();
+        let string = class(&rt, "str");
+
+        let args = rt.tuple(vec![rt.int(42)]);
+        let result = string.op_call(&rt, &args, &rt.tuple(vec![]), &rt.dict(rs::Dict::new())).unwrap();
+        assert_eq!(result, rt.str("42"));
+    }
+}
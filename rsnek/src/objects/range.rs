@@ -0,0 +1,488 @@
+//! PyRange - an immutable sequence of integers that is computed instead of stored
+//!
+//! ```ignore
+//! range(10)
+//! range(1, 10, 2)
+//! ```
+//!
+//! `len()`, `in`, indexing, and slicing are all O(1) regardless of the size
+//! of the range.
+use std::fmt;
+
+use num::{FromPrimitive, Signed, ToPrimitive, Zero};
+
+use ::api::result::Error;
+use ::api::method::{self, Equal, StringCast};
+use ::api::RtObject;
+use ::api::selfref::{self, SelfRef};
+use ::api::{self, RtValue, typing};
+use ::api::typing::BuiltinType;
+use ::api::result::{ObjectResult, RtResult};
+use ::resources::strings;
+use ::runtime::Runtime;
+use ::runtime::traits::{BooleanProvider, IntegerProvider, IteratorProvider, RangeProvider, StringProvider};
+use ::modules::builtins::Type;
+use ::objects::integer::PyIntegerType;
+use ::objects::number;
+use ::system::hash;
+use ::system::primitives as rs;
+
+
+const TYPE_NAME: &'static str = "range";
+
+
+pub struct PyRangeType {}
+
+
+impl typing::BuiltinType for PyRangeType {
+    type T = PyRange;
+    type V = rs::Range;
+
+    #[allow(unused_variables)]
+    fn new(&self, rt: &Runtime, value: Self::V) -> RtObject {
+        PyRangeType::inject_selfref(PyRangeType::alloc(value))
+    }
+
+    fn init_type() -> Self {
+        PyRangeType {}
+    }
+
+    fn inject_selfref(value: Self::T) -> RtObject {
+        let object = RtObject::new(Type::Range(value));
+        let new = object.clone();
+
+        match object.as_ref() {
+            &Type::Range(ref range) => {
+                range.rc.set(&object.clone());
+            }
+            _ => unreachable!(),
+        }
+        new
+    }
+
+    fn alloc(value: Self::V) -> Self::T {
+        PyRange {
+            value: RangeValue(value),
+            rc: selfref::RefCount::default(),
+        }
+    }
+}
+
+
+pub struct RangeValue(pub rs::Range);
+pub type PyRange = RtValue<RangeValue>;
+
+
+impl PyRange {
+    /// The number of elements in the range
+    pub fn native_count(&self) -> rs::Integer {
+        let ref range = self.value.0;
+        let one = rs::Integer::from(1);
+
+        if range.step.is_positive() && range.start < range.stop {
+            (&range.stop - &range.start - &one) / &range.step + one
+        } else if range.step.is_negative() && range.start > range.stop {
+            (&range.start - &range.stop - &one) / -&range.step + one
+        } else {
+            rs::Integer::zero()
+        }
+    }
+
+    /// The element at position `idx` where a negative index counts from the end
+    fn value_at(&self, idx: &rs::Integer) -> RtResult<rs::Integer> {
+        let len = self.native_count();
+        let idx = if idx.is_negative() { idx + &len } else { idx.clone() };
+
+        if idx.is_negative() || idx >= len {
+            return Err(rsnek_exception_index!(TYPE_NAME));
+        }
+
+        Ok(&self.value.0.start + idx * &self.value.0.step)
+    }
+
+    /// Slicing a range produces another range which references the same values
+    fn slice(&self, slice: &Type) -> RtResult<rs::Range> {
+        let len = match self.native_count().to_usize() {
+            Some(len) => len,
+            None => return Err(Error::overflow("range length does not fit in a native size"))
+        };
+
+        let (start, stop, step) = match slice {
+            &Type::Slice(ref slice) => slice.native_indices(len)?,
+            _ => unreachable!()
+        };
+
+        let ref range = self.value.0;
+        Ok(rs::Range {
+            start: &range.start + rs::Integer::from(start) * &range.step,
+            stop: &range.start + rs::Integer::from(stop) * &range.step,
+            step: &range.step * rs::Integer::from(step),
+        })
+    }
+
+    fn native_contains_int(&self, value: &rs::Integer) -> rs::Boolean {
+        let ref range = self.value.0;
+
+        if range.step.is_positive() {
+            range.start <= *value && *value < range.stop &&
+                ((value - &range.start) % &range.step).is_zero()
+        } else {
+            range.stop < *value && *value <= range.start &&
+                ((&range.start - value) % -&range.step).is_zero()
+        }
+    }
+}
+
+
+impl fmt::Debug for PyRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Range({}, {}, {})", self.value.0.start, self.value.0.stop, self.value.0.step)
+    }
+}
+
+
+impl api::PyAPI for PyRange {}
+
+
+/// `self.rhs`
+impl method::GetAttr for PyRange {
+    #[allow(unused_variables)]
+    fn op_getattr(&self, rt: &Runtime, name: &RtObject) -> ObjectResult {
+        match name.as_ref() {
+            &Type::Str(ref pystring) => {
+                match pystring.value.0.as_str() {
+                    "start" => Ok(rt.int(self.value.0.start.clone())),
+                    "stop"  => Ok(rt.int(self.value.0.stop.clone())),
+                    "step"  => Ok(rt.int(self.value.0.step.clone())),
+                    missing => Err(Error::attribute(
+                        &strings_error_no_attribute!(TYPE_NAME, missing)))
+                }
+            },
+            other => Err(Error::typerr(
+                string_error_bad_attr_type!("str", other.debug_name())))
+        }
+    }
+}
+
+
+/// `hash(self)`
+impl method::Hashed for PyRange {
+    fn op_hash(&self, rt: &Runtime) -> ObjectResult {
+        let hash = self.native_hash()?;
+        Ok(rt.int(hash))
+    }
+
+    /// Like CPython, hash `(len, start, step)` ignoring the fields that do not
+    /// affect equality so that `hash(range(0)) == hash(range(5, 5))`.
+    fn native_hash(&self) -> RtResult<rs::HashId> {
+        let ref range = self.value.0;
+        let len = self.native_count();
+        let zero = rs::Integer::zero();
+
+        let (start, step) = match len.to_u8() {
+            Some(0) => (&zero, &zero),
+            Some(1) => (&range.start, &zero),
+            _ => (&range.start, &range.step),
+        };
+
+        let hashes = vec![number::hash_int(&len), number::hash_int(start), number::hash_int(step)];
+        Ok(hash::hash_tuple(hashes.into_iter()))
+    }
+}
+
+
+impl method::StringCast for PyRange {
+    fn op_str(&self, rt: &Runtime) -> ObjectResult {
+        let string = self.native_str()?;
+        Ok(rt.str(string))
+    }
+
+    fn native_str(&self) -> RtResult<rs::String> {
+        let ref range = self.value.0;
+
+        if range.step == rs::Integer::from(1) {
+            Ok(format!("range({}, {})", range.start, range.stop))
+        } else {
+            Ok(format!("range({}, {}, {})", range.start, range.stop, range.step))
+        }
+    }
+}
+
+
+impl method::StringRepresentation for PyRange {
+    fn op_repr(&self, rt: &Runtime) -> ObjectResult {
+        self.op_str(rt)
+    }
+
+    fn native_repr(&self) -> RtResult<rs::String> {
+        self.native_str()
+    }
+}
+
+
+/// Ranges are equal when they produce the same sequence of values
+impl method::Equal for PyRange {
+    fn op_eq(&self, rt: &Runtime, rhs: &RtObject) -> ObjectResult {
+        let truth = self.native_eq(rhs.as_ref())?;
+        Ok(rt.bool(truth))
+    }
+
+    fn native_eq(&self, rhs: &Type) -> RtResult<rs::Boolean> {
+        match rhs {
+            &Type::Range(ref other) => {
+                let len = self.native_count();
+                if len != other.native_count() {
+                    return Ok(false);
+                }
+
+                let (ref lhs, ref rhs) = (&self.value.0, &other.value.0);
+                match len.to_u8() {
+                    Some(0) => Ok(true),
+                    Some(1) => Ok(lhs.start == rhs.start),
+                    _ => Ok(lhs.start == rhs.start && lhs.step == rhs.step)
+                }
+            },
+            _ => Ok(false)
+        }
+    }
+}
+
+
+impl method::NotEqual for PyRange {
+    fn op_ne(&self, rt: &Runtime, rhs: &RtObject) -> ObjectResult {
+        let truth = self.native_ne(rhs.as_ref())?;
+        Ok(rt.bool(truth))
+    }
+
+    fn native_ne(&self, rhs: &Type) -> RtResult<rs::Boolean> {
+        let truth = self.native_eq(rhs)?;
+        Ok(!truth)
+    }
+}
+
+
+impl method::BooleanCast for PyRange {
+    fn op_bool(&self, rt: &Runtime) -> ObjectResult {
+        let truth = self.native_bool()?;
+        Ok(rt.bool(truth))
+    }
+
+    fn native_bool(&self) -> RtResult<rs::Boolean> {
+        Ok(!self.native_count().is_zero())
+    }
+}
+
+
+/// `item in self`
+impl method::Contains for PyRange {
+    fn op_contains(&self, rt: &Runtime, item: &RtObject) -> ObjectResult {
+        let truth = self.native_contains(item.as_ref())?;
+        Ok(rt.bool(truth))
+    }
+
+    fn native_contains(&self, item: &Type) -> RtResult<rs::Boolean> {
+        match item {
+            &Type::Int(ref int) => Ok(self.native_contains_int(&int.value.0)),
+            &Type::Bool(ref boolean) => Ok(self.native_contains_int(&boolean.value.0)),
+            &Type::Float(ref float) if float.value.0.fract() == 0.0 => {
+                match rs::Integer::from_f64(float.value.0) {
+                    Some(int) => Ok(self.native_contains_int(&int)),
+                    None => Ok(false)
+                }
+            },
+            _ => Ok(false)
+        }
+    }
+}
+
+
+impl method::Iter for PyRange {
+    fn op_iter(&self, rt: &Runtime) -> ObjectResult {
+        let iter = self.native_iter()?;
+        Ok(rt.iter(iter))
+    }
+
+    fn native_iter(&self) -> RtResult<rs::Iterator> {
+        Ok(rs::Iterator::range(&self.value.0))
+    }
+}
+
+
+impl method::Reversed for PyRange {
+    fn op_reversed(&self, rt: &Runtime) -> ObjectResult {
+        let iter = self.native_reversed()?;
+        Ok(rt.iter(iter))
+    }
+
+    /// Count down from the last element by the negated step
+    fn native_reversed(&self) -> RtResult<rs::Iterator> {
+        let ref range = self.value.0;
+        let len = self.native_count();
+
+        if len.is_zero() {
+            return Ok(rs::Iterator::Empty);
+        }
+
+        let last = &range.start + (len - rs::Integer::from(1)) * &range.step;
+        let reversed = rs::Range {
+            start: last,
+            stop: &range.start - &range.step,
+            step: -&range.step,
+        };
+
+        Ok(rs::Iterator::range(&reversed))
+    }
+}
+
+
+/// `len(self)`
+impl method::Length for PyRange {
+    fn op_len(&self, rt: &Runtime) -> ObjectResult {
+        let len = self.native_len()?;
+        Ok(rt.int(len))
+    }
+
+    fn native_len(&self) -> RtResult<rs::Integer> {
+        Ok(self.native_count())
+    }
+}
+
+
+/// `self[index]`
+impl method::GetItem for PyRange {
+    fn op_getitem(&self, rt: &Runtime, item: &RtObject) -> ObjectResult {
+        match item.as_ref() {
+            &Type::Int(ref int) => Ok(rt.int(self.value_at(&int.value.0)?)),
+            &Type::Bool(ref boolean) => Ok(rt.int(self.value_at(&boolean.value.0)?)),
+            slice @ &Type::Slice(_) => Ok(rt.range(self.slice(slice)?)),
+            other => Err(Error::typerr(&format!(
+                "range indices must be integers or slices, not {}", other.debug_name())))
+        }
+    }
+
+    fn native_getitem(&self, index: &Type) -> RtResult<RtObject> {
+        match index {
+            &Type::Int(ref int) => {
+                let value = self.value_at(&int.value.0)?;
+                Ok(PyIntegerType::inject_selfref(PyIntegerType::alloc(value)))
+            },
+            &Type::Bool(ref boolean) => {
+                let value = self.value_at(&boolean.value.0)?;
+                Ok(PyIntegerType::inject_selfref(PyIntegerType::alloc(value)))
+            },
+            slice @ &Type::Slice(_) => {
+                let range = self.slice(slice)?;
+                Ok(PyRangeType::inject_selfref(PyRangeType::alloc(range)))
+            },
+            other => Err(Error::typerr(&format!(
+                "range indices must be integers or slices, not {}", other.debug_name())))
+        }
+    }
+}
+
+
+method_not_implemented!(PyRange,
+    AbsValue   Add   AddItem   Append
+    Await   BitwiseAnd   BitwiseOr   BytesCast
+    Call   Clear   Close   ComplexCast
+    Count   DelAttr   Delete   DeleteItem
+    DescriptorGet   DescriptorSet   DescriptorSetName   Discard
+    DivMod   Enter   Exit   Extend
+    FloatCast   FloorDivision   Get   GetAttribute
+    GreaterOrEqual   GreaterThan   Id   InPlaceAdd
+    InPlaceBitwiseAnd   InPlaceBitwiseOr   InPlaceDivMod   InPlaceFloorDivision
+    InPlaceLeftShift   InPlaceMatrixMultiply   InPlaceModulus   InPlaceMultiply
+    InPlacePow   InPlaceRightShift   InPlaceSubtract   InPlaceTrueDivision
+    InPlaceXOr   Index   Init   IntegerCast
+    InvertValue   Is   IsDisjoint   IsNot
+    Items   Keys   LeftShift   LengthHint
+    LessOrEqual   LessThan   MatrixMultiply   Modulus
+    Multiply   NegateValue   New   Next
+    Pop   PopItem   PositiveValue   Pow
+    ReflectedAdd   ReflectedBitwiseAnd   ReflectedBitwiseOr   ReflectedDivMod
+    ReflectedFloorDivision   ReflectedLeftShift   ReflectedMatrixMultiply   ReflectedModulus
+    ReflectedMultiply   ReflectedPow   ReflectedRightShift   ReflectedSubtract
+    ReflectedTrueDivision   ReflectedXOr   Remove   RightShift
+    Rounding   Send   SetAttr   SetDefault
+    SetItem   StringFormat   Subtract   Throw
+    TrueDivision   Update   Values   XOr
+);
+
+
+#[cfg(test)]
+mod tests {
+    use ::objects::iterator::try_collect;
+    use ::runtime::traits::{NoneProvider, SliceProvider};
+    use api::method::*;
+    use super::*;
+
+    fn setup() -> Runtime {
+        Runtime::new()
+    }
+
+    fn range(rt: &Runtime, start: i64, stop: i64, step: i64) -> RtObject {
+        rt.range(rs::Range {
+            start: rs::Integer::from(start),
+            stop: rs::Integer::from(stop),
+            step: rs::Integer::from(step)
+        })
+    }
+
+    #[test]
+    fn __len__() {
+        let rt = setup();
+
+        assert_eq!(range(&rt, 0, 10, 1).native_len().unwrap(), rs::Integer::from(10));
+        assert_eq!(range(&rt, 0, 10, 3).native_len().unwrap(), rs::Integer::from(4));
+        assert_eq!(range(&rt, 10, 0, -3).native_len().unwrap(), rs::Integer::from(4));
+        assert_eq!(range(&rt, 10, 0, 1).native_len().unwrap(), rs::Integer::from(0));
+    }
+
+    #[test]
+    fn __contains__() {
+        let rt = setup();
+        let evens = range(&rt, 0, 10, 2);
+
+        assert_eq!(evens.native_contains(rt.int(4).as_ref()).unwrap(), true);
+        assert_eq!(evens.native_contains(rt.int(5).as_ref()).unwrap(), false);
+        assert_eq!(evens.native_contains(rt.int(10).as_ref()).unwrap(), false);
+
+        let countdown = range(&rt, 10, 0, -2);
+        assert_eq!(countdown.native_contains(rt.int(10).as_ref()).unwrap(), true);
+        assert_eq!(countdown.native_contains(rt.int(0).as_ref()).unwrap(), false);
+    }
+
+    #[test]
+    fn __getitem__() {
+        let rt = setup();
+        let evens = range(&rt, 0, 10, 2);
+
+        assert_eq!(evens.op_getitem(&rt, &rt.int(1)).unwrap(), rt.int(2));
+        assert_eq!(evens.op_getitem(&rt, &rt.int(-1)).unwrap(), rt.int(8));
+        assert!(evens.op_getitem(&rt, &rt.int(5)).is_err());
+
+        let slice = rt.slice(rs::Slice {start: rt.none(), stop: rt.none(), step: rt.int(-1)});
+        let reversed = evens.op_getitem(&rt, &slice).unwrap();
+        assert_eq!(reversed, range(&rt, 8, -2, -2));
+    }
+
+    #[test]
+    fn __reversed__() {
+        let rt = setup();
+        let iter = range(&rt, 0, 10, 3).op_reversed(&rt).unwrap();
+        let values = try_collect(&iter).unwrap();
+
+        assert_eq!(values, vec![rt.int(9), rt.int(6), rt.int(3), rt.int(0)]);
+    }
+
+    #[test]
+    fn __eq__() {
+        let rt = setup();
+
+        assert_eq!(range(&rt, 0, 0, 1), range(&rt, 5, 5, 3));
+        assert_eq!(range(&rt, 0, 3, 5), range(&rt, 0, 1, 1));
+        assert!(range(&rt, 0, 10, 1) != range(&rt, 0, 10, 2));
+        assert_eq!(range(&rt, 0, 0, 1).native_hash().unwrap(),
+                   range(&rt, 5, 5, 3).native_hash().unwrap());
+    }
+}
@@ -58,7 +58,7 @@ impl typing::BuiltinType for PySetType {
 
 
 #[derive(Clone)]
-pub struct SetValue(rs::Set);
+pub struct SetValue(pub rs::Set);
 pub type PySet = RtValue<SetValue>;
 
 
@@ -0,0 +1,275 @@
+//! PySlice - the object created by `slice()` and, eventually, by the `x[start:stop:step]`
+//! subscript syntax.
+//!
+//! ```ignore
+//! slice(3)
+//! slice(1, None, 2)
+//! ```
+//!
+use std::fmt;
+
+use num::ToPrimitive;
+use num_bigint::Sign;
+
+use ::api::result::Error;
+use ::api::method::{self, Equal, StringCast};
+use ::api::RtObject;
+use ::api::selfref::{self, SelfRef};
+use ::api::{self, RtValue, typing};
+use ::api::result::{ObjectResult, RtResult};
+use ::runtime::Runtime;
+use ::runtime::traits::{BooleanProvider, StringProvider};
+use ::modules::builtins::Type;
+use ::objects::collection::sequence;
+use ::system::primitives as rs;
+
+
+const TYPE_NAME: &'static str = "slice";
+
+
+pub struct PySliceType {}
+
+
+impl typing::BuiltinType for PySliceType {
+    type T = PySlice;
+    type V = rs::Slice;
+
+    #[allow(unused_variables)]
+    fn new(&self, rt: &Runtime, value: Self::V) -> RtObject {
+        PySliceType::inject_selfref(PySliceType::alloc(value))
+    }
+
+    fn init_type() -> Self {
+        PySliceType {}
+    }
+
+    fn inject_selfref(value: Self::T) -> RtObject {
+        let object = RtObject::new(Type::Slice(value));
+        let new = object.clone();
+
+        match object.as_ref() {
+            &Type::Slice(ref slice) => {
+                slice.rc.set(&object.clone());
+            }
+            _ => unreachable!(),
+        }
+        new
+    }
+
+    fn alloc(value: Self::V) -> Self::T {
+        PySlice {
+            value: SliceValue(value),
+            rc: selfref::RefCount::default(),
+        }
+    }
+}
+
+
+pub struct SliceValue(pub rs::Slice);
+pub type PySlice = RtValue<SliceValue>;
+
+
+impl PySlice {
+    /// Convert the `start`, `stop`, and `step` objects into native values where `None`
+    /// means "use the default". Combine with `sequence::get_slice` to apply the slice.
+    pub fn native_bounds(&self) -> RtResult<(Option<isize>, Option<isize>, Option<isize>)> {
+        let ref slice = self.value.0;
+        Ok((as_bound(&slice.start)?, as_bound(&slice.stop)?, as_bound(&slice.step)?))
+    }
+
+    /// `slice.indices(len)`
+    pub fn native_indices(&self, len: usize) -> RtResult<(isize, isize, isize)> {
+        let (start, stop, step) = self.native_bounds()?;
+        sequence::slice_indices(len, start, stop, step)
+    }
+}
+
+
+fn as_bound(object: &RtObject) -> RtResult<Option<isize>> {
+    match object.as_ref() {
+        &Type::None(_) => Ok(None),
+        &Type::Int(ref int) => {
+            match int.value.0.to_isize() {
+                Some(idx) => Ok(Some(idx)),
+                // Out of range bounds are clamped to the sequence length regardless
+                None if int.value.0.sign() == Sign::Minus => Ok(Some(isize::min_value())),
+                None => Ok(Some(isize::max_value()))
+            }
+        },
+        &Type::Bool(ref boolean) => Ok(Some(if boolean.value.0 {1} else {0})),
+        other => Err(Error::typerr(&format!(
+            "slice indices must be integers or None, not {}", other.debug_name())))
+    }
+}
+
+
+impl fmt::Debug for PySlice {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Slice({:?}, {:?}, {:?})", self.value.0.start, self.value.0.stop, self.value.0.step)
+    }
+}
+
+
+impl api::PyAPI for PySlice {}
+
+
+/// `self.rhs`
+impl method::GetAttr for PySlice {
+    #[allow(unused_variables)]
+    fn op_getattr(&self, rt: &Runtime, name: &RtObject) -> ObjectResult {
+        self.native_getattr(name.as_ref())
+    }
+
+    fn native_getattr(&self, name: &Type) -> RtResult<RtObject> {
+        match name {
+            &Type::Str(ref pystring) => {
+                match pystring.value.0.as_str() {
+                    "start" => Ok(self.value.0.start.clone()),
+                    "stop"  => Ok(self.value.0.stop.clone()),
+                    "step"  => Ok(self.value.0.step.clone()),
+                    missing => Err(Error::attribute(
+                        &strings_error_no_attribute!(TYPE_NAME, missing)))
+                }
+            },
+            other => Err(Error::typerr(
+                string_error_bad_attr_type!("str", other.debug_name())))
+        }
+    }
+}
+
+
+impl method::Hashed for PySlice {
+    #[allow(unused_variables)]
+    fn op_hash(&self, rt: &Runtime) -> ObjectResult {
+        Err(Error::typerr(&format!("Unhashable type {}", TYPE_NAME)))
+    }
+
+    fn native_hash(&self) -> RtResult<rs::HashId> {
+        Err(Error::typerr(&format!("Unhashable type {}", TYPE_NAME)))
+    }
+}
+
+
+impl method::StringCast for PySlice {
+    fn op_str(&self, rt: &Runtime) -> ObjectResult {
+        let string = self.native_str()?;
+        Ok(rt.str(string))
+    }
+
+    fn native_str(&self) -> RtResult<rs::String> {
+        let ref slice = self.value.0;
+        Ok(format!("slice({}, {}, {})",
+                   slice.start.native_str()?,
+                   slice.stop.native_str()?,
+                   slice.step.native_str()?))
+    }
+}
+
+
+impl method::StringRepresentation for PySlice {
+    fn op_repr(&self, rt: &Runtime) -> ObjectResult {
+        self.op_str(rt)
+    }
+
+    fn native_repr(&self) -> RtResult<rs::String> {
+        self.native_str()
+    }
+}
+
+
+impl method::Equal for PySlice {
+    fn op_eq(&self, rt: &Runtime, rhs: &RtObject) -> ObjectResult {
+        let truth = self.native_eq(rhs.as_ref())?;
+        Ok(rt.bool(truth))
+    }
+
+    fn native_eq(&self, rhs: &Type) -> RtResult<rs::Boolean> {
+        match rhs {
+            &Type::Slice(ref other) => {
+                let (ref lhs, ref rhs) = (&self.value.0, &other.value.0);
+                Ok(lhs.start == rhs.start && lhs.stop == rhs.stop && lhs.step == rhs.step)
+            },
+            _ => Ok(false)
+        }
+    }
+}
+
+
+impl method::NotEqual for PySlice {
+    fn op_ne(&self, rt: &Runtime, rhs: &RtObject) -> ObjectResult {
+        let truth = self.native_ne(rhs.as_ref())?;
+        Ok(rt.bool(truth))
+    }
+
+    fn native_ne(&self, rhs: &Type) -> RtResult<rs::Boolean> {
+        let truth = self.native_eq(rhs)?;
+        Ok(!truth)
+    }
+}
+
+
+method_not_implemented!(PySlice,
+    AbsValue   Add   AddItem   Append
+    Await   BitwiseAnd   BitwiseOr   BooleanCast
+    BytesCast   Call   Clear   Close
+    ComplexCast   Contains   Count   DelAttr
+    Delete   DeleteItem   DescriptorGet   DescriptorSet
+    DescriptorSetName   Discard   DivMod   Enter
+    Exit   Extend   FloatCast   FloorDivision
+    Get   GetAttribute   GetItem   GreaterOrEqual
+    GreaterThan   Id   InPlaceAdd   InPlaceBitwiseAnd
+    InPlaceBitwiseOr   InPlaceDivMod   InPlaceFloorDivision   InPlaceLeftShift
+    InPlaceMatrixMultiply   InPlaceModulus   InPlaceMultiply   InPlacePow
+    InPlaceRightShift   InPlaceSubtract   InPlaceTrueDivision   InPlaceXOr
+    Index   Init   IntegerCast   InvertValue
+    Is   IsDisjoint   IsNot   Items
+    Iter   Keys   LeftShift   Length
+    LengthHint   LessOrEqual   LessThan   MatrixMultiply
+    Modulus   Multiply   NegateValue   New
+    Next   Pop   PopItem   PositiveValue
+    Pow   ReflectedAdd   ReflectedBitwiseAnd   ReflectedBitwiseOr
+    ReflectedDivMod   ReflectedFloorDivision   ReflectedLeftShift   ReflectedMatrixMultiply
+    ReflectedModulus   ReflectedMultiply   ReflectedPow   ReflectedRightShift
+    ReflectedSubtract   ReflectedTrueDivision   ReflectedXOr   Remove
+    Reversed   RightShift   Rounding   Send
+    SetAttr   SetDefault   SetItem   StringFormat
+    Subtract   Throw   TrueDivision   Update
+    Values   XOr
+);
+
+
+#[cfg(test)]
+mod tests {
+    use ::runtime::traits::{IntegerProvider, NoneProvider, SliceProvider};
+    use super::*;
+
+    fn setup() -> Runtime {
+        Runtime::new()
+    }
+
+    #[test]
+    fn indices() {
+        let rt = setup();
+        let slice = rt.slice(rs::Slice {start: rt.none(), stop: rt.int(-1), step: rt.none()});
+
+        match slice.as_ref() {
+            &Type::Slice(ref slice) => assert_eq!(slice.native_indices(10).unwrap(), (0, 9, 1)),
+            _ => unreachable!()
+        }
+    }
+
+    #[test]
+    fn reversed_indices() {
+        let rt = setup();
+        let slice = rt.slice(rs::Slice {start: rt.none(), stop: rt.none(), step: rt.int(-1)});
+
+        match slice.as_ref() {
+            &Type::Slice(ref slice) => {
+                let (start, stop, step) = slice.native_bounds().unwrap();
+                let elems = sequence::get_slice(&[1, 2, 3, 4], start, stop, step).unwrap();
+                assert_eq!(elems, vec![4, 3, 2, 1]);
+            },
+            _ => unreachable!()
+        }
+    }
+}
@@ -7,15 +7,14 @@
 //!
 use std::borrow::Borrow;
 use std::fmt;
+use std::cmp::Ordering;
 use std::ops::Deref;
-use std::hash::{Hash, Hasher};
-use std::collections::hash_map::DefaultHasher;
 use std::str::FromStr;
 
 use num::ToPrimitive;
 
 use ::modules::precondition::{check_args, check_kwargs};
-use ::api::result::Error;
+use ::api::result::{Error, ErrorType};
 use ::api::method;
 use ::api::method::*;
 use ::api::RtObject;
@@ -29,6 +28,7 @@ use ::runtime::traits::{IntegerProvider, BooleanProvider, StringProvider, Defaul
                FunctionProvider, IteratorProvider};
 use ::modules::builtins::Type;
 use ::objects::collection::sequence;
+use ::system::hash;
 use ::system::primitives::{SignatureBuilder};
 use ::system::primitives as rs;
 
@@ -82,6 +82,22 @@ pub struct StringValue(pub rs::String);
 pub type PyString = RtValue<StringValue>;
 
 
+/// Constant strings longer than this are never interned when loaded
+pub const MAX_INTERN_LEN: usize = 20;
+
+
+/// True if `value` is a short, non-empty string of the ASCII letters, digits and
+/// `_` that identifiers are made of, like CPython's `all_name_chars`. Constant
+/// strings like this are likely used as names and are interned when loaded,
+/// anything else would only grow the intern table for good.
+pub fn all_name_chars(value: &str) -> bool {
+    !value.is_empty() && value.len() <= MAX_INTERN_LEN && value.bytes().all(|byte| match byte {
+        b'_' | b'a'...b'z' | b'A'...b'Z' | b'0'...b'9' => true,
+        _ => false
+    })
+}
+
+
 impl PyString {
     /* Missing
         [('__ceil__', <function int.__ceil__>),
@@ -278,9 +294,7 @@ impl method::Hashed for PyString {
     }
 
     fn native_hash(&self) -> RtResult<rs::HashId> {
-        let mut s = DefaultHasher::new();
-        self.value.0.hash(&mut s);
-        Ok(s.finish())
+        Ok(hash::hash_bytes(self.value.0.as_bytes()))
     }
 }
 
@@ -299,6 +313,44 @@ impl method::StringCast for PyString {
 }
 
 
+impl method::StringRepresentation for PyString {
+    fn op_repr(&self, rt: &Runtime) -> ObjectResult {
+        let string = self.native_repr()?;
+        Ok(rt.str(string))
+    }
+
+    /// Quote the string with single quotes unless it only contains single quotes, escaping
+    /// backslashes and unprintable characters the same way as CPython.
+    fn native_repr(&self) -> RtResult<rs::String> {
+        let ref value = self.value.0;
+        let quote = if value.contains('\'') && !value.contains('"') {'"'} else {'\''};
+
+        let mut repr = String::with_capacity(value.len() + 2);
+        repr.push(quote);
+
+        for ch in value.chars() {
+            match ch {
+                '\\' => repr.push_str("\\\\"),
+                '\n' => repr.push_str("\\n"),
+                '\r' => repr.push_str("\\r"),
+                '\t' => repr.push_str("\\t"),
+                ch if ch == quote => {
+                    repr.push('\\');
+                    repr.push(ch);
+                },
+                ch if (ch as u32) < 0x20 || ch as u32 == 0x7f => {
+                    repr.push_str(&format!("\\x{:02x}", ch as u32))
+                },
+                ch => repr.push(ch)
+            }
+        }
+
+        repr.push(quote);
+        Ok(repr)
+    }
+}
+
+
 impl method::Equal for PyString {
     fn op_eq(&self, rt: &Runtime, rhs: &RtObject) -> ObjectResult {
         match self.native_eq(rhs.as_ref()) {
@@ -421,6 +473,19 @@ impl method::Iter for PyString {
     }
 }
 
+
+impl method::Reversed for PyString {
+    fn op_reversed(&self, rt: &Runtime) -> ObjectResult {
+        let iter = self.native_reversed()?;
+        Ok(rt.iter(iter))
+    }
+
+    fn native_reversed(&self) -> RtResult<rs::Iterator> {
+        let selfref = self.rc.upgrade()?;
+        rs::Iterator::reversed(&selfref)
+    }
+}
+
 impl method::Length for PyString {
     fn op_len(&self, rt: &Runtime) -> ObjectResult {
         Ok(rt.int(self.value.0.len()))
@@ -454,23 +519,30 @@ impl method::GetItem for PyString {
 }
 
 
+/// `self < rhs`, `self <= rhs`, `self > rhs`, `self >= rhs`
+ordering_comparison_impls!(PyString, TYPE_NAME, |this: &PyString, rhs: &Type| match rhs {
+    &Type::Str(ref other) => Ok(Some(this.value.0.cmp(&other.value.0))),
+    other => Err(Error::typerr(other.debug_name()))
+});
+
+
 method_not_implemented!(PyString,
     AbsValue   AddItem   Append   Await   BitwiseAnd   BitwiseOr   
     BytesCast   Call   Clear   Close   ComplexCast   Count   
     DelAttr   Delete   DeleteItem   DescriptorGet   DescriptorSet   DescriptorSetName   
     Discard   DivMod   Enter   Exit   Extend   FloatCast   
-    FloorDivision   Get  GetAttribute   GreaterOrEqual   GreaterThan
+    FloorDivision   Get  GetAttribute   
     Id   Index   Init   InPlaceAdd   InPlaceBitwiseAnd   InPlaceBitwiseOr   
     InPlaceDivMod   InPlaceFloorDivision   InPlaceLeftShift   InPlaceMatrixMultiply
     InPlaceModulus   InPlaceMultiply InPlacePow   InPlaceRightShift   InPlaceSubtract
     InPlaceTrueDivision   InPlaceXOr   InvertValue Is   IsDisjoint   IsNot   Items   Keys
-    LeftShift LengthHint   LessOrEqual   LessThan   MatrixMultiply   Modulus   NegateValue
+    LeftShift LengthHint   MatrixMultiply   Modulus   NegateValue
     New   Next   NotEqual   Pop   PopItem   PositiveValue  Pow   ReflectedAdd   ReflectedBitwiseAnd
     ReflectedBitwiseOr   ReflectedDivMod   ReflectedFloorDivision ReflectedLeftShift
     ReflectedMatrixMultiply   ReflectedModulus   ReflectedMultiply   ReflectedPow
     ReflectedRightShift ReflectedSubtract   ReflectedTrueDivision   ReflectedXOr   Remove
-    Reversed   RightShift Rounding   Send   SetAttr   SetDefault   SetItem   StringFormat
-    StringRepresentation   Subtract   Throw   TrueDivision   Update   Values XOr
+    RightShift Rounding   Send   SetAttr   SetDefault   SetItem   StringFormat
+    Subtract   Throw   TrueDivision   Update   Values XOr
 );
 
 
@@ -486,4 +558,15 @@ mod tests {
     fn stub() {
         info!("stub");
     }
+
+    #[test]
+    fn intern_candidates() {
+        use super::all_name_chars;
+
+        assert!(all_name_chars("snake_case_1"));
+        assert!(!all_name_chars(""));
+        assert!(!all_name_chars("not a name"));
+        assert!(!all_name_chars("caf\u{e9}"));
+        assert!(!all_name_chars(&"x".repeat(21)));
+    }
 }
\ No newline at end of file
@@ -6,13 +6,11 @@
 //! ```
 //!
 use std::fmt;
-use std::ops::{Add, Deref};
+use std::ops::Deref;
 use std::borrow::Borrow;
-use std::hash::{Hash, Hasher};
-use std::collections::hash_map::DefaultHasher;
 
 use itertools::Itertools;
-use num::{ToPrimitive, Zero};
+use num::ToPrimitive;
 
 use ::api::result::Error;
 use ::api::method::{self, Id, Length, StringRepresentation};
@@ -26,6 +24,7 @@ use ::runtime::traits::{BooleanProvider, IntegerProvider, StringProvider,
                IteratorProvider, DefaultTupleProvider, TupleProvider};
 use ::modules::builtins::Type;
 use ::objects::collection::sequence;
+use ::system::hash;
 use ::system::primitives::{Tuple};
 use ::system::primitives as rs;
 
@@ -97,17 +96,11 @@ impl method::Hashed for PyTuple {
     }
 
     fn native_hash(&self) -> RtResult<rs::HashId> {
-        if self.native_len().unwrap().is_zero() {
-            let mut s = DefaultHasher::new();
-            let this_object = self.rc.upgrade()?;
-            this_object.native_id().hash(&mut s);
-
-            return Ok(s.finish());
-        }
-
-        self.value.0.iter()
+        let hashes = self.value.0.iter()
             .map(RtObject::native_hash)
-            .fold_results(0, Add::add)
+            .collect::<RtResult<Vec<rs::HashId>>>()?;
+
+        Ok(hash::hash_tuple(hashes.into_iter()))
     }
 }
 
@@ -209,6 +202,19 @@ impl method::Iter for PyTuple {
 }
 
 
+impl method::Reversed for PyTuple {
+    fn op_reversed(&self, rt: &Runtime) -> ObjectResult {
+        let iter = self.native_reversed()?;
+        Ok(rt.iter(iter))
+    }
+
+    fn native_reversed(&self) -> RtResult<rs::Iterator> {
+        let selfref = self.rc.upgrade()?;
+        rs::Iterator::reversed(&selfref)
+    }
+}
+
+
 impl method::Length for PyTuple {
     fn op_len(&self, rt: &Runtime) -> ObjectResult {
         let value = self.native_len()?;
@@ -255,7 +261,7 @@ method_not_implemented!(PyTuple,
     ReflectedBitwiseOr   ReflectedDivMod   ReflectedFloorDivision   ReflectedLeftShift
     ReflectedMatrixMultiply   ReflectedModulus   ReflectedMultiply   ReflectedPow
     ReflectedRightShift   ReflectedSubtract ReflectedTrueDivision   ReflectedXOr   Remove
-    Reversed   RightShift   Rounding Send   SetAttr   SetDefault   SetItem   StringFormat
+    RightShift   Rounding Send   SetAttr   SetDefault   SetItem   StringFormat
     StringRepresentation Subtract   Throw   TrueDivision   Update   Values   XOr
 );
 
@@ -316,6 +322,17 @@ mod tests {
             assert!(r1 != r3);
         }
 
+        /// `hash((1, 2))` in CPython 3.8+ and order matters
+        #[test]
+        fn matches_cpython() {
+            let rt = setup_test();
+            let tuple = rt.tuple(vec![rt.int(1), rt.int(2)]);
+            let reversed = rt.tuple(vec![rt.int(2), rt.int(1)]);
+
+            assert_eq!(tuple.native_hash().unwrap(), -3550055125485641917);
+            assert!(tuple.native_hash().unwrap() != reversed.native_hash().unwrap());
+        }
+
         #[test]
         #[should_panic]
         fn unhashable_items_causes_error() {
@@ -45,9 +45,32 @@ pub const PROMPT:  &'static str = include_str!("prompt.in");
 
 /// Static strings are love, static strings are life
 pub const BUILTINS_MODULE: &'static str = "builtins";
+pub const DIS_MODULE: &'static str = "dis";
+pub const GC_MODULE: &'static str = "gc";
+pub const GREENLET_MODULE: &'static str = "greenlet";
+pub const IO_MODULE: &'static str = "io";
+pub const CPROFILE_MODULE: &'static str = "cProfile";
+pub const PROFILE_MODULE: &'static str = "profile";
+pub const SYS_MODULE: &'static str = "sys";
+pub const THREADING_MODULE: &'static str = "threading";
+pub const TRACEBACK_MODULE: &'static str = "traceback";
+
+/// Module of the functions defined by the code being run
+pub const MAIN_MODULE: &'static str = "__main__";
 
 pub const COMPILED_SOURCE_EXT: &'static str = "rsc";
 
+/// File names errors are reported against for code given with `-c` and typed
+/// into the repl
+pub const SOURCE_STRING: &'static str = "<string>";
+pub const SOURCE_STDIN: &'static str = "<stdin>";
+
+/// Lines typed into the repl are kept in this file in the home directory
+pub const HISTORY_FILE: &'static str = ".rsnek_history";
+
+/// Prompt of the debugger started by `--debug` and `breakpoint()`
+pub const DEBUG_PROMPT: &'static str = "(rdb) ";
+
 /// Stol'd from CPython
 /// ```ignore
 /// type(1).__doc__
@@ -1,5 +1,14 @@
+use slog;
+
 use ::system::{Argv, ThreadModel};
 
+
+/// Environment variable with the log levels to use, see `Logging::parse`
+pub const LOG_ENV: &'static str = "RSNEK_LOG";
+
+/// Crate name, which module filters can leave out
+const CRATE_NAME: &'static str = "rsnek";
+
 #[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize)]
 pub enum Mode {
     Interactive,
@@ -9,16 +18,245 @@ pub enum Mode {
 }
 
 
+/// Compiler artifact to print instead of running the code
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Serialize)]
+pub enum Dump {
+    Tokens,
+    Ast,
+    Bytecode,
+}
+
+
+/// Report of the profiler to print to stderr after the program ran
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Serialize)]
+pub enum Profile {
+    Stats,
+    Collapsed,
+    Opcodes,
+}
+
+
 #[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize)]
 pub struct Config<'a> {
     pub mode: Mode,
     pub arguments: Argv<'a>,
+    /// Stop in the debugger before the first line of the program
     pub debug_support: bool,
     pub thread_model: ThreadModel,
-    pub logging: Logging
+    pub logging: Logging,
+    /// Optimization level, 0 runs the code as compiled
+    pub optimize: usize,
+    /// Size in bytes of the stack of each greenlet
+    pub stack_size: usize,
+    /// Maximum depth of the python call stack
+    pub recursion_limit: usize,
+    /// Print the tokens, AST, or bytecode of the input and exit
+    pub dump: Option<Dump>,
+    /// Profile the program and print the report when it is done
+    pub profile: Option<Profile>,
+    /// Path of the line coverage report to write, or merge with, when the
+    /// program is done
+    pub coverage: Option<String>,
+}
+
+
+/// Most verbose level of the log records that are kept, in the order of
+/// `slog::Level` so they compare the same.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd, Serialize)]
+pub enum LogLevel {
+    Off = 0,
+    Critical = 1,
+    Error = 2,
+    Warning = 3,
+    Info = 4,
+    Debug = 5,
+    Trace = 6,
 }
 
 
+impl LogLevel {
+    pub fn parse(name: &str) -> Option<LogLevel> {
+        match name.to_lowercase().as_str() {
+            "off"                       => Some(LogLevel::Off),
+            "critical" | "crit"         => Some(LogLevel::Critical),
+            "error"                     => Some(LogLevel::Error),
+            "warning" | "warn"          => Some(LogLevel::Warning),
+            "info"                      => Some(LogLevel::Info),
+            "debug"                     => Some(LogLevel::Debug),
+            "trace"                     => Some(LogLevel::Trace),
+            _ => None
+        }
+    }
 
+    /// The level of `-v` given `count` times, each one shows one more level
+    /// starting from info.
+    pub fn verbosity(count: usize) -> LogLevel {
+        match count {
+            0 => LogLevel::Off,
+            1 => LogLevel::Info,
+            2 => LogLevel::Debug,
+            _ => LogLevel::Trace,
+        }
+    }
+
+    pub fn allows(&self, level: slog::Level) -> bool {
+        level.as_usize() <= *self as usize
+    }
+}
+
+
+/// Where log records are written
 #[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize)]
-pub struct Logging;
+pub enum LogTarget {
+    Stderr,
+    /// Appended to the file at the path
+    File(String),
+}
+
+
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Serialize)]
+pub enum LogFormat {
+    Text,
+    /// One JSON object per record and line
+    Json,
+}
+
+
+/// What gets logged and where. Nothing is logged by default so stdout and stderr
+/// only carry the output of the program.
+#[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize)]
+pub struct Logging {
+    pub level: LogLevel,
+    pub target: LogTarget,
+    pub format: LogFormat,
+    /// Levels of modules which differ from `level` by module path, like
+    /// `runtime::interpreter` or `rsnek::runtime::interpreter`. The filter of a
+    /// module applies to its submodules and the longest match wins.
+    pub filters: Vec<(String, LogLevel)>,
+}
+
+
+impl Default for Logging {
+    fn default() -> Self {
+        Logging {
+            level: LogLevel::Off,
+            target: LogTarget::Stderr,
+            format: LogFormat::Text,
+            filters: Vec::new(),
+        }
+    }
+}
+
+
+impl Logging {
+    /// Set the levels from a comma separated `spec` like the value of `RSNEK_LOG`.
+    /// Each entry is either a level for every module or `module=level`, for
+    /// example `warn,runtime::interpreter=trace`.
+    pub fn parse(&mut self, spec: &str) -> Result<(), String> {
+        for entry in spec.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+            let (module, name) = match entry.find('=') {
+                Some(idx) => (Some(&entry[..idx]), &entry[idx + 1..]),
+                None => (None, entry)
+            };
+
+            let level = match LogLevel::parse(name.trim()) {
+                Some(level) => level,
+                None => return Err(format!("invalid log level '{}' in '{}'", name, entry))
+            };
+
+            match module.map(str::trim) {
+                Some(module) => {
+                    let module = module.to_string();
+                    self.filters.retain(|&(ref other, _)| *other != module);
+                    self.filters.push((module, level));
+                },
+                None => self.level = level
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The level of the records of the module at `path`, which is what
+    /// `module_path!()` gives.
+    pub fn level_for(&self, path: &str) -> LogLevel {
+        self.filters.iter()
+            .filter_map(|&(ref module, level)| {
+                let qualified = format!("{}::{}", CRATE_NAME, module);
+                if contains(module, path) {
+                    Some((module.len(), level))
+                } else if contains(&qualified, path) {
+                    Some((qualified.len(), level))
+                } else {
+                    None
+                }
+            })
+            .max_by_key(|&(len, _)| len)
+            .map(|(_, level)| level)
+            .unwrap_or(self.level)
+    }
+
+    /// True if a record of `level` from the module at `path` is kept
+    pub fn allows(&self, path: &str, level: slog::Level) -> bool {
+        self.level_for(path).allows(level)
+    }
+
+    /// False when nothing would be logged, no logger needs to be set up then
+    pub fn is_enabled(&self) -> bool {
+        self.level != LogLevel::Off || self.filters.iter().any(|&(_, level)| level != LogLevel::Off)
+    }
+}
+
+
+/// True if the module at `path` is `module` or one of its submodules
+fn contains(module: &str, path: &str) -> bool {
+    path == module || (path.starts_with(module) && path[module.len()..].starts_with("::"))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_levels() {
+        let mut logging = Logging::default();
+        assert!(!logging.is_enabled());
+
+        logging.parse("warn, runtime::interpreter=trace,rsnek::compiler=off").unwrap();
+        assert_eq!(logging.level, LogLevel::Warning);
+        assert_eq!(logging.filters, vec![
+            (String::from("runtime::interpreter"), LogLevel::Trace),
+            (String::from("rsnek::compiler"), LogLevel::Off),
+        ]);
+        assert!(logging.is_enabled());
+
+        assert!(logging.parse("runtime=loud").is_err());
+    }
+
+    #[test]
+    fn module_filters() {
+        let mut logging = Logging::default();
+        logging.parse("error,runtime=info,runtime::interpreter=trace").unwrap();
+
+        assert_eq!(logging.level_for("rsnek::runtime::interpreter"), LogLevel::Trace);
+        assert_eq!(logging.level_for("rsnek::runtime::gc"), LogLevel::Info);
+        assert_eq!(logging.level_for("rsnek::runtimes"), LogLevel::Error);
+        assert_eq!(logging.level_for("rsnek"), LogLevel::Error);
+
+        logging.parse("rsnek=debug").unwrap();
+        assert_eq!(logging.level_for("rsnek"), LogLevel::Debug);
+        assert_eq!(logging.level_for("rsnek::compiler"), LogLevel::Debug);
+        assert_eq!(logging.level_for("rsnek::runtime::interpreter"), LogLevel::Trace);
+
+        assert!(logging.allows("rsnek::runtime::gc", slog::Level::Warning));
+        assert!(!logging.allows("rsnek::runtime::gc", slog::Level::Debug));
+    }
+
+    #[test]
+    fn verbosity() {
+        assert_eq!(LogLevel::verbosity(0), LogLevel::Off);
+        assert_eq!(LogLevel::verbosity(2), LogLevel::Debug);
+        assert_eq!(LogLevel::verbosity(5), LogLevel::Trace);
+    }
+}
@@ -0,0 +1,477 @@
+//! Line coverage of python code
+//!
+//! The statement lines of a file are the lines of its statements and
+//! `Stmt::Newline`s, taken from the AST by `Compiler::statement_lines` so lines
+//! the optimizer removes as dead code still count as missing. Compiled code whose
+//! source is gone falls back to the lines it sets with `SetLineNumber` or has in
+//! its line table. The interpreter counts a line of the coverage of the runtime
+//! each time a frame starts running it.
+//!
+//! `rsnek --coverage=<path>` measures the whole program and writes a report of
+//! the executed and missing lines of each file to the path, as LCOV tracefile when
+//! it ends with `.info` or `.lcov` and as JSON otherwise. The report in an existing
+//! file is merged with the new one, so the runs of a test suite add up. Runs that
+//! finish at the same time take turns through a `<path>.lock` file, and the report
+//! is written to `<path>.tmp` and renamed over the old one so that it is never
+//! left half written.
+//!
+use std::collections::{BTreeMap, BTreeSet};
+use std::ffi::OsString;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use serde_json;
+
+use ::runtime::OpCode;
+use ::system::primitives as rs;
+use ::system::primitives::{Instr, Native};
+
+
+/// The coverage shared by the runtime and the code that reports it
+pub type SharedCoverage = Arc<Mutex<Coverage>>;
+
+/// A lock file older than this was left by a run that died while saving
+const STALE_LOCK_SECS: u64 = 30;
+
+/// How long to wait for the lock before giving up on saving
+const LOCK_TIMEOUT_SECS: u64 = 60;
+
+
+/// Layout of a report file
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub enum Format {
+    Json,
+    Lcov,
+}
+
+
+impl Format {
+    /// LCOV for the `.info` and `.lcov` tracefile extensions, JSON otherwise
+    pub fn from_path(path: &Path) -> Format {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("info") | Some("lcov") => Format::Lcov,
+            _ => Format::Json
+        }
+    }
+}
+
+
+/// Run counts of the statement lines of each file
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Coverage {
+    files: BTreeMap<String, BTreeMap<usize, usize>>,
+}
+
+
+/// The JSON report, `hits` only has the executed lines
+#[derive(Debug, Serialize, Deserialize)]
+struct JsonReport {
+    files: BTreeMap<String, JsonFile>,
+}
+
+
+#[derive(Debug, Serialize, Deserialize)]
+struct JsonFile {
+    executed: Vec<usize>,
+    missing: Vec<usize>,
+    hits: BTreeMap<usize, usize>,
+}
+
+
+impl Coverage {
+    pub fn new() -> Self {
+        Coverage {
+            files: BTreeMap::new(),
+        }
+    }
+
+    pub fn shared() -> SharedCoverage {
+        Arc::new(Mutex::new(Coverage::new()))
+    }
+
+    /// Add the statement `lines` of `filename`, see `Compiler::statement_lines`
+    pub fn add_lines<I>(&mut self, filename: &str, lines: I) where I: IntoIterator<Item=usize> {
+        let file = self.files.entry(String::from(filename)).or_insert_with(BTreeMap::new);
+        for line in lines.into_iter() {
+            file.entry(line).or_insert(0);
+        }
+    }
+
+    /// Add the lines `code` and the functions it defines set, for code without a
+    /// source to take the statement lines from
+    pub fn add_code(&mut self, code: &rs::Code) {
+        let mut lines = BTreeSet::new();
+        statement_lines(code, &mut lines);
+
+        let file = self.files.entry(code.co_filename.clone()).or_insert_with(BTreeMap::new);
+        for line in lines.into_iter() {
+            file.entry(line).or_insert(0);
+        }
+
+        for nested in nested_code(code) {
+            self.add_code(nested);
+        }
+    }
+
+    /// `line` of `filename` started running
+    pub fn hit(&mut self, filename: &str, line: usize) {
+        let file = self.files.entry(String::from(filename)).or_insert_with(BTreeMap::new);
+        *file.entry(line).or_insert(0) += 1;
+    }
+
+    /// The files with statement lines
+    pub fn files(&self) -> Vec<&str> {
+        self.files.keys().map(String::as_str).collect()
+    }
+
+    /// Times `line` of `filename` was run, `None` if it is not a statement line
+    pub fn hits(&self, filename: &str, line: usize) -> Option<usize> {
+        self.files.get(filename).and_then(|file| file.get(&line)).cloned()
+    }
+
+    pub fn executed(&self, filename: &str) -> Vec<usize> {
+        self.lines(filename, |count| count > 0)
+    }
+
+    pub fn missing(&self, filename: &str) -> Vec<usize> {
+        self.lines(filename, |count| count == 0)
+    }
+
+    fn lines<F>(&self, filename: &str, filter: F) -> Vec<usize> where F: Fn(usize) -> bool {
+        match self.files.get(filename) {
+            Some(file) => file.iter()
+                .filter(|&(_, &count)| filter(count))
+                .map(|(&line, _)| line)
+                .collect(),
+            None => Vec::new()
+        }
+    }
+
+    /// Add the statement lines and counts of `other`
+    pub fn merge(&mut self, other: &Coverage) {
+        for (filename, lines) in other.files.iter() {
+            let file = self.files.entry(filename.clone()).or_insert_with(BTreeMap::new);
+            for (&line, &count) in lines.iter() {
+                *file.entry(line).or_insert(0) += count;
+            }
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        let files = self.files.keys()
+            .map(|filename| {
+                let hits = self.files[filename].iter()
+                    .filter(|&(_, &count)| count > 0)
+                    .map(|(&line, &count)| (line, count))
+                    .collect();
+
+                (filename.clone(), JsonFile {
+                    executed: self.executed(filename),
+                    missing: self.missing(filename),
+                    hits: hits,
+                })
+            })
+            .collect();
+
+        match serde_json::to_string_pretty(&JsonReport { files: files }) {
+            Ok(json) => json,
+            Err(err) => unreachable!("maps of numbers always serialize: {}", err)
+        }
+    }
+
+    pub fn from_json(json: &str) -> Result<Coverage, String> {
+        let report: JsonReport = serde_json::from_str(json).map_err(|err| err.to_string())?;
+        let mut coverage = Coverage::new();
+
+        for (filename, report) in report.files.into_iter() {
+            let file = coverage.files.entry(filename).or_insert_with(BTreeMap::new);
+            for line in report.missing.into_iter() {
+                file.insert(line, 0);
+            }
+            // A hand edited report may list a line as executed without its count
+            for line in report.executed.into_iter() {
+                file.insert(line, report.hits.get(&line).cloned().unwrap_or(1));
+            }
+        }
+
+        Ok(coverage)
+    }
+
+    /// The LCOV tracefile of the coverage, see `geninfo(1)`
+    pub fn to_lcov(&self) -> String {
+        let mut text = String::new();
+
+        for (filename, lines) in self.files.iter() {
+            text.push_str(&format!("TN:\nSF:{}\n", filename));
+            for (line, count) in lines.iter() {
+                text.push_str(&format!("DA:{},{}\n", line, count));
+            }
+
+            let executed = lines.values().filter(|&&count| count > 0).count();
+            text.push_str(&format!("LF:{}\nLH:{}\nend_of_record\n", lines.len(), executed));
+        }
+        text
+    }
+
+    /// Read the files and line counts of an LCOV tracefile, other records are skipped
+    pub fn from_lcov(text: &str) -> Result<Coverage, String> {
+        let mut coverage = Coverage::new();
+        let mut filename: Option<String> = None;
+
+        for (idx, line) in text.lines().enumerate() {
+            let invalid = || format!("invalid LCOV record on line {}: {}", idx + 1, line);
+
+            if line.starts_with("SF:") {
+                let name = String::from(&line[3..]);
+                coverage.files.entry(name.clone()).or_insert_with(BTreeMap::new);
+                filename = Some(name);
+            } else if line.starts_with("DA:") {
+                let mut fields = line[3..].split(',').map(|field| field.trim().parse::<usize>());
+                let (number, count) = match (fields.next(), fields.next(), &filename) {
+                    (Some(Ok(number)), Some(Ok(count)), &Some(_)) => (number, count),
+                    _ => return Err(invalid())
+                };
+
+                let file = coverage.files.get_mut(filename.as_ref().unwrap()).unwrap();
+                *file.entry(number).or_insert(0) += count;
+            } else if line == "end_of_record" {
+                filename = None;
+            }
+        }
+
+        Ok(coverage)
+    }
+
+    /// Read the report at `path`
+    pub fn load(path: &Path) -> io::Result<Coverage> {
+        let mut text = String::new();
+        File::open(path)?.read_to_string(&mut text)?;
+
+        let loaded = match Format::from_path(path) {
+            Format::Json => Coverage::from_json(&text),
+            Format::Lcov => Coverage::from_lcov(&text),
+        };
+
+        loaded.map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    /// Write the report to `path`, adding the report already there
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let _lock = LockFile::acquire(&sibling(path, ".lock"))?;
+
+        let mut merged = match path.exists() {
+            true => Coverage::load(path)?,
+            false => Coverage::new()
+        };
+        merged.merge(self);
+
+        let text = match Format::from_path(path) {
+            Format::Json => merged.to_json(),
+            Format::Lcov => merged.to_lcov(),
+        };
+
+        // Only the holder of the lock writes the temp file
+        let temp = sibling(path, ".tmp");
+        {
+            let mut file = File::create(&temp)?;
+            file.write_all(text.as_bytes())?;
+            file.sync_all()?;
+        }
+
+        fs::rename(&temp, path)
+    }
+}
+
+
+/// `path` with `suffix` added to its file name
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+
+/// Held while a report is read, merged, and written, removed when dropped
+struct LockFile(PathBuf);
+
+
+impl LockFile {
+    fn acquire(path: &Path) -> io::Result<LockFile> {
+        let started = Instant::now();
+
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(path) {
+                Ok(_) => return Ok(LockFile(path.to_path_buf())),
+                Err(ref err) if err.kind() == io::ErrorKind::AlreadyExists => {},
+                Err(err) => return Err(err)
+            };
+
+            if LockFile::is_stale(path) {
+                let _ = fs::remove_file(path);
+                continue
+            }
+
+            if started.elapsed() > Duration::from_secs(LOCK_TIMEOUT_SECS) {
+                return Err(io::Error::new(io::ErrorKind::TimedOut, format!(
+                    "{} is held by another run", path.display())))
+            }
+
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    fn is_stale(path: &Path) -> bool {
+        let modified = fs::metadata(path).and_then(|metadata| metadata.modified());
+
+        match modified.map(|time| SystemTime::now().duration_since(time)) {
+            Ok(Ok(age)) => age > Duration::from_secs(STALE_LOCK_SECS),
+            _ => false
+        }
+    }
+}
+
+
+impl Drop for LockFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+
+/// Add the lines of `code` itself to `lines`, without the functions it defines
+fn statement_lines(code: &rs::Code, lines: &mut BTreeSet<usize>) {
+    lines.extend(code.co_lnotab.iter().map(|&(_, line)| line));
+
+    for instr in code.co_code.iter() {
+        if let &Instr(OpCode::SetLineNumber, Some(Native::Count(line))) = instr {
+            lines.insert(line);
+        }
+    }
+
+    // The start of the code runs before its first line is set
+    lines.remove(&0);
+}
+
+
+/// The code of the functions defined by `code`
+fn nested_code(code: &rs::Code) -> Vec<&rs::Code> {
+    let constants = code.co_code.iter().filter_map(|instr| match instr {
+        &Instr(OpCode::LoadConst, Some(Native::Code(ref nested))) => Some(nested),
+        _ => None
+    });
+
+    code.co_consts.iter().chain(constants).collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use ::compiler::Compiler;
+    use super::*;
+
+    fn compile(filename: &str, source: &str) -> rs::Code {
+        let ins = Compiler::new().compile_source(filename, source).unwrap();
+        rs::Code::module(filename, ins.to_vec())
+    }
+
+    #[test]
+    fn statement_lines_of_functions() {
+        let mut coverage = Coverage::new();
+        coverage.add_code(&compile("script.py", "def f(a):\n    return a\n\nx = 1\n"));
+
+        assert_eq!(coverage.files(), vec!["script.py"]);
+        assert!(coverage.missing("script.py").contains(&2));
+        assert!(coverage.missing("script.py").contains(&4));
+        assert!(coverage.executed("script.py").is_empty());
+    }
+
+    #[test]
+    fn hits_and_merge() {
+        let mut first = Coverage::new();
+        first.add_code(&compile("script.py", "x = 1\ny = 2\n"));
+        first.hit("script.py", 1);
+        first.hit("script.py", 1);
+
+        let mut second = Coverage::new();
+        second.hit("script.py", 2);
+        second.hit("other.py", 7);
+
+        first.merge(&second);
+        assert_eq!(first.hits("script.py", 1), Some(2));
+        assert_eq!(first.hits("script.py", 2), Some(1));
+        assert_eq!(first.hits("script.py", 3), None);
+        assert_eq!(first.executed("other.py"), vec![7]);
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let mut coverage = Coverage::new();
+        coverage.hit("script.py", 1);
+        coverage.hit("script.py", 1);
+        coverage.files.get_mut("script.py").unwrap().insert(3, 0);
+
+        let json = coverage.to_json();
+        assert!(json.contains("\"executed\": [\n        1\n      ]"), "{}", json);
+        assert_eq!(Coverage::from_json(&json).unwrap(), coverage);
+        assert!(Coverage::from_json("[]").is_err());
+    }
+
+    #[test]
+    fn lcov_round_trip() {
+        let mut coverage = Coverage::new();
+        coverage.hit("script.py", 1);
+        coverage.files.get_mut("script.py").unwrap().insert(3, 0);
+
+        let lcov = coverage.to_lcov();
+        assert_eq!(lcov, "TN:\nSF:script.py\nDA:1,1\nDA:3,0\nLF:2\nLH:1\nend_of_record\n");
+        assert_eq!(Coverage::from_lcov(&lcov).unwrap(), coverage);
+        assert!(Coverage::from_lcov("DA:1,1\n").is_err());
+    }
+
+    #[test]
+    fn statement_lines_from_ast() {
+        let source = "def f():\n    return 1\n    x = 2\n";
+        let mut coverage = Coverage::new();
+        coverage.add_lines("dead.py", Compiler::new().statement_lines("dead.py", source).unwrap());
+
+        assert!(coverage.missing("dead.py").contains(&3));
+    }
+
+    /// Saves at the same time take turns instead of losing each other's hits
+    #[test]
+    fn concurrent_saves() {
+        let path = ::std::env::temp_dir().join("rsnek-coverage-unit-concurrent.json");
+        let _ = fs::remove_file(&path);
+
+        let threads: Vec<_> = (0..8).map(|_| {
+            let path = path.clone();
+            thread::spawn(move || {
+                let mut coverage = Coverage::new();
+                coverage.hit("script.py", 1);
+                coverage.save(&path).unwrap();
+            })
+        }).collect();
+
+        for thread in threads.into_iter() {
+            thread.join().unwrap();
+        }
+
+        let coverage = Coverage::load(&path).unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(coverage.hits("script.py", 1), Some(8));
+        assert!(!sibling(&path, ".lock").exists());
+        assert!(!sibling(&path, ".tmp").exists());
+    }
+
+    #[test]
+    fn format_from_path() {
+        assert_eq!(Format::from_path(Path::new("out.json")), Format::Json);
+        assert_eq!(Format::from_path(Path::new("out.info")), Format::Lcov);
+        assert_eq!(Format::from_path(Path::new("out")), Format::Json);
+    }
+}
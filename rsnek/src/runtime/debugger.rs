@@ -0,0 +1,410 @@
+//! Line debugger in the spirit of `pdb`
+//!
+//! Started by `rsnek --debug script.py`, which stops before the first line, or by
+//! a call to `breakpoint()`. Commands are read from `sys.stdin` and what the
+//! debugger shows is written to `sys.stdout`:
+//!
+//! ```text
+//! b(reak) [file:]line   stop at a line, without an argument list the breakpoints
+//! cl(ear) [file:]line   remove a breakpoint, without an argument remove them all
+//! s(tep)                run to the next line, going into calls
+//! n(ext)                run to the next line of this frame or the one it returns to
+//! c(ontinue)            run to the next breakpoint
+//! w(here)               show the stack of frames, `>` marks the selected one
+//! u(p) / d(own)         select the caller / callee of the selected frame
+//! l(ist)                show the source around the line of the selected frame
+//! p expr                show the value of an expression
+//! q(uit)                stop the program
+//! ```
+//!
+//! Any other input is run as a statement and an empty line repeats the last command.
+//! The end of the input removes the breakpoints and lets the program run to its end.
+//!
+use std::cmp;
+use std::fmt;
+
+use ::api::method::{Call, GetAttr, StringCast};
+use ::api::result::{Error, ErrorType, RtResult};
+use ::modules::io;
+use ::modules::traceback::TracebackFrame;
+use ::resources::strings;
+use ::runtime::{Interpreter, Runtime};
+use ::runtime::traits::{DefaultDictProvider, DefaultTupleProvider, ModuleImporter};
+use ::system::primitives as rs;
+
+
+const HELP: &'static str = "\
+b(reak) [file:]line   stop at a line, without an argument list the breakpoints
+cl(ear) [file:]line   remove a breakpoint, without an argument remove them all
+s(tep)                run to the next line, going into calls
+n(ext)                run to the next line of this frame or the one it returns to
+c(ontinue)            run to the next breakpoint
+w(here)               show the stack of frames, '>' marks the selected one
+u(p) / d(own)         select the caller / callee of the selected frame
+l(ist)                show the source around the line of the selected frame
+p expr                show the value of an expression
+q(uit)                stop the program
+";
+
+/// Lines shown by `list` before and after the line of the frame
+const LIST_CONTEXT: usize = 5;
+
+
+/// Where the debugger stops next besides its breakpoints
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+enum Stepping {
+    Continue,
+    Step,
+    /// Stop at a line of a frame at most this deep in the call stack
+    Next(usize),
+}
+
+
+impl Default for Stepping {
+    fn default() -> Self {
+        Stepping::Continue
+    }
+}
+
+
+/// A line of a file to stop at
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+pub struct Breakpoint {
+    filename: String,
+    line: usize,
+}
+
+
+impl Breakpoint {
+    pub fn new(filename: &str, line: usize) -> Self {
+        Breakpoint {
+            filename: String::from(filename),
+            line: line,
+        }
+    }
+
+    /// Parse `[file:]line`, the breakpoint is in `filename` when there is no file
+    pub fn parse(spec: &str, filename: &str) -> Result<Self, String> {
+        let (file, line) = match spec.rfind(':') {
+            Some(idx) => (&spec[..idx], &spec[idx + 1..]),
+            None => (filename, spec)
+        };
+
+        match line.trim().parse::<usize>() {
+            Ok(line) if line > 0 && !file.is_empty() => Ok(Breakpoint::new(file, line)),
+            _ => Err(format!("Bad breakpoint '{}', expected [file:]line", spec))
+        }
+    }
+
+    /// True if the breakpoint is at `line` of `filename`, a relative file name
+    /// matches the end of the path.
+    pub fn matches(&self, filename: &str, line: usize) -> bool {
+        self.line == line && (filename == self.filename ||
+                              filename.ends_with(&format!("/{}", self.filename)))
+    }
+}
+
+
+impl fmt::Display for Breakpoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.filename, self.line)
+    }
+}
+
+
+/// State of the debugger kept between the times it stops
+#[derive(Debug, Clone, Default)]
+pub struct Debugger {
+    breakpoints: Vec<Breakpoint>,
+    stepping: Stepping,
+    // Index into the frames of the interpreter's `frame_view`
+    selected: usize,
+    last_command: String,
+}
+
+
+impl Debugger {
+    /// A debugger that only stops at breakpoints
+    pub fn new() -> Self {
+        Debugger::default()
+    }
+
+    /// A debugger that stops at the first line that is run
+    pub fn stepping() -> Self {
+        Debugger {
+            stepping: Stepping::Step,
+            ..Debugger::default()
+        }
+    }
+
+    /// Stop at the next line that is run
+    pub fn step(&mut self) {
+        self.stepping = Stepping::Step;
+    }
+
+    pub fn add_breakpoint(&mut self, breakpoint: Breakpoint) {
+        if !self.breakpoints.contains(&breakpoint) {
+            self.breakpoints.push(breakpoint);
+        }
+    }
+
+    pub fn breakpoints(&self) -> &[Breakpoint] {
+        &self.breakpoints
+    }
+
+    /// True if the debugger stops at `line` of `filename` run in a frame `depth`
+    /// frames deep in the call stack.
+    pub fn should_stop(&self, filename: &str, line: usize, depth: usize) -> bool {
+        let stepped = match self.stepping {
+            Stepping::Continue => false,
+            Stepping::Step => true,
+            Stepping::Next(limit) => depth <= limit,
+        };
+
+        stepped || self.breakpoints.iter().any(|breakpoint| breakpoint.matches(filename, line))
+    }
+
+    /// Show where the program stopped and run commands until one of them lets it go
+    /// on. Errors raised by the code of a command are shown and do not stop the
+    /// debugger, only `quit` raises the `SystemExit` that ends the program.
+    pub fn interact(&mut self, rt: &Runtime, interpreter: &mut Interpreter) -> RtResult<rs::None> {
+        let frames = interpreter.frame_view();
+        if frames.is_empty() {
+            return Ok(rs::None())
+        }
+
+        self.selected = frames.len() - 1;
+        write(rt, &location(rt, &frames[self.selected]))?;
+
+        loop {
+            write(rt, strings::DEBUG_PROMPT)?;
+            let input = match readline(rt)? {
+                Some(input) => input,
+                None => {
+                    write(rt, "\n")?;
+                    self.breakpoints.clear();
+                    self.stepping = Stepping::Continue;
+                    return Ok(rs::None())
+                }
+            };
+
+            let command = match input.trim() {
+                "" => self.last_command.clone(),
+                command => String::from(command)
+            };
+            self.last_command = command.clone();
+
+            let (name, arg) = match command.find(char::is_whitespace) {
+                Some(idx) => (&command[..idx], command[idx..].trim()),
+                None => (command.as_str(), "")
+            };
+
+            let frame = &frames[self.selected];
+            match name {
+                "s" | "step" => {
+                    self.stepping = Stepping::Step;
+                    return Ok(rs::None())
+                },
+                "n" | "next" => {
+                    self.stepping = Stepping::Next(interpreter.depth());
+                    return Ok(rs::None())
+                },
+                "c" | "cont" | "continue" => {
+                    self.stepping = Stepping::Continue;
+                    return Ok(rs::None())
+                },
+                "q" | "quit" => return Err(Error::system_exit(0, "")),
+                "b" | "break" => self.command_break(rt, frame, arg),
+                "cl" | "clear" => self.command_clear(rt, frame, arg),
+                "w" | "where" | "bt" => write(rt, &self.stack(rt, &frames)),
+                "u" | "up" => match self.selected {
+                    0 => write(rt, "*** Oldest frame\n"),
+                    _ => {
+                        self.selected -= 1;
+                        write(rt, &location(rt, &frames[self.selected]))
+                    }
+                },
+                "d" | "down" => match self.selected + 1 < frames.len() {
+                    true => {
+                        self.selected += 1;
+                        write(rt, &location(rt, &frames[self.selected]))
+                    },
+                    false => write(rt, "*** Newest frame\n")
+                },
+                "l" | "list" => write(rt, &listing(rt, frame)),
+                "h" | "help" => write(rt, HELP),
+                "p" => run(rt, interpreter, arg),
+                "" => Ok(rs::None()),
+                _ => run(rt, interpreter, &command),
+            }?;
+        }
+    }
+
+    fn command_break(&mut self, rt: &Runtime, frame: &TracebackFrame, arg: &str) -> RtResult<rs::None> {
+        if arg.is_empty() {
+            let mut text = String::new();
+            for (idx, breakpoint) in self.breakpoints.iter().enumerate() {
+                text.push_str(&format!("{:<3} {}\n", idx + 1, breakpoint));
+            }
+            return write(rt, &text)
+        }
+
+        match Breakpoint::parse(arg, frame.filename()) {
+            Ok(breakpoint) => {
+                self.add_breakpoint(breakpoint.clone());
+                write(rt, &format!("Breakpoint at {}\n", breakpoint))
+            },
+            Err(message) => write(rt, &format!("*** {}\n", message))
+        }
+    }
+
+    fn command_clear(&mut self, rt: &Runtime, frame: &TracebackFrame, arg: &str) -> RtResult<rs::None> {
+        if arg.is_empty() {
+            self.breakpoints.clear();
+            return write(rt, "Deleted all breakpoints\n")
+        }
+
+        let breakpoint = match Breakpoint::parse(arg, frame.filename()) {
+            Ok(breakpoint) => breakpoint,
+            Err(message) => return write(rt, &format!("*** {}\n", message))
+        };
+
+        match self.breakpoints.iter().position(|existing| *existing == breakpoint) {
+            Some(idx) => {
+                self.breakpoints.remove(idx);
+                write(rt, &format!("Deleted breakpoint at {}\n", breakpoint))
+            },
+            None => write(rt, &format!("*** There is no breakpoint at {}\n", breakpoint))
+        }
+    }
+
+    /// The frames outermost first with the selected one marked
+    fn stack(&self, rt: &Runtime, frames: &[TracebackFrame]) -> String {
+        frames.iter().enumerate()
+            .map(|(idx, frame)| {
+                let text = location(rt, frame);
+                match idx == self.selected {
+                    true => text,
+                    false => format!(" {}", &text[1..])
+                }
+            })
+            .collect::<Vec<_>>()
+            .concat()
+    }
+}
+
+
+/// `> file(line)name()` and the source of the line like `pdb` shows it
+fn location(rt: &Runtime, frame: &TracebackFrame) -> String {
+    let mut text = format!("> {}({}){}()\n", frame.filename(), frame.line(), frame.name());
+
+    if let Some(source) = rt.source_line(frame.filename(), frame.line()) {
+        text.push_str(&format!("-> {}\n", source.trim()));
+    }
+    text
+}
+
+
+/// The numbered source lines around the line of `frame`
+fn listing(rt: &Runtime, frame: &TracebackFrame) -> String {
+    let first = cmp::max(frame.line().saturating_sub(LIST_CONTEXT), 1);
+    let mut text = String::new();
+
+    for line in first..(frame.line() + LIST_CONTEXT + 1) {
+        let source = match rt.source_line(frame.filename(), line) {
+            Some(source) => source,
+            None => break
+        };
+
+        let marker = if line == frame.line() { "->" } else { "" };
+        text.push_str(&format!("{:>4} {:<2} {}\n", line, marker, source));
+    }
+
+    match text.is_empty() {
+        true => format!("*** No source for {}\n", frame.filename()),
+        false => text
+    }
+}
+
+
+/// Run the `source` of a command, errors other than `SystemExit` are shown
+fn run(rt: &Runtime, interpreter: &mut Interpreter, source: &str) -> RtResult<rs::None> {
+    match interpreter.eval(rt, source) {
+        Ok(_) => Ok(rs::None()),
+        Err(err) => match err.0 {
+            ErrorType::SystemExit(_) => Err(err),
+            _ => write(rt, &format!("*** {}\n", err))
+        }
+    }
+}
+
+
+/// The next line of `sys.stdin` without its newline, `None` at the end of the input
+fn readline(rt: &Runtime) -> RtResult<Option<String>> {
+    let stdin = rt.import_module(strings::SYS_MODULE)?.op_getattr(&rt, &rt.intern("stdin"))?;
+    let readline = stdin.op_getattr(&rt, &rt.intern("readline"))?;
+    let line = readline.op_call(&rt, &rt.default_tuple(), &rt.default_tuple(), &rt.default_dict())?;
+
+    let line = line.native_str()?;
+    match line.is_empty() {
+        true => Ok(None),
+        false => Ok(Some(String::from(line.trim_right_matches('\n'))))
+    }
+}
+
+
+/// Write `text` to `sys.stdout` right away so prompts show up before the input
+fn write(rt: &Runtime, text: &str) -> RtResult<rs::None> {
+    let stdout = rt.import_module(strings::SYS_MODULE)?.op_getattr(&rt, &rt.intern("stdout"))?;
+    io::write(rt, &stdout, text)?;
+
+    // Objects standing in for stdout only need a `write` method
+    let _ = io::flush(rt, &stdout);
+    Ok(rs::None())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_breakpoint() {
+        assert_eq!(Breakpoint::parse("3", "script.py"), Ok(Breakpoint::new("script.py", 3)));
+        assert_eq!(Breakpoint::parse("lib/other.py:12", "script.py"), Ok(Breakpoint::new("lib/other.py", 12)));
+        assert!(Breakpoint::parse("x", "script.py").is_err());
+        assert!(Breakpoint::parse("0", "script.py").is_err());
+        assert!(Breakpoint::parse(":4", "script.py").is_err());
+    }
+
+    #[test]
+    fn breakpoint_matches_path_suffix() {
+        let breakpoint = Breakpoint::new("script.py", 3);
+        assert!(breakpoint.matches("script.py", 3));
+        assert!(breakpoint.matches("/tmp/project/script.py", 3));
+        assert!(!breakpoint.matches("/tmp/project/myscript.py", 3));
+        assert!(!breakpoint.matches("script.py", 4));
+    }
+
+    #[test]
+    fn should_stop() {
+        let mut debugger = Debugger::new();
+        assert!(!debugger.should_stop("script.py", 1, 2));
+
+        debugger.add_breakpoint(Breakpoint::new("script.py", 2));
+        debugger.add_breakpoint(Breakpoint::new("script.py", 2));
+        assert_eq!(debugger.breakpoints().len(), 1);
+        assert!(debugger.should_stop("script.py", 2, 5));
+        assert!(!debugger.should_stop("script.py", 3, 5));
+
+        debugger.step();
+        assert!(debugger.should_stop("script.py", 3, 5));
+
+        debugger.stepping = Stepping::Next(2);
+        assert!(debugger.should_stop("script.py", 3, 1));
+        assert!(debugger.should_stop("script.py", 3, 2));
+        assert!(!debugger.should_stop("script.py", 3, 3));
+
+        assert!(Debugger::stepping().should_stop("script.py", 1, 1));
+    }
+}
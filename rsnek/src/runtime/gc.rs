@@ -0,0 +1,503 @@
+//! Generational cycle collector for container objects.
+//!
+//! Reference counting frees everything except reference cycles, a list that contains
+//! itself or an object whose `__dict__` points back at the object will keep each other
+//! alive forever. The `Collector` follows CPython's design: containers are tracked
+//! when they are allocated and a collection finds the tracked objects that are only
+//! referenced by other tracked objects, then clears them to break the cycles.
+//!
+//! A collection of a generation works over the objects in that generation and all
+//! younger generations:
+//!
+//!  1. Start with each object's strong count (`gc_refs`)
+//!  2. Subtract every reference from another object in the set, what is left over are
+//!     references from outside of the set like the interpreter namespace or value stack
+//!  3. Objects with outside references and everything they refer to are reachable
+//!  4. Whatever is not reachable is garbage, clearing the garbage containers drops the
+//!     references that form the cycles and reference counting does the rest
+//!
+//! Survivors are moved to the next older generation.
+//!
+//! With the `rsnek_multithreaded` feature a collection on one thread would race with
+//! the threads that are mutating objects, the strong counts it reads and the containers
+//! it clears could change under it. Rather than a global lock that every mutation has
+//! to take, the collector does nothing while threads started by `threading.Thread`
+//! are running, `threading` reports them with `thread_started` and `thread_finished`.
+//! The cycles made in the meantime stay tracked and are collected once the program is
+//! back to one thread.
+//!
+use std::collections::HashMap;
+use std::mem;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use num::ToPrimitive;
+
+use ::api::{RtObject, WeakRtObject};
+use ::modules::builtins::Type;
+use ::system::primitives as rs;
+use ::system::sync::{Cell, RefCell};
+
+
+/// Number of generations, `0` is the youngest
+pub const GENERATIONS: usize = 3;
+
+/// Same defaults as CPython's `gc.get_threshold()`. Generation 0 is collected after that
+/// many containers are allocated, each older generation is collected after the younger
+/// generation has been collected that many times.
+pub const THRESHOLDS: [usize; GENERATIONS] = [700, 10, 10];
+
+
+/// Tracks the container objects of a `Runtime` and collects the unreachable cycles
+/// between them.
+pub struct Collector {
+    enabled: Cell<bool>,
+    /// Guard so a collection cannot start another collection
+    collecting: Cell<bool>,
+    generations: [RefCell<Vec<WeakRtObject>>; GENERATIONS],
+    counts: [Cell<usize>; GENERATIONS],
+    /// Number of threads running besides the one that created the runtime
+    threads: AtomicUsize,
+}
+
+
+impl Collector {
+    pub fn new() -> Self {
+        Collector {
+            enabled: Cell::new(true),
+            collecting: Cell::new(false),
+            generations: [RefCell::new(Vec::new()), RefCell::new(Vec::new()), RefCell::new(Vec::new())],
+            counts: [Cell::new(0), Cell::new(0), Cell::new(0)],
+            threads: AtomicUsize::new(0),
+        }
+    }
+
+    /// Start tracking `object` in the youngest generation if it is a container type
+    /// that can be part of a reference cycle.
+    pub fn track(&self, object: &RtObject) {
+        if !is_container(object) {
+            return;
+        }
+
+        self.generations[0].borrow_mut().push(object.downgrade());
+        self.counts[0].set(self.counts[0].get() + 1);
+    }
+
+    pub fn enable(&self) {
+        self.enabled.set(true);
+    }
+
+    pub fn disable(&self) {
+        self.enabled.set(false);
+    }
+
+    pub fn is_enabled(&self) -> rs::Boolean {
+        self.enabled.get()
+    }
+
+    /// Called before a thread that runs python code starts, collections are skipped
+    /// until it finishes.
+    pub fn thread_started(&self) {
+        self.threads.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Called once a thread started with `thread_started` has dropped the objects it used
+    pub fn thread_finished(&self) {
+        self.threads.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    /// The number of threads started with `thread_started` that have not finished
+    pub fn threads(&self) -> usize {
+        self.threads.load(Ordering::SeqCst)
+    }
+
+    /// The current collection counts, see `gc.get_count()`
+    pub fn get_count(&self) -> [usize; GENERATIONS] {
+        [self.counts[0].get(), self.counts[1].get(), self.counts[2].get()]
+    }
+
+    /// All of the live objects tracked by the collector
+    pub fn get_objects(&self) -> Vec<RtObject> {
+        self.generations.iter()
+            .flat_map(|generation| {
+                generation.borrow().iter()
+                    .filter_map(WeakRtObject::try_upgrade)
+                    .collect::<Vec<RtObject>>()
+            })
+            .collect()
+    }
+
+    /// Run an automatic collection of the oldest generation that is over its threshold.
+    /// Callers must be at a point where no container is mutably borrowed, which the
+    /// `Interpreter` ensures by only calling this between instructions.
+    pub fn collect_if_needed(&self) -> usize {
+        if !self.enabled.get() {
+            return 0;
+        }
+
+        match (0..GENERATIONS).rev().find(|&gen| self.counts[gen].get() > THRESHOLDS[gen]) {
+            Some(generation) => self.collect(generation),
+            None => 0
+        }
+    }
+
+    /// Collect `generation` and all younger generations and return the number
+    /// of unreachable objects that were found. Nothing is collected while other
+    /// threads are running.
+    pub fn collect(&self, generation: usize) -> usize {
+        let generation = generation.min(GENERATIONS - 1);
+        if self.collecting.get() || self.threads() > 0 {
+            return 0;
+        }
+
+        self.collecting.set(true);
+        let collected = self.collect_generations(generation);
+        self.collecting.set(false);
+
+        trace!("gc"; "action" => "collect", "generation" => generation, "collected" => collected);
+        collected
+    }
+
+    fn collect_generations(&self, generation: usize) -> usize {
+        let mut candidates: Vec<RtObject> = Vec::new();
+        for gen in 0..(generation + 1) {
+            let weakrefs = mem::replace(&mut *self.generations[gen].borrow_mut(), Vec::new());
+            candidates.extend(weakrefs.iter().filter_map(WeakRtObject::try_upgrade));
+        }
+
+        let index: HashMap<rs::ObjectId, usize> = candidates.iter()
+            .enumerate()
+            .map(|(idx, object)| (object.id(), idx))
+            .collect();
+
+        // Every candidate has one extra strong reference held by `candidates` itself
+        let mut gc_refs: Vec<isize> = candidates.iter()
+            .map(|object| object.strong_count().to_isize().unwrap_or(isize::max_value()) - 1)
+            .collect();
+
+        let mut referents: Vec<Vec<rs::ObjectId>> = Vec::with_capacity(candidates.len());
+        for object in candidates.iter() {
+            match referent_ids(object) {
+                Some(ids) => referents.push(ids),
+                // A container is borrowed so its references cannot be seen, it is not
+                // safe to decide what is garbage so try again next time.
+                None => {
+                    self.promote(&candidates, generation);
+                    return 0;
+                }
+            }
+        }
+
+        for ids in referents.iter() {
+            for id in ids.iter() {
+                if let Some(&idx) = index.get(id) {
+                    gc_refs[idx] -= 1;
+                }
+            }
+        }
+
+        let mut reachable: Vec<bool> = gc_refs.iter().map(|&refs| refs > 0).collect();
+        let mut pending: Vec<usize> = (0..candidates.len()).filter(|&idx| reachable[idx]).collect();
+
+        while let Some(idx) = pending.pop() {
+            for id in referents[idx].iter() {
+                if let Some(&other) = index.get(id) {
+                    if !reachable[other] {
+                        reachable[other] = true;
+                        pending.push(other);
+                    }
+                }
+            }
+        }
+
+        let (survivors, garbage): (Vec<(usize, &RtObject)>, Vec<(usize, &RtObject)>) = candidates.iter()
+            .enumerate()
+            .partition(|&(idx, _)| reachable[idx]);
+
+        let survivors: Vec<RtObject> = survivors.into_iter().map(|(_, object)| object.clone()).collect();
+        self.promote(&survivors, generation);
+
+        for &(_, object) in garbage.iter() {
+            clear(object);
+        }
+
+        // The garbage is freed when the last strong references held by `candidates`
+        // are dropped at the end of this function.
+        garbage.len()
+    }
+
+    /// Move `objects` into the generation older than `generation` and update the counts
+    fn promote(&self, objects: &[RtObject], generation: usize) {
+        let older = (generation + 1).min(GENERATIONS - 1);
+        self.generations[older].borrow_mut()
+            .extend(objects.iter().map(RtObject::downgrade));
+
+        for gen in 0..(generation + 1) {
+            self.counts[gen].set(0);
+        }
+
+        if generation + 1 < GENERATIONS {
+            self.counts[generation + 1].set(self.counts[generation + 1].get() + 1);
+        }
+    }
+}
+
+
+/// Types that hold references to other objects and may be part of a cycle
+fn is_container(object: &RtObject) -> rs::Boolean {
+    match object.as_ref() {
+        &Type::List(_)      |
+        &Type::Dict(_)      |
+        &Type::Tuple(_)     |
+        &Type::Set(_)       |
+        &Type::FrozenSet(_) |
+        &Type::Object(_)    |
+        &Type::Module(_)    |
+        &Type::Function(_)  |
+        &Type::Iter(_)      |
+        &Type::Frame(_)     => true,
+        _ => false
+    }
+}
+
+
+/// Ids of every object directly referenced by `object`, `None` if the object is
+/// currently borrowed mutably and cannot be inspected.
+fn referent_ids(object: &RtObject) -> Option<Vec<rs::ObjectId>> {
+    match object.as_ref() {
+        &Type::List(ref list) => {
+            list.value.0.try_borrow().ok()
+                .map(|elems| elems.iter().map(RtObject::id).collect())
+        },
+        &Type::Dict(ref dict) => {
+            dict.value.0.try_borrow().ok()
+                .map(|items| items.iter()
+                    .flat_map(|(key, value)| vec![key.value().id(), value.id()])
+                    .collect())
+        },
+        &Type::Tuple(ref tuple) => {
+            Some(tuple.value.0.iter().map(RtObject::id).collect())
+        },
+        &Type::Set(ref set) => {
+            Some(set.value.0.iter().map(|element| element.1.id()).collect())
+        },
+        &Type::FrozenSet(ref set) => {
+            Some(set.value.0.iter().map(|element| element.1.id()).collect())
+        },
+        &Type::Object(ref object) |
+        &Type::Module(ref object) => {
+            let inner = &object.value.0;
+            Some(vec![inner.class.id(), inner.dict.id(), inner.bases.id()])
+        },
+        // The objects captured by a `Wrapper` closure cannot be seen, they count as
+        // references from outside and keep the objects alive.
+        &Type::Function(ref func) => match func.value.0.callable {
+            rs::FuncType::MethodWrapper(ref receiver, _) => Some(vec![receiver.id()]),
+            _ => Some(Vec::new())
+        },
+        &Type::Iter(ref iterator) => Some(iterator_referent_ids(&iterator.value.0)),
+        &Type::Frame(ref frame) => {
+            let inner = &frame.value.0;
+            Some(vec![inner.f_back.id(), inner.f_code.id(), inner.f_builtins.id()])
+        },
+        _ => Some(Vec::new())
+    }
+}
+
+
+fn iterator_referent_ids(iterator: &rs::Iterator) -> Vec<rs::ObjectId> {
+    match *iterator {
+        rs::Iterator::Sequence {ref source, ..}     |
+        rs::Iterator::Reversed {ref source, ..}     |
+        rs::Iterator::Enumerate {ref source, ..}    => vec![source.id()],
+        rs::Iterator::Zip {ref sources}             => sources.iter().map(RtObject::id).collect(),
+        rs::Iterator::Map {ref func, ref sources}   => {
+            Some(func).into_iter().chain(sources.iter()).map(RtObject::id).collect()
+        },
+        rs::Iterator::Filter {ref func, ref source} => vec![func.id(), source.id()],
+        rs::Iterator::Range {..}                    |
+        rs::Iterator::Empty                         => Vec::new(),
+    }
+}
+
+
+/// Drop the references held by a garbage container. The other containers are left
+/// alone, they are immutable once created so they can only refer to objects that
+/// already existed and any cycle they are part of has to pass through a list or a
+/// `__dict__`, which are cleared.
+fn clear(object: &RtObject) {
+    match object.as_ref() {
+        &Type::List(ref list) => {
+            list.take_elements();
+        },
+        &Type::Dict(ref dict) => {
+            let items = mem::replace(&mut *dict.value.0.borrow_mut(), rs::Dict::new());
+            dict.touch();
+            drop(items);
+        },
+        _ => {}
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use ::api::method::{Append, GetAttr, SetAttr, SetItem};
+    use ::runtime::Runtime;
+    use ::runtime::traits::{DefaultListProvider, DefaultDictProvider, IteratorProvider,
+                            ListProvider, ObjectProvider, SetProvider, StringProvider,
+                            TupleProvider};
+    use super::*;
+
+    fn setup() -> Runtime {
+        let rt = Runtime::new();
+        rt.gc().collect(GENERATIONS - 1);
+        rt
+    }
+
+    #[test]
+    fn self_referencing_list() {
+        let rt = setup();
+        let list = rt.default_list();
+        list.meth_append(&rt, &list).unwrap();
+
+        let weak = list.downgrade();
+        drop(list);
+        assert!(weak.try_upgrade().is_some(), "the cycle keeps the list alive");
+
+        assert_eq!(rt.gc().collect(0), 1);
+        assert!(weak.try_upgrade().is_none(), "the list was not freed");
+    }
+
+    #[test]
+    fn dict_and_list_cycle() {
+        let rt = setup();
+        let dict = rt.default_dict();
+        let list = rt.list(vec![dict.clone()]);
+        dict.op_setitem(&rt, &rt.str("list"), &list).unwrap();
+
+        let weak_dict = dict.downgrade();
+        let weak_list = list.downgrade();
+        drop(dict);
+        drop(list);
+
+        assert_eq!(rt.gc().collect(GENERATIONS - 1), 2);
+        assert!(weak_dict.try_upgrade().is_none());
+        assert!(weak_list.try_upgrade().is_none());
+    }
+
+    #[test]
+    fn bound_method_cycle() {
+        let rt = setup();
+        let list = rt.default_list();
+        let append = list.op_getattr(&rt, &rt.str("append")).unwrap();
+        list.meth_append(&rt, &append).unwrap();
+
+        let weak_list = list.downgrade();
+        let weak_append = append.downgrade();
+        drop(list);
+        drop(append);
+
+        assert_eq!(rt.gc().collect(GENERATIONS - 1), 2);
+        assert!(weak_list.try_upgrade().is_none());
+        assert!(weak_append.try_upgrade().is_none());
+    }
+
+    #[test]
+    fn iterator_cycle() {
+        let rt = setup();
+        let list = rt.default_list();
+        let iterator = rt.iter(rs::Iterator::new(&list).unwrap());
+        list.meth_append(&rt, &iterator).unwrap();
+
+        let weak = iterator.downgrade();
+        drop(list);
+        drop(iterator);
+
+        assert_eq!(rt.gc().collect(GENERATIONS - 1), 2);
+        assert!(weak.try_upgrade().is_none());
+    }
+
+    #[test]
+    fn set_and_object_cycle() {
+        let rt = setup();
+        let object = rt.object(rs::None());
+        let mut elements = rs::Set::new();
+        elements.insert(rs::SetElement(object.id() as rs::HashId, object.clone()));
+        let set = rt.set(elements);
+        object.op_setattr(&rt, &rt.str("set"), &set).unwrap();
+
+        let weak_object = object.downgrade();
+        let weak_set = set.downgrade();
+        drop(object);
+        drop(set);
+
+        // The object's `__dict__` and bases are garbage along with the cycle
+        assert!(rt.gc().collect(GENERATIONS - 1) >= 2);
+        assert!(weak_object.try_upgrade().is_none());
+        assert!(weak_set.try_upgrade().is_none());
+    }
+
+    #[test]
+    fn reachable_cycle_survives() {
+        let rt = setup();
+        let list = rt.default_list();
+        list.meth_append(&rt, &list).unwrap();
+        let holder = rt.tuple(vec![list.clone()]);
+        drop(list);
+
+        assert_eq!(rt.gc().collect(0), 0);
+        assert_eq!(holder.strong_count(), rs::Integer::from(1));
+
+        // Everything tracked survived generation 0 and was moved to generation 1
+        assert!(rt.gc().get_objects().contains(&holder));
+        assert_eq!(rt.gc().get_count()[0], 0);
+        assert_eq!(rt.gc().get_count()[1], 1);
+    }
+
+    #[test]
+    fn automatic_collection() {
+        let rt = setup();
+        let list = rt.default_list();
+        list.meth_append(&rt, &list).unwrap();
+        let weak = list.downgrade();
+        drop(list);
+
+        rt.gc().disable();
+        for _ in 0..(THRESHOLDS[0] + 1) {
+            rt.default_list();
+        }
+        assert_eq!(rt.gc().collect_if_needed(), 0);
+        assert!(weak.try_upgrade().is_some());
+
+        rt.gc().enable();
+        assert_eq!(rt.gc().collect_if_needed(), 1);
+        assert!(weak.try_upgrade().is_none());
+    }
+
+    #[test]
+    fn untracked_types() {
+        let rt = setup();
+        let before = rt.gc().get_count()[0];
+        rt.str("not a container");
+        assert_eq!(rt.gc().get_count()[0], before);
+
+        rt.default_list();
+        assert_eq!(rt.gc().get_count()[0], before + 1);
+    }
+    #[test]
+    fn skipped_while_threads_run() {
+        let rt = setup();
+        let list = rt.default_list();
+        list.meth_append(&rt, &list).unwrap();
+        let weak = list.downgrade();
+        drop(list);
+
+        rt.gc().thread_started();
+        assert_eq!(rt.gc().collect(GENERATIONS - 1), 0);
+        assert!(weak.try_upgrade().is_some());
+
+        rt.gc().thread_finished();
+        assert_eq!(rt.gc().collect(GENERATIONS - 1), 1);
+        assert!(weak.try_upgrade().is_none());
+    }
+}
@@ -0,0 +1,351 @@
+//! Per instruction caches for attribute and name lookups
+//!
+//! Every instruction of a code object has an `InlineCache` slot. The slots belong to the
+//! `Code` and are shared by its clones, so the frames of every call of a function fill
+//! and hit the same caches. The first time a `LoadAttr` or `LoadName` runs it does the
+//! full lookup and remembers the result along with a guard. The next time the guard
+//! still holds, the result is used without looking it up again.
+//!
+//!  - `LoadAttr` of a method of a builtin type, like `list.append`, caches the method
+//!    unbound and is guarded by the type of the receiver. A hit binds it to the receiver
+//!    without going through the attribute `match` of the type, for any object of that type.
+//!  - Other attributes are guarded by the identity of the receiver, which also pins its
+//!    type, and the version of its `__dict__` for objects and modules.
+//!  - `LoadName` is guarded by the version of the interpreter namespace. The versions of
+//!    all interpreters come from one counter, so interpreters that run the same code
+//!    never take each other's entries for their own.
+//!
+//! Unbound methods are held strongly, they belong to the type and keep no object alive.
+//! Everything else is held weakly so that the caches never keep an object alive, a
+//! result that has been dropped is a cache miss.
+//!
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use ::api::{RtObject, WeakRtObject};
+use ::modules::builtins::Type;
+use ::runtime::Runtime;
+use ::runtime::traits::FunctionProvider;
+use ::system::primitives as rs;
+use ::system::primitives::{FuncType, MethodFn, Signature};
+
+
+/// What must be unchanged for a cached attribute to still be valid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Guard {
+    /// The attributes of the receiver are fixed by its type
+    Identity,
+    /// The attributes are read from a `__dict__` with this version
+    DictVersion(usize),
+}
+
+
+/// A method of a builtin type with everything needed to bind it to an object
+pub struct BuiltinMethod {
+    name: String,
+    signature: Signature,
+    module: String,
+    func: Arc<MethodFn>,
+}
+
+
+#[derive(Clone)]
+pub enum InlineCache {
+    Empty,
+    Attr {
+        receiver: WeakRtObject,
+        guard: Guard,
+        value: WeakRtObject,
+    },
+    /// A method of the builtin type named `type_name`
+    Method {
+        type_name: &'static str,
+        method: Arc<BuiltinMethod>,
+    },
+    Name {
+        version: usize,
+        value: WeakRtObject,
+    },
+}
+
+
+impl Default for InlineCache {
+    fn default() -> Self {
+        InlineCache::Empty
+    }
+}
+
+
+impl fmt::Debug for InlineCache {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            InlineCache::Empty => write!(f, "Empty"),
+            InlineCache::Attr { ref guard, .. } => write!(f, "Attr({:?})", guard),
+            InlineCache::Method { type_name, ref method } => write!(f, "Method({}, {})", type_name, method.name),
+            InlineCache::Name { version, .. } => write!(f, "Name({})", version),
+        }
+    }
+}
+
+
+impl InlineCache {
+    /// Cache `value` as the attribute of `receiver`, `Empty` if attributes of
+    /// the receiver's type cannot be cached.
+    pub fn attr(receiver: &RtObject, value: &RtObject) -> Self {
+        if let Some(method) = builtin_method(receiver, value) {
+            return method
+        }
+
+        match guard(receiver) {
+            Some(guard) => InlineCache::Attr {
+                receiver: receiver.downgrade(),
+                guard: guard,
+                value: value.downgrade(),
+            },
+            None => InlineCache::Empty
+        }
+    }
+
+    /// Cache `value` as the result of a name lookup in the namespace at `version`
+    pub fn name(version: usize, value: &RtObject) -> Self {
+        InlineCache::Name {
+            version: version,
+            value: value.downgrade(),
+        }
+    }
+
+    /// The cached attribute if this caches an attribute of `receiver` and it is still valid
+    pub fn load_attr(&self, rt: &Runtime, receiver: &RtObject) -> Option<RtObject> {
+        match *self {
+            InlineCache::Attr { receiver: ref cached, guard: cached_guard, ref value } => {
+                match cached.try_upgrade() {
+                    Some(ref cached) if cached.id() == receiver.id() => {},
+                    _ => return None
+                };
+
+                match guard(receiver) {
+                    Some(current) if current == cached_guard => value.try_upgrade(),
+                    _ => None
+                }
+            },
+            InlineCache::Method { type_name, ref method } => {
+                if builtin_type(receiver) != Some(type_name) {
+                    return None
+                }
+
+                Some(rt.function(rs::Func {
+                    name: method.name.clone(),
+                    signature: method.signature.clone(),
+                    module: method.module.clone(),
+                    callable: FuncType::MethodWrapper(receiver.clone(), method.func.clone())
+                }))
+            },
+            _ => None
+        }
+    }
+
+    /// The cached name if the namespace is still at `version`
+    pub fn load_name(&self, version: usize) -> Option<RtObject> {
+        match *self {
+            InlineCache::Name { version: cached, ref value } if cached == version => value.try_upgrade(),
+            _ => None
+        }
+    }
+}
+
+
+/// The inline caches of a code object indexed by instruction offset. Clones share
+/// the slots and the count of hits.
+#[derive(Clone, Default)]
+pub struct InlineCaches(Arc<Slots>);
+
+
+#[derive(Default)]
+struct Slots {
+    caches: Mutex<Vec<InlineCache>>,
+    hits: AtomicUsize,
+}
+
+
+impl fmt::Debug for InlineCaches {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "InlineCaches(hits: {})", self.hits())
+    }
+}
+
+
+impl InlineCaches {
+    /// The attribute of `receiver` cached for the instruction at `offset`
+    pub fn load_attr(&self, offset: usize, rt: &Runtime, receiver: &RtObject) -> Option<RtObject> {
+        let cached = self.lock().get(offset).and_then(|cache| cache.load_attr(rt, receiver));
+        self.count(cached)
+    }
+
+    /// The name cached for the instruction at `offset` if the namespace is at `version`
+    pub fn load_name(&self, offset: usize, version: usize) -> Option<RtObject> {
+        let cached = self.lock().get(offset).and_then(|cache| cache.load_name(version));
+        self.count(cached)
+    }
+
+    /// Replace the cache of the instruction at `offset`
+    pub fn set(&self, offset: usize, cache: InlineCache) {
+        let mut caches = self.lock();
+        if caches.len() <= offset {
+            caches.resize(offset + 1, InlineCache::Empty);
+        }
+        caches[offset] = cache;
+    }
+
+    /// Number of lookups that were answered by the caches
+    pub fn hits(&self) -> usize {
+        self.0.hits.load(Ordering::Relaxed)
+    }
+
+    fn count(&self, cached: Option<RtObject>) -> Option<RtObject> {
+        if cached.is_some() {
+            self.0.hits.fetch_add(1, Ordering::Relaxed);
+        }
+        cached
+    }
+
+    /// Slots only hold caches, a panic while they were locked cannot leave them
+    /// inconsistent so poisoning is ignored.
+    fn lock(&self) -> MutexGuard<Vec<InlineCache>> {
+        self.0.caches.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+
+/// `value` as an unbound method if it is a method of a builtin type bound to `receiver`
+fn builtin_method(receiver: &RtObject, value: &RtObject) -> Option<InlineCache> {
+    let type_name = match builtin_type(receiver) {
+        Some(type_name) => type_name,
+        None => return None
+    };
+
+    let func = match value.as_ref() {
+        &Type::Function(ref func) => &func.value.0,
+        _ => return None
+    };
+
+    match func.callable {
+        FuncType::MethodWrapper(ref bound, ref method) if bound.id() == receiver.id() => {
+            Some(InlineCache::Method {
+                type_name: type_name,
+                method: Arc::new(BuiltinMethod {
+                    name: func.name.clone(),
+                    signature: func.signature.clone(),
+                    module: func.module.clone(),
+                    func: method.clone(),
+                })
+            })
+        },
+        _ => None
+    }
+}
+
+
+/// The name of the builtin type of `object` if its attributes are fixed by the type
+fn builtin_type(object: &RtObject) -> Option<&'static str> {
+    match object.as_ref() {
+        &Type::None(_)          => Some("NoneType"),
+        &Type::Bool(_)          => Some("bool"),
+        &Type::Int(_)           => Some("int"),
+        &Type::Float(_)         => Some("float"),
+        &Type::Complex(_)       => Some("complex"),
+        &Type::Str(_)           => Some("str"),
+        &Type::Bytes(_)         => Some("bytes"),
+        &Type::ByteArray(_)     => Some("bytearray"),
+        &Type::Dict(_)          => Some("dict"),
+        &Type::Tuple(_)         => Some("tuple"),
+        &Type::List(_)          => Some("list"),
+        &Type::Set(_)           => Some("set"),
+        &Type::FrozenSet(_)     => Some("frozenset"),
+        &Type::Range(_)         => Some("range"),
+        &Type::Slice(_)         => Some("slice"),
+        _ => None
+    }
+}
+
+
+/// The guard for the attributes of `object`, `None` for types whose attributes can
+/// change without a dict version to tell.
+fn guard(object: &RtObject) -> Option<Guard> {
+    match object.as_ref() {
+        &Type::Object(ref object) |
+        &Type::Module(ref object) => match object.value.0.dict.as_ref() {
+            &Type::Dict(ref dict) => Some(Guard::DictVersion(dict.version())),
+            _ => None
+        },
+        _ => builtin_type(object).map(|_| Guard::Identity)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use ::api::method::{Call, GetAttr, SetAttr};
+    use ::runtime::traits::{DefaultDictProvider, DefaultListProvider, DefaultTupleProvider,
+                            IntegerProvider, ListProvider, ModuleProvider, StringProvider,
+                            TupleProvider};
+    use super::*;
+
+    #[test]
+    fn builtin_method_per_type() {
+        let rt = Runtime::new();
+        let list = rt.default_list();
+        let other = rt.default_list();
+
+        let append = list.op_getattr(&rt, &rt.str("append")).unwrap();
+        let cache = InlineCache::attr(&list, &append);
+        drop(append);
+
+        let bound = cache.load_attr(&rt, &other).unwrap();
+        bound.op_call(&rt, &rt.tuple(vec![rt.int(1)]), &rt.default_tuple(), &rt.default_dict()).unwrap();
+        assert_eq!(other, rt.list(vec![rt.int(1)]));
+        assert_eq!(list, rt.default_list());
+
+        assert!(cache.load_attr(&rt, &rt.str("append")).is_none());
+    }
+
+    #[test]
+    fn attr_dict_version() {
+        let rt = Runtime::new();
+        let module = rt.module(rs::None());
+        let name = rt.str("x");
+        module.op_setattr(&rt, &name, &rt.int(1)).unwrap();
+
+        let value = module.op_getattr(&rt, &name).unwrap();
+        let cache = InlineCache::attr(&module, &value);
+        assert_eq!(cache.load_attr(&rt, &module).unwrap().id(), value.id());
+
+        module.op_setattr(&rt, &rt.str("y"), &rt.int(2)).unwrap();
+        assert!(cache.load_attr(&rt, &module).is_none());
+    }
+
+    #[test]
+    fn name_version() {
+        let rt = Runtime::new();
+        let value = rt.int(1000);
+        let cache = InlineCache::name(1, &value);
+
+        assert_eq!(cache.load_name(1).unwrap().id(), value.id());
+        assert!(cache.load_name(2).is_none());
+        assert!(InlineCache::Empty.load_name(1).is_none());
+    }
+
+    #[test]
+    fn slots_shared_by_clones() {
+        let rt = Runtime::new();
+        let value = rt.int(1000);
+        let caches = InlineCaches::default();
+        let copy = caches.clone();
+
+        caches.set(3, InlineCache::name(7, &value));
+        assert_eq!(copy.load_name(3, 7).unwrap().id(), value.id());
+        assert!(copy.load_name(3, 8).is_none());
+        assert!(copy.load_name(10, 7).is_none());
+        assert_eq!(caches.hits(), 1);
+    }
+}
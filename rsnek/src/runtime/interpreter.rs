@@ -1,18 +1,18 @@
 //! Where the magic happens...
-use std::borrow::Borrow;
-use std::cell::{Ref, Cell, RefCell};
+use std::cell;
 use std::collections::HashMap;
 use std::collections::vec_deque::VecDeque;
 use std::convert::From;
 use std::fs::File;
-use std::io::{self, Read, Write};
+use std::io::{Read, Write};
 use std::marker::Sync;
 use std::ops::{Deref};
+use std::rc::{Rc, Weak};
+use std::sync::PoisonError;
+use std::sync::atomic::{AtomicUsize, Ordering, ATOMIC_USIZE_INIT};
 
 use fringe::generator::Yielder;
 use fringe::{OsStack, Generator};
-use itertools::Itertools;
-use num::Zero;
 use rustyline::CompletionType;
 use rustyline::Config as RLConfig;
 use rustyline::error::ReadlineError;
@@ -20,9 +20,11 @@ use rustyline;
 
 use python_ast::fmt;
 
-use ::modules::builtins::{logical_and, logical_or};
+use ::modules::builtins::{attribute_names, logical_and, logical_or, repr};
+use ::modules::io;
+use ::modules::sys;
 use ::compiler::Compiler;
-use ::api::result::Error;
+use ::api::result::{Error, ErrorType};
 use ::api::RtObject;
 use ::api::method::{
     Add,
@@ -55,6 +57,8 @@ use ::api::method::{
     NegateValue,
     GetAttr,
     SetItem,
+    Iter,
+    Next,
 };
 use ::resources::strings;
 use ::api::result::ObjectResult;
@@ -62,6 +66,7 @@ use ::runtime::{OpCode, Runtime};
 use ::runtime::traits::{
     NoneProvider,
     StringProvider,
+    BytesProvider,
     CodeProvider,
     IntegerProvider,
     FloatProvider,
@@ -71,18 +76,28 @@ use ::runtime::traits::{
     BooleanProvider,
     FrameProvider,
     FunctionProvider,
-    DefaultDictProvider
+    DefaultDictProvider,
+    ModuleImporter
 };
 use ::system::primitives::{Native, Instr, FuncType};
 use ::system::primitives as rs;
 use ::system::primitives::SignatureBuilder;
+use ::system::sync::{Ref, Cell, RefCell};
 use ::modules::builtins::Type;
+use ::modules::traceback::{Traceback, TracebackFrame};
+use ::objects::string::all_name_chars;
 use ::runtime::config::{Config, Mode, Logging};
+use ::runtime::debugger::Debugger;
+use ::runtime::profiler;
+use ::runtime::trace::Event;
+use ::runtime::inline_cache::{InlineCache, InlineCaches};
+use ::runtime::scheduler;
+use ::system::stack;
 use ::system::{
     ThreadModel, Pthread, GreenThread, Thread,
-    ExitCode, RECURSION_LIMIT, SharedMainFnRef,
+    ExitCode, SharedMainFnRef,
     MainFn, MainFnRef};
-use ::runtime::main::{create_python_main, python_main_interactive};
+use ::runtime::main::{create_dump_main, create_python_main, python_main_interactive};
 
 
 /// Macro to expand the optional of the `self.frames.back()` of
@@ -111,12 +126,39 @@ macro_rules! with_current_frame (
 );
 
 
+/// Names bound by an interpreter
+// TODO: {T100} Change namespace to be PyDict or PyModule or PyObject or something
+pub type Namespace = HashMap<rs::String, RtObject>;
+
+
+thread_local!(
+    /// Namespace of the interpreter that made the latest call to a native function
+    /// on this thread, see `Interpreter::caller_namespace`
+    static CALLER: cell::RefCell<Weak<cell::RefCell<Namespace>>> = cell::RefCell::new(Weak::new())
+);
+
+
+/// Source of the namespace versions. Code objects and their inline caches are shared
+/// by every interpreter that runs them so versions have to be unique across all of them.
+static NS_VERSION: AtomicUsize = ATOMIC_USIZE_INIT;
+
+
+/// A namespace version that no interpreter has used before
+fn next_ns_version() -> usize {
+    NS_VERSION.fetch_add(1, Ordering::Relaxed) + 1
+}
+
+
 pub struct Interpreter {
     rt: Runtime,
-    // TODO: {T100} Change namespace to be PyDict or PyModule or PyObject or something
-    ns: HashMap<rs::String, RtObject>,
+    ns: Rc<cell::RefCell<Namespace>>,
+    // Changed every time `ns` is, guards the `LoadName` inline caches
+    ns_version: usize,
     // (frame, stack)
     frames: VecDeque<InterpreterFrame>,
+    // Set while a trace hook or the debugger runs so their own code is not traced
+    tracing: bool,
+    debugger: Option<Debugger>,
 }
 
 
@@ -127,61 +169,137 @@ impl Interpreter {
         // represent the __main__ entry point.
         let main_code = rs::Code {
             co_name: String::from("__main__"),
+            co_filename: String::new(),
             co_names: Vec::new(),
             co_varnames: Vec::new(),
             co_code: Vec::new(),
-            co_consts: Vec::new()
+            co_consts: Vec::new(),
+            co_lnotab: Vec::new(),
+            co_caches: InlineCaches::default(),
         };
 
         let main_frame = rs::Frame {
             f_back: rt.none(),
             f_code: rt.code(main_code),
             f_builtins: rt.none(),
-            f_lasti: rs::Integer::zero(),
-            blocks: VecDeque::new()
+            f_lasti: Cell::new(0),
+            f_lineno: Cell::new(0),
         };
 
         let mut frames = VecDeque::new();
         frames.push_back(InterpreterFrame::new(rt.frame(main_frame)));
 
-        let mut istate = Interpreter {
-            rt: rt.clone(),
-            ns: HashMap::new(),
-            frames: frames,
-        };
-
         // TODO: {T100} use scope resolution in the future
         // Manually load the builtin print function into the interpreter namespace
         // since rsnek does not have a concept of modules at this time.
-        istate.ns.insert(String::from("print"), rt.get_builtin("print"));
-        istate.ns.insert(String::from("len"), rt.get_builtin("len"));
-        istate.ns.insert(String::from("type"), rt.get_builtin("type"));
-        istate.ns.insert(String::from("str"), rt.get_builtin("str"));
-        istate.ns.insert(String::from("int"), rt.get_builtin("int"));
-        istate.ns.insert(String::from("any"), rt.get_builtin("any"));
-        istate.ns.insert(String::from("all"), rt.get_builtin("all"));
-        istate.ns.insert(String::from("list"), rt.get_builtin("list"));
-        istate.ns.insert(String::from("globals"), rt.get_builtin("globals"));
-        istate.ns.insert(String::from("tuple"), rt.get_builtin("tuple"));
+        let mut ns = Namespace::new();
+        ns.insert(String::from("print"), rt.get_builtin("print"));
+        ns.insert(String::from("len"), rt.get_builtin("len"));
+        ns.insert(String::from("type"), rt.get_builtin("type"));
+        ns.insert(String::from("str"), rt.get_builtin("str"));
+        ns.insert(String::from("int"), rt.get_builtin("int"));
+        ns.insert(String::from("any"), rt.get_builtin("any"));
+        ns.insert(String::from("all"), rt.get_builtin("all"));
+        ns.insert(String::from("list"), rt.get_builtin("list"));
+        ns.insert(String::from("globals"), rt.get_builtin("globals"));
+        ns.insert(String::from("tuple"), rt.get_builtin("tuple"));
+        ns.insert(String::from("bytes"), rt.get_builtin("bytes"));
+        ns.insert(String::from("bytearray"), rt.get_builtin("bytearray"));
+        ns.insert(String::from("slice"), rt.get_builtin("slice"));
+        ns.insert(String::from("range"), rt.get_builtin("range"));
+        ns.insert(String::from("enumerate"), rt.get_builtin("enumerate"));
+        ns.insert(String::from("zip"), rt.get_builtin("zip"));
+        ns.insert(String::from("map"), rt.get_builtin("map"));
+        ns.insert(String::from("filter"), rt.get_builtin("filter"));
+        ns.insert(String::from("reversed"), rt.get_builtin("reversed"));
+        ns.insert(String::from("sorted"), rt.get_builtin("sorted"));
+        ns.insert(String::from("min"), rt.get_builtin("min"));
+        ns.insert(String::from("max"), rt.get_builtin("max"));
+        ns.insert(String::from("sum"), rt.get_builtin("sum"));
+        ns.insert(String::from("isinstance"), rt.get_builtin("isinstance"));
+        ns.insert(String::from("issubclass"), rt.get_builtin("issubclass"));
+        ns.insert(String::from("getattr"), rt.get_builtin("getattr"));
+        ns.insert(String::from("setattr"), rt.get_builtin("setattr"));
+        ns.insert(String::from("hasattr"), rt.get_builtin("hasattr"));
+        ns.insert(String::from("delattr"), rt.get_builtin("delattr"));
+        ns.insert(String::from("id"), rt.get_builtin("id"));
+        ns.insert(String::from("hash"), rt.get_builtin("hash"));
+        ns.insert(String::from("repr"), rt.get_builtin("repr"));
+        ns.insert(String::from("callable"), rt.get_builtin("callable"));
+        ns.insert(String::from("dir"), rt.get_builtin("dir"));
+        ns.insert(String::from("vars"), rt.get_builtin("vars"));
+        ns.insert(String::from("float"), rt.get_builtin("float"));
+        ns.insert(String::from("abs"), rt.get_builtin("abs"));
+        ns.insert(String::from("round"), rt.get_builtin("round"));
+        ns.insert(String::from("divmod"), rt.get_builtin("divmod"));
+        ns.insert(String::from("breakpoint"), rt.get_builtin("breakpoint"));
+        ns.insert(String::from(strings::CPROFILE_MODULE), rt.import_module(strings::CPROFILE_MODULE).unwrap());
+        ns.insert(String::from(strings::DIS_MODULE), rt.import_module(strings::DIS_MODULE).unwrap());
+        ns.insert(String::from(strings::GC_MODULE), rt.import_module(strings::GC_MODULE).unwrap());
+        ns.insert(String::from(strings::GREENLET_MODULE), rt.import_module(strings::GREENLET_MODULE).unwrap());
+        ns.insert(String::from(strings::IO_MODULE), rt.import_module(strings::IO_MODULE).unwrap());
+        ns.insert(String::from(strings::PROFILE_MODULE), rt.import_module(strings::PROFILE_MODULE).unwrap());
+        ns.insert(String::from(strings::SYS_MODULE), rt.import_module(strings::SYS_MODULE).unwrap());
+        ns.insert(String::from(strings::THREADING_MODULE), rt.import_module(strings::THREADING_MODULE).unwrap());
+        ns.insert(String::from(strings::TRACEBACK_MODULE), rt.import_module(strings::TRACEBACK_MODULE).unwrap());
+
+        Interpreter {
+            rt: rt.clone(),
+            ns: Rc::new(cell::RefCell::new(ns)),
+            ns_version: next_ns_version(),
+            frames: frames,
+            tracing: false,
+            debugger: None,
+        }
+    }
+
+    /// An interpreter that starts out with the names in `ns` as well as the builtins
+    pub fn with_namespace(rt: &Runtime, ns: Namespace) -> Self {
+        let mut istate = Interpreter::new(rt);
+        istate.ns.borrow_mut().extend(ns);
+        istate.ns_version = next_ns_version();
         istate
     }
 
-    // TODO: {T100} Arguments plumbing via a sys module?
+    /// A copy of the namespace of the interpreter that last called a native function
+    /// on this thread, `None` if that interpreter is gone. Natives that start python
+    /// functions in another interpreter, like `greenlet.spawn`, hand it over so the
+    /// function sees the names of its module.
+    pub fn caller_namespace() -> Option<Namespace> {
+        CALLER.with(|caller| caller.borrow().upgrade())
+            .map(|ns| ns.borrow().clone())
+    }
+
     pub fn run(config: &Config) -> i64 {
         let interactive_main: MainFnRef = &python_main_interactive;
-        let main = create_python_main(config.mode.clone(), config.arguments);
+        let main = match config.dump {
+            Some(dump) => create_dump_main(config.mode.clone(), config.arguments, dump, config.optimize),
+            None => create_python_main(config.mode.clone(), config.arguments, config.optimize,
+                                   config.debug_support, config.profile, config.coverage.clone())
+        };
 
-        let main_func = match config.mode {
-            Mode::Interactive => Box::new(interactive_main),
+        let main_func = match (&config.mode, config.dump) {
+            (&Mode::Interactive, None) => Box::new(interactive_main),
             _ => Box::new(&(*main)),
         };
 
         let main_thread: Box<Thread> = match config.thread_model {
             ThreadModel::OsThreads => Box::new(Pthread {func: (*main_func).clone()}),
-            ThreadModel::GreenThreads => Box::new(GreenThread {func: SharedMainFnRef((*main_func).clone())})
+            ThreadModel::GreenThreads => Box::new(GreenThread {
+                func: SharedMainFnRef((*main_func).clone()),
+                stack_size: config.stack_size
+            })
         };
 
+        scheduler::set_stack_size(config.stack_size);
+
         let rt = &Runtime::new();
+        rt.set_recursion_limit(config.recursion_limit);
+        if let Err(err) = sys::configure(rt, &config.mode, config.arguments) {
+            err.log();
+            return ExitCode::GenericError as i64
+        }
+
         main_thread.start(&rt)
     }
 
@@ -204,8 +322,11 @@ impl Interpreter {
 
     }
 
-    pub fn push_frame(&mut self, func: &RtObject) -> Result<usize, Error>{
-        if self.frames.len() + 1 == RECURSION_LIMIT {
+    /// Push a frame for a call to `func` that runs `code`, native functions have no code.
+    pub fn push_frame(&mut self, func: &RtObject, code: Option<&rs::Code>) -> Result<usize, Error>{
+        // Deep recursion runs out of native stack before the frame limit on small
+        // stacks, like those of greenlets, so both are checked.
+        if self.frames.len() + 1 >= self.rt.recursion_limit() || !stack::has_headroom() {
             return Err(Error::recursion())
         }
 
@@ -218,13 +339,18 @@ impl Interpreter {
                 f_back: f_back,
                 f_code: func.clone(),
                 f_builtins: self.rt.none(),
-                f_lasti: rs::Integer::zero(),
-                blocks: VecDeque::new()
+                f_lasti: Cell::new(0),
+                f_lineno: Cell::new(0),
             }
         );
 
         trace!("Interpreter"; "action" => "push_frame", "idx" => self.frames.len());
-        self.frames.push_back(InterpreterFrame::new(new_frame));
+        let frame = InterpreterFrame::new(new_frame);
+        if let Some(code) = code {
+            frame.load_code(code);
+        }
+
+        self.frames.push_back(frame);
 
         Ok(self.frames.len())
     }
@@ -241,98 +367,91 @@ impl Interpreter {
         })
     }
 
+    /// Drop values off of the top of the value stack until it has `len` values
+    pub fn truncate_stack(&mut self, len: usize) {
+        with_current_frame!(self |frame| {
+            frame.truncate_stack(len);
+        });
+    }
+
+    pub fn push_block(&mut self, block: rs::Block) {
+        with_current_frame!(self |frame| {
+            frame.push_block(block);
+        });
+    }
+
+    pub fn pop_block(&mut self) -> Option<rs::Block> {
+        with_current_frame!(self |frame| {
+            frame.pop_block()
+        })
+    }
+
     pub fn stack_view(&self) -> Ref<rs::List> {
         with_current_frame!(self |frame| {
             frame.stack()
         })
     }
 
+    /// Where each frame is, outermost first. Frames of native functions have no
+    /// file and are left out.
     pub fn frame_view(&self) -> Vec<TracebackFrame> {
         self.frames.iter()
             .map(TracebackFrame::from)
+            .filter(|frame| !frame.filename().is_empty())
             .collect::<Vec<_>>()
     }
 
+    /// The traceback of `error` which was raised in the current frame. Frames are
+    /// only popped when they return, so after an error they are all still there
+    /// until `clear_traceback` is called.
+    pub fn traceback(&self, error: &Error) -> Traceback {
+        Traceback::new(self.frame_view(), error.clone())
+    }
+
 
     /// Used in lieu of an actual exception handling mechanism,
     /// clear all frames back to __main__ and clear that frame's
-    /// value stack.
+    /// value and block stacks.
     pub fn clear_traceback(&mut self) {
         self.frames.truncate(1);
 
         with_current_frame!(self |frame| {
             frame.clear_stack();
+            frame.clear_blocks();
             frame.set_line(0);
         });
     }
 
-    fn exec_binop(&mut self, rt: &Runtime, opcode: OpCode, lhs: &RtObject, rhs: &RtObject) -> ObjectResult {
-        match opcode {
-            OpCode::CompareIs               => lhs.op_is(&rt, &rhs),
-            OpCode::CompareIsNot            => lhs.op_is_not(&rt, &rhs),
-            OpCode::CompareEqual            => lhs.op_eq(&rt, &rhs),
-            // Note flipped operators due to "lhs in rhs"
-            OpCode::CompareIn               => rhs.op_contains(&rt, &lhs),
-            OpCode::CompareNotEqual         => lhs.op_ne(&rt, &rhs),
-            OpCode::CompareLess             => lhs.op_lt(&rt, &rhs),
-            OpCode::CompareLessOrEqual      => lhs.op_le(&rt, &rhs),
-            OpCode::CompareGreater          => lhs.op_gt(&rt, &rhs),
-            OpCode::CompareGreaterOrEqual   => lhs.op_ge(&rt, &rhs),
-            OpCode::LogicalAnd              => logical_and(rt, lhs, rhs),
-            OpCode::LogicalOr               => logical_or(rt, lhs, rhs),
-            OpCode::BinaryAdd               => lhs.op_add(&rt, &rhs),
-            OpCode::BinarySubtract          => lhs.op_sub(&rt, &rhs),
-            OpCode::BinaryMultiply          => lhs.op_mul(&rt, &rhs),
-            OpCode::BinaryPower             => lhs.op_pow(&rt, &rhs, &rt.int(1)),
-            OpCode::BinaryTrueDivide        => lhs.op_truediv(&rt, &rhs),
-            OpCode::BinaryFloorDivide       => lhs.op_floordiv(&rt, &rhs),
-            OpCode::BinaryOr                => lhs.op_or(&rt, &rhs),
-            OpCode::BinaryModulo            => lhs.op_mod(&rt, &rhs),
-            OpCode::BinaryAnd               => lhs.op_and(&rt, &rhs),
-            OpCode::BinaryMatrixMultiply    => lhs.op_matmul(&rt, &rhs),
-            OpCode::BinaryXor               => lhs.op_xor(&rt, &rhs),
-            OpCode::BinaryLshift            => lhs.op_lshift(&rt, &rhs),
-            OpCode::BinaryRshift            => lhs.op_rshift(&rt, &rhs),
-            opcode                           => Err(Error::system(
-                &format!("Unhandled binary operation {:?}, this is a bug!", opcode))),
-        }
-    }
-
-    fn exec_unaryop(&mut self, rt: &Runtime, opcode: OpCode, operand: &RtObject) -> ObjectResult {
-        match opcode {
-            OpCode::UnaryNot        => {
-                let value = operand.op_bool(&rt)?;
-                Ok(rt.bool(!(value == rt.bool(true))))
-            },
-            OpCode::UnaryNegative   => operand.op_neg(&rt),
-            OpCode::UnaryPositive   => operand.op_pos(&rt),
-            OpCode::UnaryInvert     => operand.op_invert(&rt),
-            opcode                  => Err(Error::system(
-                &format!("Unhandled unary operation {:?}, this is a bug!", opcode))),
-        }
+    /// Native truth value of `object`
+    fn truth(&self, rt: &Runtime, object: &RtObject) -> Result<bool, Error> {
+        let value = object.op_bool(&rt)?;
+        Ok(value == rt.bool(true))
     }
 
-    /// Execute exactly one instruction
-    pub fn exec_one(&mut self, rt: &Runtime, instr: &Instr) -> Option<ObjectResult> {
+    /// Execute exactly one instruction in the current frame and tell the
+    /// execution loop what to do next.
+    pub fn exec_one(&mut self, rt: &Runtime, instr: &Instr) -> Signal {
         trace!("Interpreter"; "action" => "exec_one", "instr" => format!("{:?}", instr));
 
         match instr.to_tuple() {
             (OpCode::LoadConst, Some(value)) => {
                 let objref = match value {
+                    Native::Str(ref string) if all_name_chars(string) => rt.intern(string),
                     Native::Str(string) => rt.str(string),
+                    Native::Bytes(bytes) => rt.bytes(bytes),
                     Native::Int(i) => rt.int(i),
                     Native::Float(f) => rt.float(f),
                     Native::Bool(b) => rt.bool(b),
                     Native::Complex(_) => {
-                        return Some(Err(Error::system(&format!(
+                        return Signal::Raise(Error::system(&format!(
                             "Interpreter does not implement Complex values; file: {}, line: {}",
-                            file!(), line!()))))
+                            file!(), line!())))
                     },
                     Native::Code(code) => {
 
                         let func = rs::Func {
                             name: code.co_name.clone(),
-                            module: String::from("<compiled-module>"),
+                            module: String::from(strings::MAIN_MODULE),
                             signature: code.co_varnames.as_slice().as_args(),
                             callable: rs::FuncType::Code(code),
                         };
@@ -341,45 +460,76 @@ impl Interpreter {
                     },
                     Native::None => rt.none(),
                     Native::Count(_) |
-                    Native::List(_) => return Some(Err(Error::system(
+                    Native::List(_) => return Signal::Raise(Error::system(
                         &format!("Malformed LoadConst instruction {:?}, this is a bug!; file: {}; line: {}",
-                                 instr, file!(), line!())))),
+                                 instr, file!(), line!()))),
                 };
 
                 self.push_stack(&objref);
-                None
+                Signal::Continue
             },
             (OpCode::StoreName, Some(value)) => {
                 let name = match value {
                     Native::Str(string) => string,
-                    _ => return Some(Err(
-                        Error::runtime("Attempt to store a non string named value!")))
+                    _ => return Signal::Raise(
+                        Error::runtime("Attempt to store a non string named value!"))
                 };
 
                 match self.pop_stack() {
-                    Some(objref) => self.ns.insert(name, objref),
-                    None => return Some(Err(
-                        Error::runtime("No values in value stack to store!")))
+                    Some(objref) => self.ns.borrow_mut().insert(name, objref),
+                    None => return Signal::Raise(
+                        Error::runtime("No values in value stack to store!"))
                 };
 
-                None
+                self.ns_version = next_ns_version();
+                Signal::Continue
+            },
+            (OpCode::DeleteName, Some(value)) => {
+                let name = match value {
+                    Native::Str(string) => string,
+                    _ => return Signal::Raise(
+                        Error::runtime("Attempt to delete a non string named value!"))
+                };
+
+                let removed = self.ns.borrow_mut().remove(&name);
+                match removed {
+                    Some(_) => {
+                        self.ns_version = next_ns_version();
+                        Signal::Continue
+                    },
+                    None => Signal::Raise(Error::name(&name))
+                }
             },
             (OpCode::LoadName, Some(value)) => {
+                let version = self.ns_version;
+                let cached = with_current_frame!(self |frame| {
+                    frame.caches.borrow().load_name(frame.offset(), version)
+                });
+
+                if let Some(objref) = cached {
+                    self.push_stack(&objref);
+                    return Signal::Continue
+                }
+
                 let name = match value {
                     Native::Str(string) => string,
-                    _ => return Some(Err(
-                        Error::runtime("Attempt to load a non string named value!")))
+                    _ => return Signal::Raise(
+                        Error::runtime("Attempt to load a non string named value!"))
                 };
 
 
-                let to_push = match self.ns.get(&name) {
-                    Some(objref) => objref.clone(),
-                    None => return Some(Err(Error::name(&name)))
+                let found = self.ns.borrow().get(&name).cloned();
+                let to_push = match found {
+                    Some(objref) => objref,
+                    None => return Signal::Raise(Error::name(&name))
                 };
 
+                with_current_frame!(self |frame| {
+                    frame.caches.borrow().set(frame.offset(), InlineCache::name(version, &to_push))
+                });
                 self.push_stack(&to_push);
 
-                None
+                Signal::Continue
             },
             (OpCode::CompareIs, None)               |
             (OpCode::CompareIsNot, None)            |
@@ -408,23 +558,23 @@ impl Interpreter {
 
                 let rhs = match self.pop_stack() {
                     Some(objref) => objref,
-                    None => return Some(Err(Error::system(
-                        &format!("No values in value stack for {:?}!", instr.code()))))
+                    None => return Signal::Raise(Error::system(
+                        &format!("No values in value stack for {:?}!", instr.code())))
                 };
 
                 let lhs = match self.pop_stack() {
                     Some(objref) => objref,
-                    None => return Some(Err(Error::system(
-                        &format!("No values in value stack for {:?}!", instr.code()))))
+                    None => return Signal::Raise(Error::system(
+                        &format!("No values in value stack for {:?}!", instr.code())))
                 };
 
-                let result = match self.exec_binop(rt, instr.code(), &lhs, &rhs) {
+                let result = match binary_op(rt, instr.code(), &lhs, &rhs) {
                     Ok(objref) => objref,
-                    err => return Some(err)
+                    Err(err) => return Signal::Raise(err)
                 };
 
                 self.push_stack(&result);
-                None
+                Signal::Continue
             },
             (OpCode::UnaryNot, None)        |
             (OpCode::UnaryNegative, None)   |
@@ -432,39 +582,51 @@ impl Interpreter {
             (OpCode::UnaryInvert, None)     => {
                 let operand = match self.pop_stack() {
                     Some(objref) => objref,
-                    None => return Some(Err(Error::system(
-                        &format!("No values in value stack for {:?}!", instr.code()))))
+                    None => return Signal::Raise(Error::system(
+                        &format!("No values in value stack for {:?}!", instr.code())))
                 };
 
-                let result = match self.exec_unaryop(rt, instr.code(), &operand) {
+                let result = match unary_op(rt, instr.code(), &operand) {
                     Ok(objref) => objref,
-                    err => return Some(err)
+                    Err(err) => return Signal::Raise(err)
                 };
 
                 self.push_stack(&result);
-                None
+                Signal::Continue
             },
             (OpCode::LoadAttr, Some(Native::Str(name))) => {
                 let object = match self.pop_stack() {
                     Some(obj) => obj,
-                    None => return Some(Err(Error::system(
-                        &format!("No values in value stack for {:?}!", instr.code()))))
+                    None => return Signal::Raise(Error::system(
+                        &format!("No values in value stack for {:?}!", instr.code())))
                 };
 
-                let result = match object.op_getattr(&rt, &rt.str(name)) {
+                let cached = with_current_frame!(self |frame| {
+                    frame.caches.borrow().load_attr(frame.offset(), &rt, &object)
+                });
+
+                if let Some(result) = cached {
+                    self.push_stack(&result);
+                    return Signal::Continue
+                }
+
+                let result = match object.op_getattr(&rt, &rt.intern(&name)) {
                     Ok(obj) => obj,
-                    err => return Some(err)
+                    Err(err) => return Signal::Raise(err)
                 };
 
+                with_current_frame!(self |frame| {
+                    frame.caches.borrow().set(frame.offset(), InlineCache::attr(&object, &result))
+                });
                 self.push_stack(&result);
-                None
+                Signal::Continue
             }
             (OpCode::CallFunction, Some(Native::Count(arg_count))) => {
                 let mut args: VecDeque<RtObject> = VecDeque::new();
                 for _ in 0..(arg_count + 1) {
                     if self.stack_view().is_empty() {
-                        return Some(Err(Error::system(
-                            "Value stack did not contain enough values for function call!")));
+                        return Signal::Raise(Error::system(
+                            "Value stack did not contain enough values for function call!"));
                     }
 
                     args.push_front(self.pop_stack().unwrap());
@@ -472,72 +634,10 @@ impl Interpreter {
 
                 let func = match args.pop_front() {
                     Some(objref) => objref,
-                    None => return Some(Err(Error::system("No values in value stack for call!")))
+                    None => return Signal::Raise(Error::system("No values in value stack for call!"))
                 };
 
-                let result = match func.as_ref(){
-                    &Type::Function(ref pyfunc) => {
-                        match pyfunc.value.0.callable {
-                            FuncType::Wrapper(_)        |
-                            FuncType::MethodWrapper(_, _)  => {
-                                let pos_args = args.into_iter().collect::<Vec<RtObject>>();
-
-                                match self.push_frame(&func) {
-                                    Err(err) => Err(err),
-                                    Ok(_) => {
-                                        match pyfunc.op_call(&rt,
-                                                            &rt.tuple(pos_args),
-                                                            &rt.tuple(vec![]),
-                                                            &rt.dict(rs::None())) {
-
-                                            Ok(next_tos) => {
-                                                self.pop_frame();
-                                                Ok(next_tos)
-                                            },
-                                            Err(err) => Err(err)
-                                        }
-                                    }
-                                }
-                            },
-                            FuncType::Code(ref code) => {
-                                // TODO: Fix this, this kind of stuff should
-                                //   be part of a frame/scope
-                                // Because overwriting the global namespace
-                                //   with function args is always a good decision....
-                                for (name, value) in code.co_names.iter().zip(args.iter()) {
-                                    // unwrap here should be safe because of the previous check
-                                    debug!("Namespace"; "action" => "insert", "key" => name, "value" => value.to_string());
-                                    self.ns.insert(name.clone(), value.clone());
-                                }
-
-                                let ins = code.co_code.clone().into_iter().collect::<Vec<_>>();
-
-                                match self.push_frame(&func) {
-                                    Err(err) => Err(err),
-                                    Ok(_) => {
-                                        match self.exec(&rt, &ins) {
-                                            Ok(_) => {
-                                                let next_tos = match self.pop_stack() {
-                                                    Some(objref) => objref,
-                                                    None => self.rt.none()
-                                                };
-                                                self.pop_frame();
-                                                Ok(next_tos)
-                                            },
-                                            Err(err) => Err(err),
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    _ => Err(
-                        Error::system(
-                            &format!("{} {}; file: {}, line: {}",
-                                     "Interpreter does not implement function calls ",
-                                     "on non function types",
-                                     file!(), line!())))
-                };
+                let result = self.call(rt, &func, args.into_iter().collect());
 
                 match result {
                     Ok(object) => {
@@ -547,20 +647,31 @@ impl Interpreter {
                     Err(err) => {
                         // TODO: When there is exception handling, this is a prime place to
                         // do the jump to the excepthandler
-                        return Some(Err(err))
+                        return Signal::Raise(err)
                     },
                 };
 
-                None
+                Signal::Continue
             }
             (OpCode::ReturnValue, None) => {
-                None
+                match self.pop_stack() {
+                    Some(objref) => Signal::Return(objref),
+                    None => Signal::Raise(Error::system(
+                        &format!("No values in value stack for {:?}!", instr.code())))
+                }
+            },
+            (OpCode::YieldValue, None) => {
+                match self.pop_stack() {
+                    Some(objref) => Signal::Yield(objref),
+                    None => Signal::Raise(Error::system(
+                        &format!("No values in value stack for {:?}!", instr.code())))
+                }
             },
             (OpCode::MakeFunction, None) => {
                 match self.pop_stack() {
                     Some(objref) => objref,
-                    None => return Some(Err(Error::runtime(
-                        &format!("No values in value stack for {:?}!", instr.code()))))
+                    None => return Signal::Raise(Error::runtime(
+                        &format!("No values in value stack for {:?}!", instr.code())))
                 };
 
 //                let code = match self.pop_stack() {
@@ -570,14 +681,14 @@ impl Interpreter {
 //
 //
 //                self.namespace.insert(name.to_string(), code);
-                None
+                Signal::Continue
             },
             (OpCode::BuildList, Some(Native::Count(count))) => {
                 let mut elems = rs::List::new();
                 for _ in 0..count {
                     if self.stack_view().is_empty() {
-                        return Some(Err(Error::system(
-                            "Value stack did not contain enough values for function call!")));
+                        return Signal::Raise(Error::system(
+                            "Value stack did not contain enough values for function call!"));
                     }
 
                     elems.insert(0, self.pop_stack().unwrap());
@@ -586,42 +697,67 @@ impl Interpreter {
                 let objref = rt.list(elems);
                 trace!("Interpreter"; "action" => "push_stack", "object" => format!("{:?}", objref));
                 self.push_stack(&objref);
-                Some(Ok(rt.none()))
+                Signal::Continue
+            },
+            (OpCode::BuildTuple, Some(Native::Count(count))) => {
+                let mut elems = rs::Tuple::new();
+                for _ in 0..count {
+                    if self.stack_view().is_empty() {
+                        return Signal::Raise(Error::system(
+                            "Value stack did not contain enough values for function call!"));
+                    }
+
+                    elems.insert(0, self.pop_stack().unwrap());
+                }
+
+                let objref = rt.tuple(elems);
+                trace!("Interpreter"; "action" => "push_stack", "object" => format!("{:?}", objref));
+                self.push_stack(&objref);
+                Signal::Continue
             },
             (OpCode::BuildMap, Some(Native::Count(count))) => {
                 let dict = rt.default_dict();
 
                 for _ in 0..count  {
                     if self.stack_view().is_empty() || self.stack_view().len() == 1 {
-                        return Some(Err(Error::system(
-                            "Value stack did not contain enough values for function call!")));
+                        return Signal::Raise(Error::system(
+                            "Value stack did not contain enough values for function call!"));
                     }
 
                     let value = self.pop_stack().unwrap();
                     let key = self.pop_stack().unwrap();
                     match dict.op_setitem(&rt, &key, &value) {
                         Ok(_) => continue,
-                        Err(err) => return Some(Err(err))
+                        Err(err) => return Signal::Raise(err)
                     };
                 }
 
                 trace!("Interpreter"; "action" => "push_stack", "object" => format!("{:?}", dict));
                 self.push_stack(&dict);
-                Some(Ok(rt.none()))
+                Signal::Continue
             },
             (OpCode::PopTop, None) => {
-                    match self.pop_stack() {
-                        Some(objref) => Some(Ok(objref)),
-                        None => None
-                    }
+                self.pop_stack();
+                Signal::Continue
+            },
+            (OpCode::PrintExpr, None) => {
+                let objref = match self.pop_stack() {
+                    Some(objref) => objref,
+                    None => return Signal::Raise(
+                        Error::runtime("No values in value stack to display!"))
+                };
 
+                match self.display(&rt, &objref) {
+                    Ok(_) => Signal::Continue,
+                    Err(err) => Signal::Raise(err)
+                }
             },
             (OpCode::AssertCondition, Some(Native::Count(arg_count))) => {
                 let mut args: Vec<RtObject> = Vec::new();
                 for _ in 0..arg_count {
                     if self.stack_view().is_empty() {
-                        return Some(Err(Error::system(
-                            "Value stack did not contain enough values for Assertion!")));
+                        return Signal::Raise(Error::system(
+                            "Value stack did not contain enough values for Assertion!"));
                     }
 
                     args.push(self.pop_stack().unwrap());
@@ -638,149 +774,733 @@ impl Interpreter {
                     1 => {
                         assert = args.pop().unwrap();
                     },
-                    _ => return Some(Err(Error::system(
-                        "Value stack did not contain an expected number of values!")))
+                    _ => return Signal::Raise(Error::system(
+                        "Value stack did not contain an expected number of values!"))
                 }
 
                 let result = match assert.op_bool(rt){
                     Ok(objref) => objref,
-                    Err(err) => return Some(Err(err))
+                    Err(err) => return Signal::Raise(err)
                 };
 
                 trace!("Interpreter"; "action" => "assert", "test_expr" => result.to_string());
 
                 match result == rt.bool(true) {
-                    true => None,
-                    false => Some(Err(Error::assertion(&message)))
+                    true => Signal::Continue,
+                    false => Signal::Raise(Error::assertion(&message))
+                }
+            },
+            (OpCode::Nop, None) => Signal::Continue,
+            (OpCode::JumpForward, Some(Native::Count(offset)))  |
+            (OpCode::JumpAbsolute, Some(Native::Count(offset))) |
+            (OpCode::ContinueLoop, Some(Native::Count(offset))) => {
+                Signal::Jump(offset)
+            },
+            (OpCode::PopJumpIfFalse, Some(Native::Count(offset))) |
+            (OpCode::PopJumpIfTrue, Some(Native::Count(offset)))  => {
+                let tos = match self.pop_stack() {
+                    Some(objref) => objref,
+                    None => return Signal::Raise(Error::system(
+                        &format!("No values in value stack for {:?}!", instr.code())))
+                };
+
+                let truth = match self.truth(rt, &tos) {
+                    Ok(truth) => truth,
+                    Err(err) => return Signal::Raise(err)
+                };
+
+                match (instr.code(), truth) {
+                    (OpCode::PopJumpIfFalse, false) |
+                    (OpCode::PopJumpIfTrue, true)   => Signal::Jump(offset),
+                    _ => Signal::Continue
+                }
+            },
+            (OpCode::JumpIfFalseOrPop, Some(Native::Count(offset))) |
+            (OpCode::JumpIfTrueOrPop, Some(Native::Count(offset)))  => {
+                let tos = match self.stack_view().last() {
+                    Some(objref) => objref.clone(),
+                    None => return Signal::Raise(Error::system(
+                        &format!("No values in value stack for {:?}!", instr.code())))
+                };
+
+                let truth = match self.truth(rt, &tos) {
+                    Ok(truth) => truth,
+                    Err(err) => return Signal::Raise(err)
+                };
+
+                match (instr.code(), truth) {
+                    (OpCode::JumpIfFalseOrPop, false) |
+                    (OpCode::JumpIfTrueOrPop, true)   => Signal::Jump(offset),
+                    _ => {
+                        self.pop_stack();
+                        Signal::Continue
+                    }
+                }
+            },
+            (OpCode::GetIter, None) => {
+                let iterable = match self.pop_stack() {
+                    Some(objref) => objref,
+                    None => return Signal::Raise(Error::system(
+                        &format!("No values in value stack for {:?}!", instr.code())))
+                };
+
+                match iterable.op_iter(&rt) {
+                    Ok(iterator) => {
+                        self.push_stack(&iterator);
+                        Signal::Continue
+                    },
+                    Err(err) => Signal::Raise(err)
+                }
+            },
+            (OpCode::ForIter, Some(Native::Count(offset))) => {
+                let iterator = match self.stack_view().last() {
+                    Some(objref) => objref.clone(),
+                    None => return Signal::Raise(Error::system(
+                        &format!("No values in value stack for {:?}!", instr.code())))
+                };
+
+                match iterator.op_next(&rt) {
+                    Ok(value) => {
+                        self.push_stack(&value);
+                        Signal::Continue
+                    },
+                    Err(Error(ErrorType::StopIteration, _)) => {
+                        self.pop_stack();
+                        Signal::Jump(offset)
+                    },
+                    Err(err) => Signal::Raise(err)
+                }
+            },
+            (OpCode::SetupLoop, Some(Native::Count(offset))) => {
+                let level = self.stack_view().len();
+                self.push_block(rs::Block {
+                    kind: rs::BlockType::Loop,
+                    handler: offset,
+                    level: level
+                });
+                Signal::Continue
+            },
+            (OpCode::PopBlock, None) => {
+                match self.pop_block() {
+                    Some(_) => Signal::Continue,
+                    None => Signal::Raise(Error::system("No blocks in block stack to pop!"))
+                }
+            },
+            (OpCode::BreakLoop, None) => {
+                match self.pop_block() {
+                    Some(rs::Block { kind: rs::BlockType::Loop, handler, level }) => {
+                        self.truncate_stack(level);
+                        Signal::Jump(handler)
+                    },
+                    None => Signal::Raise(Error::system("'break' outside of a loop block!"))
                 }
             },
             (OpCode::SetLineNumber, Some(Native::Count(line))) => {
                 self.set_line(line);
-                None
+                Signal::Continue
             },
-            opcode => Some(Err(Error::system(&format!(
+            opcode => Signal::Raise(Error::system(&format!(
                 "Unrecognized opcode pattern: {:?}", opcode
-            ))))
+            )))
         }
     }
 
+    /// Load `ins` as the code of the current frame and run it from the start.
+    /// Errors are reported against `strings::SOURCE_STRING`.
     pub fn exec(&mut self, rt: &Runtime, ins: &[Instr]) -> ObjectResult {
-        let mut objects: VecDeque<RtObject> = VecDeque::new();
-
-        let result = ins.iter()
-            .map(|ref instr| self.exec_one(&rt, instr))
-            .filter(Option::is_some)
-            .map(Option::unwrap)
-            .fold_results((&mut objects).clone(), |mut acc, i| {acc.push_back(i); acc});
-
-        let back = objects.back();
-        match (result, back) {
-            (Ok(_), Some(objref)) => Ok(objref.clone()),
-            (Ok(_), None)         => Ok(rt.none()),
-            (Err(err), _)         => Err(err)
-        }
+        self.exec_code(rt, &rs::Code::module(strings::SOURCE_STRING, ins.to_vec()))
     }
 
-    // TODO: This might be a decent thing to be part of Frame since
-    // they should have all of the f_back pointers... maybe?
-    pub fn format_traceback(&self) -> String {
-        let frames: Vec<TracebackFrame> = self.frame_view();
-
-        let names_result = (*frames).iter()
-            .rev()
-            .map(|ref tbframe| {
+    /// Same as `exec` for a code object, line numbers are taken from the
+    /// line table of the code when it has one.
+    pub fn exec_code(&mut self, rt: &Runtime, code: &rs::Code) -> ObjectResult {
+        with_current_frame!(self |frame| {
+            frame.load_code(code);
+        });
 
-                match tbframe.object().as_ref() {
-                    &Type::Frame(ref pyframe) => {
-                        let fcode = pyframe.value.0.f_code.clone();
+        self.fire(rt, Event::Call, &rt.none())?;
+        self.run_frame(rt)
+    }
 
-                        Ok((fcode, tbframe.line()))
-                    },
-                    other => Err(Error::system(
-                        &format!("{} is not a Frame object", other.debug_name())))
-                }
-            }).map_results(|(ref code, line)| {
-
-                match code.as_ref() {
-                    &Type::Code(ref pycode) => {
-                        Ok(format!(
-                            "<{} at {:?}>",
-                            pycode.value.0.co_name.clone(),
-                            (pycode as *const _)))
-                    },
-                    &Type::Function(ref pyfunc) => {
-                        Ok(format!("<{} at {:?} in {} line ~{}>",
-                                   pyfunc.name(),
-                                   (pyfunc as *const _),
-                                   pyfunc.module(),
-                                   line))
+    /// Call `func` with the positional `args`. Functions defined in python run in a new
+    /// frame of this interpreter until they return.
+    pub fn call(&mut self, rt: &Runtime, func: &RtObject, args: Vec<RtObject>) -> ObjectResult {
+        match func.as_ref() {
+            &Type::Function(ref pyfunc) => {
+                match pyfunc.value.0.callable {
+                    FuncType::Wrapper(_)        |
+                    FuncType::MethodWrapper(_, _)  => {
+                        self.fire(rt, Event::CCall, func)?;
+                        CALLER.with(|caller| *caller.borrow_mut() = Rc::downgrade(&self.ns));
+
+                        match self.push_frame(func, None) {
+                            Err(err) => Err(err),
+                            Ok(_) => {
+                                match pyfunc.op_call(&rt,
+                                                    &rt.tuple(args),
+                                                    &rt.tuple(vec![]),
+                                                    &rt.dict(rs::None())) {
+
+                                    Ok(next_tos) => {
+                                        self.pop_frame();
+                                        self.fire(rt, Event::CReturn, func)?;
+                                        Ok(next_tos)
+                                    },
+                                    Err(err) => {
+                                        self.fire(rt, Event::CException, func)?;
+                                        Err(err)
+                                    }
+                                }
+                            }
+                        }
                     },
-                    other => {
-                        Err(Error::system(
-                            &format!("{} is not a Code or Func object, this is a bug!; file: {}, line: {}",
-                                     other.debug_name(), file!(), line!())))
+                    FuncType::Code(ref code) => {
+                        // TODO: Fix this, this kind of stuff should
+                        //   be part of a frame/scope
+                        // Because overwriting the global namespace
+                        //   with function args is always a good decision....
+                        for (name, value) in code.co_names.iter().zip(args.iter()) {
+                            // unwrap here should be safe because of the previous check
+                            debug!("Namespace"; "action" => "insert", "key" => name, "value" => value.to_string());
+                            self.ns.borrow_mut().insert(name.clone(), value.clone());
+                            self.ns_version = next_ns_version();
+                        }
+
+                        match self.push_frame(func, Some(code)) {
+                            Err(err) => Err(err),
+                            Ok(_) => {
+                                self.fire(rt, Event::Call, &rt.none())?;
+
+                                match self.run_frame(&rt) {
+                                    Ok(next_tos) => {
+                                        self.pop_frame();
+                                        Ok(next_tos)
+                                    },
+                                    Err(err) => Err(err),
+                                }
+                            }
+                        }
                     }
                 }
-            })
-            .fold_results(
-                Vec::new(),
-                |mut acc, r| {acc.push(r.unwrap()); acc});
-
-        match names_result {
-            Ok(names) => format!("Traceback (most recent call first): \n>>> {}",
-                                 names.join("\n>>> ")),
-            Err(err) => format!("{:?}", err)
+            }
+            _ => Err(
+                Error::system(
+                    &format!("{} {}; file: {}, line: {}",
+                             "Interpreter does not implement function calls ",
+                             "on non function types",
+                             file!(), line!())))
+        }
+    }
+
+    /// Run the current frame from its program counter until it returns, yields,
+    /// runs off of the end of its code, or raises an error.
+    fn run_frame(&mut self, rt: &Runtime) -> ObjectResult {
+        loop {
+            let instr = match with_current_frame!(self |frame| { frame.next_instr() }) {
+                Some(instr) => instr,
+                None => {
+                    let none = rt.none();
+                    self.fire(rt, Event::Return, &none)?;
+                    return Ok(none)
+                }
+            };
+
+            if rt.is_profiling() && !self.tracing {
+                self.count(rt, instr.code());
+            }
+
+            let signal = match self.trace_line(rt, &instr) {
+                Ok(_) => self.exec_one(&rt, &instr),
+                Err(err) => Signal::Raise(err)
+            };
+            // Instruction boundaries are the only safe points for the cycle collector
+            rt.gc().collect_if_needed();
+
+            if rt.is_covering() && !self.tracing {
+                self.cover(rt);
+            }
+
+            match signal {
+                Signal::Continue => continue,
+                Signal::Jump(offset) => {
+                    with_current_frame!(self |frame| {
+                        frame.jump(offset);
+                    })
+                },
+                // The program counter already points past the yield so running
+                // the frame again resumes it.
+                Signal::Yield(objref) |
+                Signal::Return(objref) => {
+                    self.fire(rt, Event::Return, &objref)?;
+                    return Ok(objref)
+                },
+                Signal::Raise(err) => {
+                    // Loop blocks do not handle errors, once there are exception
+                    // handlers this is where the block stack is unwound to them.
+                    with_current_frame!(self |frame| {
+                        frame.clear_blocks();
+                    });
+
+                    let exception = rt.tuple(vec![rt.str(error_name(&err)), rt.str(err.1.clone())]);
+                    self.fire(rt, Event::Exception, &exception)?;
+                    self.fire(rt, Event::Return, &rt.none())?;
+                    return Err(err)
+                }
+            }
+        }
+    }
+
+    /// Send `event` of the current frame to the hooks that want it, which are not
+    /// traced themselves. A hook that raises an error is removed and the error is
+    /// raised in the frame. What the trace function returns becomes the local trace
+    /// function of the frame, `None` stops tracing it.
+    fn fire(&mut self, rt: &Runtime, event: Event, arg: &RtObject) -> Result<(), Error> {
+        if self.tracing {
+            return Ok(())
+        }
+
+        if rt.is_profiling() {
+            self.profile(rt, event, arg);
+        }
+
+        let (frame, local) = with_current_frame!(self |frame| {
+            (frame.object().clone(), frame.tracer())
+        });
+
+        let tracer = match event {
+            Event::Call => rt.trace_func(),
+            event if event.is_traced() => local,
+            _ => None
+        };
+
+        let profiler = match event.is_profiled() {
+            true => rt.profile_func(),
+            false => None
+        };
+
+        if tracer.is_none() && profiler.is_none() {
+            return Ok(())
+        }
+
+        trace!("Interpreter"; "action" => "fire", "event" => event.name());
+        let args = vec![frame, rt.str(event.name()), arg.clone()];
+
+        self.tracing = true;
+        let result = self.call_hooks(rt, tracer, profiler, args);
+        self.tracing = false;
+        result
+    }
+
+    /// Tell the profiler of the runtime which function was called or returned, `func`
+    /// is the native function of the `c_*` events.
+    fn profile(&self, rt: &Runtime, event: Event, func: &RtObject) {
+        let shared = match rt.profiler() {
+            Some(shared) => shared,
+            None => return
+        };
+        let mut profiler = shared.lock().unwrap_or_else(PoisonError::into_inner);
+
+        match event {
+            Event::Call => profiler.enter(with_current_frame!(self |frame| { frame.function() })),
+            Event::CCall => profiler.enter(native_function(func)),
+            Event::Return       |
+            Event::CReturn      |
+            Event::CException   => profiler.exit(),
+            _ => ()
         }
     }
 
-    pub fn log_state (&self) {
-        // FIXME: Remove when parser/compiler allows for an expression of a single name
-        // or maybe just hardcode that in?
-        let stack: String = self.stack_view().iter().enumerate().map(|(idx, obj)| {
-            match obj.native_str() {
-                Ok(s) => format!("{}: {} = {}", idx, obj.as_ref().debug_name(), s),
-                Err(_) => format!("{}: {} = ??", idx, obj.as_ref().debug_name()),
+    fn count(&self, rt: &Runtime, opcode: OpCode) {
+        if let Some(shared) = rt.profiler() {
+            shared.lock().unwrap_or_else(PoisonError::into_inner).count(opcode);
+        }
+    }
+
+    /// Count the line of the current frame in the coverage of the runtime if the
+    /// frame just started running it
+    fn cover(&self, rt: &Runtime) {
+        let started = with_current_frame!(self |frame| { frame.cover_line() });
+
+        if let (Some(line), Some(shared)) = (started, rt.coverage()) {
+            let filename = with_current_frame!(self |frame| { frame.filename.borrow().clone() });
+            shared.lock().unwrap_or_else(PoisonError::into_inner).hit(&filename, line);
+        }
+    }
+
+    fn call_hooks(&mut self, rt: &Runtime, tracer: Option<RtObject>, profiler: Option<RtObject>,
+                  args: Vec<RtObject>) -> Result<(), Error> {
+        if let Some(func) = profiler {
+            if let Err(err) = self.call(rt, &func, args.clone()) {
+                rt.set_profile_func(None);
+                return Err(err)
             }
-        }).collect::<Vec<String>>().join("\n");
+        }
+
+        if let Some(func) = tracer {
+            let local = match self.call(rt, &func, args) {
+                Ok(local) => local,
+                Err(err) => {
+                    rt.set_trace_func(None);
+                    return Err(err)
+                }
+            };
+
+            let local = match local.as_ref() {
+                &Type::None(_) => None,
+                _ => Some(local.clone())
+            };
 
-        let values: Vec<String> = self.ns.iter().map(|(key, value): (&String, &RtObject)| {
+            with_current_frame!(self |frame| {
+                frame.set_tracer(local);
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Fire the line event when the current frame starts a new line, which is at the
+    /// instruction after its `SetLineNumber` or the first one the line table puts on
+    /// it, and stop in the debugger if it wants to. `breakpoint()` starts a debugger
+    /// when there is none.
+    fn trace_line(&mut self, rt: &Runtime, instr: &Instr) -> Result<(), Error> {
+        if self.tracing || instr.code() == OpCode::SetLineNumber {
+            return Ok(())
+        }
+
+        if rt.take_break_request() {
+            let mut debugger = self.debugger.take().unwrap_or_default();
+            debugger.step();
+            self.debugger = Some(debugger);
+        }
 
-            match value.native_str() {
-                Ok(ref s) if s.len() > 100  => format!("{}: {} = {}...", key, value.as_ref().debug_name(), &s[..100]),
-                Ok(s) => format!("{}: {} = {}", key, value.as_ref().debug_name(), s),
-                Err(_) => format!("{}: {} = ??", key, value.as_ref().debug_name()),
+        let (line, traced) = with_current_frame!(self |frame| {
+            (frame.start_line(), frame.tracer().is_some())
+        });
+
+        let line = match line {
+            Some(line) => line,
+            None => return Ok(())
+        };
+
+        if traced {
+            self.fire(rt, Event::Line, &rt.none())?;
+        }
+
+        let stop = match self.debugger {
+            Some(ref debugger) => {
+                let filename = with_current_frame!(self |frame| {
+                    frame.filename.borrow().clone()
+                });
+                debugger.should_stop(&filename, line, self.frames.len())
+            },
+            None => false
+        };
+
+        match stop {
+            true => self.debug(rt),
+            false => Ok(())
+        }
+    }
+
+    /// Stop in the debugger until it is told to go on
+    fn debug(&mut self, rt: &Runtime) -> Result<(), Error> {
+        let mut debugger = match self.debugger.take() {
+            Some(debugger) => debugger,
+            None => return Ok(())
+        };
+
+        self.tracing = true;
+        let result = debugger.interact(rt, self);
+        self.tracing = false;
+
+        self.debugger = Some(debugger);
+        result.map(|_| ())
+    }
+
+    /// Debug the code run by this interpreter with `debugger`
+    pub fn set_debugger(&mut self, debugger: Debugger) {
+        self.debugger = Some(debugger);
+    }
+
+    /// Number of frames on the call stack, including those of native functions
+    pub fn depth(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Compile `source` like input to the repl and run it in a frame on top of the
+    /// current one, the value of an expression is displayed. The frames share the
+    /// interpreter's namespace (see T100) so names resolve the same in all of them.
+    pub fn eval(&mut self, rt: &Runtime, source: &str) -> ObjectResult {
+        let ins = Compiler::new().compile_interactive(&format!("{}\n", source))?;
+        let code = rs::Code::module(strings::SOURCE_STDIN, ins.to_vec());
+
+        let depth = self.frames.len();
+        self.push_frame(&rt.code(code.clone()), Some(&code))?;
+
+        let result = self.run_frame(rt);
+        // Frames are left in place by errors for the traceback, there is none here
+        self.frames.truncate(depth);
+        result
+    }
+
+    /// Show a value computed in the repl by writing its `repr` to `sys.stdout` and
+    /// keep it as `_`. `None` is not shown, same as CPython.
+    fn display(&mut self, rt: &Runtime, objref: &RtObject) -> Result<(), Error> {
+        if let &Type::None(_) = objref.as_ref() {
+            return Ok(())
+        }
+
+        let text = repr(&rt, objref)?.native_str()?;
+        let stdout = rt.import_module(strings::SYS_MODULE)?.op_getattr(&rt, &rt.intern("stdout"))?;
+        io::write(&rt, &stdout, &format!("{}\n", text))?;
+
+        self.ns.borrow_mut().insert(String::from("_"), objref.clone());
+        self.ns_version = next_ns_version();
+        Ok(())
+    }
+
+    /// Names that complete `prefix` for the repl, sorted. A dotted prefix like
+    /// `sys.std` completes the attributes of the object the rest of it names.
+    pub fn completions(&self, prefix: &str) -> Vec<String> {
+        let (path, partial) = match prefix.rfind('.') {
+            Some(idx) => (&prefix[..idx], &prefix[idx + 1..]),
+            None => {
+                let mut names: Vec<String> = self.ns.borrow().keys()
+                    .filter(|name| name.starts_with(prefix))
+                    .cloned()
+                    .collect();
+                names.sort();
+                return names
             }
-        }).collect();
+        };
 
+        let mut parts = path.split('.');
+        let mut object = match parts.next().and_then(|name| self.ns.borrow().get(name).cloned()) {
+            Some(object) => object,
+            None => return Vec::new()
+        };
 
-        let state = format!("\nstack:\n{}\n\nlocals:\n----------\n{}\n", stack, values.join("\n"));
-        debug!("Interpreter"; "State" => state);
+        for name in parts {
+            object = match object.op_getattr(&self.rt, &self.rt.str(name)) {
+                Ok(attr) => attr,
+                Err(_) => return Vec::new()
+            };
+        }
+
+        match attribute_names(&object) {
+            Ok(names) => names.into_iter()
+                .filter(|name| name.starts_with(partial))
+                .map(|name| format!("{}.{}", path, name))
+                .collect(),
+            Err(_) => Vec::new()
+        }
     }
 }
 
 
 
+/// Apply the binary operation of `opcode` to the operands. Shared by the interpreter
+/// and the constant folding in `compiler::optimizer`.
+pub fn binary_op(rt: &Runtime, opcode: OpCode, lhs: &RtObject, rhs: &RtObject) -> ObjectResult {
+    match opcode {
+        OpCode::CompareIs               => lhs.op_is(&rt, &rhs),
+        OpCode::CompareIsNot            => lhs.op_is_not(&rt, &rhs),
+        OpCode::CompareEqual            => lhs.op_eq(&rt, &rhs),
+        // Note flipped operators due to "lhs in rhs"
+        OpCode::CompareIn               => rhs.op_contains(&rt, &lhs),
+        OpCode::CompareNotEqual         => lhs.op_ne(&rt, &rhs),
+        OpCode::CompareLess             => lhs.op_lt(&rt, &rhs),
+        OpCode::CompareLessOrEqual      => lhs.op_le(&rt, &rhs),
+        OpCode::CompareGreater          => lhs.op_gt(&rt, &rhs),
+        OpCode::CompareGreaterOrEqual   => lhs.op_ge(&rt, &rhs),
+        OpCode::LogicalAnd              => logical_and(rt, lhs, rhs),
+        OpCode::LogicalOr               => logical_or(rt, lhs, rhs),
+        OpCode::BinaryAdd               => lhs.op_add(&rt, &rhs),
+        OpCode::BinarySubtract          => lhs.op_sub(&rt, &rhs),
+        OpCode::BinaryMultiply          => lhs.op_mul(&rt, &rhs),
+        OpCode::BinaryPower             => lhs.op_pow(&rt, &rhs, &rt.none()),
+        OpCode::BinaryTrueDivide        => lhs.op_truediv(&rt, &rhs),
+        OpCode::BinaryFloorDivide       => lhs.op_floordiv(&rt, &rhs),
+        OpCode::BinaryOr                => lhs.op_or(&rt, &rhs),
+        OpCode::BinaryModulo            => lhs.op_mod(&rt, &rhs),
+        OpCode::BinaryAnd               => lhs.op_and(&rt, &rhs),
+        OpCode::BinaryMatrixMultiply    => lhs.op_matmul(&rt, &rhs),
+        OpCode::BinaryXor               => lhs.op_xor(&rt, &rhs),
+        OpCode::BinaryLshift            => lhs.op_lshift(&rt, &rhs),
+        OpCode::BinaryRshift            => lhs.op_rshift(&rt, &rhs),
+        opcode                           => Err(Error::system(
+            &format!("Unhandled binary operation {:?}, this is a bug!", opcode))),
+    }
+}
+
+
+/// Apply the unary operation of `opcode` to `operand`
+pub fn unary_op(rt: &Runtime, opcode: OpCode, operand: &RtObject) -> ObjectResult {
+    match opcode {
+        OpCode::UnaryNot        => {
+            let value = operand.op_bool(&rt)?;
+            Ok(rt.bool(!(value == rt.bool(true))))
+        },
+        OpCode::UnaryNegative   => operand.op_neg(&rt),
+        OpCode::UnaryPositive   => operand.op_pos(&rt),
+        OpCode::UnaryInvert     => operand.op_invert(&rt),
+        opcode                  => Err(Error::system(
+            &format!("Unhandled unary operation {:?}, this is a bug!", opcode))),
+    }
+}
+
+
+/// Name of the python type of `err` for the `exception` trace event
+fn error_name(err: &Error) -> String {
+    match err.0 {
+        ErrorType::SystemExit(_) => String::from("SystemExit"),
+        ref kind => format!("{:?}Error", kind)
+    }
+}
+
+
+/// Profiler key of a native function
+fn native_function(func: &RtObject) -> profiler::Function {
+    match func.as_ref() {
+        &Type::Function(ref pyfunc) => {
+            let func = &pyfunc.value.0;
+            profiler::Function::native(&func.module, &func.name)
+        },
+        // Only functions are called as native code
+        _ => profiler::Function::native(strings::BUILTINS_MODULE, "<unknown>")
+    }
+}
+
+
+/// Control signal returned by `Interpreter::exec_one` to the execution loop
+#[derive(Debug)]
+pub enum Signal {
+    /// Move on to the next instruction
+    Continue,
+    /// Move the program counter of the current frame to an instruction offset
+    Jump(usize),
+    /// Leave the current frame with a value
+    Return(RtObject),
+    /// Unwind the current frame with an error
+    Raise(Error),
+    /// Suspend the current frame with a value
+    Yield(RtObject),
+}
+
+
 /// Attaches extra data to a frame object which is Interpreter runtime execution
-/// metadata including the frame's linenumber and value stack.
+/// metadata including the frame's code, program counter, linenumber, and value
+/// and block stacks.
 #[derive(Clone, Debug, Serialize)]
 struct InterpreterFrame {
     frame: RtObject,
+    filename: RefCell<String>,
+    name: RefCell<String>,
+    code: RefCell<Vec<Instr>>,
+    lnotab: RefCell<Vec<rs::LineEntry>>,
+    pc: Cell<usize>,
+    blocks: RefCell<Vec<rs::Block>>,
     stack: RefCell<rs::List>,
-    lineno: Cell<usize>
+    lineno: Cell<usize>,
+    // Last line the line event was fired for
+    traced_line: Cell<usize>,
+    // Last line counted in the coverage
+    covered_line: Cell<usize>,
+    #[serde(skip_serializing)]
+    tracer: RefCell<Option<RtObject>>,
+    #[serde(skip_serializing)]
+    caches: RefCell<InlineCaches>,
 }
 
 
 impl InterpreterFrame {
-    /// Create a new frame from an RtObject. Note that there are no asserts
-    /// about the concrete type of `frame`, so be careful.
+    /// Create a new frame from an RtObject without any code to run. Note that there
+    /// are no asserts about the concrete type of `frame`, so be careful.
     fn new(frame: RtObject) -> Self {
         InterpreterFrame {
             frame: frame,
+            filename: RefCell::new(String::new()),
+            name: RefCell::new(String::new()),
+            code: RefCell::new(Vec::new()),
+            lnotab: RefCell::new(Vec::new()),
+            pc: Cell::new(0),
+            blocks: RefCell::new(Vec::new()),
             stack: RefCell::new(rs::List::new()),
-            lineno: Cell::new(0)
+            lineno: Cell::new(0),
+            traced_line: Cell::new(0),
+            covered_line: Cell::new(0),
+            tracer: RefCell::new(None),
+            caches: RefCell::new(InlineCaches::default()),
         }
     }
 
+    /// Replace the code of the frame and reset the program counter. Used by the
+    /// __main__ frame which runs each compiled input of the REPL in turn.
+    fn load_code(&self, code: &rs::Code) {
+        *self.filename.borrow_mut() = code.co_filename.clone();
+        *self.name.borrow_mut() = code.co_name.clone();
+        *self.code.borrow_mut() = code.co_code.clone();
+        *self.lnotab.borrow_mut() = code.co_lnotab.clone();
+        *self.caches.borrow_mut() = code.co_caches.clone();
+        self.pc.set(0);
+        self.set_line(0);
+        self.traced_line.set(0);
+        self.covered_line.set(0);
+        self.set_tracer(None);
+        self.clear_blocks();
+    }
+
+    /// Fetch the instruction at the program counter and advance the program
+    /// counter, `None` when the end of the code has been reached.
+    fn next_instr(&self) -> Option<Instr> {
+        let pc = self.pc.get();
+        let instr = self.code.borrow().get(pc).cloned();
+
+        if instr.is_some() {
+            self.pc.set(pc + 1);
+
+            if let &Type::Frame(ref pyframe) = self.frame.as_ref() {
+                pyframe.value.0.f_lasti.set(pc);
+            }
+
+            if let Some(line) = rs::line_for_offset(&self.lnotab.borrow(), pc) {
+                self.set_line(line);
+            }
+        }
+
+        instr
+    }
+
+    /// Set the offset of the next instruction to execute. An offset past the end
+    /// of the code ends the frame.
+    fn jump(&self, offset: usize) {
+        trace!("InterpreterFrame"; "action" => "jump", "offset" => offset);
+        // Going back to the start of a loop runs its line again
+        if offset < self.pc.get() {
+            self.traced_line.set(0);
+            self.covered_line.set(0);
+        }
+        self.pc.set(offset);
+    }
+
+    /// Offset of the instruction being executed, which is the one before the
+    /// program counter.
+    fn offset(&self) -> usize {
+        self.pc.get().saturating_sub(1)
+    }
+
+    fn push_block(&self, block: rs::Block) {
+        self.blocks.borrow_mut().push(block);
+    }
+
+    fn pop_block(&self) -> Option<rs::Block> {
+        self.blocks.borrow_mut().pop()
+    }
+
+    fn clear_blocks(&self) {
+        self.blocks.borrow_mut().clear()
+    }
+
     /// Get the reference to the object that represents the frame.
     pub fn object(&self) -> &RtObject {
         &self.frame
@@ -791,9 +1511,7 @@ impl InterpreterFrame {
         self.stack.borrow()
     }
 
-    /// The most recent line number. The parser and compiler eat a lot of the
-    /// information about the source so the line numbers right now are approximate
-    /// at best.
+    /// Line of the statement being run, 0 before the first one
     pub fn line(&self) -> usize {
         self.lineno.get()
     }
@@ -804,9 +1522,67 @@ impl InterpreterFrame {
         trace!("InterpreterFrame"; "action" => "set_line", "value" => line);
         let previous = self.lineno.get();
         self.lineno.set(line);
+
+        if let &Type::Frame(ref pyframe) = self.frame.as_ref() {
+            pyframe.value.0.f_lineno.set(line);
+        }
         previous
     }
 
+    /// The line of the frame if the line event was not fired for it yet
+    pub fn start_line(&self) -> Option<usize> {
+        let line = self.lineno.get();
+        match line == 0 || line == self.traced_line.get() {
+            true => None,
+            false => {
+                self.traced_line.set(line);
+                Some(line)
+            }
+        }
+    }
+
+    /// The line of the frame if it was not counted in the coverage since it started
+    /// running it
+    pub fn cover_line(&self) -> Option<usize> {
+        let line = self.lineno.get();
+        match line == 0 || line == self.covered_line.get() {
+            true => None,
+            false => {
+                self.covered_line.set(line);
+                Some(line)
+            }
+        }
+    }
+
+    /// The first line of the code of the frame, 0 when it has no line numbers
+    pub fn first_line(&self) -> usize {
+        if let Some(&(_, line)) = self.lnotab.borrow().first() {
+            return line
+        }
+
+        self.code.borrow().iter()
+            .filter_map(|instr| match instr.to_tuple() {
+                (OpCode::SetLineNumber, Some(Native::Count(line))) => Some(line),
+                _ => None
+            })
+            .next()
+            .unwrap_or(0)
+    }
+
+    /// What the profiler counts the time of the frame against
+    pub fn function(&self) -> profiler::Function {
+        profiler::Function::new(&self.filename.borrow(), self.first_line(), &self.name.borrow())
+    }
+
+    /// The local trace function of the frame
+    pub fn tracer(&self) -> Option<RtObject> {
+        self.tracer.borrow().clone()
+    }
+
+    pub fn set_tracer(&self, tracer: Option<RtObject>) {
+        *self.tracer.borrow_mut() = tracer;
+    }
+
     /// Push an object onto this frame's value stack. That object will become
     /// the top of stack (often referred to in the CPython documentation as TOS).
     pub fn push_stack(&self, objref: &RtObject) {
@@ -825,36 +1601,19 @@ impl InterpreterFrame {
         self.stack.borrow_mut().clear()
     }
 
-}
-
-/// Lighter weight read only version of `InterpreterFrame` without the value stack
-/// for use in creating a copy of the current frame stack before a traceback is formatted.
-#[derive(Clone, Debug, Serialize)]
-pub struct TracebackFrame {
-    frame: RtObject,
-    line: usize
-}
-
-
-impl TracebackFrame{
-    pub fn object(&self) -> &RtObject {
-        &self.frame
+    /// Drop values off of the top of the value stack until it has `len` values
+    pub fn truncate_stack(&self, len: usize) {
+        self.stack.borrow_mut().truncate(len)
     }
 
-    pub fn line(&self) -> usize {
-        self.line
-    }
 }
 
-
 impl<'a> From<&'a InterpreterFrame> for TracebackFrame {
     /// Convert an `InterpreterFrame` into a `TracebackFrame` by
     /// cloning values and dropping the value stack.
     fn from(frame: &InterpreterFrame) -> Self {
-        TracebackFrame {
-            frame: frame.object().clone(),
-            line: frame.line()
-        }
+        TracebackFrame::new(frame.object().clone(), &frame.filename.borrow(),
+                            &frame.name.borrow(), frame.line())
     }
 }
 
@@ -863,27 +1622,39 @@ impl<'a> From<&'a InterpreterFrame> for TracebackFrame {
 #[cfg(test)]
 mod tests {
     use test::Bencher;
+    use ::api::method::SetAttr;
+    use ::runtime::traits::ModuleProvider;
+    use ::system::{GREENLET_STACK_SIZE, RECURSION_LIMIT};
     use super::*;
 
     /// Use to create a test case of a single line snippet of code.
     /// `assert_run!(float_add_int, "x = 1.0 + 3", ExitCode::Ok)`
+    ///
+    /// The code is run both as compiled and optimized, the status must be the same.
     macro_rules! assert_run {
         ($name:ident, $code:expr, $status:expr) => {
             #[test]
             #[allow(non_snake_case)]
             fn $name() {
-                assert_eq!(run($code), $status as i64);
+                assert_eq!(run($code, 0), $status as i64, "optimize: 0");
+                assert_eq!(run($code, 1), $status as i64, "optimize: 1");
             }
         };
     }
 
-    fn run(code: &str) -> i64 {
+    fn run(code: &str, optimize: usize) -> i64 {
         let config = Config {
             mode: Mode::Command(code.to_string()),
             arguments: &[],
             thread_model: ThreadModel::OsThreads,
-            logging: Logging {},
+            logging: Logging::default(),
             debug_support: false,
+            optimize: optimize,
+            stack_size: GREENLET_STACK_SIZE,
+            recursion_limit: RECURSION_LIMIT,
+            dump: None,
+            profile: None,
+            coverage: None,
         };
 
         Interpreter::run(&config)
@@ -998,6 +1769,202 @@ t = tuple()
 assert len(t) == 0
 "#, ExitCode::Ok);
 
+    assert_run!(tuple_display, r#"
+t = (1, "two", [3],)
+assert len(t) == 3
+assert (1) == 1
+assert () == tuple()
+assert (1, 2) != (2, 1)
+"#, ExitCode::Ok);
+
+    assert_run!(expr_precedence, r#"
+assert 10 - 4 - 3 == 3
+assert 2 ** 3 ** 2 == 512
+assert -2 ** 2 == -4
+assert (1 + 2) * 3 == 9
+assert not 1 == 2 and 3 is not None
+"#, ExitCode::Ok);
+
+    assert_run!(list_mutation, r#"
+l = [1]
+append = l.append
+append(2)
+extend = l.extend
+extend((3, 4))
+assert l == [1, 2, 3, 4]
+clear = l.clear
+clear()
+assert len(l) == 0
+"#, ExitCode::Ok);
+
+    assert_run!(stmt_del, r#"
+x = 1
+del x
+x
+"#, ExitCode::GenericError);
+
+    assert_run!(gc_collect_self_referencing_list, r#"
+collect = gc.collect
+collect()
+a = []
+append = a.append
+append(a)
+del append
+del a
+assert collect() == 1
+assert collect() == 0
+"#, ExitCode::Ok);
+
+    assert_run!(gc_module, r#"
+disable = gc.disable
+disable()
+isenabled = gc.isenabled
+assert not isenabled()
+enable = gc.enable
+enable()
+assert isenabled()
+get_count = gc.get_count
+assert len(get_count()) == 3
+"#, ExitCode::Ok);
+
+    assert_run!(sys_intern, r#"
+intern = sys.intern
+a = intern('abc' + 'def')
+b = intern('abcdef')
+assert a is b
+assert intern('not a name' * 2) is intern('not a name' * 2)
+"#, ExitCode::Ok);
+
+    assert_run!(sys_intern_not_a_str, r#"
+intern = sys.intern
+intern(1)
+"#, ExitCode::GenericError);
+
+    assert_run!(sys_recursion_limit, r#"
+setrecursionlimit = sys.setrecursionlimit
+getrecursionlimit = sys.getrecursionlimit
+setrecursionlimit(50)
+assert getrecursionlimit() == 50
+"#, ExitCode::Ok);
+
+    assert_run!(recursion_error, r#"
+def recurse(n):
+    return recurse(n + 1)
+
+recurse(0)
+"#, ExitCode::GenericError);
+
+    // The native stack runs out long before a million frames
+    assert_run!(recursion_error_past_native_stack, r#"
+setrecursionlimit = sys.setrecursionlimit
+setrecursionlimit(1000000)
+
+def recurse(n):
+    return recurse(n + 1)
+
+recurse(0)
+"#, ExitCode::GenericError);
+
+    assert_run!(greenlet_channel, r#"
+def produce(ch, count):
+    send = ch.send
+    send(count)
+    send(count + 1)
+
+channel = greenlet.channel
+spawn = greenlet.spawn
+join = greenlet.join
+
+ch = channel()
+recv = ch.recv
+g = spawn(produce, ch, 3)
+assert recv() == 3
+assert recv() == 4
+join(g)
+"#, ExitCode::Ok);
+
+    assert_run!(greenlet_module_names, r#"
+def helper(n):
+    return n * 2
+
+def worker(n):
+    return helper(n)
+
+spawn = greenlet.spawn
+join = greenlet.join
+g = spawn(worker, 21)
+assert join(g) == 42
+"#, ExitCode::Ok);
+
+    #[cfg(feature="rsnek_multithreaded")]
+    mod threads {
+        use super::*;
+
+        assert_run!(thread_module_names, r#"
+def helper(items):
+    append = items.append
+    append(1)
+
+def worker(items):
+    helper(items)
+
+items = []
+Thread = threading.Thread
+thread = Thread(worker, [items])
+start = thread.start
+join = thread.join
+start()
+join()
+assert len(items) == 1
+"#, ExitCode::Ok);
+
+        // Threads that append to one list and set items of one dict at the same time
+        assert_run!(thread_shared_list_and_dict, r#"
+items = []
+table = {}
+append = items.append
+setitem = table.__setitem__
+getitem = table.__getitem__
+
+def fill(key, n):
+    append(n)
+    setitem((key, n), n)
+    return fill(key, n - 1) if n > 1 else None
+
+Thread = threading.Thread
+
+def spawn(key):
+    thread = Thread(fill, [key, 50])
+    start = thread.start
+    start()
+    return thread
+
+def join(thread):
+    wait = thread.join
+    wait()
+
+a = spawn(1)
+b = spawn(2)
+c = spawn(3)
+d = spawn(4)
+e = spawn(5)
+f = spawn(6)
+g = spawn(7)
+h = spawn(8)
+join(a)
+join(b)
+join(c)
+join(d)
+join(e)
+join(f)
+join(g)
+join(h)
+assert len(items) == 400
+assert len(table) == 400
+assert getitem((8, 50)) == 50
+"#, ExitCode::Ok);
+    }
+
     assert_run!(contains_01, r#"
 assert "Good" in "Good Day!"
     "#, ExitCode::Ok);
@@ -1030,4 +1997,590 @@ assert len(crazytown) == 5
 test = {[1,2,3,4]: "bad key value"}
     "#, ExitCode::GenericError);
 
+    assert_run!(dict_05, r#"
+x = {}
+setitem = x.__setitem__
+getitem = x.__getitem__
+setitem("a", 1)
+setitem((1, 2), 3)
+assert len(x) == 2
+assert getitem("a") == 1
+assert getitem((1, 2)) == 3
+    "#, ExitCode::Ok);
+
+    assert_run!(bytes_01, r#"
+b = b"\x00\xffsnek"
+assert len(b) == 6
+getitem = b.__getitem__
+assert getitem(1) == 255
+assert getitem(-1) == 107
+assert getitem(slice(2, None)) == b"snek"
+assert b + b"!" == b"\x00\xffsnek!"
+    "#, ExitCode::Ok);
+
+    assert_run!(bytes_02, r#"
+b = b"snek snek"
+hex = b.hex
+fromhex = b.fromhex
+assert fromhex(hex()) == b
+decode = b.decode
+assert decode('ascii') == 'snek snek'
+find = b.find
+assert find(b'ek', 3) == 7
+split = b.split
+assert split() == [b'snek', b'snek']
+    "#, ExitCode::Ok);
+
+    assert_run!(bytes_03, r#"
+b = b"snek" + "snek"
+    "#, ExitCode::GenericError);
+
+    assert_run!(bytearray_01, r#"
+buf = bytearray(b"abc")
+append = buf.append
+append(100)
+setitem = buf.__setitem__
+setitem(0, 122)
+assert buf == b"zbcd"
+assert len(buf) == 4
+    "#, ExitCode::Ok);
+
+    assert_run!(int_to_bytes_01, r#"
+n = 1024
+to_bytes = n.to_bytes
+b = to_bytes(4, 'big')
+assert b == b"\x00\x00\x04\x00"
+from_bytes = n.from_bytes
+assert from_bytes(b, 'big') == n
+assert from_bytes(to_bytes(2, 'little'), 'little') == n
+    "#, ExitCode::Ok);
+
+    assert_run!(int_to_bytes_02, r#"
+n = -1
+to_bytes = n.to_bytes
+b = to_bytes(2, 'big', True)
+from_bytes = n.from_bytes
+assert from_bytes(b, 'big', True) == -1
+assert from_bytes(b, 'big') == 65535
+    "#, ExitCode::Ok);
+
+    assert_run!(int_to_bytes_03, r#"
+n = 1024
+to_bytes = n.to_bytes
+b = to_bytes(1, 'big')
+    "#, ExitCode::GenericError);
+
+    assert_run!(compare_01, r#"
+assert 1 < 2
+assert 2.5 >= 2
+assert not (1 > True)
+assert "abc" < "abd"
+assert b"a" <= b"a"
+    "#, ExitCode::Ok);
+
+    assert_run!(compare_02, r#"
+x = 1 < "1"
+    "#, ExitCode::GenericError);
+
+    assert_run!(range_01, r#"
+r = range(10, 0, -2)
+assert len(r) == 5
+assert 4 in r
+assert 5 not in r
+assert list(r) == [10, 8, 6, 4, 2]
+assert list(reversed(r)) == [2, 4, 6, 8, 10]
+assert r.start == 10
+assert r.step == -2
+assert r == range(10, 1, -2)
+    "#, ExitCode::Ok);
+
+    assert_run!(range_02, r#"
+r = range(1, 10, 0)
+    "#, ExitCode::GenericError);
+
+    assert_run!(iterators_01, r#"
+pairs = list(zip(range(3), "abc"))
+assert pairs == [(0, "a"), (1, "b"), (2, "c")]
+assert list(enumerate("ab", 1)) == [(1, "a"), (2, "b")]
+assert list(map(str, range(3))) == ["0", "1", "2"]
+assert list(filter(None, [0, 1, "", "a"])) == [1, "a"]
+assert list(enumerate(map(len, ["a", "bb"]))) == [(0, 1), (1, 2)]
+    "#, ExitCode::Ok);
+
+    assert_run!(iterators_02, r#"
+assert sorted([3, 1, 2]) == [1, 2, 3]
+assert sorted("cab") == ["a", "b", "c"]
+assert min(3, 1, 2) == 1
+assert max([1, 5, 2]) == 5
+assert sum(range(5)) == 10
+assert sum([0.5, 0.5], 1) == 2.0
+    "#, ExitCode::Ok);
+
+    assert_run!(iterators_03, r#"
+x = min([])
+    "#, ExitCode::GenericError);
+
+    // Errors of the mapped function are raised by whatever exhausts the iterator
+    assert_run!(iterators_04, r#"
+x = list(map(int, ["1", "x"]))
+    "#, ExitCode::GenericError);
+
+    assert_run!(iterators_05, r#"
+def invert(a):
+    return 1 // a
+
+x = tuple(map(invert, [1, 0]))
+    "#, ExitCode::GenericError);
+
+    assert_run!(iterators_06, r#"
+x = list(zip("ab", map(int, ["1", "x"])))
+    "#, ExitCode::GenericError);
+
+    assert_run!(iterators_07, r#"
+x = bytes(map(int, ["1", "x"]))
+    "#, ExitCode::GenericError);
+
+    assert_run!(iterators_08, r#"
+x = sorted([1, "a"])
+    "#, ExitCode::GenericError);
+
+    assert_run!(reflection_01, r#"
+assert isinstance(1, int)
+assert isinstance(True, int)
+assert not isinstance(1, str)
+assert isinstance("a", (int, (list, str)))
+assert issubclass(bool, int)
+assert not issubclass(int, bool)
+assert type(1) == type(2)
+assert isinstance(1, type(2))
+assert type(True).__name__ == "bool"
+    "#, ExitCode::Ok);
+
+    assert_run!(reflection_02, r#"
+assert hasattr(1, "to_bytes")
+assert not hasattr(1, "potato")
+assert getattr(1, "potato", 5) == 5
+assert callable(len)
+assert not callable(1)
+assert repr("snek") == "'snek'"
+assert hash(1) == hash(True)
+assert id(len) == id(len)
+    "#, ExitCode::Ok);
+
+    assert_run!(reflection_03, r#"
+x = isinstance(1, "int")
+    "#, ExitCode::GenericError);
+
+    assert_run!(reflection_04, r#"
+x = getattr(1, "potato")
+    "#, ExitCode::GenericError);
+
+    assert_run!(float_01, r#"
+assert 7.5 - 2 == 5.5
+assert 7.0 // 2 == 3.0
+x = -7.5
+assert x % 2 == 0.5
+y = -2
+assert 7 // y == -4
+assert 7 % y == -1
+assert 1 / 4 == 0.25
+assert 4 ** y == 0.0625
+assert 2.0 ** 3 == 8.0
+assert divmod(7.5, 2) == (3.0, 1.5)
+assert abs(-1.5) == 1.5
+assert round(2.5) == 2
+assert round(2.675, 2) == 2.67
+assert 1.5 < 2
+assert 2 >= 1.5
+    "#, ExitCode::Ok);
+
+    assert_run!(float_02, r#"
+assert repr(0.1 + 0.2) == "0.30000000000000004"
+assert str(1.0) == "1.0"
+assert float("1_000.5") == 1000.5
+assert float("-inf") < 0
+assert float("nan") != float("nan")
+assert float() == 0.0
+assert float(3) == 3.0
+x = 1.5
+is_integer = x.is_integer
+assert is_integer() == False
+as_integer_ratio = x.as_integer_ratio
+assert as_integer_ratio() == (3, 2)
+hex = x.hex
+assert hex() == "0x1.8000000000000p+0"
+    "#, ExitCode::Ok);
+
+    assert_run!(float_03, r#"
+x = 1.0 / 0
+    "#, ExitCode::GenericError);
+
+    assert_run!(float_04, r#"
+x = 1 // 0
+    "#, ExitCode::GenericError);
+
+    assert_run!(float_05, r#"
+x = float("snek")
+    "#, ExitCode::GenericError);
+
+    assert_run!(expr_conditional, r#"
+x = 1 if True else 2
+assert x == 1
+y = 1 if [] else 2
+assert y == 2
+assert ("a" if 0 else "b" if 1 else "c") == "b"
+    "#, ExitCode::Ok);
+
+    assert_run!(logical_short_circuit, r#"
+assert (0 and never_defined) == 0
+assert (1 or never_defined) == 1
+assert (1 and 2) == 2
+assert (0 or None) is None
+    "#, ExitCode::Ok);
+
+    assert_run!(func_return_leaves_frame, r#"
+def early():
+    return 1
+    assert False, 'return did not leave the frame'
+
+assert early() == 1
+    "#, ExitCode::Ok);
+
+    assert_run!(func_implicit_return, r#"
+def noop(a):
+    a
+
+assert noop(1) is None
+    "#, ExitCode::Ok);
+
+    assert_run!(settrace_events, r#"
+events = []
+record = events.append
+
+def local(frame, event, arg):
+    record((event, frame.f_lineno, arg))
+    return local
+
+def tracer(frame, event, arg):
+    record((event, arg))
+    return local
+
+def double(a):
+    return a * 2
+
+settrace = sys.settrace
+settrace(tracer)
+result = double(4)
+settrace(None)
+double(5)
+
+assert result == 8
+assert events == [('call', None), ('line', 14, None), ('return', 14, 8)]
+    "#, ExitCode::Ok);
+
+    assert_run!(settrace_without_local_tracer, r#"
+events = []
+record = events.append
+
+def tracer(frame, event, arg):
+    record(event)
+
+def double(a):
+    return a * 2
+
+settrace = sys.settrace
+settrace(tracer)
+double(4)
+double(5)
+settrace(None)
+
+assert events == ['call', 'call']
+    "#, ExitCode::Ok);
+
+    assert_run!(settrace_error_is_raised, r#"
+def tracer(frame, event, arg):
+    return undefined_name
+
+def noop():
+    return None
+
+settrace = sys.settrace
+settrace(tracer)
+noop()
+    "#, ExitCode::GenericError);
+
+    assert_run!(setprofile_events, r#"
+events = []
+record = events.append
+
+def profiler(frame, event, arg):
+    record(event)
+
+def double(a):
+    return a * 2
+
+setprofile = sys.setprofile
+setprofile(profiler)
+double(4)
+setprofile(None)
+
+assert events == ['c_return', 'call', 'return', 'c_call']
+    "#, ExitCode::Ok);
+
+    fn int(value: i64) -> Instr {
+        Instr(OpCode::LoadConst, Some(Native::Int(rs::Integer::from(value))))
+    }
+
+    fn name(code: OpCode, name: &str) -> Instr {
+        Instr(code, Some(Native::from(name)))
+    }
+
+    fn main_frame_lasti(interpreter: &Interpreter) -> usize {
+        match interpreter.frames.back().unwrap().object().as_ref() {
+            &Type::Frame(ref pyframe) => pyframe.value.0.f_lasti.get(),
+            _ => unreachable!()
+        }
+    }
+
+    #[test]
+    fn backward_jump() {
+        let rt = Runtime::new();
+        let mut interpreter = Interpreter::new(&rt);
+
+        // i = 0
+        // while i != 5:
+        //     i = i + 1
+        let ins = vec![
+            int(0),
+            name(OpCode::StoreName, "i"),
+            name(OpCode::LoadName, "i"),
+            int(5),
+            Instr(OpCode::CompareNotEqual, None),
+            Instr(OpCode::PopJumpIfFalse, Some(Native::Count(11))),
+            name(OpCode::LoadName, "i"),
+            int(1),
+            Instr(OpCode::BinaryAdd, None),
+            name(OpCode::StoreName, "i"),
+            Instr(OpCode::JumpAbsolute, Some(Native::Count(2))),
+        ];
+
+        interpreter.exec(&rt, &ins).unwrap();
+        assert_eq!(interpreter.ns.borrow().get("i").unwrap(), &rt.int(5));
+        assert_eq!(main_frame_lasti(&interpreter), 5);
+    }
+
+    #[test]
+    fn for_iter_loop_block() {
+        let rt = Runtime::new();
+        let mut interpreter = Interpreter::new(&rt);
+        interpreter.ns.borrow_mut().insert(String::from("l"), rt.list(vec![rt.int(1), rt.int(2), rt.int(3)]));
+
+        // for x in l:
+        //     total = total + x
+        let ins = vec![
+            int(0),
+            name(OpCode::StoreName, "total"),
+            Instr(OpCode::SetupLoop, Some(Native::Count(12))),
+            name(OpCode::LoadName, "l"),
+            Instr(OpCode::GetIter, None),
+            Instr(OpCode::ForIter, Some(Native::Count(11))),
+            name(OpCode::LoadName, "total"),
+            Instr(OpCode::BinaryAdd, None),
+            name(OpCode::StoreName, "total"),
+            Instr(OpCode::JumpAbsolute, Some(Native::Count(5))),
+            Instr(OpCode::Nop, None),
+            Instr(OpCode::PopBlock, None),
+        ];
+
+        interpreter.exec(&rt, &ins).unwrap();
+        assert_eq!(interpreter.ns.borrow().get("total").unwrap(), &rt.int(6));
+        assert!(interpreter.stack_view().is_empty());
+        assert!(interpreter.pop_block().is_none());
+    }
+
+    #[test]
+    fn break_loop() {
+        let rt = Runtime::new();
+        let mut interpreter = Interpreter::new(&rt);
+        interpreter.ns.borrow_mut().insert(String::from("l"), rt.list(vec![rt.int(1), rt.int(2), rt.int(3)]));
+
+        // for x in l:
+        //     if x == 2:
+        //         break
+        let ins = vec![
+            Instr(OpCode::SetupLoop, Some(Native::Count(11))),
+            name(OpCode::LoadName, "l"),
+            Instr(OpCode::GetIter, None),
+            Instr(OpCode::ForIter, Some(Native::Count(10))),
+            name(OpCode::StoreName, "x"),
+            name(OpCode::LoadName, "x"),
+            int(2),
+            Instr(OpCode::CompareEqual, None),
+            Instr(OpCode::PopJumpIfFalse, Some(Native::Count(3))),
+            Instr(OpCode::BreakLoop, None),
+            Instr(OpCode::PopBlock, None),
+        ];
+
+        interpreter.exec(&rt, &ins).unwrap();
+        assert_eq!(interpreter.ns.borrow().get("x").unwrap(), &rt.int(2));
+        // The iterator left on the stack by the loop is dropped by the break
+        assert!(interpreter.stack_view().is_empty());
+        assert!(interpreter.pop_block().is_none());
+    }
+
+    #[test]
+    fn inline_cache_attr_invalidated_by_setattr() {
+        let rt = Runtime::new();
+        let mut interpreter = Interpreter::new(&rt);
+        let module = rt.module(rs::None());
+        module.op_setattr(&rt, &rt.str("x"), &rt.int(0)).unwrap();
+        module.op_setattr(&rt, &rt.str("y"), &rt.int(10)).unwrap();
+        interpreter.ns.borrow_mut().insert(String::from("l"), rt.list(vec![rt.int(1), rt.int(2), rt.int(3)]));
+        interpreter.ns.borrow_mut().insert(String::from("m"), module.clone());
+
+        // for v in l:
+        //     setattr(m, 'x', v)
+        //     last = m.x
+        //     y = m.y
+        let ins = vec![
+            Instr(OpCode::SetupLoop, Some(Native::Count(21))),
+            name(OpCode::LoadName, "l"),
+            Instr(OpCode::GetIter, None),
+            Instr(OpCode::ForIter, Some(Native::Count(20))),
+            name(OpCode::StoreName, "v"),
+            name(OpCode::LoadName, "setattr"),
+            name(OpCode::LoadName, "m"),
+            Instr(OpCode::LoadConst, Some(Native::from("x"))),
+            name(OpCode::LoadName, "v"),
+            Instr(OpCode::CallFunction, Some(Native::Count(3))),
+            Instr(OpCode::PopTop, None),
+            name(OpCode::LoadName, "m"),
+            name(OpCode::LoadAttr, "x"),
+            name(OpCode::StoreName, "last"),
+            name(OpCode::LoadName, "m"),
+            name(OpCode::LoadAttr, "y"),
+            name(OpCode::StoreName, "y"),
+            Instr(OpCode::JumpAbsolute, Some(Native::Count(3))),
+            Instr(OpCode::Nop, None),
+            Instr(OpCode::Nop, None),
+            Instr(OpCode::PopBlock, None),
+        ];
+
+        interpreter.exec(&rt, &ins).unwrap();
+        assert_eq!(interpreter.ns.borrow().get("last").unwrap(), &rt.int(3));
+        assert_eq!(interpreter.ns.borrow().get("y").unwrap(), &rt.int(10));
+
+        let frame = interpreter.frames.back().unwrap();
+        let cached = frame.caches.borrow().load_attr(15, &rt, &module);
+        assert_eq!(cached.unwrap(), rt.int(10));
+    }
+
+    #[test]
+    fn inline_caches_shared_by_calls() {
+        let rt = Runtime::new();
+        let mut interpreter = Interpreter::new(&rt);
+        let ins = Compiler::new().compile_str("def f(l):\n    append = l.append\n    append(1)\n").unwrap();
+        interpreter.exec(&rt, &ins).unwrap();
+
+        let f = interpreter.ns.borrow().get("f").cloned().unwrap();
+        let first = rt.default_list();
+        let second = rt.default_list();
+        interpreter.call(&rt, &f, vec![first.clone()]).unwrap();
+        interpreter.call(&rt, &f, vec![second.clone()]).unwrap();
+        assert_eq!(first, rt.list(vec![rt.int(1)]));
+        assert_eq!(second, rt.list(vec![rt.int(1)]));
+
+        // The second call binds `append` from the method cached by the first
+        match f.as_ref() {
+            &Type::Function(ref func) => match func.value.0.callable {
+                FuncType::Code(ref code) => assert!(code.co_caches.hits() > 0),
+                _ => panic!("f is not a python function")
+            },
+            _ => panic!("f is not a function")
+        }
+    }
+
+    #[test]
+    fn raise_stops_execution() {
+        let rt = Runtime::new();
+        let mut interpreter = Interpreter::new(&rt);
+
+        let ins = vec![
+            Instr(OpCode::SetupLoop, Some(Native::Count(3))),
+            name(OpCode::LoadName, "never_defined"),
+            int(1),
+        ];
+
+        assert!(interpreter.exec(&rt, &ins).is_err());
+        assert_eq!(main_frame_lasti(&interpreter), 1);
+        assert!(interpreter.pop_block().is_none());
+    }
+
+    #[test]
+    fn print_expr_displays_repr() {
+        let rt = Runtime::new();
+        let mut interpreter = Interpreter::new(&rt);
+        let buffer = io::text_stream(&rt, io::Sink::Buffer(String::new())).unwrap();
+        let sys = rt.import_module(strings::SYS_MODULE).unwrap();
+        sys.op_setattr(&rt, &rt.str("stdout"), &buffer).unwrap();
+
+        let ins = vec![
+            Instr(OpCode::LoadConst, Some(Native::from("text"))),
+            Instr(OpCode::PrintExpr, None),
+            Instr(OpCode::LoadConst, Some(Native::None)),
+            Instr(OpCode::PrintExpr, None),
+        ];
+
+        interpreter.exec(&rt, &ins).unwrap();
+        assert_eq!(interpreter.ns.borrow().get("_").unwrap(), &rt.str("text"));
+
+        let getvalue = buffer.op_getattr(&rt, &rt.str("getvalue")).unwrap();
+        assert_eq!(interpreter.call(&rt, &getvalue, vec![]).unwrap(), rt.str("'text'\n"));
+    }
+
+    #[test]
+    fn eval_in_new_frame() {
+        let rt = Runtime::new();
+        let mut interpreter = Interpreter::new(&rt);
+
+        interpreter.eval(&rt, "x = 2").unwrap();
+        assert_eq!(interpreter.ns.borrow().get("x").unwrap(), &rt.int(2));
+
+        assert!(interpreter.eval(&rt, "undefined_name").is_err());
+        assert!(interpreter.eval(&rt, "x = )").is_err());
+        assert_eq!(interpreter.depth(), 1);
+    }
+
+    #[test]
+    fn line_events_once_per_line() {
+        let rt = Runtime::new();
+        let mut interpreter = Interpreter::new(&rt);
+        let ins = vec![
+            Instr(OpCode::SetLineNumber, Some(Native::Count(1))),
+            int(1),
+            Instr(OpCode::PopTop, None),
+        ];
+
+        interpreter.exec(&rt, &ins).unwrap();
+        let frame = interpreter.frames.back().unwrap();
+        assert_eq!(frame.start_line(), None);
+
+        frame.jump(0);
+        assert_eq!(frame.start_line(), Some(1));
+        assert_eq!(frame.start_line(), None);
+    }
+
+    #[test]
+    fn completions() {
+        let rt = Runtime::new();
+        let mut interpreter = Interpreter::new(&rt);
+        let module = rt.module(rs::None());
+        module.op_setattr(&rt, &rt.str("value"), &rt.int(1)).unwrap();
+        module.op_setattr(&rt, &rt.str("other"), &rt.int(2)).unwrap();
+        interpreter.ns.borrow_mut().insert(String::from("m"), module);
+
+        assert_eq!(interpreter.completions("su"), vec!["sum", "sys"]);
+        assert_eq!(interpreter.completions("m.v"), vec!["m.value"]);
+        assert!(interpreter.completions("undefined.v").is_empty());
+    }
 }
\ No newline at end of file
@@ -3,11 +3,14 @@ use std::borrow::Borrow;
 use std::cell::{Ref, Cell, RefCell};
 use std::collections::HashMap;
 use std::collections::vec_deque::VecDeque;
+use std::rc::Rc;
 use std::convert::From;
 use std::fs::File;
 use std::io::{self, Read, Write};
 use std::marker::Sync;
 use std::ops::{Deref};
+use std::path::Path;
+use std::sync::PoisonError;
 
 use fringe::generator::Yielder;
 use fringe::{OsStack, Generator};
@@ -18,17 +21,21 @@ use rustyline::Config as RLConfig;
 use rustyline::error::ReadlineError;
 use rustyline;
 
-use python_ast::fmt;
-
-use ::api::result::Error;
+use ::api::result::{Error, ErrorType};
 use ::api::result::ObjectResult;
 use ::api::RtObject;
 use ::compiler::Compiler;
-use ::compiler::fmt::bincode as fmt_bincode;
+use ::compiler::cache;
+use ::compiler::fmt;
+use ::compiler::optimizer;
 use ::modules::builtins::{Type, logical_and, logical_or};
+use ::modules::traceback::{self, Traceback};
 use ::resources::strings;
-use ::runtime::config::Mode;
-use ::runtime::Interpreter;
+use ::runtime::config::{Dump, Mode, Profile};
+use ::runtime::coverage::{Coverage, SharedCoverage};
+use ::runtime::profiler::{Profiler, SharedProfiler, Sort};
+use ::runtime::{Debugger, Interpreter};
+use ::runtime::repl::{self, Completer};
 use ::runtime::{OpCode, Runtime};
 use ::system::primitives as rs;
 use ::system::primitives::SignatureBuilder;
@@ -41,54 +48,126 @@ use ::system::{Argv, ExitCode, MainFnRef, MainFn, SharedMainFnRef};
 /// 2. https://youtu.be/tHnA94-hTC8?t=2m47s
 #[inline(always)]
 fn print_banner() {
-    info!("\n{}", strings::BANNER2);
-    info!("{}", strings::VERSION);
-    info!("{}", strings::BUILD);
+    let stderr = io::stderr();
+    let mut stderr = stderr.lock();
+    let _ = writeln!(stderr, "\n{}", strings::BANNER2);
+    let _ = writeln!(stderr, "{}", strings::VERSION);
+    let _ = writeln!(stderr, "{}", strings::BUILD);
+}
+
+
+/// Report an uncaught error and where it happened on `sys.stderr`, which is kept free
+/// of log records so it can be read by the user or captured separately from stdout.
+/// The traceback is kept for `traceback.format_exc()`.
+fn print_error(rt: &Runtime, interpreter: &Interpreter, err: &Error) {
+    let report = interpreter.traceback(err);
+    rt.set_last_traceback(report.clone());
+
+    // `sys.stderr` can be replaced with anything, when writing to it fails both
+    // errors go to the process' stderr.
+    if let Err(print_err) = traceback::print(rt, &report) {
+        let chained = Traceback::new(Vec::new(), print_err).with_context(report);
+        let _ = write!(io::stderr(), "{}", traceback::format(rt, &chained));
+    }
+
+    err.log();
+}
+
+
+/// The exit status requested by `sys.exit()` if `err` is a `SystemExit`, its
+/// message is written to `sys.stderr` like CPython does.
+fn exit_status(rt: &Runtime, err: &Error) -> Option<i64> {
+    let status = match err.0 {
+        ErrorType::SystemExit(status) => status,
+        _ => return None
+    };
+
+    if !err.1.is_empty() {
+        if let Err(_) = traceback::write_stderr(rt, &format!("{}\n", err.1)) {
+            let _ = writeln!(io::stderr(), "{}", err.1);
+        }
+    }
+
+    Some(status)
+}
+
+
+/// Write the `report` of what `profiler` saw to stderr, where it does not mix
+/// with the output of the program.
+fn print_profile(report: Profile, profiler: &SharedProfiler) {
+    let mut profiler = profiler.lock().unwrap_or_else(PoisonError::into_inner);
+    profiler.finish();
+
+    let text = match report {
+        Profile::Stats => profiler.format_stats(Sort::Cumulative),
+        Profile::Collapsed => profiler.format_collapsed(),
+        Profile::Opcodes => profiler.format_opcodes(),
+    };
+
+    let _ = write!(io::stderr(), "{}", text);
+}
+
+
+/// Merge the line coverage of the program into the report at `path`
+fn save_coverage(path: &str, coverage: &SharedCoverage) -> Result<(), i64> {
+    let coverage = coverage.lock().unwrap_or_else(PoisonError::into_inner);
+
+    match coverage.save(Path::new(path)) {
+        Ok(_) => Ok(()),
+        Err(err) => {
+            let _ = writeln!(io::stderr(), "Unable to write the coverage report to {}: {}", path, err);
+            Err(ExitCode::GenericError as i64)
+        }
+    }
 }
 
 
 /// Create the closure with the `MainFn` signature that captures a copy
 /// of the arguments sent to `create_python_main`. The closure will try to use
-/// the first argument as the file to load.
-pub fn create_python_main(mode: Mode, args: Argv) -> Box<MainFn> {
+/// the first argument as the file to load. When `optimize` is greater than 0
+/// the compiled code is run through the peephole optimizer first. With `debug`
+/// the debugger stops before the first line is run, with `profile` the program
+/// is profiled and the report printed once it is done, and with `coverage` the
+/// lines that ran are added to the report at that path.
+pub fn create_python_main(mode: Mode, args: Argv, optimize: usize, debug: bool,
+                          profile: Option<Profile>, coverage: Option<String>) -> Box<MainFn> {
 
     let myargs: Box<Vec<String>> = Box::new(args.iter().map(|s| s.to_string()).collect());
 
     Box::new(move |rt: &Runtime| -> i64 {
-        let text: String = match (mode.clone(), myargs.get(0)) {
-            (Mode::Command(cmd), _) => cmd.clone(),
+        let mut compiler = Compiler::new();
+        let mut interpreter = Interpreter::new(&rt);
+
+        let filename = match (mode.clone(), myargs.get(0)) {
+            (Mode::File, Some(path)) => path.trim_right_matches(&format!(".{}", strings::COMPILED_SOURCE_EXT)).to_string(),
+            _ => String::from(strings::SOURCE_STRING)
+        };
+
+        // With coverage, the source is kept for the statement lines of the report
+        let (compiled, source) = match (mode.clone(), myargs.get(0)) {
+            (Mode::Command(cmd), _) => {
+                rt.add_source(&filename, &cmd);
+                (compiler.compile_str(&cmd), coverage.as_ref().map(|_| cmd.clone()))
+            },
             (Mode::Module(_), _) => {
                 error!("Not Implemented"; "mode" => "-m <module>");
                 return ExitCode::NotImplemented as i64
             },
+            (Mode::File, Some(path)) if path.ends_with(&format!(".{}", strings::COMPILED_SOURCE_EXT)) => {
+                let source = coverage.as_ref()
+                    .and_then(|_| read_file(&filename).ok())
+                    .map(|source| String::from_utf8_lossy(&source).to_string());
+
+                (cache::read(&path), source)
+            },
             (Mode::File, Some(path)) => {
-                match File::open(&path) {
-                    // TODO: {T100} Check size so we aren't going ham and trying to read a file the size
-                    // of memory or something?
-                    Ok(ref mut file) => {
-                        let mut buf: Vec<u8> = Vec::new();
-                        match file.read_to_end(&mut buf) {
-                            Err(err) => {
-                                debug!("{:?}", err);
-
-                                return match err.raw_os_error() {
-                                    Some(code) => code as i64,
-                                    _ => ExitCode::GenericError as i64
-                                };
-                            },
-                            _ => {}
-                        };
-                        // TODO: {T100} Is using lossy here a good idea?
-                        String::from_utf8_lossy(&buf).to_string()
-                    },
-                    Err(err) => {
-                        debug!("{:?}", err);
-                        return match err.raw_os_error() {
-                            Some(code) => code as i64,
-                            _ => ExitCode::GenericError as i64
-                        }
-                    }
-                }
+                let source = match read_file(&path) {
+                    Ok(source) => source,
+                    Err(code) => return code
+                };
+
+                let text = coverage.as_ref().map(|_| String::from_utf8_lossy(&source).to_string());
+                (cache::compile_file(&path, &source), text)
             },
             _ => {
                 debug!("Unable to determine input type");
@@ -96,54 +175,171 @@ pub fn create_python_main(mode: Mode, args: Argv) -> Box<MainFn> {
             }
         };
 
-        let mut compiler = Compiler::new();
-        let mut interpreter = Interpreter::new(&rt);
-
-        let ins = match compiler.compile_str(&text) {
+        let ins = match compiled {
             Ok(ins) => ins,
-            Err(_) => {
-                error!("SyntaxError: Unable to compile input");
+            Err(err) => {
+                let _ = writeln!(io::stderr(), "{}", err);
+                error!("SyntaxError: Unable to compile input"; "message" => err.1);
                 return ExitCode::SyntaxError as i64
             },
         };
 
-        if let Some(path) = myargs.get(0) {
-            let outpath = format!("{}.{}", path, strings::COMPILED_SOURCE_EXT);
-
-            match File::create(&outpath) {
-                Ok(ref mut file) => {
-                    match file.write(&fmt_bincode(&ins)) {
-                        Err(err) => {
-                            debug!("{:?}", err);
-                        }
-                        _ => {}
-                    };
-                },
-                Err(err) => {
-                    debug!("{:?}", err);
-                }
-            }
+        if debug {
+            interpreter.set_debugger(Debugger::stepping());
+        }
+
+        let profiler = profile.map(|_| Profiler::shared());
+        if profiler.is_some() {
+            rt.set_profiler(profiler.clone());
+        }
+
+        let code = rs::Code::module(&filename, ins.to_vec());
+
+        let covered = coverage.as_ref().map(|_| Coverage::shared());
+        if let Some(ref covered) = covered {
+            let mut lines = covered.lock().unwrap_or_else(PoisonError::into_inner);
+            match source.map(|source| compiler.statement_lines(&filename, &source)) {
+                Some(Ok(statements)) => lines.add_lines(&filename, statements),
+                _ => lines.add_code(&code)
+            };
+            rt.set_coverage(Some(covered.clone()));
+        }
+
+        let code = match optimize {
+            0 => code,
+            _ => optimizer::optimize(&rt, &code)
+        };
+
+        let result = interpreter.exec_code(&rt, &code);
+
+        if profiler.is_some() {
+            rt.set_profiler(None);
         }
 
-        let result = interpreter.exec(&rt, &(*ins));
+        if covered.is_some() {
+            rt.set_coverage(None);
+        }
+
+        // Output written without a trailing newline stays in the stdout buffer
+        if let Err(err) = io::stdout().flush() {
+            error!("Error Flushing STDOUT: {:?}", err);
+        }
 
         let code = match result {
             Ok(_) => {
                 ExitCode::Ok as i64
             },
-            Err(err) => {
-                error!("{}", interpreter.format_traceback());
-                error!("{:?}Error", err.0; "message" => err.1.clone());
-                ExitCode::GenericError as i64
+            Err(err) => match exit_status(&rt, &err) {
+                Some(status) => status,
+                None => {
+                    print_error(&rt, &interpreter, &err);
+                    ExitCode::GenericError as i64
+                }
             }
         };
 
+        if let (Some(report), Some(profiler)) = (profile, profiler) {
+            print_profile(report, &profiler);
+        }
+
+        if let (&Some(ref path), Some(covered)) = (&coverage, covered) {
+            if let Err(status) = save_coverage(path, &covered) {
+                if code == ExitCode::Ok as i64 {
+                    return status
+                }
+            }
+        }
+
         code
     })
 }
 
 
-/// Entry point for the interactive repl mode of the interpreter
+/// Read the whole file at `path`, errors are the exit code of the process
+fn read_file(path: &str) -> Result<Vec<u8>, i64> {
+    // TODO: {T100} Check size so we aren't going ham and trying to read a file the size
+    // of memory or something?
+    let mut buf: Vec<u8> = Vec::new();
+    match File::open(&path).and_then(|mut file| file.read_to_end(&mut buf)) {
+        Ok(_) => Ok(buf),
+        Err(err) => {
+            debug!("{:?}", err);
+            match err.raw_os_error() {
+                Some(code) => Err(code as i64),
+                _ => Err(ExitCode::GenericError as i64)
+            }
+        }
+    }
+}
+
+
+/// Create the closure with the `MainFn` signature that prints the `dump` of the
+/// input given by `mode` and `args` to stdout instead of running it. Compiled
+/// `.rsc` files only have bytecode to show.
+pub fn create_dump_main(mode: Mode, args: Argv, dump: Dump, optimize: usize) -> Box<MainFn> {
+
+    let myargs: Box<Vec<String>> = Box::new(args.iter().map(|s| s.to_string()).collect());
+
+    Box::new(move |rt: &Runtime| -> i64 {
+        let mut compiler = Compiler::new();
+
+        let (filename, source) = match (mode.clone(), myargs.get(0)) {
+            (Mode::Command(cmd), _) => (String::from(strings::SOURCE_STRING), cmd),
+            (Mode::File, Some(path)) if path.ends_with(&format!(".{}", strings::COMPILED_SOURCE_EXT)) => {
+                if dump != Dump::Bytecode {
+                    let _ = writeln!(io::stderr(), "{}: compiled files have no {:?} to dump", path, dump);
+                    return ExitCode::GenericError as i64
+                }
+
+                (path.clone(), String::new())
+            },
+            (Mode::File, Some(path)) => match read_file(&path) {
+                Ok(source) => (path.clone(), String::from_utf8_lossy(&source).to_string()),
+                Err(code) => return code
+            },
+            _ => {
+                let _ = writeln!(io::stderr(), "Nothing to dump, give a file or -c <cmd>");
+                return ExitCode::NotImplemented as i64
+            }
+        };
+
+        let dumped = match dump {
+            Dump::Tokens => compiler.dump_tokens(&source),
+            Dump::Ast => compiler.dump_ast(&filename, &source),
+            Dump::Bytecode => {
+                let compiled = match filename.ends_with(&format!(".{}", strings::COMPILED_SOURCE_EXT)) {
+                    true => cache::read(&filename),
+                    false => compiler.compile_source(&filename, &source)
+                };
+
+                compiled.map(|ins| {
+                    let code = rs::Code::module(&filename, ins.to_vec());
+                    match optimize {
+                        0 => fmt::code(&code),
+                        _ => fmt::code(&optimizer::optimize(&rt, &code))
+                    }
+                })
+            }
+        };
+
+        match dumped {
+            Ok(text) => {
+                println!("{}", text.trim_right());
+                ExitCode::Ok as i64
+            },
+            Err(err) => {
+                let _ = writeln!(io::stderr(), "{}", err);
+                err.log();
+                ExitCode::SyntaxError as i64
+            }
+        }
+    })
+}
+
+
+/// Entry point for the interactive repl mode of the interpreter. Input is read until
+/// it is a complete statement, compound statements like `def` end with a blank line.
+/// The value of an expression statement is displayed and kept as `_`.
 pub fn python_main_interactive(rt: &Runtime) -> i64 {
 
     let config = RLConfig::builder()
@@ -151,9 +347,18 @@ pub fn python_main_interactive(rt: &Runtime) -> i64 {
         .completion_type(CompletionType::List)
         .build();
 
-    let mut rl = rustyline::Editor::<()>::with_config(config);
-    let mut interpreter = Interpreter::new(&rt);
+    let interpreter = Rc::new(RefCell::new(Interpreter::new(&rt)));
+    let mut rl = rustyline::Editor::<Completer>::with_config(config);
+    rl.set_completer(Some(Completer::new(&interpreter)));
+
+    let history = repl::history_path();
+    if let Some(ref path) = history {
+        // There is no history before the first session
+        let _ = rl.load_history(path);
+    }
+
     let mut prompt_count = 0;
+    let mut status = ExitCode::Ok as i64;
 
     print_banner();
 
@@ -165,51 +370,79 @@ pub fn python_main_interactive(rt: &Runtime) -> i64 {
 
 
         prompt_count += 1;
-        info!("In[{}] {} ", prompt_count, strings::PROMPT);
+        let prompt = format!("In[{}] {} ", prompt_count, strings::PROMPT.trim());
+        let continuation = format!("{:>width$} ", "...", width = prompt.chars().count() - 1);
+
+        let mut lines: Vec<String> = Vec::new();
+        let mut compiler = Compiler::new();
+
+        let compiled = loop {
+            let line = match rl.readline(if lines.is_empty() { &prompt } else { &continuation }) {
+                Ok(line) => line,
+                Err(ReadlineError::Interrupted) if !lines.is_empty() => {
+                    // Drop the statement being typed
+                    continue 'repl
+                },
+                Err(ReadlineError::Interrupted) => {
+                    warn!("CTRL-C");
+                    break 'repl;
+                }
+                Err(ReadlineError::Eof) => {
+                    warn!("CTRL-D");
+                    break 'repl;
+                }
+                _ => continue 'repl
+            };
+
+            let blank = line.trim().is_empty();
+            if blank && lines.is_empty() {
+                continue
+            }
 
-        let text = match rl.readline(&"") {
-            Ok(line) => {
+            if !blank {
                 rl.add_history_entry(line.as_ref());
-                line
-            },
-            Err(ReadlineError::Interrupted) => {
-                warn!("CTRL-C");
-                break;
             }
-            Err(ReadlineError::Eof) => {
-                warn!("CTRL-D");
-                break;
+
+            lines.push(line);
+
+            // Compound statements go on until a blank line even when they already parse
+            if !blank && lines[0].trim_right().ends_with(':') {
+                continue
             }
-            _ => continue
-        };
 
+            let source = format!("{}\n", lines.join("\n"));
+            match compiler.compile_interactive(&source) {
+                Err(_) if compiler.is_incomplete() && !blank => continue,
+                result => break result
+            }
+        };
 
-        let mut compiler = Compiler::new();
-        let ins = match compiler.compile_str(&text) {
+        let ins = match compiled {
             Ok(ins) => ins,
             Err(err) => {
+                let _ = writeln!(io::stderr(), "{}", err);
                 err.log();
                 continue 'repl
             },
         };
 
-        'process_ins: for i in ins.iter() {
-            match interpreter.exec_one(rt, &i) {
-                Some(Err(err)) => {
-
-                    error!("{}", interpreter.format_traceback());
-                    err.log();
-                    interpreter.clear_traceback();
-                    break 'process_ins
-                },
-
-                _ => continue 'process_ins
+        let result = interpreter.borrow_mut().exec_code(rt, &rs::Code::module(strings::SOURCE_STDIN, ins.to_vec()));
+        if let Err(err) = result {
+            if let Some(exit) = exit_status(&rt, &err) {
+                status = exit;
+                break 'repl;
             }
 
+            print_error(&rt, &interpreter.borrow(), &err);
+            interpreter.borrow_mut().clear_traceback();
         }
+    }
 
-        interpreter.log_state();
+    if let Some(ref path) = history {
+        if let Err(err) = rl.save_history(path) {
+            warn!("Unable to save history"; "path" => format!("{}", path.display()), "error" => format!("{:?}", err));
+        }
     }
 
-    ExitCode::Ok as i64
+    status
 }
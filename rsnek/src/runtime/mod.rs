@@ -1,14 +1,26 @@
 //! The business end of the code base.
 //!
+mod debugger;
 mod interpreter;
 mod opcode;
 mod runtime;
 mod main;
+mod repl;
+mod trace;
 
 pub mod config;
+pub mod coverage;
+pub mod gc;
+pub mod inline_cache;
+pub mod profiler;
+pub mod scheduler;
 pub mod traits;
 
-pub use self::interpreter::Interpreter;
-pub use self::config::{Logging, Config, Mode};
+pub use self::debugger::{Breakpoint, Debugger};
+pub use self::interpreter::{Interpreter, binary_op, unary_op};
+pub use self::config::{Logging, LogFormat, LogLevel, LogTarget, Config, Dump, Mode, Profile};
+pub use self::coverage::{Coverage, SharedCoverage};
 pub use self::opcode::OpCode;
+pub use self::profiler::{Profiler, SharedProfiler};
 pub use self::runtime::Runtime;
+pub use self::trace::Event;
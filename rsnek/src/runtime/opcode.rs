@@ -2,7 +2,7 @@
 
 /// OpCodes for the interpreter. 1..1024 are reserved for CPython opcodes
 /// while 1024.. are for rsnek specific opcodes.
-#[derive(Debug, Hash, Clone, Copy, Eq, PartialEq, Serialize)]
+#[derive(Debug, Hash, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
 pub enum OpCode {
     PopTop                   =   1,
     RotTwo                   =   2,
@@ -136,5 +136,32 @@ pub enum OpCode {
     CompareIsNot             = 1036,
 
     SetLineNumber            = 2048,
+
+    /// Pseudo instruction the compiler uses to mark a jump target. Labels are
+    /// resolved to instruction offsets and removed before the code is executed.
+    Label                    = 2049,
+}
+
+
+impl OpCode {
+    /// True if the argument of the instruction is the offset of another
+    /// instruction in the same code object.
+    ///
+    /// Note that unlike CPython all jump targets are absolute, including
+    /// `JumpForward`.
+    pub fn is_jump(&self) -> bool {
+        match *self {
+            OpCode::JumpForward         |
+            OpCode::JumpAbsolute        |
+            OpCode::JumpIfFalseOrPop    |
+            OpCode::JumpIfTrueOrPop     |
+            OpCode::PopJumpIfFalse      |
+            OpCode::PopJumpIfTrue       |
+            OpCode::ForIter             |
+            OpCode::SetupLoop           |
+            OpCode::ContinueLoop        => true,
+            _ => false
+        }
+    }
 }
 
@@ -0,0 +1,378 @@
+//! Deterministic profiler for python code
+//!
+//! The interpreter tells the profiler of the runtime when each function is called
+//! and returns, and which opcodes it runs, while one is set with `set_profiler`.
+//! `rsnek --profile=<report>` profiles the whole program and the `cProfile` module
+//! a part of it. There are three reports:
+//!
+//!  - `stats`, the report of a plain `--profile`: calls, own (`tottime`) and cumulative
+//!    time per function like `pstats`
+//!  - `collapsed`: own time in microseconds per call stack, one stack per line with
+//!     the frames separated by `;`, which is the input of flame graph tools
+//!  - `opcodes`: the number of times each opcode was run
+//!
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use ::runtime::OpCode;
+
+
+/// The profiler shared by the runtime and the object that started it
+pub type SharedProfiler = Arc<Mutex<Profiler>>;
+
+
+/// Where the time goes, a python function is identified by the file and first
+/// line of its code, a native function by its module and name.
+#[derive(Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub struct Function {
+    filename: String,
+    line: usize,
+    name: String,
+}
+
+
+impl Function {
+    pub fn new(filename: &str, line: usize, name: &str) -> Self {
+        Function {
+            filename: String::from(filename),
+            line: line,
+            name: String::from(name),
+        }
+    }
+
+    /// Native functions are shown like `cProfile` shows builtins
+    pub fn native(module: &str, name: &str) -> Self {
+        Function::new("~", 0, &format!("{{built-in method {}.{}}}", module, name))
+    }
+}
+
+
+impl fmt::Display for Function {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}({})", self.filename, self.line, self.name)
+    }
+}
+
+
+/// Totals of a function
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct Stats {
+    pub calls: usize,
+    /// Time spent in the function itself
+    pub own: Duration,
+    /// Time spent in the function and what it called, recursive calls count once
+    pub cumulative: Duration,
+}
+
+
+/// Order of the rows of the `stats` report
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub enum Sort {
+    Calls,
+    Cumulative,
+    Time,
+    Name,
+}
+
+
+impl Sort {
+    /// The sort keys `pstats` takes, -1 is the standard name like it is there
+    pub fn parse(key: &str) -> Option<Sort> {
+        match key {
+            "calls" | "ncalls"                      => Some(Sort::Calls),
+            "cumulative" | "cumtime"                => Some(Sort::Cumulative),
+            "time" | "tottime"                      => Some(Sort::Time),
+            "name" | "stdname" | "filename" | "-1"  => Some(Sort::Name),
+            _ => None
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        match *self {
+            Sort::Calls         => "call count",
+            Sort::Cumulative    => "cumulative time",
+            Sort::Time          => "internal time",
+            Sort::Name          => "standard name",
+        }
+    }
+}
+
+
+#[derive(Debug)]
+struct Call {
+    function: Function,
+    start: Instant,
+    /// Time spent in the functions it called
+    children: Duration,
+}
+
+
+#[derive(Debug)]
+pub struct Profiler {
+    stats: HashMap<Function, Stats>,
+    calls: Vec<Call>,
+    stacks: HashMap<String, Duration>,
+    opcodes: HashMap<OpCode, usize>,
+}
+
+
+impl Profiler {
+    pub fn new() -> Self {
+        Profiler {
+            stats: HashMap::new(),
+            calls: Vec::new(),
+            stacks: HashMap::new(),
+            opcodes: HashMap::new(),
+        }
+    }
+
+    pub fn shared() -> SharedProfiler {
+        Arc::new(Mutex::new(Profiler::new()))
+    }
+
+    /// `function` was called
+    pub fn enter(&mut self, function: Function) {
+        self.calls.push(Call {
+            function: function,
+            start: Instant::now(),
+            children: Duration::new(0, 0),
+        });
+    }
+
+    /// The function of the last `enter` returned. Functions that were called before
+    /// the profiler was started return without having entered and are left out.
+    pub fn exit(&mut self) {
+        let call = match self.calls.pop() {
+            Some(call) => call,
+            None => return
+        };
+
+        let elapsed = call.start.elapsed();
+        let own = elapsed.checked_sub(call.children).unwrap_or(Duration::new(0, 0));
+        let recursive = self.calls.iter().any(|outer| outer.function == call.function);
+
+        if let Some(caller) = self.calls.last_mut() {
+            caller.children += elapsed;
+        }
+
+        let stack = self.calls.iter()
+            .map(|outer| outer.function.to_string())
+            .chain(Some(call.function.to_string()))
+            .collect::<Vec<_>>()
+            .join(";");
+        *self.stacks.entry(stack).or_insert(Duration::new(0, 0)) += own;
+
+        let stats = self.stats.entry(call.function).or_insert(Stats::default());
+        stats.calls += 1;
+        stats.own += own;
+        if !recursive {
+            stats.cumulative += elapsed;
+        }
+    }
+
+    /// Return from every function that was entered, used when profiling stops
+    pub fn finish(&mut self) {
+        while !self.calls.is_empty() {
+            self.exit();
+        }
+    }
+
+    /// `opcode` was run
+    pub fn count(&mut self, opcode: OpCode) {
+        *self.opcodes.entry(opcode).or_insert(0) += 1;
+    }
+
+    pub fn stats(&self) -> &HashMap<Function, Stats> {
+        &self.stats
+    }
+
+    pub fn opcode_count(&self, opcode: OpCode) -> usize {
+        self.opcodes.get(&opcode).cloned().unwrap_or(0)
+    }
+
+    /// Forget everything that was profiled so far
+    pub fn clear(&mut self) {
+        *self = Profiler::new();
+    }
+
+    /// The `stats` report, in the layout of `pstats.Stats.print_stats()`
+    pub fn format_stats(&self, sort: Sort) -> String {
+        let mut rows: Vec<(&Function, &Stats)> = self.stats.iter().collect();
+        rows.sort_by(|a, b| match sort {
+            Sort::Calls => b.1.calls.cmp(&a.1.calls),
+            Sort::Cumulative => b.1.cumulative.cmp(&a.1.cumulative),
+            Sort::Time => b.1.own.cmp(&a.1.own),
+            Sort::Name => Ordering::Equal,
+        }.then_with(|| a.0.cmp(b.0)));
+
+        let calls: usize = rows.iter().map(|&(_, stats)| stats.calls).sum();
+        let total = rows.iter().fold(Duration::new(0, 0), |total, &(_, stats)| total + stats.own);
+
+        let mut text = format!("         {} function calls in {:.3} seconds\n\n", calls, seconds(total));
+        text.push_str(&format!("   Ordered by: {}\n\n", sort.description()));
+        text.push_str("   ncalls  tottime  percall  cumtime  percall filename:lineno(function)\n");
+
+        for (function, stats) in rows.into_iter() {
+            let own = seconds(stats.own);
+            let cumulative = seconds(stats.cumulative);
+            let count = stats.calls as f64;
+
+            text.push_str(&format!("{:>9} {:>8.3} {:>8.3} {:>8.3} {:>8.3} {}\n",
+                                   stats.calls, own, own / count, cumulative, cumulative / count,
+                                   function));
+        }
+        text
+    }
+
+    /// The `collapsed` report, sorted by stack
+    pub fn format_collapsed(&self) -> String {
+        let mut stacks: Vec<(&String, &Duration)> = self.stacks.iter().collect();
+        stacks.sort();
+
+        stacks.into_iter()
+            .map(|(stack, own)| format!("{} {}\n", stack, micros(*own)))
+            .collect::<Vec<_>>()
+            .concat()
+    }
+
+    /// The `opcodes` report, most run first
+    pub fn format_opcodes(&self) -> String {
+        let mut counts: Vec<(String, usize)> = self.opcodes.iter()
+            .map(|(opcode, count)| (format!("{:?}", opcode), *count))
+            .collect();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        let total: usize = counts.iter().map(|&(_, count)| count).sum();
+        let mut text = format!("         {} instructions executed\n\n", total);
+        text.push_str("    count  opcode\n");
+
+        for (opcode, count) in counts.into_iter() {
+            text.push_str(&format!("{:>9}  {}\n", count, opcode));
+        }
+        text
+    }
+}
+
+
+impl Default for Profiler {
+    fn default() -> Self {
+        Profiler::new()
+    }
+}
+
+
+fn seconds(duration: Duration) -> f64 {
+    duration.as_secs() as f64 + duration.subsec_nanos() as f64 / 1e9
+}
+
+
+fn micros(duration: Duration) -> u64 {
+    duration.as_secs() * 1_000_000 + (duration.subsec_nanos() / 1_000) as u64
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn function(name: &str) -> Function {
+        Function::new("script.py", 1, name)
+    }
+
+    #[test]
+    fn counts_calls() {
+        let mut profiler = Profiler::new();
+        profiler.enter(function("main"));
+        profiler.enter(function("f"));
+        profiler.exit();
+        profiler.enter(function("f"));
+        profiler.enter(Function::native("builtins", "len"));
+        profiler.exit();
+        profiler.exit();
+        profiler.exit();
+
+        // Returning from a function that was called before the profiler started
+        profiler.exit();
+
+        let stats = profiler.stats();
+        assert_eq!(stats[&function("main")].calls, 1);
+        assert_eq!(stats[&function("f")].calls, 2);
+        assert_eq!(stats[&Function::native("builtins", "len")].calls, 1);
+
+        let main = stats[&function("main")];
+        assert!(main.cumulative >= stats[&function("f")].cumulative);
+        assert!(main.cumulative >= main.own);
+    }
+
+    #[test]
+    fn recursion_counts_cumulative_once() {
+        let mut profiler = Profiler::new();
+        profiler.enter(function("f"));
+        profiler.enter(function("f"));
+        profiler.exit();
+        profiler.exit();
+
+        let stats = profiler.stats()[&function("f")];
+        assert_eq!(stats.calls, 2);
+        assert!(stats.cumulative >= stats.own);
+    }
+
+    #[test]
+    fn finish_returns_from_everything() {
+        let mut profiler = Profiler::new();
+        profiler.enter(function("main"));
+        profiler.enter(function("f"));
+        profiler.finish();
+
+        assert_eq!(profiler.stats().len(), 2);
+        profiler.enter(function("g"));
+        profiler.exit();
+        assert_eq!(profiler.stats()[&function("g")].calls, 1);
+    }
+
+    #[test]
+    fn reports() {
+        let mut profiler = Profiler::new();
+        profiler.enter(function("main"));
+        profiler.enter(Function::native("builtins", "len"));
+        profiler.exit();
+        profiler.exit();
+        profiler.count(OpCode::LoadName);
+        profiler.count(OpCode::LoadName);
+        profiler.count(OpCode::CallFunction);
+
+        let stats = profiler.format_stats(Sort::Calls);
+        assert!(stats.starts_with("         2 function calls in "), "{}", stats);
+        assert!(stats.contains("   Ordered by: call count\n"), "{}", stats);
+        assert!(stats.contains(" script.py:1(main)\n"), "{}", stats);
+        assert!(stats.contains(" ~:0({built-in method builtins.len})\n"), "{}", stats);
+
+        let collapsed = profiler.format_collapsed();
+        let stacks: Vec<&str> = collapsed.lines()
+            .map(|line| line.rsplitn(2, ' ').nth(1).unwrap())
+            .collect();
+        assert_eq!(stacks, vec![
+            "script.py:1(main)",
+            "script.py:1(main);~:0({built-in method builtins.len})",
+        ]);
+
+        assert_eq!(profiler.opcode_count(OpCode::LoadName), 2);
+        assert_eq!(profiler.format_opcodes(), [
+            "         3 instructions executed",
+            "",
+            "    count  opcode",
+            "        2  LoadName",
+            "        1  CallFunction",
+            ""].join("\n"));
+    }
+
+    #[test]
+    fn parse_sort() {
+        assert_eq!(Sort::parse("cumtime"), Some(Sort::Cumulative));
+        assert_eq!(Sort::parse("-1"), Some(Sort::Name));
+        assert_eq!(Sort::parse("bogus"), None);
+    }
+}
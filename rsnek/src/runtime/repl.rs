@@ -0,0 +1,66 @@
+//! Line editing support of the interactive repl
+//!
+use std::cell::RefCell;
+use std::env;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use rustyline;
+
+use ::resources::strings;
+use ::runtime::Interpreter;
+
+
+/// Tab completion of the names in the namespace of the repl's interpreter and,
+/// after a `.`, the attributes of the object named before it.
+pub struct Completer {
+    interpreter: Rc<RefCell<Interpreter>>,
+}
+
+
+impl Completer {
+    pub fn new(interpreter: &Rc<RefCell<Interpreter>>) -> Self {
+        Completer {
+            interpreter: interpreter.clone(),
+        }
+    }
+}
+
+
+impl rustyline::completion::Completer for Completer {
+    fn complete(&self, line: &str, pos: usize) -> rustyline::Result<(usize, Vec<String>)> {
+        let start = word_start(line, pos);
+        Ok((start, self.interpreter.borrow().completions(&line[start..pos])))
+    }
+}
+
+
+/// Byte offset of the dotted name that ends at `pos`
+fn word_start(line: &str, pos: usize) -> usize {
+    line[..pos].char_indices()
+        .rev()
+        .take_while(|&(_, c)| c.is_alphanumeric() || c == '_' || c == '.')
+        .last()
+        .map(|(idx, _)| idx)
+        .unwrap_or(pos)
+}
+
+
+/// Where the repl history is kept between sessions, `None` without a home directory
+pub fn history_path() -> Option<PathBuf> {
+    env::home_dir().map(|home| home.join(strings::HISTORY_FILE))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn word_start_of_dotted_name() {
+        assert_eq!(word_start("print(sys.std", 13), 6);
+        assert_eq!(word_start("x = le", 6), 4);
+        assert_eq!(word_start("f(", 2), 2);
+        assert_eq!(word_start("", 0), 0);
+    }
+}
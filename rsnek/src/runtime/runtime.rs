@@ -2,26 +2,34 @@
 use std;
 use std::ops::BitAnd;
 use std::borrow::Borrow;
-use std::cell::{Ref, RefCell, RefMut};
-use std::collections::VecDeque;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
 
 use num::{Num, Zero};
 
 use ::modules;
+use ::modules::traceback::Traceback;
 use ::api::result::{Error, ErrorType};
 use ::api::RtObject;
 use ::api::typing::BuiltinType;
-use ::api::method::{GetItem, SetAttr, GetAttr};
+use ::api::method::{GetItem, SetAttr, SetItem, GetAttr};
 use ::resources::strings;
 use ::api::result::{ObjectResult};
-use ::system::{StrongRc, WeakRc};
+use ::system::{StrongRc, WeakRc, RECURSION_LIMIT};
+use ::runtime::gc::Collector;
+use ::runtime::coverage::SharedCoverage;
+use ::runtime::profiler::SharedProfiler;
 use ::runtime::traits::{
     BooleanProvider,
+    ByteArrayProvider,
     BytesProvider,
     CodeProvider,
+    ComplexProvider,
     DictProvider,
     FloatProvider,
     FrameProvider,
+    FrozenSetProvider,
     FunctionProvider,
     IntegerProvider,
     IteratorProvider,
@@ -32,9 +40,13 @@ use ::runtime::traits::{
     NoneProvider,
     ObjectProvider,
     PyTypeProvider,
+    RangeProvider,
+    SetProvider,
+    SliceProvider,
     StringProvider,
     TupleProvider,
 
+    DefaultBytesProvider,
     DefaultDictProvider,
     DefaultFrameProvider,
     DefaultListProvider,
@@ -43,6 +55,7 @@ use ::runtime::traits::{
 };
 use ::objects::boolean::PyBooleanType;
 use ::modules::builtins::Type;
+use ::objects::bytearray::PyByteArrayType;
 use ::objects::bytes::PyBytesType;
 use ::objects::code::PyCodeType;
 use ::objects::dictionary::PyDictType;
@@ -55,10 +68,13 @@ use ::objects::method::PyFunctionType;
 use ::objects::module::PyModuleType;
 use ::system::primitives::{SignatureBuilder};
 use ::system::primitives as rs;
+use ::system::sync::{Cell, Ref, RefCell, RefMut};
 use ::objects::none::{PyNoneType, NONE};
 use ::objects::object::PyObjectType;
 use ::objects::pytype::PyMeta;
+use ::objects::range::PyRangeType;
 use ::objects::set::PySetType;
+use ::objects::slice::PySliceType;
 use ::objects::frozenset::PyFrozenSetType;
 use ::objects::string::PyStringType;
 use ::objects::tuple::PyTupleType;
@@ -68,6 +84,17 @@ use ::objects::complex::PyComplexType;
 /// is passable and consumable in the interpreter code.
 pub struct Runtime(RuntimeRef);
 
+/// The runtime state is held in `system::sync` cells, which are lock backed
+/// when built with `rsnek_multithreaded`, so threads can share one `Runtime`.
+/// What they hold is plain data, `RtObject`s (see their `Send` and `Sync` impls),
+/// the `Collector`, which only uses `system::sync` cells and atomics, and the
+/// profiler and coverage, which are behind an `Arc<Mutex<_>>`.
+#[cfg(feature="rsnek_multithreaded")]
+unsafe impl Send for Runtime {}
+
+#[cfg(feature="rsnek_multithreaded")]
+unsafe impl Sync for Runtime {}
+
 /// Same as `Runtime` but holds a weak reference instead of a strong reference.
 pub struct WeakRuntime(RuntimeWeakRef);
 
@@ -75,8 +102,10 @@ pub struct WeakRuntime(RuntimeWeakRef);
 /// via the type specific provider methods.
 struct BuiltinTypes {
     bool: PyBooleanType,
+    bytearray: PyByteArrayType,
     bytes: PyBytesType,
     code: PyCodeType,
+    complex: PyComplexType,
     dict: PyDictType,
     float: PyFloatType,
     frame: PyFrameType,
@@ -89,17 +118,38 @@ struct BuiltinTypes {
     module: PyModuleType,
     none: PyNoneType,
     object: PyObjectType,
+    range: PyRangeType,
     set: PySetType,
+    slice: PySliceType,
     string: PyStringType,
     tuple: PyTupleType,
 }
 
-// TODO: {T99} add ability to intern objects
 /// Concrete struct that holds the current runtime state, heap, etc.
 struct RuntimeInternal {
     types: BuiltinTypes,
     modules: RefCell<RtObject>, // should be a dict
     mod_builtins: RefCell<RtObject>,
+    mod_dis: RefCell<RtObject>,
+    mod_gc: RefCell<RtObject>,
+    mod_greenlet: RefCell<RtObject>,
+    mod_io: RefCell<RtObject>,
+    mod_profile: RefCell<RtObject>,
+    mod_sys: RefCell<RtObject>,
+    mod_threading: RefCell<RtObject>,
+    mod_traceback: RefCell<RtObject>,
+    gc: Collector,
+    interned: RefCell<HashMap<rs::String, RtObject>>,
+    recursion_limit: Cell<usize>,
+    sources: RefCell<HashMap<rs::String, rs::String>>,
+    last_traceback: RefCell<Option<Traceback>>,
+    trace_func: RefCell<Option<RtObject>>,
+    profile_func: RefCell<Option<RtObject>>,
+    break_requested: Cell<bool>,
+    profiler: RefCell<Option<SharedProfiler>>,
+    profiling: Cell<bool>,
+    coverage: RefCell<Option<SharedCoverage>>,
+    covering: Cell<bool>,
 }
 
 
@@ -142,8 +192,10 @@ impl Runtime {
 
         let builtins = BuiltinTypes {
             bool: PyBooleanType::init_type(),
+            bytearray: PyByteArrayType::init_type(),
             bytes: PyBytesType::init_type(),
             code: PyCodeType::init_type(),
+            complex: PyComplexType::init_type(),
             dict: PyDictType::init_type(),
             float: PyFloatType::init_type(),
             frame: PyFrameType::init_type(),
@@ -156,7 +208,9 @@ impl Runtime {
             module: module,
             none: PyNoneType::init_type(),
             object: object,
+            range: PyRangeType::init_type(),
             set: PySetType::init_type(),
+            slice: PySliceType::init_type(),
             string: PyStringType::init_type(),
             tuple: PyTupleType::init_type(),
         };
@@ -167,6 +221,26 @@ impl Runtime {
             types: builtins,
             modules: RefCell::new(placeholder.clone()),
             mod_builtins: RefCell::new(placeholder.clone()),
+            mod_dis: RefCell::new(placeholder.clone()),
+            mod_gc: RefCell::new(placeholder.clone()),
+            mod_greenlet: RefCell::new(placeholder.clone()),
+            mod_io: RefCell::new(placeholder.clone()),
+            mod_sys: RefCell::new(placeholder.clone()),
+            mod_threading: RefCell::new(placeholder.clone()),
+            mod_traceback: RefCell::new(placeholder.clone()),
+            mod_profile: RefCell::new(placeholder.clone()),
+            gc: Collector::new(),
+            interned: RefCell::new(HashMap::new()),
+            recursion_limit: Cell::new(RECURSION_LIMIT),
+            sources: RefCell::new(HashMap::new()),
+            last_traceback: RefCell::new(None),
+            trace_func: RefCell::new(None),
+            profile_func: RefCell::new(None),
+            break_requested: Cell::new(false),
+            profiler: RefCell::new(None),
+            profiling: Cell::new(false),
+            coverage: RefCell::new(None),
+            covering: Cell::new(false),
         };
 
         let rt = Runtime(StrongRc::new(internal));
@@ -178,7 +252,46 @@ impl Runtime {
             let mut _mod: RefMut<RtObject> = rt.0.modules.borrow_mut();
             *_mod = rt.dict(rs::None());
         }
+        {
+            let mut _mod: RefMut<RtObject> = rt.0.mod_dis.borrow_mut();
+            *_mod = modules::dis::create_module(&rt).unwrap();
+        }
+        {
+            let mut _mod: RefMut<RtObject> = rt.0.mod_gc.borrow_mut();
+            *_mod = modules::gc::create_module(&rt).unwrap();
+        }
+        {
+            let mut _mod: RefMut<RtObject> = rt.0.mod_greenlet.borrow_mut();
+            *_mod = modules::greenlet::create_module(&rt).unwrap();
+        }
+        {
+            let mut _mod: RefMut<RtObject> = rt.0.mod_io.borrow_mut();
+            *_mod = modules::io::create_module(&rt).unwrap();
+        }
+        {
+            let mut _mod: RefMut<RtObject> = rt.0.mod_profile.borrow_mut();
+            *_mod = modules::profile::create_module(&rt).unwrap();
+        }
+        {
+            let mut _mod: RefMut<RtObject> = rt.0.mod_sys.borrow_mut();
+            *_mod = modules::sys::create_module(&rt).unwrap();
+        }
+        {
+            let mut _mod: RefMut<RtObject> = rt.0.mod_threading.borrow_mut();
+            *_mod = modules::threading::create_module(&rt).unwrap();
+        }
+        {
+            let mut _mod: RefMut<RtObject> = rt.0.mod_traceback.borrow_mut();
+            *_mod = modules::traceback::create_module(&rt).unwrap();
+        }
 
+        for name in [strings::BUILTINS_MODULE, strings::CPROFILE_MODULE, strings::DIS_MODULE,
+                     strings::GC_MODULE, strings::GREENLET_MODULE, strings::IO_MODULE,
+                     strings::PROFILE_MODULE, strings::SYS_MODULE, strings::THREADING_MODULE,
+                     strings::TRACEBACK_MODULE].iter() {
+            let module = rt.import_module(*name).unwrap();
+            rt.modules().op_setitem(&rt, &rt.intern(name), &module).unwrap();
+        }
 
         rt.register_builtin(modules::builtins::LenFn::create());
         rt.register_builtin(modules::builtins::PrintFn::create());
@@ -190,14 +303,49 @@ impl Runtime {
         rt.register_builtin(modules::builtins::ListFn::create());
         rt.register_builtin(modules::builtins::GlobalsFn::create());
         rt.register_builtin(modules::builtins::TupleFn::create());
+        rt.register_builtin(modules::builtins::BytesFn::create());
+        rt.register_builtin(modules::builtins::ByteArrayFn::create());
+        rt.register_builtin(modules::builtins::SliceFn::create());
+        rt.register_builtin(modules::builtins::RangeFn::create());
+        rt.register_builtin(modules::builtins::EnumerateFn::create());
+        rt.register_builtin(modules::builtins::ZipFn::create());
+        rt.register_builtin(modules::builtins::MapFn::create());
+        rt.register_builtin(modules::builtins::FilterFn::create());
+        rt.register_builtin(modules::builtins::ReversedFn::create());
+        rt.register_builtin(modules::builtins::SortedFn::create());
+        rt.register_builtin(modules::builtins::MinFn::create());
+        rt.register_builtin(modules::builtins::MaxFn::create());
+        rt.register_builtin(modules::builtins::SumFn::create());
+        rt.register_builtin(modules::builtins::IsInstanceFn::create());
+        rt.register_builtin(modules::builtins::IsSubclassFn::create());
+        rt.register_builtin(modules::builtins::GetAttrFn::create());
+        rt.register_builtin(modules::builtins::SetAttrFn::create());
+        rt.register_builtin(modules::builtins::HasAttrFn::create());
+        rt.register_builtin(modules::builtins::DelAttrFn::create());
+        rt.register_builtin(modules::builtins::IdFn::create());
+        rt.register_builtin(modules::builtins::HashFn::create());
+        rt.register_builtin(modules::builtins::ReprFn::create());
+        rt.register_builtin(modules::builtins::CallableFn::create());
+        rt.register_builtin(modules::builtins::DirFn::create());
+        rt.register_builtin(modules::builtins::VarsFn::create());
+        rt.register_builtin(modules::builtins::FloatFn::create());
+        rt.register_builtin(modules::builtins::AbsFn::create());
+        rt.register_builtin(modules::builtins::RoundFn::create());
+        rt.register_builtin(modules::builtins::DivModFn::create());
+        rt.register_builtin(modules::builtins::BreakpointFn::create());
         rt
     }
 
+    /// The `sys.modules` dict of the modules that were imported by name
+    pub fn modules(&self) -> RtObject {
+        self.0.modules.borrow().clone()
+    }
+
     /// Temporary method to put a function into the builtin module
     /// until the full module system is complete.
     pub fn register_builtin(&self, func: rs::Func) {
         let module: Ref<RtObject> = self.0.mod_builtins.borrow();
-        let key = self.str(func.name.as_ref());
+        let key = self.intern(&func.name);
         module.op_setattr(&self, &key, &self.function(func)).unwrap();
     }
 
@@ -205,23 +353,230 @@ impl Runtime {
     /// the module and namespace system is complete.
     pub fn get_builtin(&self, name: &'static str) -> RtObject {
         let module: Ref<RtObject> = self.0.mod_builtins.borrow();
-        let key = self.str(name);
+        let key = self.intern(name);
         module.op_getattr(&self, &key).unwrap()
     }
 
+    /// Get the type object of a builtin type by name, `None` if there is
+    /// no such type.
+    pub fn builtin_class(&self, name: &str) -> Option<RtObject> {
+        self.0.types.meta.class(name)
+    }
+
+    /// The cycle collector that tracks the containers allocated by this runtime
+    pub fn gc(&self) -> &Collector {
+        &self.0.gc
+    }
+
+    /// The interned `str` object equal to `value`, which is created the first time
+    /// it is asked for. Interned strings are shared for the life of the runtime so
+    /// dict lookups keyed by them short-circuit on identity.
+    pub fn intern(&self, value: &str) -> RtObject {
+        if let Some(object) = self.0.interned.borrow().get(value) {
+            return object.clone()
+        }
+
+        let object = self.str(value);
+        self.0.interned.borrow_mut().insert(String::from(value), object.clone());
+        object
+    }
+
+    /// Intern an existing `str` object and return the shared object equal to it,
+    /// which is `object` itself when no equal string was interned before.
+    pub fn intern_object(&self, object: &RtObject) -> ObjectResult {
+        let value = match object.as_ref() {
+            &Type::Str(ref string) => &string.value.0,
+            other => return Err(Error::typerr(&format!(
+                "can't intern {}", other.debug_name())))
+        };
+
+        if let Some(interned) = self.0.interned.borrow().get(value) {
+            return Ok(interned.clone())
+        }
+
+        self.0.interned.borrow_mut().insert(value.clone(), object.clone());
+        Ok(object.clone())
+    }
+
+    /// True if `object` is the interned `str` for its value
+    pub fn is_interned(&self, object: &RtObject) -> bool {
+        match object.as_ref() {
+            &Type::Str(ref string) => match self.0.interned.borrow().get(&string.value.0) {
+                Some(interned) => interned.id() == object.id(),
+                None => false
+            },
+            _ => false
+        }
+    }
+
+    /// The maximum depth of the python call stack, exceeding it raises a `RecursionError`
+    pub fn recursion_limit(&self) -> usize {
+        self.0.recursion_limit.get()
+    }
+
+    pub fn set_recursion_limit(&self, limit: usize) {
+        self.0.recursion_limit.set(limit)
+    }
+
+    /// The function set by `sys.settrace()` which the interpreter calls when a
+    /// python function is called.
+    pub fn trace_func(&self) -> Option<RtObject> {
+        self.0.trace_func.borrow().clone()
+    }
+
+    pub fn set_trace_func(&self, func: Option<RtObject>) {
+        *self.0.trace_func.borrow_mut() = func;
+    }
+
+    /// The function set by `sys.setprofile()` which the interpreter calls when a
+    /// function is called or returns.
+    pub fn profile_func(&self) -> Option<RtObject> {
+        self.0.profile_func.borrow().clone()
+    }
+
+    pub fn set_profile_func(&self, func: Option<RtObject>) {
+        *self.0.profile_func.borrow_mut() = func;
+    }
+
+    /// Ask the interpreter to stop in the debugger before the next line, which is
+    /// how `breakpoint()` gets to it.
+    pub fn request_break(&self) {
+        self.0.break_requested.set(true)
+    }
+
+    /// True once after `request_break` was called
+    pub fn take_break_request(&self) -> bool {
+        let requested = self.0.break_requested.get();
+        self.0.break_requested.set(false);
+        requested
+    }
+
+    /// The profiler the interpreter reports calls and opcodes to, set by `cProfile`
+    /// and `--profile`.
+    pub fn profiler(&self) -> Option<SharedProfiler> {
+        self.0.profiler.borrow().clone()
+    }
+
+    pub fn set_profiler(&self, profiler: Option<SharedProfiler>) {
+        self.0.profiling.set(profiler.is_some());
+        *self.0.profiler.borrow_mut() = profiler;
+    }
+
+    /// Cheaper than `profiler().is_some()`, it is checked for every instruction
+    pub fn is_profiling(&self) -> bool {
+        self.0.profiling.get()
+    }
+
+    /// The line coverage the interpreter counts the lines it runs in, set by
+    /// `--coverage`.
+    pub fn coverage(&self) -> Option<SharedCoverage> {
+        self.0.coverage.borrow().clone()
+    }
+
+    pub fn set_coverage(&self, coverage: Option<SharedCoverage>) {
+        self.0.covering.set(coverage.is_some());
+        *self.0.coverage.borrow_mut() = coverage;
+    }
+
+    pub fn is_covering(&self) -> bool {
+        self.0.covering.get()
+    }
+
+    /// Keep the `source` compiled from `filename` so tracebacks can show its lines,
+    /// needed for code that was not read from a file like `-c` commands.
+    pub fn add_source(&self, filename: &str, source: &str) {
+        self.0.sources.borrow_mut().insert(String::from(filename), String::from(source));
+    }
+
+    /// Line `line`, counting from 1, of the source compiled from `filename`. Sources
+    /// that were not added with `add_source` are read from the file the first time,
+    /// pseudo file names like `<stdin>` have no source.
+    pub fn source_line(&self, filename: &str, line: usize) -> Option<String> {
+        if !self.0.sources.borrow().contains_key(filename) {
+            if filename.starts_with('<') {
+                return None
+            }
+
+            let mut source = String::new();
+            match File::open(filename).and_then(|mut file| file.read_to_string(&mut source)) {
+                Ok(_) => self.add_source(filename, &source),
+                Err(_) => return None
+            };
+        }
+
+        let sources = self.0.sources.borrow();
+        match (sources.get(filename), line.checked_sub(1)) {
+            (Some(source), Some(idx)) => source.lines().nth(idx).map(String::from),
+            _ => None
+        }
+    }
+
+    /// The traceback of the last error that was not handled, like CPython's
+    /// `sys.last_traceback`.
+    pub fn last_traceback(&self) -> Option<Traceback> {
+        self.0.last_traceback.borrow().clone()
+    }
+
+    pub fn set_last_traceback(&self, traceback: Traceback) {
+        *self.0.last_traceback.borrow_mut() = Some(traceback);
+    }
+
+    /// Hand a newly allocated container to the cycle collector
+    #[inline]
+    fn track(&self, object: RtObject) -> RtObject {
+        self.0.gc.track(&object);
+        object
+    }
+
 }
 
 
 impl<'a> ModuleImporter<&'a str> for Runtime {
     /// Import a module by path. Currently this will only allow imports of
-    /// the builtin module with the name defined by `strings::BUILTINS_MODULE`
-    /// but will be expanded to filesystem search in a later version.
+    /// the natively defined modules, `strings::BUILTINS_MODULE`, `strings::CPROFILE_MODULE`,
+    /// `strings::DIS_MODULE`, `strings::GC_MODULE`, `strings::GREENLET_MODULE`, `strings::IO_MODULE`,
+    /// `strings::PROFILE_MODULE`, `strings::SYS_MODULE`, `strings::THREADING_MODULE`, and
+    /// `strings::TRACEBACK_MODULE`, but will be expanded to filesystem search in a later version.
     fn import_module(&self, path: &'a str) -> ObjectResult {
         match path {
             strings::BUILTINS_MODULE => {
                 let ref_: Ref<RtObject> = self.0.mod_builtins.borrow();
                 Ok(ref_.clone())
             },
+            strings::DIS_MODULE => {
+                let ref_: Ref<RtObject> = self.0.mod_dis.borrow();
+                Ok(ref_.clone())
+            },
+            strings::GC_MODULE => {
+                let ref_: Ref<RtObject> = self.0.mod_gc.borrow();
+                Ok(ref_.clone())
+            },
+            strings::GREENLET_MODULE => {
+                let ref_: Ref<RtObject> = self.0.mod_greenlet.borrow();
+                Ok(ref_.clone())
+            },
+            strings::IO_MODULE => {
+                let ref_: Ref<RtObject> = self.0.mod_io.borrow();
+                Ok(ref_.clone())
+            },
+            // `profile` is the same module, there is no pure python version
+            strings::CPROFILE_MODULE |
+            strings::PROFILE_MODULE => {
+                let ref_: Ref<RtObject> = self.0.mod_profile.borrow();
+                Ok(ref_.clone())
+            },
+            strings::SYS_MODULE => {
+                let ref_: Ref<RtObject> = self.0.mod_sys.borrow();
+                Ok(ref_.clone())
+            },
+            strings::THREADING_MODULE => {
+                let ref_: Ref<RtObject> = self.0.mod_threading.borrow();
+                Ok(ref_.clone())
+            },
+            strings::TRACEBACK_MODULE => {
+                let ref_: Ref<RtObject> = self.0.mod_traceback.borrow();
+                Ok(ref_.clone())
+            },
             _ => Err(Error::module_not_found(path))
         }
     }
@@ -277,6 +632,16 @@ impl<T: Num> FloatProvider<T> for Runtime
 }
 
 
+//
+// Complex
+//
+impl ComplexProvider<rs::Complex> for Runtime {
+    fn complex(&self, value: rs::Complex) -> RtObject {
+        self.0.types.complex.new(&self, value)
+    }
+}
+
+
 //
 // Iterators
 //
@@ -293,7 +658,7 @@ impl IteratorProvider<rs::Iterator> for Runtime {
     #[allow(unused_variables)]
     fn iter(&self, value: rs::Iterator) -> RtObject {
         let wrapped = IteratorValue(value, self.clone());
-        self.0.types.iterator.new(&self, wrapped)
+        self.track(self.0.types.iterator.new(&self, wrapped))
     }
 }
 
@@ -336,12 +701,54 @@ impl DefaultStringProvider for Runtime {
     }
 }
 
+//
+// Bytes
+//
+impl BytesProvider<rs::Bytes> for Runtime {
+    fn bytes(&self, value: rs::Bytes) -> RtObject {
+        self.0.types.bytes.new(&self, value)
+    }
+}
+
+impl DefaultBytesProvider for Runtime {
+    fn default_bytes(&self) -> RtObject {
+        self.0.types.bytes.empty.clone()
+    }
+}
+
+//
+// ByteArray
+//
+impl ByteArrayProvider<rs::Bytes> for Runtime {
+    fn bytearray(&self, value: rs::Bytes) -> RtObject {
+        self.0.types.bytearray.new(&self, value)
+    }
+}
+
+//
+// Range
+//
+impl RangeProvider<rs::Range> for Runtime {
+    fn range(&self, value: rs::Range) -> RtObject {
+        self.0.types.range.new(&self, value)
+    }
+}
+
+//
+// Slice
+//
+impl SliceProvider<rs::Slice> for Runtime {
+    fn slice(&self, value: rs::Slice) -> RtObject {
+        self.0.types.slice.new(&self, value)
+    }
+}
+
 //
 // Dict
 //
 impl DictProvider<rs::Dict> for Runtime {
     fn dict(&self, value: rs::Dict) -> RtObject {
-        self.0.types.dict.new(&self, value)
+        self.track(self.0.types.dict.new(&self, value))
     }
 }
 
@@ -356,7 +763,7 @@ impl DictProvider<rs::None> for Runtime {
 
 impl DefaultDictProvider for Runtime {
     fn default_dict(&self) -> RtObject {
-        self.0.types.dict.new(&self, rs::Dict::new())
+        self.dict(rs::Dict::new())
     }
 }
 
@@ -374,10 +781,10 @@ impl TupleProvider<rs::None> for Runtime {
 
 impl TupleProvider<rs::Tuple> for Runtime {
     fn tuple(&self, value: rs::Tuple) -> RtObject {
-        self.0
+        self.track(self.0
             .types
             .tuple
-            .new(&self, value)
+            .new(&self, value))
     }
 }
 
@@ -400,17 +807,40 @@ impl ListProvider<rs::None> for Runtime {
 
 impl ListProvider<rs::List> for Runtime {
     fn list(&self, value: rs::List) -> RtObject {
-        self.0
+        self.track(self.0
             .types
             .list
-            .new(&self, value)
+            .new(&self, value))
     }
 }
 
 
 impl DefaultListProvider for Runtime {
+    /// Lists are mutable so unlike the other default providers this always
+    /// allocates a new empty list instead of sharing a singleton.
     fn default_list(&self) -> RtObject {
-        self.0.types.list.empty.clone()
+        self.list(rs::List::new())
+    }
+}
+
+//
+// Set
+//
+impl SetProvider<rs::Set> for Runtime {
+    fn set(&self, value: rs::Set) -> RtObject {
+        self.track(self.0
+            .types
+            .set
+            .new(&self, value))
+    }
+}
+
+impl FrozenSetProvider<rs::Set> for Runtime {
+    fn frozenset(&self, value: rs::Set) -> RtObject {
+        self.track(self.0
+            .types
+            .frozenset
+            .new(&self, value))
     }
 }
 
@@ -421,7 +851,7 @@ impl DefaultListProvider for Runtime {
 impl ObjectProvider<rs::None> for Runtime {
     #[allow(unused_variables)]
     fn object(&self, value: rs::None) -> RtObject {
-        self.0
+        self.track(self.0
             .types
             .object
             .new(&self,
@@ -433,7 +863,7 @@ impl ObjectProvider<rs::None> for Runtime {
                          .clone(),
                      dict: self.dict(rs::None()),
                      bases: self.dict(rs::None()),
-                 })
+                 }))
     }
 }
 
@@ -441,10 +871,10 @@ impl ObjectProvider<rs::None> for Runtime {
 impl ObjectProvider<rs::Object> for Runtime {
     #[allow(unused_variables)]
     fn object(&self, value: rs::Object) -> RtObject {
-        self.0
+        self.track(self.0
             .types
             .object
-            .new(&self, value)
+            .new(&self, value))
     }
 }
 
@@ -462,6 +892,16 @@ impl PyTypeProvider<rs::None> for Runtime {
     }
 }
 
+impl<'a> PyTypeProvider<&'a Type> for Runtime {
+    /// Get the type object for the type of `value`
+    fn pytype(&self, value: &'a Type) -> RtObject {
+        self.0
+            .types
+            .meta
+            .class_of(value)
+    }
+}
+
 
 //
 // Functions and Methods
@@ -469,10 +909,10 @@ impl PyTypeProvider<rs::None> for Runtime {
 impl FunctionProvider<rs::Func> for Runtime {
     /// Create a function object from the rs::Function and return its `RtObject`
     fn function(&self, value: rs::Func) -> RtObject {
-        self.0
+        self.track(self.0
             .types
             .function
-            .new(&self, value)
+            .new(&self, value))
     }
 }
 
@@ -514,7 +954,7 @@ impl CodeProvider<rs::Code> for Runtime {
 //
 impl FrameProvider<rs::Frame> for Runtime {
     fn frame(&self, value: rs::Frame) -> RtObject {
-        self.0.types.frame.new(&self, value)
+        self.track(self.0.types.frame.new(&self, value))
     }
 }
 
@@ -522,12 +962,12 @@ impl FrameProvider<rs::Frame> for Runtime {
 impl DefaultFrameProvider for Runtime {
     #[allow(unused_variables)]
     fn default_frame(&self) -> RtObject {
-        self.0.types.frame.new(&self, rs::Frame {
-            f_lasti: rs::Integer::zero(),
+        self.frame(rs::Frame {
+            f_lasti: Cell::new(0),
+            f_lineno: Cell::new(0),
             f_builtins: self.default_dict(),
             f_code: self.none(),
             f_back: self.none(),
-            blocks: VecDeque::new(),
         })
     }
 }
@@ -538,7 +978,7 @@ impl DefaultFrameProvider for Runtime {
 impl ModuleProvider<rs::None> for Runtime {
     #[allow(unused_variables)]
     fn module(&self, value: rs::None) -> RtObject {
-        self.0
+        self.track(self.0
             .types
             .module
             .new(&self,
@@ -550,7 +990,7 @@ impl ModuleProvider<rs::None> for Runtime {
                          .clone(),
                      dict: self.dict(rs::None()),
                      bases: self.dict(rs::None()),
-                 })
+                 }))
     }
 }
 
@@ -613,4 +1053,42 @@ mod tests {
 
         b.iter(|| { one.op_getattr(&rt, &name).unwrap() });
     }
+
+    #[bench]
+    fn module_getattr_str(b: &mut Bencher) {
+        let rt = setup_test();
+        let module = rt.import_module(strings::BUILTINS_MODULE).unwrap();
+
+        b.iter(|| { module.op_getattr(&rt, &rt.str("len")).unwrap() });
+    }
+
+    #[bench]
+    fn module_getattr_interned(b: &mut Bencher) {
+        let rt = setup_test();
+        let module = rt.import_module(strings::BUILTINS_MODULE).unwrap();
+
+        b.iter(|| { module.op_getattr(&rt, &rt.intern("len")).unwrap() });
+    }
+
+    #[test]
+    fn intern() {
+        let rt = setup_test();
+        let name = rt.intern("name");
+
+        assert_eq!(rt.intern("name").id(), name.id());
+        assert!(rt.is_interned(&name));
+        assert!(!rt.is_interned(&rt.str("name")));
+        assert_eq!(rt.intern_object(&rt.str("name")).unwrap().id(), name.id());
+        assert!(rt.intern_object(&rt.int(1)).is_err());
+    }
+
+    #[test]
+    fn getattr_interned_and_str() {
+        let rt = setup_test();
+        let module = rt.import_module(strings::BUILTINS_MODULE).unwrap();
+
+        let interned = module.op_getattr(&rt, &rt.intern("len")).unwrap();
+        let string = module.op_getattr(&rt, &rt.str("len")).unwrap();
+        assert_eq!(interned.id(), string.id());
+    }
 }
@@ -0,0 +1,395 @@
+//! Cooperative scheduler that multiplexes greenlets over one OS thread
+//!
+//! A greenlet is a python function running in its own `Interpreter` on its own
+//! `OsStack`, wrapped in a `fringe::Generator`. The interpreter starts with a copy
+//! of the names of the interpreter that spawned it. Greenlets only give up control when
+//! they block, which suspends the generator back into the scheduler loop so it
+//! can resume the next ready greenlet.
+//!
+//! The scheduler loop runs on the stack that is not a greenlet, which is the OS
+//! thread's own stack, or the stack of `GreenThread::run` when the main function
+//! itself runs as a greenlet. Blocking from there runs other greenlets until the
+//! condition holds instead of suspending.
+//!
+//! Blocked greenlets stay in the ready queue and check their condition each time
+//! they are resumed, so there is nothing to wake and nothing is lost when the
+//! condition changes while they are suspended.
+//!
+//! Any greenlet that gets further than a blocked check counts as progress. A
+//! waiter whose condition is still false after every other greenlet had a turn
+//! without progress can never be woken, `block_until` raises a `RuntimeError`
+//! instead of spinning. While threads started by `threading` are running they
+//! may still make the condition hold, so the waiter keeps waiting.
+//!
+//! The scheduler is thread local, every OS thread has its own greenlets.
+//!
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, VecDeque};
+use std::ptr;
+use std::thread;
+
+use fringe::{Generator, OsStack};
+use fringe::generator::Yielder;
+
+use ::api::RtObject;
+use ::api::result::{Error, ObjectResult, RtResult};
+use ::runtime::{Interpreter, Runtime};
+use ::system::GREENLET_STACK_SIZE;
+use ::system::stack;
+
+
+/// Identifier handed out by `spawn`
+pub type GreenletId = usize;
+
+type Task = Generator<'static, (), (), OsStack>;
+
+
+struct Scheduler {
+    next_id: GreenletId,
+    stack_size: usize,
+    /// Greenlets that have not finished, except the one that is running
+    ready: VecDeque<(GreenletId, Task)>,
+    running: Option<GreenletId>,
+    /// Results of finished greenlets that have not been joined
+    finished: HashMap<GreenletId, ObjectResult>,
+}
+
+
+thread_local!(
+    static SCHEDULER: RefCell<Scheduler> = RefCell::new(Scheduler {
+        next_id: 1,
+        stack_size: GREENLET_STACK_SIZE,
+        ready: VecDeque::new(),
+        running: None,
+        finished: HashMap::new(),
+    });
+
+    /// The yielder of the running greenlet, null on the scheduler loop's stack
+    static CURRENT: Cell<*const Yielder<(), ()>> = Cell::new(ptr::null());
+
+    /// Bumped each time a greenlet gets further than a blocked check
+    static PROGRESS: Cell<usize> = Cell::new(0)
+);
+
+
+/// Set the size in bytes of the stacks of greenlets spawned after this call
+pub fn set_stack_size(size: usize) {
+    SCHEDULER.with(|s| s.borrow_mut().stack_size = size);
+}
+
+
+/// Make `yielder` the one that `yield_now` suspends, called first thing on the
+/// stack of each greenlet.
+pub fn enter(yielder: &Yielder<(), ()>) {
+    CURRENT.with(|current| current.set(yielder as *const Yielder<(), ()>));
+    made_progress();
+}
+
+
+fn made_progress() {
+    PROGRESS.with(|progress| progress.set(progress.get().wrapping_add(1)));
+}
+
+
+fn progress() -> usize {
+    PROGRESS.with(|progress| progress.get())
+}
+
+
+/// True when called from inside a greenlet rather than the scheduler loop
+pub fn in_greenlet() -> bool {
+    CURRENT.with(|current| !current.get().is_null())
+}
+
+
+/// Start a greenlet that calls `func(*args)` the next time the scheduler runs
+pub fn spawn(rt: &Runtime, func: &RtObject, args: Vec<RtObject>) -> RtResult<GreenletId> {
+    let (id, stack_size) = SCHEDULER.with(|s| {
+        let mut s = s.borrow_mut();
+        let id = s.next_id;
+        s.next_id += 1;
+        (id, s.stack_size)
+    });
+
+    let os_stack = match OsStack::new(stack_size) {
+        Ok(stack) => stack,
+        Err(err) => return Err(Error::system(&format!("can't allocate greenlet stack: {}", err)))
+    };
+
+    let rt = rt.clone();
+    let func = func.clone();
+    let globals = Interpreter::caller_namespace();
+    let task = Generator::new(os_stack, move |yielder, ()| {
+        enter(yielder);
+        stack::enter(stack_size);
+        let mut interpreter = match globals {
+            Some(ns) => Interpreter::with_namespace(&rt, ns),
+            None => Interpreter::new(&rt)
+        };
+        let result = interpreter.call(&rt, &func, args);
+
+        SCHEDULER.with(|s| s.borrow_mut().finished.insert(id, result));
+    });
+
+    SCHEDULER.with(|s| s.borrow_mut().ready.push_back((id, task)));
+    Ok(id)
+}
+
+
+/// Resume the next ready greenlet until it blocks or finishes. Returns false if
+/// there were no greenlets to run.
+pub fn run_one() -> bool {
+    let next = SCHEDULER.with(|s| s.borrow_mut().ready.pop_front());
+
+    let (id, mut task) = match next {
+        Some(next) => next,
+        None => return false
+    };
+
+    SCHEDULER.with(|s| s.borrow_mut().running = Some(id));
+    let bounds = stack::current();
+    let suspended = task.resume(()).is_some();
+    CURRENT.with(|current| current.set(ptr::null()));
+    stack::restore(bounds);
+
+    SCHEDULER.with(|s| {
+        let mut s = s.borrow_mut();
+        s.running = None;
+        if suspended {
+            s.ready.push_back((id, task));
+        }
+    });
+
+    if !suspended {
+        made_progress();
+    }
+
+    true
+}
+
+
+/// Let the other greenlets run. A greenlet is suspended and resumed after the
+/// ready ones, the scheduler loop runs the next ready greenlet.
+pub fn yield_now() {
+    if !in_greenlet() {
+        if !run_one() {
+            thread::yield_now();
+        }
+        return;
+    }
+
+    suspend();
+    made_progress();
+}
+
+
+/// Suspend the running greenlet back into the scheduler loop
+fn suspend() {
+    let yielder = CURRENT.with(|current| current.get());
+    let bounds = stack::current();
+    // The yielder lives on the stack of the running greenlet which is only
+    // unwound once the greenlet finishes, so it outlives this call.
+    unsafe { (*yielder).suspend(()) };
+    CURRENT.with(|current| current.set(yielder));
+    stack::restore(bounds);
+}
+
+
+/// Yield until `condition` holds. Raises a `RuntimeError` once every greenlet
+/// has had a turn without getting any further and no other thread is running.
+pub fn block_until<F>(rt: &Runtime, condition: F) -> RtResult<()> where F: Fn() -> bool {
+    let mut seen = progress();
+    // Consecutive checks of the condition without any progress in between
+    let mut idle = 0;
+
+    while !condition() {
+        if progress() == seen {
+            idle += 1;
+        } else {
+            seen = progress();
+            idle = 0;
+        }
+
+        if idle > count() {
+            if rt.gc().threads() == 0 {
+                return Err(Error::runtime("deadlock: no greenlets can run"));
+            }
+            thread::yield_now();
+        }
+
+        if in_greenlet() {
+            suspend();
+        } else {
+            run_one();
+        }
+    }
+
+    made_progress();
+    Ok(())
+}
+
+
+/// Run greenlet `id` next, then yield. Raises a `ValueError` if there is no
+/// such greenlet waiting to run.
+pub fn switch(id: GreenletId) -> RtResult<()> {
+    let found = SCHEDULER.with(|s| {
+        let mut s = s.borrow_mut();
+        match s.ready.iter().position(|&(ready, _)| ready == id) {
+            Some(idx) => {
+                let task = s.ready.remove(idx).unwrap();
+                s.ready.push_front(task);
+                true
+            },
+            None => false
+        }
+    });
+
+    if !found {
+        return Err(Error::value(&format!("greenlet {} is not ready to run", id)));
+    }
+
+    yield_now();
+    Ok(())
+}
+
+
+/// Wait for greenlet `id` to finish and return the result of its function
+pub fn join(rt: &Runtime, id: GreenletId) -> ObjectResult {
+    let known = SCHEDULER.with(|s| {
+        let s = s.borrow();
+        s.finished.contains_key(&id) ||
+            s.running == Some(id) ||
+            s.ready.iter().any(|&(ready, _)| ready == id)
+    });
+
+    if !known {
+        return Err(Error::value(&format!("unknown greenlet {}", id)));
+    }
+
+    if SCHEDULER.with(|s| s.borrow().running == Some(id)) {
+        return Err(Error::runtime("cannot join current greenlet"));
+    }
+
+    block_until(rt, || SCHEDULER.with(|s| s.borrow().finished.contains_key(&id)))?;
+    SCHEDULER.with(|s| s.borrow_mut().finished.remove(&id).unwrap())
+}
+
+
+/// The number of greenlets that have not finished
+pub fn count() -> usize {
+    SCHEDULER.with(|s| {
+        let s = s.borrow();
+        s.ready.len() + if s.running.is_some() { 1 } else { 0 }
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use ::api::method::Append;
+    use ::api::result::ErrorType;
+    use ::runtime::traits::{DefaultListProvider, FunctionProvider, IntegerProvider, ListProvider,
+                            NoneProvider};
+    use ::system::primitives as rs;
+    use ::system::primitives::{Func, FuncType, SignatureBuilder};
+    use super::*;
+
+    /// A native function that appends `tag` to `log` twice, yielding in between
+    fn worker(rt: &Runtime, log: &RtObject, tag: i64) -> RtObject {
+        let log = log.clone();
+        let callable: Box<rs::WrapperFn> = Box::new(move |rt: &Runtime, _: &RtObject, _: &RtObject, _: &RtObject| {
+            log.meth_append(&rt, &rt.int(tag))?;
+            yield_now();
+            log.meth_append(&rt, &rt.int(tag))?;
+            Ok(rt.int(tag))
+        });
+
+        rt.function(Func {
+            name: String::from("worker"),
+            module: String::from("tests"),
+            callable: FuncType::Wrapper(callable),
+            signature: [].as_args()
+        })
+    }
+
+    #[test]
+    fn interleave() {
+        let rt = Runtime::new();
+        let log = rt.default_list();
+
+        let first = spawn(&rt, &worker(&rt, &log, 1), vec![]).unwrap();
+        let second = spawn(&rt, &worker(&rt, &log, 2), vec![]).unwrap();
+        assert_eq!(count(), 2);
+
+        assert_eq!(join(&rt, first).unwrap(), rt.int(1));
+        assert_eq!(join(&rt, second).unwrap(), rt.int(2));
+        assert_eq!(count(), 0);
+
+        let expected = rt.list(vec![rt.int(1), rt.int(2), rt.int(1), rt.int(2)]);
+        assert_eq!(log, expected);
+    }
+
+    #[test]
+    fn switch_runs_greenlet_first() {
+        let rt = Runtime::new();
+        let log = rt.default_list();
+
+        let first = spawn(&rt, &worker(&rt, &log, 1), vec![]).unwrap();
+        let second = spawn(&rt, &worker(&rt, &log, 2), vec![]).unwrap();
+
+        switch(second).unwrap();
+        assert_eq!(log, rt.list(vec![rt.int(2)]));
+
+        join(&rt, first).unwrap();
+        join(&rt, second).unwrap();
+    }
+
+    #[test]
+    fn join_unknown() {
+        let rt = Runtime::new();
+        let id = spawn(&rt, &worker(&rt, &rt.default_list(), 1), vec![]).unwrap();
+
+        join(&rt, id).unwrap();
+        assert!(join(&rt, id).is_err());
+        assert!(switch(id).is_err());
+    }
+    /// A native function that waits for a condition that never holds
+    fn stuck(rt: &Runtime) -> RtObject {
+        let callable: Box<rs::WrapperFn> = Box::new(move |rt: &Runtime, _: &RtObject, _: &RtObject, _: &RtObject| {
+            block_until(&rt, || false)?;
+            Ok(rt.none())
+        });
+
+        rt.function(Func {
+            name: String::from("stuck"),
+            module: String::from("tests"),
+            callable: FuncType::Wrapper(callable),
+            signature: [].as_args()
+        })
+    }
+
+    #[test]
+    fn deadlock_without_greenlets() {
+        let rt = Runtime::new();
+
+        match block_until(&rt, || false) {
+            Err(Error(ErrorType::Runtime, message)) => assert_eq!(message, "deadlock: no greenlets can run"),
+            other => panic!("expected a RuntimeError, got {:?}", other)
+        }
+    }
+
+    #[test]
+    fn deadlock_joining_blocked_greenlet() {
+        let rt = Runtime::new();
+        let log = rt.default_list();
+
+        let stuck = spawn(&rt, &stuck(&rt), vec![]).unwrap();
+        let other = spawn(&rt, &worker(&rt, &log, 1), vec![]).unwrap();
+
+        assert_eq!(join(&rt, other).unwrap(), rt.int(1));
+        match join(&rt, stuck) {
+            Err(Error(ErrorType::Runtime, message)) => assert_eq!(message, "deadlock: no greenlets can run"),
+            other => panic!("expected a RuntimeError, got {:?}", other)
+        }
+    }
+}
@@ -0,0 +1,54 @@
+//! Events the interpreter sends to the hooks set with `sys.settrace()` and
+//! `sys.setprofile()`, and to the debugger.
+//!
+
+/// Something that happened in a frame, the name is the `event` argument of a hook
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Serialize)]
+pub enum Event {
+    /// A python function was called, the frame is the new one
+    Call,
+    /// The frame is about to run a new line, or the same one again in a loop
+    Line,
+    /// The frame returns, the argument is the value it returns
+    Return,
+    /// An error was raised in the frame, the argument is `(type, message)`
+    Exception,
+    /// A native function is about to be called, the argument is the function
+    CCall,
+    /// A native function returned
+    CReturn,
+    /// A native function raised an error
+    CException,
+}
+
+
+impl Event {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Event::Call         => "call",
+            Event::Line         => "line",
+            Event::Return       => "return",
+            Event::Exception    => "exception",
+            Event::CCall        => "c_call",
+            Event::CReturn      => "c_return",
+            Event::CException   => "c_exception",
+        }
+    }
+
+    /// True if the event goes to the local trace function of the frame, `Call`
+    /// goes to the function set by `sys.settrace()` which picks that one.
+    pub fn is_traced(&self) -> bool {
+        match *self {
+            Event::Line | Event::Return | Event::Exception => true,
+            _ => false
+        }
+    }
+
+    /// True if the event goes to the function set by `sys.setprofile()`
+    pub fn is_profiled(&self) -> bool {
+        match *self {
+            Event::Line | Event::Exception => false,
+            _ => true
+        }
+    }
+}
@@ -22,6 +22,10 @@ pub trait FloatProvider<T> {
     fn float(&self, value: T) -> RtObject;
 }
 
+pub trait ComplexProvider<T> {
+    fn complex(&self, value: T) -> RtObject;
+}
+
 pub trait IteratorProvider<T> {
     fn iter(&self, value: T) -> RtObject;
 }
@@ -38,6 +42,18 @@ pub trait BytesProvider<T> {
     fn bytes(&self, value: T) -> RtObject;
 }
 
+pub trait ByteArrayProvider<T> {
+    fn bytearray(&self, value: T) -> RtObject;
+}
+
+pub trait RangeProvider<T> {
+    fn range(&self, value: T) -> RtObject;
+}
+
+pub trait SliceProvider<T> {
+    fn slice(&self, value: T) -> RtObject;
+}
+
 pub trait TupleProvider<T> {
     fn tuple(&self, value: T) -> RtObject;
 }
@@ -46,6 +62,14 @@ pub trait ListProvider<T> {
     fn list(&self, value: T) -> RtObject;
 }
 
+pub trait SetProvider<T> {
+    fn set(&self, value: T) -> RtObject;
+}
+
+pub trait FrozenSetProvider<T> {
+    fn frozenset(&self, value: T) -> RtObject;
+}
+
 pub trait PyTypeProvider<T> {
     fn pytype(&self, value: T) -> RtObject;
 }
@@ -0,0 +1,147 @@
+//! Hash algorithms shared by the builtin types so that values which compare equal
+//! also hash equal the same way they do in CPython.
+//!
+//! Strings and bytes use SipHash-2-4 keyed from a process wide seed. Like CPython, the
+//! seed is random unless the `PYTHONHASHSEED` environment variable is set to an integer
+//! in which case hashes are reproducible across runs (handy for tests).
+//!
+//! Tuples combine the hashes of their elements with the xxHash based scheme from
+//! CPython 3.8+ `tuplehash`.
+#![allow(deprecated)]
+use std::collections::hash_map::RandomState;
+use std::env;
+use std::hash::{BuildHasher, Hasher, SipHasher};
+use std::sync::{Once, ONCE_INIT};
+
+use ::system::primitives::HashId;
+
+
+/// Name of the environment variable used to fix the string hashing seed
+pub const HASH_SEED_ENV: &'static str = "PYTHONHASHSEED";
+
+const XXPRIME_1: u64 = 11400714785074694791;
+const XXPRIME_2: u64 = 14029467366897019727;
+const XXPRIME_5: u64 = 2870177450012600261;
+
+static INIT_KEYS: Once = ONCE_INIT;
+static mut SIPHASH_KEYS: (u64, u64) = (0, 0);
+
+
+/// Returns the SipHash keys for the process, initializing them on first use
+fn siphash_keys() -> (u64, u64) {
+    INIT_KEYS.call_once(|| {
+        let keys = match env::var(HASH_SEED_ENV).ok().and_then(|seed| seed.parse::<u32>().ok()) {
+            Some(seed) => keys_from_seed(seed),
+            None => random_keys(),
+        };
+
+        unsafe { SIPHASH_KEYS = keys; }
+    });
+
+    unsafe { SIPHASH_KEYS }
+}
+
+
+/// A seed of 0 disables randomization entirely. Otherwise expand the seed into
+/// two keys using the same LCG constants CPython uses for `PYTHONHASHSEED`.
+fn keys_from_seed(seed: u32) -> (u64, u64) {
+    if seed == 0 {
+        return (0, 0);
+    }
+
+    let mut x = seed as u64;
+    let mut bytes = [0u8; 16];
+    for byte in bytes.iter_mut() {
+        x = x.wrapping_mul(214013).wrapping_add(2531011);
+        *byte = ((x >> 16) & 0xff) as u8;
+    }
+
+    let mut k0 = 0u64;
+    let mut k1 = 0u64;
+    for idx in 0..8 {
+        k0 |= (bytes[idx] as u64) << (8 * idx);
+        k1 |= (bytes[idx + 8] as u64) << (8 * idx);
+    }
+
+    (k0, k1)
+}
+
+
+/// Borrow the randomness of the std `RandomState` so no extra crates are needed
+fn random_keys() -> (u64, u64) {
+    let k0 = RandomState::new().build_hasher().finish();
+    let k1 = RandomState::new().build_hasher().finish();
+    (k0, k1)
+}
+
+
+/// Hash of a raw byte buffer which is used by both `str` and `bytes`
+pub fn hash_bytes(bytes: &[u8]) -> HashId {
+    if bytes.is_empty() {
+        return 0;
+    }
+
+    let (k0, k1) = siphash_keys();
+    let mut hasher = SipHasher::new_with_keys(k0, k1);
+    hasher.write(bytes);
+
+    match hasher.finish() as HashId {
+        -1 => -2,
+        hash => hash,
+    }
+}
+
+
+/// Combine the hashes of the elements of a tuple in order
+pub fn hash_tuple<I>(hashes: I) -> HashId where I: Iterator<Item=HashId> {
+    let mut acc = XXPRIME_5;
+    let mut len = 0u64;
+
+    for hash in hashes {
+        let lane = hash as u64;
+        acc = acc.wrapping_add(lane.wrapping_mul(XXPRIME_2));
+        acc = acc.rotate_left(31);
+        acc = acc.wrapping_mul(XXPRIME_1);
+        len += 1;
+    }
+
+    acc = acc.wrapping_add(len ^ (XXPRIME_5 ^ 3527539));
+
+    if acc == u64::max_value() {
+        return 1546275796;
+    }
+
+    acc as HashId
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hash_bytes_is_stable() {
+        assert_eq!(hash_bytes(b"snek"), hash_bytes(b"snek"));
+        assert_ne!(hash_bytes(b"snek"), hash_bytes(b"kens"));
+        assert_eq!(hash_bytes(b""), 0);
+    }
+
+    #[test]
+    fn keys_from_seed_zero_disables_randomization() {
+        assert_eq!(keys_from_seed(0), (0, 0));
+        assert_eq!(keys_from_seed(42), keys_from_seed(42));
+        assert_ne!(keys_from_seed(42), keys_from_seed(43));
+    }
+
+    /// Matches `hash(())` from CPython 3.8+ on 64 bit platforms
+    #[test]
+    fn hash_empty_tuple() {
+        assert_eq!(hash_tuple(Vec::new().into_iter()), 5740354900026072187);
+    }
+
+    /// Matches `hash((1, 2))` from CPython 3.8+ on 64 bit platforms
+    #[test]
+    fn hash_tuple_of_ints() {
+        assert_eq!(hash_tuple(vec![1, 2].into_iter()), -3550055125485641917);
+    }
+}
@@ -3,7 +3,10 @@
 mod exit;
 mod rc;
 mod thread;
+pub mod hash;
 pub mod primitives;
+pub mod stack;
+pub mod sync;
 
 pub use self::exit::ExitCode;
 pub use self::rc::{StrongRc, WeakRc};
@@ -11,11 +14,14 @@ pub use self::thread::{ThreadModel, Thread, GreenThread, Pthread};
 
 use runtime::Runtime;
 
-// TODO: make configurable
-/// The maximum number of call frames allowed before a RecursionError
-/// is thrown.
+/// The default maximum number of call frames allowed before a RecursionError
+/// is thrown, see `Config::recursion_limit` and `sys.setrecursionlimit`.
 pub const RECURSION_LIMIT: usize = 256;
 
+/// Default size in bytes of the stack of each greenlet, the main function
+/// included when running with green threads.
+pub const GREENLET_STACK_SIZE: usize = 1 << 20;
+
 /// The totally standard args string vector
 pub type Argv<'a> =  &'a [&'a str];
 
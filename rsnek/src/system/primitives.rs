@@ -6,24 +6,27 @@
 //! should return these types directly.
 use std;
 use std::fmt;
-use std::cell::Cell;
-use std::collections::{HashMap, VecDeque};
+use std::collections::HashMap;
+use std::convert::TryFrom;
 use std::str::FromStr;
 
 use num;
 #[allow(unused_imports)]
 use num::{ToPrimitive};
 use num::Num as NumTrait;
-use serde::ser::{Serializer};
 
 use python_ast::{Id, Tag, Num, OwnedTk};
 
+use ::api::method::Length;
 use ::api::result::{ObjectResult, RtResult, Error};
 use ::api::RtObject;
 use ::modules::builtins::Type as BuiltinType;
 use ::objects::collection::sequence::is_sequence;
+use ::objects::collection::buffer;
 use ::objects;
 use ::runtime::{OpCode, Runtime};
+use ::runtime::inline_cache::InlineCaches;
+use ::system::sync::{Cell, RefCell};
 
 /// The representation of the Id of an object as the cast of its memory address to
 /// the platform size. Note that this mimics the CPython way and may change. The only
@@ -31,9 +34,9 @@ use ::runtime::{OpCode, Runtime};
 /// implementation detail and is subject to change.
 pub type ObjectId = usize;
 
-/// Hashes are currently computed using the rust std machinery that computes hashes
-/// as u64. This is considered an implementation detail and is subject to change.
-pub type HashId = u64;
+/// Hashes are signed 64 bit values to mirror CPython's `Py_hash_t` so that the numeric
+/// types can follow CPython's hashing scheme. See `system::hash` and `objects::number`.
+pub type HashId = i64;
 
 /// All integer values are aliased to use `BigInt` by default
 /// to support python's idea of unbounded integer types.
@@ -72,10 +75,30 @@ pub type List = Vec<RtObject>;
 pub type Tuple = Vec<RtObject>;
 
 
+/// Backing value of a `slice(start, stop, step)` object. The members are kept as objects
+/// rather than native integers since python allows `None` for any of them and defers the
+/// conversion to indices until the slice is applied to a sequence.
+#[derive(Clone, Debug)]
+pub struct Slice {
+    pub start: RtObject,
+    pub stop: RtObject,
+    pub step: RtObject,
+}
+
+
+/// The bounds of a `range()`, the step is never zero.
+#[derive(Clone, Debug)]
+pub struct Range {
+    pub start: Integer,
+    pub stop: Integer,
+    pub step: Integer,
+}
+
+
 /// Necessary to hold the computed value of the hash since RtObject cannot call
 /// `op_hash` without a reference to the `Runtime`. So the `DictKey::hash` should
 /// should be the value returned from `op_hash` or `native_hash`.
-#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+#[derive(Clone, Debug, Hash)]
 pub struct DictKey(HashId, RtObject);
 
 impl DictKey {
@@ -92,6 +115,16 @@ impl DictKey {
     }
 }
 
+impl std::cmp::PartialEq for DictKey {
+    /// Keys holding the same object are equal without comparing their values,
+    /// so lookups with interned strings are a pointer comparison.
+    fn eq(&self, rhs: &DictKey) -> bool {
+        self.0 == rhs.0 && (self.1.id() == rhs.1.id() || self.1 == rhs.1)
+    }
+}
+
+impl std::cmp::Eq for DictKey {}
+
 
 /// Dictionaries use the standard rust HashMap from collections that map
 /// RtObject => RtObject. They are keyed using the `DictKey` instead in order
@@ -110,8 +143,23 @@ pub type Set = std::collections::HashSet<SetElement>;
 /// `fn (rt: &Runtime, args: &RtObject, stargs: &RtObject, kwargs: &RtObject) -> ObjectResult`
 ///
 /// Especially useful for creating method wrappers or exposing rust closures as python objects.
+/// With `rsnek_multithreaded` function objects are shared between threads, so the closure
+/// and everything it captures must be `Send + Sync`.
+#[cfg(not(feature="rsnek_multithreaded"))]
 pub type WrapperFn = Fn(&Runtime, &RtObject, &RtObject, &RtObject) -> ObjectResult;
 
+#[cfg(feature="rsnek_multithreaded")]
+pub type WrapperFn = Fn(&Runtime, &RtObject, &RtObject, &RtObject) -> ObjectResult + Send + Sync;
+
+/// A native method of a builtin type, called with the object it is bound to before the
+/// same arguments as a `WrapperFn`. It does not capture the object, so one `MethodFn`
+/// serves every object of the type.
+#[cfg(not(feature="rsnek_multithreaded"))]
+pub type MethodFn = Fn(&Runtime, &RtObject, &RtObject, &RtObject, &RtObject) -> ObjectResult;
+
+#[cfg(feature="rsnek_multithreaded")]
+pub type MethodFn = Fn(&Runtime, &RtObject, &RtObject, &RtObject, &RtObject) -> ObjectResult + Send + Sync;
+
 
 /// Struct defining the data needed for a Python function including the name, signature, module,
 /// and callable. Note that `FuncType` allows for both native functions and a bytecode object
@@ -131,7 +179,7 @@ pub enum FuncType {
     #[serde(skip_serializing)]
     Wrapper(Box<WrapperFn>),
     #[serde(skip_serializing)]
-    MethodWrapper(RtObject, Box<WrapperFn>),
+    MethodWrapper(RtObject, std::sync::Arc<MethodFn>),
     Code(Code),
 }
 
@@ -157,11 +205,22 @@ impl fmt::Debug for FuncType {
 
 
 /// Wrapper to hold iterators that come from difference sources.
-/// Currently, the only supported iterators are `Empty` and well known
-/// `BuiltinType` variants.
+///
+/// `Sequence` and `Reversed` index into well known `BuiltinType` sequence variants
+/// and `Range` counts without a backing collection. The remaining variants are lazy
+/// adapters whose `source`/`sources` are themselves iterator objects, so native and
+/// python iterators compose without materializing intermediate lists.
 #[derive(Debug)]
 pub enum Iterator {
     Sequence {source: RtObject, idx_next: Cell<Count>},
+    /// Walks a sequence backwards, `idx_next` is the count of remaining elements
+    Reversed {source: RtObject, idx_next: Cell<Count>},
+    Range {next: RefCell<Integer>, stop: Integer, step: Integer},
+    Enumerate {source: RtObject, count: RefCell<Integer>},
+    Zip {sources: Vec<RtObject>},
+    Map {func: RtObject, sources: Vec<RtObject>},
+    /// A `None` func filters on the truthiness of the elements
+    Filter {func: RtObject, source: RtObject},
     Empty,
 }
 
@@ -174,6 +233,28 @@ impl Iterator {
         Err(Error::typerr(
             &format!("'{}' is not a sequence", source.debug_name())))
     }
+
+    /// Iterate over a sequence from the last element to the first
+    pub fn reversed(source: &RtObject) -> RtResult<Self> {
+        if !is_sequence(source) {
+            return Err(Error::typerr(
+                &format!("'{}' is not a sequence", source.debug_name())))
+        }
+
+        match source.native_len()?.to_usize() {
+            Some(len) => Ok(Iterator::Reversed {source: source.clone(), idx_next: Cell::new(len)}),
+            None => Err(Error::overflow("sequence length does not fit in a native size"))
+        }
+    }
+
+    /// Count from `range.start` towards `range.stop` by `range.step`
+    pub fn range(range: &Range) -> Self {
+        Iterator::Range {
+            next: RefCell::new(range.start.clone()),
+            stop: range.stop.clone(),
+            step: range.step.clone(),
+        }
+    }
 }
 
 /// Work in progress to define the properties required to a generic python defined
@@ -207,44 +288,98 @@ pub struct Type {
     pub name: String,
     pub module: String,
     pub bases: Tuple,
-    pub subclasses: std::cell::RefCell<List>,
+    pub subclasses: RefCell<List>,
 }
 
 /// Defines the bytecode object.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Code {
     pub co_name: String,
+    /// Name of the file the code was compiled from, or a pseudo name like
+    /// `<string>` for code that did not come from a file.
+    pub co_filename: String,
     pub co_names: Vec<String>,
     pub co_varnames: Vec<String>,
     pub co_code: Vec<Instr>,
     pub co_consts: Vec<Code>,
+    /// Line table of `(offset, line)` entries sorted by offset, empty when the
+    /// code sets its line numbers with `SetLineNumber` instructions.
+    pub co_lnotab: Vec<LineEntry>,
+    /// Inline caches of the instructions, shared by the clones of the code
+    #[serde(skip_serializing, skip_deserializing)]
+    pub co_caches: InlineCaches,
     //pub co_argcount: Int,
     //pub co_cellvars: Tuple,
-    //pub co_filename: Str,
     //pub co_firstlineno: Int,
     //pub co_flags: Int,
     //pub co_freevars: Tuple,
     //pub co_kwonlyargcount: Int,
-    //pub co_lnotab: Bytes,
 
     //pub co_nlocals: Int,
     //pub co_stacksize: Int,
 }
 
 
-/// Placeholder for try/catch block accounting
-#[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize)]
-pub struct Block {}
+impl Code {
+    /// Top level code of a module compiled from `filename`
+    pub fn module(filename: &str, co_code: Vec<Instr>) -> Self {
+        Code {
+            co_name: String::from("<module>"),
+            co_filename: String::from(filename),
+            co_names: Vec::new(),
+            co_varnames: Vec::new(),
+            co_code: co_code,
+            co_consts: Vec::new(),
+            co_lnotab: Vec::new(),
+            co_caches: InlineCaches::default(),
+        }
+    }
+}
+
+
+/// Entry of `Code::co_lnotab`, the instruction at `offset` and the instructions
+/// after it up to the next entry are on `line`.
+pub type LineEntry = (Count, Count);
+
+
+/// Source line of the instruction at `offset` according to the line table `lnotab`
+pub fn line_for_offset(lnotab: &[LineEntry], offset: Count) -> Option<Count> {
+    match lnotab.binary_search_by_key(&offset, |&(start, _)| start) {
+        Ok(idx) => Some(lnotab[idx].1),
+        Err(0) => None,
+        Err(idx) => Some(lnotab[idx - 1].1)
+    }
+}
+
+
+/// Kinds of entries on a frame's block stack. Only loops for now,
+/// exception handlers will need `Except` and `Finally`.
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, Serialize)]
+pub enum BlockType {
+    Loop,
+}
 
 
+/// Block stack entry pushed by `SetupLoop` and friends
 #[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize)]
+pub struct Block {
+    pub kind: BlockType,
+    /// Instruction offset to jump to when leaving the block
+    pub handler: Count,
+    /// Depth of the value stack when the block was entered
+    pub level: Count,
+}
+
+
+#[derive(Clone, Debug, Serialize)]
 pub struct Frame {
     pub f_back: RtObject,
     pub f_code: RtObject,
     pub f_builtins: RtObject,
-    #[serde(serialize_with = "serialize::integer")]
-    pub f_lasti: Integer,
-    pub blocks: VecDeque<Block>,
+    /// Offset of the last instruction that was executed in this frame
+    pub f_lasti: Cell<Count>,
+    /// Line of the statement being run in this frame, 0 before the first one
+    pub f_lineno: Cell<Count>,
 }
 
 
@@ -398,7 +533,7 @@ impl SignatureBuilder for Vec<String> {
 }
 
 /// Instruction type used by the compiler and interpreter
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Instr(pub OpCode, pub Option<Native>);
 
 
@@ -420,11 +555,12 @@ impl Instr {
 /// Enum of well known primitive types similar to `modules::builtins::type::Type`.
 /// for uses where appropriate such as return values of native api methods,
 /// the compiler, etc.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Native {
     Str(String),
+    Bytes(Bytes),
     Int(
-        #[serde(serialize_with = "serialize::integer")]
+        #[serde(with = "serialize::integer")]
         Integer
     ),
     Float(Float),
@@ -435,43 +571,55 @@ pub enum Native {
     ),
     Count(Count),
     Code(Code),
+    /// Runtime objects only exist for the lifetime of a `Runtime` and
+    /// are never part of compiled code.
+    #[serde(skip_deserializing)]
     List(List),
     None,
 }
 
 
-impl<'a> From<&'a OwnedTk> for Native {
-    // TODO: {T96} Refactor to use stdlib traits From / TryFrom if possible
-    // TODO: {T96} unwrap() can cause panics, make this able to return a result
-    /// Convert an `&OwnedTk` into a `Native` type by token id.
-    fn from(tk: &'a OwnedTk) -> Self {
-        let parsed = String::from_utf8(tk.bytes().to_vec()).unwrap();
+impl<'a> TryFrom<&'a OwnedTk> for Native {
+    type Error = Error;
+
+    /// Convert an `&OwnedTk` into a `Native` type by token id. Literals that the
+    /// lexer accepts but that have no value, like `b'\xZZ'`, are errors the
+    /// compiler reports as a `SyntaxError` at the token.
+    fn try_from(tk: &'a OwnedTk) -> Result<Self, Error> {
+        let parsed = match String::from_utf8(tk.bytes().to_vec()) {
+            Ok(parsed) => parsed,
+            Err(_) => return Err(Error::syntax("source is not valid utf-8"))
+        };
         let content = parsed.as_str();
+        let invalid = || Error::value(&format!("invalid literal: {}", content));
 
-        match (tk.id(), tk.tag()) {
+        let native = match (tk.id(), tk.tag()) {
             (Id::Name, _)     => Native::Str(parsed.clone()),
             (Id::String, _)         |
             (Id::RawString, _)      |
-            (Id::FormatString, _)   |
-            (Id::ByteString, _)     => {
+            (Id::FormatString, _)   => {
                 // TODO: {T96} This is a hack to get the " or ' off of quoted strings
                 Native::Str(parsed[1..parsed.len()-1].to_string())
             },
-            (Id::Number, Tag::N(Num::Int))   => Native::Int(Integer::from_str(&parsed).unwrap()),
-            (Id::Number, Tag::N(Num::Binary))=> Native::Int(Integer::from_str_radix(&parsed[2..], 2).unwrap()),
-            (Id::Number, Tag::N(Num::Octal)) => Native::Int(Integer::from_str_radix(&parsed[2..], 8).unwrap()),
-            (Id::Number, Tag::N(Num::Hex))   => Native::Int(Integer::from_str_radix(&parsed[2..], 16).unwrap()),
-            (Id::Number, Tag::N(Num::Float)) => Native::Float(content.parse::<f64>().unwrap()),
+            (Id::ByteString, _)     => Native::Bytes(buffer::decode_literal(tk.bytes())?),
+            (Id::Number, Tag::N(Num::Int))   => Native::Int(Integer::from_str(&parsed).map_err(|_| invalid())?),
+            (Id::Number, Tag::N(Num::Binary))=> Native::Int(Integer::from_str_radix(&parsed[2..], 2).map_err(|_| invalid())?),
+            (Id::Number, Tag::N(Num::Octal)) => Native::Int(Integer::from_str_radix(&parsed[2..], 8).map_err(|_| invalid())?),
+            (Id::Number, Tag::N(Num::Hex))   => Native::Int(Integer::from_str_radix(&parsed[2..], 16).map_err(|_| invalid())?),
+            (Id::Number, Tag::N(Num::Float)) => Native::Float(content.parse::<f64>().map_err(|_| invalid())?),
             (Id::Number, Tag::N(Num::Complex)) => {
                 let real: Float = 0.0;
-                let img: Float =  content[..content.len()-1].parse::<f64>().unwrap();
+                let img: Float =  content[..content.len()-1].parse::<f64>().map_err(|_| invalid())?;
                 Native::Complex(Complex::new(real, img))
             },
             (Id::True, _) => Native::Bool(true),
             (Id::False, _) => Native::Bool(false),
             (Id::None, _) => Native::None,
-            _ => unimplemented!()
-        }
+            _ => return Err(Error::system(&format!(
+                "Token {:?} is not a literal; file: {}, line: {}", tk.id(), file!(), line!())))
+        };
+
+        Ok(native)
     }
 }
 
@@ -485,7 +633,7 @@ impl<'a> From<&'a str> for Native {
 
 /// Serde calls this the definition of the remote type. It is just a copy of the
 /// remote type. The `remote` attribute gives the path to the actual type.
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 #[serde(remote = "Complex")]
 #[doc(hidden)]
 struct ComplexSerdeDef {
@@ -497,23 +645,30 @@ struct ComplexSerdeDef {
 
 
 pub mod serialize {
-    use super::*;
 
-    /// The epsilon for doubles (the only JS number type)
-    /// becomes nonzero after 2**54 and integer values
-    /// start to loose precision.
-    const JSON_MAX_INT_BITS_LOSSLESS: usize = 54;
+    /// Serialize `rs::Integer` values as their decimal string. JSON doubles lose
+    /// precision after 2**54 and a single representation for all integer sizes
+    /// keeps non self describing formats like bincode readable.
+    pub mod integer {
+        use std::str::FromStr;
 
+        use serde::{Deserialize, Deserializer, Serializer};
+        use serde::de::Error;
 
-    /// Serialize a rs::Integer type as a string if it will fit in a JSON double
-    /// otherwise a string.
-    pub fn integer<S>(int: &Integer, serializer: S) -> Result<S::Ok, S::Error>
-        where S: Serializer
-    {
-        if int.bits() > JSON_MAX_INT_BITS_LOSSLESS {
+        use super::super::Integer;
+
+        pub fn serialize<S>(int: &Integer, serializer: S) -> Result<S::Ok, S::Error>
+            where S: Serializer
+        {
             serializer.serialize_str(&format!("{}", int))
-        } else {
-            serializer.serialize_i64(int.to_i64().unwrap())
+        }
+
+        pub fn deserialize<'de, D>(deserializer: D) -> Result<Integer, D::Error>
+            where D: Deserializer<'de>
+        {
+            let string = String::deserialize(deserializer)?;
+            Integer::from_str(&string).map_err(|_| D::Error::custom(
+                format!("invalid integer literal {:?}", string)))
         }
     }
 
@@ -0,0 +1,134 @@
+//! Native stack bounds for guarding deep recursion
+//!
+//! Python calls recurse through the Rust stack, so a high recursion limit can run
+//! out of native stack before it runs out of frames. Each thread, and each greenlet
+//! while it is running, records where its stack starts and how large it is. The
+//! interpreter refuses to push a frame once less than `RED_ZONE` bytes are left
+//! and raises a `RecursionError` instead of overflowing.
+//!
+//! The size of a stack that was never entered with `enter`, like the main
+//! thread's, is not known so `DEFAULT_STACK_SIZE` is assumed from the point the
+//! first frame is pushed.
+//!
+use std::cell::Cell;
+
+
+/// Bytes that must be left on the stack to push another frame
+pub const RED_ZONE: usize = 256 * 1024;
+
+/// Smallest stack a greenlet can be given, the `RED_ZONE` and enough room above it
+/// to run a module and a few calls.
+pub const MIN_STACK_SIZE: usize = RED_ZONE + 256 * 1024;
+
+/// Assumed size of stacks with unknown bounds, the default for spawned Rust threads
+/// which is smaller than the main thread's.
+pub const DEFAULT_STACK_SIZE: usize = 2 << 20;
+
+
+/// The address the stack starts at and its size in bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bounds {
+    base: usize,
+    size: usize,
+}
+
+
+thread_local!(
+    static BOUNDS: Cell<Option<Bounds>> = Cell::new(None)
+);
+
+
+#[inline(never)]
+fn stack_pointer() -> usize {
+    let marker = 0u8;
+    &marker as *const u8 as usize
+}
+
+
+/// Record the current position as the start of a stack of `size` bytes
+pub fn enter(size: usize) {
+    restore(Some(Bounds { base: stack_pointer(), size: size }));
+}
+
+
+/// The bounds of the running stack, to be given back to `restore` after
+/// switching to another stack and back.
+pub fn current() -> Option<Bounds> {
+    BOUNDS.with(|bounds| bounds.get())
+}
+
+
+pub fn restore(bounds: Option<Bounds>) {
+    BOUNDS.with(|current| current.set(bounds));
+}
+
+
+/// Bytes left on the running stack
+pub fn remaining() -> usize {
+    let bounds = match current() {
+        Some(bounds) => bounds,
+        None => {
+            enter(DEFAULT_STACK_SIZE);
+            return DEFAULT_STACK_SIZE
+        }
+    };
+
+    let sp = stack_pointer();
+    let used = if sp > bounds.base { sp - bounds.base } else { bounds.base - sp };
+    bounds.size.saturating_sub(used)
+}
+
+
+/// True if there is room for another frame on the running stack
+pub fn has_headroom() -> bool {
+    remaining() > RED_ZONE
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+    use test;
+    use super::*;
+
+    /// Recurse with 1kb frames until the red zone is reached. The padding is used
+    /// after the call so it cannot become a loop.
+    #[inline(never)]
+    fn depth_until_exhausted(depth: usize) -> usize {
+        let padding = [0u8; 1024];
+        if !has_headroom() {
+            return depth
+        }
+
+        let exhausted = depth_until_exhausted(depth + 1);
+        test::black_box(&padding);
+        exhausted
+    }
+
+    #[test]
+    fn exhausts_before_overflow() {
+        let handle = thread::spawn(|| {
+            enter(DEFAULT_STACK_SIZE);
+            depth_until_exhausted(0)
+        });
+
+        assert!(handle.join().unwrap() > 0);
+    }
+
+    #[test]
+    fn restore_bounds() {
+        let handle = thread::spawn(|| {
+            assert_eq!(current(), None);
+            enter(1 << 20);
+            let saved = current();
+
+            enter(RED_ZONE);
+            assert!(!has_headroom());
+
+            restore(saved);
+            assert!(has_headroom());
+        });
+
+        handle.join().unwrap();
+    }
+}
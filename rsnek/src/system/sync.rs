@@ -0,0 +1,153 @@
+//! Configure the interior mutability of object state through compile time feature flags.
+//!
+//! Objects hold their mutable state in the `Cell` and `RefCell` types exported here
+//! instead of the ones in `std::cell`. They are the `std::cell` types by default. With
+//! the `rsnek_multithreaded` feature they have the same interface but are backed by
+//! locks, so every object guards its own state and threads sharing a `Runtime` do
+//! not need a global interpreter lock.
+//!
+//! A `RefCell` borrowed mutably on one thread blocks other threads until the borrow
+//! ends instead of panicking. Borrowing one mutably while it is already borrowed on
+//! the same thread deadlocks where the `std::cell` version would panic, both are bugs.
+//!
+#[cfg(not(feature="rsnek_multithreaded"))]
+use self::impl_single_threaded as internal;
+
+#[cfg(feature="rsnek_multithreaded")]
+use self::impl_multithreaded as internal;
+
+
+pub use self::internal::{Cell, Ref, RefCell, RefMut};
+
+
+/// Single threaded cells enabled by default
+mod impl_single_threaded {
+    pub use std::cell::{Cell, Ref, RefCell, RefMut};
+}
+
+
+/// Lock backed cells enabled with `--cfg-feature="rsnek_multithreaded`
+#[cfg(feature="rsnek_multithreaded")]
+mod impl_multithreaded {
+    use std::fmt;
+    use std::sync::{Mutex, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard, TryLockError};
+
+    use serde::ser::{Serialize, Serializer};
+
+    pub type Ref<'a, T> = RwLockReadGuard<'a, T>;
+    pub type RefMut<'a, T> = RwLockWriteGuard<'a, T>;
+
+
+    /// Same interface as `std::cell::RefCell` over a `RwLock`
+    pub struct RefCell<T>(RwLock<T>);
+
+    /// Returned by `RefCell::try_borrow` and `RefCell::try_borrow_mut` when another
+    /// thread holds a conflicting borrow.
+    #[derive(Debug)]
+    pub struct BorrowError;
+
+    impl<T> RefCell<T> {
+        pub fn new(value: T) -> Self {
+            RefCell(RwLock::new(value))
+        }
+
+        /// A panic while a borrow was held does not make the value any less usable
+        /// than the `std::cell::RefCell` would leave it, so poisoning is ignored.
+        pub fn borrow(&self) -> Ref<T> {
+            self.0.read().unwrap_or_else(PoisonError::into_inner)
+        }
+
+        pub fn borrow_mut(&self) -> RefMut<T> {
+            self.0.write().unwrap_or_else(PoisonError::into_inner)
+        }
+
+        pub fn try_borrow(&self) -> Result<Ref<T>, BorrowError> {
+            match self.0.try_read() {
+                Ok(guard) => Ok(guard),
+                Err(TryLockError::Poisoned(err)) => Ok(err.into_inner()),
+                Err(TryLockError::WouldBlock) => Err(BorrowError)
+            }
+        }
+
+        pub fn try_borrow_mut(&self) -> Result<RefMut<T>, BorrowError> {
+            match self.0.try_write() {
+                Ok(guard) => Ok(guard),
+                Err(TryLockError::Poisoned(err)) => Ok(err.into_inner()),
+                Err(TryLockError::WouldBlock) => Err(BorrowError)
+            }
+        }
+
+        pub fn into_inner(self) -> T {
+            self.0.into_inner().unwrap_or_else(PoisonError::into_inner)
+        }
+    }
+
+    impl<T: Clone> Clone for RefCell<T> {
+        fn clone(&self) -> Self {
+            RefCell::new(self.borrow().clone())
+        }
+    }
+
+    impl<T: Default> Default for RefCell<T> {
+        fn default() -> Self {
+            RefCell::new(T::default())
+        }
+    }
+
+    impl<T: fmt::Debug> fmt::Debug for RefCell<T> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self.try_borrow() {
+                Ok(value) => write!(f, "RefCell {{ value: {:?} }}", *value),
+                Err(_) => write!(f, "RefCell {{ value: <borrowed> }}")
+            }
+        }
+    }
+
+    impl<T: Serialize> Serialize for RefCell<T> {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+            self.borrow().serialize(serializer)
+        }
+    }
+
+
+    /// Same interface as `std::cell::Cell` over a `Mutex`
+    pub struct Cell<T: Copy>(Mutex<T>);
+
+    impl<T: Copy> Cell<T> {
+        pub fn new(value: T) -> Self {
+            Cell(Mutex::new(value))
+        }
+
+        pub fn get(&self) -> T {
+            *self.0.lock().unwrap_or_else(PoisonError::into_inner)
+        }
+
+        pub fn set(&self, value: T) {
+            *self.0.lock().unwrap_or_else(PoisonError::into_inner) = value;
+        }
+    }
+
+    impl<T: Copy> Clone for Cell<T> {
+        fn clone(&self) -> Self {
+            Cell::new(self.get())
+        }
+    }
+
+    impl<T: Copy + Default> Default for Cell<T> {
+        fn default() -> Self {
+            Cell::new(T::default())
+        }
+    }
+
+    impl<T: Copy + fmt::Debug> fmt::Debug for Cell<T> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "Cell {{ value: {:?} }}", self.get())
+        }
+    }
+
+    impl<T: Copy + Serialize> Serialize for Cell<T> {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+            self.get().serialize(serializer)
+        }
+    }
+}
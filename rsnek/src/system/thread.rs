@@ -1,19 +1,14 @@
 //! Where the magic happens...
-use std::borrow::Borrow;
-use std::cell::{Ref, Cell, RefCell};
-use std::collections::HashMap;
-use std::collections::vec_deque::VecDeque;
-use std::convert::From;
-use std::fs::File;
-use std::io::{self, Read, Write};
-use std::marker::Sync;
-use std::ops::{Deref};
+use std::cell::Cell;
+use std::thread;
 
 use fringe::generator::Yielder;
 use fringe::{OsStack, Generator};
 
 use ::runtime::Runtime;
-use ::system::{SharedMainFnRef, MainFnRef, MainFn};
+use ::runtime::scheduler;
+use ::system::{SharedMainFnRef, MainFnRef};
+use ::system::stack;
 
 /// Optimistic definitions pf supported threading models
 #[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize)]
@@ -54,7 +49,9 @@ impl<'a> Thread<'a> for Pthread<'a> {
 /// Wrapper around a sharable function pointer that can be used as the
 /// entry point of a generator driven userland managed thread stack.
 pub struct GreenThread<'a> {
-    pub func: SharedMainFnRef<'a>
+    pub func: SharedMainFnRef<'a>,
+    /// Size in bytes of the stack of the main greenlet
+    pub stack_size: usize,
 }
 
 
@@ -63,49 +60,58 @@ impl<'a> Thread<'a> for GreenThread<'a> {
         self.run(&rt)
     }
 
-    /// Note: Since `Runtime` doesn't implement Sync or Send
-    /// it cannot be passed into the context of the greenlet.
-    /// It is on the roadmap to fix that so there is not the
-    /// need to create 2 runtimes.
-    #[allow(unused_variables)]
+    /// Run the main function as a greenlet. Whenever it blocks the other
+    /// greenlets spawned through the scheduler get to run until it is ready
+    /// again, the program ends with the main greenlet. When none of them can
+    /// get any further `scheduler::block_until` raises a `RuntimeError` in the
+    /// waiting greenlet, so this loop does not spin with nothing able to run.
     fn run<'b>(&self, rt: &'b Runtime) -> i64 {
-        /// Start the stack off with 4kb
-        let stack = OsStack::new(1 << 12).unwrap();
-
-        let mut gen = Generator::new(stack, move |yielder, ()| {
-            let main_thread = Greenlet {
-                yielder: yielder,
-                func: self.func.clone()
-            };
-            let rt = Runtime::new();
-
-            main_thread.start(&rt);
-        });
-
-        let mut prev: Option<i64> = None;
-
-        /// The hallowed event loop
-        loop {
-            let out = gen.resume(());
-            match out {
-                None => { break },
-                _ => (),
-            };
-            prev = out;
+        let os_stack = match OsStack::new(self.stack_size) {
+            Ok(stack) => stack,
+            Err(err) => {
+                error!("GreenThread"; "action" => "allocate stack", "size" => self.stack_size,
+                       "error" => format!("{}", err));
+                return 1
+            }
+        };
+
+        let status = Cell::new(0);
+
+        {
+            let mut gen = Generator::new(os_stack, |yielder, ()| {
+                let main_thread = Greenlet {
+                    yielder: yielder,
+                    func: self.func.clone(),
+                    stack_size: self.stack_size,
+                };
+
+                status.set(main_thread.start(rt));
+            });
+
+            let bounds = stack::current();
+
+            /// The hallowed event loop
+            while let Some(()) = gen.resume(()) {
+                stack::restore(bounds);
+                if !scheduler::run_one() {
+                    thread::yield_now();
+                }
+            }
         }
 
-        prev.unwrap_or(0)
+        status.get()
     }
 }
 
 
 /// The greenlet is created by a GreenThread and represents a resumbale thread context
-/// with its own stack. `func` is the entry point for the greenlet and `yielde` is the
+/// with its own stack. `func` is the entry point for the greenlet and `yielder` is the
 /// libfringe special sauce that is causes the user mode context switch on
 /// call to `Yielder::suspend`.
 struct Greenlet<'a> {
-    yielder: &'a mut Yielder<(), i64>,
+    yielder: &'a Yielder<(), ()>,
     func: SharedMainFnRef<'a>,
+    stack_size: usize,
 }
 
 
@@ -115,9 +121,9 @@ impl<'a> Thread<'a> for Greenlet<'a> {
     }
 
     fn run(&self, rt: &Runtime) -> i64 {
+        scheduler::enter(self.yielder);
+        stack::enter(self.stack_size);
         let func = self.func.0.clone();
-        self.yielder.suspend(func(rt));
-        0
+        func(rt)
     }
 }
-
@@ -0,0 +1,143 @@
+extern crate rsnek;
+
+mod support;
+
+use rsnek::runtime::Coverage;
+use std::env;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::Output;
+
+use support::command;
+
+
+/// Each run calls one of the functions, `f` when it is given an argument. There
+/// are no blank lines so that each line is a statement of a single function.
+const SCRIPT: &'static str = "\
+def f():
+    return 1
+def g():
+    return 2
+argv = sys.argv
+f() if len(argv) > 1 else g()
+";
+
+
+/// A fresh path in the temp dir for the files of the test `name`
+fn temp_path(name: &str) -> PathBuf {
+    let path = env::temp_dir().join(format!("rsnek-coverage-{}", name));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+
+fn write_script(name: &str) -> PathBuf {
+    let path = temp_path(&format!("{}.py", name));
+    File::create(&path).unwrap().write_all(SCRIPT.as_bytes()).unwrap();
+    path
+}
+
+
+fn run(report: &Path, script: &Path, args: &[&str]) -> Output {
+    run_with(&[], report, script, args)
+}
+
+
+fn run_with(flags: &[&str], report: &Path, script: &Path, args: &[&str]) -> Output {
+    command()
+        .args(flags)
+        .arg(format!("--coverage={}", report.display()))
+        .arg(script)
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+
+#[test]
+fn json_report() {
+    let script = write_script("json");
+    let report = temp_path("json.json");
+    let filename = script.to_str().unwrap();
+
+    let output = run(&report, &script, &["x"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let coverage = Coverage::load(&report).unwrap();
+    assert_eq!(coverage.files(), vec![filename]);
+    assert!(coverage.executed(filename).contains(&2));
+    assert!(coverage.executed(filename).contains(&6));
+    assert_eq!(coverage.missing(filename), vec![4]);
+
+    let mut text = String::new();
+    File::open(&report).unwrap().read_to_string(&mut text).unwrap();
+    assert!(text.contains("\"missing\": [\n        4\n      ]"), "{}", text);
+}
+
+
+/// A report that already exists is merged with the coverage of the next run
+#[test]
+fn merge_runs() {
+    let script = write_script("merge");
+    let report = temp_path("merge.json");
+    let filename = script.to_str().unwrap();
+
+    assert!(run(&report, &script, &["x"]).status.success());
+    assert!(run(&report, &script, &[]).status.success());
+
+    let coverage = Coverage::load(&report).unwrap();
+    assert!(coverage.missing(filename).is_empty(), "{:?}", coverage);
+    assert_eq!(coverage.hits(filename, 2), Some(1));
+    assert_eq!(coverage.hits(filename, 4), Some(1));
+    assert_eq!(coverage.hits(filename, 6), Some(2));
+}
+
+
+#[test]
+fn lcov_report() {
+    let script = write_script("lcov");
+    let report = temp_path("lcov.info");
+
+    assert!(run(&report, &script, &[]).status.success());
+
+    let mut text = String::new();
+    File::open(&report).unwrap().read_to_string(&mut text).unwrap();
+
+    assert!(text.starts_with(&format!("TN:\nSF:{}\n", script.display())), "{}", text);
+    assert!(text.contains("\nDA:2,0\n"), "{}", text);
+    assert!(text.contains("\nDA:4,1\n"), "{}", text);
+    assert!(text.ends_with("end_of_record\n"), "{}", text);
+}
+
+
+/// Errors end the program, the lines that ran up to them are still reported
+#[test]
+fn report_after_error() {
+    let script = temp_path("error.py");
+    File::create(&script).unwrap().write_all(b"x = 1\nundefined_name\ny = 2\n").unwrap();
+    let report = temp_path("error.json");
+    let filename = script.to_str().unwrap();
+
+    assert!(!run(&report, &script, &[]).status.success());
+
+    let coverage = Coverage::load(&report).unwrap();
+    assert_eq!(coverage.executed(filename), vec![1, 2]);
+    assert_eq!(coverage.missing(filename), vec![3]);
+}
+
+
+/// Lines the optimizer removes as dead code are still statements that never ran
+#[test]
+fn optimized_dead_code() {
+    let script = temp_path("dead.py");
+    File::create(&script).unwrap().write_all(b"def f():\n    return 1\n    x = 2\nf()\n").unwrap();
+    let report = temp_path("dead.json");
+    let filename = script.to_str().unwrap();
+
+    assert!(run_with(&["-O"], &report, &script, &[]).status.success());
+
+    let coverage = Coverage::load(&report).unwrap();
+    assert_eq!(coverage.executed(filename), vec![1, 2, 4]);
+    assert_eq!(coverage.missing(filename), vec![3]);
+}
@@ -0,0 +1,111 @@
+extern crate rsnek;
+
+mod support;
+
+use std::io::Write;
+use std::process::{Output, Stdio};
+
+use support::command;
+
+
+/// Run `code` with `args` and type `commands` into the debugger
+fn debug(args: &[&str], code: &str, commands: &str) -> Output {
+    let mut child = command()
+        .args(args)
+        .arg("-c")
+        .arg(code)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    // Dropping stdin ends the input once the commands are written
+    child.stdin.take().unwrap().write_all(commands.as_bytes()).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+
+#[test]
+fn stops_at_first_line() {
+    let output = debug(&["--debug"], "x = 1\nprint(x + 1)", "c\n");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success());
+    assert!(stdout.starts_with("> <string>(1)<module>()\n-> x = 1\n(rdb) "), "{}", stdout);
+    assert!(stdout.ends_with("2\n"), "{}", stdout);
+}
+
+
+#[test]
+fn breakpoint_where_and_print() {
+    let code = "def f(a):\n    b = a + 1\n    return b\n\nf(1)\nprint('done')";
+    let output = debug(&["--debug"], code, "b 3\nc\np b\nw\nc\n");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success());
+    assert!(stdout.contains("Breakpoint at <string>:3\n"), "{}", stdout);
+    assert!(stdout.contains("> <string>(3)f()\n-> return b\n(rdb) 2\n"), "{}", stdout);
+    assert!(stdout.contains("  <string>(5)<module>()\n-> f(1)\n> <string>(3)f()\n"), "{}", stdout);
+    assert!(stdout.ends_with("done\n"), "{}", stdout);
+}
+
+
+#[test]
+fn step_and_next() {
+    let code = "def f(a):\n    return a\n\nx = f(1)\ny = 2\nprint('done')";
+    let output = debug(&["--debug"], code, "n\ns\nn\nc\n");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    let stops: Vec<&str> = stdout.lines()
+        .filter(|line| line.contains("> <string>("))
+        .map(|line| line.trim_left_matches("(rdb) "))
+        .collect();
+
+    assert_eq!(stops, vec![
+        "> <string>(1)<module>()",
+        "> <string>(4)<module>()",
+        "> <string>(2)f()",
+        "> <string>(5)<module>()",
+    ]);
+    assert!(stdout.ends_with("done\n"), "{}", stdout);
+}
+
+
+#[test]
+fn breakpoint_builtin() {
+    let code = "x = 1\nbreakpoint()\ny = x + 1\nprint(y)";
+    let output = debug(&[], code, "p x\nx = 5\nc\n");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success());
+    assert!(stdout.starts_with("> <string>(3)<module>()\n-> y = x + 1\n(rdb) 1\n"), "{}", stdout);
+    assert!(stdout.ends_with("6\n"), "{}", stdout);
+}
+
+
+#[test]
+fn errors_do_not_stop_the_debugger() {
+    let output = debug(&["--debug"], "print('done')", "p undefined_name\nc\n");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success());
+    assert!(stdout.contains("*** NameError"), "{}", stdout);
+    assert!(stdout.ends_with("done\n"), "{}", stdout);
+}
+
+
+#[test]
+fn end_of_input_runs_to_the_end() {
+    let output = debug(&["--debug"], "print('done')", "");
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).ends_with("done\n"));
+}
+
+
+#[test]
+fn quit() {
+    let output = debug(&["--debug"], "print('never')", "q\n");
+    assert!(output.status.success());
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("never\n"));
+}
@@ -0,0 +1,67 @@
+extern crate rsnek;
+
+mod support;
+
+use std::process::Output;
+
+use support::run;
+
+
+fn dump(kind: &str, code: &str) -> Output {
+    run(&[&format!("--dump={}", kind)], code, &[])
+}
+
+
+/// Dumping does not run the code
+#[test]
+fn dump_does_not_run() {
+    let output = dump("bytecode", "print('never')");
+    assert!(output.status.success());
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("never\n"));
+}
+
+
+#[test]
+fn dump_tokens() {
+    let output = dump("tokens", "x = 1");
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Name"));
+}
+
+
+#[test]
+fn dump_ast() {
+    let output = dump("ast", "x = 1");
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Assign"));
+}
+
+
+#[test]
+fn dump_bytecode() {
+    let output = dump("bytecode", "def f(a):\n    return a\n\nf(1)");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success());
+    assert!(stdout.contains("StoreName                f\n"), "{}", stdout);
+    assert!(stdout.contains("CallFunction             1\n"), "{}", stdout);
+    assert!(stdout.contains("\nDisassembly of <code object f, file \"<string>\">:\n"), "{}", stdout);
+}
+
+
+#[test]
+fn dump_syntax_error() {
+    let output = dump("ast", "x = )");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("SyntaxError"));
+}
+
+
+#[test]
+fn dis_function() {
+    let output = run(&[], "def f(a):\n    return a\n\ndisassemble = dis.dis\ndisassemble(f)", &[]);
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("LoadName                 a\n"), "{}", stdout);
+    assert!(stdout.contains("ReturnValue\n"), "{}", stdout);
+}
@@ -0,0 +1,87 @@
+extern crate rsnek;
+
+mod support;
+
+use rsnek::runtime::config::LOG_ENV;
+use std::env;
+use std::fs::{self, File};
+use std::io::Read;
+use std::process::Output;
+
+use support::command;
+
+
+fn run(args: &[&str], log_env: Option<&str>) -> Output {
+    let mut command = command();
+    command.args(args).arg("-c").arg("print('done')").env_remove(LOG_ENV);
+
+    if let Some(spec) = log_env {
+        command.env(LOG_ENV, spec);
+    }
+
+    command.output().unwrap()
+}
+
+
+#[test]
+fn quiet_by_default() {
+    let output = run(&[], None);
+    assert!(output.status.success());
+    assert_eq!(output.stdout, b"done\n");
+    assert_eq!(output.stderr, b"");
+}
+
+
+#[test]
+fn verbose() {
+    let output = run(&["-v"], None);
+    assert!(output.status.success());
+    assert_eq!(output.stdout, b"done\n");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("welcome to"));
+}
+
+
+#[test]
+fn quiet_overrides_environment() {
+    let output = run(&["-q"], Some("trace"));
+    assert!(output.status.success());
+    assert_eq!(output.stderr, b"");
+}
+
+
+#[test]
+fn environment_module_filters() {
+    // The welcome record comes from the binary, not one of the library's modules
+    let output = run(&[], Some("off,runtime=info"));
+    assert!(!String::from_utf8_lossy(&output.stderr).contains("welcome to"));
+
+    let output = run(&[], Some("off,rsnek=info"));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("welcome to"));
+}
+
+
+#[test]
+fn invalid_environment() {
+    let output = run(&[], Some("loud"));
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains(LOG_ENV));
+    assert_eq!(output.stdout, b"");
+}
+
+
+#[test]
+fn json_lines_to_file() {
+    let path = env::temp_dir().join("rsnek-logging-json-lines.log");
+    let _ = fs::remove_file(&path);
+
+    let output = run(&["-v", "--log-format=json", "--log-file", path.to_str().unwrap()], None);
+    let mut log = String::new();
+    File::open(&path).unwrap().read_to_string(&mut log).unwrap();
+    let _ = fs::remove_file(&path);
+
+    assert!(output.status.success());
+    assert_eq!(output.stderr, b"");
+    assert!(!log.is_empty());
+    assert!(log.lines().all(|line| line.starts_with('{') && line.ends_with('}')), "{}", log);
+    assert!(log.contains("\"msg\":\"welcome to"), "{}", log);
+}
@@ -0,0 +1,50 @@
+extern crate rsnek;
+
+mod support;
+
+use std::fs::{self, File};
+use std::io::Read;
+
+use support::run;
+
+
+const CORPUS: &'static str = "tests/src-python";
+
+
+/// Optimized code must behave exactly like the code it was made from
+#[test]
+fn corpus_matches_unoptimized() {
+    let mut paths = fs::read_dir(CORPUS).unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().map_or(false, |ext| ext == "py"))
+        .collect::<Vec<_>>();
+    paths.sort();
+    assert!(!paths.is_empty());
+
+    for path in paths.iter() {
+        let mut code = String::new();
+        File::open(path).unwrap().read_to_string(&mut code).unwrap();
+
+        // Given with `-c` so no `.rsc` caches are written to the corpus
+        let plain = run(&[], &code, &[]);
+        let optimized = run(&["-O"], &code, &[]);
+
+        assert_eq!(String::from_utf8_lossy(&optimized.stdout), String::from_utf8_lossy(&plain.stdout),
+                   "stdout of {}", path.display());
+        assert_eq!(String::from_utf8_lossy(&optimized.stderr), String::from_utf8_lossy(&plain.stderr),
+                   "stderr of {}", path.display());
+        assert_eq!(optimized.status.code(), plain.status.code(), "status of {}", path.display());
+    }
+}
+
+
+/// Folding checks the size of the operands first, so huge constants on lines that
+/// never run do not stall the compiler
+#[test]
+fn huge_constants_compile() {
+    let code = "def never():\n    x = 2 ** 10 ** 9\n    y = 'a' * 10 ** 9\n\nprint('done')\n";
+
+    let output = run(&["-O"], code, &[]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(output.stdout, b"done\n");
+}
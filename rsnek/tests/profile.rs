@@ -0,0 +1,131 @@
+extern crate rsnek;
+
+mod support;
+
+use std::env;
+use std::fs::File;
+use std::io::Write;
+use std::process::Output;
+
+use support::{command, run};
+
+
+const CODE: &'static str = "def double(a):\n    return a * 2\n\ndouble(1)\nprint(double(2))";
+
+
+fn profile(report: &str, code: &str) -> Output {
+    run(&[&format!("--profile={}", report)], code, &[])
+}
+
+
+/// The columns of the row of `function` in a stats report
+fn row(stats: &str, function: &str) -> Vec<String> {
+    let line = stats.lines()
+        .find(|line| line.ends_with(function))
+        .expect(&format!("no row for {} in\n{}", function, stats));
+
+    line.split_whitespace().map(String::from).collect()
+}
+
+
+#[test]
+fn stats() {
+    let output = profile("stats", CODE);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(output.status.success());
+    assert_eq!(output.stdout, b"4\n");
+    assert!(stderr.starts_with("         4 function calls in "), "{}", stderr);
+    assert!(stderr.contains("   Ordered by: cumulative time\n"), "{}", stderr);
+
+    assert_eq!(row(&stderr, "<string>:2(double)")[0], "2");
+    assert_eq!(row(&stderr, "<string>:1(<module>)")[0], "1");
+    assert_eq!(row(&stderr, "~:0({built-in method builtins.print})")[0], "1");
+}
+
+
+#[test]
+fn collapsed() {
+    let output = profile("collapsed", CODE);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    let stacks: Vec<&str> = stderr.lines()
+        .map(|line| line.rsplitn(2, ' ').nth(1).unwrap())
+        .collect();
+
+    assert!(output.status.success());
+    assert!(stacks.contains(&"<string>:1(<module>);<string>:2(double)"), "{}", stderr);
+    assert!(stacks.contains(&"<string>:1(<module>);~:0({built-in method builtins.print})"), "{}", stderr);
+}
+
+
+#[test]
+fn opcodes() {
+    let output = profile("opcodes", CODE);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    let count = stderr.lines()
+        .find(|line| line.ends_with("  CallFunction"))
+        .map(|line| line.split_whitespace().next().unwrap().to_string());
+
+    assert!(output.status.success());
+    assert_eq!(count, Some(String::from("3")), "{}", stderr);
+}
+
+
+/// Without a report `--profile` prints the stats, the script after it is not taken as the report
+#[test]
+fn default_report() {
+    let path = env::temp_dir().join("rsnek-profile-default.py");
+    File::create(&path).unwrap().write_all(CODE.as_bytes()).unwrap();
+
+    let output = command().arg("--profile").arg(&path).output().unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(output.status.success(), "{}", stderr);
+    assert_eq!(output.stdout, b"4\n");
+    assert!(stderr.starts_with("         4 function calls in "), "{}", stderr);
+}
+
+
+/// Errors are reported before the profile of the program up to them
+#[test]
+fn stats_after_error() {
+    let output = profile("stats", "x = 1\nundefined_name");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(!output.status.success());
+    assert!(stderr.contains("NameError"), "{}", stderr);
+    assert!(stderr.contains(" <string>:1(<module>)\n"), "{}", stderr);
+}
+
+
+#[test]
+fn cprofile_module() {
+    let code = [
+        "def double(a):",
+        "    return a * 2",
+        "",
+        "Profile = cProfile.Profile",
+        "profiler = Profile()",
+        "enable = profiler.enable",
+        "disable = profiler.disable",
+        "print_stats = profiler.print_stats",
+        "",
+        "double(1)",
+        "enable()",
+        "double(2)",
+        "double(3)",
+        "disable()",
+        "print_stats('calls')",
+    ].join("\n");
+
+    let output = run(&[], &code, &[]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success());
+    assert!(stdout.contains("   Ordered by: call count\n"), "{}", stdout);
+    assert_eq!(row(&stdout, "<string>:2(double)")[0], "2");
+    assert_eq!(row(&stdout, "~:0({built-in method cProfile.disable})")[0], "1");
+    assert!(!stdout.contains("(<module>)"), "{}", stdout);
+}
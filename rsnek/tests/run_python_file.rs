@@ -1,8 +1,10 @@
 extern crate rsnek;
 
-use rsnek::resources::strings;
+mod support;
+
 use std::env;
-use std::process::Command;
+
+use support::command;
 
 
 /// Sanity check to make sure the executable sort of works
@@ -10,9 +12,7 @@ use std::process::Command;
 fn run_file() {
 
     env::vars_os().map(|v| {println!("{:?}", v); 1}).sum::<usize>();
-    let path = format!("../target/debug/{}", strings::PROGRAM);
-
-    let result = Command::new(&path)
+    let result = command()
         .arg("tests/test.py")
         .status();
 
@@ -0,0 +1,34 @@
+extern crate rsnek;
+
+mod support;
+
+use rsnek::system::stack::MIN_STACK_SIZE;
+use support::run;
+
+
+#[test]
+fn runs_with_minimum() {
+    let size = MIN_STACK_SIZE.to_string();
+    let output = run(&["-g", "--stack-size", &size], "print(1 + 1)", &[]);
+    assert!(output.status.success());
+    assert_eq!(output.stdout, b"2\n");
+}
+
+
+#[test]
+fn too_small() {
+    let size = (MIN_STACK_SIZE - 1).to_string();
+    let output = run(&["-g", "--stack-size", &size], "print(1)", &[]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(output.stdout, b"");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("too small"));
+}
+
+
+#[test]
+fn not_a_number() {
+    let output = run(&["--stack-size", "big"], "print(1)", &[]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(output.stdout, b"");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("not a size"));
+}
@@ -0,0 +1,92 @@
+extern crate rsnek;
+
+mod support;
+
+use support::run;
+
+
+fn assert_stdout(code: &str, expected: &[u8]) {
+    let output = run(&[], code, &[]);
+    assert_eq!(output.stdout, expected,
+               "stdout: {:?}", String::from_utf8_lossy(&output.stdout));
+}
+
+
+#[test]
+fn print_args() {
+    assert_stdout("print('hello', 'world', 1)", b"hello world 1\n");
+}
+
+
+#[test]
+fn print_no_args() {
+    assert_stdout("print()", b"\n");
+}
+
+
+#[test]
+fn print_lines() {
+    assert_stdout("print('a')\nprint('b')\nprint(len('abc'))\n", b"a\nb\n3\n");
+}
+
+
+#[test]
+fn print_unicode() {
+    assert_stdout("print('h\u{e9}llo \u{1f40d}')", "h\u{e9}llo \u{1f40d}\n".as_bytes());
+}
+
+
+#[test]
+fn sys_stdout_write() {
+    assert_stdout("write = sys.stdout.write\nwrite('no newline')", b"no newline");
+}
+
+
+#[test]
+fn sys_stderr_write() {
+    let output = run(&[], "write = sys.stderr.write\nwrite('to stderr')\nprint('to stdout')", &[]);
+    assert_eq!(output.stdout, b"to stdout\n");
+    assert_eq!(output.stderr, b"to stderr");
+}
+
+
+/// Uncaught errors are reported on stderr and leave stdout alone
+#[test]
+fn error_on_stderr() {
+    let output = run(&[], "print('before')\nundefined_name\nprint('after')", &[]);
+    assert_eq!(output.stdout, b"before\n");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("NameError"));
+}
+
+
+/// Syntax errors point at the offending token like CPython
+#[test]
+fn syntax_error_on_stderr() {
+    let output = run(&[], "print('never')\nx = )", &[]);
+    assert_eq!(output.stdout, b"");
+    assert_eq!(String::from_utf8_lossy(&output.stderr), [
+        "  File \"<string>\", line 2",
+        "    x = )",
+        "        ^",
+        "SyntaxError: unexpected ')', expected an expression\n"].join("\n"));
+}
+
+
+/// Uncaught errors are reported with a CPython style traceback
+#[test]
+fn traceback_on_stderr() {
+    let output = run(&[], "def f():\n    return undefined\n\nf()", &[]);
+    assert_eq!(String::from_utf8_lossy(&output.stderr), [
+        "Traceback (most recent call last):",
+        "  File \"<string>\", line 4, in <module>",
+        "    f()",
+        "  File \"<string>\", line 2, in f",
+        "    return undefined",
+        "NameError: name 'undefined' is not defined\n"].join("\n"));
+}
+
+
+#[test]
+fn format_exc_without_error() {
+    assert_stdout("format_exc = traceback.format_exc\nprint(format_exc())", b"NoneType: None\n\n");
+}
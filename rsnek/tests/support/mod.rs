@@ -0,0 +1,23 @@
+//! Helpers shared by the integration tests, which run the binary of the debug build
+#![allow(dead_code)]
+
+use rsnek::resources::strings;
+use std::process::{Command, Output};
+
+
+/// A command that runs the binary of the debug build
+pub fn command() -> Command {
+    Command::new(format!("../target/debug/{}", strings::PROGRAM))
+}
+
+
+/// Run `code` given with `-c`, with `flags` before it and `args` after it
+pub fn run(flags: &[&str], code: &str, args: &[&str]) -> Output {
+    command()
+        .args(flags)
+        .arg("-c")
+        .arg(code)
+        .args(args)
+        .output()
+        .unwrap()
+}
@@ -0,0 +1,36 @@
+extern crate rsnek;
+
+mod support;
+
+use support::run;
+
+
+#[test]
+fn argv() {
+    let output = run(&[], "print(sys.argv)", &["a", "b"]);
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "['-c', 'a', 'b']\n");
+}
+
+
+#[test]
+fn exit_status() {
+    let output = run(&[], "exit = sys.exit\nprint('before')\nexit(3)\nprint('after')", &[]);
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(output.stdout, b"before\n");
+    assert_eq!(output.stderr, b"");
+}
+
+
+#[test]
+fn exit_message() {
+    let output = run(&[], "exit = sys.exit\nexit('goodbye')", &[]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(output.stderr, b"goodbye\n");
+}
+
+
+#[test]
+fn exit_without_status() {
+    let output = run(&[], "exit = sys.exit\nexit()", &[]);
+    assert!(output.status.success());
+}